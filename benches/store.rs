@@ -0,0 +1,198 @@
+//! Benchmarks of the store operations that grow with the number of notes tracked by the client,
+//! and of the account state operations the client performs for every transaction.
+//!
+//! Run with `cargo bench --bench store`.
+
+use std::{env::temp_dir, fs};
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use crypto::{dsa::rpo_falcon512::KeyPair, Felt, FieldElement};
+use miden_client::{
+    config::StoreConfig,
+    store::{
+        accounts::AuthInfo,
+        notes::{InputNoteFilter, InputNoteRecord, NoteFilter, NoteOrigin},
+        Store,
+    },
+};
+use miden_lib::transaction::TransactionKernel;
+use mock::{
+    constants::{
+        generate_account_seed, AccountSeedType, ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN,
+        ACCOUNT_ID_SENDER,
+    },
+    mock::account::mock_account,
+};
+use objects::{
+    accounts::{AccountDelta, AccountId, AccountStorageDelta, AccountVaultDelta},
+    assembly::ProgramAst,
+    assets::FungibleAsset,
+    notes::{Note, NoteScript},
+};
+use uuid::Uuid;
+
+/// Number of notes the store is filled with
+const NOTE_COUNT: u64 = 10_000;
+
+/// Number of distinct tags the notes are spread over
+const TAG_COUNT: u64 = 100;
+
+// HELPERS
+// ================================================================================================
+
+/// Opens a new store in the temporary directory, returning it along with its path
+fn create_store() -> (Store, String) {
+    let path = temp_dir()
+        .join(format!("{}.sqlite3", Uuid::new_v4()))
+        .into_os_string()
+        .into_string()
+        .unwrap();
+    let store = Store::new(StoreConfig::try_from(path.as_str()).unwrap()).unwrap();
+    (store, path)
+}
+
+fn remove_store(path: &str) {
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = fs::remove_file(format!("{path}{suffix}"));
+    }
+}
+
+/// Path of a store whose files are removed when it is dropped, so that benchmark routines can
+/// return it and leave the removal to criterion, out of the measured time
+struct StoreFiles(String);
+
+impl Drop for StoreFiles {
+    fn drop(&mut self) {
+        remove_store(&self.0);
+    }
+}
+
+/// Returns [NOTE_COUNT] distinct notes sharing the same script, spread over [TAG_COUNT] tags
+fn mock_note_records() -> Vec<InputNoteRecord> {
+    let sender = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let (script, _) = NoteScript::new(
+        ProgramAst::parse("begin push.1 drop end").unwrap(),
+        &TransactionKernel::assembler(),
+    )
+    .unwrap();
+
+    (0..NOTE_COUNT)
+        .map(|i| {
+            let asset = FungibleAsset::new(faucet_id, i + 1).unwrap().into();
+            let serial_num = [Felt::new(i), Felt::ZERO, Felt::ZERO, Felt::ONE];
+            let note = Note::new(
+                script.clone(),
+                &[Felt::new(i)],
+                &[asset],
+                serial_num,
+                sender,
+                Felt::new(i % TAG_COUNT),
+            )
+            .unwrap();
+            InputNoteRecord::new(note, None)
+        })
+        .collect()
+}
+
+// BENCHMARKS
+// ================================================================================================
+
+fn insert_notes(c: &mut Criterion) {
+    let notes = mock_note_records();
+
+    let mut group = c.benchmark_group("store");
+    group.sample_size(10);
+    group.bench_function("insert 10k input notes", |b| {
+        b.iter_batched(
+            create_store,
+            |(mut store, path)| {
+                for note in notes.iter() {
+                    store
+                        .insert_input_note(note, &NoteOrigin::Imported)
+                        .unwrap();
+                }
+                // the store is closed before its files are removed
+                (store, StoreFiles(path))
+            },
+            BatchSize::PerIteration,
+        )
+    });
+    group.finish();
+}
+
+fn query_notes(c: &mut Criterion) {
+    let (mut store, path) = create_store();
+    for note in mock_note_records().iter() {
+        store
+            .insert_input_note(note, &NoteOrigin::Imported)
+            .unwrap();
+    }
+
+    let mut group = c.benchmark_group("store");
+    group.sample_size(10);
+    group.bench_function("query all of 10k input notes", |b| {
+        b.iter(|| store.get_input_notes(InputNoteFilter::All).unwrap())
+    });
+    group.bench_function("query 10k input notes by tag", |b| {
+        b.iter(|| store.get_input_notes(NoteFilter::new().tag(7)).unwrap())
+    });
+    group.finish();
+
+    remove_store(&path);
+}
+
+fn account_state(c: &mut Criterion) {
+    let (mut store, path) = create_store();
+    let (account_id, account_seed) =
+        generate_account_seed(AccountSeedType::RegularAccountUpdatableCodeOnChain);
+    let account = mock_account(
+        Some(account_id.into()),
+        Felt::ZERO,
+        None,
+        &TransactionKernel::assembler(),
+    );
+    store
+        .insert_account(
+            &account,
+            account_seed,
+            &AuthInfo::RpoFalcon512(KeyPair::new().unwrap()),
+        )
+        .unwrap();
+
+    let mut group = c.benchmark_group("store");
+    group.bench_function("get account hash", |b| {
+        b.iter(|| store.get_account_hash(account_id).unwrap())
+    });
+    group.bench_function("get account hash from the full account", |b| {
+        b.iter(|| store.get_account_by_id(account_id).unwrap().0.hash())
+    });
+
+    // every delta must set a higher nonce than the one it is applied to
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let mut nonce = 0;
+    group.bench_function("apply account delta", |b| {
+        b.iter_batched(
+            || {
+                nonce += 1;
+                let storage_delta = AccountStorageDelta {
+                    cleared_items: vec![],
+                    updated_items: vec![(5, [Felt::new(nonce); 4])],
+                };
+                let vault_delta = AccountVaultDelta {
+                    added_assets: vec![FungibleAsset::new(faucet_id, 1).unwrap().into()],
+                    removed_assets: vec![],
+                };
+                AccountDelta::new(storage_delta, vault_delta, Some(Felt::new(nonce))).unwrap()
+            },
+            |delta| store.update_account(account_id, &delta).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+
+    remove_store(&path);
+}
+
+criterion_group!(benches, insert_notes, query_notes, account_state);
+criterion_main!(benches);
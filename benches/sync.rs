@@ -0,0 +1,47 @@
+//! End-to-end benchmark of a sync against the mock node, from requesting the state updates to
+//! applying them to the store.
+//!
+//! Run with `cargo bench --bench sync`.
+
+use std::env::temp_dir;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use miden_client::{
+    client::Client,
+    config::{ClientConfig, RpcConfig},
+    mock::insert_mock_data,
+};
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+/// Returns a client whose store holds the mock account and notes, and whose mock node has the
+/// state updates committing them
+fn create_client(runtime: &Runtime) -> Client {
+    let store_path = temp_dir()
+        .join(format!("{}.sqlite3", Uuid::new_v4()))
+        .into_os_string()
+        .into_string()
+        .unwrap();
+    let config = ClientConfig::new(store_path.try_into().unwrap(), RpcConfig::default());
+    let mut client = Client::new(config).unwrap();
+    runtime.block_on(insert_mock_data(&mut client));
+    client
+}
+
+fn sync_state(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+
+    let mut group = c.benchmark_group("sync");
+    group.sample_size(10);
+    group.bench_function("sync mock chain from genesis", |b| {
+        b.iter_batched(
+            || create_client(&runtime),
+            |mut client| runtime.block_on(client.sync_state()).unwrap(),
+            BatchSize::PerIteration,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, sync_state);
+criterion_main!(benches);
@@ -0,0 +1,78 @@
+//! Benchmarks of transaction execution against the mock node, and of the serialization of the
+//! values the client stores and submits for every transaction.
+//!
+//! Run with `cargo bench --bench transactions`.
+
+use std::env::temp_dir;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use crypto::utils::{Deserializable, Serializable};
+use miden_client::{
+    client::{prover::TransactionProverMode, transactions::TransactionResult, Client},
+    config::{ClientConfig, RpcConfig},
+    mock::create_mock_payment_template,
+};
+use miden_tx::ProvingOptions;
+use objects::{notes::Note, transaction::ProvenTransaction};
+use uuid::Uuid;
+
+fn create_client() -> Client {
+    let store_path = temp_dir()
+        .join(format!("{}.sqlite3", Uuid::new_v4()))
+        .into_os_string()
+        .into_string()
+        .unwrap();
+    let config = ClientConfig::new(store_path.try_into().unwrap(), RpcConfig::default());
+    Client::new(config).unwrap()
+}
+
+// BENCHMARKS
+// ================================================================================================
+
+fn execute_transaction(c: &mut Criterion) {
+    let mut client = create_client();
+    let transaction_template = create_mock_payment_template(&mut client);
+
+    let mut group = c.benchmark_group("transactions");
+    group.sample_size(10);
+    group.bench_function("execute payment", |b| {
+        b.iter(|| {
+            client
+                .new_transaction(transaction_template.clone())
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn serialization_round_trips(c: &mut Criterion) {
+    let mut client = create_client();
+    let transaction_template = create_mock_payment_template(&mut client);
+    let transaction_result = client.new_transaction(transaction_template).unwrap();
+    // a placeholder proof serializes like a STARK one without the cost of proving
+    let proven_transaction = TransactionProverMode::Trusted
+        .prove(
+            &ProvingOptions::default(),
+            transaction_result.executed_transaction().clone(),
+        )
+        .unwrap();
+
+    let mut group = c.benchmark_group("serialization");
+    group.bench_function("transaction result round trip", |b| {
+        b.iter(|| TransactionResult::read_from_bytes(&transaction_result.to_bytes()).unwrap())
+    });
+    group.bench_function("created notes round trip", |b| {
+        b.iter(|| {
+            for note in transaction_result.created_notes() {
+                Note::read_from_bytes(&note.to_bytes()).unwrap();
+            }
+        })
+    });
+    group.bench_function("proven transaction round trip", |b| {
+        b.iter(|| ProvenTransaction::read_from_bytes(&proven_transaction.to_bytes()).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, execute_transaction, serialization_round_trips);
+criterion_main!(benches);
@@ -0,0 +1,51 @@
+/*
+ * C bindings of the Miden client, exported by the library when built with the `ffi` feature.
+ *
+ * Functions return MIDEN_OK on success and one of the MIDEN_ERROR_* statuses otherwise, in which
+ * case miden_last_error_code and miden_last_error_message describe the error. Strings written to
+ * out parameters are owned by the caller and must be released with miden_string_free.
+ */
+
+#ifndef MIDEN_CLIENT_H
+#define MIDEN_CLIENT_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define MIDEN_OK 0
+#define MIDEN_ERROR_FATAL 1
+#define MIDEN_ERROR_INVALID_INPUT 2
+#define MIDEN_ERROR_RETRYABLE 75
+
+#define MIDEN_NOTES_ALL 0
+#define MIDEN_NOTES_COMMITTED 1
+#define MIDEN_NOTES_CONSUMED 2
+#define MIDEN_NOTES_PENDING 3
+#define MIDEN_NOTES_EXPECTED 4
+
+typedef struct MidenClient MidenClient;
+
+int32_t miden_client_new(const char *config_json, MidenClient **out_client);
+void miden_client_free(MidenClient *client);
+
+int32_t miden_client_sync(MidenClient *client, uint32_t *out_block_num);
+int32_t miden_client_new_wallet(MidenClient *client, bool on_chain, uint64_t *out_account_id);
+int32_t miden_client_list_notes(MidenClient *client, uint8_t status, char **out_notes_json);
+int32_t miden_client_send_payment(MidenClient *client, uint64_t sender_id, uint64_t target_id,
+                                  uint64_t faucet_id, uint64_t amount, char **out_receipt_json);
+int32_t miden_client_consume_notes(MidenClient *client, uint64_t account_id,
+                                   const char *note_ids_json, char **out_receipt_json);
+
+const char *miden_last_error_code(void);
+const char *miden_last_error_message(void);
+void miden_string_free(char *string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* MIDEN_CLIENT_H */
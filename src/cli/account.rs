@@ -3,18 +3,31 @@ use comfy_table::{presets, Attribute, Cell, ContentArrangement, Table};
 use crypto::{
     dsa::rpo_falcon512::KeyPair,
     utils::{bytes_to_hex_string, Deserializable, Serializable},
-    StarkField, ZERO,
+    Felt, StarkField, Word, ZERO,
 };
-use miden_client::client::{accounts, Client};
+use figment::{
+    providers::{Format, Toml},
+    Figment,
+};
+use miden_client::client::{accounts, address::Address, Client};
 
 use objects::{
     accounts::{AccountData, AccountId, AccountStorage, AccountStub, AccountType, StorageSlotType},
-    assets::{Asset, TokenSymbol},
+    assets::{Asset, FungibleAsset, TokenSymbol},
+    notes::NoteId,
+    Digest,
+};
+use qrcode::{render::unicode::Dense1x2, QrCode};
+use serde::Deserialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
 };
-use std::{fs, path::PathBuf};
 use tracing::info;
 
-use crate::cli::create_dynamic_table;
+use crate::cli::{
+    create_dynamic_table, format_amount, parse_account_id, parse_amount, store::confirm, CliError,
+};
 
 // ACCOUNT COMMAND
 // ================================================================================================
@@ -24,13 +37,20 @@ use crate::cli::create_dynamic_table;
 pub enum AccountCmd {
     /// List all accounts monitored by this client
     #[clap(short_flag = 'l')]
-    List,
+    List {
+        /// Only list the accounts with the specified label
+        #[clap(long)]
+        label: Option<String>,
+        /// List the removed accounts that can be restored instead
+        #[clap(long, default_value_t = false)]
+        hidden: bool,
+    },
 
     /// Show details of the account for the specified ID
     #[clap(short_flag = 's')]
     Show {
         // TODO: We should create a value parser for catching input parsing errors earlier (ie AccountID) once complexity grows
-        #[clap()]
+        #[clap(value_name = "ACCOUNT_ID")]
         id: Option<String>,
         #[clap(short, long, default_value_t = false)]
         keys: bool,
@@ -40,12 +60,106 @@ pub enum AccountCmd {
         storage: bool,
         #[clap(short, long, default_value_t = false)]
         code: bool,
+        /// Show the state the account had at the specified block instead of its current state.
+        /// States are dated by the block the client was synced to when it stored them, so the
+        /// state left by a transaction is shown from the block the transaction was executed
+        /// against, which precedes the block it was committed in
+        #[clap(long)]
+        at_block: Option<u32>,
+    },
+    /// Show the balances of the account for the specified ID, aggregated per faucet
+    #[clap(short_flag = 'b')]
+    Balance {
+        #[clap(value_name = "ACCOUNT_ID")]
+        id: String,
+    },
+    /// Show the transactions executed against the account for the specified ID, along with the
+    /// notes they consumed and created and the resulting asset changes
+    History {
+        #[clap(value_name = "ACCOUNT_ID")]
+        id: String,
+    },
+    /// Label the account for the specified ID, such as "savings", or show its current label if
+    /// none is given
+    Label {
+        #[clap(value_name = "ACCOUNT_ID")]
+        id: String,
+        #[clap()]
+        label: Option<String>,
+        /// Remove the account's label
+        #[clap(long, default_value_t = false, conflicts_with = "label")]
+        clear: bool,
+    },
+    /// Show the address of the account for the specified ID, a checksummed encoding of the ID
+    /// that is accepted anywhere an account ID is
+    ShowAddress {
+        #[clap(value_name = "ACCOUNT_ID")]
+        id: String,
+        /// Note tag to encode in the address along with the account ID
+        #[clap(long)]
+        tag: Option<u64>,
+        /// Also render the address as a QR code
+        #[clap(long, default_value_t = false)]
+        qr: bool,
     },
     /// Create new account and store it locally
     #[clap(short_flag = 'n')]
     New {
         #[clap(subcommand)]
-        template: AccountTemplate,
+        template: Option<AccountTemplate>,
+        /// Create an account running custom code, as described by a TOML template file
+        #[clap(long, conflicts_with = "template")]
+        from_template: Option<PathBuf>,
+    },
+    /// Replace the code of an account with updatable code and submit the transaction making the
+    /// change. The account's current code must export a `set_code` procedure
+    Upgrade {
+        #[clap(value_name = "ACCOUNT_ID")]
+        id: String,
+        /// File holding the MASM source of the new code
+        #[clap(long)]
+        code: PathBuf,
+    },
+    /// Sync, then check the state of an account against the chain tip by verifying the proof of
+    /// its state against the account root of the latest block
+    Verify {
+        #[clap(value_name = "ACCOUNT_ID")]
+        id: String,
+    },
+    /// Export a proof that the account for the specified ID holds its balance of a fungible token,
+    /// as JSON, for a third party to check. The client syncs and verifies the account's state
+    /// first, so that the proof leads to the account root of the block it is synced to. Fails if
+    /// the local state is not the one committed on chain
+    ProveAsset {
+        #[clap(value_name = "ACCOUNT_ID")]
+        id: String,
+        /// Faucet of the token whose balance is proven
+        #[clap(long, value_name = "FAUCET_ID")]
+        faucet: String,
+        /// Fail unless the account holds at least this amount of the token
+        #[clap(long)]
+        min_amount: Option<String>,
+        /// File the proof is written to. It is printed if not given
+        #[clap(long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Remove the account for the specified ID from lists and syncs. Its data is kept, so that it
+    /// can be restored with `account restore`, unless `--purge` is passed
+    Remove {
+        #[clap(value_name = "ACCOUNT_ID")]
+        id: String,
+        /// Delete the account along with its keys, its transactions and their notes, and the notes
+        /// targeting it. This cannot be undone
+        #[clap(long, default_value_t = false)]
+        purge: bool,
+        /// Do not ask for confirmation before purging
+        #[clap(short, long, default_value_t = false, requires = "purge")]
+        yes: bool,
+    },
+    /// List and sync an account removed without `--purge` again
+    Restore {
+        #[clap(value_name = "ACCOUNT_ID")]
+        id: String,
     },
     /// Import accounts from binary files (with .mac extension)
     #[clap(short_flag = 'i')]
@@ -77,12 +191,31 @@ pub enum AccountTemplate {
 }
 
 impl AccountCmd {
-    pub fn execute(&self, mut client: Client) -> Result<(), String> {
+    pub async fn execute(&self, mut client: Client) -> Result<(), CliError> {
         match self {
-            AccountCmd::List => {
-                list_accounts(client)?;
+            AccountCmd::List { label, hidden } => {
+                list_accounts(client, label.as_deref().map(str::trim), *hidden)?;
+            }
+            AccountCmd::New {
+                template: None,
+                from_template: Some(template_file),
+            } => {
+                let client_template = read_account_template(template_file)?;
+                let (new_account, _account_seed) = client.new_account(client_template)?;
+                println!("Created account {}", new_account.id());
+            }
+            AccountCmd::New {
+                template: None,
+                from_template: None,
+            } => {
+                return Err(CliError::Input(
+                    "Either an account template or --from-template is required".to_string(),
+                ));
             }
-            AccountCmd::New { template } => {
+            AccountCmd::New {
+                template: Some(template),
+                ..
+            } => {
                 let client_template = match template {
                     AccountTemplate::BasicImmutable => accounts::AccountTemplate::BasicWallet {
                         mutable_code: false,
@@ -97,8 +230,9 @@ impl AccountCmd {
                         decimals,
                         max_supply,
                     } => accounts::AccountTemplate::FungibleFaucet {
-                        token_symbol: TokenSymbol::new(token_symbol)
-                            .map_err(|err| format!("error: token symbol is invalid: {}", err))?,
+                        token_symbol: TokenSymbol::new(token_symbol).map_err(|err| {
+                            CliError::Input(format!("error: token symbol is invalid: {}", err))
+                        })?,
                         decimals: *decimals,
                         max_supply: *max_supply,
                         storage_mode: accounts::AccountStorageMode::Local,
@@ -116,10 +250,133 @@ impl AccountCmd {
                 vault,
                 storage,
                 code,
+                at_block,
+            } => {
+                let account_id = parse_account_id(&client, v)?;
+                show_account(
+                    client, account_id, *keys, *vault, *storage, *code, *at_block,
+                )?;
+            }
+            AccountCmd::Balance { id } => {
+                let account_id = parse_account_id(&client, id)?;
+                show_balances(client, account_id)?;
+            }
+            AccountCmd::History { id } => {
+                let account_id = parse_account_id(&client, id)?;
+                show_history(client, account_id)?;
+            }
+            AccountCmd::Label { id, label, clear } => {
+                let account_id = parse_account_id(&client, id)?;
+                match (label, clear) {
+                    (Some(label), _) => {
+                        client.set_account_label(account_id, Some(label))?;
+                        println!("Labelled account {account_id} as {label}");
+                    }
+                    (None, true) => {
+                        client.set_account_label(account_id, None)?;
+                        println!("Removed the label of account {account_id}");
+                    }
+                    (None, false) => match client.get_account_label(account_id)? {
+                        Some(label) => println!("{label}"),
+                        None => println!("Account {account_id} has no label"),
+                    },
+                }
+            }
+            AccountCmd::ShowAddress { id, tag, qr } => {
+                let account_id = parse_account_id(&client, id)?;
+                show_address(account_id, *tag, *qr)?;
+            }
+            AccountCmd::Upgrade { id, code } => {
+                let account_id = parse_account_id(&client, id)?;
+                let new_code = fs::read_to_string(code).map_err(|err| {
+                    CliError::Fatal(format!(
+                        "Failed to read account code {}: {err}",
+                        code.display()
+                    ))
+                })?;
+                let transaction_id = client.upgrade_account_code(account_id, &new_code).await?;
+                println!(
+                    "Upgraded the code of account {account_id} in transaction {transaction_id}"
+                );
+            }
+            AccountCmd::Verify { id } => {
+                let account_id = parse_account_id(&client, id)?;
+                client.sync_state().await?;
+                let account_proof = client.verify_account(account_id).await?;
+                println!(
+                    "Verified state {} of account {account_id} at block {}",
+                    account_proof.account_hash, account_proof.block_num
+                );
+            }
+            AccountCmd::ProveAsset {
+                id,
+                faucet,
+                min_amount,
+                output,
             } => {
-                let account_id: AccountId = AccountId::from_hex(v)
-                    .map_err(|_| "Input number was not a valid Account Id")?;
-                show_account(client, account_id, *keys, *vault, *storage, *code)?;
+                let account_id = parse_account_id(&client, id)?;
+                let faucet_id = parse_account_id(&client, faucet)?;
+                let min_amount = match min_amount {
+                    Some(min_amount) => parse_amount(&client, faucet_id, min_amount)?,
+                    None => 0,
+                };
+                let asset = FungibleAsset::new(faucet_id, min_amount)
+                    .map_err(|err| CliError::Input(err.to_string()))?
+                    .into();
+
+                client.sync_state().await?;
+                client.verify_account(account_id).await?;
+                let asset_proof = client.get_asset_proof(account_id, asset)?;
+                let block_num = asset_proof.block_num().ok_or_else(|| {
+                    CliError::Input(format!(
+                        "The state of account {account_id} is not proven at any block, so the \
+                        proof would not show that it holds the asset"
+                    ))
+                })?;
+                let json = serde_json::to_string_pretty(&asset_proof.to_json()?)
+                    .map_err(|err| CliError::Fatal(err.to_string()))?;
+
+                match output {
+                    Some(output) => {
+                        fs::write(output, json).map_err(|err| {
+                            CliError::Fatal(format!(
+                                "Failed to write the proof to {}: {err}",
+                                output.display()
+                            ))
+                        })?;
+                        println!(
+                            "Exported the proof of the asset of account {account_id} at block {block_num} to {}",
+                            output.display()
+                        );
+                    }
+                    None => println!("{json}"),
+                }
+            }
+            AccountCmd::Remove { id, purge, yes } => {
+                let account_id = parse_account_id(&client, id)?;
+                if !purge {
+                    client.hide_account(account_id)?;
+                    println!(
+                        "Removed account {account_id}, restore it with `account restore {account_id}`"
+                    );
+                    return Ok(());
+                }
+
+                let question = format!(
+                    "Delete account {account_id}, its keys, its transactions and their notes, \
+                    and the notes targeting it, for good?"
+                );
+                if !yes && !confirm(&question)? {
+                    println!("The account was not modified.");
+                    return Ok(());
+                }
+                client.purge_account(account_id)?;
+                println!("Purged account {account_id}");
+            }
+            AccountCmd::Restore { id } => {
+                let account_id = parse_account_id(&client, id)?;
+                client.restore_account(account_id)?;
+                println!("Restored account {account_id}");
             }
             AccountCmd::Import { filenames } => {
                 validate_paths(filenames, "mac")?;
@@ -136,27 +393,41 @@ impl AccountCmd {
 // LIST ACCOUNTS
 // ================================================================================================
 
-fn list_accounts(client: Client) -> Result<(), String> {
-    let accounts = client.get_accounts()?;
+fn list_accounts(client: Client, label: Option<&str>, hidden: bool) -> Result<(), CliError> {
+    let accounts = if hidden {
+        client.get_hidden_accounts()?
+    } else {
+        client.get_accounts()?
+    };
+    let labels = client.get_account_labels()?;
 
     let mut table = create_dynamic_table(&[
         "Account ID",
+        "Label",
         "Code Root",
         "Vault Root",
         "Storage Root",
         "Type",
         "Nonce",
     ]);
-    accounts.iter().for_each(|(acc, _acc_seed)| {
-        table.add_row(vec![
-            acc.id().to_string(),
-            acc.code_root().to_string(),
-            acc.vault_root().to_string(),
-            acc.storage_root().to_string(),
-            get_account_type(acc),
-            acc.nonce().as_int().to_string(),
-        ]);
-    });
+    accounts
+        .iter()
+        .filter(|(acc, _acc_seed)| {
+            label.map_or(true, |label| {
+                labels.get(&acc.id()).map(String::as_str) == Some(label)
+            })
+        })
+        .for_each(|(acc, _acc_seed)| {
+            table.add_row(vec![
+                acc.id().to_string(),
+                labels.get(&acc.id()).cloned().unwrap_or("-".to_string()),
+                acc.code_root().to_string(),
+                acc.vault_root().to_string(),
+                acc.storage_root().to_string(),
+                get_account_type(acc),
+                acc.nonce().as_int().to_string(),
+            ]);
+        });
 
     println!("{table}");
     Ok(())
@@ -169,8 +440,12 @@ pub fn show_account(
     show_vault: bool,
     show_storage: bool,
     show_code: bool,
-) -> Result<(), String> {
-    let (account, _account_seed) = client.get_account_stub_by_id(account_id)?;
+    at_block: Option<u32>,
+) -> Result<(), CliError> {
+    let (account, _account_seed) = match at_block {
+        Some(block_num) => client.get_account_stub_at(account_id, block_num)?,
+        None => client.get_account_stub_by_id(account_id)?,
+    };
 
     let mut table = create_dynamic_table(&[
         "Account ID",
@@ -192,24 +467,42 @@ pub fn show_account(
     ]);
     println!("{table}\n");
 
+    if let Some(desynchronized) = client.get_desynchronized_account(account_id)? {
+        println!(
+            "Warning: at block {}, the on-chain hash of the account was {} instead of the local \
+            one, {}. It was probably updated from another store; import an up-to-date account \
+            file before executing transactions against it.\n",
+            desynchronized.block_num, desynchronized.remote_hash, desynchronized.local_hash
+        );
+    }
+
     if show_vault {
         let assets = client.get_vault_assets(account.vault_root())?;
 
         println!("Assets: ");
 
-        let mut table = create_dynamic_table(&["Asset Type", "Faucet ID", "Amount"]);
+        let mut table = create_dynamic_table(&["Asset Type", "Faucet ID", "Amount", "Digest"]);
         for asset in assets {
-            let (asset_type, faucet_id, amount) = match asset {
+            let (asset_type, faucet_id, amount, digest) = match asset {
                 Asset::Fungible(fungible_asset) => (
                     "Fungible Asset",
                     fungible_asset.faucet_id(),
                     fungible_asset.amount(),
+                    "-".to_string(),
+                ),
+                Asset::NonFungible(non_fungible_asset) => (
+                    "Non Fungible Asset",
+                    non_fungible_asset.faucet_id(),
+                    1,
+                    Digest::from(Word::from(non_fungible_asset)).to_string(),
                 ),
-                Asset::NonFungible(non_fungible_asset) => {
-                    ("Non Fungible Asset", non_fungible_asset.faucet_id(), 1)
-                }
             };
-            table.add_row(vec![asset_type, &faucet_id.to_hex(), &amount.to_string()]);
+            table.add_row(vec![
+                asset_type,
+                &faucet_id.to_hex(),
+                &amount.to_string(),
+                &digest,
+            ]);
         }
 
         println!("{table}\n");
@@ -305,23 +598,245 @@ pub fn show_account(
     Ok(())
 }
 
+// SHOW BALANCES
+// ================================================================================================
+
+fn show_balances(client: Client, account_id: AccountId) -> Result<(), CliError> {
+    let balances = client.get_account_balances(account_id)?;
+
+    let mut table = create_dynamic_table(&["Faucet ID", "Amount"]);
+    for (faucet_id, amount) in balances {
+        table.add_row(vec![
+            faucet_id.to_hex(),
+            format_amount(&client, faucet_id, amount)?,
+        ]);
+    }
+
+    println!("{table}");
+    Ok(())
+}
+
+// SHOW HISTORY
+// ================================================================================================
+
+fn show_history(client: Client, account_id: AccountId) -> Result<(), CliError> {
+    let history = client.get_account_history(account_id)?;
+
+    let mut table = create_dynamic_table(&[
+        "Transaction ID",
+        "Block Number",
+        "Status",
+        "Consumed Notes",
+        "Created Notes",
+        "Asset Changes",
+        "Memos",
+    ]);
+    for entry in history {
+        let asset_changes = entry
+            .asset_changes
+            .iter()
+            .map(|(faucet_id, change)| {
+                let sign = if *change < 0 { "-" } else { "+" };
+                let amount = format_amount(&client, *faucet_id, change.unsigned_abs() as u64)?;
+                Ok(format!("{sign}{amount} ({faucet_id})"))
+            })
+            .collect::<Result<Vec<String>, CliError>>()?;
+
+        table.add_row(vec![
+            entry.transaction_id.to_string(),
+            entry.block_num.to_string(),
+            entry.transaction_status.to_string(),
+            format_note_ids(&entry.consumed_notes),
+            format_note_ids(&entry.created_notes),
+            asset_changes.join("\n"),
+            entry
+                .memos
+                .iter()
+                .map(|(_, memo)| memo.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ]);
+    }
+
+    println!("{table}");
+    Ok(())
+}
+
+fn format_note_ids(note_ids: &[NoteId]) -> String {
+    note_ids
+        .iter()
+        .map(|note_id| note_id.inner().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// ACCOUNT TEMPLATE FILE
+// ================================================================================================
+
+/// Custom account described by a TOML template file, such as:
+///
+/// ```toml
+/// account_type = "regular-immutable"
+/// storage_mode = "local"
+/// auth_scheme = "rpo-falcon512"
+/// code = "account.masm"
+///
+/// [[storage]]
+/// index = 1
+/// value = [1, 0, 0, 0]
+/// ```
+///
+/// The path of the code is relative to the template file. Storage slot 0 holds the account's
+/// public key and cannot be set by the template.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AccountTemplateFile {
+    account_type: TemplateAccountType,
+    #[serde(default)]
+    storage_mode: TemplateStorageMode,
+    #[serde(default)]
+    auth_scheme: TemplateAuthScheme,
+    code: PathBuf,
+    #[serde(default)]
+    storage: Vec<TemplateStorageSlot>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum TemplateAccountType {
+    RegularImmutable,
+    RegularUpdatable,
+    FungibleFaucet,
+    NonFungibleFaucet,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum TemplateStorageMode {
+    #[default]
+    Local,
+    OnChain,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum TemplateAuthScheme {
+    #[default]
+    RpoFalcon512,
+}
+
+/// Initial value of a storage slot, given as its four field elements
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TemplateStorageSlot {
+    index: u8,
+    value: [u64; 4],
+}
+
+/// Reads the template file at `template_file` along with the account code it points to
+fn read_account_template(template_file: &Path) -> Result<accounts::AccountTemplate, CliError> {
+    let template = fs::read_to_string(template_file).map_err(|err| {
+        CliError::Fatal(format!(
+            "Failed to read account template {}: {err}",
+            template_file.display()
+        ))
+    })?;
+    let template: AccountTemplateFile =
+        Figment::from(Toml::string(&template))
+            .extract()
+            .map_err(|err| {
+                CliError::Input(format!(
+                    "Invalid account template {}: {err}",
+                    template_file.display()
+                ))
+            })?;
+
+    let account_type = match template.account_type {
+        TemplateAccountType::RegularImmutable => AccountType::RegularAccountImmutableCode,
+        TemplateAccountType::RegularUpdatable => AccountType::RegularAccountUpdatableCode,
+        TemplateAccountType::FungibleFaucet => AccountType::FungibleFaucet,
+        TemplateAccountType::NonFungibleFaucet => AccountType::NonFungibleFaucet,
+    };
+    let storage_mode = match template.storage_mode {
+        TemplateStorageMode::Local => accounts::AccountStorageMode::Local,
+        TemplateStorageMode::OnChain => {
+            return Err(CliError::Input(
+                "On-chain accounts are not supported yet".to_string(),
+            ))
+        }
+    };
+    // the client only creates accounts controlled by RPO Falcon 512 keys for now
+    let TemplateAuthScheme::RpoFalcon512 = template.auth_scheme;
+
+    let storage_slots = template
+        .storage
+        .iter()
+        .map(|slot| {
+            if slot.value.iter().any(|&element| element >= Felt::MODULUS) {
+                return Err(CliError::Input(format!(
+                    "The value of storage slot {} is not made of valid field elements",
+                    slot.index
+                )));
+            }
+            Ok((slot.index, slot.value.map(Felt::new)))
+        })
+        .collect::<Result<Vec<(u8, Word)>, CliError>>()?;
+
+    let code_path = template_file.with_file_name(&template.code);
+    let code = fs::read_to_string(&code_path).map_err(|err| {
+        CliError::Fatal(format!(
+            "Failed to read account code {}: {err}",
+            code_path.display()
+        ))
+    })?;
+
+    Ok(accounts::AccountTemplate::Custom {
+        account_type,
+        code,
+        storage_slots,
+        storage_mode,
+    })
+}
+
+// SHOW ADDRESS
+// ================================================================================================
+
+fn show_address(account_id: AccountId, tag: Option<u64>, qr: bool) -> Result<(), CliError> {
+    let address = match tag {
+        Some(tag) => Address::new(account_id).with_note_tag(tag),
+        None => Address::new(account_id),
+    };
+    println!("{address}");
+
+    if qr {
+        // uppercase addresses are valid too, and fit in smaller QR codes
+        let code = QrCode::new(address.to_string().to_uppercase().as_bytes()).map_err(|err| {
+            CliError::Fatal(format!("Failed to render address as a QR code: {err}"))
+        })?;
+        println!("{}", code.render::<Dense1x2>().quiet_zone(true).build());
+    }
+    Ok(())
+}
+
 // IMPORT ACCOUNT
 // ================================================================================================
 
-fn import_account(client: &mut Client, filename: &PathBuf) -> Result<(), String> {
+fn import_account(client: &mut Client, filename: &PathBuf) -> Result<(), CliError> {
     info!(
         "Attempting to import account data from {}...",
         fs::canonicalize(filename)
-            .map_err(|err| err.to_string())?
+            .map_err(|err| CliError::Fatal(err.to_string()))?
             .as_path()
             .display()
     );
-    let account_data_file_contents = fs::read(filename).map_err(|err| err.to_string())?;
-    let account_data =
-        AccountData::read_from_bytes(&account_data_file_contents).map_err(|err| err.to_string())?;
+    let account_data_file_contents =
+        fs::read(filename).map_err(|err| CliError::Fatal(err.to_string()))?;
+    let account_data = AccountData::read_from_bytes(&account_data_file_contents)
+        .map_err(|err| CliError::Input(err.to_string()))?;
     let account_id = account_data.account.id();
 
     client.import_account(account_data)?;
+    client.register_account_tags(account_id)?;
     println!("Imported account with ID: {}", account_id);
 
     Ok(())
@@ -332,7 +847,7 @@ fn import_account(client: &mut Client, filename: &PathBuf) -> Result<(), String>
 
 /// Checks that all files exist, otherwise returns an error. It also ensures that all files have a
 /// specific extension
-fn validate_paths(paths: &[PathBuf], expected_extension: &str) -> Result<(), String> {
+fn validate_paths(paths: &[PathBuf], expected_extension: &str) -> Result<(), CliError> {
     let invalid_path = paths.iter().find(|path| {
         !path.exists()
             || path
@@ -341,11 +856,10 @@ fn validate_paths(paths: &[PathBuf], expected_extension: &str) -> Result<(), Str
     });
 
     if let Some(path) = invalid_path {
-        Err(format!(
+        Err(CliError::Input(format!(
             "The path `{}` does not exist or does not have the appropiate extension",
             path.to_string_lossy()
-        )
-        .to_string())
+        )))
     } else {
         Ok(())
     }
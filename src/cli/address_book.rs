@@ -0,0 +1,71 @@
+use super::{CliError, Client, Parser};
+use crate::cli::{create_dynamic_table, parse_account_id};
+
+#[derive(Debug, Parser, Clone)]
+#[clap(about = "Name accounts so they can be referred to by name in other commands")]
+pub enum AddressBookCmd {
+    /// List all named accounts
+    #[clap(short_flag = 'l')]
+    List,
+
+    /// Give a name to an account, replacing the account previously given that name, if any
+    #[clap(short_flag = 'a')]
+    Add {
+        #[clap()]
+        name: String,
+
+        /// ID of the account, which need not be tracked by this client
+        #[clap()]
+        account_id: String,
+    },
+
+    /// Remove a name from the address book
+    #[clap(short_flag = 'r')]
+    Remove {
+        #[clap()]
+        name: String,
+    },
+}
+
+impl AddressBookCmd {
+    pub fn execute(&self, client: Client) -> Result<(), CliError> {
+        match self {
+            AddressBookCmd::List => {
+                list_entries(client)?;
+            }
+            AddressBookCmd::Add { name, account_id } => {
+                add_entry(client, name, account_id)?;
+            }
+            AddressBookCmd::Remove { name } => {
+                remove_entry(client, name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// HELPERS
+// ================================================================================================
+fn list_entries(client: Client) -> Result<(), CliError> {
+    let mut table = create_dynamic_table(&["Name", "Account ID"]);
+
+    for entry in client.get_address_book()? {
+        table.add_row(vec![entry.name, entry.account_id.to_string()]);
+    }
+
+    println!("{table}");
+    Ok(())
+}
+
+fn add_entry(mut client: Client, name: &str, account_id: &str) -> Result<(), CliError> {
+    let account_id = parse_account_id(&client, account_id)?;
+    client.add_address_book_entry(name, account_id)?;
+    println!("account {} named {}", account_id, name);
+    Ok(())
+}
+
+fn remove_entry(mut client: Client, name: &str) -> Result<(), CliError> {
+    client.remove_address_book_entry(name)?;
+    println!("{} removed from the address book", name);
+    Ok(())
+}
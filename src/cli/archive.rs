@@ -0,0 +1,97 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crypto::utils::{Deserializable, Serializable};
+use miden_client::{client::archive::WalletArchive, config::ClientConfig};
+
+use super::{CliError, Client};
+
+// EXPORT WALLET ARCHIVE
+// ================================================================================================
+
+pub fn export_archive(
+    client: &Client,
+    config: ClientConfig,
+    file: &Path,
+    include_keys: bool,
+) -> Result<(), CliError> {
+    let archive = client.export_archive(include_keys)?.with_config(config);
+
+    File::create(file)
+        .and_then(|mut file| file.write_all(&archive.to_bytes()))
+        .map_err(|err| CliError::Fatal(format!("Failed to write archive file: {err}")))?;
+
+    println!(
+        "Exported {} accounts, {} notes and {} transactions to {}.",
+        archive.accounts.len(),
+        archive.input_notes.len(),
+        archive.transactions.len(),
+        file.display()
+    );
+    if include_keys {
+        println!("The archive holds the keys of the accounts, keep it safe.");
+    }
+    Ok(())
+}
+
+// IMPORT WALLET ARCHIVE
+// ================================================================================================
+
+pub fn import_archive(
+    mut client: Client,
+    file: &Path,
+    config_file: Option<&PathBuf>,
+) -> Result<(), CliError> {
+    let contents = fs::read(file)
+        .map_err(|err| CliError::Fatal(format!("Failed to read archive file: {err}")))?;
+    let archive = WalletArchive::read_from_bytes(&contents).map_err(|err| {
+        CliError::Input(format!("Invalid archive file {}: {err}", file.display()))
+    })?;
+
+    if let Some(config_file) = config_file {
+        write_archived_config(&archive, config_file)?;
+    }
+
+    let summary = client.import_archive(archive)?;
+    println!(
+        "Imported {} accounts, {} notes and {} transactions.",
+        summary.imported_accounts, summary.imported_notes, summary.imported_transactions
+    );
+    if !summary.accounts_without_keys.is_empty() {
+        let account_ids: Vec<String> = summary
+            .accounts_without_keys
+            .iter()
+            .map(|account_id| account_id.to_string())
+            .collect();
+        println!(
+            "Skipped accounts {}, as the archive was exported without their keys.",
+            account_ids.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Writes the configuration stored in `archive` to `config_file` in JSON format
+fn write_archived_config(archive: &WalletArchive, config_file: &Path) -> Result<(), CliError> {
+    let config = archive.config.as_ref().ok_or_else(|| {
+        CliError::Input("The archive does not include a configuration".to_string())
+    })?;
+    if config_file.exists() {
+        return Err(CliError::Input(format!(
+            "{} already exists, the archived configuration was not written",
+            config_file.display()
+        )));
+    }
+
+    let contents =
+        serde_json::to_string_pretty(config).map_err(|err| CliError::Fatal(err.to_string()))?;
+    fs::write(config_file, contents).map_err(|err| CliError::Fatal(err.to_string()))?;
+    println!(
+        "Wrote the archived configuration to {}.",
+        config_file.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,425 @@
+use std::collections::BTreeMap;
+
+use clap::{builder::PossibleValue, Arg, Command, ValueEnum};
+use miden_client::{
+    client::read_only::ReadOnlyClient,
+    store::{accounts::AddressBookEntry, notes::InputNoteFilter},
+};
+use objects::accounts::AccountId;
+
+use super::CliError;
+
+// SHELL SCRIPTS
+// ================================================================================================
+
+/// Shells completion scripts can be generated for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Bash completion, which drops the descriptions of the candidates and falls back to file names
+/// when there are none
+const BASH_SCRIPT: &str = r#"_miden_client() {
+    local IFS=$'\n'
+    local candidates
+    candidates=$(miden-client complete --index "$COMP_CWORD" -- "${COMP_WORDS[@]}" 2>/dev/null | cut -f1)
+    COMPREPLY=($(compgen -W "$candidates" -- "${COMP_WORDS[COMP_CWORD]}"))
+}
+complete -o default -F _miden_client miden-client
+"#;
+
+/// Zsh completion, showing the descriptions of the candidates and falling back to file names
+/// when there are none
+const ZSH_SCRIPT: &str = r#"#compdef miden-client
+_miden_client() {
+    local -a candidates
+    candidates=("${(@f)$(miden-client complete --index $((CURRENT - 1)) -- "${words[@]}" 2>/dev/null | sed -e 's/:/\\:/g' -e 's/\t/:/')}")
+    if [[ -n "${candidates[1]}" ]]; then
+        _describe 'values' candidates
+    else
+        _files
+    fi
+}
+compdef _miden_client miden-client
+"#;
+
+/// Fish completion, which reads the descriptions of the candidates after a tab as is
+const FISH_SCRIPT: &str = r#"complete -c miden-client -a '(miden-client complete --index (count (commandline -opc)) -- (commandline -opc) (commandline -ct) 2>/dev/null)'
+"#;
+
+/// Returns the script registering the completion of the CLI's commands in `shell`. Candidates are
+/// computed by `complete`, so that account and note IDs are read from the store.
+pub fn completion_script(shell: Shell) -> &'static str {
+    match shell {
+        Shell::Bash => BASH_SCRIPT,
+        Shell::Zsh => ZSH_SCRIPT,
+        Shell::Fish => FISH_SCRIPT,
+    }
+}
+
+// COMPLETION
+// ================================================================================================
+
+/// Kind of value read from the store to complete an argument, given by the argument's value name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    /// Arguments whose value name ends with `ACCOUNT`, `ACCOUNT_ID` or `FAUCET_ID`, completed
+    /// with the IDs of the client's accounts and the names of the address book entries
+    Account,
+    /// Arguments whose value name ends with `NOTE_ID`, completed with the IDs of the input notes
+    Note,
+}
+
+impl ValueKind {
+    fn of(arg: &Arg) -> Option<Self> {
+        let value_name = arg
+            .get_value_names()
+            .and_then(|value_names| value_names.first())
+            .map(|value_name| value_name.as_str().to_string())
+            .unwrap_or_else(|| arg.get_id().as_str().to_ascii_uppercase());
+
+        if ["ACCOUNT", "ACCOUNT_ID", "FAUCET_ID"]
+            .iter()
+            .any(|suffix| value_name.ends_with(suffix))
+        {
+            Some(ValueKind::Account)
+        } else if value_name.ends_with("NOTE_ID") {
+            Some(ValueKind::Note)
+        } else {
+            None
+        }
+    }
+}
+
+/// Value proposed for the word being completed, with a short description shown by the shells
+/// that support them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub value: String,
+    pub description: Option<String>,
+}
+
+impl Candidate {
+    fn new(value: impl Into<String>, description: Option<String>) -> Self {
+        Self {
+            value: value.into(),
+            description,
+        }
+    }
+}
+
+/// Returns the candidates for the word at `index` of the command line `words`, whose first word
+/// is the binary's name: subcommands, flags, the possible values of an argument or, for accounts
+/// and notes, the values returned by `lookup`. The shell narrows them down to the ones starting
+/// with the word typed so far.
+pub fn complete(
+    root: &Command,
+    words: &[String],
+    index: usize,
+    lookup: &mut dyn FnMut(ValueKind) -> Vec<Candidate>,
+) -> Vec<Candidate> {
+    let mut root = root.clone();
+    root.build();
+
+    let mut command = &root;
+    let mut positionals = 0;
+    let mut pending_option: Option<&Arg> = None;
+    for word in words.iter().take(index).skip(1) {
+        if pending_option.take().is_some() {
+            continue;
+        }
+
+        if let Some(long) = word.strip_prefix("--") {
+            let option = command
+                .get_arguments()
+                .find(|arg| !long.contains('=') && arg.get_long() == Some(long));
+            pending_option = option.filter(|arg| takes_value(arg));
+        } else if let Some(short) = short_flag(word) {
+            if let Some(subcommand) = command
+                .get_subcommands()
+                .find(|subcommand| subcommand.get_short_flag() == Some(short))
+            {
+                command = subcommand;
+                positionals = 0;
+                continue;
+            }
+            let option = command
+                .get_arguments()
+                .find(|arg| arg.get_short() == Some(short));
+            pending_option = option.filter(|arg| takes_value(arg));
+        } else if let Some(subcommand) = find_subcommand(command, word) {
+            command = subcommand;
+            positionals = 0;
+        } else {
+            positionals += 1;
+        }
+    }
+
+    if let Some(option) = pending_option {
+        return arg_values(option, lookup);
+    }
+
+    let current = words.get(index).map(String::as_str).unwrap_or_default();
+    if current.starts_with('-') {
+        return command
+            .get_arguments()
+            .filter(|arg| !arg.is_hide_set())
+            .filter_map(|arg| {
+                arg.get_long().map(|long| {
+                    Candidate::new(
+                        format!("--{long}"),
+                        arg.get_help().map(|help| help.to_string()),
+                    )
+                })
+            })
+            .collect();
+    }
+
+    let mut candidates: Vec<Candidate> = command
+        .get_subcommands()
+        .filter(|subcommand| !subcommand.is_hide_set())
+        .map(|subcommand| {
+            Candidate::new(
+                subcommand.get_name(),
+                subcommand.get_about().map(|about| about.to_string()),
+            )
+        })
+        .collect();
+    if let Some(positional) = positional_at(command, positionals) {
+        candidates.extend(arg_values(positional, lookup));
+    }
+    candidates
+}
+
+/// Returns the values an argument can take: its possible values if it has some, or the values
+/// read from the store for arguments referring to accounts or notes
+fn arg_values(arg: &Arg, lookup: &mut dyn FnMut(ValueKind) -> Vec<Candidate>) -> Vec<Candidate> {
+    let possible_values: Vec<PossibleValue> = arg.get_possible_values();
+    if !possible_values.is_empty() {
+        return possible_values
+            .iter()
+            .filter(|value| !value.is_hide_set())
+            .map(|value| {
+                Candidate::new(
+                    value.get_name(),
+                    value.get_help().map(|help| help.to_string()),
+                )
+            })
+            .collect();
+    }
+
+    ValueKind::of(arg).map(lookup).unwrap_or_default()
+}
+
+/// Returns the positional argument the `position`th positional value (from 0) is given to. The
+/// last positional argument takes every remaining value if it accepts several
+fn positional_at(command: &Command, position: usize) -> Option<&Arg> {
+    let positionals: Vec<&Arg> = command.get_positionals().collect();
+    positionals.get(position).copied().or_else(|| {
+        positionals.last().copied().filter(|arg| {
+            arg.get_num_args()
+                .map(|num_args| num_args.max_values() > 1)
+                .unwrap_or(false)
+        })
+    })
+}
+
+fn find_subcommand<'a>(command: &'a Command, name: &str) -> Option<&'a Command> {
+    command.get_subcommands().find(|subcommand| {
+        subcommand.get_name() == name || subcommand.get_all_aliases().any(|alias| alias == name)
+    })
+}
+
+/// Returns the flag of a word made of a dash and a single character, such as `-l`
+fn short_flag(word: &str) -> Option<char> {
+    let mut chars = word.strip_prefix('-')?.chars();
+    match (chars.next(), chars.next()) {
+        (Some(short), None) if short != '-' => Some(short),
+        _ => None,
+    }
+}
+
+fn takes_value(arg: &Arg) -> bool {
+    arg.get_num_args()
+        .map(|num_args| num_args.takes_values())
+        .unwrap_or(false)
+}
+
+/// Reads the values of `kind` from the client's store
+pub fn lookup_values(client: &ReadOnlyClient, kind: ValueKind) -> Result<Vec<Candidate>, CliError> {
+    let candidates = match kind {
+        ValueKind::Account => {
+            let labels = client.get_account_labels()?;
+            let address_book = client.get_address_book()?;
+            let mut candidates: Vec<Candidate> = client
+                .get_accounts()?
+                .into_iter()
+                .map(|(account, _seed)| {
+                    Candidate::new(account.id().to_hex(), labels.get(&account.id()).cloned())
+                })
+                .collect();
+            candidates.extend(label_candidates(&labels, &address_book));
+            candidates.extend(
+                address_book
+                    .into_iter()
+                    .map(|entry| Candidate::new(entry.name, Some(entry.account_id.to_hex()))),
+            );
+            candidates
+        }
+        ValueKind::Note => client
+            .get_input_notes(InputNoteFilter::All)?
+            .into_iter()
+            .map(|note| Candidate::new(note.note_id().inner().to_string(), None))
+            .collect(),
+    };
+    Ok(candidates)
+}
+
+/// Returns the account labels that resolve to an account: the ones no other account shares and
+/// no address book name shadows. Labels holding spaces are left out, as the shell would split them
+fn label_candidates(
+    labels: &BTreeMap<AccountId, String>,
+    address_book: &[AddressBookEntry],
+) -> Vec<Candidate> {
+    let mut accounts_by_label: BTreeMap<&str, Vec<AccountId>> = BTreeMap::new();
+    for (account_id, label) in labels.iter() {
+        accounts_by_label
+            .entry(label)
+            .or_default()
+            .push(*account_id);
+    }
+
+    accounts_by_label
+        .into_iter()
+        .filter(|(label, _)| !label.contains(char::is_whitespace))
+        .filter(|(label, _)| !address_book.iter().any(|entry| entry.name == *label))
+        .filter_map(|(label, account_ids)| match account_ids[..] {
+            [account_id] => Some(Candidate::new(label, Some(account_id.to_hex()))),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Prints the candidates one per line, followed by a tab and their description if they have one
+pub fn print_candidates(candidates: &[Candidate]) {
+    for candidate in candidates {
+        match &candidate.description {
+            // descriptions are shown on a single line
+            Some(description) => println!(
+                "{}\t{}",
+                candidate.value,
+                description.lines().next().unwrap_or_default()
+            ),
+            None => println!("{}", candidate.value),
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use clap::CommandFactory;
+    use miden_client::store::accounts::AddressBookEntry;
+    use mock::constants::{ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN, ACCOUNT_ID_SENDER};
+    use objects::accounts::AccountId;
+
+    use super::{complete, label_candidates, Candidate, ValueKind};
+    use crate::cli::Cli;
+
+    /// Completes the last word of `line`, with a single account and note in the store
+    fn complete_line(line: &str) -> Vec<String> {
+        let mut words: Vec<String> = line.split(' ').map(str::to_string).collect();
+        if line.ends_with(' ') {
+            words.pop();
+            words.push(String::new());
+        }
+        let index = words.len() - 1;
+
+        complete(&Cli::command(), &words, index, &mut |kind| match kind {
+            ValueKind::Account => vec![Candidate::new("0x9a3f", Some("savings".to_string()))],
+            ValueKind::Note => vec![Candidate::new("0x42ab", None)],
+        })
+        .into_iter()
+        .map(|candidate| candidate.value)
+        .collect()
+    }
+
+    #[test]
+    fn subcommands_and_flags_are_completed() {
+        let candidates = complete_line("miden-client ");
+        assert!(candidates.contains(&"account".to_string()));
+        assert!(candidates.contains(&"tx".to_string()));
+        assert!(!candidates.contains(&"complete".to_string()));
+
+        let candidates = complete_line("miden-client sync --");
+        assert!(candidates.contains(&"--watch".to_string()));
+        assert!(candidates.contains(&"--proxy".to_string()));
+    }
+
+    #[test]
+    fn accounts_and_notes_are_completed_from_the_store() {
+        assert_eq!(complete_line("miden-client account show "), ["0x9a3f"]);
+        assert_eq!(
+            complete_line("miden-client input-notes show 0x4"),
+            ["0x42ab"]
+        );
+        assert_eq!(
+            complete_line("miden-client tx new p2id 0x9a3f "),
+            ["0x9a3f"]
+        );
+        assert_eq!(
+            complete_line("miden-client tx new consume-notes 0x9a3f 0x42ab "),
+            ["0x42ab"]
+        );
+        assert_eq!(complete_line("miden-client sync --account "), ["0x9a3f"]);
+        assert_eq!(
+            complete_line("miden-client tx new recall --note "),
+            ["0x42ab"]
+        );
+        assert_eq!(complete_line("miden-client input-notes -s "), ["0x42ab"]);
+    }
+
+    #[test]
+    fn labels_of_a_single_account_are_completed() {
+        let account_ids = [
+            AccountId::from_hex("0x168187d729b31a84").unwrap(),
+            AccountId::try_from(ACCOUNT_ID_SENDER).unwrap(),
+            AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap(),
+        ];
+        let labels: BTreeMap<AccountId, String> = [
+            (account_ids[0], "savings"),
+            (account_ids[1], "shared"),
+            (account_ids[2], "shared"),
+        ]
+        .into_iter()
+        .map(|(account_id, label)| (account_id, label.to_string()))
+        .collect();
+
+        let candidates = label_candidates(&labels, &[]);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].value, "savings");
+        assert_eq!(candidates[0].description, Some(account_ids[0].to_hex()));
+
+        // address book names take precedence over labels
+        let address_book = [AddressBookEntry {
+            name: "savings".to_string(),
+            account_id: account_ids[1],
+        }];
+        assert!(label_candidates(&labels, &address_book).is_empty());
+    }
+
+    #[test]
+    fn possible_values_are_completed() {
+        let candidates = complete_line("miden-client input-notes list --filter ");
+        assert!(candidates.contains(&"committed".to_string()));
+        assert!(candidates.contains(&"expected".to_string()));
+        assert!(complete_line("miden-client completions ").contains(&"fish".to_string()));
+    }
+}
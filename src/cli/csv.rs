@@ -0,0 +1,186 @@
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use miden_client::{
+    client::{assets::AssetAmount, note_interpreter::DecodedNote, Client},
+    store::chain_data::BlockTime,
+};
+use objects::{accounts::AccountId, assets::Asset};
+
+use super::CliError;
+
+// CSV WRITER
+// ================================================================================================
+
+/// Writes rows of comma-separated values to a file, as read by spreadsheet applications
+pub(super) struct CsvWriter {
+    writer: BufWriter<File>,
+    columns: usize,
+}
+
+impl CsvWriter {
+    /// Creates the file, overwriting it if it exists, and writes the header row
+    pub fn create(path: &Path, header: &[&str]) -> Result<Self, CliError> {
+        let file = File::create(path).map_err(|err| {
+            CliError::Fatal(format!("Failed to create {}: {err}", path.display()))
+        })?;
+        let mut writer = Self {
+            writer: BufWriter::new(file),
+            columns: header.len(),
+        };
+        writer.write_fields(header.iter().copied())?;
+        Ok(writer)
+    }
+
+    /// Writes a row, which must have as many fields as the header
+    pub fn write_row(&mut self, row: &[String]) -> Result<(), CliError> {
+        debug_assert_eq!(row.len(), self.columns);
+        self.write_fields(row.iter().map(String::as_str))
+    }
+
+    pub fn finish(mut self) -> Result<(), CliError> {
+        self.writer.flush().map_err(write_error)?;
+        Ok(())
+    }
+
+    fn write_fields<'a>(&mut self, fields: impl Iterator<Item = &'a str>) -> Result<(), CliError> {
+        let line = fields.map(escape_field).collect::<Vec<_>>().join(",");
+        // CRLF line endings, as RFC 4180 describes
+        write!(self.writer, "{line}\r\n").map_err(write_error)?;
+        Ok(())
+    }
+}
+
+fn write_error(err: io::Error) -> CliError {
+    CliError::Fatal(format!("Failed to write CSV file: {err}"))
+}
+
+/// Quotes fields holding separators, quotes or line breaks. Fields starting with a character
+/// spreadsheets take as the start of a formula, such as memos chosen by the sender of a note,
+/// are prefixed with a quote so that they are shown as text.
+fn escape_field(field: &str) -> Cow<'_, str> {
+    let field = if field.starts_with(['=', '+', '-', '@']) {
+        Cow::Owned(format!("'{field}"))
+    } else {
+        Cow::Borrowed(field)
+    };
+
+    if field.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        field
+    }
+}
+
+// FORMATTING
+// ================================================================================================
+
+/// Returns the number of the block and its timestamp, as an ISO 8601 date and time in UTC, or
+/// empty fields if the block is not known
+pub(super) fn block_time_fields(block_time: Option<&BlockTime>) -> [String; 2] {
+    match block_time {
+        Some(block_time) => [
+            block_time.block_num.to_string(),
+            block_time
+                .timestamp
+                .map(format_timestamp)
+                .unwrap_or_default(),
+        ],
+        None => [String::new(), String::new()],
+    }
+}
+
+/// Formats a UNIX timestamp as an ISO 8601 date and time in UTC, e.g. `2024-03-01T12:30:00Z`
+fn format_timestamp(timestamp: u64) -> String {
+    let (days, seconds) = (timestamp / 86_400, timestamp % 86_400);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// Converts a number of days since the UNIX epoch into a date of the proleptic Gregorian
+/// calendar, following Howard Hinnant's `civil_from_days` algorithm
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    // shift the epoch to 0000-03-01, so that leap days end the year
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    (year, month, day)
+}
+
+/// Returns the faucet, token symbol, amount in the token's unit and amount in base units of an
+/// asset. Non-fungible assets count as a single base unit.
+pub(super) fn asset_fields(client: &Client, asset: &Asset) -> Result<[String; 4], CliError> {
+    let (faucet_id, amount): (AccountId, u64) = match asset {
+        Asset::Fungible(asset) => (asset.faucet_id(), asset.amount()),
+        Asset::NonFungible(asset) => (asset.faucet_id(), 1),
+    };
+
+    let (symbol, display_amount) = match client.get_faucet_details(faucet_id)? {
+        Some(details) if matches!(asset, Asset::Fungible(_)) => (
+            details.token_symbol.to_str(),
+            AssetAmount::new(amount, details.decimals).to_string(),
+        ),
+        _ => (String::new(), amount.to_string()),
+    };
+
+    Ok([
+        faucet_id.to_hex(),
+        symbol,
+        display_amount,
+        amount.to_string(),
+    ])
+}
+
+/// Returns the account a pay-to-ID note is meant for, or an empty field for other notes
+pub(super) fn target_field(decoded_note: Option<&DecodedNote>) -> String {
+    match decoded_note {
+        Some(DecodedNote::P2ID { target })
+        | Some(DecodedNote::P2IDR { target, .. })
+        | Some(DecodedNote::P2IDTimelock { target, .. }) => target.to_hex(),
+        _ => String::new(),
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_field, format_timestamp};
+
+    #[test]
+    fn fields_are_escaped() {
+        assert_eq!(escape_field("0x9a3f"), "0x9a3f");
+        assert_eq!(escape_field("rent, march"), "\"rent, march\"");
+        assert_eq!(escape_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_field("=SUM(A1:A2)"), "'=SUM(A1:A2)");
+    }
+
+    #[test]
+    fn timestamps_are_formatted_in_utc() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(951_825_600), "2000-02-29T12:00:00Z");
+        assert_eq!(format_timestamp(1_709_296_200), "2024-03-01T12:30:00Z");
+    }
+}
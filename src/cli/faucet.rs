@@ -0,0 +1,246 @@
+use std::io::{self, Write};
+
+use clap::Parser;
+use miden_client::client::{
+    accounts::{self, FaucetMetadata},
+    assets::AssetAmount,
+    transactions::TransactionTemplate,
+    Client,
+};
+use miden_client::store::accounts::FaucetDetails;
+use objects::{
+    accounts::AccountId,
+    assets::{FungibleAsset, TokenSymbol},
+};
+use tracing::info;
+
+use crate::cli::{create_dynamic_table, parse_account_id, parse_amount, CliError};
+
+// FAUCET COMMAND
+// ================================================================================================
+
+#[derive(Debug, Clone, Parser)]
+#[clap(about = "Create fungible faucets, mint their tokens and inspect their issuance")]
+pub enum FaucetCmd {
+    /// Create a new fungible faucet. Missing parameters are prompted for
+    #[clap(short_flag = 'n')]
+    Create {
+        #[clap(short, long)]
+        token_symbol: Option<String>,
+        #[clap(short, long)]
+        decimals: Option<u8>,
+        #[clap(short, long)]
+        max_supply: Option<u64>,
+    },
+    /// Mint tokens from the specified faucet into a note for the target account
+    #[clap(short_flag = 'm')]
+    Mint {
+        #[clap()]
+        faucet_id: String,
+        #[clap(long, value_name = "ACCOUNT_ID")]
+        to: String,
+        /// Amount to mint, in whole tokens (e.g. `1.5`) if the faucet's decimals are known and in
+        /// base units otherwise
+        #[clap(long)]
+        amount: String,
+    },
+    /// Show the metadata and total issuance of the specified faucet, or of all faucets if none is
+    /// specified
+    #[clap(short_flag = 's')]
+    Status {
+        #[clap()]
+        faucet_id: Option<String>,
+    },
+    /// Record the token symbol and decimals of a faucet not managed by this client, so that amounts
+    /// of its tokens are read and shown in whole tokens
+    #[clap(short_flag = 'd')]
+    Details {
+        #[clap()]
+        faucet_id: String,
+        #[clap(long)]
+        token_symbol: String,
+        #[clap(long)]
+        decimals: u8,
+    },
+    /// Request test tokens for an account from a faucet service, such as the faucet of a public
+    /// testnet. The note it mints is retrieved by the next sync
+    #[clap(short_flag = 'r')]
+    Request {
+        /// Account the tokens are sent to
+        #[clap(long)]
+        account: String,
+        /// Amount to request, in base units of the faucet's token
+        #[clap(long)]
+        amount: u64,
+        /// URL of the faucet service. Defaults to the faucet of the active wallet set under
+        /// `[faucet]` in the config file
+        #[clap(long)]
+        url: Option<String>,
+    },
+}
+
+impl FaucetCmd {
+    pub async fn execute(
+        &self,
+        mut client: Client,
+        default_faucet_url: Option<&str>,
+    ) -> Result<(), CliError> {
+        match self {
+            FaucetCmd::Create {
+                token_symbol,
+                decimals,
+                max_supply,
+            } => {
+                let token_symbol = match token_symbol {
+                    Some(token_symbol) => token_symbol.clone(),
+                    None => prompt("Token symbol")?,
+                };
+                let decimals = match decimals {
+                    Some(decimals) => *decimals,
+                    None => prompt("Decimals")?,
+                };
+                let max_supply = match max_supply {
+                    Some(max_supply) => *max_supply,
+                    None => prompt("Max supply")?,
+                };
+
+                let template = accounts::AccountTemplate::FungibleFaucet {
+                    token_symbol: TokenSymbol::new(&token_symbol).map_err(|err| {
+                        CliError::Input(format!("error: token symbol is invalid: {}", err))
+                    })?,
+                    decimals,
+                    max_supply,
+                    storage_mode: accounts::AccountStorageMode::Local,
+                };
+                let (faucet, _seed) = client.new_account(template)?;
+                println!("Created faucet with ID: {}", faucet.id());
+            }
+            FaucetCmd::Mint {
+                faucet_id,
+                to,
+                amount,
+            } => {
+                let faucet_id = parse_account_id(&client, faucet_id)?;
+                let target_account_id = parse_account_id(&client, to)?;
+                let base_units = parse_amount(&client, faucet_id, amount)?;
+                let asset = FungibleAsset::new(faucet_id, base_units)
+                    .map_err(|err| CliError::Input(err.to_string()))?;
+
+                let transaction_execution_result =
+                    client.new_transaction(TransactionTemplate::MintFungibleAsset {
+                        asset,
+                        target_account_id,
+                    })?;
+
+                info!("Executed mint transaction, proving and then submitting...");
+
+                client
+                    .send_transaction(transaction_execution_result)
+                    .await?;
+                println!("Minted {amount} tokens from faucet {faucet_id} to {target_account_id}");
+            }
+            FaucetCmd::Status { faucet_id } => {
+                let faucet_ids = match faucet_id {
+                    Some(faucet_id) => vec![parse_account_id(&client, faucet_id)?],
+                    None => client.get_faucet_ids()?,
+                };
+                show_faucet_status(&client, &faucet_ids)?;
+            }
+            FaucetCmd::Details {
+                faucet_id,
+                token_symbol,
+                decimals,
+            } => {
+                let faucet_id = parse_account_id(&client, faucet_id)?;
+                let faucet_details = FaucetDetails {
+                    token_symbol: TokenSymbol::new(token_symbol).map_err(|err| {
+                        CliError::Input(format!("error: token symbol is invalid: {}", err))
+                    })?,
+                    decimals: *decimals,
+                };
+                client.insert_faucet_details(faucet_id, &faucet_details)?;
+                println!("Recorded the details of faucet {faucet_id}");
+            }
+            FaucetCmd::Request {
+                account,
+                amount,
+                url,
+            } => {
+                let faucet_url = url.as_deref().or(default_faucet_url).ok_or_else(|| {
+                    CliError::Input(
+                        "No faucet service set, pass one with --url or set `faucet.url` in the config file"
+                            .to_string(),
+                    )
+                })?;
+                let account_id = parse_account_id(&client, account)?;
+
+                let grant = client
+                    .request_faucet_tokens(faucet_url, account_id, *amount)
+                    .await?;
+                println!(
+                    "Faucet {faucet_url} minted note {} for {account_id}, it will be retrieved by the next sync",
+                    grant.note_id.inner()
+                );
+                if !grant.details_known {
+                    println!("The faucet did not share the note's details, so the note can only be consumed once they are imported");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// SHOW STATUS
+// ================================================================================================
+
+fn show_faucet_status(client: &Client, faucet_ids: &[AccountId]) -> Result<(), CliError> {
+    let mut table = create_dynamic_table(&[
+        "Faucet ID",
+        "Symbol",
+        "Decimals",
+        "Max Supply",
+        "Total Issuance",
+    ]);
+    for faucet_id in faucet_ids {
+        let FaucetMetadata {
+            token_symbol,
+            decimals,
+            max_supply,
+            total_issuance,
+        } = client.get_faucet_metadata(*faucet_id)?;
+
+        table.add_row(vec![
+            faucet_id.to_hex(),
+            token_symbol.to_str(),
+            decimals.to_string(),
+            AssetAmount::new(max_supply, decimals).to_string(),
+            AssetAmount::new(total_issuance, decimals).to_string(),
+        ]);
+    }
+
+    println!("{table}");
+    Ok(())
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Asks the user for a value on stdin until one that can be parsed as `T` is entered
+fn prompt<T: std::str::FromStr>(message: &str) -> Result<T, CliError> {
+    loop {
+        print!("{message}: ");
+        io::stdout()
+            .flush()
+            .map_err(|err| CliError::Fatal(err.to_string()))?;
+
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .map_err(|err| CliError::Fatal(err.to_string()))?;
+
+        match input.trim().parse() {
+            Ok(value) => return Ok(value),
+            Err(_) => println!("Invalid value, please try again"),
+        }
+    }
+}
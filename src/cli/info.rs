@@ -1,15 +1,17 @@
 use miden_client::client::Client;
 
-pub fn print_client_info(client: &Client) -> Result<(), String> {
+use super::CliError;
+
+pub fn print_client_info(client: &Client) -> Result<(), CliError> {
     print_block_number(client)
 }
 
 // HELPERS
 // ================================================================================================
-fn print_block_number(client: &Client) -> Result<(), String> {
-    println!(
-        "block number: {}",
-        client.get_sync_height().map_err(|e| e.to_string())?
-    );
+fn print_block_number(client: &Client) -> Result<(), CliError> {
+    println!("block number: {}", client.get_sync_height()?);
+    if let Some(progress) = client.get_sync_progress()? {
+        println!("interrupted sync: {progress}");
+    }
     Ok(())
 }
@@ -1,24 +1,36 @@
 use std::{
+    collections::BTreeMap,
     fs::File,
     io::{Read, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
-use crate::cli::create_dynamic_table;
+use crate::cli::{
+    create_dynamic_table,
+    csv::{asset_fields, block_time_fields, target_field, CsvWriter},
+    parse_account_id, parse_note_id, CliError, PageArgs,
+};
 
 use super::{Client, Parser};
 use clap::ValueEnum;
 use comfy_table::{presets, Attribute, Cell, ContentArrangement, Table};
-use miden_client::store::notes::{InputNoteFilter, InputNoteRecord};
+use miden_client::{
+    client::notes::{decode_memo, decode_unlock_height, ExpectedNote},
+    store::{
+        accounts::FaucetDetails,
+        notes::{self, InputNoteFilter, InputNoteRecord, NoteOrigin},
+    },
+};
 
-use crypto::utils::{Deserializable, Serializable};
+use crypto::utils::{ByteReader, Deserializable, DeserializationError, Serializable, SliceReader};
 
-use objects::{notes::NoteId, Digest};
+use objects::{accounts::AccountId, notes::NoteId, Digest};
 use tracing::warn;
 
 #[derive(Clone, Debug, ValueEnum)]
 pub enum NoteFilter {
     Pending,
+    Expected,
     Committed,
     Consumed,
 }
@@ -32,13 +44,44 @@ pub enum InputNotes {
         /// Filter the displayed note list
         #[clap(short, long)]
         filter: Option<NoteFilter>,
+
+        /// Only list notes sent by the specified account
+        #[clap(long, value_name = "ACCOUNT_ID")]
+        sender: Option<String>,
+
+        /// Only list notes with the specified tag
+        #[clap(long)]
+        tag: Option<u64>,
+
+        /// Only list notes carrying assets issued by the specified faucet
+        #[clap(long, value_name = "FAUCET_ID")]
+        faucet: Option<String>,
+
+        /// Only list notes included in a block at or after the specified block number
+        #[clap(long)]
+        from_block: Option<u32>,
+
+        /// Only list notes included in a block at or before the specified block number
+        #[clap(long)]
+        to_block: Option<u32>,
+
+        /// Only list committed notes that can be consumed by the specified account
+        #[clap(long, value_name = "ACCOUNT_ID")]
+        consumable_by: Option<String>,
+
+        /// Only list notes whose memo contains the specified text
+        #[clap(long)]
+        memo: Option<String>,
+
+        #[clap(flatten)]
+        page: PageArgs,
     },
 
     /// Show details of the input note for the specified note ID
     #[clap(short_flag = 's')]
     Show {
-        /// Note ID of the input note to show
-        #[clap()]
+        /// Note ID of the input note to show, or a prefix of it identifying a single note
+        #[clap(value_name = "NOTE_ID")]
         id: String,
 
         /// Show note script
@@ -54,42 +97,106 @@ pub enum InputNotes {
         inputs: bool,
     },
 
-    /// Export input note data to a binary file
+    /// Export input note data to a binary file, or every input note to a CSV file. The binary file
+    /// also holds the token symbol and decimals of the faucets of the note's assets, if known
     #[clap(short_flag = 'e')]
     Export {
         /// Note ID of the input note to show
-        #[clap()]
-        id: String,
+        #[clap(required_unless_present = "csv", value_name = "NOTE_ID")]
+        id: Option<String>,
 
         /// Path to the file that will contain the input note data. If not provided, the filename will be the input note ID
         #[clap()]
         filename: Option<PathBuf>,
+
+        /// Write every input note to this CSV file instead, one row per asset, with the times the
+        /// notes were committed and consumed
+        #[clap(long, value_name = "FILE", conflicts_with_all = ["id", "filename"])]
+        csv: Option<PathBuf>,
     },
 
-    /// Import input note data from a binary file
+    /// Import input note data from a binary file, recording the details of the faucets of its
+    /// assets that this client does not know yet
     #[clap(short_flag = 'i')]
     Import {
         /// Path to the file that contains the input note data
         #[clap()]
         filename: PathBuf,
     },
+
+    /// Wait for a note that is not committed yet, e.g. a payment announced by the payer. Syncing
+    /// reports the note once it is committed
+    Expect {
+        /// ID of the expected note, of which only the arrival is recorded as it can't be consumed
+        /// without its details
+        #[clap(required_unless_present = "filename", requires = "tag")]
+        id: Option<String>,
+
+        /// Tag the note is created with
+        #[clap(long)]
+        tag: Option<u64>,
+
+        /// Path to a file containing the note's data, as written by `export`, so that the note can
+        /// be consumed once committed
+        #[clap(long, conflicts_with = "id")]
+        filename: Option<PathBuf>,
+
+        /// Description of the expected note
+        #[clap(long)]
+        label: Option<String>,
+    },
+
+    /// List the notes expected by ID and the block they were committed in
+    ListExpected,
 }
 
 impl InputNotes {
-    pub fn execute(&self, mut client: Client) -> Result<(), String> {
+    pub async fn execute(&self, mut client: Client) -> Result<(), CliError> {
         match self {
-            InputNotes::List { filter } => {
-                let filter = match filter {
+            InputNotes::List {
+                filter,
+                sender,
+                tag,
+                faucet,
+                from_block,
+                to_block,
+                consumable_by,
+                memo,
+                page,
+            } => {
+                let status = match filter {
                     Some(NoteFilter::Committed) => InputNoteFilter::Committed,
                     Some(NoteFilter::Consumed) => {
                         warn!("Nullifiers are not currently being set on the node");
                         InputNoteFilter::Consumed
                     }
                     Some(NoteFilter::Pending) => InputNoteFilter::Pending,
+                    Some(NoteFilter::Expected) => InputNoteFilter::Expected,
                     None => InputNoteFilter::All,
                 };
 
-                list_input_notes(client, filter)?;
+                let mut filter = notes::NoteFilter::new().status(status);
+                if let Some(sender) = sender {
+                    filter = filter.sender(parse_account_id(&client, sender)?);
+                }
+                if let Some(tag) = tag {
+                    filter = filter.tag(*tag);
+                }
+                if let Some(faucet) = faucet {
+                    filter = filter.faucet(parse_account_id(&client, faucet)?);
+                }
+                if from_block.is_some() || to_block.is_some() {
+                    filter =
+                        filter.block_range(from_block.unwrap_or(0), to_block.unwrap_or(u32::MAX));
+                }
+                if let Some(account_id) = consumable_by {
+                    filter = filter.consumable_by(parse_account_id(&client, account_id)?);
+                }
+                if let Some(memo) = memo {
+                    filter = filter.memo(memo);
+                }
+
+                list_input_notes(client, filter, page)?;
             }
             InputNotes::Show {
                 id,
@@ -99,13 +206,79 @@ impl InputNotes {
             } => {
                 show_input_note(client, id.to_owned(), *script, *vault, *inputs)?;
             }
-            InputNotes::Export { id, filename } => {
+            InputNotes::Export {
+                csv: Some(path), ..
+            } => {
+                let num_notes = export_notes_csv(&client, path)?;
+                println!("Exported {num_notes} notes to {}", path.display());
+            }
+            InputNotes::Export {
+                id: Some(id),
+                filename,
+                ..
+            } => {
                 export_note(&client, id, filename.clone())?;
                 println!("Succesfully exported note {}", id);
             }
+            InputNotes::Export { .. } => {
+                unreachable!("clap requires a note ID unless --csv is set")
+            }
             InputNotes::Import { filename } => {
                 let note_id = import_note(&mut client, filename.clone())?;
                 println!("Succesfully imported note {}", note_id.inner());
+
+                // notes committed in blocks the client synced past need the block's MMR data
+                let inclusion_proof = client.get_input_note(note_id)?.inclusion_proof().cloned();
+                if let Some(inclusion_proof) = inclusion_proof {
+                    let block_num = inclusion_proof.origin().block_num;
+                    if let Err(err) = client.authenticate_block(block_num).await {
+                        warn!("Failed to authenticate block {block_num}, it will be retried on the next sync: {err}");
+                    }
+                }
+            }
+            InputNotes::Expect {
+                id,
+                tag,
+                filename,
+                label,
+            } => {
+                let expected_note = match (id, tag, filename) {
+                    (Some(id), Some(tag), _) => {
+                        let note_id = Digest::try_from(id.as_str())
+                            .map_err(|err| CliError::Input(format!("Invalid note ID {id}: {err}")))?
+                            .into();
+                        ExpectedNote::Id { note_id, tag: *tag }
+                    }
+                    (_, _, Some(filename)) => {
+                        ExpectedNote::Details(read_note_file(filename)?.0.note().clone())
+                    }
+                    _ => unreachable!("clap requires either a note ID and tag or a file"),
+                };
+
+                let note_id = match &expected_note {
+                    ExpectedNote::Id { note_id, .. } => *note_id,
+                    ExpectedNote::Details(note) => note.id(),
+                };
+                if client.add_expected_note(expected_note, label.as_deref())? {
+                    println!("Waiting for note {}", note_id.inner());
+                } else {
+                    println!("Note {} is already known", note_id.inner());
+                }
+            }
+            InputNotes::ListExpected => {
+                let mut table = create_dynamic_table(&["Note ID", "Tag", "Label", "Commit Height"]);
+                for record in client.get_expected_note_ids()? {
+                    table.add_row(vec![
+                        record.note_id.inner().to_string(),
+                        record.tag.to_string(),
+                        record.label.unwrap_or_default(),
+                        record
+                            .commit_height
+                            .map(|block_num| block_num.to_string())
+                            .unwrap_or("-".to_string()),
+                    ]);
+                }
+                println!("{table}");
             }
         }
         Ok(())
@@ -114,8 +287,15 @@ impl InputNotes {
 
 // LIST INPUT NOTES
 // ================================================================================================
-fn list_input_notes(client: Client, input_note_filter: InputNoteFilter) -> Result<(), String> {
-    let notes = client.get_input_notes(input_note_filter)?;
+fn list_input_notes(
+    client: Client,
+    note_filter: notes::NoteFilter,
+    page: &PageArgs,
+) -> Result<(), CliError> {
+    let notes = match page.page() {
+        Some(page) => client.get_input_notes_page(note_filter, page)?,
+        None => client.get_input_notes(note_filter)?,
+    };
 
     print_notes_summary(&notes);
     Ok(())
@@ -127,10 +307,8 @@ pub fn export_note(
     client: &Client,
     note_id: &str,
     filename: Option<PathBuf>,
-) -> Result<File, String> {
-    let note_id = Digest::try_from(note_id)
-        .map_err(|err| format!("Failed to parse input note id: {}", err))?
-        .into();
+) -> Result<File, CliError> {
+    let note_id = parse_note_id(client, note_id)?;
     let note = client.get_input_note(note_id)?;
 
     let file_path = filename.unwrap_or_else(|| {
@@ -139,33 +317,153 @@ pub fn export_note(
         dir
     });
 
-    let mut file = File::create(file_path).map_err(|err| err.to_string())?;
+    // the details of the note's faucets follow the note, a note carries at most 255 assets
+    let faucet_details = client.get_note_faucet_details(note.note())?;
+    let mut bytes = note.to_bytes();
+    bytes.push(faucet_details.len() as u8);
+    for (faucet_id, details) in faucet_details.iter() {
+        bytes.extend(faucet_id.to_bytes());
+        bytes.extend(details.to_bytes());
+    }
 
-    file.write_all(&note.to_bytes())
-        .map_err(|err| err.to_string())?;
+    let mut file = File::create(file_path).map_err(|err| CliError::Fatal(err.to_string()))?;
+
+    file.write_all(&bytes)
+        .map_err(|err| CliError::Fatal(err.to_string()))?;
 
     Ok(file)
 }
 
+/// Columns of the CSV export of input notes
+const NOTES_CSV_HEADER: [&str; 15] = [
+    "note_id",
+    "status",
+    "sender",
+    "target",
+    "tag",
+    "faucet_id",
+    "token",
+    "amount",
+    "base_units",
+    "committed_block",
+    "committed_at",
+    "consumed_block",
+    "consumed_at",
+    "script",
+    "memo",
+];
+
+/// Writes every input note to a CSV file, one row per asset so that amounts can be summed per
+/// token. Returns the number of notes written.
+fn export_notes_csv(client: &Client, path: &Path) -> Result<usize, CliError> {
+    let mut notes: BTreeMap<Digest, InputNoteRecord> = client
+        .get_input_notes(InputNoteFilter::All)?
+        .into_iter()
+        .map(|note| (note.note_id().inner(), note))
+        .collect();
+    let timelines = client.get_note_timelines()?;
+
+    let mut writer = CsvWriter::create(path, &NOTES_CSV_HEADER)?;
+    let mut num_notes = 0;
+    for timeline in timelines {
+        let note = match notes.remove(&timeline.note_id.inner()) {
+            Some(note) => note,
+            None => continue,
+        };
+        let status = match (&timeline.committed, &timeline.consumed) {
+            (_, Some(_)) => "consumed",
+            (Some(_), None) => "committed",
+            (None, None) => "pending",
+        };
+        let decoded_note = client.decode_note(timeline.note_id)?;
+        let metadata = note.note().metadata();
+
+        let row_prefix = vec![
+            timeline.note_id.inner().to_string(),
+            status.to_string(),
+            metadata.sender().to_hex(),
+            target_field(decoded_note.as_ref()),
+            u64::from(metadata.tag()).to_string(),
+        ];
+        let mut row_suffix = Vec::new();
+        row_suffix.extend(block_time_fields(timeline.committed.as_ref()));
+        row_suffix.extend(block_time_fields(timeline.consumed.as_ref()));
+        row_suffix.push(
+            decoded_note
+                .as_ref()
+                .map(|decoded_note| decoded_note.script_name().to_string())
+                .unwrap_or_default(),
+        );
+        row_suffix.push(decode_memo(note.note().inputs().inputs()).unwrap_or_default());
+
+        let assets: Vec<_> = note.note().assets().iter().collect();
+        if assets.is_empty() {
+            let mut row = row_prefix.clone();
+            row.extend(std::iter::repeat(String::new()).take(4));
+            row.extend(row_suffix.iter().cloned());
+            writer.write_row(&row)?;
+        }
+        for asset in assets {
+            let mut row = row_prefix.clone();
+            row.extend(asset_fields(client, asset)?);
+            row.extend(row_suffix.iter().cloned());
+            writer.write_row(&row)?;
+        }
+        num_notes += 1;
+    }
+    writer.finish()?;
+
+    Ok(num_notes)
+}
+
 // IMPORT INPUT NOTE
 // ================================================================================================
-pub fn import_note(client: &mut Client, filename: PathBuf) -> Result<NoteId, String> {
-    let mut contents = vec![];
-    let mut _file = File::open(filename)
-        .and_then(|mut f| f.read_to_end(&mut contents))
-        .map_err(|err| err.to_string());
-
+pub fn import_note(client: &mut Client, filename: PathBuf) -> Result<NoteId, CliError> {
     // TODO: When importing a RecordedNote we want to make sure that the note actually exists in the chain (RPC call)
     // and start monitoring its nullifiers (ie, update the list of relevant tags in the state sync table)
-    let input_note_record =
-        InputNoteRecord::read_from_bytes(&contents).map_err(|err| err.to_string())?;
+    let (input_note_record, faucet_details) = read_note_file(&filename)?;
 
     let note_id = input_note_record.note().id();
-    client.import_input_note(input_note_record)?;
+    client.import_input_note_from(
+        input_note_record,
+        NoteOrigin::File(filename.display().to_string()),
+    )?;
+    client.import_faucet_details(&faucet_details)?;
 
     Ok(note_id)
 }
 
+/// Reads input note data written by [export_note], along with the details of the faucets of the
+/// note's assets. Files written before faucet details were exported end with the note.
+fn read_note_file(
+    filename: &Path,
+) -> Result<(InputNoteRecord, Vec<(AccountId, FaucetDetails)>), CliError> {
+    let mut contents = vec![];
+    File::open(filename)
+        .and_then(|mut f| f.read_to_end(&mut contents))
+        .map_err(|err| CliError::Fatal(err.to_string()))?;
+
+    read_note_file_contents(&mut SliceReader::new(&contents))
+        .map_err(|err| CliError::Input(err.to_string()))
+}
+
+fn read_note_file_contents<R: ByteReader>(
+    reader: &mut R,
+) -> Result<(InputNoteRecord, Vec<(AccountId, FaucetDetails)>), DeserializationError> {
+    let note = InputNoteRecord::read_from(reader)?;
+    let mut faucet_details = Vec::new();
+    if reader.has_more_bytes() {
+        for _ in 0..reader.read_u8()? {
+            faucet_details.push((
+                AccountId::read_from(reader)?,
+                FaucetDetails::read_from(reader)?,
+            ));
+        }
+    }
+
+    Ok((note, faucet_details))
+}
+
 // SHOW INPUT NOTE
 // ================================================================================================
 fn show_input_note(
@@ -174,12 +472,11 @@ fn show_input_note(
     show_script: bool,
     show_vault: bool,
     show_inputs: bool,
-) -> Result<(), String> {
-    let note_id = Digest::try_from(note_id)
-        .map_err(|err| format!("Failed to parse input note with ID: {}", err))?
-        .into();
+) -> Result<(), CliError> {
+    let note_id = parse_note_id(&client, &note_id)?;
 
     let input_note_record = client.get_input_note(note_id)?;
+    let provenance = client.get_note_provenance(note_id)?;
 
     // print note summary
     print_notes_summary(core::iter::once(&input_note_record));
@@ -189,6 +486,56 @@ fn show_input_note(
         .load_preset(presets::UTF8_HORIZONTAL_ONLY)
         .set_content_arrangement(ContentArrangement::DynamicFullWidth);
 
+    // print where the note came from
+    let origin = match provenance.origin {
+        Some(NoteOrigin::Imported) => "Imported".to_string(),
+        Some(NoteOrigin::File(path)) => format!("Imported from file {path}"),
+        Some(NoteOrigin::Transaction(transaction_id)) => {
+            format!("Created by transaction {transaction_id}")
+        }
+        Some(NoteOrigin::Expected) => "Added as an expected note".to_string(),
+        None => "Unknown".to_string(),
+    };
+    let committed = match (provenance.commit_height, provenance.committed_at) {
+        (Some(block_num), Some(timestamp)) => {
+            format!("Found by sync in block {block_num} at UNIX time {timestamp}")
+        }
+        (Some(block_num), None) => format!("Committed in block {block_num}"),
+        (None, _) => "-".to_string(),
+    };
+    table
+        .add_row(vec![
+            Cell::new("Origin").add_attribute(Attribute::Bold),
+            Cell::new(origin),
+        ])
+        .add_row(vec![
+            Cell::new("Stored at (UNIX time)").add_attribute(Attribute::Bold),
+            Cell::new(
+                provenance
+                    .created_at
+                    .map(|timestamp| timestamp.to_string())
+                    .unwrap_or("-".to_string()),
+            ),
+        ])
+        .add_row(vec![
+            Cell::new("Committed").add_attribute(Attribute::Bold),
+            Cell::new(committed),
+        ]);
+
+    // print the inputs of notes created with a known script
+    if let Some(decoded_note) = client.decode_note(note_id)? {
+        table.add_row(vec![
+            Cell::new("Script").add_attribute(Attribute::Bold),
+            Cell::new(decoded_note.script_name()),
+        ]);
+        for (name, value) in decoded_note.fields() {
+            table.add_row(vec![
+                Cell::new(name).add_attribute(Attribute::Bold),
+                Cell::new(value),
+            ]);
+        }
+    }
+
     // print note script
     if show_script {
         table
@@ -254,6 +601,8 @@ where
         "Inputs Hash",
         "Serial Num",
         "Commit Height",
+        "Memo",
+        "Spendable After",
     ]);
 
     notes.into_iter().for_each(|input_note_record| {
@@ -268,6 +617,10 @@ where
             input_note_record.note().inputs().hash().to_string(),
             Digest::new(input_note_record.note().serial_num()).to_string(),
             commit_height,
+            decode_memo(input_note_record.note().inputs().inputs()).unwrap_or_default(),
+            decode_unlock_height(input_note_record.note())
+                .map(|block_num| block_num.to_string())
+                .unwrap_or("-".to_string()),
         ]);
     });
 
@@ -284,11 +637,12 @@ mod tests {
     use miden_client::{
         client::Client,
         config::{ClientConfig, Endpoint},
-        store::notes::InputNoteRecord,
+        store::{accounts::FaucetDetails, notes::InputNoteRecord},
     };
     use mock::mock::{
         account::MockAccountType, notes::AssetPreservationStatus, transaction::mock_inputs,
     };
+    use objects::assets::{Asset, TokenSymbol};
     use std::env::temp_dir;
     use uuid::Uuid;
 
@@ -325,6 +679,24 @@ mod tests {
         assert!(pending_note.inclusion_proof().is_none());
         assert!(committed_note.inclusion_proof().is_some());
 
+        // the first fungible faucet of the committed note is known to the exporting client
+        let faucet_id = committed_note
+            .note()
+            .assets()
+            .iter()
+            .find_map(|asset| match asset {
+                Asset::Fungible(asset) => Some(asset.faucet_id()),
+                Asset::NonFungible(_) => None,
+            })
+            .unwrap();
+        let faucet_details = FaucetDetails {
+            token_symbol: TokenSymbol::new("TST").unwrap(),
+            decimals: 6,
+        };
+        client
+            .insert_faucet_details(faucet_id, &faucet_details)
+            .unwrap();
+
         let mut filename_path = temp_dir();
         filename_path.push("test_import");
 
@@ -367,6 +739,10 @@ mod tests {
             client.get_input_note(committed_note.note().id()).unwrap();
 
         assert_eq!(committed_note.note().id(), imported_note_record.note().id());
+        assert_eq!(
+            client.get_faucet_details(faucet_id).unwrap(),
+            Some(faucet_details)
+        );
 
         import_note(&mut client, filename_path_pending).unwrap();
         let imported_pending_note_record = client.get_input_note(pending_note.note().id()).unwrap();
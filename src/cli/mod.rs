@@ -1,22 +1,53 @@
-use std::path::Path;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 use comfy_table::{presets, Attribute, Cell, ContentArrangement, Table};
 use figment::{
-    providers::{Format, Toml},
+    providers::{Format, Json, Toml},
     Figment,
 };
-use miden_client::{client::Client, config::ClientConfig};
+use miden_client::{
+    client::{
+        assets::AssetAmount, notifications::WebhookNotifier, read_only::ReadOnlyClient, Client,
+    },
+    config::{default_data_directory, ClientConfig},
+    errors::{AssetAmountError, ClientError, ErrorCategory, RpcApiError, StoreError},
+    store::{Page, Store},
+};
+use objects::{accounts::AccountId, notes::NoteId};
 
 mod account;
+mod address_book;
+mod archive;
+mod completion;
+mod config;
+mod csv;
+mod faucet;
 mod info;
 mod input_notes;
+mod nullifiers;
+mod orders;
+mod progress;
+mod scripts;
+mod serve;
+mod store;
 mod sync;
 mod tags;
+mod telemetry;
 mod transactions;
+#[cfg(feature = "tui")]
+mod tui;
+mod wallet;
+
+/// Config file names, in order of precedence. The format of the file is given by its extension
+const CLIENT_CONFIG_FILE_NAMES: [&str; 2] = ["miden-client.toml", "miden-client.json"];
 
-/// Config file name
-const CLIENT_CONFIG_FILE_NAME: &str = "miden-client.toml";
+/// Environment variable the store passphrase can be provided through
+#[cfg(feature = "sqlcipher")]
+const STORE_PASSPHRASE_ENV: &str = "MIDEN_STORE_PASSPHRASE";
 
 /// Root CLI struct
 #[derive(Parser, Debug)]
@@ -29,6 +60,13 @@ const CLIENT_CONFIG_FILE_NAME: &str = "miden-client.toml";
 pub struct Cli {
     #[clap(subcommand)]
     action: Command,
+    #[clap(flatten)]
+    telemetry: telemetry::TelemetryArgs,
+    /// Reach the node through this proxy, such as `http://proxy.example.com:3128` or
+    /// `socks5h://localhost:1080`, instead of the one set by `rpc.proxy` in the config file or
+    /// through the environment. `none` connects directly
+    #[clap(long, global = true, value_name = "URL")]
+    proxy: Option<String>,
 }
 
 /// CLI actions
@@ -37,9 +75,91 @@ pub enum Command {
     #[clap(subcommand)]
     Account(account::AccountCmd),
     #[clap(subcommand)]
+    AddressBook(address_book::AddressBookCmd),
+    /// Print the candidates for the word at `index` of the command line, for the completion
+    /// scripts
+    #[clap(hide = true)]
+    Complete {
+        #[clap(long)]
+        index: usize,
+        #[clap(last = true)]
+        words: Vec<String>,
+    },
+    /// Print the script completing the CLI's commands, account IDs and note IDs in the specified
+    /// shell, e.g. `source <(miden-client completions zsh)`
+    Completions {
+        #[clap(value_enum)]
+        shell: completion::Shell,
+    },
+    #[clap(subcommand)]
+    Config(config::ConfigCmd),
+    /// Export the accounts, notes, submitted transactions and configuration of the client to a
+    /// portable archive, e.g. to move them to another store or to keep a backup
+    Export {
+        /// File the archive is written to
+        #[clap(long, value_name = "FILE")]
+        all: PathBuf,
+        /// Leave the keys of the accounts out of the archive. Accounts cannot be imported from
+        /// such an archive into a client that does not track them already
+        #[clap(long, default_value_t = false)]
+        without_keys: bool,
+    },
+    #[clap(subcommand)]
+    Faucet(faucet::FaucetCmd),
+    /// Import the accounts, notes and transactions of an archive created with `export --all` that
+    /// the client does not track yet
+    Import {
+        /// Archive file to import
+        #[clap(long, value_name = "FILE")]
+        all: PathBuf,
+        /// Also write the configuration stored in the archive to this file, in JSON format. The
+        /// file must not exist yet
+        #[clap(long, value_name = "FILE")]
+        config_file: Option<PathBuf>,
+    },
+    #[clap(subcommand)]
     InputNotes(input_notes::InputNotes),
+    #[clap(subcommand)]
+    Nullifiers(nullifiers::NullifiersCmd),
+    #[clap(subcommand)]
+    Orders(orders::OrdersCmd),
+    #[clap(subcommand)]
+    Scripts(scripts::ScriptsCmd),
+    /// Run the client as a wallet daemon, serving its API over JSON/HTTP so that several local
+    /// applications can share one synced wallet. Requests authenticate with the token set in the
+    /// `MIDEN_SERVE_TOKEN` environment variable, or with the one printed at startup if it is not set
+    Serve {
+        /// Address the API is served on
+        #[clap(long, default_value = "127.0.0.1:8080")]
+        listen: std::net::SocketAddr,
+        /// Seconds to wait between syncs
+        #[clap(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+        sync_interval: u64,
+    },
+    #[clap(subcommand)]
+    Store(store::StoreCmd),
     /// Sync this client with the latest state of the Miden network.
-    Sync,
+    Sync {
+        /// Only sync the specified accounts. Can be repeated to sync several accounts
+        #[clap(long)]
+        account: Vec<String>,
+        /// Keep syncing periodically until stopped, reporting sync events to the webhook set
+        /// under `[notifications]` in the config file
+        #[clap(long, default_value_t = false)]
+        watch: bool,
+        /// Seconds to wait between syncs in watch mode
+        #[clap(long, default_value_t = 10, requires = "watch")]
+        interval: u64,
+        /// Do not display the progress of the sync on stderr
+        #[clap(long, default_value_t = false, conflicts_with = "watch")]
+        no_progress: bool,
+        /// Serve Prometheus metrics at `http://<address>/metrics` while in watch mode
+        #[cfg(feature = "metrics")]
+        #[clap(long, requires = "watch")]
+        metrics_address: Option<std::net::SocketAddr>,
+        #[clap(subcommand)]
+        command: Option<sync::SyncCmd>,
+    },
     /// View a summary of the current client state
     Info,
     #[clap(subcommand)]
@@ -47,6 +167,16 @@ pub enum Command {
     #[clap(subcommand, name = "tx")]
     #[clap(visible_alias = "transaction")]
     Transaction(transactions::Transaction),
+    #[cfg(feature = "tui")]
+    /// Show a terminal dashboard of the accounts, notes and recent transactions, syncing
+    /// periodically until `q` is pressed
+    Tui {
+        /// Seconds to wait between syncs
+        #[clap(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+        sync_interval: u64,
+    },
+    #[clap(subcommand)]
+    Wallet(wallet::WalletCmd),
     #[cfg(feature = "mock")]
     /// Insert mock data into the client. This is optional because it takes a few seconds
     MockData {
@@ -55,24 +185,192 @@ pub enum Command {
     },
 }
 
+// CLI ERROR
+// ================================================================================================
+
+/// Error returned by CLI commands. The process exits with a code that depends on its
+/// [ErrorCategory], see [CliError::exit_code].
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    #[error(transparent)]
+    Client(#[from] ClientError),
+    /// Invalid arguments or files provided to the command
+    #[error("{0}")]
+    Input(String),
+    /// Failures the command cannot recover from, such as a file that cannot be read or written
+    #[error("{0}")]
+    Fatal(String),
+}
+
+impl CliError {
+    /// Exit code of the process when the command fails with this error:
+    ///
+    /// - `1` for fatal errors.
+    /// - `2` for invalid input.
+    /// - `75` for errors that may go away if the command is retried (`EX_TEMPFAIL`).
+    pub fn exit_code(&self) -> i32 {
+        match self.category() {
+            ErrorCategory::Fatal => 1,
+            ErrorCategory::InvalidInput => 2,
+            ErrorCategory::Retryable => 75,
+        }
+    }
+
+    fn category(&self) -> ErrorCategory {
+        match self {
+            CliError::Client(err) => err.category(),
+            CliError::Input(_) => ErrorCategory::InvalidInput,
+            CliError::Fatal(_) => ErrorCategory::Fatal,
+        }
+    }
+
+    /// Suggests how to recover from the error, if there is a command for it
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            CliError::Client(ClientError::NetworkMismatch(..)) => Some(
+                "Run `miden-client store reset` to sync the node's chain from scratch while keeping accounts, or `miden-client store reset --network` to archive the store and start over with an empty one.",
+            ),
+            CliError::Client(ClientError::RpcApiError(
+                RpcApiError::IncompatibleNodeVersion { .. },
+            )) => Some(
+                "Install a client release matching the node's version, or set `rpc.endpoint` to a node running a version the client supports.",
+            ),
+            CliError::Client(ClientError::AccountStateDesynchronized(_)) => Some(
+                "Run `miden-client account import <file>` with a file holding the current state of the account.",
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl From<StoreError> for CliError {
+    fn from(err: StoreError) -> Self {
+        Self::Client(err.into())
+    }
+}
+
+impl From<AssetAmountError> for CliError {
+    fn from(err: AssetAmountError) -> Self {
+        Self::Input(err.to_string())
+    }
+}
+
 /// CLI entry point
 impl Cli {
-    pub async fn execute(&self) -> Result<(), String> {
+    pub async fn execute(&self) -> Result<(), CliError> {
+        self.telemetry.init().map_err(CliError::Fatal)?;
+
+        match &self.action {
+            Command::Complete { index, words } => {
+                // the store is only opened if an account or note is being completed
+                let mut client = None;
+                let candidates =
+                    completion::complete(&Cli::command(), words, *index, &mut |kind| {
+                        if client.is_none() {
+                            client = open_client_for_completion();
+                        }
+                        client
+                            .as_ref()
+                            .and_then(|client| completion::lookup_values(client, kind).ok())
+                            .unwrap_or_default()
+                    });
+                completion::print_candidates(&candidates);
+                return Ok(());
+            }
+            Command::Completions { shell } => {
+                print!("{}", completion::completion_script(*shell));
+                return Ok(());
+            }
+            _ => {}
+        }
+
         // Create the client
-        let mut current_dir = std::env::current_dir().map_err(|err| err.to_string())?;
-        current_dir.push(CLIENT_CONFIG_FILE_NAME);
+        let config_file = find_config_file()?;
+        match &self.action {
+            Command::Config(config) => return config.execute(&config_file),
+            Command::Wallet(wallet) => return wallet.execute(&config_file),
+            _ => {}
+        }
+
+        let mut client_config = load_config(&config_file)?;
+        if let Some(proxy) = &self.proxy {
+            client_config.rpc.proxy = Some(proxy.clone());
+            client_config
+                .validate()
+                .map_err(|err| CliError::Input(format!("Invalid --proxy: {err}")))?;
+        }
+        move_stores_to_data_directory(&client_config)?;
+        #[cfg(feature = "sqlcipher")]
+        resolve_store_passphrase(&mut client_config)?;
 
-        let client_config = load_config(current_dir.as_path())?;
+        // Store commands manage the database themselves and must run before the client opens
+        // (and thereby migrates) it
+        if let Command::Store(store) = &self.action {
+            return store.execute((&client_config).into());
+        }
+
+        #[cfg(feature = "metrics")]
+        if let Command::Sync {
+            metrics_address: Some(address),
+            ..
+        } = &self.action
+        {
+            miden_client::metrics::install_prometheus_exporter(*address).map_err(|err| {
+                CliError::Fatal(format!("Failed to start the metrics endpoint: {err}"))
+            })?;
+        }
+
+        let webhook = WebhookNotifier::from_config(&client_config.notifications);
+        let faucet_url = client_config.faucet_url().map(str::to_string);
+        let data_directory = client_config.data_directory.clone();
         let client = Client::new(client_config)?;
 
         // Execute cli command
         match &self.action {
-            Command::Account(account) => account.execute(client),
+            Command::Account(account) => account.execute(client).await,
+            Command::AddressBook(address_book) => address_book.execute(client),
+            Command::Complete { .. } | Command::Completions { .. } => {
+                unreachable!("completion commands are executed without a client")
+            }
+            Command::Config(_) => unreachable!("config commands are executed without a client"),
+            Command::Export { all, without_keys } => {
+                archive::export_archive(&client, load_config(&config_file)?, all, !without_keys)
+            }
+            Command::Faucet(faucet) => faucet.execute(client, faucet_url.as_deref()).await,
+            Command::Import { all, config_file } => {
+                archive::import_archive(client, all, config_file.as_ref())
+            }
             Command::Info => info::print_client_info(&client),
-            Command::InputNotes(notes) => notes.execute(client),
-            Command::Sync => sync::sync_state(client).await,
+            Command::InputNotes(notes) => notes.execute(client).await,
+            Command::Nullifiers(nullifiers) => nullifiers.execute(client),
+            Command::Orders(orders) => orders.execute(client),
+            Command::Serve {
+                listen,
+                sync_interval,
+            } => serve::serve(client, *listen, *sync_interval).await,
+            Command::Store(_) => unreachable!("store commands are executed without a client"),
+            Command::Sync {
+                account,
+                watch: true,
+                interval,
+                command: None,
+            } => sync::watch_state(client, account, *interval, webhook).await,
+            Command::Sync {
+                account,
+                no_progress,
+                command: None,
+                ..
+            } => sync::sync_state(client, account, *no_progress, self.telemetry.is_verbose()).await,
+            Command::Sync {
+                command: Some(command),
+                ..
+            } => command.execute(client).await,
+            Command::Scripts(scripts) => scripts.execute(client),
             Command::Tags(tags) => tags.execute(client).await,
-            Command::Transaction(transaction) => transaction.execute(client).await,
+            Command::Transaction(transaction) => transaction.execute(client, &data_directory).await,
+            #[cfg(feature = "tui")]
+            Command::Tui { sync_interval } => tui::run_dashboard(client, *sync_interval).await,
+            Command::Wallet(_) => unreachable!("wallet commands are executed without a client"),
             #[cfg(feature = "mock")]
             Command::MockData { transaction } => {
                 let mut client = client;
@@ -86,21 +384,157 @@ impl Cli {
     }
 }
 
-/// Loads the client configuration.
-///
-/// This function will look for the configuration file at the provided path. If the path is
-/// relative, searches in parent directories all the way to the root as well.
-pub fn load_config(config_file: &Path) -> Result<ClientConfig, String> {
-    Figment::from(Toml::file(config_file))
-        .extract()
-        .map_err(|err| {
-            format!(
-                "Failed to load {} config file: {err}",
-                config_file.display()
-            )
+/// Opens the store of the config file in the current directory read-only to complete account and
+/// note IDs, which neither migrates the store nor waits for a lock held by a running client.
+/// Completion must stay silent, so no client is returned if the store cannot be opened as is, e.g.
+/// because it needs a passphrase
+fn open_client_for_completion() -> Option<ReadOnlyClient> {
+    let client_config = load_config(&find_config_file().ok()?).ok()?;
+    let store_path = client_config.database_path(client_config.active_store());
+    Client::new_read_only(store_path).ok()
+}
+
+/// Returns the path of the config file in the current directory
+fn find_config_file() -> Result<PathBuf, CliError> {
+    let current_dir = std::env::current_dir().map_err(|err| CliError::Fatal(err.to_string()))?;
+
+    CLIENT_CONFIG_FILE_NAMES
+        .iter()
+        .map(|file_name| current_dir.join(file_name))
+        .find(|config_file| config_file.exists())
+        .ok_or_else(|| {
+            CliError::Input(format!(
+                "No config file found in {}, expected one of {}",
+                current_dir.display(),
+                CLIENT_CONFIG_FILE_NAMES.join(", ")
+            ))
         })
 }
 
+/// Loads the client configuration from the provided file, which is parsed as JSON if it has a
+/// `.json` extension and as TOML otherwise.
+///
+/// Options missing from the file take their default values. Unknown options and values outside
+/// of their valid range are rejected, with every problem found reported along with the key it
+/// concerns.
+pub fn load_config(config_file: &Path) -> Result<ClientConfig, CliError> {
+    let figment = if is_json_config(config_file) {
+        Figment::from(Json::file(config_file))
+    } else {
+        Figment::from(Toml::file(config_file))
+    };
+
+    extract_config(figment, config_file)
+}
+
+/// Returns whether the config file is in JSON rather than TOML format
+fn is_json_config(config_file: &Path) -> bool {
+    matches!(config_file.extension(), Some(extension) if extension == "json")
+}
+
+/// Extracts and validates the client configuration from `figment`, reporting errors as coming
+/// from `config_file`
+fn extract_config(figment: Figment, config_file: &Path) -> Result<ClientConfig, CliError> {
+    let mut config: ClientConfig = figment.extract().map_err(|errors| {
+        let errors: Vec<String> = errors.into_iter().map(|err| format!("  - {err}")).collect();
+        CliError::Input(format!(
+            "Invalid config file {}:\n{}",
+            config_file.display(),
+            errors.join("\n")
+        ))
+    })?;
+
+    // unlike library users, the CLI keeps its files in the platform's data directory by default
+    if config.data_directory.as_os_str().is_empty() {
+        config.data_directory = default_data_directory();
+    }
+
+    config.validate().map_err(|err| {
+        CliError::Input(format!(
+            "Invalid config file {}: {err}",
+            config_file.display()
+        ))
+    })?;
+
+    Ok(config)
+}
+
+/// Moves the stores configured with a relative path that were created in the current directory,
+/// before relative paths were resolved against the data directory, into the data directory.
+///
+/// # Errors
+/// Fails if the data directory already holds a store at the same path, as it is not known which
+/// of the two stores the user means to keep using.
+fn move_stores_to_data_directory(config: &ClientConfig) -> Result<(), CliError> {
+    let stores = [&config.store]
+        .into_iter()
+        .chain(config.wallets.stores.values());
+    for store in stores {
+        let legacy_path = Path::new(&store.database_filepath);
+        let database_path = config.database_path(store);
+        if legacy_path.is_absolute() || !legacy_path.exists() {
+            continue;
+        }
+        if database_path.exists() {
+            // with the data directory set to the current directory both paths are the same store
+            let is_same_store = match (
+                fs::canonicalize(legacy_path),
+                fs::canonicalize(&database_path),
+            ) {
+                (Ok(legacy_path), Ok(database_path)) => legacy_path == database_path,
+                _ => false,
+            };
+            if is_same_store {
+                continue;
+            }
+            return Err(CliError::Input(format!(
+                "Found store {} in the current directory while the data directory already holds \
+                store {}. Stores are now kept in the data directory: remove or move away one of \
+                them, or set `data_directory = \".\"` in the config file to keep using the one in \
+                the current directory",
+                legacy_path.display(),
+                database_path.display()
+            )));
+        }
+
+        Store::relocate(legacy_path, &database_path).map_err(|err| {
+            CliError::Fatal(format!(
+                "Failed to move store {} to the data directory {}: {err}",
+                legacy_path.display(),
+                config.data_directory.display()
+            ))
+        })?;
+        eprintln!(
+            "Moved store {} to {}, as stores are now kept in the data directory. Set \
+            `data_directory = \".\"` in the config file to keep them in the current directory.",
+            legacy_path.display(),
+            database_path.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Fills in the store passphrase when it is not set in the config file, reading it from the
+/// [STORE_PASSPHRASE_ENV] environment variable or prompting for it if the store is encrypted.
+#[cfg(feature = "sqlcipher")]
+fn resolve_store_passphrase(config: &mut ClientConfig) -> Result<(), CliError> {
+    let database_path = config.database_path(config.active_store());
+    let store_config = config.active_store_mut();
+    if store_config.passphrase.is_none() {
+        store_config.passphrase = std::env::var(STORE_PASSPHRASE_ENV).ok();
+    }
+
+    let is_encrypted = Store::is_encrypted(database_path)?;
+    if store_config.passphrase.is_none() && is_encrypted {
+        let passphrase = rpassword::prompt_password("Store passphrase: ")
+            .map_err(|err| CliError::Fatal(err.to_string()))?;
+        store_config.passphrase = Some(passphrase);
+    }
+
+    Ok(())
+}
+
 pub fn create_dynamic_table(headers: &[&str]) -> Table {
     let header_cells = headers
         .iter()
@@ -115,3 +549,64 @@ pub fn create_dynamic_table(headers: &[&str]) -> Table {
 
     table
 }
+
+/// Pagination flags shared by the commands that list store records
+#[derive(Debug, Clone, clap::Args)]
+pub struct PageArgs {
+    /// Maximum number of records to list
+    #[clap(long)]
+    limit: Option<u32>,
+    /// Page of records to list, starting at 1
+    #[clap(long, requires = "limit", value_parser = clap::value_parser!(u64).range(1..))]
+    page: Option<u64>,
+}
+
+impl PageArgs {
+    /// Returns the requested page, or `None` if all records should be listed
+    pub fn page(&self) -> Option<Page> {
+        self.limit
+            .map(|limit| Page::nth(limit, self.page.unwrap_or(1)))
+    }
+}
+
+/// Parses an amount of tokens issued by `faucet_id`. If the faucet's decimals are known the amount
+/// is read in whole tokens (e.g. `1.5`), otherwise it is read in base units.
+pub fn parse_amount(client: &Client, faucet_id: AccountId, amount: &str) -> Result<u64, CliError> {
+    match client.get_faucet_details(faucet_id)? {
+        Some(details) => Ok(AssetAmount::parse(amount, details.decimals)?.base_units()),
+        None => amount.parse().map_err(|_| {
+            CliError::Input(format!(
+                "amount `{amount}` is not a valid number of base units"
+            ))
+        }),
+    }
+}
+
+/// Parses the ID of one of the client's input notes, which can be shortened to any prefix that
+/// identifies a single note
+pub fn parse_note_id(client: &Client, note_id: &str) -> Result<NoteId, CliError> {
+    Ok(client.get_input_note_id_by_prefix(note_id)?)
+}
+
+/// Parses a reference to an account, which is either a full account ID, a prefix of the ID of an
+/// account known to the client or the name of an address book entry
+pub fn parse_account_id(client: &Client, account_id: &str) -> Result<AccountId, CliError> {
+    Ok(client.resolve_account_id(account_id)?)
+}
+
+/// Formats an amount of base units of tokens issued by `faucet_id` in whole tokens along with
+/// their symbol, falling back to base units if the faucet's details are not known.
+pub fn format_amount(
+    client: &Client,
+    faucet_id: AccountId,
+    amount: u64,
+) -> Result<String, CliError> {
+    Ok(match client.get_faucet_details(faucet_id)? {
+        Some(details) => format!(
+            "{} {}",
+            AssetAmount::new(amount, details.decimals),
+            details.token_symbol.to_str()
+        ),
+        None => amount.to_string(),
+    })
+}
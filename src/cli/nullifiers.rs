@@ -0,0 +1,99 @@
+use objects::Digest;
+
+use super::{CliError, Client, Parser};
+use crate::cli::create_dynamic_table;
+
+#[derive(Debug, Parser, Clone)]
+#[clap(
+    about = "Watch the nullifiers of notes the client does not hold to learn when they are consumed"
+)]
+pub enum NullifiersCmd {
+    /// List the watched nullifiers, along with the block their note was consumed in
+    #[clap(short_flag = 'l')]
+    List,
+
+    /// Watch a nullifier, such as the nullifier of a note created for another account. Syncing
+    /// reports when the note is consumed
+    #[clap(short_flag = 'w')]
+    Watch {
+        #[clap()]
+        nullifier: String,
+
+        /// Description of the note, shown when listing watched nullifiers
+        #[clap(long)]
+        label: Option<String>,
+
+        /// Block to look for the nullifier from, the genesis block by default
+        #[clap(long, default_value_t = 0)]
+        from_block: u32,
+    },
+
+    /// Stop watching a nullifier
+    #[clap(short_flag = 'u')]
+    Unwatch {
+        #[clap()]
+        nullifier: String,
+    },
+}
+
+impl NullifiersCmd {
+    pub fn execute(&self, client: Client) -> Result<(), CliError> {
+        match self {
+            NullifiersCmd::List => list_watched_nullifiers(client),
+            NullifiersCmd::Watch {
+                nullifier,
+                label,
+                from_block,
+            } => watch_nullifier(client, nullifier, label.as_deref(), *from_block),
+            NullifiersCmd::Unwatch { nullifier } => unwatch_nullifier(client, nullifier),
+        }
+    }
+}
+
+// HELPERS
+// ================================================================================================
+fn list_watched_nullifiers(client: Client) -> Result<(), CliError> {
+    let mut table = create_dynamic_table(&["Nullifier", "Label", "Checked Up To", "Consumed In"]);
+
+    for watched in client.get_watched_nullifiers()? {
+        table.add_row(vec![
+            watched.nullifier.to_string(),
+            watched.label.unwrap_or_default(),
+            watched.checked_height.to_string(),
+            watched
+                .consumed_height
+                .map(|block_num| block_num.to_string())
+                .unwrap_or("-".to_string()),
+        ]);
+    }
+
+    println!("{table}");
+    Ok(())
+}
+
+fn watch_nullifier(
+    mut client: Client,
+    nullifier: &str,
+    label: Option<&str>,
+    from_block: u32,
+) -> Result<(), CliError> {
+    let nullifier = parse_nullifier(nullifier)?;
+    if client.watch_nullifier(nullifier, label, from_block)? {
+        println!("nullifier {} watched", nullifier);
+    } else {
+        println!("nullifier {} is already watched", nullifier);
+    }
+    Ok(())
+}
+
+fn unwatch_nullifier(mut client: Client, nullifier: &str) -> Result<(), CliError> {
+    let nullifier = parse_nullifier(nullifier)?;
+    client.unwatch_nullifier(nullifier)?;
+    println!("nullifier {} unwatched", nullifier);
+    Ok(())
+}
+
+fn parse_nullifier(nullifier: &str) -> Result<Digest, CliError> {
+    Digest::try_from(nullifier)
+        .map_err(|err| CliError::Input(format!("invalid nullifier `{nullifier}`: {err}")))
+}
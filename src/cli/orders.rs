@@ -0,0 +1,71 @@
+use super::{CliError, Client, Parser};
+use crate::cli::{create_dynamic_table, parse_account_id};
+
+#[derive(Debug, Parser, Clone)]
+#[clap(about = "View the SWAP and P2IDR notes created by the client's accounts")]
+pub enum OrdersCmd {
+    /// List the orders, along with their status: open, expired once the recall height of the
+    /// note is reached, filled by a counterparty or recalled by the sender. Statuses are updated
+    /// by syncing
+    #[clap(short_flag = 'l')]
+    List {
+        /// Only list the orders created by this account
+        #[clap(long, value_name = "ACCOUNT")]
+        account: Option<String>,
+
+        /// Only list the orders whose note was not consumed yet
+        #[clap(long, default_value_t = false)]
+        open: bool,
+    },
+}
+
+impl OrdersCmd {
+    pub fn execute(&self, client: Client) -> Result<(), CliError> {
+        match self {
+            OrdersCmd::List { account, open } => list_orders(client, account.as_deref(), *open),
+        }
+    }
+}
+
+// HELPERS
+// ================================================================================================
+fn list_orders(client: Client, account: Option<&str>, open: bool) -> Result<(), CliError> {
+    let account_id = account
+        .map(|account| parse_account_id(&client, account))
+        .transpose()?;
+
+    let mut table = create_dynamic_table(&[
+        "Note ID",
+        "Kind",
+        "Account ID",
+        "Status",
+        "Recall Height",
+        "Closed In",
+    ]);
+
+    for order in client.get_orders()? {
+        if account_id.map_or(false, |account_id| account_id != order.account_id)
+            || (open && order.status.is_closed())
+        {
+            continue;
+        }
+
+        table.add_row(vec![
+            order.note_id.inner().to_string(),
+            order.kind.to_string(),
+            order.account_id.to_string(),
+            order.status.to_string(),
+            order
+                .recall_height
+                .map(|block_num| block_num.to_string())
+                .unwrap_or("-".to_string()),
+            order
+                .closed_height
+                .map(|block_num| block_num.to_string())
+                .unwrap_or("-".to_string()),
+        ]);
+    }
+
+    println!("{table}");
+    Ok(())
+}
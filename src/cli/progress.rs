@@ -0,0 +1,198 @@
+use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use miden_client::client::events::ClientEvent;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+const TICK: Duration = Duration::from_millis(100);
+
+// PROGRESS INDICATOR
+// ================================================================================================
+
+/// Spinner written to stderr while a long-running command is executing, proving or submitting a
+/// transaction, or syncing, along with the time spent in the current phase. The phase moves to
+/// submitting on its own once the client reports the transaction as proven, and syncs show the
+/// block reached out of the chain tip they are heading to.
+///
+/// Nothing is written when stderr is not a terminal, so that the output of commands that are
+/// piped or redirected is not cluttered with the spinner's frames.
+pub struct ProgressIndicator {
+    state: Arc<Mutex<ProgressState>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+struct ProgressState {
+    phase: &'static str,
+    phase_started_at: Instant,
+    /// Progress within the phase, such as the block reached by a sync
+    detail: Option<String>,
+    done: bool,
+}
+
+impl ProgressIndicator {
+    /// Starts displaying the spinner for `phase`, following the client events received through
+    /// `events`
+    pub fn start(phase: &'static str, events: UnboundedReceiver<ClientEvent>) -> Self {
+        Self::start_with(phase, events, atty::is(atty::Stream::Stderr))
+    }
+
+    /// Starts the indicator for `phase`, only displaying the spinner if `display` is set
+    fn start_with(
+        phase: &'static str,
+        mut events: UnboundedReceiver<ClientEvent>,
+        display: bool,
+    ) -> Self {
+        let state = Arc::new(Mutex::new(ProgressState {
+            phase,
+            phase_started_at: Instant::now(),
+            detail: None,
+            done: false,
+        }));
+        if !display {
+            return Self {
+                state,
+                handle: None,
+            };
+        }
+
+        let thread_state = state.clone();
+        let handle = thread::spawn(move || {
+            let mut line_len = 0;
+            for frame in SPINNER.iter().cycle() {
+                {
+                    let mut state = thread_state.lock().expect("progress state lock poisoned");
+                    while let Ok(event) = events.try_recv() {
+                        state.apply(event);
+                    }
+                    if state.done {
+                        break;
+                    }
+
+                    let line = state.line(*frame);
+                    eprint!("\r{line:line_len$}");
+                    line_len = line.len();
+                    let _ = io::stderr().flush();
+                }
+                thread::sleep(TICK);
+            }
+            eprint!("\r{:line_len$}\r", "");
+            let _ = io::stderr().flush();
+        });
+
+        Self {
+            state,
+            handle: Some(handle),
+        }
+    }
+
+    /// Moves the indicator to a new phase, restarting its elapsed time
+    pub fn set_phase(&self, phase: &'static str) {
+        self.state
+            .lock()
+            .expect("progress state lock poisoned")
+            .set_phase(phase);
+    }
+}
+
+impl ProgressState {
+    fn set_phase(&mut self, phase: &'static str) {
+        self.phase = phase;
+        self.phase_started_at = Instant::now();
+        self.detail = None;
+    }
+
+    /// Updates the phase or its progress from an event of the client
+    fn apply(&mut self, event: ClientEvent) {
+        match event {
+            ClientEvent::TransactionProven { .. } => self.set_phase("Submitting transaction"),
+            ClientEvent::SyncProgressed {
+                block_num,
+                target_block_num,
+            } => self.detail = Some(format!("{block_num}/{target_block_num}")),
+            _ => {}
+        }
+    }
+
+    /// Returns the line displayed for the current phase with the specified spinner frame
+    fn line(&self, frame: char) -> String {
+        let detail = self
+            .detail
+            .as_ref()
+            .map(|detail| format!(" {detail}"))
+            .unwrap_or_default();
+        format!(
+            "{frame} {}...{detail} {}s",
+            self.phase,
+            self.phase_started_at.elapsed().as_secs()
+        )
+    }
+}
+
+impl Drop for ProgressIndicator {
+    /// Stops the spinner and clears its line
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.done = true;
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_client::client::events::ClientEvent;
+    use objects::Digest;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    use super::ProgressIndicator;
+
+    #[test]
+    fn phases_follow_the_client_events() {
+        let (_sender, receiver) = unbounded_channel();
+        let progress = ProgressIndicator::start_with("Syncing state", receiver, false);
+
+        let mut state = progress.state.lock().unwrap();
+        assert_eq!(state.line('|'), "| Syncing state... 0s");
+
+        state.apply(ClientEvent::SyncProgressed {
+            block_num: 8,
+            target_block_num: 10,
+        });
+        assert_eq!(state.line('/'), "/ Syncing state... 8/10 0s");
+
+        // moving to another phase drops the progress within the previous one
+        state.apply(ClientEvent::TransactionProven {
+            transaction_id: Digest::default(),
+        });
+        assert_eq!(state.line('-'), "- Submitting transaction... 0s");
+    }
+
+    #[test]
+    fn nothing_is_displayed_when_disabled() {
+        let (_sender, receiver) = unbounded_channel();
+        let progress = ProgressIndicator::start_with("Executing transaction", receiver, false);
+        assert!(progress.handle.is_none());
+
+        // the phase is still tracked, and dropping the indicator does not wait for a thread
+        progress.set_phase("Proving transaction");
+        assert_eq!(progress.state.lock().unwrap().phase, "Proving transaction");
+    }
+
+    #[test]
+    fn displayed_indicators_stop_when_dropped() {
+        let (_sender, receiver) = unbounded_channel();
+        let progress = ProgressIndicator::start_with("Executing transaction", receiver, true);
+        assert!(progress.handle.is_some());
+
+        let state = progress.state.clone();
+        drop(progress);
+        assert!(state.lock().unwrap().done);
+    }
+}
@@ -0,0 +1,62 @@
+use std::{fs, path::PathBuf};
+
+use super::{CliError, Client, Parser};
+use crate::cli::create_dynamic_table;
+
+#[derive(Debug, Parser, Clone)]
+#[clap(about = "View and register note scripts")]
+pub enum ScriptsCmd {
+    /// List the note scripts stored by this client
+    #[clap(short_flag = 'l')]
+    List,
+
+    /// Compile a MASM file and register it as a note script under the provided name
+    #[clap(short_flag = 'r')]
+    Register {
+        /// Name the script can later be referenced by
+        #[clap()]
+        name: String,
+
+        /// Path to the MASM source of the script
+        #[clap()]
+        filename: PathBuf,
+    },
+}
+
+impl ScriptsCmd {
+    pub fn execute(&self, client: Client) -> Result<(), CliError> {
+        match self {
+            ScriptsCmd::List => {
+                list_scripts(client)?;
+            }
+            ScriptsCmd::Register { name, filename } => {
+                register_script(client, name, filename)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// HELPERS
+// ================================================================================================
+fn list_scripts(client: Client) -> Result<(), CliError> {
+    let mut table = create_dynamic_table(&["Script Hash", "Name"]);
+
+    for record in client.get_note_scripts()? {
+        table.add_row(vec![
+            record.script_hash.to_string(),
+            record.name.unwrap_or_else(|| "-".to_string()),
+        ]);
+    }
+
+    println!("{table}");
+    Ok(())
+}
+
+fn register_script(mut client: Client, name: &str, filename: &PathBuf) -> Result<(), CliError> {
+    let masm = fs::read_to_string(filename).map_err(|err| CliError::Fatal(err.to_string()))?;
+
+    let script_hash = client.register_note_script(name, &masm)?;
+    println!("Registered note script `{name}` with hash {script_hash}");
+    Ok(())
+}
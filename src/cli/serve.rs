@@ -0,0 +1,653 @@
+use std::{net::SocketAddr, time::Duration};
+
+use miden_client::{
+    client::{
+        accounts::{AccountStorageMode, AccountTemplate},
+        notes::NoteSummary,
+        transactions::{PaymentTransactionData, TransactionRecord, TransactionTemplate},
+        Client,
+    },
+    errors::{ClientError, ErrorCategory},
+    store::{notes::InputNoteFilter, transactions::TransactionFilter},
+};
+use objects::assets::{Asset, FungibleAsset};
+use rand::Rng;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::mpsc::unbounded_channel,
+};
+
+use crate::cli::{parse_account_id, parse_note_id, CliError};
+
+/// Environment variable the token clients authenticate with can be provided through. A random
+/// token is generated and printed at startup if it is not set
+const SERVE_TOKEN_ENV: &str = "MIDEN_SERVE_TOKEN";
+
+/// Maximum size of the body of a request
+const MAX_BODY_SIZE: usize = 1 << 20;
+
+/// Maximum number of headers of a request
+const MAX_HEADERS: usize = 64;
+
+/// Time a connection has to send its request before it is dropped
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// WALLET DAEMON
+// ================================================================================================
+
+/// Serves the client's API over JSON/HTTP on `address` until the process is stopped, syncing
+/// the client every `sync_interval` seconds in between requests.
+///
+/// Requests must carry an `Authorization: Bearer <token>` header. Connections are read and
+/// answered concurrently, so a slow client does not hold up the others, but the requests are
+/// run one at a time, as they share the client and its store.
+pub async fn serve(
+    mut client: Client,
+    address: SocketAddr,
+    sync_interval: u64,
+) -> Result<(), CliError> {
+    let token = match std::env::var(SERVE_TOKEN_ENV) {
+        Ok(token) if !token.is_empty() => token,
+        _ => {
+            let token = generate_token();
+            println!("No {SERVE_TOKEN_ENV} set, authenticate with token {token}");
+            token
+        }
+    };
+
+    let listener = TcpListener::bind(address)
+        .await
+        .map_err(|err| CliError::Fatal(format!("Failed to listen on {address}: {err}")))?;
+    println!("Serving the client API on http://{address}");
+
+    let (request_sender, mut requests) = unbounded_channel();
+    let mut sync_timer = tokio::time::interval(Duration::from_secs(sync_interval));
+    loop {
+        tokio::select! {
+            _ = sync_timer.tick() => match client.sync_state().await {
+                Ok(block_num) => println!("State synced to block {block_num}"),
+                Err(err) => eprintln!("Sync failed, retrying in {sync_interval}s: {err}"),
+            },
+            connection = listener.accept() => match connection {
+                Ok((stream, peer)) => {
+                    let request_sender = request_sender.clone();
+                    tokio::spawn(async move {
+                        let mut stream = stream;
+                        let request = tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream))
+                            .await
+                            .unwrap_or_else(|_| Err("timed out reading the request".to_string()));
+                        // the receiver lives as long as the daemon
+                        let _ = request_sender.send((stream, peer, request));
+                    });
+                },
+                Err(err) => eprintln!("Failed to accept connection: {err}"),
+            },
+            Some((stream, peer, request)) = requests.recv() => {
+                let response = match request {
+                    Ok(request) if !request.is_authorized(&token) => Err(ApiError::unauthorized()),
+                    Ok(request) => route(&mut client, &request).await,
+                    Err(err) => Err(ApiError::bad_request(err)),
+                };
+                tokio::spawn(async move {
+                    if let Err(err) = write_response(stream, response).await {
+                        eprintln!("Failed to serve request from {peer}: {err}");
+                    }
+                });
+            },
+        }
+    }
+}
+
+/// Writes `response` to `stream` and closes the connection
+async fn write_response(
+    mut stream: TcpStream,
+    response: Result<Value, ApiError>,
+) -> Result<(), std::io::Error> {
+    let (status, body) = match response {
+        Ok(body) => (200, body),
+        Err(err) => (
+            err.status,
+            json!({ "code": err.code, "message": err.message }),
+        ),
+    };
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        reason_phrase(status),
+        body.len()
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+// ROUTES
+// ================================================================================================
+
+/// Runs the operation requested and returns its result
+async fn route(client: &mut Client, request: &Request) -> Result<Value, ApiError> {
+    let segments: Vec<&str> = request
+        .path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["accounts"]) => list_accounts(client),
+        ("POST", ["accounts"]) => new_account(client, request.json()?),
+        ("GET", ["accounts", account_id]) => show_account(client, account_id),
+        ("GET", ["notes"]) => list_notes(client, request.query_param("status")),
+        ("POST", ["sync"]) => Ok(json!({ "block_num": client.sync_state().await? })),
+        ("GET", ["transactions"]) => list_transactions(client),
+        ("POST", ["transactions", "pay"]) => send_payment(client, request.json()?).await,
+        ("POST", ["transactions", "consume"]) => consume_notes(client, request.json()?).await,
+        _ => Err(ApiError {
+            status: 404,
+            code: "api.not_found",
+            message: format!("no route for {} {}", request.method, request.path),
+        }),
+    }
+}
+
+fn list_accounts(client: &Client) -> Result<Value, ApiError> {
+    let accounts: Vec<Value> = client
+        .get_accounts()?
+        .iter()
+        .map(|(account, _seed)| {
+            json!({ "id": account.id().to_hex(), "nonce": account.nonce().as_int() })
+        })
+        .collect();
+
+    Ok(Value::Array(accounts))
+}
+
+/// Body of a request creating an account
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewAccountRequest {
+    #[serde(default)]
+    on_chain: bool,
+    #[serde(default)]
+    mutable_code: bool,
+}
+
+fn new_account(client: &mut Client, request: NewAccountRequest) -> Result<Value, ApiError> {
+    let storage_mode = if request.on_chain {
+        AccountStorageMode::OnChain
+    } else {
+        AccountStorageMode::Local
+    };
+    let (account, _seed) = client.new_account(AccountTemplate::BasicWallet {
+        mutable_code: request.mutable_code,
+        storage_mode,
+    })?;
+
+    Ok(json!({ "id": account.id().to_hex() }))
+}
+
+fn show_account(client: &Client, account_id: &str) -> Result<Value, ApiError> {
+    let account_id = parse_account_id(client, account_id)?;
+    let (account, _seed) = client.get_account_stub_by_id(account_id)?;
+    let balances: Vec<Value> = client
+        .get_account_balances(account_id)?
+        .into_iter()
+        .map(|(faucet_id, amount)| json!({ "faucet_id": faucet_id.to_hex(), "amount": amount }))
+        .collect();
+
+    Ok(json!({
+        "id": account.id().to_hex(),
+        "nonce": account.nonce().as_int(),
+        "balances": balances,
+    }))
+}
+
+fn list_notes(client: &Client, status: Option<&str>) -> Result<Value, ApiError> {
+    let filter = match status {
+        None | Some("all") => InputNoteFilter::All,
+        Some("committed") => InputNoteFilter::Committed,
+        Some("consumed") => InputNoteFilter::Consumed,
+        Some("pending") => InputNoteFilter::Pending,
+        Some("expected") => InputNoteFilter::Expected,
+        Some(status) => {
+            return Err(ApiError::bad_request(format!(
+                "invalid note status `{status}`"
+            )))
+        }
+    };
+
+    let notes: Vec<NoteSummary> = client
+        .get_input_notes(filter)?
+        .iter()
+        .map(NoteSummary::from)
+        .collect();
+    Ok(json!(notes))
+}
+
+fn list_transactions(client: &Client) -> Result<Value, ApiError> {
+    let transactions: Vec<Value> = client
+        .get_transactions(TransactionFilter::All)?
+        .iter()
+        .map(transaction_summary)
+        .collect();
+
+    Ok(Value::Array(transactions))
+}
+
+/// Body of a request sending a payment. Accounts can be referred to as on the command line, and
+/// the amount is in base units of the faucet's asset
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PaymentRequest {
+    sender: String,
+    target: String,
+    faucet: String,
+    amount: u64,
+    #[serde(default)]
+    memo: Option<String>,
+    #[serde(default)]
+    change_note: bool,
+}
+
+async fn send_payment(client: &mut Client, request: PaymentRequest) -> Result<Value, ApiError> {
+    let faucet_id = parse_account_id(client, &request.faucet)?;
+    let asset = FungibleAsset::new(faucet_id, request.amount)
+        .map_err(|err| ApiError::bad_request(err.to_string()))?;
+    let mut payment = PaymentTransactionData::new(
+        Asset::Fungible(asset),
+        parse_account_id(client, &request.sender)?,
+        parse_account_id(client, &request.target)?,
+    );
+    if let Some(memo) = request.memo {
+        payment = payment.with_memo(memo);
+    }
+    if request.change_note {
+        payment = payment.with_change_note();
+    }
+
+    submit_transaction(client, TransactionTemplate::PayToId(payment)).await
+}
+
+/// Body of a request consuming notes. Note IDs can be shortened to any unambiguous prefix
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConsumeNotesRequest {
+    account: String,
+    notes: Vec<String>,
+}
+
+async fn consume_notes(
+    client: &mut Client,
+    request: ConsumeNotesRequest,
+) -> Result<Value, ApiError> {
+    let account_id = parse_account_id(client, &request.account)?;
+    let note_ids = request
+        .notes
+        .iter()
+        .map(|note_id| parse_note_id(client, note_id))
+        .collect::<Result<Vec<_>, _>>()?;
+    client.authenticate_input_notes(&note_ids).await?;
+
+    submit_transaction(
+        client,
+        TransactionTemplate::ConsumeNotes(account_id, note_ids),
+    )
+    .await
+}
+
+/// Executes, proves and submits the transaction described by `template` and returns its receipt
+async fn submit_transaction(
+    client: &mut Client,
+    template: TransactionTemplate,
+) -> Result<Value, ApiError> {
+    let transaction_result = client.new_transaction(template)?;
+    let receipt = transaction_result.receipt();
+    client.send_transaction(transaction_result).await?;
+
+    Ok(json!(receipt))
+}
+
+fn transaction_summary(transaction: &TransactionRecord) -> Value {
+    json!({
+        "id": transaction.id.to_hex(),
+        "account_id": transaction.account_id.to_hex(),
+        "status": transaction.transaction_status.to_string(),
+        "block_num": transaction.block_num,
+    })
+}
+
+// REQUESTS AND ERRORS
+// ================================================================================================
+
+/// HTTP request received by the daemon
+struct Request {
+    method: String,
+    path: String,
+    query: Option<String>,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    /// Returns whether the request carries `token` as its bearer token
+    fn is_authorized(&self, token: &str) -> bool {
+        let provided = match self
+            .authorization
+            .as_deref()
+            .and_then(|auth| auth.strip_prefix("Bearer "))
+        {
+            Some(provided) => provided.as_bytes(),
+            None => return false,
+        };
+
+        // compare every byte so that the time taken does not reveal the matching prefix
+        provided.len() == token.len()
+            && provided
+                .iter()
+                .zip(token.as_bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+
+    /// Returns the value of the query parameter `name`, if present
+    fn query_param(&self, name: &str) -> Option<&str> {
+        self.query.as_deref()?.split('&').find_map(|param| {
+            let (key, value) = param.split_once('=')?;
+            (key == name).then_some(value)
+        })
+    }
+
+    /// Parses the body of the request as JSON. An empty body is read as an empty object
+    fn json<T: DeserializeOwned>(&self) -> Result<T, ApiError> {
+        let body = if self.body.is_empty() {
+            b"{}".as_slice()
+        } else {
+            &self.body
+        };
+        serde_json::from_slice(body)
+            .map_err(|err| ApiError::bad_request(format!("invalid request body: {err}")))
+    }
+}
+
+/// Reads an HTTP/1.1 request from `stream`
+async fn read_request<R: AsyncRead + Unpin>(stream: R) -> Result<Request, String> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader
+        .read_line(&mut request_line)
+        .await
+        .map_err(|err| err.to_string())?;
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = match (parts.next(), parts.next()) {
+        (Some(method), Some(target)) => (method.to_string(), target),
+        _ => return Err("malformed request line".to_string()),
+    };
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (target.to_string(), None),
+    };
+
+    let mut authorization = None;
+    let mut content_length = 0;
+    for _ in 0..=MAX_HEADERS {
+        let mut header = String::new();
+        reader
+            .read_line(&mut header)
+            .await
+            .map_err(|err| err.to_string())?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            let mut body = vec![0; content_length];
+            reader
+                .read_exact(&mut body)
+                .await
+                .map_err(|err| err.to_string())?;
+            return Ok(Request {
+                method,
+                path,
+                query,
+                authorization,
+                body,
+            });
+        }
+
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| format!("malformed header `{header}`"))?;
+        if name.eq_ignore_ascii_case("authorization") {
+            authorization = Some(value.trim().to_string());
+        } else if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.trim().parse().map_err(|_| "invalid content length")?;
+            if content_length > MAX_BODY_SIZE {
+                return Err(format!("request body exceeds {MAX_BODY_SIZE} bytes"));
+            }
+        }
+    }
+
+    Err(format!("request has more than {MAX_HEADERS} headers"))
+}
+
+/// Error answered to a request, with a stable `code` clients can match on
+#[derive(Debug)]
+struct ApiError {
+    status: u16,
+    code: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: 400,
+            code: "api.bad_request",
+            message: message.into(),
+        }
+    }
+
+    fn unauthorized() -> Self {
+        Self {
+            status: 401,
+            code: "api.unauthorized",
+            message: "missing or invalid bearer token".to_string(),
+        }
+    }
+}
+
+impl From<ClientError> for ApiError {
+    fn from(err: ClientError) -> Self {
+        let status = match err.category() {
+            ErrorCategory::InvalidInput => 400,
+            ErrorCategory::Retryable => 503,
+            ErrorCategory::Fatal => 500,
+        };
+        Self {
+            status,
+            code: err.code(),
+            message: err.to_string(),
+        }
+    }
+}
+
+impl From<CliError> for ApiError {
+    fn from(err: CliError) -> Self {
+        match err {
+            CliError::Client(err) => err.into(),
+            CliError::Input(message) => ApiError::bad_request(message),
+            CliError::Fatal(message) => Self {
+                status: 500,
+                code: "api.internal",
+                message,
+            },
+        }
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Returns a random token of 32 bytes in hex format
+fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::thread_rng().gen();
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env::temp_dir;
+
+    use miden_client::{
+        client::Client,
+        config::{ClientConfig, Endpoint},
+    };
+    use uuid::Uuid;
+
+    use super::{read_request, route, Request};
+
+    fn create_test_client() -> Client {
+        let mut path = temp_dir();
+        path.push(Uuid::new_v4().to_string());
+        Client::new(ClientConfig::new(
+            path.into_os_string()
+                .into_string()
+                .unwrap()
+                .try_into()
+                .unwrap(),
+            Endpoint::default().into(),
+        ))
+        .unwrap()
+    }
+
+    fn test_request(method: &str, path: &str, query: Option<&str>, body: &str) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: query.map(str::to_string),
+            authorization: Some("Bearer secret".to_string()),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[tokio::test]
+    async fn requests_are_read_with_their_headers_and_body() {
+        let raw = "POST /accounts?status=all HTTP/1.1\r\nHost: localhost\r\n\
+            authorization: Bearer secret\r\nContent-Length: 17\r\n\r\n{\"on_chain\":true}";
+        let request = read_request(raw.as_bytes()).await.unwrap();
+
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/accounts");
+        assert_eq!(request.query.as_deref(), Some("status=all"));
+        assert_eq!(request.authorization.as_deref(), Some("Bearer secret"));
+        assert_eq!(request.body, b"{\"on_chain\":true}");
+    }
+
+    #[tokio::test]
+    async fn malformed_requests_are_rejected() {
+        assert!(read_request("GET\r\n\r\n".as_bytes()).await.is_err());
+        assert!(
+            read_request("GET / HTTP/1.1\r\nno-colon\r\n\r\n".as_bytes())
+                .await
+                .is_err()
+        );
+
+        let oversized = format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n", usize::MAX);
+        assert!(read_request(oversized.as_bytes()).await.is_err());
+
+        let too_many_headers = format!("GET / HTTP/1.1\r\n{}\r\n", "X-Header: 1\r\n".repeat(100));
+        assert!(read_request(too_many_headers.as_bytes()).await.is_err());
+    }
+
+    #[test]
+    fn requests_are_authorized_by_their_bearer_token() {
+        let mut request = test_request("GET", "/accounts", None, "");
+        assert!(request.is_authorized("secret"));
+        assert!(!request.is_authorized("secreT"));
+        assert!(!request.is_authorized("secret2"));
+        assert!(!request.is_authorized("secre"));
+
+        request.authorization = Some("Basic secret".to_string());
+        assert!(!request.is_authorized("secret"));
+        request.authorization = None;
+        assert!(!request.is_authorized("secret"));
+    }
+
+    #[test]
+    fn query_params_are_looked_up_by_name() {
+        let request = test_request("GET", "/notes", Some("limit=5&status=committed&flag"), "");
+        assert_eq!(request.query_param("status"), Some("committed"));
+        assert_eq!(request.query_param("limit"), Some("5"));
+        assert_eq!(request.query_param("flag"), None);
+        assert_eq!(request.query_param("stat"), None);
+        assert_eq!(
+            test_request("GET", "/notes", None, "").query_param("status"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn requests_are_routed_to_their_operation() {
+        let mut client = create_test_client();
+
+        let account = route(&mut client, &test_request("POST", "/accounts", None, ""))
+            .await
+            .unwrap();
+        let accounts = route(&mut client, &test_request("GET", "/accounts/", None, ""))
+            .await
+            .unwrap();
+        assert_eq!(accounts[0]["id"], account["id"]);
+
+        let notes = route(
+            &mut client,
+            &test_request("GET", "/notes", Some("status=committed"), ""),
+        )
+        .await
+        .unwrap();
+        assert_eq!(notes, serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn invalid_requests_are_answered_with_client_errors() {
+        let mut client = create_test_client();
+
+        let err = route(&mut client, &test_request("DELETE", "/accounts", None, ""))
+            .await
+            .unwrap_err();
+        assert_eq!((err.status, err.code), (404, "api.not_found"));
+
+        let err = route(
+            &mut client,
+            &test_request("GET", "/notes", Some("status=spent"), ""),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!((err.status, err.code), (400, "api.bad_request"));
+
+        let err = route(
+            &mut client,
+            &test_request("POST", "/accounts", None, "{\"unknown\":1}"),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!((err.status, err.code), (400, "api.bad_request"));
+
+        // on-chain accounts are not supported yet
+        let err = route(
+            &mut client,
+            &test_request("POST", "/accounts", None, "{\"on_chain\":true}"),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!((err.status, err.code), (400, "client.unsupported_feature"));
+        assert!(client.get_accounts().unwrap().is_empty());
+    }
+}
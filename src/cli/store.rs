@@ -0,0 +1,264 @@
+use std::io::{self, Write};
+
+use clap::Parser;
+use miden_client::{config::StoreConfig, store::Store};
+
+use super::{create_dynamic_table, CliError};
+
+// STORE COMMAND
+// ================================================================================================
+
+#[derive(Debug, Clone, Parser)]
+#[clap(about = "Manage the client's local store")]
+pub enum StoreCmd {
+    /// Apply pending migrations to the store. A copy of the store is saved next to it before
+    /// any migration is applied or reverted
+    #[clap(short_flag = 'm')]
+    Migrate {
+        /// Run the migrations against a temporary copy of the store and report the outcome,
+        /// leaving the original store untouched
+        #[clap(long, default_value_t = false, conflicts_with_all = ["status", "to"])]
+        check: bool,
+        /// Show the schema version of the store and the latest one, without migrating it
+        #[clap(long, default_value_t = false, conflicts_with = "to")]
+        status: bool,
+        /// Apply or revert migrations until the store is at this schema version. Reverting
+        /// migrations drops the data older versions of the client cannot represent
+        #[clap(long)]
+        to: Option<usize>,
+    },
+    /// Remove the chain MMR data that is not needed to authenticate the client's unconsumed notes.
+    /// This also happens after every sync
+    #[clap(short_flag = 'p')]
+    Prune,
+    /// Show the size of the store, its schema version, the number of rows of each table, the
+    /// range of stored block headers and the number of notes targeting each account
+    #[clap(short_flag = 's')]
+    Stats,
+    /// Clear the sync state, notes and transactions, keeping accounts and their keys, so that the
+    /// next sync starts over from the genesis block. Useful after a devnet restart
+    Reset {
+        /// Instead, archive the whole store and start over with an empty one, for when the client
+        /// is pointed to another network. The archive is kept next to the store
+        #[clap(long, default_value_t = false)]
+        network: bool,
+        /// Do not ask for confirmation
+        #[clap(short, long, default_value_t = false)]
+        yes: bool,
+    },
+    /// Encrypt a plaintext store with a passphrase. The passphrase is taken from the config file
+    /// or the environment if set there, and prompted for otherwise
+    #[cfg(feature = "sqlcipher")]
+    #[clap(short_flag = 'e')]
+    Encrypt,
+}
+
+impl StoreCmd {
+    pub fn execute(&self, store_config: StoreConfig) -> Result<(), CliError> {
+        match self {
+            StoreCmd::Migrate { check: true, .. } => check_migrations(store_config)?,
+            StoreCmd::Migrate { status: true, .. } => show_migration_status(store_config)?,
+            StoreCmd::Migrate {
+                to: Some(version), ..
+            } => migrate_to(store_config, *version)?,
+            StoreCmd::Migrate { .. } => {
+                Store::new(store_config)?;
+                println!("Store is up to date.");
+            }
+            StoreCmd::Prune => {
+                let removed_nodes = Store::new(store_config)?.prune_irrelevant_mmr_nodes()?;
+                println!("Removed {removed_nodes} chain MMR nodes.");
+            }
+            StoreCmd::Stats => show_store_stats(store_config)?,
+            StoreCmd::Reset { network, yes } => {
+                let question = if *network {
+                    "Archive the store and start over with an empty one?"
+                } else {
+                    "Discard the client's sync state, notes and transactions?"
+                };
+                if !yes && !confirm(question)? {
+                    println!("The store was not modified.");
+                    return Ok(());
+                }
+
+                if !network {
+                    Store::new(store_config)?.reset_sync_data()?;
+                    println!("Cleared the sync state, notes and transactions. Accounts were kept.");
+                } else if let Some(archive_path) = Store::reset(store_config)? {
+                    println!(
+                        "Archived the store to {} and created an empty one.",
+                        archive_path.display()
+                    );
+                } else {
+                    println!("There was no store to archive, created an empty one.");
+                }
+            }
+            #[cfg(feature = "sqlcipher")]
+            StoreCmd::Encrypt => encrypt_store(store_config)?,
+        }
+        Ok(())
+    }
+}
+
+// CHECK MIGRATIONS
+// ================================================================================================
+
+fn check_migrations(store_config: StoreConfig) -> Result<(), CliError> {
+    let check = Store::check_migrations(store_config).map_err(|err| {
+        CliError::Fatal(format!(
+            "Migration check failed, the store was not modified: {err}"
+        ))
+    })?;
+
+    if check.pending_migrations() == 0 {
+        println!(
+            "Store is up to date at schema version {}.",
+            check.current_version
+        );
+    } else {
+        println!(
+            "Applied {} pending migrations (schema version {} -> {}) to a copy of the store in {:.2?}. The store was not modified.",
+            check.pending_migrations(),
+            check.current_version,
+            check.latest_version,
+            check.duration
+        );
+    }
+    Ok(())
+}
+
+// MIGRATION STATUS
+// ================================================================================================
+
+fn show_migration_status(store_config: StoreConfig) -> Result<(), CliError> {
+    let status = Store::migration_status(store_config)?;
+
+    println!("Current schema version: {}", status.current_version);
+    println!("Target schema version: {}", status.latest_version);
+    if status.pending_migrations() > 0 {
+        println!(
+            "{} migrations are pending, they are applied the next time the store is opened.",
+            status.pending_migrations()
+        );
+    }
+    Ok(())
+}
+
+fn migrate_to(store_config: StoreConfig, version: usize) -> Result<(), CliError> {
+    let database_filepath = store_config.database_filepath.clone();
+    let previous_version = Store::migration_status(store_config.clone())?.current_version;
+    if previous_version == version {
+        println!("Store is already at schema version {version}.");
+        return Ok(());
+    }
+
+    Store::migrate_to(store_config, version)?;
+    println!("Migrated store from schema version {previous_version} to {version}.");
+    if previous_version > 0 {
+        println!(
+            "A copy of the store before migrating was saved to {}.",
+            Store::backup_path(&database_filepath, previous_version).display()
+        );
+    }
+    Ok(())
+}
+
+// STORE STATS
+// ================================================================================================
+
+fn show_store_stats(store_config: StoreConfig) -> Result<(), CliError> {
+    let stats = Store::new(store_config)?.get_stats()?;
+
+    println!("Schema version: {}", stats.schema_version);
+    println!(
+        "Database size: {} ({} free, reclaimed by `VACUUM`)",
+        format_size(stats.database_size),
+        format_size(stats.free_size)
+    );
+    match stats.block_header_range {
+        Some((oldest, newest)) => println!("Block headers: blocks {oldest} to {newest}"),
+        None => println!("Block headers: none"),
+    }
+
+    let mut table = create_dynamic_table(&["Table", "Rows"]);
+    for (name, rows) in stats.table_rows {
+        table.add_row(vec![name, rows.to_string()]);
+    }
+    println!("{table}");
+
+    if !stats.account_notes.is_empty() {
+        let mut table = create_dynamic_table(&["Account ID", "Notes", "Unconsumed Notes"]);
+        for counts in stats.account_notes {
+            table.add_row(vec![
+                counts.account_id.to_string(),
+                counts.notes.to_string(),
+                counts.unconsumed_notes.to_string(),
+            ]);
+        }
+        println!("{table}");
+    }
+    Ok(())
+}
+
+/// Formats a number of bytes with the largest binary unit that keeps it above 1
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+// ENCRYPT STORE
+// ================================================================================================
+
+#[cfg(feature = "sqlcipher")]
+fn encrypt_store(store_config: StoreConfig) -> Result<(), CliError> {
+    let passphrase = match &store_config.passphrase {
+        Some(passphrase) => passphrase.clone(),
+        None => {
+            let passphrase = rpassword::prompt_password("New store passphrase: ")
+                .map_err(|err| CliError::Fatal(err.to_string()))?;
+            let confirmation = rpassword::prompt_password("Confirm store passphrase: ")
+                .map_err(|err| CliError::Fatal(err.to_string()))?;
+            if passphrase != confirmation {
+                return Err(CliError::Input(
+                    "Passphrases do not match, the store was not modified".to_string(),
+                ));
+            }
+            passphrase
+        }
+    };
+
+    Store::encrypt(&store_config, &passphrase)?;
+    println!(
+        "Encrypted store at {}. Keep the passphrase safe, the store cannot be opened without it.",
+        store_config.database_filepath
+    );
+    Ok(())
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Asks the user to confirm an action on stdin, which is only confirmed by answering yes
+pub(super) fn confirm(message: &str) -> Result<bool, CliError> {
+    print!("{message} [y/N]: ");
+    io::stdout()
+        .flush()
+        .map_err(|err| CliError::Fatal(err.to_string()))?;
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .map_err(|err| CliError::Fatal(err.to_string()))?;
+
+    Ok(matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+}
@@ -1,7 +1,231 @@
-use miden_client::client::Client;
+use std::time::Duration;
 
-pub async fn sync_state(mut client: Client) -> Result<(), String> {
-    let block_num = client.sync_state().await?;
+use clap::Parser;
+use miden_client::client::{
+    events::ClientEvent,
+    notifications::{Notification, WebhookNotifier},
+    Client,
+};
+use objects::{accounts::AccountId, notes::NoteId, Digest};
+
+use crate::cli::{
+    create_dynamic_table, parse_account_id, progress::ProgressIndicator,
+    transactions::print_outbox_flush, CliError,
+};
+
+#[derive(Debug, Parser, Clone)]
+pub enum SyncCmd {
+    /// Inspect and retry notes that were set aside because their inclusion proof could not be
+    /// built while syncing
+    #[clap(subcommand)]
+    Quarantine(QuarantineCmd),
+}
+
+#[derive(Debug, Parser, Clone)]
+pub enum QuarantineCmd {
+    /// List quarantined notes
+    #[clap(short_flag = 'l')]
+    List,
+    /// Request the quarantined notes from the node again and retry building their inclusion proofs
+    #[clap(short_flag = 'r')]
+    Retry,
+}
+
+impl SyncCmd {
+    pub async fn execute(&self, client: Client) -> Result<(), CliError> {
+        match self {
+            SyncCmd::Quarantine(QuarantineCmd::List) => list_quarantined_notes(client),
+            SyncCmd::Quarantine(QuarantineCmd::Retry) => retry_quarantined_notes(client).await,
+        }
+    }
+}
+
+pub async fn sync_state(
+    mut client: Client,
+    account_ids: &[String],
+    no_progress: bool,
+    verbose: bool,
+) -> Result<(), CliError> {
+    let account_ids = parse_account_ids(&client, account_ids)?;
+    if let Some(progress) = client.get_sync_progress()? {
+        println!("Resuming interrupted sync at block {progress}");
+    }
+    let unconsumed_watched_nullifiers: Vec<Digest> = client
+        .get_watched_nullifiers()?
+        .into_iter()
+        .filter(|watched| watched.consumed_height.is_none())
+        .map(|watched| watched.nullifier)
+        .collect();
+
+    let mut events = client.subscribe();
+    let progress =
+        (!no_progress).then(|| ProgressIndicator::start("Syncing state", client.subscribe()));
+    let block_num = sync_once(&mut client, &account_ids).await?;
+    drop(progress);
     println!("State synced to block {}", block_num);
+
+    let mut externally_consumed_notes = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if let ClientEvent::NoteConsumedExternally { note_id, block_num } = event {
+            externally_consumed_notes.push((note_id, block_num));
+        }
+    }
+    print_externally_consumed_notes(&externally_consumed_notes, verbose);
+
+    for watched in client
+        .get_watched_nullifiers()?
+        .into_iter()
+        .filter(|watched| unconsumed_watched_nullifiers.contains(&watched.nullifier))
+    {
+        if let Some(block_num) = watched.consumed_height {
+            let label = watched
+                .label
+                .map(|label| format!(" ({label})"))
+                .unwrap_or_default();
+            println!(
+                "The note with watched nullifier {}{label} was consumed in block {block_num}",
+                watched.nullifier
+            );
+        }
+    }
+
+    // transactions queued while the node could not be reached are submitted now that it can
+    if !client.is_offline() && !client.get_queued_transactions()?.is_empty() {
+        print_outbox_flush(&client.flush_outbox().await?);
+    }
+
+    let quarantined_notes = client.get_quarantined_notes()?;
+    if !quarantined_notes.is_empty() {
+        println!(
+            "{} notes are quarantined, run `sync quarantine list` for details",
+            quarantined_notes.len()
+        );
+    }
     Ok(())
 }
+
+/// Reports the notes consumed by transactions the client did not execute, listing them if
+/// `verbose` is set
+fn print_externally_consumed_notes(notes: &[(NoteId, u32)], verbose: bool) {
+    if notes.is_empty() {
+        return;
+    }
+    if !verbose {
+        println!(
+            "{} notes were consumed by transactions this client did not execute, run `sync --verbose` for details",
+            notes.len()
+        );
+        return;
+    }
+
+    println!("Notes consumed by transactions this client did not execute:");
+    let mut table = create_dynamic_table(&["Note ID", "Consumed in Block"]);
+    for (note_id, block_num) in notes {
+        table.add_row(vec![note_id.inner().to_string(), block_num.to_string()]);
+    }
+    println!("{table}");
+}
+
+// WATCH MODE
+// ================================================================================================
+
+/// Syncs the client every `interval` seconds until the process is stopped. The events observed
+/// while syncing and the balance changes of the client's accounts are POSTed to the webhook, if
+/// one is configured. Failed syncs and undeliverable notifications are reported but do not stop
+/// the loop.
+pub async fn watch_state(
+    mut client: Client,
+    account_ids: &[String],
+    interval: u64,
+    webhook: Option<WebhookNotifier>,
+) -> Result<(), CliError> {
+    let account_ids = parse_account_ids(&client, account_ids)?;
+
+    let mut events = client.subscribe();
+    let mut account_deltas = Vec::new();
+    for (account, _seed) in client.get_accounts()? {
+        account_deltas.push(client.watch_account(account.id())?);
+    }
+
+    loop {
+        match sync_once(&mut client, &account_ids).await {
+            Ok(block_num) => println!("State synced to block {}", block_num),
+            Err(err) => eprintln!("Sync failed, retrying in {interval}s: {err}"),
+        }
+
+        let mut notifications = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            notifications.push(Notification::from(&event));
+        }
+        for receiver in account_deltas.iter_mut() {
+            while let Ok(delta) = receiver.try_recv() {
+                notifications.extend(Notification::balance_changes(&delta));
+            }
+        }
+
+        if let Some(webhook) = &webhook {
+            for notification in notifications.iter() {
+                if let Err(err) = webhook.notify(notification).await {
+                    eprintln!("Dropped notification {notification:?}: {err}");
+                }
+            }
+        }
+
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+    }
+}
+
+// SYNC QUARANTINE
+// ================================================================================================
+
+fn list_quarantined_notes(client: Client) -> Result<(), CliError> {
+    let quarantined_notes = client.get_quarantined_notes()?;
+
+    let mut table = create_dynamic_table(&["Note ID", "Block Number", "Note Index", "Reason"]);
+    for note in quarantined_notes {
+        table.add_row(vec![
+            note.note_id.inner().to_string(),
+            note.block_num.to_string(),
+            note.note_index.to_string(),
+            note.reason,
+        ]);
+    }
+
+    println!("{table}");
+    Ok(())
+}
+
+async fn retry_quarantined_notes(mut client: Client) -> Result<(), CliError> {
+    let released_notes = client.retry_quarantined_notes().await?;
+    for note_id in released_notes.iter() {
+        println!("Released note {}", note_id.inner());
+    }
+
+    let remaining_notes = client.get_quarantined_notes()?.len();
+    println!(
+        "Released {} notes, {} remain quarantined",
+        released_notes.len(),
+        remaining_notes
+    );
+    Ok(())
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Syncs the specified accounts, or the whole client state if none is specified
+async fn sync_once(client: &mut Client, account_ids: &[AccountId]) -> Result<u32, CliError> {
+    let block_num = if account_ids.is_empty() {
+        client.sync_state().await?
+    } else {
+        client.sync_state_for_accounts(account_ids).await?
+    };
+    Ok(block_num)
+}
+
+fn parse_account_ids(client: &Client, account_ids: &[String]) -> Result<Vec<AccountId>, CliError> {
+    account_ids
+        .iter()
+        .map(|account_id| parse_account_id(client, account_id))
+        .collect()
+}
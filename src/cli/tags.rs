@@ -1,28 +1,77 @@
-use super::{Client, Parser};
+use miden_client::{
+    client::note_tags::{account_note_tag, swap_note_tag},
+    store::sync::NoteTagSource,
+};
+
+use super::{CliError, Client, Parser};
+use crate::cli::{create_dynamic_table, parse_account_id};
 
 #[derive(Debug, Parser, Clone)]
-#[clap(about = "View and add tags")]
+#[clap(about = "View, add and remove tags")]
 pub enum TagsCmd {
-    /// List all tags monitored by this client
+    /// List all tags monitored by this client, along with what added them
     #[clap(short_flag = 'l')]
     List,
 
-    /// Add a new tag to the list of tags monitored by this client
+    /// Add a new tag to the list of tags monitored by this client, given as is or computed for the
+    /// notes of an account or for SWAP notes
     #[clap(short_flag = 'a')]
     Add {
+        #[clap(
+            required_unless_present_any = ["account", "swap"],
+            conflicts_with_all = ["account", "swap"]
+        )]
+        tag: Option<u64>,
+
+        /// Track the tag of the notes meant for this account, such as P2ID notes
+        #[clap(long, value_name = "ACCOUNT_ID", conflicts_with = "swap")]
+        account: Option<String>,
+
+        /// Track the tag of the SWAP notes offering tokens of the first faucet in exchange for
+        /// tokens of the second one
+        #[clap(long, num_args = 2, value_names = ["OFFERED_FAUCET_ID", "REQUESTED_FAUCET_ID"])]
+        swap: Vec<String>,
+
+        /// Description of the tag, shown when listing tags
+        #[clap(long)]
+        label: Option<String>,
+    },
+
+    /// Stop monitoring a tag, including tags added automatically for imported notes
+    #[clap(short_flag = 'r')]
+    Remove {
         #[clap()]
         tag: u64,
     },
 }
 
 impl TagsCmd {
-    pub async fn execute(&self, client: Client) -> Result<(), String> {
+    pub async fn execute(&self, client: Client) -> Result<(), CliError> {
         match self {
             TagsCmd::List => {
                 list_tags(client)?;
             }
-            TagsCmd::Add { tag } => {
-                add_tag(client, *tag)?;
+            TagsCmd::Add {
+                tag,
+                account,
+                swap,
+                label,
+            } => {
+                let tag = match (tag, account, swap.as_slice()) {
+                    (Some(tag), ..) => *tag,
+                    (None, Some(account), _) => {
+                        account_note_tag(parse_account_id(&client, account)?)
+                    }
+                    (None, None, [offered, requested]) => swap_note_tag(
+                        parse_account_id(&client, offered)?,
+                        parse_account_id(&client, requested)?,
+                    ),
+                    _ => unreachable!("clap requires a tag, an account or a pair of faucets"),
+                };
+                add_tag(client, tag, label.as_deref())?;
+            }
+            TagsCmd::Remove { tag } => {
+                remove_tag(client, *tag)?;
             }
         }
         Ok(())
@@ -31,14 +80,37 @@ impl TagsCmd {
 
 // HELPERS
 // ================================================================================================
-fn list_tags(client: Client) -> Result<(), String> {
-    let tags = client.get_note_tags()?;
-    println!("tags: {:?}", tags);
+fn list_tags(client: Client) -> Result<(), CliError> {
+    let mut table = create_dynamic_table(&["Tag", "Source", "Label"]);
+
+    for record in client.get_note_tag_records()? {
+        let source = match record.source {
+            NoteTagSource::User => "user".to_string(),
+            NoteTagSource::Note(note_id) => format!("note {}", note_id.inner()),
+            NoteTagSource::Account(account_id) => format!("account {}", account_id),
+        };
+        table.add_row(vec![
+            record.tag.to_string(),
+            source,
+            record.label.unwrap_or_default(),
+        ]);
+    }
+
+    println!("{table}");
     Ok(())
 }
 
-fn add_tag(mut client: Client, tag: u64) -> Result<(), String> {
-    client.add_note_tag(tag)?;
+fn add_tag(mut client: Client, tag: u64, label: Option<&str>) -> Result<(), CliError> {
+    match label {
+        Some(label) => client.add_labeled_note_tag(tag, label)?,
+        None => client.add_note_tag(tag)?,
+    }
     println!("tag {} added", tag);
     Ok(())
 }
+
+fn remove_tag(mut client: Client, tag: u64) -> Result<(), CliError> {
+    client.remove_note_tag(tag)?;
+    println!("tag {} removed", tag);
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use clap::{ArgAction, ValueEnum};
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
+
+// TELEMETRY
+// ================================================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human readable lines
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+/// Logging flags shared by every command
+#[derive(Debug, Clone, clap::Args)]
+pub struct TelemetryArgs {
+    /// Log more details. Can be repeated: once for debug logs, twice for trace logs
+    #[clap(long, action = ArgAction::Count, global = true, conflicts_with = "quiet")]
+    verbose: u8,
+    /// Only log errors
+    #[clap(long, default_value_t = false, global = true)]
+    quiet: bool,
+    /// Format of the logs, which are written to stderr
+    #[clap(long, value_enum, default_value_t = LogFormat::Text, global = true)]
+    log_format: LogFormat,
+}
+
+impl TelemetryArgs {
+    /// Installs the global tracing subscriber.
+    ///
+    /// The level set through the flags can be refined per module with the `RUST_LOG` environment
+    /// variable (e.g. `RUST_LOG=miden_client::client=trace`). Spans for syncing, executing and
+    /// proving transactions and RPC calls are logged when they close, along with their duration.
+    pub fn init(&self) -> Result<(), String> {
+        let filter = EnvFilter::builder()
+            .with_default_directive(self.level().into())
+            .from_env_lossy();
+
+        let subscriber = tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_writer(std::io::stderr);
+
+        match self.log_format {
+            LogFormat::Text => subscriber.try_init(),
+            LogFormat::Json => subscriber.json().try_init(),
+        }
+        .map_err(|err| format!("Failed to initialize logging: {err}"))
+    }
+
+    /// Returns true if `--verbose` was passed, which also makes commands print more details
+    pub fn is_verbose(&self) -> bool {
+        self.verbose > 0
+    }
+
+    fn level(&self) -> LevelFilter {
+        match (self.quiet, self.verbose) {
+            (true, _) => LevelFilter::ERROR,
+            (false, 0) => LevelFilter::INFO,
+            (false, 1) => LevelFilter::DEBUG,
+            (false, _) => LevelFilter::TRACE,
+        }
+    }
+}
@@ -1,15 +1,47 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use figment::{
+    providers::{Format, Json, Toml},
+    Figment,
+};
 use miden_client::{
-    client::transactions::{PaymentTransactionData, TransactionRecord, TransactionTemplate},
+    client::{
+        script_inputs::TransactionScriptInputs,
+        transactions::{
+            OutboxFlush, PaymentTransactionData, TransactionReceipt, TransactionRecord,
+            TransactionStatus, TransactionTemplate,
+        },
+    },
+    errors::{ClientError, StoreError},
     store::transactions::TransactionFilter,
 };
 
-use objects::{accounts::AccountId, assets::FungibleAsset, notes::NoteId};
+use crypto::{utils::Serializable, Word};
+use objects::{
+    assembly::ProgramAst,
+    assets::{Asset, FungibleAsset},
+    notes::NoteId,
+    Digest,
+};
 use tracing::info;
 
-use crate::cli::create_dynamic_table;
+use crate::cli::{
+    create_dynamic_table,
+    csv::{asset_fields, block_time_fields, target_field, CsvWriter},
+    format_amount, parse_account_id, parse_amount, parse_note_id,
+    progress::ProgressIndicator,
+    CliError, PageArgs,
+};
 
 use super::{Client, Parser};
 
+/// File receipts are appended to, within the data directory, when `--receipt` is given no file
+const RECEIPTS_FILE_NAME: &str = "receipts.jsonl";
+
 #[derive(Clone, Debug, Parser)]
 #[clap()]
 pub enum TransactionType {
@@ -17,90 +49,217 @@ pub enum TransactionType {
         sender_account_id: String,
         target_account_id: String,
         faucet_id: String,
-        amount: u64,
+        /// Amount to send, in whole tokens (e.g. `1.5`) if the faucet's decimals are known and in
+        /// base units otherwise
+        amount: String,
+        /// Short text attached to the note, such as an invoice reference, readable by the target
+        #[clap(long)]
+        memo: Option<String>,
+        /// When notes are consumed to cover the payment, send what they carry beyond it back to
+        /// the sender in a separate note instead of leaving it in the sender's vault
+        #[clap(long)]
+        change_note: bool,
+        /// Only let the target consume the note once the chain is past the specified block
+        #[clap(long, value_name = "BLOCK")]
+        spendable_after: Option<u32>,
     },
     Mint {
         target_account_id: String,
         faucet_id: String,
-        amount: u64,
+        /// Amount to mint, in whole tokens (e.g. `1.5`) if the faucet's decimals are known and in
+        /// base units otherwise
+        amount: String,
     },
     P2IDR,
+    #[clap(about = "Transfer a non-fungible asset, identified by the digest shown in the vault.")]
+    SendNft {
+        sender_account_id: String,
+        target_account_id: String,
+        asset: String,
+    },
+    #[clap(
+        about = "Consume the specified notes, whose IDs can be shortened to any prefix identifying a single note."
+    )]
     ConsumeNotes {
         account_id: String,
+        #[clap(value_name = "NOTE_ID")]
         list_of_notes: Vec<String>,
     },
+    #[clap(
+        about = "Consume every committed note the account can consume, over as many transactions as needed."
+    )]
+    ConsumeAll {
+        #[clap(long)]
+        account: String,
+    },
+    #[clap(
+        about = "Consume a P2IDR note back into the account that sent it, once the client is synced up to the note's recall height."
+    )]
+    Recall {
+        /// P2IDR note to recall, whose ID can be shortened to any prefix identifying a single note
+        #[clap(long, value_name = "NOTE_ID")]
+        note: String,
+        /// Account that sent the note
+        #[clap(long, value_name = "ACCOUNT")]
+        account: String,
+    },
     #[clap(about = "Create a limit order note consumable by anyone who can fulfill it.")]
     LimitOrder {
         asset_selling_faucet_id: String,
         asset_buying_faucet_id: String,
-        amount_selling: u64,
-        amount_buying: u64,
+        amount_selling: String,
+        amount_buying: String,
+    },
+    #[clap(about = "Run a custom transaction script against an account.")]
+    Custom {
+        account_id: String,
+        /// Path to the MASM source of the transaction script
+        script: PathBuf,
+        /// TOML or JSON file holding the inputs loaded into the script's advice map, by key
+        #[clap(long)]
+        inputs_file: Option<PathBuf>,
     },
 }
 
-impl TryInto<TransactionTemplate> for &TransactionType {
-    type Error = String;
-
-    fn try_into(self) -> Result<TransactionTemplate, Self::Error> {
+impl TransactionType {
+    /// Builds the [TransactionTemplate] described by the command's arguments. Notes to consume
+    /// can be referred to by a prefix of their ID, which is resolved against the client's notes.
+    fn to_template(&self, client: &Client) -> Result<TransactionTemplate, CliError> {
         match self {
-            TransactionType::P2ID { sender_account_id, target_account_id, faucet_id, amount } => {
-                let faucet_id = AccountId::from_hex(faucet_id).map_err(|err| err.to_string())?;
-                let fungible_asset =
-                    FungibleAsset::new(faucet_id, *amount).map_err(|err| err.to_string())?.into();
-                let sender_account_id =
-                    AccountId::from_hex(sender_account_id).map_err(|err| err.to_string())?;
-                let target_account_id =
-                    AccountId::from_hex(target_account_id).map_err(|err| err.to_string())?;
-                let payment_transaction = PaymentTransactionData::new(
+            TransactionType::P2ID {
+                sender_account_id,
+                target_account_id,
+                faucet_id,
+                amount,
+                memo,
+                change_note,
+                spendable_after,
+            } => {
+                let faucet_id = parse_account_id(client, faucet_id)?;
+                let amount = parse_amount(client, faucet_id, amount)?;
+                let fungible_asset = FungibleAsset::new(faucet_id, amount)
+                    .map_err(|err| CliError::Input(err.to_string()))?
+                    .into();
+                let sender_account_id = parse_account_id(client, sender_account_id)?;
+                let target_account_id = parse_account_id(client, target_account_id)?;
+                let mut payment_transaction = PaymentTransactionData::new(
                     fungible_asset,
                     sender_account_id,
                     target_account_id,
                 );
+                if let Some(memo) = memo {
+                    payment_transaction = payment_transaction.with_memo(memo.as_str());
+                }
+                if *change_note {
+                    payment_transaction = payment_transaction.with_change_note();
+                }
 
-                Ok(TransactionTemplate::PayToId(payment_transaction))
+                match spendable_after {
+                    Some(spendable_after_block) => Ok(TransactionTemplate::PayToIdWithTimelock {
+                        payment_data: payment_transaction,
+                        spendable_after_block: *spendable_after_block,
+                    }),
+                    None => Ok(TransactionTemplate::PayToId(payment_transaction)),
+                }
             }
             TransactionType::P2IDR => {
                 todo!()
             }
             TransactionType::Mint { faucet_id, target_account_id, amount } => {
-                let faucet_id = AccountId::from_hex(faucet_id).map_err(|err| err.to_string())?;
-                let fungible_asset =
-                    FungibleAsset::new(faucet_id, *amount).map_err(|err| err.to_string())?;
-                let target_account_id =
-                    AccountId::from_hex(target_account_id).map_err(|err| err.to_string())?;
+                let faucet_id = parse_account_id(client, faucet_id)?;
+                let amount = parse_amount(client, faucet_id, amount)?;
+                let fungible_asset = FungibleAsset::new(faucet_id, amount)
+                    .map_err(|err| CliError::Input(err.to_string()))?;
+                let target_account_id = parse_account_id(client, target_account_id)?;
 
                 Ok(TransactionTemplate::MintFungibleAsset {
                     asset: fungible_asset,
                     target_account_id,
                 })
             }
+            TransactionType::SendNft { sender_account_id, target_account_id, asset } => {
+                let asset_word: Word = Digest::try_from(asset.as_str())
+                    .map_err(|err| CliError::Input(err.to_string()))?
+                    .into();
+                let asset = match Asset::try_from(asset_word)
+                    .map_err(|err| CliError::Input(err.to_string()))?
+                {
+                    Asset::NonFungible(asset) => asset,
+                    Asset::Fungible(_) => {
+                        return Err(CliError::Input(
+                            "The provided digest is not a non-fungible asset".to_string(),
+                        ))
+                    }
+                };
+                let sender_account_id = parse_account_id(client, sender_account_id)?;
+                let target_account_id = parse_account_id(client, target_account_id)?;
+
+                Ok(TransactionTemplate::SendNonFungibleAsset {
+                    asset,
+                    sender_account_id,
+                    target_account_id,
+                })
+            }
             TransactionType::ConsumeNotes { account_id, list_of_notes } => {
                 let list_of_notes = list_of_notes
                     .iter()
-                    .map(|n| NoteId::try_from_hex(n).map_err(|err| err.to_string()))
+                    .map(|note_id| parse_note_id(client, note_id))
                     .collect::<Result<Vec<NoteId>, _>>()?;
 
-                let account_id = AccountId::from_hex(account_id).map_err(|err| err.to_string())?;
+                let account_id = parse_account_id(client, account_id)?;
 
                 Ok(TransactionTemplate::ConsumeNotes(account_id, list_of_notes))
             }
+            TransactionType::ConsumeAll { account } => {
+                let account_id = parse_account_id(client, account)?;
+
+                Ok(TransactionTemplate::ConsumeAllNotes(account_id))
+            }
+            TransactionType::Recall { note, account } => {
+                let note_id = parse_note_id(client, note)?;
+                let sender_account_id = parse_account_id(client, account)?;
+
+                Ok(TransactionTemplate::RecallNote { sender_account_id, note_id })
+            }
             TransactionType::LimitOrder {
                 asset_selling_faucet_id,
                 asset_buying_faucet_id,
                 amount_selling,
                 amount_buying,
             } => {
-                let asset_selling_faucet_id =
-                    AccountId::from_hex(asset_selling_faucet_id).map_err(|err| err.to_string())?;
-                let asset_buying_faucet_id =
-                    AccountId::from_hex(asset_buying_faucet_id).map_err(|err| err.to_string())?;
-
-                let asset_selling = FungibleAsset::new(asset_selling_faucet_id, *amount_selling)
-                    .map_err(|err| err.to_string())?;
-                let asset_buying = FungibleAsset::new(asset_buying_faucet_id, *amount_buying)
-                    .map_err(|err| err.to_string())?;
+                let asset_selling_faucet_id = parse_account_id(client, asset_selling_faucet_id)?;
+                let asset_buying_faucet_id = parse_account_id(client, asset_buying_faucet_id)?;
+
+                let amount_selling = parse_amount(client, asset_selling_faucet_id, amount_selling)?;
+                let amount_buying = parse_amount(client, asset_buying_faucet_id, amount_buying)?;
+
+                let asset_selling = FungibleAsset::new(asset_selling_faucet_id, amount_selling)
+                    .map_err(|err| CliError::Input(err.to_string()))?;
+                let asset_buying = FungibleAsset::new(asset_buying_faucet_id, amount_buying)
+                    .map_err(|err| CliError::Input(err.to_string()))?;
                 println!("LIMIT ORDER: {:?} {:?}", asset_selling, asset_buying);
-                Err("Not implemented".to_string())
+                Err(CliError::Input("Not implemented".to_string()))
+            }
+            TransactionType::Custom { account_id, script, inputs_file } => {
+                let script_code = fs::read_to_string(script).map_err(|err| {
+                    CliError::Fatal(format!(
+                        "Failed to read transaction script {}: {err}",
+                        script.display()
+                    ))
+                })?;
+                let script = ProgramAst::parse(&script_code).map_err(|err| {
+                    CliError::Input(format!(
+                        "Failed to parse transaction script {}: {err}",
+                        script.display()
+                    ))
+                })?;
+                let script_inputs = match inputs_file {
+                    Some(inputs_file) => read_script_inputs(inputs_file)?,
+                    None => TransactionScriptInputs::default(),
+                };
+                let account_id = parse_account_id(client, account_id)?;
+
+                Ok(TransactionTemplate::CustomScript { account_id, script, script_inputs })
             }
         }
     }
@@ -111,30 +270,187 @@ impl TryInto<TransactionTemplate> for &TransactionType {
 pub enum Transaction {
     /// List transactions
     #[clap(short_flag = 'l')]
-    List,
-    /// Execute a transaction, prove and submit it to the node
+    List {
+        #[clap(flatten)]
+        page: PageArgs,
+    },
+    /// Execute a transaction, prove and submit it to the node. A JSON receipt with the
+    /// transaction's ID, created notes and account changes is printed for every transaction
+    /// submitted
     #[clap(short_flag = 'n')]
     New {
         #[clap(subcommand)]
         transaction_type: TransactionType,
+        /// Also write the receipts to this file, one JSON object per line. Without a file, they
+        /// are appended to `receipts.jsonl` in the data directory
+        #[clap(long, global = true, value_name = "FILE")]
+        receipt: Option<Option<PathBuf>>,
+        /// Do not display the progress of execution and proving on stderr
+        #[clap(long, global = true)]
+        no_progress: bool,
+        /// Prove the transaction and queue it instead of submitting it, as when the
+        /// `transactions.offline` option is set. Queued transactions are submitted by `tx flush`
+        /// or the next sync
+        #[clap(long, global = true)]
+        offline: bool,
+    },
+    /// Resume a transaction that was interrupted before being submitted, proving and submitting
+    /// it as needed
+    #[clap(short_flag = 'a')]
+    Advance {
+        id: String,
+        /// Execute the transaction again against the current sync height if it is stale
+        #[clap(long)]
+        reexecute: bool,
+        /// Do not display the progress of proving on stderr
+        #[clap(long)]
+        no_progress: bool,
+    },
+    /// Submit the transactions queued in offline mode, in the order they were queued
+    Flush,
+    /// Stop tracking a transaction that will never be committed, reverting the account changes
+    /// applied for it locally and dropping the notes it creates
+    Discard { id: String },
+    /// Write the proof of a proven transaction to a file, so that its STARK proof and account
+    /// delta can be verified independently of the client
+    ExportProof {
+        id: String,
+        /// File the serialized proven transaction is written to
+        #[clap(long)]
+        file: PathBuf,
+    },
+    /// Write every transaction to a CSV file, one row per asset moved by the notes it consumed
+    /// and created, along with the time the transaction was committed
+    Export {
+        /// CSV file the transactions are written to
+        #[clap(long, value_name = "FILE")]
+        csv: PathBuf,
     },
 }
 
 impl Transaction {
-    pub async fn execute(&self, mut client: Client) -> Result<(), String> {
+    pub async fn execute(&self, mut client: Client, data_directory: &Path) -> Result<(), CliError> {
         match self {
-            Transaction::List => {
-                list_transactions(client)?;
+            Transaction::List { page } => {
+                list_transactions(client, page)?;
             }
-            Transaction::New { transaction_type } => {
-                let transaction_template: TransactionTemplate = transaction_type.try_into()?;
+            Transaction::New { transaction_type, receipt, no_progress, offline } => {
+                if *offline {
+                    client.set_offline_mode(true);
+                }
+                let transaction_template = transaction_type.to_template(&client)?;
+                // created before executing anything so that an unwritable path fails early
+                let mut receipt_file = match receipt {
+                    Some(Some(path)) => Some(File::create(path)),
+                    Some(None) => Some(
+                        OpenOptions::new()
+                            .create(true)
+                            .append(true)
+                            .open(data_directory.join(RECEIPTS_FILE_NAME)),
+                    ),
+                    None => None,
+                }
+                .transpose()
+                .map_err(|err| CliError::Fatal(format!("Failed to create receipt file: {err}")))?;
 
-                let transaction_execution_result =
-                    client.new_transaction(transaction_template.clone())?;
+                // notes created right before, such as by a mint, are committed by the node
+                // after the last sync
+                if let TransactionTemplate::ConsumeNotes(_, note_ids) = &transaction_template {
+                    client.authenticate_input_notes(note_ids).await?;
+                }
 
-                info!("Executed transaction, proving and then submitting...");
+                let batches = client.split_transaction_template(transaction_template)?;
+                let batch_count = batches.len();
+                if batch_count > 1 {
+                    // stdout only holds the receipts, so that it can be parsed as JSON lines
+                    eprintln!(
+                        "Transaction exceeds the configured limits, splitting it into {batch_count} transactions"
+                    );
+                }
 
-                client.send_transaction(transaction_execution_result).await?
+                let mut submitted_transactions = Vec::new();
+                for (batch_index, transaction_template) in batches.into_iter().enumerate() {
+                    let result =
+                        execute_and_send(&mut client, transaction_template, *no_progress).await;
+                    let receipt = match result {
+                        Ok(receipt) => receipt,
+                        Err(err) => {
+                            // the batches before the failed one were submitted and stay so
+                            if !submitted_transactions.is_empty() {
+                                let submitted = submitted_transactions.join(", ");
+                                eprintln!(
+                                    "Transaction {} of {batch_count} failed, the ones before it were submitted: {submitted}",
+                                    batch_index + 1
+                                );
+                            }
+                            return Err(err.into());
+                        }
+                    };
+                    submitted_transactions.push(receipt.transaction_id.clone());
+
+                    let receipt = format_receipt(&receipt, client.is_offline())?;
+                    println!("{receipt}");
+                    if let Some(receipt_file) = receipt_file.as_mut() {
+                        writeln!(receipt_file, "{receipt}").map_err(|err| {
+                            CliError::Fatal(format!("Failed to write receipt: {err}"))
+                        })?;
+                    }
+                }
+
+                if client.is_offline() {
+                    let queued_transactions = client.get_queued_transactions()?.len();
+                    eprintln!("{queued_transactions} transactions are queued, run `tx flush` or sync once the node can be reached to submit them");
+                }
+            }
+            Transaction::Flush => {
+                let flush = client.flush_outbox().await?;
+                if flush == OutboxFlush::default() {
+                    println!("No transactions are queued");
+                }
+                print_outbox_flush(&flush);
+            }
+            Transaction::Advance { id, reexecute, no_progress } => {
+                let transaction_id = Digest::try_from(id.as_str())
+                    .map_err(|err| CliError::Input(err.to_string()))?;
+                let progress = (!no_progress)
+                    .then(|| ProgressIndicator::start("Advancing transaction", client.subscribe()));
+                let result = client.advance_transaction(transaction_id).await;
+                drop(progress);
+                match result {
+                    Ok(status) => println!("Transaction {transaction_id} is now {status}"),
+                    Err(ClientError::StaleTransaction(..)) if *reexecute => {
+                        let new_transaction_id =
+                            client.reexecute_transaction(transaction_id).await?;
+                        println!(
+                            "Transaction {transaction_id} was stale, re-executed and submitted as {new_transaction_id}"
+                        );
+                    }
+                    Err(err) => {
+                        if let ClientError::StaleTransaction(..) = err {
+                            eprintln!("Run the command again with --reexecute to execute the transaction against the current sync height");
+                        }
+                        return Err(err.into());
+                    }
+                }
+            }
+            Transaction::Discard { id } => {
+                let transaction_id = Digest::try_from(id.as_str())
+                    .map_err(|err| CliError::Input(err.to_string()))?;
+                client.discard_transaction(transaction_id)?;
+                println!("Discarded transaction {transaction_id}");
+            }
+            Transaction::ExportProof { id, file } => {
+                let transaction_id = Digest::try_from(id.as_str())
+                    .map_err(|err| CliError::Input(err.to_string()))?;
+                let proven_transaction = client.get_proven_transaction(transaction_id)?;
+                File::create(file)
+                    .and_then(|mut file| file.write_all(&proven_transaction.to_bytes()))
+                    .map_err(|err| CliError::Fatal(format!("Failed to write proof file: {err}")))?;
+                println!("Exported proof of transaction {transaction_id} to {}", file.display());
+            }
+            Transaction::Export { csv } => {
+                let num_transactions = export_transactions_csv(&client, csv)?;
+                println!("Exported {num_transactions} transactions to {}", csv.display());
             }
         }
         Ok(())
@@ -143,15 +459,185 @@ impl Transaction {
 
 // LIST TRANSACTIONS
 // ================================================================================================
-fn list_transactions(client: Client) -> Result<(), String> {
+fn list_transactions(client: Client, page: &PageArgs) -> Result<(), CliError> {
+    let transactions = match page.page() {
+        Some(page) => client.get_transactions_page(TransactionFilter::All, page)?,
+        None => client.get_transactions(TransactionFilter::All)?,
+    };
+    print_transactions_summary(&client, &transactions)
+}
+
+// EXPORT TRANSACTIONS
+// ================================================================================================
+
+/// Columns of the CSV export of transactions
+const TRANSACTIONS_CSV_HEADER: [&str; 12] = [
+    "transaction_id",
+    "status",
+    "account_id",
+    "committed_block",
+    "committed_at",
+    "direction",
+    "note_id",
+    "counterparty",
+    "faucet_id",
+    "token",
+    "amount",
+    "base_units",
+];
+
+/// Writes every transaction to a CSV file. Each asset carried by a note the transaction consumed
+/// is a row in the `in` direction, with the note's sender as counterparty, and each asset carried
+/// by a note it created is a row in the `out` direction, with the note's target as counterparty
+/// when its script is known. Notes the client does not track are skipped, and transactions that
+/// move no assets get a single row. Returns the number of transactions written.
+fn export_transactions_csv(client: &Client, path: &Path) -> Result<usize, CliError> {
     let transactions = client.get_transactions(TransactionFilter::All)?;
-    print_transactions_summary(&transactions);
-    Ok(())
+    let commit_times = client.get_transaction_commit_times()?;
+
+    let mut writer = CsvWriter::create(path, &TRANSACTIONS_CSV_HEADER)?;
+    for transaction in transactions.iter() {
+        let status = match transaction.transaction_status {
+            TransactionStatus::Executed => "executed",
+            TransactionStatus::Proven => "proven",
+            TransactionStatus::Submitted => "submitted",
+            TransactionStatus::Committed(_) => "committed",
+        };
+        let mut row_prefix =
+            vec![transaction.id.to_string(), status.to_string(), transaction.account_id.to_hex()];
+        row_prefix.extend(block_time_fields(commit_times.get(&transaction.id)));
+
+        // consumed notes unknown to the client are left out, as their assets are not known
+        let mut notes: Vec<(&str, NoteId, String, Vec<Asset>)> = Vec::new();
+        for nullifier in transaction.input_note_nullifiers.iter() {
+            let note_id = NoteId::from(*nullifier);
+            let note = match client.get_input_note(note_id) {
+                Ok(note) => note,
+                Err(ClientError::StoreError(StoreError::InputNoteNotFound(_))) => continue,
+                Err(err) => return Err(err.into()),
+            };
+            let sender = note.note().metadata().sender().to_hex();
+            notes.push(("in", note_id, sender, note.note().assets().iter().copied().collect()));
+        }
+        // created notes carry their assets in the transaction, even the ones the client only
+        // knows the recipient of, whose target cannot be decoded
+        for note in transaction.output_notes.iter() {
+            let target = match client.decode_note(note.id()) {
+                Ok(decoded_note) => target_field(decoded_note.as_ref()),
+                Err(ClientError::StoreError(StoreError::InputNoteNotFound(_))) => String::new(),
+                Err(err) => return Err(err.into()),
+            };
+            notes.push(("out", note.id(), target, note.assets().iter().copied().collect()));
+        }
+
+        let mut num_rows = 0;
+        for (direction, note_id, counterparty, assets) in notes {
+            for asset in assets.iter() {
+                let mut row = row_prefix.clone();
+                row.extend([
+                    direction.to_string(),
+                    note_id.inner().to_string(),
+                    counterparty.clone(),
+                ]);
+                row.extend(asset_fields(client, asset)?);
+                writer.write_row(&row)?;
+                num_rows += 1;
+            }
+        }
+
+        if num_rows == 0 {
+            let mut row = row_prefix;
+            row.extend(std::iter::repeat(String::new()).take(7));
+            writer.write_row(&row)?;
+        }
+    }
+    writer.finish()?;
+
+    Ok(transactions.len())
+}
+
+// OUTBOX
+// ================================================================================================
+
+/// Prints which of the transactions queued in offline mode were submitted, and which are left in
+/// the outbox
+pub(crate) fn print_outbox_flush(flush: &OutboxFlush) {
+    for transaction_id in flush.submitted.iter() {
+        println!("Submitted queued transaction {transaction_id}");
+    }
+    for transaction_id in flush.stale.iter() {
+        println!("Queued transaction {transaction_id} is stale, run `tx advance {transaction_id} --reexecute` to execute it again");
+    }
+    for transaction_id in flush.blocked.iter() {
+        println!("Queued transaction {transaction_id} was left in the outbox, as it follows a stale transaction of the same account");
+    }
+}
+
+// SCRIPT INPUTS
+// ================================================================================================
+
+/// Reads the inputs of a transaction script from a TOML file, or from a JSON file if its
+/// extension is `.json`
+fn read_script_inputs(inputs_file: &Path) -> Result<TransactionScriptInputs, CliError> {
+    let contents = fs::read_to_string(inputs_file).map_err(|err| {
+        CliError::Fatal(format!("Failed to read script inputs {}: {err}", inputs_file.display()))
+    })?;
+    let figment = match inputs_file.extension() {
+        Some(extension) if extension == "json" => Figment::from(Json::string(&contents)),
+        _ => Figment::from(Toml::string(&contents)),
+    };
+
+    figment.extract().map_err(|err| {
+        CliError::Input(format!("Invalid script inputs {}: {err}", inputs_file.display()))
+    })
 }
 
 // HELPERS
 // ================================================================================================
-fn print_transactions_summary<'a, I>(executed_transactions: I)
+
+/// Executes the transaction described by `transaction_template`, then proves and submits it
+async fn execute_and_send(
+    client: &mut Client,
+    transaction_template: TransactionTemplate,
+    no_progress: bool,
+) -> Result<TransactionReceipt, ClientError> {
+    let progress = (!no_progress)
+        .then(|| ProgressIndicator::start("Executing transaction", client.subscribe()));
+    let transaction_execution_result = client.new_transaction(transaction_template)?;
+
+    info!("Executed transaction, proving and then submitting...");
+    if let Some(progress) = progress.as_ref() {
+        progress.set_phase("Proving transaction");
+    }
+
+    let receipt = transaction_execution_result.receipt();
+    client.send_transaction(transaction_execution_result).await?;
+    drop(progress);
+
+    Ok(receipt)
+}
+
+/// Formats the receipt of a transaction as a JSON line. A transaction queued in offline mode was
+/// not submitted, so only its ID and account are printed, marked as queued, as its effects are
+/// not final until it is committed.
+fn format_receipt(receipt: &TransactionReceipt, queued: bool) -> Result<String, CliError> {
+    let receipt = if queued {
+        serde_json::json!({
+            "transaction_id": receipt.transaction_id,
+            "account_id": receipt.account_id,
+            "status": "queued",
+        })
+        .to_string()
+    } else {
+        serde_json::to_string(receipt).map_err(|err| CliError::Fatal(err.to_string()))?
+    };
+    Ok(receipt)
+}
+
+fn print_transactions_summary<'a, I>(
+    client: &Client,
+    executed_transactions: I,
+) -> Result<(), CliError>
 where
     I: IntoIterator<Item = &'a TransactionRecord>,
 {
@@ -162,9 +648,14 @@ where
         "Script Hash",
         "Input Notes Count",
         "Output Notes Count",
+        "Fee",
     ]);
 
     for tx in executed_transactions {
+        let fee = match tx.fee {
+            Some(fee) => format_amount(client, fee.faucet_id(), fee.amount())?,
+            None => "-".to_string(),
+        };
         table.add_row(vec![
             tx.id.to_string(),
             tx.transaction_status.to_string(),
@@ -172,8 +663,52 @@ where
             tx.transaction_script.as_ref().map(|x| x.hash().to_string()).unwrap_or("-".to_string()),
             tx.input_note_nullifiers.len().to_string(),
             tx.output_notes.num_notes().to_string(),
+            fee,
         ]);
     }
 
     println!("{table}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_client::client::transactions::{AccountDeltaSummary, TransactionReceipt};
+    use serde_json::Value;
+
+    use super::format_receipt;
+
+    #[test]
+    fn queued_transactions_get_no_receipt() {
+        let receipt = TransactionReceipt {
+            transaction_id: "0x01".to_string(),
+            account_id: "0x02".to_string(),
+            block_num: 3,
+            output_note_ids: vec!["0x04".to_string()],
+            account_delta: AccountDeltaSummary {
+                nonce: Some(5),
+                added_assets: Vec::new(),
+                removed_assets: Vec::new(),
+                updated_storage_slots: Vec::new(),
+            },
+            fee: None,
+        };
+
+        let submitted: Value =
+            serde_json::from_str(&format_receipt(&receipt, false).unwrap()).unwrap();
+        assert_eq!(submitted["transaction_id"], "0x01");
+        assert_eq!(submitted["block_num"], 3);
+        assert_eq!(submitted["account_delta"]["nonce"], 5);
+        assert!(submitted.get("status").is_none());
+
+        let queued: Value = serde_json::from_str(&format_receipt(&receipt, true).unwrap()).unwrap();
+        assert_eq!(
+            queued,
+            serde_json::json!({
+                "transaction_id": "0x01",
+                "account_id": "0x02",
+                "status": "queued",
+            })
+        );
+    }
 }
@@ -0,0 +1,483 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Stdout},
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use miden_client::{
+    client::{events::ClientEvent, Client},
+    store::{notes::InputNoteFilter, transactions::TransactionFilter},
+};
+use objects::assets::Asset;
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Row, Table},
+    Frame, Terminal,
+};
+
+use super::{format_amount, CliError};
+
+/// Number of transactions shown, most recent first
+const RECENT_TRANSACTIONS: usize = 10;
+
+/// Number of events kept in the activity log of the sync pane
+const ACTIVITY_LOG_LEN: usize = 8;
+
+/// How often the dashboard is redrawn and the keyboard polled
+const REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+
+type DashboardTerminal = Terminal<CrosstermBackend<Stdout>>;
+
+// DASHBOARD
+// ================================================================================================
+
+/// Shows a terminal dashboard of the client's accounts, notes and transactions, syncing every
+/// `sync_interval` seconds until `q` or `Esc` is pressed. `s` syncs right away.
+///
+/// The panes are filled from the same client queries as the other commands, and refreshed after
+/// every sync. The sync pane follows the [ClientEvent]s emitted while syncing.
+pub async fn run_dashboard(mut client: Client, sync_interval: u64) -> Result<(), CliError> {
+    let mut dashboard = Dashboard::default();
+    dashboard.refresh(&client)?;
+
+    let mut terminal = TerminalGuard::enter()?;
+    let mut events = client.subscribe();
+    let sync_interval = Duration::from_secs(sync_interval);
+    let mut next_sync = Instant::now();
+
+    loop {
+        if Instant::now() >= next_sync {
+            dashboard.sync_status = SyncStatus::Syncing {
+                block_num: None,
+                target_block_num: None,
+            };
+
+            // the dashboard keeps being redrawn while the sync is in progress
+            let result = {
+                let sync = client.sync_state();
+                tokio::pin!(sync);
+                loop {
+                    tokio::select! {
+                        result = &mut sync => break Some(result),
+                        _ = tokio::time::sleep(REFRESH_INTERVAL) => {
+                            while let Ok(event) = events.try_recv() {
+                                dashboard.apply_event(&event);
+                            }
+                            terminal.draw(&dashboard)?;
+                            if read_key()? == Some(KeyAction::Quit) {
+                                break None;
+                            }
+                        }
+                    }
+                }
+            };
+
+            while let Ok(event) = events.try_recv() {
+                dashboard.apply_event(&event);
+            }
+            dashboard.sync_status = match result {
+                None => return Ok(()),
+                Some(Ok(block_num)) => SyncStatus::Synced {
+                    block_num,
+                    at: Instant::now(),
+                },
+                Some(Err(err)) => SyncStatus::Failed(err.to_string()),
+            };
+            dashboard.refresh(&client)?;
+            next_sync = Instant::now() + sync_interval;
+        }
+
+        terminal.draw(&dashboard)?;
+        match read_key()? {
+            Some(KeyAction::Quit) => return Ok(()),
+            Some(KeyAction::Sync) => next_sync = Instant::now(),
+            None => tokio::time::sleep(REFRESH_INTERVAL).await,
+        }
+    }
+}
+
+/// State of the last or ongoing sync
+#[derive(Debug, Clone, PartialEq)]
+enum SyncStatus {
+    NotSynced,
+    Syncing {
+        block_num: Option<u32>,
+        target_block_num: Option<u32>,
+    },
+    Synced {
+        block_num: u32,
+        at: Instant,
+    },
+    Failed(String),
+}
+
+/// Contents of the dashboard's panes, formatted when read from the client so that drawing does
+/// not query the store
+#[derive(Debug)]
+struct Dashboard {
+    /// Label or ID, then balances, of every account
+    accounts: Vec<[String; 2]>,
+    /// ID, status and assets of the notes not consumed yet
+    notes: Vec<[String; 3]>,
+    /// ID, account and status of the most recent transactions
+    transactions: Vec<[String; 3]>,
+    sync_height: u32,
+    sync_status: SyncStatus,
+    /// Most recent events, latest last
+    activity: VecDeque<String>,
+}
+
+impl Default for Dashboard {
+    fn default() -> Self {
+        Self {
+            accounts: Vec::new(),
+            notes: Vec::new(),
+            transactions: Vec::new(),
+            sync_height: 0,
+            sync_status: SyncStatus::NotSynced,
+            activity: VecDeque::new(),
+        }
+    }
+}
+
+impl Dashboard {
+    /// Reads the accounts, notes and transactions from the client
+    fn refresh(&mut self, client: &Client) -> Result<(), CliError> {
+        let labels = client.get_account_labels()?;
+        self.accounts = client
+            .get_accounts()?
+            .into_iter()
+            .map(|(account, _seed)| {
+                let account_id = account.id();
+                let balances = client
+                    .get_account_balances(account_id)?
+                    .into_iter()
+                    .map(|(faucet_id, amount)| format_amount(client, faucet_id, amount))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let name = labels
+                    .get(&account_id)
+                    .cloned()
+                    .unwrap_or_else(|| account_id.to_hex());
+                Ok([name, balances.join(", ")])
+            })
+            .collect::<Result<_, CliError>>()?;
+
+        self.notes = Vec::new();
+        for (filter, status) in [
+            (InputNoteFilter::Committed, "committed"),
+            (InputNoteFilter::Pending, "pending"),
+            (InputNoteFilter::Expected, "expected"),
+        ] {
+            for note in client.get_input_notes(filter)? {
+                let assets = note
+                    .note()
+                    .assets()
+                    .iter()
+                    .map(|asset| match asset {
+                        Asset::Fungible(asset) => {
+                            format_amount(client, asset.faucet_id(), asset.amount())
+                        }
+                        Asset::NonFungible(asset) => Ok(format!("NFT of {}", asset.faucet_id())),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.notes.push([
+                    note.note_id().inner().to_string(),
+                    status.to_string(),
+                    assets.join(", "),
+                ]);
+            }
+        }
+
+        self.transactions = client
+            .get_transactions(TransactionFilter::All)?
+            .iter()
+            .rev()
+            .take(RECENT_TRANSACTIONS)
+            .map(|transaction| {
+                [
+                    transaction.id.to_string(),
+                    labels
+                        .get(&transaction.account_id)
+                        .cloned()
+                        .unwrap_or_else(|| transaction.account_id.to_hex()),
+                    transaction.transaction_status.to_string(),
+                ]
+            })
+            .collect();
+
+        self.sync_height = client.get_sync_height()?;
+        Ok(())
+    }
+
+    /// Updates the sync pane with an event emitted by the client
+    fn apply_event(&mut self, event: &ClientEvent) {
+        let entry = match event {
+            ClientEvent::SyncProgressed {
+                block_num,
+                target_block_num,
+            } => {
+                self.sync_status = SyncStatus::Syncing {
+                    block_num: Some(*block_num),
+                    target_block_num: Some(*target_block_num),
+                };
+                return;
+            }
+            ClientEvent::NoteCommitted { note_id, block_num } => {
+                format!("Note {} committed in block {block_num}", note_id.inner())
+            }
+            ClientEvent::NoteConsumed {
+                note_id, block_num, ..
+            } => format!("Note {} consumed in block {block_num}", note_id.inner()),
+            ClientEvent::NoteConsumedExternally { note_id, .. } => {
+                format!("Note {} was consumed by another client", note_id.inner())
+            }
+            ClientEvent::TransactionProven { transaction_id } => {
+                format!("Transaction {transaction_id} proven")
+            }
+            ClientEvent::TransactionSubmitted { transaction_id } => {
+                format!("Transaction {transaction_id} submitted")
+            }
+            ClientEvent::TransactionCommitted {
+                transaction_id,
+                block_num,
+            } => format!("Transaction {transaction_id} committed in block {block_num}"),
+            ClientEvent::AccountStateDesynchronized { account_id, .. } => {
+                format!("Account {account_id} is desynchronized, import it again")
+            }
+            ClientEvent::WatchedNullifierConsumed {
+                nullifier,
+                block_num,
+            } => format!("Watched nullifier {nullifier} consumed in block {block_num}"),
+        };
+
+        if self.activity.len() == ACTIVITY_LOG_LEN {
+            self.activity.pop_front();
+        }
+        self.activity.push_back(entry);
+    }
+
+    fn sync_status_line(&self) -> String {
+        match &self.sync_status {
+            SyncStatus::NotSynced => "Not synced yet".to_string(),
+            SyncStatus::Syncing {
+                block_num: Some(block_num),
+                target_block_num: Some(target_block_num),
+            } => format!("Syncing: block {block_num} of {target_block_num}"),
+            SyncStatus::Syncing { .. } => "Syncing...".to_string(),
+            SyncStatus::Synced { block_num, at } => format!(
+                "Synced to block {block_num} {}s ago",
+                at.elapsed().as_secs()
+            ),
+            SyncStatus::Failed(err) => format!("Sync failed: {err}"),
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame<'_, CrosstermBackend<Stdout>>) {
+        let [top, middle, bottom] = split::<3>(
+            Direction::Vertical,
+            frame.size(),
+            [
+                Constraint::Percentage(30),
+                Constraint::Percentage(35),
+                Constraint::Percentage(35),
+            ],
+        );
+        let [notes_area, transactions_area] = split::<2>(
+            Direction::Horizontal,
+            middle,
+            [Constraint::Percentage(50), Constraint::Percentage(50)],
+        );
+
+        frame.render_widget(
+            table(
+                "Accounts",
+                ["Account", "Balances"],
+                &self.accounts,
+                &[Constraint::Percentage(40), Constraint::Percentage(60)],
+            ),
+            top,
+        );
+        frame.render_widget(
+            table(
+                "Notes",
+                ["Note ID", "Status", "Assets"],
+                &self.notes,
+                &[
+                    Constraint::Percentage(45),
+                    Constraint::Length(10),
+                    Constraint::Percentage(40),
+                ],
+            ),
+            notes_area,
+        );
+        frame.render_widget(
+            table(
+                "Recent transactions",
+                ["Transaction ID", "Account", "Status"],
+                &self.transactions,
+                &[
+                    Constraint::Percentage(40),
+                    Constraint::Percentage(35),
+                    Constraint::Percentage(25),
+                ],
+            ),
+            transactions_area,
+        );
+
+        let mut lines = vec![
+            Line::from(format!(
+                "{} | sync height {} | s: sync now, q: quit",
+                self.sync_status_line(),
+                self.sync_height
+            )),
+            Line::from(""),
+        ];
+        lines.extend(self.activity.iter().map(|entry| Line::from(entry.clone())));
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::default().title("Sync").borders(Borders::ALL)),
+            bottom,
+        );
+    }
+}
+
+// TERMINAL
+// ================================================================================================
+
+/// Terminal switched to raw mode on the alternate screen, restored when dropped so that errors
+/// do not leave the user's terminal unusable
+struct TerminalGuard(DashboardTerminal);
+
+impl TerminalGuard {
+    fn enter() -> Result<Self, CliError> {
+        enable_raw_mode().map_err(terminal_error)?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen).map_err(terminal_error)?;
+        let terminal = Terminal::new(CrosstermBackend::new(stdout)).map_err(terminal_error)?;
+        Ok(Self(terminal))
+    }
+
+    fn draw(&mut self, dashboard: &Dashboard) -> Result<(), CliError> {
+        self.0
+            .draw(|frame| dashboard.draw(frame))
+            .map_err(terminal_error)?;
+        Ok(())
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(self.0.backend_mut(), LeaveAlternateScreen);
+        let _ = self.0.show_cursor();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyAction {
+    Quit,
+    Sync,
+}
+
+/// Returns the action of the key pressed since the last call, if any, without waiting
+fn read_key() -> Result<Option<KeyAction>, CliError> {
+    while event::poll(Duration::ZERO).map_err(terminal_error)? {
+        if let Event::Key(key) = event::read().map_err(terminal_error)? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(Some(KeyAction::Quit)),
+                KeyCode::Char('s') => return Ok(Some(KeyAction::Sync)),
+                _ => {}
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn terminal_error(err: io::Error) -> CliError {
+    CliError::Fatal(format!("Terminal error: {err}"))
+}
+
+// HELPERS
+// ================================================================================================
+
+fn split<const N: usize>(
+    direction: Direction,
+    area: ratatui::layout::Rect,
+    constraints: [Constraint; N],
+) -> [ratatui::layout::Rect; N] {
+    let areas = Layout::default()
+        .direction(direction)
+        .constraints(constraints)
+        .split(area);
+    core::array::from_fn(|index| areas[index])
+}
+
+fn table<'a, const N: usize>(
+    title: &'a str,
+    header: [&'a str; N],
+    rows: &'a [[String; N]],
+    widths: &'a [Constraint],
+) -> Table<'a> {
+    let rows = rows
+        .iter()
+        .map(|row| Row::new(row.iter().map(String::as_str).collect::<Vec<_>>()));
+    Table::new(rows)
+        .header(Row::new(header.to_vec()).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .widths(widths)
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_client::client::events::ClientEvent;
+    use objects::Digest;
+
+    use super::{Dashboard, SyncStatus, ACTIVITY_LOG_LEN};
+
+    #[test]
+    fn events_update_the_sync_pane() {
+        let mut dashboard = Dashboard::default();
+        dashboard.apply_event(&ClientEvent::SyncProgressed {
+            block_num: 10,
+            target_block_num: 40,
+        });
+        assert_eq!(
+            dashboard.sync_status,
+            SyncStatus::Syncing {
+                block_num: Some(10),
+                target_block_num: Some(40)
+            }
+        );
+        assert_eq!(
+            dashboard.sync_status_line(),
+            "Syncing: block 10 of 40".to_string()
+        );
+        assert!(dashboard.activity.is_empty());
+
+        for block_num in 0..ACTIVITY_LOG_LEN as u32 + 2 {
+            dashboard.apply_event(&ClientEvent::TransactionCommitted {
+                transaction_id: Digest::default(),
+                block_num,
+            });
+        }
+        assert_eq!(dashboard.activity.len(), ACTIVITY_LOG_LEN);
+        assert!(dashboard
+            .activity
+            .back()
+            .unwrap()
+            .ends_with(&format!("committed in block {}", ACTIVITY_LOG_LEN + 1)));
+    }
+}
@@ -0,0 +1,305 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use clap::Parser;
+use miden_client::{
+    config::{StoreConfig, WalletsConfig},
+    store::Store,
+};
+use serde_json::Value;
+
+use super::{
+    config::{edit_config_file, ConfigEdit},
+    create_dynamic_table, load_config,
+    store::confirm,
+    CliError,
+};
+
+// WALLET COMMAND
+// ================================================================================================
+
+#[derive(Debug, Clone, Parser)]
+#[clap(about = "Manage named wallets, each keeping its accounts and notes in a store of its own")]
+pub enum WalletCmd {
+    /// List the wallets along with their stores, marking the one in use
+    #[clap(short_flag = 'l')]
+    List,
+
+    /// Create a wallet with a store of its own. The wallet is not switched to
+    #[clap(short_flag = 'c')]
+    Create {
+        #[clap()]
+        name: String,
+        /// Path of the wallet's store file, `<name>.sqlite3` by default
+        #[clap(long)]
+        store: Option<PathBuf>,
+    },
+
+    /// Use the specified wallet in the following commands. `default` switches back to the store
+    /// set in the `store` section of the config file
+    #[clap(short_flag = 's')]
+    Switch {
+        #[clap()]
+        name: String,
+    },
+
+    /// Delete a wallet along with its store file, which holds the keys of its accounts. The
+    /// wallet in use cannot be deleted
+    #[clap(short_flag = 'd')]
+    Delete {
+        #[clap()]
+        name: String,
+        /// Keep the wallet's store file, only removing the wallet from the config file
+        #[clap(long, default_value_t = false)]
+        keep_store: bool,
+        /// Do not ask for confirmation before deleting the store file
+        #[clap(short, long, default_value_t = false)]
+        yes: bool,
+    },
+}
+
+impl WalletCmd {
+    pub fn execute(&self, config_file: &Path) -> Result<(), CliError> {
+        match self {
+            WalletCmd::List => list_wallets(config_file),
+            WalletCmd::Create { name, store } => create_wallet(config_file, name, store.as_deref()),
+            WalletCmd::Switch { name } => switch_wallet(config_file, name),
+            WalletCmd::Delete {
+                name,
+                keep_store,
+                yes,
+            } => delete_wallet(config_file, name, *keep_store, *yes),
+        }
+    }
+}
+
+// LIST WALLETS
+// ================================================================================================
+
+fn list_wallets(config_file: &Path) -> Result<(), CliError> {
+    let config = load_config(config_file)?;
+    let active = config.wallets.active.as_deref();
+
+    let mut table = create_dynamic_table(&["Name", "Store", "Active"]);
+    let wallets = [(WalletsConfig::DEFAULT_WALLET, &config.store)]
+        .into_iter()
+        .chain(
+            config
+                .wallets
+                .stores
+                .iter()
+                .map(|(name, store)| (name.as_str(), store)),
+        );
+    for (name, store) in wallets {
+        let is_active = active.unwrap_or(WalletsConfig::DEFAULT_WALLET) == name;
+        table.add_row(vec![
+            name.to_string(),
+            config.database_path(store).display().to_string(),
+            if is_active { "*" } else { "" }.to_string(),
+        ]);
+    }
+
+    println!("{table}");
+    Ok(())
+}
+
+// CREATE WALLET
+// ================================================================================================
+
+fn create_wallet(config_file: &Path, name: &str, store: Option<&Path>) -> Result<(), CliError> {
+    if !WalletsConfig::is_valid_name(name) {
+        return Err(CliError::Input(format!(
+            "Invalid wallet name `{name}`, expected letters, digits, `-` and `_`, other than `{}`",
+            WalletsConfig::DEFAULT_WALLET
+        )));
+    }
+
+    let config = load_config(config_file)?;
+    if config.wallets.stores.contains_key(name) {
+        return Err(CliError::Input(format!("Wallet `{name}` already exists")));
+    }
+
+    let database_filepath = match store {
+        Some(store) => store.to_string_lossy().into_owned(),
+        None => format!("{name}.sqlite3"),
+    };
+    let database_path = config.data_directory.join(&database_filepath);
+    let used_stores = [&config.store]
+        .into_iter()
+        .chain(config.wallets.stores.values());
+    for used_store in used_stores {
+        if is_same_file(&config.database_path(used_store), &database_path) {
+            return Err(CliError::Input(format!(
+                "Store {database_filepath} is already used by another wallet"
+            )));
+        }
+    }
+
+    // The store is created before the wallet is added to the config file, so that a store that
+    // cannot be created does not leave a wallet behind
+    let store_existed = database_path.exists();
+    Store::new(
+        StoreConfig::try_from(database_path.to_string_lossy().as_ref()).map_err(CliError::Input)?,
+    )?;
+    let key = format!("wallets.stores.{name}.database_filepath");
+    if let Err(err) = edit_config_file(
+        config_file,
+        &[ConfigEdit::Set(
+            &key,
+            Value::String(database_filepath.clone()),
+        )],
+    ) {
+        if !store_existed {
+            let _ = fs::remove_file(&database_path);
+        }
+        return Err(err);
+    }
+
+    println!(
+        "Created wallet `{name}` with store {}",
+        database_path.display()
+    );
+    println!("Run `miden-client wallet switch {name}` to start using it");
+    Ok(())
+}
+
+// SWITCH WALLET
+// ================================================================================================
+
+fn switch_wallet(config_file: &Path, name: &str) -> Result<(), CliError> {
+    if name == WalletsConfig::DEFAULT_WALLET {
+        edit_config_file(config_file, &[ConfigEdit::Remove("wallets.active")])?;
+    } else {
+        let config = load_config(config_file)?;
+        if !config.wallets.stores.contains_key(name) {
+            return Err(CliError::Input(format!("Unknown wallet `{name}`")));
+        }
+        edit_config_file(
+            config_file,
+            &[ConfigEdit::Set(
+                "wallets.active",
+                Value::String(name.to_string()),
+            )],
+        )?;
+    }
+
+    println!("Switched to wallet `{name}`");
+    Ok(())
+}
+
+// DELETE WALLET
+// ================================================================================================
+
+fn delete_wallet(
+    config_file: &Path,
+    name: &str,
+    keep_store: bool,
+    yes: bool,
+) -> Result<(), CliError> {
+    let config = load_config(config_file)?;
+    let store = config
+        .wallets
+        .stores
+        .get(name)
+        .ok_or_else(|| CliError::Input(format!("Unknown wallet `{name}`")))?;
+    if config.wallets.active.as_deref() == Some(name) {
+        return Err(CliError::Input(format!(
+            "Wallet `{name}` is in use, switch to another wallet before deleting it"
+        )));
+    }
+
+    let store_file = config.database_path(store);
+    let remove_store = !keep_store && store_file.exists();
+    if remove_store
+        && !yes
+        && !confirm(&format!(
+            "Delete wallet `{name}` along with its store {}, including the keys of its accounts?",
+            store_file.display()
+        ))?
+    {
+        println!("The wallet was not deleted.");
+        return Ok(());
+    }
+
+    edit_config_file(
+        config_file,
+        &[ConfigEdit::Remove(&format!("wallets.stores.{name}"))],
+    )?;
+
+    if !remove_store {
+        println!("Deleted wallet `{name}`");
+        return Ok(());
+    }
+    fs::remove_file(&store_file).map_err(|err| {
+        CliError::Fatal(format!(
+            "Deleted wallet `{name}` but failed to remove its store {}: {err}",
+            store_file.display()
+        ))
+    })?;
+
+    println!(
+        "Deleted wallet `{name}` along with its store {}",
+        store_file.display()
+    );
+    Ok(())
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Returns whether two paths point to the same file, whether or not it exists, resolving
+/// relative paths, `.` and `..` components and symbolic links of the existing directories
+fn is_same_file(path: &Path, other: &Path) -> bool {
+    canonical_path(path) == canonical_path(other)
+}
+
+/// Returns the canonical form of `path`. The file may not exist yet, in which case its closest
+/// existing ancestor is canonicalized instead
+fn canonical_path(path: &Path) -> PathBuf {
+    let mut absolute = match env::current_dir() {
+        Ok(current_dir) => current_dir.join(path),
+        Err(_) => path.to_path_buf(),
+    };
+
+    let mut missing_components = Vec::new();
+    loop {
+        if let Ok(canonical) = fs::canonicalize(&absolute) {
+            return missing_components
+                .into_iter()
+                .rev()
+                .fold(canonical, |path, component| path.join(component));
+        }
+        match (absolute.file_name(), absolute.parent()) {
+            (Some(file_name), Some(parent)) => {
+                missing_components.push(file_name.to_os_string());
+                absolute = parent.to_path_buf();
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::is_same_file;
+
+    #[test]
+    fn store_paths_are_compared_once_resolved() {
+        let current_dir = env::current_dir().unwrap();
+
+        assert!(is_same_file("./a.sqlite3".as_ref(), "a.sqlite3".as_ref()));
+        assert!(is_same_file(
+            "a.sqlite3".as_ref(),
+            &current_dir.join("a.sqlite3")
+        ));
+        assert!(is_same_file(
+            "src/../a.sqlite3".as_ref(),
+            "a.sqlite3".as_ref()
+        ));
+        assert!(!is_same_file("a.sqlite3".as_ref(), "b.sqlite3".as_ref()));
+    }
+}
@@ -1,19 +1,63 @@
-use crypto::{dsa::rpo_falcon512::KeyPair, Felt, Word};
-use miden_lib::AuthScheme;
+use crypto::{dsa::rpo_falcon512::KeyPair, Felt, StarkField, Word, ZERO};
+use miden_lib::{
+    transaction::{memory::FAUCET_STORAGE_DATA_SLOT, TransactionKernel},
+    AuthScheme,
+};
 use objects::{
     accounts::{
-        Account, AccountData, AccountDelta, AccountId, AccountStorage, AccountStub, AccountType,
-        AuthData,
+        Account, AccountCode, AccountData, AccountDelta, AccountId, AccountStorage, AccountStub,
+        AccountType, AuthData, StorageSlotType,
     },
     assembly::ModuleAst,
-    assets::{Asset, TokenSymbol},
+    assets::{Asset, AssetVault, TokenSymbol},
+    notes::Note,
+    utils::collections::BTreeMap,
     Digest,
 };
-use rand::{rngs::ThreadRng, Rng};
+use rand::Rng;
+use std::ops::RangeBounds;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tracing::warn;
 
-use crate::{errors::ClientError, store::accounts::AuthInfo};
+use crate::{
+    errors::{ClientError, StoreError},
+    store::accounts::{
+        AccountCodeUpgrade, AccountDeltaRecord, AccountProof, AddressBookEntry, AuthInfo,
+        DesynchronizedAccount, FaucetDetails, FAUCET_METADATA_SLOT,
+    },
+};
 
-use super::Client;
+use super::{address::Address, sync::verify_account_proof, Client};
+
+// KEYSTORE
+// ================================================================================================
+
+/// Provides the keys the transactions of the client's accounts are signed with.
+///
+/// By default the keys are kept in the store along with the accounts. A keystore set with
+/// [ClientBuilder::keystore](super::builder::ClientBuilder::keystore) is asked for the keys of an
+/// account first, which allows keeping them elsewhere, such as in the keychain of the operating
+/// system. The keys of the accounts it does not hold are still read from the store.
+pub trait KeyStore: Send {
+    /// Returns the keys of the account with the specified ID, or `None` if the keystore does not
+    /// hold them.
+    ///
+    /// # Errors
+    /// Returning an error keeps the transaction that needed the keys from being executed.
+    fn get_account_auth(&self, account_id: AccountId) -> Result<Option<AuthInfo>, ClientError>;
+}
+
+impl<F> KeyStore for F
+where
+    F: Fn(AccountId) -> Result<Option<AuthInfo>, ClientError> + Send,
+{
+    fn get_account_auth(&self, account_id: AccountId) -> Result<Option<AuthInfo>, ClientError> {
+        self(account_id)
+    }
+}
+
+// ACCOUNT TEMPLATES
+// ================================================================================================
 
 pub enum AccountTemplate {
     BasicWallet {
@@ -26,6 +70,17 @@ pub enum AccountTemplate {
         max_supply: u64,
         storage_mode: AccountStorageMode,
     },
+    /// An account running custom code. Like the other templates, the account is controlled by
+    /// an RPO Falcon 512 key pair whose public key is kept in storage slot 0, where the auth
+    /// procedures exported by the code are expected to read it from.
+    Custom {
+        account_type: AccountType,
+        /// MASM source of the account's code module
+        code: String,
+        /// Initial values of the account's storage slots other than slot 0
+        storage_slots: Vec<(u8, Word)>,
+        storage_mode: AccountStorageMode,
+    },
 }
 
 pub enum AccountStorageMode {
@@ -33,46 +88,143 @@ pub enum AccountStorageMode {
     OnChain,
 }
 
+/// Senders of the [AccountStateDelta] streams handed out by [Client::watch_account], per account.
+pub(crate) type AccountWatchers = BTreeMap<AccountId, Vec<UnboundedSender<AccountStateDelta>>>;
+
+/// Changes between two consecutive local states of a watched account.
+///
+/// Fungible assets whose amount changed show up both in `removed_assets` (with the previous
+/// amount) and in `added_assets` (with the new amount).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountStateDelta {
+    pub account_id: AccountId,
+    pub previous_nonce: Felt,
+    pub nonce: Felt,
+    /// Storage slots whose value changed, as `(index, previous value, new value)`
+    pub storage_slots: Vec<(u8, Digest, Digest)>,
+    pub added_assets: Vec<Asset>,
+    pub removed_assets: Vec<Asset>,
+}
+
+impl AccountStateDelta {
+    /// Computes the changes between `previous` and `current`, returning `None` if both states
+    /// are the same.
+    pub fn from_states(previous: &Account, current: &Account) -> Option<Self> {
+        if previous.hash() == current.hash() {
+            return None;
+        }
+
+        let storage_slots = (0..current.storage().layout().len())
+            .map(|idx| idx as u8)
+            .filter_map(|idx| {
+                let previous_value = previous.storage().get_item(idx);
+                let value = current.storage().get_item(idx);
+                (previous_value != value).then_some((idx, previous_value, value))
+            })
+            .collect();
+
+        let previous_assets: Vec<Asset> = previous.vault().assets().collect();
+        let assets: Vec<Asset> = current.vault().assets().collect();
+
+        Some(Self {
+            account_id: current.id(),
+            previous_nonce: previous.nonce(),
+            nonce: current.nonce(),
+            storage_slots,
+            added_assets: assets
+                .iter()
+                .filter(|asset| !previous_assets.contains(asset))
+                .copied()
+                .collect(),
+            removed_assets: previous_assets
+                .iter()
+                .filter(|asset| !assets.contains(asset))
+                .copied()
+                .collect(),
+        })
+    }
+}
+
+/// Metadata of a fungible faucet, as read from the faucet account's storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaucetMetadata {
+    pub token_symbol: TokenSymbol,
+    pub decimals: u8,
+    pub max_supply: u64,
+    pub total_issuance: u64,
+}
+
 impl Client {
     // ACCOUNT CREATION
     // --------------------------------------------------------------------------------------------
 
-    /// Creates a new [Account] based on an [AccountTemplate] and saves it in the store
+    /// Creates a new [Account] based on an [AccountTemplate] and saves it in the store, along with
+    /// the note tags targeting it
     pub fn new_account(
         &mut self,
         template: AccountTemplate,
     ) -> Result<(Account, Word), ClientError> {
-        let mut rng = rand::thread_rng();
+        // we need to use an initial seed to create the account
+        let init_seed: [u8; 32] = self.rng.gen();
 
         let account_and_seed = match template {
             AccountTemplate::BasicWallet {
                 mutable_code,
                 storage_mode,
-            } => self.new_basic_wallet(mutable_code, &mut rng, storage_mode),
+            } => self.new_basic_wallet(mutable_code, init_seed, storage_mode),
             AccountTemplate::FungibleFaucet {
                 token_symbol,
                 decimals,
                 max_supply,
                 storage_mode,
+            } => self.new_fungible_faucet(
+                token_symbol,
+                decimals,
+                max_supply,
+                init_seed,
+                storage_mode,
+            ),
+            AccountTemplate::Custom {
+                account_type,
+                code,
+                storage_slots,
+                storage_mode,
             } => {
-                self.new_fungible_faucet(token_symbol, decimals, max_supply, &mut rng, storage_mode)
+                self.new_custom_account(account_type, &code, storage_slots, init_seed, storage_mode)
             }
         }?;
+        self.register_account_tags(account_and_seed.0.id())?;
 
         Ok(account_and_seed)
     }
 
     /// Saves in the store the [Account] corresponding to `account_data`.
     ///
+    /// Importing an account a sync found desynchronized replaces its local state, which clears
+    /// the divergence, see [Client::get_desynchronized_accounts].
+    ///
     /// # Errors
     ///
-    /// Will return an error if trying to import a new account without providing its seed
+    /// Will return an error if trying to import a new account without providing its seed, or if
+    /// the account is desynchronized and the imported state is not the on-chain one
     ///
     /// # Panics
     ///
     /// Will panic when trying to import a non new account without a seed since it's not
     /// implemented yet
     pub fn import_account(&mut self, account_data: AccountData) -> Result<(), ClientError> {
+        // an account whose on-chain state diverged can only be replaced by that state
+        let account_id = account_data.account.id();
+        if let Some(desynchronized) = self.store.get_desynchronized_account(account_id)? {
+            if account_data.account.hash() != desynchronized.remote_hash {
+                return Err(ClientError::AccountStateDesynchronized(account_id));
+            }
+            return self
+                .store
+                .replace_desynchronized_account(&account_data.account)
+                .map_err(|err| err.into());
+        }
+
         match account_data.auth {
             AuthData::RpoFalcon512Seed(key_pair) => {
                 let keypair = KeyPair::from_seed(&key_pair)?;
@@ -83,7 +235,7 @@ impl Client {
                         &AuthInfo::RpoFalcon512(keypair),
                     ),
                     (false, Some(seed)) => {
-                        tracing::warn!(
+                        warn!(
                             "Imported an existing account and still provided a seed when it is not needed. It's possible that the account's file was incorrectly generated."
                         );
 
@@ -106,11 +258,13 @@ impl Client {
     fn new_basic_wallet(
         &mut self,
         mutable_code: bool,
-        rng: &mut ThreadRng,
+        init_seed: [u8; 32],
         account_storage_mode: AccountStorageMode,
     ) -> Result<(Account, Word), ClientError> {
         if let AccountStorageMode::OnChain = account_storage_mode {
-            todo!("Recording the account on chain is not supported yet");
+            return Err(ClientError::UnsupportedFeature(
+                "recording accounts on chain".to_string(),
+            ));
         }
 
         let key_pair: objects::crypto::dsa::rpo_falcon512::KeyPair =
@@ -120,9 +274,6 @@ impl Client {
             pub_key: key_pair.public_key(),
         };
 
-        // we need to use an initial seed to create the wallet account
-        let init_seed: [u8; 32] = rng.gen();
-
         let (account, seed) = if !mutable_code {
             miden_lib::accounts::wallets::create_basic_wallet(
                 init_seed,
@@ -146,11 +297,13 @@ impl Client {
         token_symbol: TokenSymbol,
         decimals: u8,
         max_supply: u64,
-        rng: &mut ThreadRng,
+        init_seed: [u8; 32],
         account_storage_mode: AccountStorageMode,
     ) -> Result<(Account, Word), ClientError> {
         if let AccountStorageMode::OnChain = account_storage_mode {
-            todo!("On-chain accounts are not supported yet");
+            return Err(ClientError::UnsupportedFeature(
+                "recording accounts on chain".to_string(),
+            ));
         }
 
         let key_pair: objects::crypto::dsa::rpo_falcon512::KeyPair =
@@ -160,9 +313,6 @@ impl Client {
             pub_key: key_pair.public_key(),
         };
 
-        // we need to use an initial seed to create the wallet account
-        let init_seed: [u8; 32] = rng.gen();
-
         let (account, seed) = miden_lib::accounts::faucets::create_basic_fungible_faucet(
             init_seed,
             token_symbol,
@@ -176,6 +326,62 @@ impl Client {
         Ok((account, seed))
     }
 
+    /// Creates a new account running the provided code and saves it in the store along with its
+    /// seed and auth data
+    fn new_custom_account(
+        &mut self,
+        account_type: AccountType,
+        code: &str,
+        storage_slots: Vec<(u8, Word)>,
+        init_seed: [u8; 32],
+        account_storage_mode: AccountStorageMode,
+    ) -> Result<(Account, Word), ClientError> {
+        if let AccountStorageMode::OnChain = account_storage_mode {
+            return Err(ClientError::UnsupportedFeature(
+                "recording accounts on chain".to_string(),
+            ));
+        }
+
+        let key_pair: objects::crypto::dsa::rpo_falcon512::KeyPair =
+            objects::crypto::dsa::rpo_falcon512::KeyPair::new()?;
+
+        let code_ast = ModuleAst::parse(code).map_err(|err| {
+            ClientError::InvalidAccountTemplate(format!("failed to parse the account code: {err}"))
+        })?;
+        let account_code = AccountCode::new(code_ast, &TransactionKernel::assembler())?;
+
+        let value_slot = |value: Word| (StorageSlotType::Value { value_arity: 0 }, value);
+        let mut slots = vec![(0, value_slot(key_pair.public_key().into()))];
+        for (index, value) in storage_slots {
+            if index == 0 {
+                return Err(ClientError::InvalidAccountTemplate(
+                    "storage slot 0 is reserved for the account's public key".to_string(),
+                ));
+            }
+            slots.push((index, value_slot(value)));
+        }
+        let account_storage = AccountStorage::new(slots)?;
+
+        let account_seed = AccountId::get_account_seed(
+            init_seed,
+            account_type,
+            false,
+            account_code.root(),
+            account_storage.root(),
+        )?;
+        let account_id = AccountId::new(account_seed, account_code.root(), account_storage.root())?;
+        let account = Account::new(
+            account_id,
+            AssetVault::new(&[]).expect("an empty vault is valid"),
+            account_storage,
+            account_code,
+            ZERO,
+        );
+
+        self.insert_account(&account, account_seed, &AuthInfo::RpoFalcon512(key_pair))?;
+        Ok((account, account_seed))
+    }
+
     /// Inserts a new account into the client's store.
     pub fn insert_account(
         &mut self,
@@ -194,9 +400,72 @@ impl Client {
         account_id: AccountId,
         account_delta: &AccountDelta,
     ) -> Result<(), ClientError> {
+        let watched_accounts = self.get_watched_accounts()?;
         self.store
             .update_account(account_id, account_delta)
-            .map_err(ClientError::StoreError)
+            .map_err(ClientError::StoreError)?;
+
+        self.notify_account_watchers(watched_accounts)
+    }
+
+    // ACCOUNT WATCHING
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a stream of [AccountStateDelta] events for the specified account.
+    ///
+    /// An event is produced every time the locally stored state of the account changes, be it
+    /// through a sync or through a transaction executed by this client. The stream ends when the
+    /// client is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the account is not tracked by this client.
+    pub fn watch_account(
+        &mut self,
+        account_id: AccountId,
+    ) -> Result<UnboundedReceiver<AccountStateDelta>, ClientError> {
+        self.store.get_account_stub_by_id(account_id)?;
+
+        let (sender, receiver) = unbounded_channel();
+        self.account_watchers
+            .entry(account_id)
+            .or_default()
+            .push(sender);
+
+        Ok(receiver)
+    }
+
+    /// Returns the current state of every watched account, to be later handed to
+    /// [Client::notify_account_watchers] once the store has been updated.
+    pub(crate) fn get_watched_accounts(&self) -> Result<Vec<Account>, ClientError> {
+        self.account_watchers
+            .keys()
+            .map(|account_id| Ok(self.store.get_account_by_id(*account_id)?.0))
+            .collect()
+    }
+
+    /// Compares the `previous` state of each watched account against its stored state and sends
+    /// the resulting [AccountStateDelta] to the account's watchers. Watchers whose stream was
+    /// dropped are discarded.
+    pub(crate) fn notify_account_watchers(
+        &mut self,
+        previous: Vec<Account>,
+    ) -> Result<(), ClientError> {
+        for previous_account in previous {
+            let (current_account, _seed) = self.store.get_account_by_id(previous_account.id())?;
+            let Some(delta) = AccountStateDelta::from_states(&previous_account, &current_account)
+            else {
+                continue;
+            };
+
+            if let Some(senders) = self.account_watchers.get_mut(&current_account.id()) {
+                senders.retain(|sender| sender.send(delta.clone()).is_ok());
+            }
+        }
+        self.account_watchers
+            .retain(|_account_id, senders| !senders.is_empty());
+
+        Ok(())
     }
 
     // ACCOUNT DATA RETRIEVAL
@@ -225,8 +494,122 @@ impl Client {
             .map_err(|err| err.into())
     }
 
+    /// Returns summary info about the state the specified account had at block `block_num`,
+    /// which is the latest state stored while the client was synced up to that block. States
+    /// are dated by the sync height when they were stored rather than by their commit block, see
+    /// [Store::get_account_stub_at](crate::store::Store::get_account_stub_at).
+    pub fn get_account_stub_at(
+        &self,
+        account_id: AccountId,
+        block_num: u32,
+    ) -> Result<(AccountStub, Word), ClientError> {
+        self.store
+            .get_account_stub_at(account_id, block_num)
+            .map_err(|err| err.into())
+    }
+
+    /// Returns the full state the specified account had at block `block_num`. See
+    /// [Client::get_account_stub_at].
+    pub fn get_account_state_at(
+        &self,
+        account_id: AccountId,
+        block_num: u32,
+    ) -> Result<(Account, Word), ClientError> {
+        self.store
+            .get_account_state_at(account_id, block_num)
+            .map_err(|err| err.into())
+    }
+
+    /// Returns the hash of the current state of the specified account, without loading the
+    /// account's code, storage or vault.
+    pub fn get_account_hash(&self, account_id: AccountId) -> Result<Digest, ClientError> {
+        self.store
+            .get_account_hash(account_id)
+            .map_err(|err| err.into())
+    }
+
+    /// Returns the deltas applied to the specified account, in the order they were applied, whose
+    /// resulting account nonce is within `nonces`. Each delta records what a transaction changed
+    /// in the account's vault, storage and nonce.
+    pub fn get_account_deltas(
+        &self,
+        account_id: AccountId,
+        nonces: impl RangeBounds<u64>,
+    ) -> Result<Vec<AccountDeltaRecord>, ClientError> {
+        self.store
+            .get_account_deltas(account_id, nonces)
+            .map_err(|err| err.into())
+    }
+
+    /// Returns the upgrades made to the code of the specified account through
+    /// [Client::upgrade_account_code], in the order they were made. The code the account had
+    /// before each upgrade can be retrieved by its root with [Client::get_account_code].
+    pub fn get_account_code_upgrades(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<AccountCodeUpgrade>, ClientError> {
+        self.store
+            .get_account_code_upgrades(account_id)
+            .map_err(|err| err.into())
+    }
+
+    /// Returns the latest verified proof of the state of the specified on-chain account, recorded
+    /// when syncing or by [Client::verify_account]
+    pub fn get_account_proof(&self, account_id: AccountId) -> Result<AccountProof, ClientError> {
+        self.store
+            .get_account_proof(account_id)
+            .map_err(|err| err.into())
+    }
+
+    /// Requests the proof of the state of an account at the block the client is synced to,
+    /// verifies it against the block's account root and checks that it matches the local state
+    /// of the account. Syncing beforehand checks the account against the chain tip. The account
+    /// tree holds the hash of the state of off-chain accounts too, so their state can be proven
+    /// as well.
+    ///
+    /// The verified proof is stored and returned.
+    ///
+    /// # Errors
+    /// Returns an error if the proof doesn't verify or if the proven state differs from the local
+    /// one, for instance because a transaction of the account was not committed yet.
+    pub async fn verify_account(
+        &mut self,
+        account_id: AccountId,
+    ) -> Result<AccountProof, ClientError> {
+        let sync_height = self.store.get_sync_height()?;
+        let (block_header, _) = self.store.get_block_header_by_num(sync_height)?;
+        let (proof_block_header, account_hash, merkle_path) = self
+            .rpc_api
+            .get_account_proof(account_id, sync_height)
+            .await?;
+        if proof_block_header.hash() != block_header.hash() {
+            return Err(ClientError::InvalidAccountProof(
+                account_id,
+                format!("the proof was made against another header of block {sync_height}"),
+            ));
+        }
+
+        let account_proof =
+            verify_account_proof(account_id, account_hash, merkle_path, &block_header)?;
+        if account_proof.account_hash != self.store.get_account_hash(account_id)? {
+            return Err(StoreError::AccountHashMismatch(account_id).into());
+        }
+        self.store.insert_account_proof(&account_proof)?;
+
+        Ok(account_proof)
+    }
+
     /// Returns key pair structure for an Account Id.
+    ///
+    /// The keys are taken from the client's [KeyStore] if it has one and it holds them, and from
+    /// the store otherwise.
     pub fn get_account_auth(&self, account_id: AccountId) -> Result<AuthInfo, ClientError> {
+        if let Some(keystore) = &self.keystore {
+            if let Some(auth_info) = keystore.get_account_auth(account_id)? {
+                return Ok(auth_info);
+            }
+        }
+
         self.store
             .get_account_auth(account_id)
             .map_err(|err| err.into())
@@ -239,6 +622,307 @@ impl Client {
             .map_err(|err| err.into())
     }
 
+    /// Returns the amount of the asset issued by `faucet_id` held by the specified account.
+    ///
+    /// Returns 0 if the account's vault does not contain any asset issued by the faucet.
+    pub fn get_account_balance(
+        &self,
+        account_id: AccountId,
+        faucet_id: AccountId,
+    ) -> Result<u64, ClientError> {
+        Ok(self
+            .store
+            .get_account_balances(account_id)?
+            .get(&faucet_id)
+            .copied()
+            .unwrap_or(0))
+    }
+
+    /// Returns the balances of the specified account aggregated per faucet, as a list of faucet
+    /// IDs and amounts.
+    pub fn get_account_balances(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<(AccountId, u64)>, ClientError> {
+        Ok(self
+            .store
+            .get_account_balances(account_id)?
+            .into_iter()
+            .collect())
+    }
+
+    // ACCOUNT ID RESOLUTION
+    // --------------------------------------------------------------------------------------------
+
+    /// Resolves a user-provided reference to an account into its ID. The reference is, in order
+    /// of precedence:
+    ///
+    /// - The name of an address book entry.
+    /// - The label of a single tracked account, see [Client::set_account_label]. Labels shared by
+    ///   several accounts do not resolve.
+    /// - An [Address], which need not belong to an account known to the client.
+    /// - A full hex account ID, which need not belong to an account known to the client.
+    /// - A hex prefix, with or without `0x`, of the ID of a single account tracked by the client or
+    ///   named in the address book.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reference matches no account, the prefix matches several or the
+    /// reference is a malformed address.
+    pub fn resolve_account_id(&self, account_ref: &str) -> Result<AccountId, ClientError> {
+        if let Some(account_id) = self.store.get_address_book_account_id(account_ref)? {
+            return Ok(account_id);
+        }
+        let labelled_accounts: Vec<AccountId> = self
+            .store
+            .get_account_labels()?
+            .into_iter()
+            .filter(|(_, label)| label == account_ref)
+            .map(|(account_id, _)| account_id)
+            .collect();
+        if let [account_id] = labelled_accounts[..] {
+            return Ok(account_id);
+        }
+        if Address::has_address_prefix(account_ref) {
+            return Ok(account_ref.parse::<Address>()?.account_id());
+        }
+        if let Ok(account_id) = AccountId::from_hex(account_ref) {
+            return Ok(account_id);
+        }
+        Ok(self.store.get_account_id_by_prefix(account_ref)?)
+    }
+
+    /// Returns the entries of the address book, sorted by name.
+    pub fn get_address_book(&self) -> Result<Vec<AddressBookEntry>, ClientError> {
+        self.store.get_address_book().map_err(|err| err.into())
+    }
+
+    /// Names the account `account_id` in the address book, so that it can be referred to by
+    /// `name` in [Client::resolve_account_id]. An entry with the same name is replaced.
+    pub fn add_address_book_entry(
+        &mut self,
+        name: &str,
+        account_id: AccountId,
+    ) -> Result<(), ClientError> {
+        self.store
+            .insert_address_book_entry(name, account_id)
+            .map_err(|err| err.into())
+    }
+
+    /// Removes the entry named `name` from the address book.
+    pub fn remove_address_book_entry(&mut self, name: &str) -> Result<(), ClientError> {
+        self.store
+            .remove_address_book_entry(name)
+            .map_err(|err| err.into())
+    }
+
+    // ACCOUNT LABELS
+    // --------------------------------------------------------------------------------------------
+
+    /// Labels the account `account_id` to organize the wallet, for example as `savings`. Several
+    /// accounts can share a label, and an account's previous label is replaced. Passing `None`
+    /// removes the account's label.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the account is not tracked by the client or the label is blank.
+    pub fn set_account_label(
+        &mut self,
+        account_id: AccountId,
+        label: Option<&str>,
+    ) -> Result<(), ClientError> {
+        self.store.get_account_stub_by_id(account_id)?;
+        self.store
+            .set_account_label(account_id, label)
+            .map_err(|err| err.into())
+    }
+
+    /// Returns the label of the account `account_id`, if it has one.
+    pub fn get_account_label(&self, account_id: AccountId) -> Result<Option<String>, ClientError> {
+        self.store
+            .get_account_label(account_id)
+            .map_err(|err| err.into())
+    }
+
+    /// Returns the labels of all labelled accounts.
+    pub fn get_account_labels(&self) -> Result<BTreeMap<AccountId, String>, ClientError> {
+        self.store.get_account_labels().map_err(|err| err.into())
+    }
+
+    // REMOVING ACCOUNTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Hides the account `account_id`, which is no longer listed by [Client::get_accounts] nor
+    /// synced, while its data is kept so that it can be restored with [Client::restore_account].
+    /// Transactions executed against the account are only seen committed once it is restored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the account is not tracked by the client.
+    pub fn hide_account(&mut self, account_id: AccountId) -> Result<(), ClientError> {
+        self.store.get_account_stub_by_id(account_id)?;
+        if !self.store.hide_account(account_id)? {
+            warn!("Account {} is already hidden", account_id);
+        }
+        Ok(())
+    }
+
+    /// Lists and syncs the hidden account `account_id` again. The next [Client::sync_state]
+    /// retrieves the notes committed for the account while it was hidden.
+    pub fn restore_account(&mut self, account_id: AccountId) -> Result<(), ClientError> {
+        if !self.store.restore_account(account_id)? {
+            warn!("Account {} is not hidden", account_id);
+        }
+        Ok(())
+    }
+
+    /// Returns the accounts hidden with [Client::hide_account]
+    pub fn get_hidden_accounts(&self) -> Result<Vec<(AccountStub, Word)>, ClientError> {
+        self.store.get_hidden_accounts().map_err(|err| err.into())
+    }
+
+    /// Deletes the account `account_id` from the store, along with its keys, its transactions,
+    /// the notes they created and the notes targeting it. This cannot be undone.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the account is not tracked by the client.
+    pub fn purge_account(&mut self, account_id: AccountId) -> Result<(), ClientError> {
+        self.store.get_account_stub_by_id(account_id)?;
+        self.store
+            .purge_account(account_id)
+            .map_err(|err| err.into())
+    }
+
+    // DESYNCHRONIZED ACCOUNTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the accounts whose on-chain state was found by a sync to differ from the local one,
+    /// usually because they were updated from another store. Transactions against them fail with
+    /// [ClientError::AccountStateDesynchronized] until they are imported again with
+    /// [Client::import_account].
+    pub fn get_desynchronized_accounts(&self) -> Result<Vec<DesynchronizedAccount>, ClientError> {
+        self.store
+            .get_desynchronized_accounts()
+            .map_err(|err| err.into())
+    }
+
+    /// Returns the divergence found between the local and on-chain states of the account, if any.
+    pub fn get_desynchronized_account(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<DesynchronizedAccount>, ClientError> {
+        self.store
+            .get_desynchronized_account(account_id)
+            .map_err(|err| err.into())
+    }
+
+    // FAUCET DATA RETRIEVAL
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the IDs of all fungible faucets managed by this client.
+    pub fn get_faucet_ids(&self) -> Result<Vec<AccountId>, ClientError> {
+        Ok(self
+            .store
+            .get_account_ids()?
+            .into_iter()
+            .filter(|account_id| account_id.account_type() == AccountType::FungibleFaucet)
+            .collect())
+    }
+
+    /// Returns the [FaucetMetadata] of the specified fungible faucet, including the total amount
+    /// of tokens issued so far.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the account is not a fungible faucet or if its storage does not hold
+    /// valid faucet metadata.
+    pub fn get_faucet_metadata(&self, faucet_id: AccountId) -> Result<FaucetMetadata, ClientError> {
+        if faucet_id.account_type() != AccountType::FungibleFaucet {
+            return Err(ClientError::AccountIsNotFaucet(faucet_id));
+        }
+
+        let storage = self.store.get_account_storage_by_account_id(faucet_id)?;
+
+        let FaucetDetails {
+            token_symbol,
+            decimals,
+        } = FaucetDetails::try_from(&storage)?;
+        let max_supply = storage.get_item(FAUCET_METADATA_SLOT)[0];
+        let total_issuance = storage.get_item(FAUCET_STORAGE_DATA_SLOT)[3];
+
+        Ok(FaucetMetadata {
+            token_symbol,
+            decimals,
+            max_supply: max_supply.as_int(),
+            total_issuance: total_issuance.as_int(),
+        })
+    }
+
+    /// Returns the token symbol and decimals recorded for the specified faucet, or `None` if the
+    /// faucet is unknown to this client.
+    pub fn get_faucet_details(
+        &self,
+        faucet_id: AccountId,
+    ) -> Result<Option<FaucetDetails>, ClientError> {
+        self.store
+            .get_faucet_details(faucet_id)
+            .map_err(|err| err.into())
+    }
+
+    /// Records the token symbol and decimals of a faucet that is not managed by this client, so
+    /// that amounts of its assets can be presented in whole tokens.
+    pub fn insert_faucet_details(
+        &mut self,
+        faucet_id: AccountId,
+        faucet_details: &FaucetDetails,
+    ) -> Result<(), ClientError> {
+        self.store
+            .insert_faucet_details(faucet_id, faucet_details)
+            .map_err(|err| err.into())
+    }
+
+    /// Returns the recorded details of the faucets of the fungible assets carried by `note`, to be
+    /// shared along with the note so that its recipient can present its amounts in whole tokens.
+    pub fn get_note_faucet_details(
+        &self,
+        note: &Note,
+    ) -> Result<Vec<(AccountId, FaucetDetails)>, ClientError> {
+        let mut faucet_details = BTreeMap::new();
+        for asset in note.assets().iter() {
+            if let Asset::Fungible(asset) = asset {
+                if let Some(details) = self.store.get_faucet_details(asset.faucet_id())? {
+                    faucet_details.insert(asset.faucet_id(), details);
+                }
+            }
+        }
+
+        Ok(faucet_details.into_iter().collect())
+    }
+
+    /// Records the faucet details shared along with an imported note, for the faucets whose
+    /// details are not known to this client yet. Details already recorded, such as those of the
+    /// faucets managed by this client, are kept.
+    ///
+    /// Returns the number of faucets whose details were recorded.
+    pub fn import_faucet_details(
+        &mut self,
+        faucet_details: &[(AccountId, FaucetDetails)],
+    ) -> Result<usize, ClientError> {
+        let mut num_recorded = 0;
+        for (faucet_id, details) in faucet_details {
+            if faucet_id.account_type() != AccountType::FungibleFaucet
+                || self.store.get_faucet_details(*faucet_id)?.is_some()
+            {
+                continue;
+            }
+            self.store.insert_faucet_details(*faucet_id, details)?;
+            num_recorded += 1;
+        }
+
+        Ok(num_recorded)
+    }
+
     /// Returns account code data from a root.
     pub fn get_account_code(
         &self,
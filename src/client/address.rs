@@ -0,0 +1,157 @@
+use core::{fmt, str::FromStr};
+
+use bech32::{FromBase32, ToBase32, Variant};
+use objects::accounts::AccountId;
+
+use crate::errors::AddressError;
+
+/// Human-readable part every address starts with
+pub const ADDRESS_HRP: &str = "mdn";
+
+// ADDRESS
+// ================================================================================================
+
+/// A human-readable encoding of an account ID, optionally along with the note tag the account's
+/// owner listens to, for sharing with whoever wants to send assets to the account.
+///
+/// Addresses are encoded with [bech32m](https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki)
+/// under the `mdn` prefix, e.g. `mdn1z6qc04efkvdgg32a57w`. Their checksum catches typos and
+/// swapped characters, and they only use characters that are not easily confused with each
+/// other, which makes them safe to copy by hand or to share as a QR code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Address {
+    account_id: AccountId,
+    note_tag: Option<u64>,
+}
+
+impl Address {
+    /// Returns the address of `account_id`
+    pub const fn new(account_id: AccountId) -> Self {
+        Self {
+            account_id,
+            note_tag: None,
+        }
+    }
+
+    /// Returns the address with `note_tag` encoded along with the account ID
+    pub const fn with_note_tag(mut self, note_tag: u64) -> Self {
+        self.note_tag = Some(note_tag);
+        self
+    }
+
+    pub const fn account_id(&self) -> AccountId {
+        self.account_id
+    }
+
+    pub const fn note_tag(&self) -> Option<u64> {
+        self.note_tag
+    }
+
+    /// Returns whether `value` has the prefix of an address, in which case it should be parsed as
+    /// one rather than as some other kind of account reference
+    pub fn has_address_prefix(value: &str) -> bool {
+        value
+            .to_ascii_lowercase()
+            .starts_with(&format!("{ADDRESS_HRP}1"))
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut data = u64::from(self.account_id).to_be_bytes().to_vec();
+        if let Some(note_tag) = self.note_tag {
+            data.extend_from_slice(&note_tag.to_be_bytes());
+        }
+
+        let address = bech32::encode(ADDRESS_HRP, data.to_base32(), Variant::Bech32m)
+            .expect("addresses are well below the length limit of bech32 strings");
+        f.write_str(&address)
+    }
+}
+
+impl FromStr for Address {
+    type Err = AddressError;
+
+    /// Parses an address, which is case-insensitive as long as it is not mixed-case
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        let (hrp, data, variant) = bech32::decode(address)
+            .map_err(|err| AddressError::InvalidEncoding(address.to_string(), err.to_string()))?;
+        if hrp != ADDRESS_HRP {
+            return Err(AddressError::UnknownPrefix(hrp));
+        }
+        if variant != Variant::Bech32m {
+            return Err(AddressError::InvalidEncoding(
+                address.to_string(),
+                "expected a bech32m checksum".to_string(),
+            ));
+        }
+
+        let data = Vec::<u8>::from_base32(&data)
+            .map_err(|err| AddressError::InvalidEncoding(address.to_string(), err.to_string()))?;
+        let (account_id, note_tag) = match data.len() {
+            8 => (&data[..], None),
+            16 => (&data[..8], Some(&data[8..])),
+            length => return Err(AddressError::InvalidLength(length)),
+        };
+
+        let account_id = u64::from_be_bytes(account_id.try_into().expect("slice has 8 bytes"));
+        let account_id = AccountId::try_from(account_id)
+            .map_err(|err| AddressError::InvalidAccountId(err.to_string()))?;
+        let address = Self::new(account_id);
+
+        Ok(match note_tag {
+            Some(note_tag) => address.with_note_tag(u64::from_be_bytes(
+                note_tag.try_into().expect("slice has 8 bytes"),
+            )),
+            None => address,
+        })
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use objects::accounts::AccountId;
+
+    use super::Address;
+    use crate::errors::AddressError;
+
+    #[test]
+    fn address_round_trip() {
+        let account_id = AccountId::from_hex("0x168187d729b31a84").unwrap();
+
+        let address = Address::new(account_id);
+        let encoded = address.to_string();
+        assert_eq!(encoded, "mdn1z6qc04efkvdgg32a57w");
+        assert!(Address::has_address_prefix(&encoded));
+        assert_eq!(encoded.parse::<Address>().unwrap(), address);
+        assert_eq!(encoded.to_uppercase().parse::<Address>().unwrap(), address);
+
+        let address = address.with_note_tag(0xabcd_0000_0000_0000);
+        let decoded: Address = address.to_string().parse().unwrap();
+        assert_eq!(decoded.account_id(), account_id);
+        assert_eq!(decoded.note_tag(), Some(0xabcd_0000_0000_0000));
+    }
+
+    #[test]
+    fn address_typos_are_detected() {
+        let account_id = AccountId::from_hex("0x168187d729b31a84").unwrap();
+        let encoded = Address::new(account_id).to_string();
+
+        // change the last character of the checksum
+        let last = encoded.chars().last().unwrap();
+        let typo = format!(
+            "{}{}",
+            &encoded[..encoded.len() - 1],
+            if last == 'q' { 'p' } else { 'q' }
+        );
+        assert!(matches!(
+            typo.parse::<Address>(),
+            Err(AddressError::InvalidEncoding(..))
+        ));
+
+        assert!(!Address::has_address_prefix("0x168187d729b31a84"));
+    }
+}
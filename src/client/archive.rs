@@ -0,0 +1,406 @@
+use std::collections::BTreeSet;
+
+use crypto::{
+    utils::{
+        collections::BTreeMap, ByteReader, ByteWriter, Deserializable, DeserializationError,
+        Serializable,
+    },
+    Felt, Word,
+};
+use objects::{
+    accounts::{Account, AccountId},
+    assembly::{AstSerdeOptions, ProgramAst},
+    transaction::{OutputNote, OutputNotes, TransactionScript},
+    Digest,
+};
+
+use super::{
+    transactions::{TransactionRecord, TransactionStatus},
+    Client,
+};
+use crate::{
+    config::ClientConfig,
+    errors::ClientError,
+    store::{
+        accounts::AuthInfo,
+        notes::{InputNoteFilter, InputNoteRecord},
+        transactions::TransactionFilter,
+    },
+};
+
+/// Bytes every wallet archive starts with
+const ARCHIVE_MAGIC: [u8; 4] = *b"MWAR";
+
+/// Version of the wallet archive format written by this client. Archives of earlier versions can
+/// still be read.
+pub const ARCHIVE_VERSION: u8 = 1;
+
+// WALLET ARCHIVE
+// ================================================================================================
+
+/// A portable copy of the state tracked by a client, independent of the store it was exported
+/// from, built by [Client::export_archive] and restored by [Client::import_archive].
+///
+/// The archive holds the latest state of each account, optionally along with its keys, the input
+/// notes, the transactions that were submitted and the client's configuration. The chain data,
+/// account histories and transactions that were not submitted yet are left out, as the client
+/// rebuilds the former when syncing and cannot resume the latter without the store they were
+/// executed against.
+pub struct WalletArchive {
+    /// Configuration of the exported client, without any store passphrase
+    pub config: Option<ClientConfig>,
+    pub accounts: Vec<ArchivedAccount>,
+    pub input_notes: Vec<InputNoteRecord>,
+    pub transactions: Vec<TransactionRecord>,
+}
+
+impl WalletArchive {
+    /// Returns the archive with `config` included in it. The store passphrases it holds are
+    /// removed, so that the archive does not disclose them.
+    pub fn with_config(mut self, mut config: ClientConfig) -> Self {
+        config.store.passphrase = None;
+        for store in config.wallets.stores.values_mut() {
+            store.passphrase = None;
+        }
+
+        self.config = Some(config);
+        self
+    }
+}
+
+/// An account stored in a [WalletArchive]
+#[derive(Debug)]
+pub struct ArchivedAccount {
+    pub account: Account,
+    pub account_seed: Word,
+    /// Keys of the account, unless the archive was exported without them
+    pub auth_info: Option<AuthInfo>,
+}
+
+/// Outcome of [Client::import_archive]. Records the client already tracked are left as they are
+/// and not counted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArchiveImportSummary {
+    pub imported_accounts: usize,
+    /// Accounts that were not imported because the archive does not include their keys
+    pub accounts_without_keys: Vec<AccountId>,
+    pub imported_notes: usize,
+    pub imported_transactions: usize,
+}
+
+impl Client {
+    /// Returns a [WalletArchive] of the state tracked by the client, including the keys of its
+    /// accounts if `include_keys` is set.
+    ///
+    /// The archive does not include the client's configuration, which can be added to it with
+    /// [WalletArchive::with_config].
+    pub fn export_archive(&self, include_keys: bool) -> Result<WalletArchive, ClientError> {
+        let accounts = self
+            .store
+            .get_account_ids()?
+            .into_iter()
+            .map(|account_id| {
+                let (account, account_seed) = self.store.get_account_by_id(account_id)?;
+                let auth_info = if include_keys {
+                    Some(self.get_account_auth(account_id)?)
+                } else {
+                    None
+                };
+
+                Ok(ArchivedAccount {
+                    account,
+                    account_seed,
+                    auth_info,
+                })
+            })
+            .collect::<Result<Vec<_>, ClientError>>()?;
+
+        let transactions = self
+            .store
+            .get_transactions(TransactionFilter::All)?
+            .into_iter()
+            .filter(|transaction| {
+                matches!(
+                    transaction.transaction_status,
+                    TransactionStatus::Submitted | TransactionStatus::Committed(_)
+                )
+            })
+            .collect();
+
+        Ok(WalletArchive {
+            config: None,
+            accounts,
+            input_notes: self.get_input_notes(InputNoteFilter::All)?,
+            transactions,
+        })
+    }
+
+    /// Imports the accounts, input notes and transactions of `archive` the client does not track
+    /// yet. Accounts whose keys are not part of the archive are skipped.
+    ///
+    /// The status of the imported notes is not archived, so notes that were consumed are only
+    /// marked as such by the next sync.
+    pub fn import_archive(
+        &mut self,
+        archive: WalletArchive,
+    ) -> Result<ArchiveImportSummary, ClientError> {
+        let mut summary = ArchiveImportSummary::default();
+
+        let known_accounts: BTreeSet<AccountId> =
+            self.store.get_account_ids()?.into_iter().collect();
+        for archived_account in archive.accounts {
+            let account_id = archived_account.account.id();
+            if known_accounts.contains(&account_id) {
+                continue;
+            }
+
+            match archived_account.auth_info {
+                Some(auth_info) => {
+                    self.insert_account(
+                        &archived_account.account,
+                        archived_account.account_seed,
+                        &auth_info,
+                    )?;
+                    self.register_account_tags(account_id)?;
+                    summary.imported_accounts += 1;
+                }
+                None => summary.accounts_without_keys.push(account_id),
+            }
+        }
+
+        let known_notes: BTreeSet<_> = self
+            .get_input_notes(InputNoteFilter::All)?
+            .iter()
+            .map(|note| note.note_id())
+            .collect();
+        for note in archive.input_notes {
+            if !known_notes.contains(&note.note_id()) {
+                self.import_input_note(note)?;
+                summary.imported_notes += 1;
+            }
+        }
+
+        let known_transactions: BTreeSet<Digest> = self
+            .store
+            .get_transactions(TransactionFilter::All)?
+            .iter()
+            .map(|transaction| transaction.id)
+            .collect();
+        for transaction in archive.transactions {
+            if !known_transactions.contains(&transaction.id) {
+                self.store.insert_transaction_record(&transaction)?;
+                summary.imported_transactions += 1;
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl Serializable for WalletArchive {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_bytes(&ARCHIVE_MAGIC);
+        target.write_u8(ARCHIVE_VERSION);
+
+        let config = self.config.as_ref().map(|config| {
+            serde_json::to_vec(config).expect("client configuration should serialize to JSON")
+        });
+        write_optional_bytes(target, config.as_deref());
+
+        target.write_u64(self.accounts.len() as u64);
+        for account in self.accounts.iter() {
+            account.account.write_into(target);
+            Digest::from(account.account_seed).write_into(target);
+            write_optional_bytes(
+                target,
+                account
+                    .auth_info
+                    .as_ref()
+                    .map(|auth| auth.to_bytes())
+                    .as_deref(),
+            );
+        }
+
+        target.write_u64(self.input_notes.len() as u64);
+        target.write_many(&self.input_notes);
+
+        target.write_u64(self.transactions.len() as u64);
+        for transaction in self.transactions.iter() {
+            write_transaction_record(target, transaction);
+        }
+    }
+}
+
+impl Deserializable for WalletArchive {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        if source.read_array::<4>()? != ARCHIVE_MAGIC {
+            return Err(DeserializationError::InvalidValue(
+                "not a wallet archive".to_string(),
+            ));
+        }
+        let version = source.read_u8()?;
+        if version > ARCHIVE_VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "wallet archive version {version} is not supported, the latest supported version is {ARCHIVE_VERSION}"
+            )));
+        }
+
+        let config = read_optional_bytes(source)?
+            .map(|config| serde_json::from_slice(&config))
+            .transpose()
+            .map_err(|err| DeserializationError::InvalidValue(err.to_string()))?;
+
+        let num_accounts = source.read_u64()? as usize;
+        let mut accounts = Vec::with_capacity(num_accounts);
+        for _ in 0..num_accounts {
+            let account = Account::read_from(source)?;
+            let account_seed = Digest::read_from(source)?.into();
+            let auth_info = read_optional_bytes(source)?
+                .map(|auth_info| AuthInfo::read_from_bytes(&auth_info))
+                .transpose()?;
+            accounts.push(ArchivedAccount {
+                account,
+                account_seed,
+                auth_info,
+            });
+        }
+
+        let num_notes = source.read_u64()? as usize;
+        let input_notes = source.read_many::<InputNoteRecord>(num_notes)?;
+
+        let num_transactions = source.read_u64()? as usize;
+        let transactions = (0..num_transactions)
+            .map(|_| read_transaction_record(source))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            config,
+            accounts,
+            input_notes,
+            transactions,
+        })
+    }
+}
+
+fn write_optional_bytes<W: ByteWriter>(target: &mut W, bytes: Option<&[u8]>) {
+    match bytes {
+        Some(bytes) => {
+            target.write_u8(1);
+            target.write_u64(bytes.len() as u64);
+            target.write_bytes(bytes);
+        }
+        None => target.write_u8(0),
+    }
+}
+
+fn read_optional_bytes<R: ByteReader>(
+    source: &mut R,
+) -> Result<Option<Vec<u8>>, DeserializationError> {
+    match source.read_u8()? {
+        0 => Ok(None),
+        1 => {
+            let len = source.read_u64()? as usize;
+            Ok(Some(source.read_vec(len)?))
+        }
+        flag => Err(DeserializationError::InvalidValue(format!(
+            "invalid optional value flag {flag}"
+        ))),
+    }
+}
+
+fn write_transaction_record<W: ByteWriter>(target: &mut W, transaction: &TransactionRecord) {
+    transaction.id.write_into(target);
+    transaction.account_id.write_into(target);
+    transaction.init_account_state.write_into(target);
+    transaction.final_account_state.write_into(target);
+    target.write_u64(transaction.input_note_nullifiers.len() as u64);
+    target.write_many(&transaction.input_note_nullifiers);
+    transaction.output_notes.write_into(target);
+    target.write_u32(transaction.block_num);
+    // only submitted transactions are archived
+    match transaction.transaction_status {
+        TransactionStatus::Committed(block_num) => {
+            target.write_u8(1);
+            target.write_u32(block_num);
+        }
+        _ => target.write_u8(0),
+    }
+
+    let tx_script = transaction.transaction_script.as_ref();
+    let script_code = tx_script.map(|tx_script| {
+        tx_script.code().to_bytes(AstSerdeOptions {
+            serialize_imports: true,
+        })
+    });
+    write_optional_bytes(target, script_code.as_deref());
+    if let Some(tx_script) = tx_script {
+        tx_script.hash().write_into(target);
+        target.write_u64(tx_script.inputs().len() as u64);
+        for (key, values) in tx_script.inputs() {
+            key.write_into(target);
+            target.write_u64(values.len() as u64);
+            target.write_many(values);
+        }
+    }
+}
+
+fn read_transaction_record<R: ByteReader>(
+    source: &mut R,
+) -> Result<TransactionRecord, DeserializationError> {
+    let id = Digest::read_from(source)?;
+    let account_id = AccountId::read_from(source)?;
+    let init_account_state = Digest::read_from(source)?;
+    let final_account_state = Digest::read_from(source)?;
+    let num_nullifiers = source.read_u64()? as usize;
+    let input_note_nullifiers = source.read_many::<Digest>(num_nullifiers)?;
+    let output_notes = OutputNotes::<OutputNote>::read_from(source)?;
+    let block_num = source.read_u32()?;
+    let transaction_status = match source.read_u8()? {
+        0 => TransactionStatus::Submitted,
+        1 => TransactionStatus::Committed(source.read_u32()?),
+        status => {
+            return Err(DeserializationError::InvalidValue(format!(
+                "invalid transaction status {status}"
+            )))
+        }
+    };
+
+    let transaction_script = match read_optional_bytes(source)? {
+        Some(script_code) => {
+            let code = ProgramAst::from_bytes(&script_code)
+                .map_err(|err| DeserializationError::InvalidValue(err.to_string()))?;
+            let hash = Digest::read_from(source)?;
+            let num_inputs = source.read_u64()? as usize;
+            let mut inputs = BTreeMap::new();
+            for _ in 0..num_inputs {
+                let key = Digest::read_from(source)?;
+                let num_values = source.read_u64()? as usize;
+                inputs.insert(key, source.read_many::<Felt>(num_values)?);
+            }
+
+            let tx_script = TransactionScript::from_parts(
+                code,
+                hash,
+                inputs.into_iter().map(|(key, values)| (key.into(), values)),
+            )
+            .map_err(|err| DeserializationError::InvalidValue(err.to_string()))?;
+            Some(tx_script)
+        }
+        None => None,
+    };
+
+    Ok(TransactionRecord::new(
+        id,
+        account_id,
+        init_account_state,
+        final_account_state,
+        input_note_nullifiers,
+        output_notes,
+        transaction_script,
+        block_num,
+        transaction_status,
+    ))
+}
@@ -0,0 +1,419 @@
+use crypto::{merkle::SmtProof, Felt, StarkField, Word};
+use objects::{
+    accounts::{hash_account, AccountId},
+    assets::Asset,
+    BlockHeader, Digest,
+};
+use serde_json::{json, Value};
+
+use super::Client;
+use crate::{errors::ClientError, store::accounts::AccountProof};
+
+// ASSET PROOF
+// ================================================================================================
+
+/// Proof that an account holds an asset, for third parties such as auditors: the path from the
+/// asset's leaf to the root of the account's vault, the other commitments the account's hash is
+/// made of and, for accounts whose state was verified, the proof of that state at a block.
+///
+/// Anyone can make up an account state holding any asset, so only a proof leading to the account
+/// root of a block, as checked by [AssetProof::verify], shows that the account holds the asset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetProof {
+    pub account_id: AccountId,
+    /// The asset as held in the vault. For fungible assets, it is the account's whole balance of
+    /// the faucet's token
+    pub asset: Asset,
+    /// Opening of the asset's leaf in the vault's sparse Merkle tree
+    pub vault_proof: SmtProof,
+    pub vault_root: Digest,
+    pub nonce: Felt,
+    pub storage_root: Digest,
+    pub code_root: Digest,
+    /// Proof of the account's state against the account root of a block, if the account's state
+    /// was verified by syncing or by [Client::verify_account]
+    pub account_proof: Option<AccountProof>,
+}
+
+impl AssetProof {
+    /// Returns the hash of the account's state the proof was made for
+    pub fn account_hash(&self) -> Digest {
+        hash_account(
+            self.account_id,
+            self.nonce,
+            self.vault_root,
+            self.storage_root,
+            self.code_root,
+        )
+    }
+
+    /// Returns the number of the block the account's state is proven at, if any
+    pub fn block_num(&self) -> Option<u32> {
+        self.account_proof
+            .as_ref()
+            .map(|account_proof| account_proof.block_num)
+    }
+
+    /// Checks that the asset's leaf leads to the vault root, and that the account proof is for
+    /// the account's state the vault belongs to and leads to the account root of `block_header`,
+    /// the header of the block the account's state is proven at.
+    ///
+    /// # Errors
+    /// Returns an error if the account's state is not proven at any block, if `block_header` is
+    /// not the header of that block or if any of the paths does not lead to the expected root.
+    pub fn verify(&self, block_header: &BlockHeader) -> Result<(), ClientError> {
+        let block_num = self.block_num().ok_or_else(|| {
+            ClientError::InvalidAssetProof(
+                self.account_id,
+                "the account's state is not proven at any block".to_string(),
+            )
+        })?;
+        if block_header.block_num() != block_num {
+            return Err(ClientError::InvalidAssetProof(
+                self.account_id,
+                format!(
+                    "the account's state is proven at block {block_num}, not at block {}",
+                    block_header.block_num()
+                ),
+            ));
+        }
+
+        match self.compute_account_root()? {
+            Some(account_root) if account_root == block_header.account_root() => Ok(()),
+            _ => Err(ClientError::InvalidAssetProof(
+                self.account_id,
+                format!(
+                    "the account's path does not lead to the account root of block {block_num}"
+                ),
+            )),
+        }
+    }
+
+    /// Checks that the asset's leaf leads to the vault root and that the account proof, if any,
+    /// is for the account's state the vault belongs to. Returns the account root the account
+    /// proof leads to, which has to match the one of its block's header.
+    fn compute_account_root(&self) -> Result<Option<Digest>, ClientError> {
+        let vault_key: Digest = self.asset.vault_key().into();
+        if !self.vault_proof.verify_membership(
+            &vault_key,
+            &Word::from(self.asset),
+            &self.vault_root,
+        ) {
+            return Err(ClientError::InvalidAssetProof(
+                self.account_id,
+                "the asset's path does not lead to the vault root".to_string(),
+            ));
+        }
+
+        let account_proof = match &self.account_proof {
+            Some(account_proof) => account_proof,
+            None => return Ok(None),
+        };
+        if account_proof.account_hash != self.account_hash() {
+            return Err(ClientError::InvalidAssetProof(
+                self.account_id,
+                format!(
+                    "the account proof of block {} is for another state of the account",
+                    account_proof.block_num
+                ),
+            ));
+        }
+
+        account_proof
+            .merkle_path
+            .compute_root(u64::from(self.account_id), account_proof.account_hash)
+            .map(Some)
+            .map_err(|err| ClientError::InvalidAssetProof(self.account_id, err.to_string()))
+    }
+
+    /// Returns the proof as a JSON object, with digests and words as hex strings, so that it can
+    /// be checked without the client:
+    ///
+    /// 1. Hashing the entries of `vault_leaf` gives the leaf at `vault_leaf_index`, from which
+    ///    `vault_path` leads to `vault_root`.
+    /// 2. Hashing `account_id`, `nonce`, `vault_root`, `storage_root` and `code_root` gives
+    ///    `account_hash`.
+    /// 3. `account_path` leads from `account_hash`, at the index given by `account_id`, to
+    ///    `account_root`, the account root of block `block_num`. Without this step, which is only
+    ///    possible if `block_num` is set, the proof shows nothing.
+    pub fn to_json(&self) -> Result<Value, ClientError> {
+        let asset = match self.asset {
+            Asset::Fungible(asset) => json!({
+                "faucet_id": asset.faucet_id().to_hex(),
+                "amount": asset.amount(),
+            }),
+            Asset::NonFungible(asset) => json!({
+                "faucet_id": asset.faucet_id().to_hex(),
+                "word": word_to_hex(Word::from(asset)),
+            }),
+        };
+        let vault_leaf: Vec<Value> = self
+            .vault_proof
+            .leaf()
+            .entries()
+            .into_iter()
+            .map(|(key, value)| json!([key.to_hex(), word_to_hex(*value)]))
+            .collect();
+        let vault_path: Vec<String> = self.vault_proof.path().iter().map(Digest::to_hex).collect();
+
+        let account_hash = self.account_hash();
+        let account_root = self.compute_account_root()?;
+        let account_path = self.account_proof.as_ref().map(|account_proof| {
+            account_proof
+                .merkle_path
+                .iter()
+                .map(Digest::to_hex)
+                .collect::<Vec<_>>()
+        });
+
+        Ok(json!({
+            "account_id": self.account_id.to_hex(),
+            "asset": asset,
+            "vault_key": Digest::from(self.asset.vault_key()).to_hex(),
+            "vault_leaf_index": self.vault_proof.leaf().index().value(),
+            "vault_leaf": vault_leaf,
+            "vault_path": vault_path,
+            "vault_root": self.vault_root.to_hex(),
+            "nonce": self.nonce.as_int(),
+            "storage_root": self.storage_root.to_hex(),
+            "code_root": self.code_root.to_hex(),
+            "account_hash": account_hash.to_hex(),
+            "block_num": self.block_num(),
+            "account_path": account_path,
+            "account_root": account_root.map(|account_root| account_root.to_hex()),
+        }))
+    }
+}
+
+fn word_to_hex(word: Word) -> String {
+    Digest::from(word).to_hex()
+}
+
+impl Client {
+    // ASSET PROOFS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the proof that the account `account_id` holds `asset` in its current state. For a
+    /// fungible asset, the vault must hold at least the asset's amount of the faucet's token, and
+    /// the proof is made for the account's whole balance of it.
+    ///
+    /// The proof goes up to the account's hash, and on to the account root of a block when the
+    /// latest proof of the account's state recorded by syncing or by [Client::verify_account] is
+    /// for its current state, in which case it is verified against the stored header of that
+    /// block. Verifying the account beforehand thus proves the asset at the block the client is
+    /// synced to. Otherwise, [AssetProof::block_num] is `None` and the proof shows nothing to a
+    /// third party.
+    ///
+    /// # Errors
+    /// Returns an error if the account is not tracked by the client, if its vault does not hold
+    /// the asset or if the proof does not verify.
+    pub fn get_asset_proof(
+        &self,
+        account_id: AccountId,
+        asset: Asset,
+    ) -> Result<AssetProof, ClientError> {
+        let (account, _seed) = self.store.get_account_by_id(account_id)?;
+
+        let vault_key = asset.vault_key();
+        let held_asset = account
+            .vault()
+            .assets()
+            .find(|held_asset| held_asset.vault_key() == vault_key)
+            .ok_or_else(|| ClientError::AssetNotInVault(account_id, describe_asset(&asset)))?;
+        if let (Asset::Fungible(asset), Asset::Fungible(held_asset)) = (asset, held_asset) {
+            if held_asset.amount() < asset.amount() {
+                return Err(ClientError::AssetNotInVault(
+                    account_id,
+                    format!(
+                        "{} (its balance is {})",
+                        describe_asset(&asset.into()),
+                        held_asset.amount()
+                    ),
+                ));
+            }
+        }
+
+        let vault_proof = account.vault().asset_tree().open(&vault_key.into());
+        let account_proof = match self.store.get_account_proof(account_id) {
+            Ok(account_proof) if account_proof.account_hash == account.hash() => {
+                Some(account_proof)
+            }
+            _ => None,
+        };
+
+        let asset_proof = AssetProof {
+            account_id,
+            asset: held_asset,
+            vault_proof,
+            vault_root: account.vault().commitment(),
+            nonce: account.nonce(),
+            storage_root: account.storage().root(),
+            code_root: account.code().root(),
+            account_proof,
+        };
+        match asset_proof.block_num() {
+            Some(_) => self.verify_asset_proof(&asset_proof)?,
+            None => {
+                asset_proof.compute_account_root()?;
+            }
+        }
+
+        Ok(asset_proof)
+    }
+
+    /// Verifies an asset proof against the stored header of the block the account's state is
+    /// proven at, see [AssetProof::verify].
+    ///
+    /// # Errors
+    /// Returns an error if the account's state is not proven at any block, if the header of that
+    /// block is not stored or if the proof does not verify.
+    pub fn verify_asset_proof(&self, asset_proof: &AssetProof) -> Result<(), ClientError> {
+        let block_num = asset_proof.block_num().ok_or_else(|| {
+            ClientError::InvalidAssetProof(
+                asset_proof.account_id,
+                "the account's state is not proven at any block".to_string(),
+            )
+        })?;
+        let (block_header, _) = self.store.get_block_header_by_num(block_num)?;
+        asset_proof.verify(&block_header)
+    }
+}
+
+fn describe_asset(asset: &Asset) -> String {
+    match asset {
+        Asset::Fungible(asset) => format!(
+            "{} base units of faucet {}",
+            asset.amount(),
+            asset.faucet_id()
+        ),
+        Asset::NonFungible(asset) => {
+            format!("non-fungible asset {}", word_to_hex(Word::from(*asset)))
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crypto::merkle::{EmptySubtreeRoots, MerklePath};
+    use mock::mock::block::mock_block_header;
+    use objects::assets::{Asset, FungibleAsset};
+
+    use crate::{
+        client::{accounts::tests::create_initial_accounts_data, sync::ACCOUNT_TREE_DEPTH},
+        errors::ClientError,
+        store::{accounts::AccountProof, tests::create_test_client},
+    };
+
+    #[tokio::test]
+    async fn asset_proofs_lead_to_the_account_hash() {
+        let mut client = create_test_client();
+        let account_data = create_initial_accounts_data().remove(0);
+        let account = account_data.account.clone();
+        client.import_account(account_data).unwrap();
+
+        let held_asset = account
+            .vault()
+            .assets()
+            .find_map(|asset| match asset {
+                Asset::Fungible(asset) => Some(asset),
+                Asset::NonFungible(_) => None,
+            })
+            .expect("mock accounts hold fungible assets");
+        let asset = FungibleAsset::new(held_asset.faucet_id(), 1).unwrap();
+
+        let asset_proof = client.get_asset_proof(account.id(), asset.into()).unwrap();
+        assert_eq!(asset_proof.asset, held_asset.into());
+        assert_eq!(asset_proof.account_hash(), account.hash());
+        assert_eq!(asset_proof.compute_account_root().unwrap(), None);
+
+        let json = asset_proof.to_json().unwrap();
+        assert_eq!(json["account_hash"], account.hash().to_hex());
+        assert!(json["block_num"].is_null());
+
+        // the account's state is not proven at any block, so the proof shows nothing
+        let block_header = mock_block_header(0, None, None, &[]);
+        assert!(matches!(
+            asset_proof.verify(&block_header),
+            Err(ClientError::InvalidAssetProof(..))
+        ));
+        assert!(matches!(
+            client.verify_asset_proof(&asset_proof),
+            Err(ClientError::InvalidAssetProof(..))
+        ));
+
+        let mut tampered_proof = asset_proof;
+        tampered_proof.asset = FungibleAsset::new(held_asset.faucet_id(), held_asset.amount() + 1)
+            .unwrap()
+            .into();
+        assert!(matches!(
+            tampered_proof.compute_account_root(),
+            Err(ClientError::InvalidAssetProof(..))
+        ));
+    }
+
+    #[tokio::test]
+    async fn asset_proofs_are_checked_against_the_header_of_their_block() {
+        let mut client = create_test_client();
+        let account_data = create_initial_accounts_data().remove(0);
+        let account = account_data.account.clone();
+        client.import_account(account_data).unwrap();
+        let asset = account
+            .vault()
+            .assets()
+            .next()
+            .expect("mock accounts hold assets");
+
+        // the account's state is proven at block 3, whose account tree only holds the account, by
+        // a path of empty subtrees
+        let empty_hashes = EmptySubtreeRoots::empty_hashes(ACCOUNT_TREE_DEPTH as u8);
+        let merkle_path = MerklePath::new(empty_hashes[1..].iter().rev().copied().collect());
+        let mut asset_proof = client.get_asset_proof(account.id(), asset).unwrap();
+        asset_proof.account_proof = Some(AccountProof {
+            account_id: account.id(),
+            block_num: 3,
+            account_hash: account.hash(),
+            merkle_path,
+        });
+
+        let block_header = mock_block_header(3, None, None, &[account.clone()]);
+        asset_proof.verify(&block_header).unwrap();
+
+        // a header of another block, or committing to another account root, is rejected
+        let other_block_header = mock_block_header(4, None, None, &[account.clone()]);
+        assert!(matches!(
+            asset_proof.verify(&other_block_header),
+            Err(ClientError::InvalidAssetProof(..))
+        ));
+        let other_root_header = mock_block_header(3, None, None, &[]);
+        assert!(matches!(
+            asset_proof.verify(&other_root_header),
+            Err(ClientError::InvalidAssetProof(..))
+        ));
+    }
+
+    #[tokio::test]
+    async fn assets_missing_from_the_vault_are_not_proven() {
+        let mut client = create_test_client();
+        let account_data = create_initial_accounts_data().remove(0);
+        let account = account_data.account.clone();
+        client.import_account(account_data).unwrap();
+
+        let held_asset = account
+            .vault()
+            .assets()
+            .find_map(|asset| match asset {
+                Asset::Fungible(asset) => Some(asset),
+                Asset::NonFungible(_) => None,
+            })
+            .expect("mock accounts hold fungible assets");
+        let asset = FungibleAsset::new(held_asset.faucet_id(), held_asset.amount() + 1).unwrap();
+
+        assert!(matches!(
+            client.get_asset_proof(account.id(), asset.into()),
+            Err(ClientError::AssetNotInVault(..))
+        ));
+    }
+}
@@ -0,0 +1,107 @@
+use core::fmt;
+
+use crate::errors::AssetAmountError;
+
+// ASSET AMOUNT
+// ================================================================================================
+
+/// An amount of fungible tokens, expressed in the base units of a faucet with the given number of
+/// decimals.
+///
+/// Converts between base units, which is how amounts are represented in assets, and whole tokens,
+/// which is how they are entered and shown to users. For instance, with 6 decimals `1.5` tokens
+/// are `1500000` base units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssetAmount {
+    base_units: u64,
+    decimals: u8,
+}
+
+impl AssetAmount {
+    /// Returns a new [AssetAmount] of `base_units` for a faucet with `decimals` decimals.
+    pub const fn new(base_units: u64, decimals: u8) -> Self {
+        Self {
+            base_units,
+            decimals,
+        }
+    }
+
+    /// Parses an amount of whole tokens such as `1.5` for a faucet with `decimals` decimals.
+    ///
+    /// # Errors
+    /// Returns an error if the amount is not a decimal number, if it has more fractional digits
+    /// than the faucet's decimals or if it does not fit in a `u64` once converted to base units.
+    pub fn parse(amount: &str, decimals: u8) -> Result<Self, AssetAmountError> {
+        let (integer, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+
+        let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if integer.is_empty() || !is_digits(integer) || !is_digits(fraction) {
+            return Err(AssetAmountError::InvalidFormat(amount.to_string()));
+        }
+        if fraction.len() > decimals as usize {
+            return Err(AssetAmountError::TooManyDecimals(
+                amount.to_string(),
+                decimals,
+            ));
+        }
+
+        let base_units = format!("{integer}{fraction:0<width$}", width = decimals as usize)
+            .parse()
+            .map_err(|_| AssetAmountError::Overflow(amount.to_string()))?;
+
+        Ok(Self::new(base_units, decimals))
+    }
+
+    /// Returns the amount in base units
+    pub const fn base_units(&self) -> u64 {
+        self.base_units
+    }
+
+    /// Returns the number of decimals of the faucet the amount belongs to
+    pub const fn decimals(&self) -> u8 {
+        self.decimals
+    }
+}
+
+impl fmt::Display for AssetAmount {
+    /// Formats the amount in whole tokens, omitting trailing zeros in the fractional part
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decimals = self.decimals as usize;
+        let digits = format!("{:0>width$}", self.base_units, width = decimals + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - decimals);
+
+        match fraction.trim_end_matches('0') {
+            "" => write!(f, "{integer}"),
+            fraction => write!(f, "{integer}.{fraction}"),
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::AssetAmount;
+
+    #[test]
+    fn asset_amount_round_trip() {
+        let amount = AssetAmount::parse("1.5", 6).unwrap();
+        assert_eq!(amount.base_units(), 1_500_000);
+        assert_eq!(amount.to_string(), "1.5");
+
+        assert_eq!(AssetAmount::parse("42", 2).unwrap().base_units(), 4200);
+        assert_eq!(AssetAmount::new(4200, 2).to_string(), "42");
+        assert_eq!(AssetAmount::new(5, 3).to_string(), "0.005");
+        assert_eq!(AssetAmount::new(1000, 0).to_string(), "1000");
+    }
+
+    #[test]
+    fn asset_amount_parse_errors() {
+        assert!(AssetAmount::parse("1.234", 2).is_err());
+        assert!(AssetAmount::parse("1.2.3", 6).is_err());
+        assert!(AssetAmount::parse(".5", 6).is_err());
+        assert!(AssetAmount::parse("-1", 6).is_err());
+        assert!(AssetAmount::parse("18446744073709551616", 0).is_err());
+    }
+}
@@ -0,0 +1,233 @@
+use std::time::Duration;
+
+use miden_tx::{ProvingOptions, TransactionExecutor};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+#[cfg(not(any(test, feature = "mock")))]
+use tonic::transport::Channel;
+
+#[cfg(not(any(test, feature = "mock")))]
+use super::rpc_client::RpcClient;
+use super::{
+    accounts::KeyStore,
+    prover::TransactionProverMode,
+    transactions::{FeePolicy, NoFees},
+    Client, ClientFeePolicy, ClientKeyStore, ClientRng,
+};
+#[cfg(not(any(test, feature = "mock")))]
+use crate::store::data_store::SqliteDataStore;
+use crate::{
+    config::ClientConfig,
+    errors::ClientError,
+    store::{data_store::AccountStateOverrides, Store},
+};
+#[cfg(any(test, feature = "mock"))]
+use crate::{mock::MockRpcApi, store::mock_executor_data_store::MockDataStore};
+
+// CLIENT BUILDER
+// ================================================================================================
+
+/// Builds a [Client], letting library users provide their own instances of the components the
+/// client is made of instead of having them created from a [ClientConfig].
+///
+/// Every component has a default:
+/// - The configuration is [ClientConfig::default], which connects to a node on `localhost` and
+///   keeps the store in `store.sqlite3` within the current directory.
+/// - The stores are opened from the store section of the configuration.
+/// - The connection to the node is established from the configured endpoint, through the
+///   configured proxy if any, when first needed.
+/// - Transactions are proven in the configured [TransactionProverMode], with the default
+///   [ProvingOptions].
+/// - Account seeds and note serial numbers are drawn from a [StdRng] seeded from the operating
+///   system's entropy source.
+/// - Transactions are charged no fee, see [NoFees].
+/// - The keys of the accounts are read from the store they are kept in, see [KeyStore].
+///
+/// ```ignore
+/// let client = ClientBuilder::new()
+///     .config(config)
+///     .rng(StdRng::seed_from_u64(0))
+///     .build()?;
+/// ```
+pub struct ClientBuilder {
+    config: ClientConfig,
+    stores: Option<(Store, Store)>,
+    #[cfg(not(any(test, feature = "mock")))]
+    rpc_channel: Option<Channel>,
+    #[cfg(not(any(test, feature = "mock")))]
+    rpc_api: Option<RpcClient>,
+    #[cfg(any(test, feature = "mock"))]
+    rpc_api: Option<MockRpcApi>,
+    proving_options: ProvingOptions,
+    prover_mode: Option<TransactionProverMode>,
+    rng: Option<ClientRng>,
+    fee_policy: Option<ClientFeePolicy>,
+    keystore: Option<ClientKeyStore>,
+}
+
+impl ClientBuilder {
+    /// Returns a builder with every component set to its default
+    pub fn new() -> Self {
+        Self {
+            config: ClientConfig::default(),
+            stores: None,
+            #[cfg(not(any(test, feature = "mock")))]
+            rpc_channel: None,
+            rpc_api: None,
+            proving_options: ProvingOptions::default(),
+            prover_mode: None,
+            rng: None,
+            fee_policy: None,
+            keystore: None,
+        }
+    }
+
+    /// Sets the configuration the components that are not provided explicitly are created from,
+    /// along with the client's retry policy, sync privacy settings, note selection strategy and
+    /// transaction staleness limit
+    pub fn config(mut self, config: ClientConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Uses already opened stores instead of opening the configured one. The client keeps its
+    /// state in `store`, while the transaction executor reads accounts and notes through
+    /// `executor_store`, which must be a separate connection to the same database.
+    pub fn stores(mut self, store: Store, executor_store: Store) -> Self {
+        self.stores = Some((store, executor_store));
+        self
+    }
+
+    /// Talks to the node over `channel`, which allows setting up TLS, timeouts or a proxy, instead
+    /// of connecting to the configured endpoint
+    #[cfg(not(any(test, feature = "mock")))]
+    pub fn rpc_channel(mut self, channel: Channel) -> Self {
+        self.rpc_channel = Some(channel);
+        self
+    }
+
+    /// Talks to the node through `rpc_api` instead of creating an RPC client from the configured
+    /// endpoint. Takes precedence over [ClientBuilder::rpc_channel].
+    #[cfg(not(any(test, feature = "mock")))]
+    pub fn rpc_client(mut self, rpc_api: RpcClient) -> Self {
+        self.rpc_api = Some(rpc_api);
+        self
+    }
+
+    /// Serves the node's responses from `rpc_api` instead of the default mocked chain
+    #[cfg(any(test, feature = "mock"))]
+    pub fn rpc_client(mut self, rpc_api: MockRpcApi) -> Self {
+        self.rpc_api = Some(rpc_api);
+        self
+    }
+
+    /// Sets the options transactions are proven with
+    pub fn proving_options(mut self, proving_options: ProvingOptions) -> Self {
+        self.proving_options = proving_options;
+        self
+    }
+
+    /// Sets how transactions are proven, instead of the mode set by the `transactions.prover`
+    /// option of the configuration
+    pub fn prover_mode(mut self, prover_mode: TransactionProverMode) -> Self {
+        self.prover_mode = Some(prover_mode);
+        self
+    }
+
+    /// Sets the random number generator account seeds and note serial numbers are drawn from.
+    /// A seeded generator makes them reproducible, which can be useful in tests.
+    pub fn rng(mut self, rng: impl RngCore + Send + 'static) -> Self {
+        self.rng = Some(Box::new(rng));
+        self
+    }
+
+    /// Sets the policy the fees of the transactions executed by the client are estimated with
+    pub fn fee_policy(mut self, fee_policy: impl FeePolicy + 'static) -> Self {
+        self.fee_policy = Some(Box::new(fee_policy));
+        self
+    }
+
+    /// Sets the keystore asked for the keys of an account before reading them from the store
+    pub fn keystore(mut self, keystore: impl KeyStore + 'static) -> Self {
+        self.keystore = Some(Box::new(keystore));
+        self
+    }
+
+    /// Returns a new [Client] made of the provided components, creating the missing ones.
+    ///
+    /// # Errors
+    /// Returns an error if the stores were not provided and the configured store cannot be
+    /// opened or migrated.
+    pub fn build(self) -> Result<Client, ClientError> {
+        let (store, executor_store) = match self.stores {
+            Some((store, executor_store)) => (store, Some(executor_store)),
+            None => (Store::new((&self.config).into())?, None),
+        };
+
+        let account_state_overrides = AccountStateOverrides::default();
+
+        #[cfg(not(any(test, feature = "mock")))]
+        let (rpc_api, tx_executor) = {
+            let endpoint = self.config.rpc.endpoint.to_string();
+            let rpc_api = match (self.rpc_api, self.rpc_channel) {
+                (Some(rpc_api), _) => rpc_api,
+                (None, Some(channel)) => RpcClient::with_channel(endpoint, channel),
+                (None, None) => RpcClient::new(endpoint, self.config.rpc.proxy.clone()),
+            };
+            let executor_store = match executor_store {
+                Some(executor_store) => executor_store,
+                None => Store::new((&self.config).into())?,
+            };
+
+            (
+                rpc_api,
+                TransactionExecutor::new(SqliteDataStore::with_account_states(
+                    executor_store,
+                    account_state_overrides.clone(),
+                )),
+            )
+        };
+
+        // the mock executor serves the data set up by the tests instead of reading the store
+        #[cfg(any(test, feature = "mock"))]
+        let (rpc_api, tx_executor) = (
+            self.rpc_api.unwrap_or_default(),
+            TransactionExecutor::new(MockDataStore::new()),
+        );
+
+        Ok(Client {
+            store,
+            rpc_api,
+            tx_executor,
+            account_state_overrides,
+            account_watchers: Default::default(),
+            nullifier_prefix_bits: self.config.rpc.nullifier_prefix_bits,
+            nullifier_chunk_size: self.config.rpc.nullifier_chunk_size,
+            trim_note_paths: self.config.rpc.trim_note_paths,
+            event_subscribers: Default::default(),
+            retry_policy: self.config.retry,
+            proving_options: self.proving_options,
+            prover_mode: self.prover_mode.unwrap_or(self.config.transactions.prover),
+            slow_proving_warning: match self.config.transactions.slow_proving_warning_secs {
+                0 => None,
+                secs => Some(Duration::from_secs(secs)),
+            },
+            rng: self.rng.unwrap_or_else(|| Box::new(StdRng::from_entropy())),
+            note_selection: self.config.note_selection,
+            note_interpreter: Default::default(),
+            max_transaction_staleness: self.config.transactions.max_staleness,
+            offline_mode: self.config.transactions.offline,
+            protocol_limits: self.config.transactions.limits,
+            rpc_proxy: self.config.rpc.proxy.clone(),
+            fee_policy: self.fee_policy.unwrap_or_else(|| Box::new(NoFees)),
+            keystore: self.keystore,
+            #[cfg(any(test, feature = "mock"))]
+            executor_store,
+        })
+    }
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
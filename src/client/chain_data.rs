@@ -1,7 +1,12 @@
+use crypto::merkle::{InOrderIndex, PartialMmr};
+use objects::utils::collections::BTreeSet;
+
 use super::Client;
+use crate::{
+    errors::{ClientError, StoreError},
+    store::{data_store::mmr_merkle_path_len, notes::InputNoteFilter},
+};
 
-#[cfg(test)]
-use crate::errors::ClientError;
 #[cfg(test)]
 use objects::BlockHeader;
 
@@ -26,4 +31,81 @@ impl Client {
             .get_block_headers(block_numbers)
             .map_err(ClientError::StoreError)
     }
+
+    /// Fetches the header of block `block_num` and its authentication path in the chain MMR from
+    /// the node and stores them, so that notes committed in that block can be consumed. This is
+    /// needed for notes imported along with the proof of their inclusion in a block the client
+    /// synced past without tracking it.
+    ///
+    /// Blocks the client already tracks are left untouched, as are blocks at or after the sync
+    /// height, which are tracked while syncing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the node's proof does not authenticate the block against the client's
+    /// chain MMR.
+    pub async fn authenticate_block(&mut self, block_num: u32) -> Result<(), ClientError> {
+        let sync_height = self.store.get_sync_height()?;
+        if block_num >= sync_height {
+            return Ok(());
+        }
+        match self.store.get_block_header_by_num(block_num) {
+            Ok((_, true)) => return Ok(()),
+            Ok((_, false)) | Err(StoreError::BlockHeaderNotFound(_)) => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        let (block_header, mmr_proof) = self.rpc_api.get_block_header_with_proof(block_num).await?;
+
+        // The node proves the block against its own chain MMR, which may have grown past the
+        // client's. Paths within a peak do not change as the MMR grows, so the lower part of the
+        // node's path authenticates the block against the peaks at the sync height.
+        let mut merkle_path = mmr_proof.merkle_path;
+        merkle_path.truncate(mmr_merkle_path_len(
+            block_num as usize,
+            sync_height as usize,
+        ));
+
+        let mut partial_mmr =
+            PartialMmr::from_peaks(self.store.get_chain_mmr_peaks_by_block_num(sync_height)?);
+        partial_mmr
+            .track(block_num as usize, block_header.hash(), &merkle_path)
+            .map_err(StoreError::MmrError)?;
+
+        let mut index = InOrderIndex::from_leaf_pos(block_num as usize);
+        let mut nodes = vec![(index, block_header.hash())];
+        for node in merkle_path.iter() {
+            nodes.push((index.sibling(), *node));
+            index = index.parent();
+        }
+
+        self.store
+            .insert_authenticated_block_header(block_header, &nodes)
+            .map_err(ClientError::StoreError)
+    }
+
+    /// Authenticates the blocks of the committed input notes that were included in blocks the
+    /// client does not track, see [Client::authenticate_block].
+    pub(crate) async fn authenticate_committed_note_blocks(&mut self) -> Result<(), ClientError> {
+        let tracked_blocks: BTreeSet<u32> = self
+            .store
+            .get_tracked_block_headers()?
+            .iter()
+            .map(|block_header| block_header.block_num())
+            .collect();
+
+        let untracked_blocks: BTreeSet<u32> = self
+            .store
+            .get_input_notes(InputNoteFilter::Committed)?
+            .iter()
+            .filter_map(|note| note.inclusion_proof())
+            .map(|inclusion_proof| inclusion_proof.origin().block_num)
+            .filter(|block_num| !tracked_blocks.contains(block_num))
+            .collect();
+
+        for block_num in untracked_blocks {
+            self.authenticate_block(block_num).await?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,75 @@
+use objects::{accounts::AccountId, notes::NoteId, Digest};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use super::Client;
+
+// CLIENT EVENTS
+// ================================================================================================
+
+pub(crate) type EventSubscribers = Vec<UnboundedSender<ClientEvent>>;
+
+/// A change in the lifecycle of a note, transaction or account tracked by the client, emitted to the
+/// subscribers registered through [Client::subscribe].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientEvent {
+    /// A tracked note was included in the specified block
+    NoteCommitted { note_id: NoteId, block_num: u32 },
+    /// A tracked note was found to be consumed when syncing to the specified block. `by_client`
+    /// is false when the note was consumed by a transaction the client did not execute
+    NoteConsumed {
+        note_id: NoteId,
+        block_num: u32,
+        by_client: bool,
+    },
+    /// A tracked note was consumed in the specified block by a transaction the client did not
+    /// execute, e.g. from another store holding the keys of the same account. Emitted after the
+    /// note's [ClientEvent::NoteConsumed] event
+    NoteConsumedExternally { note_id: NoteId, block_num: u32 },
+    /// A transaction executed by the client was proven and is about to be submitted to the node
+    TransactionProven { transaction_id: Digest },
+    /// A transaction executed by the client was submitted to the node
+    TransactionSubmitted { transaction_id: Digest },
+    /// A transaction executed by the client was included in the specified block
+    TransactionCommitted {
+        transaction_id: Digest,
+        block_num: u32,
+    },
+    /// The on-chain state of a tracked account, reported when syncing to the specified block,
+    /// differs from the local one. Transactions against the account are rejected until it is
+    /// imported again
+    AccountStateDesynchronized {
+        account_id: AccountId,
+        local_hash: Digest,
+        remote_hash: Digest,
+        block_num: u32,
+    },
+    /// The note with a nullifier watched through [Client::watch_nullifier] was consumed in the
+    /// specified block
+    WatchedNullifierConsumed { nullifier: Digest, block_num: u32 },
+    /// A sync update was applied up to the specified block, which is still behind the chain tip
+    /// the sync is heading to
+    SyncProgressed {
+        block_num: u32,
+        target_block_num: u32,
+    },
+}
+
+impl Client {
+    /// Returns a stream of the [ClientEvent]s emitted from now on, as the client syncs and
+    /// submits transactions. The subscription ends when the receiver is dropped.
+    pub fn subscribe(&mut self) -> UnboundedReceiver<ClientEvent> {
+        let (sender, receiver) = unbounded_channel();
+        self.event_subscribers.push(sender);
+
+        receiver
+    }
+
+    /// Sends the provided events to every subscriber, discarding the subscribers whose stream was
+    /// dropped.
+    pub(crate) fn emit_events(&mut self, events: impl IntoIterator<Item = ClientEvent>) {
+        for event in events {
+            self.event_subscribers
+                .retain(|sender| sender.send(event.clone()).is_ok());
+        }
+    }
+}
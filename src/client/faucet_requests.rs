@@ -0,0 +1,448 @@
+use std::time::Duration;
+
+use crypto::utils::Deserializable;
+use objects::{
+    accounts::AccountId,
+    assets::Asset,
+    notes::{Note, NoteId},
+    Digest,
+};
+use reqwest::{header::CONTENT_TYPE, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+
+use super::{note_interpreter::DecodedNote, notes::ExpectedNote, proxy::resolve_proxy, Client};
+use crate::{errors::ClientError, store::notes::InputNoteRecord};
+
+// FAUCET SERVICE
+// ================================================================================================
+
+/// Path of the faucet service endpoint tokens are requested from, relative to the service's URL
+const GET_TOKENS_PATH: &str = "get_tokens";
+
+/// How long to wait for the faucet service to mint the note and answer
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Body of a token request
+#[derive(Debug, Serialize)]
+struct TokenRequest {
+    account_id: String,
+    asset_amount: u64,
+}
+
+/// Note a faucet service minted, as described by services that answer with JSON instead of the
+/// note's data
+#[derive(Debug, Deserialize)]
+struct MintedNote {
+    note_id: String,
+    tag: u64,
+}
+
+/// Answer of a faucet service to a token request
+#[derive(Debug)]
+enum FaucetResponse {
+    /// The minted note, serialized as by `input-notes export`
+    Note(InputNoteRecord),
+    /// ID and tag of the minted note, whose details are not shared
+    Id { note_id: NoteId, tag: u64 },
+}
+
+/// Note minted by a faucet service in answer to [Client::request_faucet_tokens]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaucetGrant {
+    pub note_id: NoteId,
+    /// Whether the faucet service sent the note's details, which are needed to consume the note.
+    /// Otherwise only the note's arrival is reported by syncing
+    pub details_known: bool,
+}
+
+/// Returns the URL of the token endpoint of the faucet service at `faucet_url`
+fn get_tokens_url(faucet_url: &str) -> Result<Url, ClientError> {
+    let mut url = Url::parse(faucet_url).map_err(|err| {
+        ClientError::FaucetRequestRejected(format!("invalid faucet URL `{faucet_url}`: {err}"))
+    })?;
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+
+    url.join(GET_TOKENS_PATH).map_err(|err| {
+        ClientError::FaucetRequestRejected(format!("invalid faucet URL `{faucet_url}`: {err}"))
+    })
+}
+
+/// Parses the answer of a faucet service: either the minted note's data, sent as an
+/// `application/octet-stream` body, or a JSON object with the note's ID and tag
+fn parse_faucet_response(
+    content_type: Option<&str>,
+    body: &[u8],
+) -> Result<FaucetResponse, ClientError> {
+    let is_binary = content_type
+        .map(|content_type| content_type.starts_with("application/octet-stream"))
+        .unwrap_or(false);
+    if is_binary {
+        let note = InputNoteRecord::read_from_bytes(body).map_err(|err| {
+            ClientError::FaucetRequestRejected(format!("invalid note received: {err}"))
+        })?;
+        return Ok(FaucetResponse::Note(note));
+    }
+
+    let minted_note: MintedNote = serde_json::from_slice(body).map_err(|err| {
+        ClientError::FaucetRequestRejected(format!("invalid response received: {err}"))
+    })?;
+    let note_id = Digest::try_from(minted_note.note_id.as_str())
+        .map_err(|err| {
+            ClientError::FaucetRequestRejected(format!(
+                "invalid note ID `{}` received: {err}",
+                minted_note.note_id
+            ))
+        })?
+        .into();
+
+    Ok(FaucetResponse::Id {
+        note_id,
+        tag: minted_note.tag,
+    })
+}
+
+/// Asks the faucet service at `faucet_url` to mint `amount` base units of its token into a note
+/// for `account_id`. The request goes through the proxy resolved from `proxy`, the value of the
+/// `rpc.proxy` option, as the node's requests do.
+async fn request_tokens(
+    faucet_url: &str,
+    proxy: Option<&str>,
+    account_id: AccountId,
+    amount: u64,
+) -> Result<FaucetResponse, ClientError> {
+    let url = get_tokens_url(faucet_url)?;
+    let proxy = resolve_proxy(proxy, url.as_str())
+        .and_then(|proxy| proxy.map(|proxy| proxy.to_reqwest_proxy()).transpose())
+        .map_err(ClientError::FaucetRequestRejected)?;
+    let http_client = match proxy {
+        Some(proxy) => reqwest::Client::builder().proxy(proxy),
+        // the environment was already looked at when resolving the proxy
+        None => reqwest::Client::builder().no_proxy(),
+    }
+    .timeout(REQUEST_TIMEOUT)
+    .build()
+    .map_err(|err| ClientError::FaucetRequestFailed(err.to_string()))?;
+
+    let response = http_client
+        .post(url)
+        .json(&TokenRequest {
+            account_id: account_id.to_hex(),
+            asset_amount: amount,
+        })
+        .send()
+        .await
+        .map_err(|err| ClientError::FaucetRequestFailed(err.to_string()))?;
+
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .map(str::to_string);
+    let body = response
+        .bytes()
+        .await
+        .map_err(|err| ClientError::FaucetRequestFailed(err.to_string()))?;
+
+    if !status.is_success() {
+        let message = format!("{status}: {}", String::from_utf8_lossy(&body).trim());
+        // rate limits and server errors may go away, other errors are caused by the request
+        return Err(
+            if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+                ClientError::FaucetRequestFailed(message)
+            } else {
+                ClientError::FaucetRequestRejected(message)
+            },
+        );
+    }
+
+    parse_faucet_response(content_type.as_deref(), &body)
+}
+
+impl Client {
+    // FAUCET REQUESTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Requests `amount` base units of test tokens for `account_id` from the faucet service at
+    /// `faucet_url`, such as the faucet of a public testnet, then waits for the note it mints as
+    /// [Client::add_expected_note] does, so that syncing retrieves the note once it is committed.
+    ///
+    /// The service is sent a JSON `POST` request to `<faucet_url>/get_tokens`, with the account's
+    /// ID and the amount as `account_id` and `asset_amount`. It answers with the minted note's
+    /// data, as written by `input-notes export`, or with a JSON object holding the note's
+    /// `note_id` and `tag` if it does not share the note's details. The request is sent through
+    /// the proxy of the `rpc.proxy` option, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the account is not tracked by the client, the faucet service cannot
+    /// be reached or rejects the request, or its answer is invalid. Notes whose details are sent
+    /// have to be P2ID notes for `account_id` carrying `amount` base units of a single asset.
+    pub async fn request_faucet_tokens(
+        &mut self,
+        faucet_url: &str,
+        account_id: AccountId,
+        amount: u64,
+    ) -> Result<FaucetGrant, ClientError> {
+        self.get_account_stub_by_id(account_id)?;
+
+        let label = format!("faucet {faucet_url}");
+        let response =
+            request_tokens(faucet_url, self.rpc_proxy.as_deref(), account_id, amount).await?;
+        if let FaucetResponse::Note(note) = &response {
+            self.check_minted_note(note.note(), account_id, amount)?;
+        }
+
+        match response {
+            // the note may have been committed by the time the service answered
+            FaucetResponse::Note(note) if note.inclusion_proof().is_some() => {
+                let note_id = note.note_id();
+                self.import_input_note(note)?;
+                Ok(FaucetGrant {
+                    note_id,
+                    details_known: true,
+                })
+            }
+            FaucetResponse::Note(note) => {
+                let note_id = note.note_id();
+                self.add_expected_note(ExpectedNote::Details(note.note().clone()), Some(&label))?;
+                Ok(FaucetGrant {
+                    note_id,
+                    details_known: true,
+                })
+            }
+            FaucetResponse::Id { note_id, tag } => {
+                self.add_expected_note(ExpectedNote::Id { note_id, tag }, Some(&label))?;
+                Ok(FaucetGrant {
+                    note_id,
+                    details_known: false,
+                })
+            }
+        }
+    }
+
+    /// Checks that a note minted by a faucet service is the P2ID note requested: one that
+    /// `account_id` can consume, carrying `amount` base units of a single fungible asset
+    fn check_minted_note(
+        &self,
+        note: &Note,
+        account_id: AccountId,
+        amount: u64,
+    ) -> Result<(), ClientError> {
+        match self.note_interpreter.decode(note) {
+            Some(DecodedNote::P2ID { target }) if target == account_id => {}
+            _ => {
+                return Err(ClientError::FaucetRequestRejected(format!(
+                    "note {} received is not a P2ID note for account {account_id}",
+                    note.id().inner()
+                )))
+            }
+        }
+
+        match note.assets().iter().collect::<Vec<_>>()[..] {
+            [Asset::Fungible(asset)] if asset.amount() == amount => Ok(()),
+            _ => Err(ClientError::FaucetRequestRejected(format!(
+                "note {} received does not carry the {amount} requested tokens",
+                note.id().inner()
+            ))),
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crypto::{rand::RpoRandomCoin, utils::Serializable, Felt, FieldElement};
+    use miden_lib::notes::create_p2id_note;
+    use mock::{
+        constants::ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN,
+        mock::{
+            account::MockAccountType, notes::AssetPreservationStatus, transaction::mock_inputs,
+        },
+    };
+    use objects::{accounts::AccountId, assets::FungibleAsset};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        task::JoinHandle,
+    };
+
+    use super::{get_tokens_url, parse_faucet_response, FaucetResponse};
+    use crate::{
+        client::{
+            accounts::{AccountStorageMode, AccountTemplate},
+            proxy::DIRECT_CONNECTION,
+        },
+        errors::ClientError,
+        store::{notes::InputNoteRecord, tests::create_test_client},
+    };
+
+    /// Answers a single HTTP request on a local port with `note`, as a faucet service sharing the
+    /// note's details does. Returns the URL of the listener and a handle resolving to the request
+    async fn serve_once(note: Vec<u8>) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            // the request ends with its JSON body
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            while !request.ends_with(b"}") {
+                let read = stream.read(&mut buffer).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+
+            let head = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/octet-stream\r\n\
+                content-length: {}\r\nconnection: close\r\n\r\n",
+                note.len()
+            );
+            stream.write_all(head.as_bytes()).await.unwrap();
+            stream.write_all(&note).await.unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+
+        (url, handle)
+    }
+
+    /// Returns the serialized P2ID note a faucet service mints to send `amount` tokens to `target`
+    fn minted_note(target: AccountId, amount: u64) -> Vec<u8> {
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let asset = FungibleAsset::new(faucet_id, amount).unwrap();
+        let note = create_p2id_note(
+            faucet_id,
+            target,
+            vec![asset.into()],
+            RpoRandomCoin::new([Felt::ZERO; 4]),
+        )
+        .unwrap();
+        InputNoteRecord::from(note).to_bytes()
+    }
+
+    #[tokio::test]
+    async fn minted_notes_are_checked_against_the_request() {
+        let mut client = create_test_client();
+        client.rpc_proxy = Some(DIRECT_CONNECTION.to_string());
+        let (account, _) = client
+            .new_account(AccountTemplate::BasicWallet {
+                mutable_code: false,
+                storage_mode: AccountStorageMode::Local,
+            })
+            .unwrap();
+
+        let (faucet_url, request) = serve_once(minted_note(account.id(), 100)).await;
+        let grant = client
+            .request_faucet_tokens(&faucet_url, account.id(), 100)
+            .await
+            .unwrap();
+        assert!(grant.details_known);
+        let request = request.await.unwrap();
+        assert!(request.starts_with("POST /get_tokens "));
+        assert!(request.contains(&format!(
+            r#"{{"account_id":"{}","asset_amount":100}}"#,
+            account.id().to_hex()
+        )));
+
+        // notes carrying another amount or meant for another account are not waited for
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        for (target, amount) in [(account.id(), 50), (faucet_id, 100)] {
+            let (faucet_url, _) = serve_once(minted_note(target, amount)).await;
+            assert!(matches!(
+                client
+                    .request_faucet_tokens(&faucet_url, account.id(), 100)
+                    .await,
+                Err(ClientError::FaucetRequestRejected(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn faucet_requests_go_through_the_rpc_proxy() {
+        let mut client = create_test_client();
+        let (account, _) = client
+            .new_account(AccountTemplate::BasicWallet {
+                mutable_code: false,
+                storage_mode: AccountStorageMode::Local,
+            })
+            .unwrap();
+
+        // the listener plays an HTTP proxy, which is asked for the faucet's URL
+        let (proxy_url, request) = serve_once(minted_note(account.id(), 100)).await;
+        client.rpc_proxy = Some(proxy_url);
+        client
+            .request_faucet_tokens("http://faucet.invalid", account.id(), 100)
+            .await
+            .unwrap();
+        assert!(request
+            .await
+            .unwrap()
+            .starts_with("POST http://faucet.invalid/get_tokens "));
+    }
+
+    #[test]
+    fn token_endpoint_is_relative_to_the_faucet_url() {
+        assert_eq!(
+            get_tokens_url("https://faucet.example.com")
+                .unwrap()
+                .as_str(),
+            "https://faucet.example.com/get_tokens"
+        );
+        assert_eq!(
+            get_tokens_url("http://localhost:8080/testnet/")
+                .unwrap()
+                .as_str(),
+            "http://localhost:8080/testnet/get_tokens"
+        );
+        assert!(matches!(
+            get_tokens_url("faucet.example.com"),
+            Err(ClientError::FaucetRequestRejected(_))
+        ));
+    }
+
+    #[test]
+    fn faucet_responses_carry_the_note_or_its_id() {
+        let transaction_inputs = mock_inputs(
+            MockAccountType::StandardExisting,
+            AssetPreservationStatus::Preserved,
+        );
+        let note = InputNoteRecord::new(
+            transaction_inputs.input_notes().get_note(0).note().clone(),
+            None,
+        );
+
+        match parse_faucet_response(Some("application/octet-stream"), &note.to_bytes()) {
+            Ok(FaucetResponse::Note(received_note)) => {
+                assert_eq!(received_note.note_id(), note.note_id())
+            }
+            other => panic!("unexpected response {other:?}"),
+        }
+
+        let body = format!(
+            r#"{{"note_id": "{}", "tag": 42}}"#,
+            note.note_id().inner().to_hex()
+        );
+        match parse_faucet_response(Some("application/json"), body.as_bytes()) {
+            Ok(FaucetResponse::Id { note_id, tag }) => {
+                assert_eq!(note_id, note.note_id());
+                assert_eq!(tag, 42);
+            }
+            other => panic!("unexpected response {other:?}"),
+        }
+
+        assert!(matches!(
+            parse_faucet_response(Some("application/octet-stream"), b"not a note"),
+            Err(ClientError::FaucetRequestRejected(_))
+        ));
+        assert!(matches!(
+            parse_faucet_response(None, br#"{"note_id": "0x12"}"#),
+            Err(ClientError::FaucetRequestRejected(_))
+        ));
+    }
+}
@@ -1,13 +1,46 @@
 #[cfg(not(any(test, feature = "mock")))]
 use crate::store::data_store::SqliteDataStore;
-use crate::{config::ClientConfig, errors::ClientError, store::Store};
-use miden_tx::TransactionExecutor;
-pub use rpc_client::RpcApiEndpoint;
+#[cfg(not(any(test, feature = "mock")))]
+use crate::store::Store;
+use crate::{
+    config::{ClientConfig, RetryPolicy},
+    errors::ClientError,
+    store::data_store::AccountStateOverrides,
+};
+use accounts::{AccountWatchers, KeyStore};
+use events::EventSubscribers;
+use miden_tx::{ProvingOptions, TransactionExecutor};
+use note_interpreter::NoteInterpreter;
+use note_selection::NoteSelectionStrategy;
+use prover::TransactionProverMode;
+use rand::RngCore;
+#[cfg(not(any(test, feature = "mock")))]
+pub use rpc_client::RpcClient;
+pub use rpc_client::{RpcApiEndpoint, NODE_PROTOCOL_VERSION};
+use std::time::Duration;
+pub use sync::SyncProgress;
+use transactions::{FeePolicy, ProtocolLimits};
 
 pub mod accounts;
+pub mod address;
+pub mod archive;
+pub mod asset_proofs;
+pub mod assets;
+pub mod builder;
 mod chain_data;
-mod notes;
+pub mod events;
+pub mod faucet_requests;
+pub mod note_interpreter;
+pub mod note_selection;
+pub mod note_tags;
+pub mod notes;
+pub mod notifications;
+pub mod orders;
+pub mod prover;
+pub mod proxy;
+pub mod read_only;
 pub(crate) mod rpc_client;
+pub mod script_inputs;
 pub(crate) mod sync;
 pub mod transactions;
 
@@ -28,25 +61,103 @@ pub struct Client {
     store: Store,
     rpc_api: rpc_client::RpcClient,
     tx_executor: TransactionExecutor<SqliteDataStore>,
+    /// Account states the executor is pointed to instead of the latest ones, see
+    /// [Client::reexecute_transaction].
+    account_state_overrides: AccountStateOverrides,
+    /// Streams of account state changes requested through [Client::watch_account].
+    account_watchers: AccountWatchers,
+    /// Number of nullifier bits revealed to the node when syncing.
+    nullifier_prefix_bits: u8,
+    /// Maximum number of nullifier prefixes sent in a single sync request.
+    nullifier_chunk_size: usize,
+    /// Whether the first node of the note paths received when syncing or importing notes is
+    /// dropped.
+    trim_note_paths: bool,
+    /// Streams of events requested through [Client::subscribe].
+    event_subscribers: EventSubscribers,
+    /// Policy applied to operations failing with a retryable error.
+    retry_policy: RetryPolicy,
+    /// Options transactions are proven with.
+    proving_options: ProvingOptions,
+    /// Whether transactions are proven with a STARK proof or a placeholder one.
+    prover_mode: TransactionProverMode,
+    /// Time after which a warning is logged while proving a transaction, if any.
+    slow_proving_warning: Option<Duration>,
+    /// Source of the randomness of account seeds and note serial numbers.
+    rng: ClientRng,
+    /// How notes are picked to top up an account's vault for a payment.
+    note_selection: NoteSelectionStrategy,
+    /// Decoders of the inputs of notes created with known scripts.
+    note_interpreter: NoteInterpreter,
+    /// Number of blocks past which an unsubmitted transaction is stale.
+    max_transaction_staleness: u32,
+    /// Whether transactions are queued in the outbox instead of being submitted.
+    offline_mode: bool,
+    /// Limits transactions are validated against before being executed.
+    protocol_limits: ProtocolLimits,
+    /// Value of the `rpc.proxy` option, which faucet requests are sent through as well.
+    rpc_proxy: Option<String>,
+    /// Estimates the fees of the transactions executed by the client.
+    fee_policy: ClientFeePolicy,
+    /// Keys the accounts are signed with instead of the ones kept in the store, if any.
+    keystore: Option<ClientKeyStore>,
 }
 
-#[cfg(not(any(test, feature = "mock")))]
+/// Random number generator used by the [Client]
+pub type ClientRng = Box<dyn RngCore + Send>;
+
+/// Fee policy used by the [Client]
+pub type ClientFeePolicy = Box<dyn FeePolicy>;
+
+/// Keystore used by the [Client]
+pub type ClientKeyStore = Box<dyn KeyStore>;
+
 impl Client {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
 
     /// Returns a new instance of [Client] instantiated with the specified configuration options.
+    /// Use a [ClientBuilder](builder::ClientBuilder) to provide some of the client's components
+    /// instead.
     ///
     /// # Errors
     /// Returns an error if the client could not be instantiated.
     pub fn new(config: ClientConfig) -> Result<Self, ClientError> {
-        Ok(Self {
-            store: Store::new((&config).into())?,
-            rpc_api: rpc_client::RpcClient::new(config.rpc.endpoint.to_string()),
-            tx_executor: TransactionExecutor::new(SqliteDataStore::new(Store::new(
-                (&config).into(),
-            )?)),
-        })
+        builder::ClientBuilder::new().config(config).build()
+    }
+}
+
+// RETRY POLICY
+// ================================================================================================
+
+impl Client {
+    /// Returns the policy applied to syncs, transaction submissions and store writes that fail
+    /// with a retryable error.
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Replaces the policy set in the client's configuration. Library users that handle retries
+    /// themselves can disable them with [RetryPolicy::NO_RETRIES].
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+}
+
+// OFFLINE MODE
+// ================================================================================================
+
+impl Client {
+    /// Returns true if the client queues transactions instead of submitting them, as set in the
+    /// `transactions.offline` option of its configuration
+    pub fn is_offline(&self) -> bool {
+        self.offline_mode
+    }
+
+    /// Switches offline mode on or off. In offline mode, [Client::send_transaction] proves
+    /// transactions and queues them in the outbox, until [Client::flush_outbox] submits them.
+    pub fn set_offline_mode(&mut self, offline_mode: bool) {
+        self.offline_mode = offline_mode;
     }
 }
 
@@ -58,23 +169,41 @@ pub use mock::Client;
 
 #[cfg(any(test, feature = "mock"))]
 mod mock {
-    use super::{ClientConfig, ClientError, Store, TransactionExecutor};
-    use crate::{mock::MockRpcApi, store::mock_executor_data_store::MockDataStore};
+    use super::{
+        AccountStateOverrides, AccountWatchers, ClientFeePolicy, ClientKeyStore, ClientRng,
+        Duration, EventSubscribers, NoteInterpreter, NoteSelectionStrategy, ProtocolLimits,
+        ProvingOptions, RetryPolicy, TransactionExecutor, TransactionProverMode,
+    };
+    use crate::{
+        mock::MockRpcApi,
+        store::{mock_executor_data_store::MockDataStore, Store},
+    };
 
     pub struct Client {
         pub(crate) store: Store,
         pub(crate) rpc_api: MockRpcApi,
         pub(crate) tx_executor: TransactionExecutor<MockDataStore>,
-    }
-
-    #[cfg(any(test, feature = "mock"))]
-    impl Client {
-        pub fn new(config: ClientConfig) -> Result<Self, ClientError> {
-            Ok(Self {
-                store: Store::new((&config).into())?,
-                rpc_api: Default::default(),
-                tx_executor: TransactionExecutor::new(MockDataStore::new()),
-            })
-        }
+        pub(crate) account_state_overrides: AccountStateOverrides,
+        pub(crate) account_watchers: AccountWatchers,
+        pub(crate) nullifier_prefix_bits: u8,
+        pub(crate) nullifier_chunk_size: usize,
+        pub(crate) trim_note_paths: bool,
+        pub(crate) event_subscribers: EventSubscribers,
+        pub(crate) retry_policy: RetryPolicy,
+        pub(crate) proving_options: ProvingOptions,
+        pub(crate) prover_mode: TransactionProverMode,
+        pub(crate) slow_proving_warning: Option<Duration>,
+        pub(crate) rng: ClientRng,
+        pub(crate) note_selection: NoteSelectionStrategy,
+        pub(crate) note_interpreter: NoteInterpreter,
+        pub(crate) max_transaction_staleness: u32,
+        pub(crate) offline_mode: bool,
+        pub(crate) protocol_limits: ProtocolLimits,
+        pub(crate) rpc_proxy: Option<String>,
+        pub(crate) fee_policy: ClientFeePolicy,
+        pub(crate) keystore: Option<ClientKeyStore>,
+        /// Store provided to the builder for the executor, which the mock executor does not read
+        /// from but which is kept open like the real client does
+        pub(crate) executor_store: Option<Store>,
     }
 }
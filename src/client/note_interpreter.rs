@@ -0,0 +1,361 @@
+use crypto::{rand::RpoRandomCoin, Felt, FieldElement, StarkField, Word};
+use lazy_static::lazy_static;
+use miden_lib::notes::{create_p2id_note, create_p2idr_note, create_swap_note};
+use objects::{
+    accounts::AccountId,
+    assets::{Asset, FungibleAsset},
+    notes::{Note, NoteId},
+    utils::collections::BTreeMap,
+    Digest,
+};
+
+use super::{notes::P2ID_TIMELOCK_SCRIPT, Client};
+use crate::errors::ClientError;
+
+lazy_static! {
+    /// Hashes of the standard note scripts of `miden_lib`
+    static ref STANDARD_SCRIPT_HASHES: StandardScriptHashes = StandardScriptHashes::new();
+}
+
+/// Regular account and fungible faucet the standard scripts are read with, whose IDs are only
+/// used to satisfy the constructors of `miden_lib`
+const SCRIPT_ACCOUNT_ID: u64 = 0x168187d729b31a84;
+const SCRIPT_FAUCET_ID: u64 = 10347894387879516201;
+
+struct StandardScriptHashes {
+    p2id: Digest,
+    p2idr: Digest,
+    swap: Digest,
+}
+
+impl StandardScriptHashes {
+    /// `miden_lib` only exposes its note scripts through the constructors of the notes running
+    /// them, so the scripts are taken from notes built by those constructors, which require at
+    /// least one asset. A script's hash depends neither on the note's inputs nor on its assets.
+    fn new() -> Self {
+        let account_id =
+            AccountId::try_from(SCRIPT_ACCOUNT_ID).expect("script account ID is valid");
+        let faucet_id = AccountId::try_from(SCRIPT_FAUCET_ID).expect("script faucet ID is valid");
+        let asset: Asset = FungibleAsset::new(faucet_id, 1)
+            .expect("script asset is valid")
+            .into();
+        let rng = || RpoRandomCoin::new([Felt::ZERO; 4]);
+
+        let p2id = create_p2id_note(account_id, account_id, vec![asset], rng())
+            .expect("P2ID script is valid");
+        let p2idr = create_p2idr_note(account_id, account_id, vec![asset], 0, rng())
+            .expect("P2IDR script is valid");
+        let (swap, _) =
+            create_swap_note(account_id, asset, asset, rng()).expect("SWAP script is valid");
+
+        Self {
+            p2id: p2id.script().hash(),
+            p2idr: p2idr.script().hash(),
+            swap: swap.script().hash(),
+        }
+    }
+}
+
+// DECODED NOTE
+// ================================================================================================
+
+/// Structured view of the inputs of a note created with a script known to the
+/// [NoteInterpreter].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodedNote {
+    /// Pay-to-ID note, which only the target account can consume
+    P2ID { target: AccountId },
+    /// Pay-to-ID note that the sender can recall once the chain is past `recall_height`
+    P2IDR {
+        target: AccountId,
+        recall_height: u32,
+    },
+    /// Pay-to-ID note that the target account can only consume once the chain is past
+    /// `spendable_after_block`, see
+    /// [create_p2id_timelock_note](super::notes::create_p2id_timelock_note)
+    P2IDTimelock {
+        target: AccountId,
+        spendable_after_block: u32,
+    },
+    /// Note offering its assets to whoever sends `requested_asset` back to the sender, in a note
+    /// with the given recipient and tag
+    Swap {
+        requested_asset: Asset,
+        payback_recipient: Digest,
+        payback_tag: u64,
+    },
+    /// Note created with a script decoded by a decoder registered with
+    /// [NoteInterpreter::register]
+    Custom {
+        script_name: String,
+        fields: Vec<(String, String)>,
+    },
+}
+
+impl DecodedNote {
+    /// Returns the name of the note's script
+    pub fn script_name(&self) -> &str {
+        match self {
+            DecodedNote::P2ID { .. } => "P2ID",
+            DecodedNote::P2IDR { .. } => "P2IDR",
+            DecodedNote::P2IDTimelock { .. } => "P2ID with timelock",
+            DecodedNote::Swap { .. } => "SWAP",
+            DecodedNote::Custom { script_name, .. } => script_name,
+        }
+    }
+
+    /// Returns the decoded inputs as pairs of field names and human-readable values
+    pub fn fields(&self) -> Vec<(String, String)> {
+        let fields = match self {
+            DecodedNote::P2ID { target } => vec![("Target account", target.to_hex())],
+            DecodedNote::P2IDR {
+                target,
+                recall_height,
+            } => vec![
+                ("Target account", target.to_hex()),
+                ("Recallable after block", recall_height.to_string()),
+            ],
+            DecodedNote::P2IDTimelock {
+                target,
+                spendable_after_block,
+            } => vec![
+                ("Target account", target.to_hex()),
+                ("Spendable after block", spendable_after_block.to_string()),
+            ],
+            DecodedNote::Swap {
+                requested_asset,
+                payback_recipient,
+                payback_tag,
+            } => vec![
+                ("Requested asset", describe_asset(requested_asset)),
+                ("Payback recipient", payback_recipient.to_string()),
+                ("Payback tag", payback_tag.to_string()),
+            ],
+            DecodedNote::Custom { fields, .. } => return fields.clone(),
+        };
+
+        fields
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+}
+
+fn describe_asset(asset: &Asset) -> String {
+    match asset {
+        Asset::Fungible(asset) => {
+            format!(
+                "{} of faucet {}",
+                asset.amount(),
+                asset.faucet_id().to_hex()
+            )
+        }
+        Asset::NonFungible(asset) => format!(
+            "non-fungible asset {} of faucet {}",
+            Digest::from(Word::from(*asset)).to_hex(),
+            asset.faucet_id().to_hex()
+        ),
+    }
+}
+
+// NOTE INTERPRETER
+// ================================================================================================
+
+/// Decodes the inputs of notes created with a known script, returning `None` if they do not match
+/// the layout the script expects
+pub type NoteDecoder = fn(&[Felt]) -> Option<DecodedNote>;
+
+/// Registry of the decoders of known note scripts, by script hash.
+///
+/// The standard P2ID, P2IDR and SWAP scripts, along with the timelocked P2ID script of the client,
+/// are registered by default.
+#[derive(Clone, Debug)]
+pub struct NoteInterpreter {
+    decoders: BTreeMap<Digest, NoteDecoder>,
+}
+
+impl NoteInterpreter {
+    /// Registers the decoder of the notes whose script has the specified hash, replacing the
+    /// decoder previously registered for it, if any
+    pub fn register(&mut self, script_hash: Digest, decoder: NoteDecoder) {
+        self.decoders.insert(script_hash, decoder);
+    }
+
+    /// Decodes the inputs of the note, returning `None` if its script is unknown or its inputs do
+    /// not match the layout its script expects
+    pub fn decode(&self, note: &Note) -> Option<DecodedNote> {
+        let decoder = self.decoders.get(&note.script().hash())?;
+        decoder(note.inputs().inputs())
+    }
+}
+
+impl Default for NoteInterpreter {
+    fn default() -> Self {
+        let mut interpreter = Self {
+            decoders: BTreeMap::new(),
+        };
+        interpreter.register(STANDARD_SCRIPT_HASHES.p2id, decode_p2id);
+        interpreter.register(STANDARD_SCRIPT_HASHES.p2idr, decode_p2idr);
+        interpreter.register(STANDARD_SCRIPT_HASHES.swap, decode_swap);
+        interpreter.register(P2ID_TIMELOCK_SCRIPT.hash(), decode_p2id_timelock);
+        interpreter
+    }
+}
+
+fn decode_p2id(inputs: &[Felt]) -> Option<DecodedNote> {
+    let target = AccountId::try_from(*inputs.first()?).ok()?;
+    Some(DecodedNote::P2ID { target })
+}
+
+fn decode_p2idr(inputs: &[Felt]) -> Option<DecodedNote> {
+    let target = AccountId::try_from(*inputs.first()?).ok()?;
+    let recall_height = u32::try_from(inputs.get(1)?.as_int()).ok()?;
+    Some(DecodedNote::P2IDR {
+        target,
+        recall_height,
+    })
+}
+
+pub(super) fn decode_p2id_timelock(inputs: &[Felt]) -> Option<DecodedNote> {
+    let target = AccountId::try_from(*inputs.first()?).ok()?;
+    let spendable_after_block = u32::try_from(inputs.get(1)?.as_int()).ok()?;
+    Some(DecodedNote::P2IDTimelock {
+        target,
+        spendable_after_block,
+    })
+}
+
+/// SWAP notes hold the payback recipient, then the requested asset, then the payback tag
+fn decode_swap(inputs: &[Felt]) -> Option<DecodedNote> {
+    let payback_recipient: Word = inputs.get(0..4)?.try_into().ok()?;
+    let requested_asset: Word = inputs.get(4..8)?.try_into().ok()?;
+    Some(DecodedNote::Swap {
+        requested_asset: Asset::try_from(requested_asset).ok()?,
+        payback_recipient: Digest::new(payback_recipient),
+        payback_tag: inputs.get(8)?.as_int(),
+    })
+}
+
+impl Client {
+    /// Decodes the inputs of the specified input note with the client's [NoteInterpreter].
+    /// Returns `None` if the note's script is not known to it.
+    ///
+    /// # Errors
+    /// Returns an error if the note is not tracked by the client.
+    pub fn decode_note(&self, note_id: NoteId) -> Result<Option<DecodedNote>, ClientError> {
+        let note = self.get_input_note(note_id)?;
+        Ok(self.note_interpreter.decode(note.note()))
+    }
+
+    /// Registers the decoder used by [Client::decode_note] for the notes whose script has the
+    /// specified hash
+    pub fn register_note_decoder(&mut self, script_hash: Digest, decoder: NoteDecoder) {
+        self.note_interpreter.register(script_hash, decoder);
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crypto::{rand::RpoRandomCoin, Felt, FieldElement};
+    use miden_lib::notes::{create_p2id_note, create_p2idr_note, create_swap_note};
+    use mock::constants::{ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN, ACCOUNT_ID_SENDER};
+    use objects::{
+        accounts::AccountId,
+        assets::{Asset, FungibleAsset},
+    };
+
+    use super::{decode_swap, DecodedNote, NoteInterpreter};
+    use crate::client::notes::create_p2id_timelock_note;
+
+    #[test]
+    fn standard_notes_are_decoded() {
+        let interpreter = NoteInterpreter::default();
+        let sender = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let asset: Asset = FungibleAsset::new(faucet_id, 100).unwrap().into();
+        let rng = || RpoRandomCoin::new([Felt::ZERO; 4]);
+
+        let note = create_p2id_note(sender, faucet_id, vec![asset], rng()).unwrap();
+        assert_eq!(
+            interpreter.decode(&note),
+            Some(DecodedNote::P2ID { target: faucet_id })
+        );
+
+        let note = create_p2idr_note(sender, faucet_id, vec![asset], 42, rng()).unwrap();
+        assert_eq!(
+            interpreter.decode(&note),
+            Some(DecodedNote::P2IDR {
+                target: faucet_id,
+                recall_height: 42
+            })
+        );
+
+        let note = create_p2id_timelock_note(sender, faucet_id, &[asset], 7, None, rng()).unwrap();
+        let decoded = interpreter.decode(&note).unwrap();
+        assert_eq!(
+            decoded,
+            DecodedNote::P2IDTimelock {
+                target: faucet_id,
+                spendable_after_block: 7
+            }
+        );
+        assert_eq!(
+            decoded.fields()[1],
+            ("Spendable after block".to_string(), "7".to_string())
+        );
+    }
+
+    #[test]
+    fn swap_notes_are_decoded() {
+        let interpreter = NoteInterpreter::default();
+        let sender = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let offered_asset: Asset = FungibleAsset::new(faucet_id, 100).unwrap().into();
+        let requested_asset: Asset = FungibleAsset::new(faucet_id, 25).unwrap().into();
+
+        let (note, _) = create_swap_note(
+            sender,
+            offered_asset,
+            requested_asset,
+            RpoRandomCoin::new([Felt::ZERO; 4]),
+        )
+        .unwrap();
+        match interpreter.decode(&note) {
+            Some(DecodedNote::Swap {
+                requested_asset: decoded_asset,
+                ..
+            }) => assert_eq!(decoded_asset, requested_asset),
+            decoded => panic!("expected a SWAP note, got {decoded:?}"),
+        }
+
+        // the payback tag follows the payback recipient and the requested asset
+        assert!(decode_swap(&note.inputs().inputs()[..8]).is_none());
+        assert!(decode_swap(&[Felt::ZERO; 9]).is_none());
+    }
+
+    #[test]
+    fn registered_decoders_take_precedence() {
+        let mut interpreter = NoteInterpreter::default();
+        let sender = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let asset: Asset = FungibleAsset::new(faucet_id, 100).unwrap().into();
+        let note = create_p2id_note(
+            sender,
+            sender,
+            vec![asset],
+            RpoRandomCoin::new([Felt::ZERO; 4]),
+        )
+        .unwrap();
+
+        interpreter.register(note.script().hash(), |inputs| {
+            Some(DecodedNote::Custom {
+                script_name: "payroll".to_string(),
+                fields: vec![("Inputs".to_string(), inputs.len().to_string())],
+            })
+        });
+        assert_eq!(interpreter.decode(&note).unwrap().script_name(), "payroll");
+    }
+}
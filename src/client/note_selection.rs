@@ -0,0 +1,248 @@
+use core::cmp::Reverse;
+
+use objects::notes::NoteId;
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of subsets of notes explored by [NoteSelectionStrategy::BranchAndBound] before
+/// it settles for the best selection found so far
+const MAX_BRANCH_AND_BOUND_STEPS: usize = 100_000;
+
+// NOTE SELECTION STRATEGY
+// ================================================================================================
+
+/// How the notes consumed to top up an account's vault are picked when a payment needs more of an
+/// asset than the account holds.
+///
+/// Only P2ID notes addressed to the account, timelocked or not, are considered, and at most as many
+/// of them as a transaction can consume are picked.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NoteSelectionStrategy {
+    /// Consumes the notes carrying the smallest amounts first, which cleans up dust at the cost of
+    /// consuming more notes
+    SmallestFirst,
+    /// Consumes the notes carrying the largest amounts first, which consumes as few notes as
+    /// possible
+    #[default]
+    LargestFirst,
+    /// Looks for the notes whose amounts add up as close to the missing amount as possible, so
+    /// that the remaining notes are kept for later payments. Falls back to
+    /// [NoteSelectionStrategy::LargestFirst] when the search gives up.
+    BranchAndBound,
+}
+
+impl NoteSelectionStrategy {
+    /// Selects at most `max_notes` notes from `candidates`, given with the amount of the asset
+    /// they carry, whose amounts add up to at least `target`. Returns `None` if no selection does.
+    pub fn select(
+        &self,
+        candidates: &[(NoteId, u64)],
+        target: u64,
+        max_notes: usize,
+    ) -> Option<Vec<NoteId>> {
+        if target == 0 {
+            return Some(Vec::new());
+        }
+
+        let mut candidates: Vec<(NoteId, u64)> = candidates
+            .iter()
+            .filter(|(_, amount)| *amount > 0)
+            .copied()
+            .collect();
+        match self {
+            NoteSelectionStrategy::SmallestFirst => {
+                candidates.sort_by_key(|(_, amount)| *amount);
+                select_in_order(&candidates, target, max_notes)
+            }
+            NoteSelectionStrategy::LargestFirst => {
+                candidates.sort_by_key(|(_, amount)| Reverse(*amount));
+                select_in_order(&candidates, target, max_notes)
+            }
+            NoteSelectionStrategy::BranchAndBound => {
+                candidates.sort_by_key(|(_, amount)| Reverse(*amount));
+                branch_and_bound(&candidates, target, max_notes)
+                    .or_else(|| select_in_order(&candidates, target, max_notes))
+            }
+        }
+    }
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Takes notes in the order they are given until their amounts reach `target`
+fn select_in_order(
+    candidates: &[(NoteId, u64)],
+    target: u64,
+    max_notes: usize,
+) -> Option<Vec<NoteId>> {
+    let mut selected = Vec::new();
+    let mut total = 0u64;
+
+    for (note_id, amount) in candidates.iter().take(max_notes) {
+        selected.push(*note_id);
+        total = total.saturating_add(*amount);
+        if total >= target {
+            return Some(selected);
+        }
+    }
+
+    None
+}
+
+/// Searches for the selection whose amounts exceed `target` the least, preferring fewer notes on
+/// ties. `candidates` are expected to be sorted by decreasing amount, which lets the search skip
+/// the subsets that cannot reach the target anymore.
+fn branch_and_bound(
+    candidates: &[(NoteId, u64)],
+    target: u64,
+    max_notes: usize,
+) -> Option<Vec<NoteId>> {
+    struct Search {
+        amounts: Vec<u64>,
+        /// Sum of the amounts from each index to the end, to prune branches that fall short
+        remaining: Vec<u64>,
+        target: u64,
+        max_notes: usize,
+        steps: usize,
+        current: Vec<usize>,
+        best: Option<(u64, Vec<usize>)>,
+    }
+
+    impl Search {
+        fn explore(&mut self, index: usize, total: u64) {
+            self.steps += 1;
+            if self.steps > MAX_BRANCH_AND_BOUND_STEPS {
+                return;
+            }
+
+            if total >= self.target {
+                let is_better = match &self.best {
+                    Some((best_total, best)) => {
+                        total < *best_total
+                            || (total == *best_total && self.current.len() < best.len())
+                    }
+                    None => true,
+                };
+                if is_better {
+                    self.best = Some((total, self.current.clone()));
+                }
+                return;
+            }
+
+            let cannot_reach = index == self.amounts.len()
+                || total.saturating_add(self.remaining[index]) < self.target
+                || self.current.len() == self.max_notes;
+            let cannot_improve =
+                matches!(&self.best, Some((best_total, _)) if total >= *best_total);
+            if cannot_reach || cannot_improve {
+                return;
+            }
+
+            // an exact match cannot be improved upon
+            if matches!(&self.best, Some((best_total, _)) if *best_total == self.target) {
+                return;
+            }
+
+            self.current.push(index);
+            self.explore(index + 1, total.saturating_add(self.amounts[index]));
+            self.current.pop();
+            self.explore(index + 1, total);
+        }
+    }
+
+    let amounts: Vec<u64> = candidates.iter().map(|(_, amount)| *amount).collect();
+    let mut remaining = vec![0u64; amounts.len() + 1];
+    for index in (0..amounts.len()).rev() {
+        remaining[index] = remaining[index + 1].saturating_add(amounts[index]);
+    }
+
+    let mut search = Search {
+        amounts,
+        remaining,
+        target,
+        max_notes,
+        steps: 0,
+        current: Vec::new(),
+        best: None,
+    };
+    search.explore(0, 0);
+
+    search.best.map(|(_, selected)| {
+        selected
+            .into_iter()
+            .map(|index| candidates[index].0)
+            .collect()
+    })
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crypto::Felt;
+    use objects::{notes::NoteId, Digest};
+
+    use super::NoteSelectionStrategy;
+    use crate::client::transactions::MAX_INPUT_NOTES_PER_TRANSACTION;
+
+    fn note_id(seed: u64) -> NoteId {
+        Digest::new([Felt::new(seed); 4]).into()
+    }
+
+    fn selected_amounts(
+        strategy: NoteSelectionStrategy,
+        amounts: &[u64],
+        target: u64,
+    ) -> Option<Vec<u64>> {
+        let candidates: Vec<(NoteId, u64)> = amounts
+            .iter()
+            .enumerate()
+            .map(|(index, amount)| (note_id(index as u64), *amount))
+            .collect();
+
+        strategy
+            .select(&candidates, target, MAX_INPUT_NOTES_PER_TRANSACTION)
+            .map(|selected| {
+                selected
+                    .iter()
+                    .map(|note_id| {
+                        candidates
+                            .iter()
+                            .find(|(candidate, _)| candidate == note_id)
+                            .unwrap()
+                            .1
+                    })
+                    .collect()
+            })
+    }
+
+    #[test]
+    fn strategies_select_enough_notes() {
+        let amounts = [5, 40, 10, 25, 20];
+
+        assert_eq!(
+            selected_amounts(NoteSelectionStrategy::SmallestFirst, &amounts, 30),
+            Some(vec![5, 10, 20])
+        );
+        assert_eq!(
+            selected_amounts(NoteSelectionStrategy::LargestFirst, &amounts, 30),
+            Some(vec![40])
+        );
+        // 25 + 5 matches the target exactly, keeping the large note for later
+        assert_eq!(
+            selected_amounts(NoteSelectionStrategy::BranchAndBound, &amounts, 30),
+            Some(vec![25, 5])
+        );
+
+        for strategy in [
+            NoteSelectionStrategy::SmallestFirst,
+            NoteSelectionStrategy::LargestFirst,
+            NoteSelectionStrategy::BranchAndBound,
+        ] {
+            assert_eq!(selected_amounts(strategy, &amounts, 0), Some(vec![]));
+            assert_eq!(selected_amounts(strategy, &amounts, 101), None);
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use objects::accounts::AccountId;
+
+pub use super::sync::account_note_tag;
+use super::{sync::FILTER_ID_SHIFT, Client};
+use crate::errors::ClientError;
+
+// NOTE TAGS
+// ================================================================================================
+//
+// The node matches the notes of a sync request by the 16 most significant bits of their tag, so
+// these bits decide which clients retrieve a note. Notes meant for a single account are tagged
+// with the account's ID, see [account_note_tag]. Other tags are laid out as follows:
+//
+// - bit 63: the [NoteExecutionHint] of the notes.
+// - bits 48 to 62: the use case of the notes, shared by every note of the same kind.
+// - bits 32 to 47: zero, which keeps the tag a valid field element.
+// - bits 0 to 31: a payload specific to the use case.
+
+/// Use case of the tags of SWAP notes, see [swap_note_tag]
+pub const SWAP_USE_CASE: u16 = 1;
+
+/// Largest use case that can be encoded in a tag
+pub const MAX_USE_CASE: u16 = (1 << 15) - 1;
+
+/// How the notes carrying a tag are meant to be consumed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteExecutionHint {
+    /// By a transaction the node executes against an on-chain account
+    Network,
+    /// By a transaction a client executes and proves locally
+    Local,
+}
+
+/// Returns the tag of the notes of an application-defined `use_case`, e.g. the orders of a
+/// particular market, carrying `payload` in its least significant bits. Clients tracking any tag
+/// of the use case retrieve every note of it, so the payload only tells them apart once they are
+/// retrieved.
+///
+/// # Errors
+/// Returns an error if `use_case` is greater than [MAX_USE_CASE].
+pub fn custom_note_tag(
+    use_case: u16,
+    payload: u32,
+    execution: NoteExecutionHint,
+) -> Result<u64, ClientError> {
+    if use_case > MAX_USE_CASE {
+        return Err(ClientError::InvalidNote(format!(
+            "note tag use case {use_case} is greater than {MAX_USE_CASE}"
+        )));
+    }
+
+    let execution_bit = match execution {
+        NoteExecutionHint::Network => 0,
+        NoteExecutionHint::Local => 1 << 63,
+    };
+    Ok(execution_bit | (u64::from(use_case) << FILTER_ID_SHIFT) | u64::from(payload))
+}
+
+/// Returns the tag of SWAP notes offering tokens of `offered_faucet_id` in exchange for tokens of
+/// `requested_faucet_id`. The payload holds the 16 most significant bits of both faucet IDs, so
+/// that the notes trading a pair of tokens can be told apart from the other SWAP notes.
+pub fn swap_note_tag(offered_faucet_id: AccountId, requested_faucet_id: AccountId) -> u64 {
+    let faucet_prefix = |faucet_id: AccountId| (u64::from(faucet_id) >> FILTER_ID_SHIFT) as u32;
+    let payload = (faucet_prefix(offered_faucet_id) << 16) | faucet_prefix(requested_faucet_id);
+
+    custom_note_tag(SWAP_USE_CASE, payload, NoteExecutionHint::Local)
+        .expect("the SWAP use case fits in a tag")
+}
+
+/// Returns the execution hint, use case and payload encoded in a tag built by [custom_note_tag],
+/// or `None` if the tag does not have the layout of custom tags. The tags of an account's notes
+/// whose bits 32 to 47 happen to be clear are decoded as well.
+pub fn decode_custom_note_tag(tag: u64) -> Option<(NoteExecutionHint, u16, u32)> {
+    // the bits between the use case and the payload are only clear in custom tags
+    if (tag >> 32) & 0xffff != 0 {
+        return None;
+    }
+
+    let execution = if tag >> 63 == 1 {
+        NoteExecutionHint::Local
+    } else {
+        NoteExecutionHint::Network
+    };
+    let use_case = ((tag >> FILTER_ID_SHIFT) as u16) & MAX_USE_CASE;
+    Some((execution, use_case, tag as u32))
+}
+
+impl Client {
+    // NOTE TAGS
+    // --------------------------------------------------------------------------------------------
+
+    /// Tracks the tag of the SWAP notes offering tokens of `offered_faucet_id` in exchange for
+    /// tokens of `requested_faucet_id`, see [swap_note_tag], so that syncing retrieves the orders
+    /// the client's accounts can fill.
+    pub fn add_swap_note_tag(
+        &mut self,
+        offered_faucet_id: AccountId,
+        requested_faucet_id: AccountId,
+    ) -> Result<u64, ClientError> {
+        let tag = swap_note_tag(offered_faucet_id, requested_faucet_id);
+        let label = format!("SWAP {offered_faucet_id} for {requested_faucet_id}");
+        self.add_labeled_note_tag(tag, &label)?;
+        Ok(tag)
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use mock::constants::{ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN, ACCOUNT_ID_SENDER};
+    use objects::{accounts::AccountId, Felt, StarkField};
+
+    use super::{
+        custom_note_tag, decode_custom_note_tag, swap_note_tag, NoteExecutionHint, MAX_USE_CASE,
+        SWAP_USE_CASE,
+    };
+    use crate::errors::ClientError;
+
+    #[test]
+    fn custom_tags_encode_the_use_case_in_their_prefix() {
+        let tag = custom_note_tag(42, 7, NoteExecutionHint::Local).unwrap();
+        assert_eq!(tag >> 48, (1 << 15) | 42);
+        assert_eq!(
+            decode_custom_note_tag(tag),
+            Some((NoteExecutionHint::Local, 42, 7))
+        );
+
+        let tag = custom_note_tag(MAX_USE_CASE, u32::MAX, NoteExecutionHint::Local).unwrap();
+        assert_eq!(
+            Felt::new(tag).as_int(),
+            tag,
+            "tags are valid field elements"
+        );
+
+        let tag = custom_note_tag(42, 7, NoteExecutionHint::Network).unwrap();
+        assert_eq!(
+            decode_custom_note_tag(tag),
+            Some((NoteExecutionHint::Network, 42, 7))
+        );
+
+        assert!(matches!(
+            custom_note_tag(MAX_USE_CASE + 1, 0, NoteExecutionHint::Local),
+            Err(ClientError::InvalidNote(_))
+        ));
+    }
+
+    #[test]
+    fn swap_tags_depend_on_the_traded_pair() {
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let other_faucet_id = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+
+        let tag = swap_note_tag(faucet_id, other_faucet_id);
+        let (execution, use_case, _) = decode_custom_note_tag(tag).unwrap();
+        assert_eq!(execution, NoteExecutionHint::Local);
+        assert_eq!(use_case, SWAP_USE_CASE);
+        assert_ne!(tag, swap_note_tag(other_faucet_id, faucet_id));
+    }
+}
@@ -1,23 +1,389 @@
-use super::Client;
+use super::{
+    note_interpreter::{decode_p2id_timelock, DecodedNote},
+    note_tags::{account_note_tag, swap_note_tag},
+    transactions::{AssetSummary, ProtocolLimit, ProtocolLimits},
+    Client,
+};
 
 use crate::{
-    errors::ClientError,
-    store::notes::{InputNoteFilter, InputNoteRecord},
+    errors::{ClientError, StoreError},
+    store::{
+        notes::{
+            ExpectedNoteIdRecord, InputNoteRecord, NoteFilter, NoteOrigin, NoteProvenance,
+            NoteScriptRecord, NoteTimeline, PartialNoteRecord,
+        },
+        sync::NoteTagSource,
+        Page,
+    },
+};
+use crypto::{merkle::MerklePath, rand::FeltRng, Felt, StarkField, Word};
+use lazy_static::lazy_static;
+use miden_lib::transaction::TransactionKernel;
+use objects::{
+    accounts::AccountId,
+    assembly::ProgramAst,
+    assets::Asset,
+    notes::{Note, NoteId, NoteInclusionProof, NoteScript, NOTE_TREE_DEPTH},
+    Digest,
 };
-use objects::notes::NoteId;
+use serde::Serialize;
+
+// NOTE BUILDER
+// ================================================================================================
+
+/// Assembles a custom [Note] from its script, inputs, assets and metadata.
+///
+/// The resulting notes can be created by the sender account with
+/// [TransactionTemplate::CreateNotes](super::transactions::TransactionTemplate::CreateNotes).
+/// [Client::build_note] builds them with the client's randomness and limits.
+///
+/// ```ignore
+/// let builder = NoteBuilder::new(sender_id)
+///     .script(ProgramAst::parse(NOTE_SCRIPT)?)
+///     .inputs(vec![Felt::new(42)])
+///     .add_asset(asset)
+///     .tag_for_account(target_id);
+/// let note = client.build_note(builder)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct NoteBuilder {
+    sender: AccountId,
+    script: Option<ProgramAst>,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+    serial_num: Option<Word>,
+    tag: u64,
+}
+
+impl NoteBuilder {
+    /// Returns a builder for a note sent by the specified account
+    pub fn new(sender: AccountId) -> Self {
+        Self {
+            sender,
+            script: None,
+            inputs: Vec::new(),
+            assets: Vec::new(),
+            serial_num: None,
+            tag: 0,
+        }
+    }
+
+    /// Sets the code run when the note is consumed. It is compiled when the note is built
+    pub fn script(mut self, script: ProgramAst) -> Self {
+        self.script = Some(script);
+        self
+    }
+
+    /// Sets the inputs made available to the note script
+    pub fn inputs(mut self, inputs: Vec<Felt>) -> Self {
+        self.inputs = inputs;
+        self
+    }
+
+    /// Adds an asset carried by the note
+    pub fn add_asset(mut self, asset: Asset) -> Self {
+        self.assets.push(asset);
+        self
+    }
+
+    /// Sets the serial number of the note. One is drawn from the random generator the note is
+    /// built with if not set
+    pub fn serial_num(mut self, serial_num: Word) -> Self {
+        self.serial_num = Some(serial_num);
+        self
+    }
+
+    /// Sets the tag the note can be retrieved by, 0 by default. The helpers of
+    /// [note_tags](super::note_tags) compute the tags of common kinds of notes
+    pub fn tag(mut self, tag: u64) -> Self {
+        self.tag = tag;
+        self
+    }
+
+    /// Tags the note for the specified account, which retrieves it when syncing, as done for the
+    /// P2ID notes the client creates. Use this for notes meant to be consumed by that account
+    pub fn tag_for_account(self, account_id: AccountId) -> Self {
+        self.tag(account_note_tag(account_id))
+    }
+
+    /// Tags the note as a SWAP note offering tokens of `offered_faucet_id` in exchange for tokens
+    /// of `requested_faucet_id`, see [swap_note_tag], so that the clients tracking the traded pair
+    /// with [Client::add_swap_note_tag] retrieve it
+    pub fn tag_for_swap(
+        self,
+        offered_faucet_id: AccountId,
+        requested_faucet_id: AccountId,
+    ) -> Self {
+        self.tag(swap_note_tag(offered_faucet_id, requested_faucet_id))
+    }
+
+    /// Compiles the script and builds the note, drawing its serial number from `rng` if none was
+    /// set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no script was set, the script does not compile, the note carries more
+    /// assets than allowed by the protocol, or the inputs, assets or tag are otherwise invalid.
+    pub fn build<R: FeltRng>(self, rng: R) -> Result<Note, ClientError> {
+        self.build_with_limits(rng, ProtocolLimits::default())
+    }
+
+    /// Builds the note as [NoteBuilder::build] does, checking the number of assets it carries
+    /// against `limits`
+    fn build_with_limits<R: FeltRng>(
+        self,
+        mut rng: R,
+        limits: ProtocolLimits,
+    ) -> Result<Note, ClientError> {
+        let script = self.script.ok_or_else(|| {
+            ClientError::InvalidNote("a script is required to build a note".to_string())
+        })?;
+        limits.check(ProtocolLimit::AssetsPerNote, self.assets.len())?;
+        if self.tag >= Felt::MODULUS {
+            return Err(ClientError::InvalidNote(format!(
+                "tag {} is not a valid field element",
+                self.tag
+            )));
+        }
+
+        let (script, _) = NoteScript::new(script, &TransactionKernel::assembler())?;
+        let serial_num = self.serial_num.unwrap_or_else(|| rng.draw_word());
+        let tag = Felt::new(self.tag);
+
+        Ok(Note::new(
+            script,
+            &self.inputs,
+            &self.assets,
+            serial_num,
+            self.sender,
+            tag,
+        )?)
+    }
+}
+
+/// Removes the first node of a note path sent by a node building its note tree from note IDs,
+/// whose paths start at the note ID and are one node longer than the note tree is deep. Paths
+/// that are as long as the note tree is deep are left unchanged, so that a path that was already
+/// trimmed, such as the one of an exported note, is not trimmed twice.
+///
+/// See: https://github.com/0xPolygonMiden/miden-node/blob/main/store/src/state.rs#L274
+pub(super) fn trim_node_note_path(note_path: &mut MerklePath) {
+    if note_path.len() > NOTE_TREE_DEPTH as usize {
+        let _ = note_path.remove(0);
+    }
+}
+
+// NOTE MEMOS
+// ================================================================================================
+
+/// Maximum length of a note memo, in bytes
+pub const MAX_MEMO_LEN: usize = 64;
+
+/// Last note input of the notes carrying a memo, which the inputs before it hold. Spells `memo` in
+/// ASCII
+const MEMO_MARKER: u64 = 0x6d65_6d6f;
+
+/// Number of memo bytes packed into each note input, few enough for any input to be a valid field
+/// element
+const MEMO_BYTES_PER_INPUT: usize = 7;
+
+/// Encodes `memo` as note inputs to append to the inputs a note's script reads: its UTF-8 bytes
+/// packed [MEMO_BYTES_PER_INPUT] per input, the memo's length in bytes, then the memo marker.
+/// Scripts that only read their leading inputs, such as the P2ID script, ignore the memo.
+///
+/// The memo has to be the last of the note's inputs, as it is found from the end of the inputs.
+///
+/// # Errors
+/// Returns an error if the memo is longer than [MAX_MEMO_LEN] bytes.
+pub fn encode_memo(memo: &str) -> Result<Vec<Felt>, ClientError> {
+    if memo.len() > MAX_MEMO_LEN {
+        return Err(ClientError::InvalidNote(format!(
+            "memo is {} bytes long, the maximum is {MAX_MEMO_LEN}",
+            memo.len()
+        )));
+    }
+
+    let mut inputs: Vec<Felt> = memo
+        .as_bytes()
+        .chunks(MEMO_BYTES_PER_INPUT)
+        .map(|chunk| {
+            let mut bytes = [0u8; 8];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            Felt::new(u64::from_le_bytes(bytes))
+        })
+        .collect();
+    inputs.extend([Felt::new(memo.len() as u64), Felt::new(MEMO_MARKER)]);
+    Ok(inputs)
+}
+
+/// Returns the memo encoded by [encode_memo] at the end of the note's inputs, if any. Inputs
+/// before the memo are not looked at, even if one of them happens to equal the memo marker.
+pub fn decode_memo(inputs: &[Felt]) -> Option<String> {
+    let (marker, inputs) = inputs.split_last()?;
+    if marker.as_int() != MEMO_MARKER {
+        return None;
+    }
+    let (len, inputs) = inputs.split_last()?;
+    let len = len.as_int() as usize;
+    if len > MAX_MEMO_LEN {
+        return None;
+    }
+
+    let num_chunks = (len + MEMO_BYTES_PER_INPUT - 1) / MEMO_BYTES_PER_INPUT;
+    let start = inputs.len().checked_sub(num_chunks)?;
+    let bytes: Vec<u8> = inputs[start..]
+        .iter()
+        .flat_map(|chunk| {
+            chunk
+                .as_int()
+                .to_le_bytes()
+                .into_iter()
+                .take(MEMO_BYTES_PER_INPUT)
+        })
+        .take(len)
+        .collect();
+    String::from_utf8(bytes).ok()
+}
+
+// TIMELOCKED NOTES
+// ================================================================================================
+
+const P2ID_TIMELOCK_SCRIPT_SOURCE: &str = include_str!("asm/note_scripts/p2id_timelock.masm");
+
+lazy_static! {
+    /// Script of the pay-to-ID notes that can only be consumed past a given block
+    pub(crate) static ref P2ID_TIMELOCK_SCRIPT: NoteScript = {
+        let program = ProgramAst::parse(P2ID_TIMELOCK_SCRIPT_SOURCE)
+            .expect("timelock note script is well-formed");
+        NoteScript::new(program, &TransactionKernel::assembler())
+            .expect("timelock note script compiles")
+            .0
+    };
+}
+
+/// Creates a pay-to-ID note carrying `assets` that the target account can only consume in
+/// transactions executed against a block past `spendable_after_block`. The memo, if any, follows
+/// the two inputs the note script reads. The serial number of the note is drawn from `rng`.
+///
+/// # Errors
+/// Returns an error if the memo is too long or the note is otherwise invalid.
+pub fn create_p2id_timelock_note<R: FeltRng>(
+    sender: AccountId,
+    target: AccountId,
+    assets: &[Asset],
+    spendable_after_block: u32,
+    memo: Option<&str>,
+    mut rng: R,
+) -> Result<Note, ClientError> {
+    let mut inputs = vec![target.into(), Felt::from(spendable_after_block)];
+    if let Some(memo) = memo {
+        inputs.extend(encode_memo(memo)?);
+    }
+
+    Ok(Note::new(
+        P2ID_TIMELOCK_SCRIPT.clone(),
+        &inputs,
+        assets,
+        rng.draw_word(),
+        sender,
+        Felt::new(account_note_tag(target)),
+    )?)
+}
+
+/// Returns the block the note can be consumed after, if it was created by
+/// [create_p2id_timelock_note]
+pub fn decode_unlock_height(note: &Note) -> Option<u32> {
+    if note.script().hash() != P2ID_TIMELOCK_SCRIPT.hash() {
+        return None;
+    }
+
+    match decode_p2id_timelock(note.inputs().inputs())? {
+        DecodedNote::P2IDTimelock {
+            spendable_after_block,
+            ..
+        } => Some(spendable_after_block),
+        _ => None,
+    }
+}
+
+// NOTE SUMMARY
+// ================================================================================================
+
+/// Machine-readable summary of an input note, meant for services embedding the client.
+/// Identifiers are serialized as hex strings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NoteSummary {
+    pub note_id: String,
+    pub sender_id: String,
+    pub tag: u64,
+    /// Block the note was committed in, if it is known to be committed
+    pub commit_height: Option<u32>,
+    pub assets: Vec<AssetSummary>,
+    pub memo: Option<String>,
+    /// Block the note can be consumed after, if it is timelocked
+    pub unlock_height: Option<u32>,
+}
+
+impl From<&InputNoteRecord> for NoteSummary {
+    fn from(note: &InputNoteRecord) -> Self {
+        let metadata = note.note().metadata();
+        Self {
+            note_id: note.note_id().inner().to_hex(),
+            sender_id: metadata.sender().to_hex(),
+            tag: u64::from(metadata.tag()),
+            commit_height: note
+                .inclusion_proof()
+                .map(|inclusion_proof| inclusion_proof.origin().block_num),
+            assets: note
+                .note()
+                .assets()
+                .iter()
+                .map(AssetSummary::from)
+                .collect(),
+            memo: decode_memo(note.note().inputs().inputs()),
+            unlock_height: decode_unlock_height(note.note()),
+        }
+    }
+}
+
+// EXPECTED NOTE
+// ================================================================================================
+
+/// A note the client is told to wait for before it is committed, e.g. a payment announced by the
+/// payer. See [Client::add_expected_note].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpectedNote {
+    /// Only the note's ID is known, along with the tag it is created with
+    Id { note_id: NoteId, tag: u64 },
+    /// The note's details are known, so it can be consumed once committed
+    Details(Note),
+}
 
 impl Client {
     // INPUT NOTE DATA RETRIEVAL
     // --------------------------------------------------------------------------------------------
 
-    /// Returns input notes managed by this client.
+    /// Returns the input notes managed by this client that match the provided filter, which can
+    /// be either a status filter or a [NoteFilter] combining several criteria.
     pub fn get_input_notes(
         &self,
-        filter: InputNoteFilter,
+        filter: impl Into<NoteFilter>,
     ) -> Result<Vec<InputNoteRecord>, ClientError> {
         self.store.get_input_notes(filter).map_err(|err| err.into())
     }
 
+    /// Returns the specified page of the input notes managed by this client that match the
+    /// provided filter.
+    pub fn get_input_notes_page(
+        &self,
+        filter: impl Into<NoteFilter>,
+        page: Page,
+    ) -> Result<Vec<InputNoteRecord>, ClientError> {
+        self.store
+            .get_input_notes_page(filter, page)
+            .map_err(|err| err.into())
+    }
+
     /// Returns the input note with the specified hash.
     pub fn get_input_note(&self, note_id: NoteId) -> Result<InputNoteRecord, ClientError> {
         self.store
@@ -25,13 +391,176 @@ impl Client {
             .map_err(|err| err.into())
     }
 
+    /// Returns the ID of the input note whose ID starts with `prefix`, the way short commit hashes
+    /// are resolved by git. See [Store::get_input_note_id_by_prefix](crate::store::Store::get_input_note_id_by_prefix).
+    pub fn get_input_note_id_by_prefix(&self, prefix: &str) -> Result<NoteId, ClientError> {
+        self.store
+            .get_input_note_id_by_prefix(prefix)
+            .map_err(|err| err.into())
+    }
+
+    /// Returns the notes created by the client of which only the recipient is known. They are
+    /// completed by importing the full note with [Client::import_input_note].
+    pub fn get_partial_output_notes(&self) -> Result<Vec<PartialNoteRecord>, ClientError> {
+        self.store
+            .get_partial_output_notes()
+            .map_err(|err| err.into())
+    }
+
+    // NOTE SCRIPTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Compiles the provided MASM into a note script and stores it under `name`, so that it can
+    /// be retrieved with [Client::get_note_script] when building notes. Returns the hash of the
+    /// script.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the code does not compile, a different script is already registered
+    /// under `name` or the script is already registered under another name.
+    pub fn register_note_script(&mut self, name: &str, masm: &str) -> Result<Digest, ClientError> {
+        let code = ProgramAst::parse(masm)
+            .map_err(|err| ClientError::InvalidNote(format!("invalid note script: {err}")))?;
+        let (script, _) = NoteScript::new(code, &TransactionKernel::assembler())?;
+
+        self.store.register_note_script(name, &script)?;
+        Ok(script.hash())
+    }
+
+    /// Returns the note script registered under `name`
+    pub fn get_note_script(&self, name: &str) -> Result<NoteScriptRecord, ClientError> {
+        self.store
+            .get_note_script_by_name(name)
+            .map_err(|err| err.into())
+    }
+
+    /// Returns every note script stored by the client, including the unnamed scripts of the notes
+    /// it tracks
+    pub fn get_note_scripts(&self) -> Result<Vec<NoteScriptRecord>, ClientError> {
+        self.store.get_note_scripts().map_err(|err| err.into())
+    }
+
+    // NOTE BUILDING
+    // --------------------------------------------------------------------------------------------
+
+    /// Builds the note assembled by `builder`, drawing its serial number from the client's random
+    /// generator if none was set, as done for the P2ID notes the client creates.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the note is invalid, see [NoteBuilder::build], or carries more assets
+    /// than allowed by the limits of the client's configuration.
+    pub fn build_note(&mut self, builder: NoteBuilder) -> Result<Note, ClientError> {
+        let rng = self.get_random_coin();
+        builder.build_with_limits(rng, self.protocol_limits)
+    }
+
     // INPUT NOTE CREATION
     // --------------------------------------------------------------------------------------------
 
     /// Imports a new input note into the client's store.
+    ///
+    /// If the note is not known to be committed yet, its tag is tracked so that the note can be
+    /// retrieved while syncing. The tag stops being tracked once the note is consumed. Importing
+    /// a note the client created knowing only its recipient completes the partial record.
+    ///
+    /// The inclusion proofs of imported notes are trimmed like the note paths received when
+    /// syncing, see [RpcConfig::trim_note_paths](crate::config::RpcConfig::trim_note_paths).
+    ///
+    /// Importing a note that is already tracked does not fail. If the stored note was not
+    /// committed yet and the imported one has an inclusion proof, the stored note is updated
+    /// with it, otherwise the store is left unchanged.
+    ///
+    /// Notes committed in a block before the sync height can only be consumed once the block is
+    /// authenticated through [Client::authenticate_block], which the next sync does otherwise.
     pub fn import_input_note(&mut self, note: InputNoteRecord) -> Result<(), ClientError> {
+        self.import_input_note_from(note, NoteOrigin::Imported)
+    }
+
+    /// Imports a new input note like [Client::import_input_note], recording `origin` as where the
+    /// note came from instead of [NoteOrigin::Imported]
+    pub fn import_input_note_from(
+        &mut self,
+        note: InputNoteRecord,
+        origin: NoteOrigin,
+    ) -> Result<(), ClientError> {
+        let note = match note.inclusion_proof() {
+            Some(proof) if self.trim_note_paths => {
+                let mut note_path = proof.note_path().clone();
+                trim_node_note_path(&mut note_path);
+                let inclusion_proof = NoteInclusionProof::new(
+                    proof.origin().block_num,
+                    proof.sub_hash(),
+                    proof.note_root(),
+                    proof.origin().node_index.value(),
+                    note_path,
+                )?;
+                InputNoteRecord::new(note.note().clone(), Some(inclusion_proof))
+            }
+            _ => note,
+        };
+        self.store.insert_input_note(&note, &origin)?;
+
+        if note.inclusion_proof().is_none() {
+            let tag = u64::from(note.note().metadata().tag());
+            self.store
+                .add_note_tag(tag, NoteTagSource::Note(note.note().id()), None)?;
+        }
+        Ok(())
+    }
+
+    /// Waits for a note that is not committed yet. The note's tag is tracked so that syncing
+    /// retrieves the note, and a [ClientEvent::NoteCommitted](super::events::ClientEvent::NoteCommitted)
+    /// is emitted when it is committed.
+    ///
+    /// A note whose details are known is stored as an input note with the `expected` status,
+    /// and is committed by syncing like any other note. A note of which only the ID is known can't
+    /// be consumed, but its arrival is recorded and reported by [Client::get_expected_note_ids].
+    /// Returns false if the note was already known, in which case the store is left unchanged.
+    pub fn add_expected_note(
+        &mut self,
+        expected_note: ExpectedNote,
+        label: Option<&str>,
+    ) -> Result<bool, ClientError> {
+        match expected_note {
+            ExpectedNote::Id { note_id, tag } => {
+                Ok(self.store.insert_expected_note_id(note_id, tag, label)?)
+            }
+            ExpectedNote::Details(note) => {
+                let note_id = note.id();
+                match self.store.get_input_note_by_id(note_id) {
+                    Ok(_) => return Ok(false),
+                    Err(StoreError::InputNoteNotFound(_)) => {}
+                    Err(err) => return Err(err.into()),
+                }
+
+                let tag = u64::from(note.metadata().tag());
+                self.store
+                    .insert_expected_input_note(&InputNoteRecord::new(note, None))?;
+                self.store
+                    .add_note_tag(tag, NoteTagSource::Note(note_id), label)?;
+                Ok(true)
+            }
+        }
+    }
+
+    /// Returns where the input note with the specified ID came from: whether it was imported or
+    /// created by one of the client's transactions, when it was stored and when syncing found it
+    /// committed
+    pub fn get_note_provenance(&self, note_id: NoteId) -> Result<NoteProvenance, ClientError> {
         self.store
-            .insert_input_note(&note)
+            .get_note_provenance(note_id)
             .map_err(|err| err.into())
     }
+
+    /// Returns the blocks the input notes were committed and consumed in, with the blocks'
+    /// timestamps when their headers are stored, in the order the notes were stored
+    pub fn get_note_timelines(&self) -> Result<Vec<NoteTimeline>, ClientError> {
+        self.store.get_note_timelines().map_err(|err| err.into())
+    }
+
+    /// Returns the notes expected by ID, whether they were committed or not
+    pub fn get_expected_note_ids(&self) -> Result<Vec<ExpectedNoteIdRecord>, ClientError> {
+        self.store.get_expected_note_ids().map_err(|err| err.into())
+    }
 }
@@ -0,0 +1,262 @@
+use std::time::Duration;
+
+use objects::{accounts::AccountId, assets::Asset, utils::collections::BTreeMap};
+use serde::Serialize;
+use tracing::warn;
+
+use super::{accounts::AccountStateDelta, events::ClientEvent};
+use crate::{config::NotificationsConfig, errors::ClientError};
+
+// NOTIFICATIONS
+// ================================================================================================
+
+/// JSON payload POSTed to the configured webhook for every change observed while syncing.
+///
+/// Identifiers are serialized as hex strings and the kind of change is given by the `event`
+/// field, e.g. `{"event":"note_committed","note_id":"0x...","block_num":8}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Notification {
+    NoteCommitted {
+        note_id: String,
+        block_num: u32,
+    },
+    NoteConsumed {
+        note_id: String,
+        block_num: u32,
+        by_client: bool,
+    },
+    NoteConsumedExternally {
+        note_id: String,
+        block_num: u32,
+    },
+    TransactionProven {
+        transaction_id: String,
+    },
+    TransactionSubmitted {
+        transaction_id: String,
+    },
+    TransactionCommitted {
+        transaction_id: String,
+        block_num: u32,
+    },
+    AccountStateDesynchronized {
+        account_id: String,
+        local_hash: String,
+        remote_hash: String,
+        block_num: u32,
+    },
+    WatchedNullifierConsumed {
+        nullifier: String,
+        block_num: u32,
+    },
+    SyncProgressed {
+        block_num: u32,
+        target_block_num: u32,
+    },
+    /// The amount of a fungible asset held by a tracked account changed
+    BalanceChanged {
+        account_id: String,
+        faucet_id: String,
+        previous_balance: u64,
+        balance: u64,
+    },
+}
+
+impl Notification {
+    /// Returns a [Notification::BalanceChanged] for every fungible asset whose amount differs
+    /// between the two states of the account described by `delta`.
+    pub fn balance_changes(delta: &AccountStateDelta) -> Vec<Self> {
+        let mut balances: BTreeMap<AccountId, (u64, u64)> = BTreeMap::new();
+        for asset in delta.removed_assets.iter() {
+            if let Asset::Fungible(asset) = asset {
+                balances.entry(asset.faucet_id()).or_default().0 = asset.amount();
+            }
+        }
+        for asset in delta.added_assets.iter() {
+            if let Asset::Fungible(asset) = asset {
+                balances.entry(asset.faucet_id()).or_default().1 = asset.amount();
+            }
+        }
+
+        balances
+            .into_iter()
+            .map(
+                |(faucet_id, (previous_balance, balance))| Notification::BalanceChanged {
+                    account_id: delta.account_id.to_hex(),
+                    faucet_id: faucet_id.to_hex(),
+                    previous_balance,
+                    balance,
+                },
+            )
+            .collect()
+    }
+}
+
+impl From<&ClientEvent> for Notification {
+    fn from(event: &ClientEvent) -> Self {
+        match event {
+            ClientEvent::NoteCommitted { note_id, block_num } => Notification::NoteCommitted {
+                note_id: note_id.inner().to_hex(),
+                block_num: *block_num,
+            },
+            ClientEvent::NoteConsumed {
+                note_id,
+                block_num,
+                by_client,
+            } => Notification::NoteConsumed {
+                note_id: note_id.inner().to_hex(),
+                block_num: *block_num,
+                by_client: *by_client,
+            },
+            ClientEvent::NoteConsumedExternally { note_id, block_num } => {
+                Notification::NoteConsumedExternally {
+                    note_id: note_id.inner().to_hex(),
+                    block_num: *block_num,
+                }
+            }
+            ClientEvent::TransactionProven { transaction_id } => Notification::TransactionProven {
+                transaction_id: transaction_id.to_hex(),
+            },
+            ClientEvent::TransactionSubmitted { transaction_id } => {
+                Notification::TransactionSubmitted {
+                    transaction_id: transaction_id.to_hex(),
+                }
+            }
+            ClientEvent::TransactionCommitted {
+                transaction_id,
+                block_num,
+            } => Notification::TransactionCommitted {
+                transaction_id: transaction_id.to_hex(),
+                block_num: *block_num,
+            },
+            ClientEvent::AccountStateDesynchronized {
+                account_id,
+                local_hash,
+                remote_hash,
+                block_num,
+            } => Notification::AccountStateDesynchronized {
+                account_id: account_id.to_hex(),
+                local_hash: local_hash.to_hex(),
+                remote_hash: remote_hash.to_hex(),
+                block_num: *block_num,
+            },
+            ClientEvent::WatchedNullifierConsumed {
+                nullifier,
+                block_num,
+            } => Notification::WatchedNullifierConsumed {
+                nullifier: nullifier.to_hex(),
+                block_num: *block_num,
+            },
+            ClientEvent::SyncProgressed {
+                block_num,
+                target_block_num,
+            } => Notification::SyncProgressed {
+                block_num: *block_num,
+                target_block_num: *target_block_num,
+            },
+        }
+    }
+}
+
+// WEBHOOK NOTIFIER
+// ================================================================================================
+
+/// Delivers [Notification]s to a webhook URL, retrying failed requests with exponential backoff.
+pub struct WebhookNotifier {
+    http_client: reqwest::Client,
+    url: String,
+    max_retries: u32,
+    retry_delay: Duration,
+}
+
+impl WebhookNotifier {
+    /// Returns a notifier for the webhook set in `config`, or `None` if no webhook is configured.
+    pub fn from_config(config: &NotificationsConfig) -> Option<Self> {
+        config.webhook_url.as_ref().map(|url| Self {
+            http_client: reqwest::Client::new(),
+            url: url.clone(),
+            max_retries: config.max_retries,
+            retry_delay: Duration::from_millis(config.retry_delay_ms),
+        })
+    }
+
+    /// POSTs `notification` as JSON to the webhook. A request is considered delivered once the
+    /// webhook answers with a success status.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the notification could not be delivered after the configured number of
+    /// retries. The delay between attempts doubles after every failure.
+    pub async fn notify(&self, notification: &Notification) -> Result<(), ClientError> {
+        let mut retry_delay = self.retry_delay;
+        let mut attempt = 0;
+        loop {
+            let result = self
+                .http_client
+                .post(&self.url)
+                .json(notification)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+
+            match result {
+                Ok(_) => return Ok(()),
+                Err(err) if attempt < self.max_retries => {
+                    warn!("Webhook request failed ({err}), retrying in {retry_delay:?}");
+                    tokio::time::sleep(retry_delay).await;
+                    retry_delay *= 2;
+                    attempt += 1;
+                }
+                Err(err) => return Err(ClientError::WebhookError(err.to_string())),
+            }
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crypto::{Felt, FieldElement};
+    use mock::constants::{ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN, ACCOUNT_ID_SENDER};
+    use objects::{
+        accounts::AccountId,
+        assets::{Asset, FungibleAsset},
+    };
+
+    use super::Notification;
+    use crate::client::accounts::AccountStateDelta;
+
+    #[test]
+    fn balance_changes_pair_previous_and_new_amounts() {
+        let account_id = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let asset = |amount| Asset::Fungible(FungibleAsset::new(faucet_id, amount).unwrap());
+
+        let delta = AccountStateDelta {
+            account_id,
+            previous_nonce: Felt::ONE,
+            nonce: Felt::new(2),
+            storage_slots: Vec::new(),
+            added_assets: vec![asset(150)],
+            removed_assets: vec![asset(100)],
+        };
+
+        let notifications = Notification::balance_changes(&delta);
+        assert_eq!(
+            notifications,
+            vec![Notification::BalanceChanged {
+                account_id: account_id.to_hex(),
+                faucet_id: faucet_id.to_hex(),
+                previous_balance: 100,
+                balance: 150,
+            }]
+        );
+
+        let json = serde_json::to_value(&notifications[0]).unwrap();
+        assert_eq!(json["event"], "balance_changed");
+        assert_eq!(json["balance"], 150);
+    }
+}
@@ -0,0 +1,104 @@
+use objects::{notes::NoteId, utils::collections::BTreeSet, Digest};
+
+use super::{note_interpreter::DecodedNote, Client};
+use crate::{
+    errors::ClientError,
+    store::{
+        orders::{OrderKind, OrderRecord, OrderStatus},
+        transactions::TransactionFilter,
+    },
+};
+
+impl Client {
+    // ORDERS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the SWAP and P2IDR notes created by the client's accounts, in the order they were
+    /// created, along with the stage of their lifecycle.
+    ///
+    /// Orders are recorded once the transaction creating them is submitted. Syncing marks them as
+    /// filled or recalled when their nullifier is spent, depending on whether the note was
+    /// consumed by its sender, and as expired once the chain reaches the recall height of a note
+    /// still open.
+    pub fn get_orders(&self) -> Result<Vec<OrderRecord>, ClientError> {
+        self.store.get_orders().map_err(|err| err.into())
+    }
+
+    /// Returns the orders among the notes created by the specified executed transaction
+    pub(super) fn get_created_orders(
+        &self,
+        transaction_id: Digest,
+    ) -> Result<Vec<OrderRecord>, ClientError> {
+        let tx_result = self.store.get_executed_transaction(transaction_id)?;
+        let account_id = tx_result.executed_transaction().account_id();
+
+        let orders = tx_result
+            .created_notes()
+            .iter()
+            .filter_map(|note| {
+                let (kind, recall_height) = match self.note_interpreter.decode(note)? {
+                    DecodedNote::Swap { .. } => (OrderKind::Swap, None),
+                    DecodedNote::P2IDR { recall_height, .. } => {
+                        (OrderKind::P2idr, Some(recall_height))
+                    }
+                    _ => return None,
+                };
+                Some(OrderRecord {
+                    note_id: note.id(),
+                    nullifier: note.nullifier().inner(),
+                    kind,
+                    account_id,
+                    transaction_id,
+                    recall_height,
+                    status: OrderStatus::Open,
+                    closed_height: None,
+                })
+            })
+            .collect();
+        Ok(orders)
+    }
+
+    /// Returns the orders whose nullifier is among `nullifiers`, along with the status they are
+    /// closed with: recalled if a transaction of their sender consumed the note, filled otherwise
+    pub(super) fn get_closed_orders(
+        &self,
+        nullifiers: &[Digest],
+    ) -> Result<Vec<(NoteId, OrderStatus)>, ClientError> {
+        let orders: Vec<OrderRecord> = self
+            .store
+            .get_orders()?
+            .into_iter()
+            .filter(|order| !order.status.is_closed() && nullifiers.contains(&order.nullifier))
+            .collect();
+        if orders.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // transactions record the IDs of the notes they consumed, not their nullifiers
+        let consumed_by_client: BTreeSet<_> = self
+            .store
+            .get_transactions(TransactionFilter::All)?
+            .into_iter()
+            .flat_map(|transaction| {
+                let account_id = transaction.account_id;
+                transaction
+                    .input_note_nullifiers
+                    .into_iter()
+                    .map(move |note_id| (account_id, note_id))
+            })
+            .collect();
+
+        Ok(orders
+            .into_iter()
+            .map(|order| {
+                let status =
+                    if consumed_by_client.contains(&(order.account_id, order.note_id.inner())) {
+                        OrderStatus::Recalled
+                    } else {
+                        OrderStatus::Filled
+                    };
+                (order.note_id, status)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,83 @@
+use miden_prover::{ExecutionProof, HashFunction, StarkProof};
+use miden_tx::{ProvingOptions, TransactionProver};
+use objects::transaction::{ExecutedTransaction, ProvenTransaction};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::ClientError;
+
+// TRANSACTION PROVER MODE
+// ================================================================================================
+
+/// How the client proves the transactions it submits.
+///
+/// Proving is by far the slowest step of sending a transaction, so networks that do not verify
+/// transaction proofs, such as local devnets and the mock node used by tests, can be sent
+/// transactions proven in [TransactionProverMode::Trusted] mode to speed up development.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TransactionProverMode {
+    /// Transactions are proven locally with a STARK proof, which every node accepts
+    #[default]
+    Local,
+    /// Transactions are submitted with a placeholder proof, skipping STARK proving altogether.
+    /// Nodes verifying transaction proofs reject them
+    Trusted,
+}
+
+impl TransactionProverMode {
+    /// Turns the executed transaction into a [ProvenTransaction] that can be submitted to the node
+    pub fn prove(
+        &self,
+        proving_options: &ProvingOptions,
+        executed_transaction: ExecutedTransaction,
+    ) -> Result<ProvenTransaction, ClientError> {
+        match self {
+            TransactionProverMode::Local => Ok(TransactionProver::new(proving_options.clone())
+                .prove_transaction(executed_transaction)?),
+            TransactionProverMode::Trusted => Ok(ProvenTransaction::new(
+                executed_transaction.account_id(),
+                executed_transaction.initial_account().hash(),
+                executed_transaction.final_account().hash(),
+                executed_transaction.input_notes().into(),
+                executed_transaction.output_notes().into(),
+                executed_transaction
+                    .tx_script()
+                    .map(|script| *script.hash()),
+                executed_transaction.block_header().hash(),
+                ExecutionProof::new(StarkProof::new_dummy(), HashFunction::Blake3_192),
+            )),
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crypto::utils::{Deserializable, Serializable};
+    use miden_tx::ProvingOptions;
+    use mock::mock::{notes::AssetPreservationStatus, transaction::mock_executed_tx};
+    use objects::transaction::ProvenTransaction;
+
+    use super::TransactionProverMode;
+
+    #[test]
+    fn trusted_proofs_commit_to_the_executed_transaction() {
+        let executed_transaction = mock_executed_tx(AssetPreservationStatus::Preserved);
+
+        let proven_transaction = TransactionProverMode::Trusted
+            .prove(&ProvingOptions::default(), executed_transaction.clone())
+            .unwrap();
+        assert_eq!(proven_transaction.id(), executed_transaction.id());
+        assert_eq!(
+            proven_transaction.account_id(),
+            executed_transaction.account_id()
+        );
+
+        // the placeholder proof is stored and submitted like any other
+        let stored_transaction =
+            ProvenTransaction::read_from_bytes(&proven_transaction.to_bytes()).unwrap();
+        assert_eq!(stored_transaction.id(), executed_transaction.id());
+    }
+}
@@ -0,0 +1,174 @@
+use std::path::Path;
+
+use crypto::Word;
+use objects::{
+    accounts::{Account, AccountId, AccountStub},
+    assets::Asset,
+    notes::NoteId,
+    utils::collections::BTreeMap,
+    Digest,
+};
+
+use super::{transactions::TransactionRecord, Client};
+use crate::{
+    errors::ClientError,
+    store::{
+        accounts::AddressBookEntry,
+        notes::{InputNoteRecord, NoteFilter},
+        orders::OrderRecord,
+        transactions::TransactionFilter,
+        Page, Store,
+    },
+};
+
+// READ-ONLY CLIENT
+// ================================================================================================
+
+/// A view over the store of a client that only retrieves data, for reporting tools such as
+/// dashboards that query the store of a wallet while it keeps running.
+///
+/// The store is opened with [Store::open_read_only], so the view holds no write lock and cannot
+/// modify the store. The view exposes none of the [Client] APIs that sync, execute transactions
+/// or otherwise write to the store, and does not connect to the node.
+pub struct ReadOnlyClient {
+    store: Store,
+}
+
+impl Client {
+    /// Returns a [ReadOnlyClient] over the existing store at `store_path`.
+    ///
+    /// # Errors
+    /// Returns an error if the store cannot be opened read-only, see [Store::open_read_only].
+    pub fn new_read_only(store_path: impl AsRef<Path>) -> Result<ReadOnlyClient, ClientError> {
+        Ok(ReadOnlyClient {
+            store: Store::open_read_only(store_path)?,
+        })
+    }
+}
+
+impl ReadOnlyClient {
+    // ACCOUNTS
+    // --------------------------------------------------------------------------------------------
+
+    /// See [Client::get_accounts].
+    pub fn get_accounts(&self) -> Result<Vec<(AccountStub, Word)>, ClientError> {
+        self.store.get_accounts().map_err(|err| err.into())
+    }
+
+    /// See [Client::get_account_by_id].
+    pub fn get_account_by_id(&self, account_id: AccountId) -> Result<(Account, Word), ClientError> {
+        self.store
+            .get_account_by_id(account_id)
+            .map_err(|err| err.into())
+    }
+
+    /// See [Client::get_account_stub_by_id].
+    pub fn get_account_stub_by_id(
+        &self,
+        account_id: AccountId,
+    ) -> Result<(AccountStub, Word), ClientError> {
+        self.store
+            .get_account_stub_by_id(account_id)
+            .map_err(|err| err.into())
+    }
+
+    /// See [Client::get_account_balances].
+    pub fn get_account_balances(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<(AccountId, u64)>, ClientError> {
+        Ok(self
+            .store
+            .get_account_balances(account_id)?
+            .into_iter()
+            .collect())
+    }
+
+    /// See [Client::get_account_labels].
+    pub fn get_account_labels(&self) -> Result<BTreeMap<AccountId, String>, ClientError> {
+        self.store.get_account_labels().map_err(|err| err.into())
+    }
+
+    /// See [Client::get_address_book].
+    pub fn get_address_book(&self) -> Result<Vec<AddressBookEntry>, ClientError> {
+        self.store.get_address_book().map_err(|err| err.into())
+    }
+
+    /// See [Client::get_vault_assets].
+    pub fn get_vault_assets(&self, vault_root: Digest) -> Result<Vec<Asset>, ClientError> {
+        self.store
+            .get_vault_assets(vault_root)
+            .map_err(|err| err.into())
+    }
+
+    // NOTES
+    // --------------------------------------------------------------------------------------------
+
+    /// See [Client::get_input_notes].
+    pub fn get_input_notes(
+        &self,
+        filter: impl Into<NoteFilter>,
+    ) -> Result<Vec<InputNoteRecord>, ClientError> {
+        self.store.get_input_notes(filter).map_err(|err| err.into())
+    }
+
+    /// See [Client::get_input_notes_page].
+    pub fn get_input_notes_page(
+        &self,
+        filter: impl Into<NoteFilter>,
+        page: Page,
+    ) -> Result<Vec<InputNoteRecord>, ClientError> {
+        self.store
+            .get_input_notes_page(filter, page)
+            .map_err(|err| err.into())
+    }
+
+    /// See [Client::get_input_note].
+    pub fn get_input_note(&self, note_id: NoteId) -> Result<InputNoteRecord, ClientError> {
+        self.store
+            .get_input_note_by_id(note_id)
+            .map_err(|err| err.into())
+    }
+
+    /// See [Client::get_orders].
+    pub fn get_orders(&self) -> Result<Vec<OrderRecord>, ClientError> {
+        self.store.get_orders().map_err(|err| err.into())
+    }
+
+    // TRANSACTIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// See [Client::get_transactions].
+    pub fn get_transactions(
+        &self,
+        transaction_filter: TransactionFilter,
+    ) -> Result<Vec<TransactionRecord>, ClientError> {
+        self.store
+            .get_transactions(transaction_filter)
+            .map_err(|err| err.into())
+    }
+
+    /// See [Client::get_transactions_page].
+    pub fn get_transactions_page(
+        &self,
+        transaction_filter: TransactionFilter,
+        page: Page,
+    ) -> Result<Vec<TransactionRecord>, ClientError> {
+        self.store
+            .get_transactions_page(transaction_filter, page)
+            .map_err(|err| err.into())
+    }
+
+    // SYNC STATE
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the block number the store was last synced up to, see [Client::get_sync_height].
+    pub fn get_sync_height(&self) -> Result<u32, ClientError> {
+        self.store.get_sync_height().map_err(|err| err.into())
+    }
+
+    /// See [Client::get_note_tags].
+    pub fn get_note_tags(&self) -> Result<Vec<u64>, ClientError> {
+        self.store.get_note_tags().map_err(|err| err.into())
+    }
+}
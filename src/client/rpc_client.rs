@@ -22,8 +22,8 @@ pub struct StateSyncInfo {
     pub account_hash_updates: Vec<(AccountId, Digest)>,
     /// List of tuples of Note ID, Note Index and Merkle Path for all new notes
     pub note_inclusions: Vec<CommittedNote>,
-    /// List of nullifiers that identify spent notes
-    pub nullifiers: Vec<Digest>,
+    /// List of nullifiers that identify spent notes, along with the block they were created in
+    pub nullifiers: Vec<(Digest, u32)>,
 }
 
 impl TryFrom<SyncStateResponse> for StateSyncInfo {
@@ -94,8 +94,9 @@ impl TryFrom<SyncStateResponse> for StateSyncInfo {
                     .nullifier
                     .ok_or(RpcApiError::ExpectedFieldMissing("Nullifier".into()))
                     .and_then(|n| Digest::try_from(n).map_err(RpcApiError::ConversionFailure))
+                    .map(|nullifier| (nullifier, nul_update.block_num))
             })
-            .collect::<Result<Vec<Digest>, RpcApiError>>()?;
+            .collect::<Result<Vec<(Digest, u32)>, RpcApiError>>()?;
 
         Ok(Self {
             chain_tip,
@@ -150,48 +151,134 @@ impl CommittedNote {
         &self.merkle_path
     }
 
-    #[allow(dead_code)]
     pub fn metadata(&self) -> NoteMetadata {
         self.metadata
     }
 }
 
+// NODE VERSION
+// ================================================================================================
+
+/// Version of the node the client's RPC definitions come from. Nodes with the same major and
+/// minor version serve the same RPC definitions.
+pub const NODE_PROTOCOL_VERSION: &str = "0.1";
+
+/// Response metadata entry in which nodes report their version
+const NODE_VERSION_METADATA_KEY: &str = "x-miden-node-version";
+
+/// Start of the message of the status returned by gRPC when a message does not match the
+/// definitions of the side decoding it
+const DECODE_ERROR_MESSAGE: &str = "failed to decode Protobuf message";
+
+/// Returns true if a node reporting `node_version` serves the RPC definitions of the client
+#[cfg_attr(any(test, feature = "mock"), allow(dead_code))]
+fn is_compatible_node_version(node_version: &str) -> bool {
+    let major_minor = |version: &str| {
+        version
+            .trim()
+            .trim_start_matches('v')
+            .split('.')
+            .take(2)
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+    major_minor(node_version) == major_minor(NODE_PROTOCOL_VERSION)
+}
+
+/// Returns true if a request failed because the node and the client disagree on the RPC
+/// definitions, either because the node does not serve the requested endpoint or because one of
+/// them could not decode a message of the other
+#[cfg_attr(any(test, feature = "mock"), allow(dead_code))]
+fn is_protocol_mismatch(status: &tonic::Status) -> bool {
+    status.code() == tonic::Code::Unimplemented || status.message().contains(DECODE_ERROR_MESSAGE)
+}
+
+/// Returns the version reported by the node in the metadata of a response, if any
+#[cfg_attr(any(test, feature = "mock"), allow(dead_code))]
+fn node_version(metadata: &tonic::metadata::MetadataMap) -> Option<&str> {
+    metadata
+        .get(NODE_VERSION_METADATA_KEY)
+        .and_then(|version| version.to_str().ok())
+}
+
+#[cfg_attr(any(test, feature = "mock"), allow(dead_code))]
+fn incompatible_node_version(node_version: Option<&str>) -> RpcApiError {
+    RpcApiError::IncompatibleNodeVersion {
+        node: node_version.unwrap_or("unknown").to_string(),
+        client: format!(
+            "{} (node protocol {NODE_PROTOCOL_VERSION})",
+            env!("CARGO_PKG_VERSION")
+        ),
+    }
+}
+
 // RPC CLIENT
 // ================================================================================================
 //
 #[cfg(not(any(test, feature = "mock")))]
-pub(crate) use client::RpcClient;
+pub use client::RpcClient;
 
 use crate::errors::RpcApiError;
 
 #[cfg(not(any(test, feature = "mock")))]
 mod client {
-    use super::{RpcApiEndpoint, StateSyncInfo};
-    use crate::errors::RpcApiError;
+    use super::{
+        incompatible_node_version, is_compatible_node_version, is_protocol_mismatch, node_version,
+        RpcApiEndpoint, StateSyncInfo,
+    };
+    use crate::{
+        client::proxy::{resolve_proxy, ProxyConnector},
+        errors::RpcApiError,
+        metrics,
+    };
+    use crypto::merkle::{MerklePath, MmrProof};
     use miden_node_proto::{
         requests::{
-            GetBlockHeaderByNumberRequest, SubmitProvenTransactionRequest, SyncStateRequest,
+            GetAccountProofRequest, GetBlockHeaderByNumberRequest, SubmitProvenTransactionRequest,
+            SyncStateRequest,
         },
         responses::SubmitProvenTransactionResponse,
         rpc::api_client::ApiClient,
     };
-    use objects::{accounts::AccountId, BlockHeader};
-    use tonic::transport::Channel;
-
-    /// Wrapper for ApiClient which defers establishing a connection with a node until necessary
-    pub(crate) struct RpcClient {
+    use objects::{accounts::AccountId, BlockHeader, Digest};
+    use tonic::transport::{Channel, Endpoint};
+    use tracing::{debug, instrument, warn};
+
+    /// Wrapper for ApiClient which defers establishing a connection with a node until necessary,
+    /// and checks that the node is compatible with the client before sending it any request.
+    /// Clones share the connection once it is established.
+    #[derive(Clone)]
+    pub struct RpcClient {
         rpc_api: Option<ApiClient<Channel>>,
         endpoint: String,
+        /// Value of the `rpc.proxy` option, resolved when connecting
+        proxy: Option<String>,
+        node_version_checked: bool,
     }
 
     impl RpcClient {
-        pub fn new(config_endpoint: String) -> RpcClient {
+        /// Returns a client connecting to the node at `config_endpoint`, through the proxy at
+        /// `config_proxy` if any, when it is first used
+        pub fn new(config_endpoint: String, config_proxy: Option<String>) -> RpcClient {
             RpcClient {
                 rpc_api: None,
                 endpoint: config_endpoint,
+                proxy: config_proxy,
+                node_version_checked: false,
             }
         }
 
+        /// Returns a client talking to the node over an already configured `channel`
+        pub fn with_channel(config_endpoint: String, channel: Channel) -> RpcClient {
+            RpcClient {
+                rpc_api: Some(ApiClient::new(channel)),
+                endpoint: config_endpoint,
+                proxy: None,
+                node_version_checked: false,
+            }
+        }
+
+        #[instrument(name = "rpc_submit_proven_transaction", skip_all, err)]
         pub async fn submit_proven_transaction(
             &mut self,
             request: impl tonic::IntoRequest<SubmitProvenTransactionRequest>,
@@ -200,21 +287,19 @@ mod client {
             rpc_api
                 .submit_proven_transaction(request)
                 .await
-                .map_err(|err| RpcApiError::RequestError(RpcApiEndpoint::SubmitProvenTx, err))
+                .map_err(|err| request_error(RpcApiEndpoint::SubmitProvenTx, err))
         }
 
+        #[instrument(name = "rpc_get_block_header_by_number", skip_all, err)]
         pub async fn get_block_header_by_number(
             &mut self,
             request: impl tonic::IntoRequest<GetBlockHeaderByNumberRequest>,
         ) -> Result<BlockHeader, RpcApiError> {
             let rpc_api = self.rpc_api().await?;
-            let api_response =
-                rpc_api
-                    .get_block_header_by_number(request)
-                    .await
-                    .map_err(|err| {
-                        RpcApiError::RequestError(RpcApiEndpoint::GetBlockHeaderByNumber, err)
-                    })?;
+            let api_response = rpc_api
+                .get_block_header_by_number(request)
+                .await
+                .map_err(|err| request_error(RpcApiEndpoint::GetBlockHeaderByNumber, err))?;
 
             api_response
                 .into_inner()
@@ -224,21 +309,124 @@ mod client {
                 .map_err(RpcApiError::ConversionFailure)
         }
 
-        /// Takes care of establishing the RPC connection if not connected yet and returns a reference
-        /// to the inner ApiClient
+        /// Requests the header of block `block_num` along with the proof of its inclusion in the
+        /// node's current chain MMR, whose forest is the length of the node's chain.
+        #[instrument(name = "rpc_get_block_header_with_proof", skip(self), err)]
+        pub async fn get_block_header_with_proof(
+            &mut self,
+            block_num: u32,
+        ) -> Result<(BlockHeader, MmrProof), RpcApiError> {
+            let request = GetBlockHeaderByNumberRequest {
+                block_num: Some(block_num),
+                include_mmr_proof: Some(true),
+            };
+
+            let rpc_api = self.rpc_api().await?;
+            let response = rpc_api
+                .get_block_header_by_number(request)
+                .await
+                .map_err(|err| request_error(RpcApiEndpoint::GetBlockHeaderByNumber, err))?
+                .into_inner();
+
+            let block_header: BlockHeader = response
+                .block_header
+                .ok_or(RpcApiError::ExpectedFieldMissing("BlockHeader".into()))?
+                .try_into()?;
+            let merkle_path = response
+                .mmr_path
+                .ok_or(RpcApiError::ExpectedFieldMissing("MmrPath".into()))?
+                .try_into()?;
+            let forest = response
+                .chain_length
+                .ok_or(RpcApiError::ExpectedFieldMissing("ChainLength".into()))?;
+
+            Ok((
+                block_header,
+                MmrProof {
+                    forest: forest as usize,
+                    position: block_num as usize,
+                    merkle_path,
+                },
+            ))
+        }
+
+        /// Requests the hash of the state of an account at block `block_num`, along with the
+        /// block's header and the path from the account's leaf to the block's account root.
+        #[instrument(name = "rpc_get_account_proof", skip(self), err)]
+        pub async fn get_account_proof(
+            &mut self,
+            account_id: AccountId,
+            block_num: u32,
+        ) -> Result<(BlockHeader, Digest, MerklePath), RpcApiError> {
+            let request = GetAccountProofRequest {
+                account_id: Some(account_id.into()),
+                block_num: Some(block_num),
+            };
+
+            let rpc_api = self.rpc_api().await?;
+            let response = rpc_api
+                .get_account_proof(request)
+                .await
+                .map_err(|err| request_error(RpcApiEndpoint::GetAccountProof, err))?
+                .into_inner();
+
+            let block_header: BlockHeader = response
+                .block_header
+                .ok_or(RpcApiError::ExpectedFieldMissing("BlockHeader".into()))?
+                .try_into()?;
+            let account_hash: Digest = response
+                .account_hash
+                .ok_or(RpcApiError::ExpectedFieldMissing("AccountHash".into()))?
+                .try_into()?;
+            let account_proof: MerklePath = response
+                .account_proof
+                .ok_or(RpcApiError::ExpectedFieldMissing("AccountProof".into()))?
+                .try_into()?;
+
+            Ok((block_header, account_hash, account_proof))
+        }
+
+        /// Takes care of establishing the RPC connection and checking the node's version if not
+        /// done yet, and returns a reference to the inner ApiClient
         async fn rpc_api(&mut self) -> Result<&mut ApiClient<Channel>, RpcApiError> {
-            if self.rpc_api.is_some() {
-                Ok(self.rpc_api.as_mut().unwrap())
-            } else {
-                let rpc_api = ApiClient::connect(self.endpoint.clone())
-                    .await
-                    .map_err(RpcApiError::ConnectionError)?;
-                Ok(self.rpc_api.insert(rpc_api))
+            if self.rpc_api.is_none() {
+                let proxy = resolve_proxy(self.proxy.as_deref(), &self.endpoint)
+                    .map_err(RpcApiError::InvalidProxy)?;
+                let rpc_api = match proxy {
+                    Some(proxy) => {
+                        debug!("Connecting to {} through proxy {proxy}", self.endpoint);
+                        let channel = Endpoint::from_shared(self.endpoint.clone())
+                            .map_err(RpcApiError::ConnectionError)?
+                            .connect_with_connector(ProxyConnector::new(proxy))
+                            .await
+                            .map_err(RpcApiError::ConnectionError)?;
+                        ApiClient::new(channel)
+                    }
+                    None => ApiClient::connect(self.endpoint.clone())
+                        .await
+                        .map_err(RpcApiError::ConnectionError)?,
+                };
+                self.rpc_api = Some(rpc_api);
+            }
+
+            let rpc_api = self
+                .rpc_api
+                .as_mut()
+                .expect("connection was established above");
+            if !self.node_version_checked {
+                check_node_version(rpc_api).await?;
+                self.node_version_checked = true;
             }
+            Ok(rpc_api)
         }
 
         /// Sends a sync state request to the Miden node, validates and converts the response
         /// into a [StateSyncInfo] struct.
+        #[instrument(
+            name = "rpc_sync_state",
+            skip(self, account_ids, note_tags, nullifiers_tags),
+            err
+        )]
         pub async fn sync_state(
             &mut self,
             block_num: u32,
@@ -266,10 +454,51 @@ mod client {
             let response = rpc_api
                 .sync_state(request)
                 .await
-                .map_err(|err| RpcApiError::RequestError(RpcApiEndpoint::SyncState, err))?;
+                .map_err(|err| request_error(RpcApiEndpoint::SyncState, err))?;
             response.into_inner().try_into()
         }
     }
+
+    /// Checks that the node serves the RPC definitions of the client. The version the node reports
+    /// is checked if it reports one, and the genesis block header is requested from the node, as
+    /// its response cannot be decoded if the definitions of the node and the client drifted apart.
+    async fn check_node_version(rpc_api: &mut ApiClient<Channel>) -> Result<(), RpcApiError> {
+        let request = GetBlockHeaderByNumberRequest {
+            block_num: Some(0),
+            include_mmr_proof: None,
+        };
+        let response = rpc_api
+            .get_block_header_by_number(request)
+            .await
+            .map_err(|err| request_error(RpcApiEndpoint::GetBlockHeaderByNumber, err))?;
+
+        let node_version = node_version(response.metadata()).map(str::to_string);
+        if let Some(version) = node_version.as_deref() {
+            if !is_compatible_node_version(version) {
+                return Err(incompatible_node_version(Some(version)));
+            }
+        }
+
+        match response
+            .into_inner()
+            .block_header
+            .map(BlockHeader::try_from)
+        {
+            Some(Ok(_)) => Ok(()),
+            _ => Err(incompatible_node_version(node_version.as_deref())),
+        }
+    }
+
+    /// Wraps a failed request to `endpoint`, counting it in the client's metrics. Failures showing
+    /// that the node does not serve the RPC definitions of the client are reported as such.
+    fn request_error(endpoint: RpcApiEndpoint, err: tonic::Status) -> RpcApiError {
+        metrics::record_rpc_error(&endpoint);
+        if is_protocol_mismatch(&err) {
+            warn!("request to {endpoint} failed as the node uses other RPC definitions: {err}");
+            return incompatible_node_version(node_version(err.metadata()));
+        }
+        RpcApiError::RequestError(endpoint, err)
+    }
 }
 
 // RPC API ENDPOINT
@@ -277,6 +506,7 @@ mod client {
 //
 #[derive(Debug)]
 pub enum RpcApiEndpoint {
+    GetAccountProof,
     GetBlockHeaderByNumber,
     SyncState,
     SubmitProvenTx,
@@ -285,9 +515,41 @@ pub enum RpcApiEndpoint {
 impl fmt::Display for RpcApiEndpoint {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            RpcApiEndpoint::GetAccountProof => write!(f, "get_account_proof"),
             RpcApiEndpoint::GetBlockHeaderByNumber => write!(f, "get_block_header_by_number"),
             RpcApiEndpoint::SyncState => write!(f, "sync_state"),
             RpcApiEndpoint::SubmitProvenTx => write!(f, "submit_proven_transaction"),
         }
     }
 }
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use tonic::{Code, Status};
+
+    use super::{is_compatible_node_version, is_protocol_mismatch};
+
+    #[test]
+    fn node_versions_are_compatible_within_a_minor_version() {
+        assert!(is_compatible_node_version("0.1.0"));
+        assert!(is_compatible_node_version("v0.1.3"));
+        assert!(!is_compatible_node_version("0.2.0"));
+        assert!(!is_compatible_node_version("1.1.0"));
+        assert!(!is_compatible_node_version("0"));
+    }
+
+    #[test]
+    fn protocol_mismatches_are_inferred_from_statuses() {
+        assert!(is_protocol_mismatch(&Status::unimplemented("")));
+        assert!(is_protocol_mismatch(&Status::internal(
+            "failed to decode Protobuf message: SyncStateResponse.block_header: invalid wire type"
+        )));
+        assert!(!is_protocol_mismatch(&Status::new(
+            Code::Unavailable,
+            "connection refused"
+        )));
+    }
+}
@@ -0,0 +1,160 @@
+use crypto::{Felt, StarkField, Word};
+use objects::{utils::collections::BTreeSet, Digest};
+use serde::Deserialize;
+
+use crate::errors::ScriptInputsError;
+
+// TRANSACTION SCRIPT INPUTS
+// ================================================================================================
+
+/// Inputs of a custom transaction script, loaded into the advice map under their key so that the
+/// script can read them with `adv.push_mapval`. They are usually written in a TOML or JSON file,
+/// such as:
+///
+/// ```toml
+/// [[inputs]]
+/// key = "0x0000000000000000010000000000000002000000000000000300000000000000"
+/// values = [1, 2, "0x0400000000000000050000000000000006000000000000000700000000000000", [8, 9]]
+/// ```
+///
+/// Keys are hex-encoded words. Each value is either an integer, which is pushed as one field
+/// element, a hex-encoded word, which is pushed as its four elements, or an array of values,
+/// which is pushed element by element.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransactionScriptInputs {
+    #[serde(default)]
+    pub inputs: Vec<ScriptInput>,
+}
+
+/// A single entry of the advice map of a transaction script
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptInput {
+    pub key: String,
+    pub values: Vec<ScriptInputValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum ScriptInputValue {
+    Integer(u64),
+    Word(String),
+    Array(Vec<ScriptInputValue>),
+}
+
+impl TransactionScriptInputs {
+    /// Validates the inputs and returns them as the entries of the script's advice map, in the
+    /// form expected by [TransactionExecutor::compile_tx_script](miden_tx::TransactionExecutor::compile_tx_script).
+    ///
+    /// # Errors
+    /// Returns an error if a key or a word is not a valid hex-encoded word, if an integer is not a
+    /// valid field element, or if a key is declared twice.
+    pub fn to_advice_map(&self) -> Result<Vec<(Word, Vec<Felt>)>, ScriptInputsError> {
+        let mut keys = BTreeSet::new();
+        let mut advice_map = Vec::with_capacity(self.inputs.len());
+        for input in self.inputs.iter() {
+            let key = Digest::try_from(input.key.as_str())
+                .map_err(|err| ScriptInputsError::InvalidKey(input.key.clone(), err.to_string()))?;
+            if !keys.insert(key) {
+                return Err(ScriptInputsError::DuplicateKey(input.key.clone()));
+            }
+
+            let mut elements = Vec::new();
+            for value in input.values.iter() {
+                push_value(&input.key, value, &mut elements)?;
+            }
+            advice_map.push((key.into(), elements));
+        }
+
+        Ok(advice_map)
+    }
+}
+
+/// Appends the field elements `value` stands for to `elements`
+fn push_value(
+    key: &str,
+    value: &ScriptInputValue,
+    elements: &mut Vec<Felt>,
+) -> Result<(), ScriptInputsError> {
+    match value {
+        ScriptInputValue::Integer(integer) => {
+            if *integer >= Felt::MODULUS {
+                return Err(ScriptInputsError::InvalidFieldElement(
+                    key.to_string(),
+                    *integer,
+                ));
+            }
+            elements.push(Felt::new(*integer));
+        }
+        ScriptInputValue::Word(word) => {
+            let word: Word = Digest::try_from(word.as_str())
+                .map_err(|err| {
+                    ScriptInputsError::InvalidWord(key.to_string(), word.clone(), err.to_string())
+                })?
+                .into();
+            elements.extend(word);
+        }
+        ScriptInputValue::Array(values) => {
+            for value in values {
+                push_value(key, value, elements)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto::{Felt, StarkField};
+
+    use super::TransactionScriptInputs;
+    use crate::errors::ScriptInputsError;
+
+    const KEY: &str = "0x0000000000000000010000000000000002000000000000000300000000000000";
+
+    fn parse(json: &str) -> Result<Vec<(crypto::Word, Vec<Felt>)>, ScriptInputsError> {
+        serde_json::from_str::<TransactionScriptInputs>(json)
+            .unwrap()
+            .to_advice_map()
+    }
+
+    #[test]
+    fn values_are_flattened_into_field_elements() {
+        let advice_map = parse(&format!(
+            r#"{{"inputs": [{{"key": "{KEY}", "values": [7, "{KEY}", [8, [9]]]}}]}}"#
+        ))
+        .unwrap();
+
+        let (key, elements) = &advice_map[0];
+        assert_eq!(key, &[0, 1, 2, 3].map(Felt::new));
+        assert_eq!(elements, &[7, 0, 1, 2, 3, 8, 9].map(Felt::new).to_vec());
+    }
+
+    #[test]
+    fn invalid_inputs_are_rejected() {
+        assert!(matches!(
+            parse(r#"{"inputs": [{"key": "0x01", "values": []}]}"#),
+            Err(ScriptInputsError::InvalidKey(..))
+        ));
+        assert!(matches!(
+            parse(&format!(
+                r#"{{"inputs": [{{"key": "{KEY}", "values": ["0xzz"]}}]}}"#
+            )),
+            Err(ScriptInputsError::InvalidWord(..))
+        ));
+        assert!(matches!(
+            parse(&format!(
+                r#"{{"inputs": [{{"key": "{KEY}", "values": [{}]}}]}}"#,
+                Felt::MODULUS
+            )),
+            Err(ScriptInputsError::InvalidFieldElement(..))
+        ));
+        assert!(matches!(
+            parse(&format!(
+                r#"{{"inputs": [{{"key": "{KEY}", "values": []}}, {{"key": "{KEY}", "values": []}}]}}"#
+            )),
+            Err(ScriptInputsError::DuplicateKey(_))
+        ));
+    }
+}
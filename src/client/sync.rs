@@ -1,32 +1,134 @@
-use super::{rpc_client::CommittedNote, Client};
+use std::{fmt, time::Instant};
 
-use crypto::merkle::{InOrderIndex, MmrDelta, MmrPeaks, PartialMmr};
+use super::{
+    events::ClientEvent,
+    notes::trim_node_note_path,
+    rpc_client::{CommittedNote, StateSyncInfo},
+    transactions::{TransactionRecord, TransactionStatus},
+    Client,
+};
+
+use crypto::merkle::{InOrderIndex, MerklePath, MmrDelta, MmrPeaks, PartialMmr};
 use miden_node_proto::requests::GetBlockHeaderByNumberRequest;
 
 use objects::{
     accounts::{AccountId, AccountStub},
     crypto,
-    notes::{NoteId, NoteInclusionProof},
-    BlockHeader, Digest, StarkField,
+    notes::{NoteId, NoteInclusionProof, NoteMetadata},
+    utils::collections::{BTreeMap, BTreeSet},
+    BlockHeader, Digest, Hasher, StarkField, Word,
 };
 
 use crate::{
-    errors::{ClientError, StoreError},
-    store::{chain_data::ChainMmrNodeFilter, Store},
+    errors::{ClientError, RpcApiError, StoreError},
+    metrics,
+    store::{
+        accounts::{AccountProof, DesynchronizedAccount},
+        chain_data::ChainMmrNodeFilter,
+        notes::{InputNoteFilter, InputNoteRecord, NoteFilter},
+        sync::{NoteTagRecord, NoteTagSource, QuarantinedNote, WatchedNullifier},
+        transactions::TransactionFilter,
+        Store,
+    },
 };
-use tracing::warn;
+use tokio::{sync::mpsc, task::JoinHandle};
+use tracing::{debug, info, instrument, warn, Span};
 
 pub enum SyncStatus {
     SyncedToLastBlock(u32),
     SyncedToBlock(u32),
 }
 
+/// Progress of a sync that has not reached the chain tip yet, see [Client::get_sync_progress].
+/// Displayed as `current_block/target_block`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncProgress {
+    /// Block the client is synced to
+    pub current_block: u32,
+    /// Chain tip the sync is heading to
+    pub target_block: u32,
+}
+
+impl fmt::Display for SyncProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.current_block, self.target_block)
+    }
+}
+
+/// Parameters of a state sync request sent to the node
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StateSyncRequest {
+    block_num: u32,
+    account_ids: Vec<AccountId>,
+    note_tags: Vec<u16>,
+    nullifiers_tags: Vec<u16>,
+}
+
+/// A state sync response requested ahead of the client applying the previous update, along with
+/// the request it answers
+struct PrefetchedStateSync {
+    request: StateSyncRequest,
+    response: Result<StateSyncInfo, RpcApiError>,
+}
+
+/// RPC client the state sync responses are prefetched with, a clone of the client's own
+#[cfg(not(any(test, feature = "mock")))]
+type SyncRpcApi = super::rpc_client::RpcClient;
+#[cfg(any(test, feature = "mock"))]
+type SyncRpcApi = crate::mock::MockRpcApi;
+
+/// State sync responses requested by a background task while the client verifies and applies the
+/// previous updates, see [prefetch_state_syncs]. The task is stopped when the pipeline is dropped.
+struct SyncPipeline {
+    receiver: mpsc::Receiver<PrefetchedStateSync>,
+    task: JoinHandle<()>,
+}
+
+impl SyncPipeline {
+    /// Starts requesting the state sync responses following `request` included
+    fn start(rpc_api: SyncRpcApi, request: StateSyncRequest) -> Self {
+        let (sender, receiver) = mpsc::channel(SYNC_PIPELINE_DEPTH);
+        let task = tokio::spawn(prefetch_state_syncs(rpc_api, request, sender));
+        Self { receiver, task }
+    }
+
+    /// Returns the next prefetched response if it answers `request`. Otherwise, the prefetched
+    /// response is discarded, as are the ones requested after it, and `None` is returned.
+    async fn next_response(&mut self, request: &StateSyncRequest) -> Option<StateSyncInfo> {
+        let prefetched = self.receiver.recv().await?;
+        match prefetched.response {
+            Ok(response) if prefetched.request == *request => Some(response),
+            Ok(_) => {
+                debug!("Discarding outdated prefetched state sync response");
+                None
+            }
+            Err(err) => {
+                debug!("Discarding failed prefetched state sync response: {err}");
+                None
+            }
+        }
+    }
+}
+
+impl Drop for SyncPipeline {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
 // CONSTANTS
 // ================================================================================================
 
+/// Number of state sync responses requested ahead of the update the client is applying.
+const SYNC_PIPELINE_DEPTH: usize = 4;
+
 /// The number of bits to shift identifiers for in use of filters.
 pub const FILTER_ID_SHIFT: u8 = 48;
 
+/// Depth of the tree of account states committed to by the account root of block headers, whose
+/// leaves are indexed by account ID.
+pub const ACCOUNT_TREE_DEPTH: usize = 64;
+
 impl Client {
     // SYNC STATE
     // --------------------------------------------------------------------------------------------
@@ -36,14 +138,81 @@ impl Client {
         self.store.get_sync_height().map_err(|err| err.into())
     }
 
+    /// Returns how far the last sync got if it was interrupted before reaching the chain tip, or
+    /// `None` if it completed.
+    ///
+    /// Every sync update is applied along with the sync height, so the next sync resumes from the
+    /// last applied block. While syncing, the same progress is reported by
+    /// [ClientEvent::SyncProgressed] events.
+    pub fn get_sync_progress(&self) -> Result<Option<SyncProgress>, ClientError> {
+        let target_block = match self.store.get_sync_target()? {
+            Some(target_block) => target_block,
+            None => return Ok(None),
+        };
+
+        Ok(Some(SyncProgress {
+            current_block: self.store.get_sync_height()?,
+            target_block,
+        }))
+    }
+
     /// Returns the list of note tags tracked by the client.
     pub fn get_note_tags(&self) -> Result<Vec<u64>, ClientError> {
         self.store.get_note_tags().map_err(|err| err.into())
     }
 
+    /// Returns every registration of the note tags tracked by the client, along with what
+    /// registered them and their label.
+    pub fn get_note_tag_records(&self) -> Result<Vec<NoteTagRecord>, ClientError> {
+        self.store.get_note_tag_records().map_err(|err| err.into())
+    }
+
     /// Adds a note tag for the client to track.
     pub fn add_note_tag(&mut self, tag: u64) -> Result<(), ClientError> {
-        match self.store.add_note_tag(tag).map_err(|err| err.into()) {
+        self.add_user_note_tag(tag, None)
+    }
+
+    /// Adds a note tag for the client to track, along with a label describing it.
+    pub fn add_labeled_note_tag(&mut self, tag: u64, label: &str) -> Result<(), ClientError> {
+        self.add_user_note_tag(tag, Some(label))
+    }
+
+    /// Removes a note tag so that the client stops tracking it. This also drops the registrations
+    /// made automatically for imported notes.
+    pub fn remove_note_tag(&mut self, tag: u64) -> Result<(), ClientError> {
+        match self.store.remove_note_tag(tag).map_err(|err| err.into()) {
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                warn!("Tag {} is not being tracked", tag);
+                Ok(())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Registers the note tags targeting the specified account, so that notes sent to it, such
+    /// as P2ID notes, are retrieved while syncing. Tags are registered automatically for accounts
+    /// created by the client, and should be registered this way for imported accounts.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the account is not stored in the client.
+    pub fn register_account_tags(&mut self, account_id: AccountId) -> Result<(), ClientError> {
+        self.store.get_account_stub_by_id(account_id)?;
+        self.store.add_note_tag(
+            account_note_tag(account_id),
+            NoteTagSource::Account(account_id),
+            None,
+        )?;
+        Ok(())
+    }
+
+    fn add_user_note_tag(&mut self, tag: u64, label: Option<&str>) -> Result<(), ClientError> {
+        match self
+            .store
+            .add_note_tag(tag, NoteTagSource::User, label)
+            .map_err(|err| err.into())
+        {
             Ok(true) => Ok(()),
             Ok(false) => {
                 warn!("Tag {} is already being tracked", tag);
@@ -58,22 +227,106 @@ impl Client {
     ///
     /// Returns the block number the client has been synced to.
     pub async fn sync_state(&mut self) -> Result<u32, ClientError> {
+        self.sync(None).await
+    }
+
+    /// Syncs the client's state with the current state of the Miden network, only looking for
+    /// updates relevant to the specified accounts. Note tag filters, nullifier checks and account
+    /// state verification are limited to these accounts, which makes syncing faster for stores
+    /// with many accounts.
+    ///
+    /// The client-wide sync height and chain data still move forward, so notes for other accounts
+    /// committed in the blocks synced this way are not retrieved yet. Their own sync height,
+    /// returned by [Client::get_account_sync_height], is left behind, and the next sync of every
+    /// account catches them up from it.
+    ///
+    /// Returns the block number the client has been synced to.
+    pub async fn sync_state_for_accounts(
+        &mut self,
+        account_ids: &[AccountId],
+    ) -> Result<u32, ClientError> {
+        self.sync(Some(account_ids)).await
+    }
+
+    /// Returns the block number the specified account was last synced to, or `None` if it was
+    /// never synced.
+    pub fn get_account_sync_height(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<u32>, ClientError> {
+        self.store
+            .get_account_sync_height(account_id)
+            .map_err(|err| err.into())
+    }
+
+    #[instrument(skip_all)]
+    async fn sync(&mut self, account_ids: Option<&[AccountId]>) -> Result<u32, ClientError> {
+        let started_at = Instant::now();
         self.ensure_genesis_in_place().await?;
+        self.rollback_reorganized_blocks().await?;
+        let initial_block_num = self.store.get_sync_height()?;
+        if let Some(progress) = self.get_sync_progress()? {
+            info!("Resuming interrupted sync at block {progress}");
+        }
+        if account_ids.is_none() {
+            self.catch_up_lagging_accounts(initial_block_num).await?;
+        }
+
+        // each step is applied atomically, so a failed step can be retried from the last applied
+        // block. The responses to the next steps are requested while the current one is verified
+        // and persisted
+        let mut attempt = 1;
+        let mut pipeline = None;
         loop {
-            let response = self.sync_state_once().await?;
-            if let SyncStatus::SyncedToLastBlock(v) = response {
-                return Ok(v);
+            match self.sync_state_once(account_ids, &mut pipeline).await {
+                Ok(SyncStatus::SyncedToLastBlock(v)) => {
+                    // notes imported after their block was synced past can only be consumed once
+                    // the block is authenticated. Failing to do so does not invalidate the sync
+                    if let Err(err) = self.authenticate_committed_note_blocks().await {
+                        warn!("Failed to authenticate the blocks of committed notes: {err}");
+                    }
+                    match self.store.prune_irrelevant_mmr_nodes() {
+                        Ok(0) => {}
+                        Ok(removed) => debug!("Pruned {removed} chain MMR nodes"),
+                        Err(err) => warn!("Failed to prune the chain MMR: {err}"),
+                    }
+                    metrics::record_sync(started_at.elapsed(), v.saturating_sub(initial_block_num));
+                    return Ok(v);
+                }
+                Ok(SyncStatus::SyncedToBlock(_)) => attempt = 1,
+                Err(err) => match self.retry_policy.retry_delay(&err, attempt) {
+                    Some(delay) => {
+                        warn!("Sync step failed ({err}), retrying in {delay:?}");
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                    }
+                    None => return Err(err),
+                },
             }
         }
     }
 
-    /// Attempts to retrieve the genesis block from the store. If not found,
-    /// it requests it from the node and store it.
+    /// Attempts to retrieve the genesis block from the store. If not found, it requests it from
+    /// the node and stores it. Otherwise, it checks that the node's genesis block is the stored
+    /// one, so that a store is never synced against a different network.
+    ///
+    /// # Errors
+    /// Returns [ClientError::NetworkMismatch] if the node's genesis block is not the stored one,
+    /// which happens when the node was reset or the client is pointed to another network.
     async fn ensure_genesis_in_place(&mut self) -> Result<(), ClientError> {
         let genesis = self.store.get_block_header_by_num(0);
 
         match genesis {
-            Ok(_) => Ok(()),
+            Ok((genesis_block, _)) => {
+                let node_genesis_block = self.get_node_block_header(0).await?;
+                if genesis_block.hash() != node_genesis_block.hash() {
+                    return Err(ClientError::NetworkMismatch(
+                        genesis_block.hash(),
+                        node_genesis_block.hash(),
+                    ));
+                }
+                Ok(())
+            }
             Err(StoreError::BlockHeaderNotFound(0)) => self.retrieve_and_store_genesis().await,
             Err(err) => Err(ClientError::StoreError(err)),
         }
@@ -82,12 +335,9 @@ impl Client {
     /// Calls `get_block_header_by_number` requesting the genesis block and storing it
     /// in the local database
     async fn retrieve_and_store_genesis(&mut self) -> Result<(), ClientError> {
-        let genesis_block = self
-            .rpc_api
-            .get_block_header_by_number(GetBlockHeaderByNumberRequest { block_num: Some(0) })
-            .await?;
+        let genesis_block = self.get_node_block_header(0).await?;
 
-        let tx = self.store.db.transaction()?;
+        let tx = self.store.write_transaction()?;
 
         Store::insert_block_header(
             &tx,
@@ -100,134 +350,835 @@ impl Client {
         Ok(())
     }
 
-    async fn sync_state_once(&mut self) -> Result<SyncStatus, ClientError> {
+    /// Checks whether the block the client is synced to is still part of the node's chain. If the
+    /// chain was reorganized since the last sync, the client's state is rolled back to the most
+    /// recent stored block that is still part of it, so that the following sync starts from there.
+    ///
+    /// The genesis block is checked against the node's beforehand by
+    /// [Client::ensure_genesis_in_place], so a matching block is always found.
+    async fn rollback_reorganized_blocks(&mut self) -> Result<(), ClientError> {
+        let sync_height = self.store.get_sync_height()?;
+        if sync_height == 0 || self.is_block_in_node_chain(sync_height).await? {
+            return Ok(());
+        }
+
+        for block_num in self.store.get_block_numbers_below(sync_height)? {
+            if self.is_block_in_node_chain(block_num).await? {
+                warn!(
+                    "Chain reorganization detected, rolling back the client's state from block {} to block {}",
+                    sync_height, block_num
+                );
+                return self
+                    .store
+                    .rollback_to_block(block_num)
+                    .map_err(ClientError::StoreError);
+            }
+        }
+
+        let (genesis_block, _) = self.store.get_block_header_by_num(0)?;
+        let node_genesis_block = self.get_node_block_header(0).await?;
+        Err(ClientError::NetworkMismatch(
+            genesis_block.hash(),
+            node_genesis_block.hash(),
+        ))
+    }
+
+    /// Returns whether the stored header of the specified block matches the node's
+    async fn is_block_in_node_chain(&mut self, block_num: u32) -> Result<bool, ClientError> {
+        let (block_header, _) = self.store.get_block_header_by_num(block_num)?;
+        let node_block_header = self.get_node_block_header(block_num).await?;
+
+        Ok(block_header.hash() == node_block_header.hash())
+    }
+
+    /// Requests the header of the specified block from the node
+    async fn get_node_block_header(&mut self, block_num: u32) -> Result<BlockHeader, ClientError> {
+        Ok(self
+            .rpc_api
+            .get_block_header_by_number(GetBlockHeaderByNumberRequest {
+                block_num: Some(block_num),
+                include_mmr_proof: None,
+            })
+            .await?)
+    }
+
+    /// Requests and applies the next state sync update, limited to the specified accounts if any.
+    ///
+    /// The next response of `pipeline` is used instead of requesting a new one if it answers the
+    /// request the client would send. Otherwise, the pipeline is restarted from the next step
+    /// unless the chain tip is reached, so that the following responses are requested while the
+    /// update is verified and persisted.
+    #[instrument(skip_all, fields(block_num))]
+    async fn sync_state_once(
+        &mut self,
+        account_ids: Option<&[AccountId]>,
+        pipeline: &mut Option<SyncPipeline>,
+    ) -> Result<SyncStatus, ClientError> {
         let current_block_num = self.store.get_sync_height()?;
+        Span::current().record("block_num", current_block_num);
 
-        let accounts: Vec<AccountStub> = self
-            .store
-            .get_accounts()?
-            .into_iter()
-            .map(|(acc_stub, _)| acc_stub)
-            .collect();
+        let accounts: Vec<AccountStub> = match account_ids {
+            Some(account_ids) => account_ids
+                .iter()
+                .map(|account_id| Ok(self.store.get_account_stub_by_id(*account_id)?.0))
+                .collect::<Result<_, ClientError>>()?,
+            None => self
+                .store
+                .get_accounts()?
+                .into_iter()
+                .map(|(acc_stub, _)| acc_stub)
+                .collect(),
+        };
 
-        let note_tags: Vec<u16> = accounts
+        // The node filters notes by the high bits of their tags. When syncing a subset of the
+        // accounts, only the tags targeting them are requested
+        let mut note_tags: BTreeSet<u16> = accounts
             .iter()
-            .map(|acc| ((u64::from(acc.id()) >> FILTER_ID_SHIFT) as u16))
+            .map(|acc| note_tag_prefix(account_note_tag(acc.id())))
             .collect();
+        if account_ids.is_none() {
+            note_tags.extend(self.store.get_note_tags()?.into_iter().map(note_tag_prefix));
+        }
+        let note_tags: Vec<u16> = note_tags.into_iter().collect();
 
-        let nullifiers_tags: Vec<u16> = self
-            .store
-            .get_unspent_input_note_nullifiers()?
-            .iter()
-            .map(|nullifier| (nullifier[3].as_int() >> FILTER_ID_SHIFT) as u16)
-            .collect();
+        let mut nullifiers = match account_ids {
+            Some(account_ids) => self.get_unspent_input_note_nullifiers_for(account_ids)?,
+            None => self.store.get_unspent_input_note_nullifiers()?,
+        };
+        let mut check_heights = self.store.get_nullifier_check_heights()?;
 
-        // Send request
-        let account_ids: Vec<AccountId> = accounts.iter().map(|acc| acc.id()).collect();
-        let response = self
-            .rpc_api
-            .sync_state(
-                current_block_num,
-                &account_ids,
-                &note_tags,
-                &nullifiers_tags,
-            )
-            .await?;
+        // Like user note tags, the watched nullifiers are only checked when syncing every account
+        let mut watched_nullifiers = BTreeSet::new();
+        if account_ids.is_none() {
+            for watched in self.store.get_watched_nullifiers()? {
+                if watched.consumed_height.is_some() || nullifiers.contains(&watched.nullifier) {
+                    continue;
+                }
+                check_heights.insert(watched.nullifier, watched.checked_height);
+                nullifiers.push(watched.nullifier);
+                watched_nullifiers.insert(watched.nullifier);
+            }
+        }
+
+        // Nullifiers checked up to an earlier block, e.g. because previous syncs were limited to
+        // other accounts, are caught up separately once the next block is known
+        let (nullifiers, lagging_nullifiers): (Vec<Digest>, Vec<Digest>) =
+            nullifiers.into_iter().partition(|nullifier| {
+                check_heights
+                    .get(nullifier)
+                    .map_or(true, |check_height| *check_height >= current_block_num)
+            });
+        let nullifiers_tags = nullifier_prefixes(&nullifiers, self.nullifier_prefix_bits);
+        let (request_nullifiers_tags, remaining_nullifiers_tags) =
+            nullifiers_tags.split_at(nullifiers_tags.len().min(self.nullifier_chunk_size));
+
+        // Send request, unless its response was already requested while applying the previous
+        // update
+        let request = StateSyncRequest {
+            block_num: current_block_num,
+            account_ids: accounts.iter().map(|acc| acc.id()).collect(),
+            note_tags,
+            nullifiers_tags: request_nullifiers_tags.to_vec(),
+        };
+        let prefetched = match pipeline.as_mut() {
+            Some(pipeline) => pipeline.next_response(&request).await,
+            None => None,
+        };
+        let response = match prefetched {
+            Some(response) => response,
+            None => {
+                // the responses requested after an outdated one are outdated as well
+                *pipeline = None;
+                self.rpc_api
+                    .sync_state(
+                        request.block_num,
+                        &request.account_ids,
+                        &request.note_tags,
+                        &request.nullifiers_tags,
+                    )
+                    .await?
+            }
+        };
+        let account_ids = &request.account_ids;
 
         // We don't need to continue if the chain has not advanced
         if response.block_header.block_num() == current_block_num {
             return Ok(SyncStatus::SyncedToLastBlock(current_block_num));
         }
 
-        let committed_notes =
+        // Build PartialMmr with current data and apply updates, which the new block header must
+        // commit to before anything else is derived from the response
+        let (current_block, has_relevant_notes) =
+            self.store.get_block_header_by_num(current_block_num)?;
+        let (new_peaks, new_authentication_nodes) = apply_mmr_changes(
+            self.build_current_partial_mmr()?,
+            response.mmr_delta,
+            current_block,
+            has_relevant_notes,
+        )?;
+        validate_block_header(
+            &current_block,
+            &response.block_header,
+            response.chain_tip,
+            &new_peaks,
+        )?;
+
+        let account_proofs = self
+            .get_account_proofs(
+                &response.account_hash_updates,
+                account_ids,
+                &response.block_header,
+            )
+            .await?;
+
+        let committed_expected_note_ids = self
+            .get_committed_expected_note_ids(&response.note_inclusions, &response.block_header)?;
+        let (committed_notes, quarantined_notes) =
             self.build_inclusion_proofs(response.note_inclusions, &response.block_header)?;
+        metrics::record_notes_discovered(committed_notes.len());
+        for quarantined_note in quarantined_notes.iter() {
+            warn!(
+                "Quarantined note {} after failing to build or verify its inclusion proof: {}",
+                quarantined_note.note_id.inner(),
+                quarantined_note.reason
+            );
+        }
+
+        // Accounts whose on-chain hash differs from the local one were updated from another
+        // store. They are flagged instead of failing the sync, so that the other accounts keep
+        // being synced and transactions against them are rejected before reaching the executor.
+        // The state a pending local transaction results in is not a divergence, as it is only
+        // the first of several pipelined transactions being committed
+        let uncommitted_transactions =
+            self.store.get_transactions(TransactionFilter::Uncomitted)?;
+        let (desynchronized_accounts, resynchronized_accounts) = find_desynchronized_accounts(
+            &response.account_hash_updates,
+            &accounts,
+            &uncommitted_transactions,
+            response.block_header.block_num(),
+        );
+        for account in desynchronized_accounts.iter() {
+            warn!(
+                "The on-chain state of account {} ({}) differs from the local one ({}), it was \
+                probably updated from another store. Import an up-to-date account file with \
+                `miden-client account import` before executing transactions against it",
+                account.account_id, account.remote_hash, account.local_hash
+            );
+        }
 
-        // Check if the returned account hashes match latest account hashes in the database
-        check_account_hashes(&response.account_hash_updates, &accounts)?;
+        // Check the nullifiers that did not fit in the request, and catch up the lagging ones from
+        // the block each of them was last checked at
+        let block_num = response.block_header.block_num();
+        let mut received_nullifiers: Vec<(Digest, u32)> = response.nullifiers;
+        received_nullifiers.extend(
+            self.fetch_nullifiers_in_chunks(
+                current_block_num,
+                block_num,
+                remaining_nullifiers_tags,
+            )
+            .await?,
+        );
+        let mut lagging_nullifiers_by_height: BTreeMap<u32, Vec<Digest>> = BTreeMap::new();
+        for nullifier in lagging_nullifiers.iter() {
+            lagging_nullifiers_by_height
+                .entry(check_heights[nullifier])
+                .or_default()
+                .push(*nullifier);
+        }
+        for (check_height, nullifiers) in lagging_nullifiers_by_height {
+            let nullifiers_tags = nullifier_prefixes(&nullifiers, self.nullifier_prefix_bits);
+            received_nullifiers.extend(
+                self.fetch_nullifiers_in_chunks(check_height, block_num, &nullifiers_tags)
+                    .await?,
+            );
+        }
+
+        // The nullifiers of the notes committed by this update were not requested, as the notes
+        // were not committed yet, so they are checked over the blocks of the update as well in
+        // case the notes were consumed right away
+        let committed_note_nullifiers: Vec<Digest> = committed_notes
+            .iter()
+            .map(|(note_id, _)| {
+                Ok(self
+                    .store
+                    .get_input_note(*note_id)?
+                    .note()
+                    .nullifier()
+                    .inner())
+            })
+            .collect::<Result<_, ClientError>>()?;
+        if !committed_note_nullifiers.is_empty() {
+            let nullifiers_tags =
+                nullifier_prefixes(&committed_note_nullifiers, self.nullifier_prefix_bits);
+            received_nullifiers.extend(
+                self.fetch_nullifiers_in_chunks(current_block_num, block_num, &nullifiers_tags)
+                    .await?,
+            );
+        }
+        let checked_nullifiers: Vec<Digest> = nullifiers
+            .into_iter()
+            .chain(lagging_nullifiers)
+            .chain(committed_note_nullifiers.iter().copied())
+            .collect();
 
         // Derive new nullifiers data
-        let new_nullifiers = self.get_new_nullifiers(response.nullifiers)?;
+        let consumed_watched_nullifiers: Vec<(Digest, u32)> = received_nullifiers
+            .iter()
+            .filter(|(nullifier, _)| watched_nullifiers.contains(nullifier))
+            .copied()
+            .collect::<BTreeMap<Digest, u32>>()
+            .into_iter()
+            .collect();
+        let new_nullifiers = self.get_new_nullifiers(
+            received_nullifiers
+                .into_iter()
+                .map(|(nullifier, _)| nullifier)
+                .collect(),
+            &committed_note_nullifiers,
+        )?;
+
+        // Gather the events the updates result in while the previous state is still stored
+        let mut events: Vec<ClientEvent> = committed_notes
+            .iter()
+            .map(|(note_id, inclusion_proof)| ClientEvent::NoteCommitted {
+                note_id: *note_id,
+                block_num: inclusion_proof.origin().block_num,
+            })
+            .collect();
+        events.extend(committed_expected_note_ids.iter().map(|note_id| {
+            ClientEvent::NoteCommitted {
+                note_id: *note_id,
+                block_num,
+            }
+        }));
+        let committed_note_ids: Vec<NoteId> = committed_notes
+            .iter()
+            .map(|(note_id, _)| *note_id)
+            .collect();
+        events.extend(self.get_consumed_note_events(
+            &new_nullifiers,
+            &committed_note_ids,
+            block_num,
+        )?);
+        let closed_orders = self.get_closed_orders(&new_nullifiers)?;
+        events.extend(
+            consumed_watched_nullifiers
+                .iter()
+                .map(
+                    |(nullifier, block_num)| ClientEvent::WatchedNullifierConsumed {
+                        nullifier: *nullifier,
+                        block_num: *block_num,
+                    },
+                ),
+        );
+        events.extend(desynchronized_accounts.iter().map(|account| {
+            ClientEvent::AccountStateDesynchronized {
+                account_id: account.account_id,
+                local_hash: account.local_hash,
+                remote_hash: account.remote_hash,
+                block_num,
+            }
+        }));
 
-        // Build PartialMmr with current data and apply updates
-        let (new_peaks, new_authentication_nodes) = {
-            let current_partial_mmr = self.build_current_partial_mmr()?;
+        // Once the update is applied, the nullifiers checked by this step that were not consumed
+        // are checked from the new block on, so the next request is expected to only differ from
+        // this one by its block. The responses to it and to the following requests are compared
+        // to the actual requests before being used
+        let synced_to_chain_tip = response.chain_tip == block_num;
+        let next_request = (!synced_to_chain_tip && pipeline.is_none()).then(|| {
+            let unspent_nullifiers: Vec<Digest> = checked_nullifiers
+                .iter()
+                .filter(|nullifier| {
+                    !new_nullifiers.contains(nullifier)
+                        && !consumed_watched_nullifiers
+                            .iter()
+                            .any(|(consumed, _)| consumed == *nullifier)
+                })
+                .copied()
+                .collect();
+            let nullifiers_tags =
+                nullifier_prefixes(&unspent_nullifiers, self.nullifier_prefix_bits);
+            StateSyncRequest {
+                block_num,
+                account_ids: request.account_ids.clone(),
+                note_tags: request.note_tags.clone(),
+                nullifiers_tags: nullifiers_tags
+                    .chunks(self.nullifier_chunk_size)
+                    .next()
+                    .unwrap_or_default()
+                    .to_vec(),
+            }
+        });
 
-            let (current_block, has_relevant_notes) =
-                self.store.get_block_header_by_num(current_block_num)?;
+        // Apply received and computed updates to the store while the next responses are
+        // requested. Yielding before writing lets the pipeline send its request first, as writing
+        // does not await
+        if let Some(next_request) = next_request {
+            *pipeline = Some(SyncPipeline::start(self.rpc_api.clone(), next_request));
+            tokio::task::yield_now().await;
+        }
+        let watched_accounts = self.get_watched_accounts()?;
+        self.store.apply_state_sync(
+            account_ids,
+            response.block_header,
+            response.chain_tip,
+            new_nullifiers,
+            &checked_nullifiers,
+            &consumed_watched_nullifiers,
+            &closed_orders,
+            committed_notes,
+            &committed_expected_note_ids,
+            &quarantined_notes,
+            &account_proofs,
+            &desynchronized_accounts,
+            &resynchronized_accounts,
+            new_peaks,
+            &new_authentication_nodes,
+        )?;
 
-            apply_mmr_changes(
-                current_partial_mmr,
-                response.mmr_delta,
-                current_block,
-                has_relevant_notes,
-            )?
-        };
+        self.notify_account_watchers(watched_accounts)?;
 
-        // Apply received and computed updates to the store
-        self.store
-            .apply_state_sync(
-                response.block_header,
-                new_nullifiers,
-                committed_notes,
-                new_peaks,
-                &new_authentication_nodes,
-            )
-            .map_err(ClientError::StoreError)?;
+        for transaction in uncommitted_transactions {
+            if let TransactionStatus::Committed(block_num) =
+                self.store.get_transaction_status(transaction.id)?
+            {
+                events.push(ClientEvent::TransactionCommitted {
+                    transaction_id: transaction.id,
+                    block_num,
+                });
+            }
+        }
+        if !synced_to_chain_tip {
+            events.push(ClientEvent::SyncProgressed {
+                block_num,
+                target_block_num: response.chain_tip,
+            });
+        }
+        self.emit_events(events);
 
-        if response.chain_tip == response.block_header.block_num() {
+        if synced_to_chain_tip {
             Ok(SyncStatus::SyncedToLastBlock(response.chain_tip))
         } else {
-            Ok(SyncStatus::SyncedToBlock(response.block_header.block_num()))
+            Ok(SyncStatus::SyncedToBlock(block_num))
         }
     }
 
+    // SYNC QUARANTINE
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the notes that were quarantined while syncing because their inclusion proof could
+    /// not be built.
+    pub fn get_quarantined_notes(&self) -> Result<Vec<QuarantinedNote>, ClientError> {
+        self.store.get_quarantined_notes().map_err(|err| err.into())
+    }
+
+    /// Requests the inclusion of the quarantined notes from the node again, and tries to build
+    /// their inclusion proofs from the paths it returns. Notes whose proof can now be built are
+    /// marked as committed and released from the quarantine, while the rest are kept with an
+    /// updated reason.
+    ///
+    /// Returns the IDs of the released notes.
+    pub async fn retry_quarantined_notes(&mut self) -> Result<Vec<NoteId>, ClientError> {
+        let mut released_notes = Vec::new();
+        for quarantined_note in self.store.get_quarantined_notes()? {
+            match self.refetch_inclusion_proof(&quarantined_note).await {
+                Ok(inclusion_proof) => {
+                    self.store
+                        .release_quarantined_note(quarantined_note.note_id, &inclusion_proof)?;
+                    self.emit_events([ClientEvent::NoteCommitted {
+                        note_id: quarantined_note.note_id,
+                        block_num: quarantined_note.block_num,
+                    }]);
+                    released_notes.push(quarantined_note.note_id);
+                }
+                Err(err) => self.store.quarantine_note(&QuarantinedNote {
+                    reason: err.to_string(),
+                    ..quarantined_note
+                })?,
+            }
+        }
+
+        Ok(released_notes)
+    }
+
+    /// Requests the path of a quarantined note from the node and builds its inclusion proof
+    /// against the block header the client stored for the note's block. The node responds with
+    /// the first block after the requested one that has notes with the requested tags, so the
+    /// block preceding the note's block is requested.
+    async fn refetch_inclusion_proof(
+        &mut self,
+        quarantined_note: &QuarantinedNote,
+    ) -> Result<NoteInclusionProof, ClientError> {
+        let (block_header, _) = self
+            .store
+            .get_block_header_by_num(quarantined_note.block_num)?;
+        let metadata = *self
+            .store
+            .get_input_note(quarantined_note.note_id)?
+            .note()
+            .metadata();
+
+        let note_tag = note_tag_prefix(u64::from(metadata.tag()));
+        let response = self
+            .rpc_api
+            .sync_state(
+                quarantined_note.block_num.saturating_sub(1),
+                &Vec::new(),
+                &[note_tag],
+                &[],
+            )
+            .await?;
+
+        let committed_note = response
+            .note_inclusions
+            .iter()
+            .find(|note| *note.note_id() == quarantined_note.note_id)
+            .filter(|_| response.block_header.block_num() == quarantined_note.block_num)
+            .ok_or_else(|| {
+                ClientError::InvalidNote(format!(
+                    "the node did not report note {} as committed in block {}",
+                    quarantined_note.note_id.inner(),
+                    quarantined_note.block_num
+                ))
+            })?;
+        let located_note = self.locate_committed_note(committed_note, &block_header);
+
+        build_inclusion_proof(&located_note, &metadata, &block_header)
+    }
+
+    // WATCHED NULLIFIERS
+    // --------------------------------------------------------------------------------------------
+
+    /// Watches the nullifier of a note the client does not hold, such as a note it created for
+    /// another account. Syncs of every account check the nullifier against the node from block
+    /// `from_block_num` on, and emit a [ClientEvent::WatchedNullifierConsumed] with the block the
+    /// note was consumed in once they find it.
+    ///
+    /// Returns false if the nullifier was already watched, in which case it is left unchanged.
+    pub fn watch_nullifier(
+        &mut self,
+        nullifier: Digest,
+        label: Option<&str>,
+        from_block_num: u32,
+    ) -> Result<bool, ClientError> {
+        self.store
+            .watch_nullifier(nullifier, label, from_block_num)
+            .map_err(|err| err.into())
+    }
+
+    /// Stops watching a nullifier
+    pub fn unwatch_nullifier(&mut self, nullifier: Digest) -> Result<(), ClientError> {
+        if !self.store.unwatch_nullifier(nullifier)? {
+            warn!("Nullifier {} is not being watched", nullifier);
+        }
+        Ok(())
+    }
+
+    /// Returns the watched nullifiers, along with the block their note was consumed in if syncing
+    /// found it consumed
+    pub fn get_watched_nullifiers(&self) -> Result<Vec<WatchedNullifier>, ClientError> {
+        self.store
+            .get_watched_nullifiers()
+            .map_err(|err| err.into())
+    }
+
     // HELPERS
     // --------------------------------------------------------------------------------------------
 
+    /// Returns a [ClientEvent::NoteConsumed] event for each tracked note with one of the provided
+    /// nullifiers, among the committed notes and the ones in `committed_note_ids`, which the update
+    /// being applied commits. Notes are considered consumed by the client if one of its
+    /// transactions used them as input, otherwise their event is followed by a
+    /// [ClientEvent::NoteConsumedExternally] one.
+    fn get_consumed_note_events(
+        &self,
+        nullifiers: &[Digest],
+        committed_note_ids: &[NoteId],
+        block_num: u32,
+    ) -> Result<Vec<ClientEvent>, ClientError> {
+        if nullifiers.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // transactions record the IDs of the notes they consumed, not their nullifiers
+        let client_note_ids: BTreeSet<Digest> = self
+            .store
+            .get_transactions(TransactionFilter::All)?
+            .into_iter()
+            .flat_map(|transaction| transaction.input_note_nullifiers)
+            .collect();
+
+        let mut notes = self.store.get_input_notes(InputNoteFilter::Committed)?;
+        for note_id in committed_note_ids {
+            notes.push(self.store.get_input_note(*note_id)?);
+        }
+
+        let mut events = Vec::new();
+        for note in notes {
+            if !nullifiers.contains(&note.note().nullifier().inner()) {
+                continue;
+            }
+
+            let by_client = client_note_ids.contains(&note.note_id().inner());
+            events.push(ClientEvent::NoteConsumed {
+                note_id: note.note_id(),
+                block_num,
+                by_client,
+            });
+            if !by_client {
+                warn!(
+                    "Note {} was consumed in block {block_num} by a transaction the client did not execute",
+                    note.note_id().inner()
+                );
+                events.push(ClientEvent::NoteConsumedExternally {
+                    note_id: note.note_id(),
+                    block_num,
+                });
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Retrieves the notes committed up to block `sync_height` for the accounts whose own sync
+    /// height is behind it, because previous syncs were limited to other accounts or the accounts
+    /// were hidden while the client synced. Nullifiers are caught up by the regular sync, which
+    /// checks each of them from the block it was last checked at.
+    ///
+    /// The blocks the notes were committed in may not be tracked by the client, so the notes are
+    /// committed along with their full inclusion proof, the way imported notes are, and their
+    /// blocks authenticated once the sync completes.
+    async fn catch_up_lagging_accounts(&mut self, sync_height: u32) -> Result<(), ClientError> {
+        let mut lagging_accounts: BTreeMap<u32, Vec<AccountId>> = BTreeMap::new();
+        for (account_stub, _) in self.store.get_accounts()? {
+            match self.store.get_account_sync_height(account_stub.id())? {
+                Some(block_num) if block_num < sync_height => lagging_accounts
+                    .entry(block_num)
+                    .or_default()
+                    .push(account_stub.id()),
+                _ => {}
+            }
+        }
+
+        for (mut block_num, account_ids) in lagging_accounts {
+            info!(
+                "Catching up {} accounts from block {block_num} to block {sync_height}",
+                account_ids.len()
+            );
+            let note_tags: Vec<u16> = account_ids
+                .iter()
+                .map(|account_id| note_tag_prefix(account_note_tag(*account_id)))
+                .collect::<BTreeSet<u16>>()
+                .into_iter()
+                .collect();
+
+            while block_num < sync_height {
+                let response = self
+                    .rpc_api
+                    .sync_state(block_num, &account_ids, &note_tags, &[])
+                    .await?;
+                let response_block_num = response.block_header.block_num();
+                // the remaining blocks hold no notes for the accounts, or are synced next
+                if response_block_num <= block_num || response_block_num > sync_height {
+                    break;
+                }
+
+                let committed_expected_note_ids = self.get_committed_expected_note_ids(
+                    &response.note_inclusions,
+                    &response.block_header,
+                )?;
+                let (committed_notes, quarantined_notes) =
+                    self.build_inclusion_proofs(response.note_inclusions, &response.block_header)?;
+                let committed_notes = committed_notes
+                    .into_iter()
+                    .map(|(note_id, inclusion_proof)| {
+                        let note = self.store.get_input_note_by_id(note_id)?;
+                        Ok(InputNoteRecord::new(
+                            note.note().clone(),
+                            Some(inclusion_proof),
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, ClientError>>()?;
+
+                self.store.apply_account_catch_up(
+                    &account_ids,
+                    response_block_num,
+                    &committed_notes,
+                    &committed_expected_note_ids,
+                    &quarantined_notes,
+                )?;
+                block_num = response_block_num;
+            }
+
+            self.store
+                .apply_account_catch_up(&account_ids, sync_height, &[], &[], &[])?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the nullifiers of the unspent notes that can be consumed by any of the specified
+    /// accounts
+    fn get_unspent_input_note_nullifiers_for(
+        &self,
+        account_ids: &[AccountId],
+    ) -> Result<Vec<Digest>, ClientError> {
+        let mut nullifiers = BTreeSet::new();
+        for account_id in account_ids {
+            let notes = self
+                .store
+                .get_input_notes(NoteFilter::new().consumable_by(*account_id))?;
+            nullifiers.extend(notes.iter().map(|note| note.note().nullifier().inner()));
+        }
+
+        Ok(nullifiers.into_iter().collect())
+    }
+
+    /// Requests and verifies the proofs of the updated states of the tracked on-chain accounts
+    /// against the account root of `block_header`, so that the reported account hashes are not
+    /// taken on trust.
+    async fn get_account_proofs(
+        &mut self,
+        account_hash_updates: &[(AccountId, Digest)],
+        tracked_accounts: &[AccountId],
+        block_header: &BlockHeader,
+    ) -> Result<Vec<AccountProof>, ClientError> {
+        let mut account_proofs = Vec::new();
+        // the account tree commits to the hash of off-chain accounts too
+        for (account_id, account_hash) in account_hash_updates
+            .iter()
+            .filter(|(account_id, _)| tracked_accounts.contains(account_id))
+        {
+            let (proof_block_header, proven_hash, merkle_path) = self
+                .rpc_api
+                .get_account_proof(*account_id, block_header.block_num())
+                .await?;
+            if proof_block_header.hash() != block_header.hash() {
+                return Err(ClientError::InvalidAccountProof(
+                    *account_id,
+                    format!(
+                        "the proof was made against another header of block {}",
+                        block_header.block_num()
+                    ),
+                ));
+            }
+            if proven_hash != *account_hash {
+                return Err(ClientError::InvalidAccountProof(
+                    *account_id,
+                    format!(
+                        "the proven hash {proven_hash} differs from the reported {account_hash}"
+                    ),
+                ));
+            }
+
+            account_proofs.push(verify_account_proof(
+                *account_id,
+                proven_hash,
+                merkle_path,
+                block_header,
+            )?);
+        }
+
+        Ok(account_proofs)
+    }
+
     /// Extracts information about notes that the client is interested in, creating the note inclusion
-    /// proof in order to correctly update store data
+    /// proof in order to correctly update store data.
+    ///
+    /// Notes whose inclusion proof can't be built, or doesn't verify against the block's note
+    /// root, are returned separately as [QuarantinedNote]s so that they don't prevent the rest of
+    /// the sync from being applied.
     fn build_inclusion_proofs(
         &self,
         committed_notes: Vec<CommittedNote>,
         block_header: &BlockHeader,
-    ) -> Result<Vec<(NoteId, NoteInclusionProof)>, ClientError> {
-        let pending_notes: Vec<NoteId> = self
+    ) -> Result<(Vec<(NoteId, NoteInclusionProof)>, Vec<QuarantinedNote>), ClientError> {
+        // expected notes, created by the client's own transactions, are reconciled the same way
+        let mut pending_notes = self
             .store
-            .get_input_notes(crate::store::notes::InputNoteFilter::Pending)?
+            .get_input_notes(crate::store::notes::InputNoteFilter::Pending)?;
+        pending_notes.extend(
+            self.store
+                .get_input_notes(crate::store::notes::InputNoteFilter::Expected)?,
+        );
+        let pending_notes: BTreeMap<NoteId, NoteMetadata> = pending_notes
             .iter()
-            .map(|n| n.note().id())
+            .map(|n| (n.note().id(), *n.note().metadata()))
             .collect();
 
-        committed_notes
+        let mut proven_notes = Vec::new();
+        let mut quarantined_notes = Vec::new();
+        for (commited_note, metadata) in committed_notes.iter().filter_map(|note| {
+            pending_notes
+                .get(note.note_id())
+                .map(|metadata| (note, metadata))
+        }) {
+            let quarantined_note = self.locate_committed_note(commited_note, block_header);
+            match build_inclusion_proof(&quarantined_note, metadata, block_header) {
+                Ok(proof) => proven_notes.push((quarantined_note.note_id, proof)),
+                Err(err) => quarantined_notes.push(QuarantinedNote {
+                    reason: err.to_string(),
+                    ..quarantined_note
+                }),
+            }
+        }
+
+        Ok((proven_notes, quarantined_notes))
+    }
+
+    /// Returns the notes expected by ID that were committed in the block. Their inclusion proofs
+    /// are checked against the block's note root using the metadata sent by the node, as the
+    /// client does not know the notes' details.
+    fn get_committed_expected_note_ids(
+        &self,
+        committed_notes: &[CommittedNote],
+        block_header: &BlockHeader,
+    ) -> Result<Vec<NoteId>, ClientError> {
+        let expected_note_ids: BTreeSet<NoteId> = self
+            .store
+            .get_expected_note_ids()?
+            .into_iter()
+            .filter(|record| record.commit_height.is_none())
+            .map(|record| record.note_id)
+            .collect();
+
+        let mut committed_note_ids = Vec::new();
+        for committed_note in committed_notes
             .iter()
-            .filter_map(|commited_note| {
-                if pending_notes.contains(commited_note.note_id()) {
-                    // FIXME: This removal is to accomodate a problem with how the node constructs paths where
-                    // they are constructed using note ID instead of authentication hash, so for now we remove the first
-                    // node here.
-                    //
-                    // See: https://github.com/0xPolygonMiden/miden-node/blob/main/store/src/state.rs#L274
-                    let mut merkle_path = commited_note.merkle_path().clone();
-                    if merkle_path.len() > 0 {
-                        let _ = merkle_path.remove(0);
-                    }
+            .filter(|note| expected_note_ids.contains(note.note_id()))
+        {
+            let located_note = self.locate_committed_note(committed_note, block_header);
+            match build_inclusion_proof(&located_note, &committed_note.metadata(), block_header) {
+                Ok(_) => committed_note_ids.push(located_note.note_id),
+                Err(err) => warn!(
+                    "Ignoring expected note {} as its inclusion proof does not verify: {err}",
+                    located_note.note_id.inner()
+                ),
+            }
+        }
 
-                    let note_id_and_proof = NoteInclusionProof::new(
-                        block_header.block_num(),
-                        block_header.sub_hash(),
-                        block_header.note_root(),
-                        commited_note.note_index().into(),
-                        merkle_path,
-                    )
-                    .map_err(ClientError::NoteError)
-                    .map(|proof| (*commited_note.note_id(), proof));
+        Ok(committed_note_ids)
+    }
 
-                    Some(note_id_and_proof)
-                } else {
-                    None
-                }
-            })
-            .collect()
+    /// Locates a note in the note tree of the block it was committed in, as a [QuarantinedNote]
+    /// without a reason from which its inclusion proof is built
+    fn locate_committed_note(
+        &self,
+        committed_note: &CommittedNote,
+        block_header: &BlockHeader,
+    ) -> QuarantinedNote {
+        let mut merkle_path = committed_note.merkle_path().clone();
+        if self.trim_note_paths {
+            trim_node_note_path(&mut merkle_path);
+        }
+
+        QuarantinedNote {
+            note_id: *committed_note.note_id(),
+            block_num: block_header.block_num(),
+            note_index: committed_note.note_index().into(),
+            note_path: merkle_path,
+            reason: String::new(),
+        }
     }
 
     /// Builds the current view of the chain's [PartialMmr]. Because we want to add all new
@@ -261,16 +1212,75 @@ impl Client {
         ))
     }
 
+    /// Requests the nullifiers matching `nullifiers_tags` created after block `from_block_num` and
+    /// up to block `to_block_num`, with no more than `nullifier_chunk_size` prefixes per request.
+    async fn fetch_nullifiers_in_chunks(
+        &mut self,
+        from_block_num: u32,
+        to_block_num: u32,
+        nullifiers_tags: &[u16],
+    ) -> Result<Vec<(Digest, u32)>, ClientError> {
+        let mut nullifiers = Vec::new();
+        for nullifiers_tags in nullifiers_tags.chunks(self.nullifier_chunk_size) {
+            nullifiers.extend(
+                self.fetch_nullifiers(from_block_num, to_block_num, nullifiers_tags)
+                    .await?,
+            );
+        }
+
+        Ok(nullifiers)
+    }
+
+    /// Requests the nullifiers matching `nullifiers_tags` created after block `from_block_num` and
+    /// up to block `to_block_num`, along with the block each of them was created in, with as many
+    /// sync requests as needed to cover the range.
+    async fn fetch_nullifiers(
+        &mut self,
+        from_block_num: u32,
+        to_block_num: u32,
+        nullifiers_tags: &[u16],
+    ) -> Result<Vec<(Digest, u32)>, ClientError> {
+        let mut nullifiers = Vec::new();
+        let mut block_num = from_block_num;
+        while block_num < to_block_num {
+            let response = self
+                .rpc_api
+                .sync_state(block_num, &Vec::<AccountId>::new(), &[], nullifiers_tags)
+                .await?;
+            nullifiers.extend(
+                response
+                    .nullifiers
+                    .into_iter()
+                    .filter(|(_, nullifier_block_num)| *nullifier_block_num <= to_block_num),
+            );
+
+            // the node answers up to the chain tip if no later block matches the request
+            if response.block_header.block_num() <= block_num {
+                break;
+            }
+            block_num = response.block_header.block_num();
+        }
+
+        Ok(nullifiers)
+    }
+
     /// Extracts information about nullifiers for unspent input notes that the client is tracking
-    /// from the received [SyncStateResponse]
-    fn get_new_nullifiers(&self, new_nullifiers: Vec<Digest>) -> Result<Vec<Digest>, ClientError> {
+    /// from the received [SyncStateResponse], including the notes the response commits, whose
+    /// nullifiers are `committed_note_nullifiers`
+    fn get_new_nullifiers(
+        &self,
+        new_nullifiers: Vec<Digest>,
+        committed_note_nullifiers: &[Digest],
+    ) -> Result<Vec<Digest>, ClientError> {
         // Get current unspent nullifiers
-        let nullifiers = self.store.get_unspent_input_note_nullifiers()?;
+        let mut nullifiers = self.store.get_unspent_input_note_nullifiers()?;
+        nullifiers.extend_from_slice(committed_note_nullifiers);
 
-        let new_nullifiers = new_nullifiers
+        let new_nullifiers: BTreeSet<Digest> = new_nullifiers
             .into_iter()
             .filter(|nullifier| nullifiers.contains(nullifier))
             .collect();
+        let new_nullifiers = new_nullifiers.into_iter().collect();
 
         Ok(new_nullifiers)
     }
@@ -279,6 +1289,197 @@ impl Client {
 // UTILS
 // --------------------------------------------------------------------------------------------
 
+/// Returns the 16-bit nullifier prefixes to request from the node in order to learn about the
+/// provided nullifiers while only revealing their `prefix_bits` most significant bits.
+///
+/// Every 16-bit prefix that shares those bits with one of the nullifiers is requested, so the node
+/// cannot tell which of them the client is interested in. The nullifiers returned for the extra
+/// prefixes are discarded when the sync response is applied.
+fn nullifier_prefixes(nullifiers: &[Digest], prefix_bits: u8) -> Vec<u16> {
+    let hidden_bits = 16 - prefix_bits.min(16) as u32;
+
+    nullifiers
+        .iter()
+        .map(|nullifier| (nullifier[3].as_int() >> FILTER_ID_SHIFT) as u32 >> hidden_bits)
+        .collect::<BTreeSet<u32>>()
+        .into_iter()
+        .flat_map(|prefix| {
+            let first = prefix << hidden_bits;
+            (first..first + (1 << hidden_bits)).map(|prefix| prefix as u16)
+        })
+        .collect()
+}
+
+/// Returns the tag of the notes targeting the specified account. Notes meant to be consumed by a
+/// single account, such as P2ID notes, are tagged with the ID of that account.
+pub fn account_note_tag(account_id: AccountId) -> u64 {
+    u64::from(account_id)
+}
+
+/// Returns the prefix of `tag` the node filters notes by when syncing
+fn note_tag_prefix(tag: u64) -> u16 {
+    (tag >> FILTER_ID_SHIFT) as u16
+}
+
+/// Requests the state sync updates starting with `request` and sends them to the client applying
+/// them, which holds back the requests once [SYNC_PIPELINE_DEPTH] responses wait to be applied.
+///
+/// Each next request is predicted from the previous response: it is sent from the block the
+/// response reaches, for the same accounts, note tags and nullifier prefixes. The client discards
+/// the responses to requests it would not send, such as when one of its nullifiers was consumed.
+/// Requesting stops at the chain tip, after a failed request, or once the client stops receiving.
+async fn prefetch_state_syncs(
+    mut rpc_api: SyncRpcApi,
+    mut request: StateSyncRequest,
+    sender: mpsc::Sender<PrefetchedStateSync>,
+) {
+    loop {
+        let response = rpc_api
+            .sync_state(
+                request.block_num,
+                &request.account_ids,
+                &request.note_tags,
+                &request.nullifiers_tags,
+            )
+            .await;
+        let next_block_num = match &response {
+            Ok(response) if response.block_header.block_num() < response.chain_tip => {
+                Some(response.block_header.block_num())
+            }
+            _ => None,
+        };
+
+        let prefetched = PrefetchedStateSync {
+            request: request.clone(),
+            response,
+        };
+        if sender.send(prefetched).await.is_err() {
+            return;
+        }
+        match next_block_num {
+            Some(block_num) => request.block_num = block_num,
+            None => return,
+        }
+    }
+}
+
+/// Builds the inclusion proof of a note reported as committed in the block with the provided
+/// header, after checking that the note's path leads to the block's note root
+fn build_inclusion_proof(
+    note: &QuarantinedNote,
+    metadata: &NoteMetadata,
+    block_header: &BlockHeader,
+) -> Result<NoteInclusionProof, ClientError> {
+    // the leaves of a block's note tree are the authentication hashes of its notes
+    let auth_hash = Hasher::merge(&[note.note_id.inner(), Word::from(*metadata).into()]);
+    if !note
+        .note_path
+        .verify(note.note_index, auth_hash, &block_header.note_root())
+    {
+        return Err(ClientError::InvalidNoteInclusionProof(
+            note.note_id,
+            block_header.block_num(),
+        ));
+    }
+
+    NoteInclusionProof::new(
+        block_header.block_num(),
+        block_header.sub_hash(),
+        block_header.note_root(),
+        note.note_index,
+        note.note_path.clone(),
+    )
+    .map_err(ClientError::NoteError)
+}
+
+/// Checks that `block_header`, received from the node when syncing from `current_block`, extends
+/// the client's chain: it must come after the current block without going past the chain tip,
+/// follow the current block if it is the next one, and commit to the chain MMR the client built by
+/// applying the received MMR delta.
+fn validate_block_header(
+    current_block: &BlockHeader,
+    block_header: &BlockHeader,
+    chain_tip: u32,
+    chain_mmr_peaks: &MmrPeaks,
+) -> Result<(), ClientError> {
+    let block_num = block_header.block_num();
+    let invalid = |reason: String| Err(ClientError::InvalidBlockHeaderReceived(block_num, reason));
+
+    if block_num <= current_block.block_num() {
+        return invalid(format!(
+            "expected a block after the client's block {}",
+            current_block.block_num()
+        ));
+    }
+    if block_num > chain_tip {
+        return invalid(format!("block is past the chain tip {chain_tip}"));
+    }
+    if block_num == current_block.block_num() + 1
+        && block_header.prev_hash() != current_block.hash()
+    {
+        return invalid(format!(
+            "previous block hash {} does not match the hash {} of block {}",
+            block_header.prev_hash(),
+            current_block.hash(),
+            current_block.block_num()
+        ));
+    }
+    if chain_mmr_peaks.num_leaves() != block_num as usize {
+        return invalid(format!(
+            "the chain MMR delta leads to a chain of {} blocks",
+            chain_mmr_peaks.num_leaves()
+        ));
+    }
+    let chain_root = chain_mmr_peaks.hash_peaks();
+    if block_header.chain_root() != chain_root {
+        return invalid(format!(
+            "chain root {} does not match the root {chain_root} of the client's chain MMR",
+            block_header.chain_root()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks that `merkle_path` leads from the leaf of `account_id`, holding `account_hash`, to the
+/// account root of `block_header`
+pub(crate) fn verify_account_proof(
+    account_id: AccountId,
+    account_hash: Digest,
+    merkle_path: MerklePath,
+    block_header: &BlockHeader,
+) -> Result<AccountProof, ClientError> {
+    if merkle_path.len() != ACCOUNT_TREE_DEPTH {
+        return Err(ClientError::InvalidAccountProof(
+            account_id,
+            format!(
+                "expected a path of {ACCOUNT_TREE_DEPTH} nodes, got {}",
+                merkle_path.len()
+            ),
+        ));
+    }
+    if !merkle_path.verify(
+        u64::from(account_id),
+        account_hash,
+        &block_header.account_root(),
+    ) {
+        return Err(ClientError::InvalidAccountProof(
+            account_id,
+            format!(
+                "the path does not lead to the account root of block {}",
+                block_header.block_num()
+            ),
+        ));
+    }
+
+    Ok(AccountProof {
+        account_id,
+        block_num: block_header.block_num(),
+        account_hash,
+        merkle_path,
+    })
+}
+
 /// Applies changes to the Mmr structure, storing authentication nodes for leaves we track
 /// and returns the updated [PartialMmr]
 fn apply_mmr_changes(
@@ -308,22 +1509,429 @@ fn apply_mmr_changes(
     Ok((partial_mmr.peaks(), new_authentication_nodes))
 }
 
-/// Validates account hash updates and returns an error if there is a mismatch.
-fn check_account_hashes(
+/// Splits the tracked accounts updated by the node at `block_num` into the ones whose hash
+/// differs from any of their local states, and the IDs of the ones matching a local state
+///
+/// The local states of an account are its latest stored state and the final states of its
+/// uncommitted transactions, any of which the node may report while they are being committed
+fn find_desynchronized_accounts(
     account_updates: &[(AccountId, Digest)],
     current_accounts: &[AccountStub],
-) -> Result<(), StoreError> {
+    uncommitted_transactions: &[TransactionRecord],
+    block_num: u32,
+) -> (Vec<DesynchronizedAccount>, Vec<AccountId>) {
+    let mut desynchronized_accounts = Vec::new();
+    let mut resynchronized_accounts = Vec::new();
     for (remote_account_id, remote_account_hash) in account_updates {
+        let local_account = match current_accounts
+            .iter()
+            .find(|acc| *remote_account_id == acc.id())
         {
-            if let Some(local_account) = current_accounts
-                .iter()
-                .find(|acc| *remote_account_id == acc.id())
-            {
-                if *remote_account_hash != local_account.hash() {
-                    return Err(StoreError::AccountHashMismatch(*remote_account_id));
-                }
-            }
+            Some(local_account) => local_account,
+            None => continue,
+        };
+        let matches_local_state = *remote_account_hash == local_account.hash()
+            || uncommitted_transactions.iter().any(|transaction| {
+                transaction.account_id == *remote_account_id
+                    && transaction.final_account_state == *remote_account_hash
+            });
+        if matches_local_state {
+            resynchronized_accounts.push(*remote_account_id);
+        } else {
+            desynchronized_accounts.push(DesynchronizedAccount {
+                account_id: *remote_account_id,
+                local_hash: local_account.hash(),
+                remote_hash: *remote_account_hash,
+                block_num,
+            });
         }
     }
-    Ok(())
+    (desynchronized_accounts, resynchronized_accounts)
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crypto::{
+        merkle::{EmptySubtreeRoots, MerklePath, MmrPeaks, PartialMmr},
+        Felt, FieldElement,
+    };
+    use miden_node_proto::{
+        block_header::BlockHeader as NodeBlockHeader,
+        mmr::MmrDelta,
+        requests::SyncStateRequest,
+        responses::{NullifierUpdate, SyncStateResponse},
+    };
+    use mock::{
+        constants::ACCOUNT_ID_SENDER,
+        mock::{
+            account::MockAccountType, block::mock_block_header, notes::AssetPreservationStatus,
+            transaction::mock_inputs,
+        },
+    };
+    use objects::{
+        accounts::{AccountId, AccountStub},
+        notes::NOTE_TREE_DEPTH,
+        transaction::OutputNotes,
+        utils::collections::BTreeMap,
+        Digest, Hasher, Word,
+    };
+
+    use super::{
+        build_inclusion_proof, find_desynchronized_accounts, nullifier_prefixes,
+        validate_block_header, verify_account_proof, StateSyncRequest, SyncPipeline,
+        ACCOUNT_TREE_DEPTH, FILTER_ID_SHIFT,
+    };
+    use crate::{
+        client::transactions::{TransactionRecord, TransactionStatus},
+        errors::ClientError,
+        mock::MockRpcApi,
+        store::{sync::QuarantinedNote, tests::create_test_client},
+    };
+
+    /// Returns a mocked node whose chain goes from the genesis block to block 2 in a first state
+    /// sync update, then to its tip, block 4
+    fn mock_chain_rpc_api() -> MockRpcApi {
+        let mut state_sync_requests = BTreeMap::new();
+        for (block_num, next_block_num) in [(0, 2), (2, 4)] {
+            state_sync_requests.insert(
+                SyncStateRequest {
+                    block_num,
+                    account_ids: vec![],
+                    note_tags: vec![],
+                    nullifiers: vec![],
+                },
+                SyncStateResponse {
+                    chain_tip: 4,
+                    mmr_delta: Some(MmrDelta {
+                        forest: u64::from(next_block_num),
+                        data: vec![],
+                    }),
+                    block_header: Some(NodeBlockHeader::from(mock_block_header(
+                        next_block_num,
+                        None,
+                        None,
+                        &[],
+                    ))),
+                    accounts: vec![],
+                    notes: vec![],
+                    nullifiers: vec![],
+                },
+            );
+        }
+
+        MockRpcApi {
+            state_sync_requests,
+            genesis_block: mock_block_header(0, None, None, &[]),
+        }
+    }
+
+    fn request_from(block_num: u32) -> StateSyncRequest {
+        StateSyncRequest {
+            block_num,
+            account_ids: vec![],
+            note_tags: vec![],
+            nullifiers_tags: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn state_sync_responses_are_prefetched_up_to_the_chain_tip() {
+        let mut pipeline = SyncPipeline::start(mock_chain_rpc_api(), request_from(0));
+
+        let response = pipeline.next_response(&request_from(0)).await.unwrap();
+        assert_eq!(response.block_header.block_num(), 2);
+        let response = pipeline.next_response(&request_from(2)).await.unwrap();
+        assert_eq!(response.block_header.block_num(), 4);
+
+        // nothing is requested past the chain tip
+        assert!(pipeline.receiver.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn outdated_prefetched_responses_are_discarded() {
+        // once a nullifier of the client is consumed, its prefix is no longer requested
+        let mut pipeline = SyncPipeline::start(mock_chain_rpc_api(), request_from(0));
+        let request = StateSyncRequest {
+            nullifiers_tags: vec![0xabcd],
+            ..request_from(0)
+        };
+        assert!(pipeline.next_response(&request).await.is_none());
+
+        // failed requests are discarded as well, the node has no update from its tip
+        let mut pipeline = SyncPipeline::start(mock_chain_rpc_api(), request_from(4));
+        assert!(pipeline.next_response(&request_from(4)).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn nullifiers_are_fetched_in_chunks_up_to_the_requested_block() {
+        // the mocked node reports a nullifier created in block 1 in its first update and another
+        // one created in block 4 in the second
+        let first_nullifier = Digest::new([Felt::ONE, Felt::ZERO, Felt::ZERO, Felt::ZERO]);
+        let second_nullifier = Digest::new([Felt::new(2), Felt::ZERO, Felt::ZERO, Felt::ZERO]);
+        let mut rpc_api = mock_chain_rpc_api();
+        for (request, response) in rpc_api.state_sync_requests.iter_mut() {
+            let (nullifier, block_num) = match request.block_num {
+                0 => (first_nullifier, 1),
+                _ => (second_nullifier, 4),
+            };
+            response.nullifiers = vec![NullifierUpdate {
+                nullifier: Some(nullifier.into()),
+                block_num,
+            }];
+        }
+
+        let mut client = create_test_client();
+        client.rpc_api = rpc_api;
+        client.nullifier_chunk_size = 2;
+
+        // the range is covered with as many requests as needed
+        let nullifiers = client.fetch_nullifiers(0, 4, &[1]).await.unwrap();
+        assert_eq!(
+            nullifiers,
+            vec![(first_nullifier, 1), (second_nullifier, 4)]
+        );
+
+        // nullifiers past the requested block are left for the next sync
+        let nullifiers = client.fetch_nullifiers(0, 3, &[1]).await.unwrap();
+        assert_eq!(nullifiers, vec![(first_nullifier, 1)]);
+
+        // lagging nullifiers are caught up from the block they were last checked at
+        let nullifiers = client.fetch_nullifiers(2, 4, &[1]).await.unwrap();
+        assert_eq!(nullifiers, vec![(second_nullifier, 4)]);
+
+        // the mocked node ignores the requested prefixes, so each of the two chunks the three
+        // prefixes are split into receives the same nullifier
+        let nullifiers = client
+            .fetch_nullifiers_in_chunks(2, 4, &[1, 2, 3])
+            .await
+            .unwrap();
+        assert_eq!(nullifiers, vec![(second_nullifier, 4); 2]);
+    }
+
+    #[test]
+    fn nullifier_prefixes_hide_the_least_significant_bits() {
+        let nullifier = Digest::new([
+            Felt::ZERO,
+            Felt::ZERO,
+            Felt::ZERO,
+            Felt::new(0xabcd << FILTER_ID_SHIFT),
+        ]);
+
+        assert_eq!(nullifier_prefixes(&[nullifier], 16), vec![0xabcd]);
+        assert_eq!(
+            nullifier_prefixes(&[nullifier, nullifier], 12),
+            (0xabc0..=0xabcf).collect::<Vec<u16>>()
+        );
+        assert_eq!(nullifier_prefixes(&[nullifier], 0).len(), 1 << 16);
+    }
+
+    #[test]
+    fn inclusion_proofs_are_verified_against_the_note_root() {
+        let transaction_inputs = mock_inputs(
+            MockAccountType::StandardExisting,
+            AssetPreservationStatus::Preserved,
+        );
+        let note = transaction_inputs.input_notes().get_note(0).note().clone();
+
+        // the note is the first leaf of a tree whose other nodes are all empty
+        let note_path = MerklePath::new(vec![Digest::default(); NOTE_TREE_DEPTH as usize]);
+        let auth_hash = Hasher::merge(&[note.id().inner(), Word::from(*note.metadata()).into()]);
+        let note_root = note_path
+            .iter()
+            .fold(auth_hash, |node, sibling| Hasher::merge(&[node, *sibling]));
+
+        let quarantined_note = QuarantinedNote {
+            note_id: note.id(),
+            block_num: 4,
+            note_index: 0,
+            note_path,
+            reason: String::new(),
+        };
+        let block_header = mock_block_header(4, None, Some(note_root), &[]);
+        let inclusion_proof =
+            build_inclusion_proof(&quarantined_note, note.metadata(), &block_header).unwrap();
+        assert_eq!(inclusion_proof.note_root(), note_root);
+
+        // the same path at another index leads to another root
+        let misplaced_note = QuarantinedNote {
+            note_index: 1,
+            ..quarantined_note
+        };
+        assert!(matches!(
+            build_inclusion_proof(&misplaced_note, note.metadata(), &block_header),
+            Err(ClientError::InvalidNoteInclusionProof(note_id, 4)) if note_id == note.id()
+        ));
+    }
+
+    #[test]
+    fn block_headers_must_extend_the_chain() {
+        let genesis = mock_block_header(0, None, None, &[]);
+        let block_1 = mock_block_header(1, None, None, &[]);
+
+        let mut chain_mmr = PartialMmr::from_peaks(MmrPeaks::new(0, vec![]).unwrap());
+        chain_mmr.add(genesis.hash(), false);
+        chain_mmr.add(block_1.hash(), false);
+        let peaks = chain_mmr.peaks();
+        let block_2 = mock_block_header(2, Some(peaks.hash_peaks()), None, &[]);
+
+        assert!(validate_block_header(&genesis, &block_2, 2, &peaks).is_ok());
+
+        let invalid_headers = [
+            // past the chain tip
+            (genesis, block_2, 1),
+            // not after the client's block
+            (block_2, block_2, 2),
+            // the previous hash of the mocked header is random
+            (block_1, block_2, 2),
+            // committing to another chain
+            (genesis, mock_block_header(2, None, None, &[]), 2),
+        ];
+        for (current_block, block_header, chain_tip) in invalid_headers {
+            assert!(matches!(
+                validate_block_header(&current_block, &block_header, chain_tip, &peaks),
+                Err(ClientError::InvalidBlockHeaderReceived(2, _))
+            ));
+        }
+    }
+
+    #[test]
+    fn account_proofs_are_verified_against_the_account_root() {
+        let account_id = AccountId::from_hex("0x168187d729b31a84").unwrap();
+        // the mocked block commits to an empty account tree
+        let block_header = mock_block_header(3, None, None, &[]);
+        let empty_hashes = EmptySubtreeRoots::empty_hashes(ACCOUNT_TREE_DEPTH as u8);
+        let merkle_path = MerklePath::new(empty_hashes[1..].iter().rev().copied().collect());
+
+        let account_proof = verify_account_proof(
+            account_id,
+            Digest::default(),
+            merkle_path.clone(),
+            &block_header,
+        )
+        .unwrap();
+        assert_eq!(account_proof.block_num, 3);
+
+        let other_hash = Digest::new([Felt::ONE; 4]);
+        let truncated_path = MerklePath::new(merkle_path.to_vec()[1..].to_vec());
+        for (account_hash, merkle_path) in [
+            (other_hash, merkle_path),
+            (Digest::default(), truncated_path),
+        ] {
+            assert!(matches!(
+                verify_account_proof(account_id, account_hash, merkle_path, &block_header),
+                Err(ClientError::InvalidAccountProof(..))
+            ));
+        }
+    }
+
+    #[test]
+    fn diverging_account_hashes_are_flagged() {
+        let account_id = AccountId::from_hex("0x168187d729b31a84").unwrap();
+        let other_account_id = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+        let local_account = AccountStub::new(
+            account_id,
+            Felt::ONE,
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+        );
+        let remote_hash = Digest::new([Felt::ONE; 4]);
+
+        // updates matching the local state are reported as such, and untracked accounts ignored
+        let (desynchronized, resynchronized) = find_desynchronized_accounts(
+            &[
+                (account_id, local_account.hash()),
+                (other_account_id, remote_hash),
+            ],
+            &[local_account.clone()],
+            &[],
+            5,
+        );
+        assert!(desynchronized.is_empty());
+        assert_eq!(resynchronized, vec![account_id]);
+
+        let (desynchronized, resynchronized) = find_desynchronized_accounts(
+            &[(account_id, remote_hash)],
+            &[local_account.clone()],
+            &[],
+            5,
+        );
+        assert!(resynchronized.is_empty());
+        assert_eq!(desynchronized.len(), 1);
+        assert_eq!(desynchronized[0].account_id, account_id);
+        assert_eq!(desynchronized[0].local_hash, local_account.hash());
+        assert_eq!(desynchronized[0].remote_hash, remote_hash);
+        assert_eq!(desynchronized[0].block_num, 5);
+    }
+
+    #[test]
+    fn states_of_pipelined_transactions_are_not_flagged() {
+        let account_id = AccountId::from_hex("0x168187d729b31a84").unwrap();
+        let local_account = AccountStub::new(
+            account_id,
+            Felt::new(2),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+        );
+        // two transactions were executed against the account, and only the first one is
+        // committed in the reported block
+        let intermediate_state = Digest::new([Felt::ONE; 4]);
+        let pending_transactions: Vec<TransactionRecord> = [
+            (
+                Digest::new([Felt::new(7); 4]),
+                Digest::default(),
+                intermediate_state,
+            ),
+            (
+                Digest::new([Felt::new(8); 4]),
+                intermediate_state,
+                local_account.hash(),
+            ),
+        ]
+        .into_iter()
+        .map(
+            |(transaction_id, init_account_state, final_account_state)| {
+                TransactionRecord::new(
+                    transaction_id,
+                    account_id,
+                    init_account_state,
+                    final_account_state,
+                    Vec::new(),
+                    OutputNotes::new(Vec::new()).unwrap(),
+                    None,
+                    0,
+                    TransactionStatus::Submitted,
+                )
+            },
+        )
+        .collect();
+
+        let (desynchronized, resynchronized) = find_desynchronized_accounts(
+            &[(account_id, intermediate_state)],
+            &[local_account.clone()],
+            &pending_transactions,
+            5,
+        );
+        assert!(desynchronized.is_empty());
+        assert_eq!(resynchronized, vec![account_id]);
+
+        // the final state of a transaction of another account does not count
+        let (desynchronized, _) = find_desynchronized_accounts(
+            &[(account_id, intermediate_state)],
+            &[local_account],
+            &pending_transactions
+                .into_iter()
+                .map(|mut transaction| {
+                    transaction.account_id = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+                    transaction
+                })
+                .collect::<Vec<_>>(),
+            5,
+        );
+        assert_eq!(desynchronized.len(), 1);
+    }
 }
@@ -1,31 +1,58 @@
-use crypto::{rand::RpoRandomCoin, utils::Serializable, Felt, StarkField, Word};
-use miden_lib::notes::create_p2id_note;
+use std::{
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crypto::{
+    rand::RpoRandomCoin,
+    utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable},
+    Felt, StarkField, Word,
+};
+use miden_lib::{
+    notes::{create_p2id_note, create_p2idr_note},
+    transaction::TransactionKernel,
+};
 use miden_node_proto::{
     requests::SubmitProvenTransactionRequest, responses::SubmitProvenTransactionResponse,
 };
 
-use miden_tx::{ProvingOptions, TransactionProver};
-
 use mock::procedures::prepare_word;
 use objects::{
-    accounts::{AccountDelta, AccountId},
-    assembly::ProgramAst,
-    assets::{Asset, FungibleAsset},
+    accounts::{AccountCode, AccountDelta, AccountId, AccountType},
+    assembly::{ModuleAst, ProgramAst},
+    assets::{Asset, FungibleAsset, NonFungibleAsset},
     notes::{Note, NoteId},
     transaction::{
         ExecutedTransaction, OutputNote, OutputNotes, ProvenTransaction, TransactionScript,
     },
+    utils::collections::BTreeMap,
     Digest,
 };
 use rand::Rng;
-use tracing::info;
+use serde::{Deserialize, Serialize};
+use tracing::{info, info_span, instrument, warn};
 
 use crate::{
-    errors::ClientError,
-    store::{accounts::AuthInfo, transactions::TransactionFilter},
+    errors::{ClientError, StoreError},
+    metrics,
+    store::{
+        accounts::AuthInfo,
+        chain_data::BlockTime,
+        notes::{InputNoteRecord, NoteFilter},
+        transactions::TransactionFilter,
+        Page,
+    },
 };
 
-use super::Client;
+use super::{
+    events::ClientEvent,
+    note_interpreter::DecodedNote,
+    note_tags::account_note_tag,
+    notes::{create_p2id_timelock_note, decode_memo, encode_memo},
+    script_inputs::TransactionScriptInputs,
+    Client,
+};
 
 // MASM SCRIPTS
 // --------------------------------------------------------------------------------------------
@@ -33,7 +60,96 @@ const AUTH_CONSUME_NOTES_SCRIPT: &str =
     include_str!("asm/transaction_scripts/auth_consume_notes.masm");
 const DISTRIBUTE_FUNGIBLE_ASSET_SCRIPT: &str =
     include_str!("asm/transaction_scripts/distribute_fungible_asset.masm");
-const AUTH_SEND_ASSET_SCRIPT: &str = include_str!("asm/transaction_scripts/auth_send_asset.masm");
+const CREATE_NOTES_SCRIPT: &str = include_str!("asm/transaction_scripts/create_notes.masm");
+const UPGRADE_ACCOUNT_CODE_SCRIPT: &str =
+    include_str!("asm/transaction_scripts/upgrade_account_code.masm");
+
+/// Procedure the code of an account must export for the account's code to be upgradable. It takes
+/// the root of the new code and passes it to `miden::account::set_code`, which can only be called
+/// from the account's own code.
+pub const SET_CODE_PROCEDURE: &str = "set_code";
+
+// PROTOCOL LIMITS
+// --------------------------------------------------------------------------------------------
+
+/// Maximum number of notes that can be consumed by a single transaction.
+pub const MAX_INPUT_NOTES_PER_TRANSACTION: usize = 1023;
+/// Maximum number of notes that can be created by a single transaction.
+pub const MAX_OUTPUT_NOTES_PER_TRANSACTION: usize = 4096;
+/// Maximum number of assets that can be carried by a single note.
+pub const MAX_ASSETS_PER_NOTE: usize = 255;
+
+/// Protocol limits that transactions are validated against before being executed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProtocolLimit {
+    InputNotesPerTransaction,
+    OutputNotesPerTransaction,
+    AssetsPerNote,
+}
+
+impl ProtocolLimit {
+    /// Returns the maximum value allowed by the protocol for this limit
+    pub const fn max(&self) -> usize {
+        match self {
+            ProtocolLimit::InputNotesPerTransaction => MAX_INPUT_NOTES_PER_TRANSACTION,
+            ProtocolLimit::OutputNotesPerTransaction => MAX_OUTPUT_NOTES_PER_TRANSACTION,
+            ProtocolLimit::AssetsPerNote => MAX_ASSETS_PER_NOTE,
+        }
+    }
+}
+
+impl std::fmt::Display for ProtocolLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProtocolLimit::InputNotesPerTransaction => write!(f, "input notes per transaction"),
+            ProtocolLimit::OutputNotesPerTransaction => write!(f, "output notes per transaction"),
+            ProtocolLimit::AssetsPerNote => write!(f, "assets per note"),
+        }
+    }
+}
+
+/// Limits the client validates transactions against before executing them, set through the
+/// `[transactions.limits]` section of the configuration.
+///
+/// They default to the maximums of the protocol, and can be lowered for a node that only accepts
+/// smaller transactions. They cannot be raised past the maximums of the protocol.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProtocolLimits {
+    pub max_input_notes_per_transaction: usize,
+    pub max_output_notes_per_transaction: usize,
+    pub max_assets_per_note: usize,
+}
+
+impl ProtocolLimits {
+    /// Returns the maximum value allowed for `limit`
+    pub const fn max(&self, limit: ProtocolLimit) -> usize {
+        match limit {
+            ProtocolLimit::InputNotesPerTransaction => self.max_input_notes_per_transaction,
+            ProtocolLimit::OutputNotesPerTransaction => self.max_output_notes_per_transaction,
+            ProtocolLimit::AssetsPerNote => self.max_assets_per_note,
+        }
+    }
+
+    /// Returns an error naming `limit` if `count` exceeds it
+    pub fn check(&self, limit: ProtocolLimit, count: usize) -> Result<(), ClientError> {
+        let max = self.max(limit);
+        if count > max {
+            return Err(ClientError::ProtocolLimitExceeded(limit, max, count));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ProtocolLimits {
+    fn default() -> Self {
+        Self {
+            max_input_notes_per_transaction: MAX_INPUT_NOTES_PER_TRANSACTION,
+            max_output_notes_per_transaction: MAX_OUTPUT_NOTES_PER_TRANSACTION,
+            max_assets_per_note: MAX_ASSETS_PER_NOTE,
+        }
+    }
+}
 
 // TRANSACTION TEMPLATE
 // --------------------------------------------------------------------------------------------
@@ -41,7 +157,15 @@ const AUTH_SEND_ASSET_SCRIPT: &str = include_str!("asm/transaction_scripts/auth_
 #[derive(Clone)]
 pub enum TransactionTemplate {
     /// Consume outstanding notes for an account.
+    ///
+    /// The notes must be committed: notes that are still expected, such as the notes minted by a
+    /// transaction submitted right before, can only be consumed once
+    /// [Client::authenticate_input_notes] retrieved their inclusion proofs.
     ConsumeNotes(AccountId, Vec<NoteId>),
+    /// Consume every committed note the account can consume, up to the limit of input notes per
+    /// transaction. [Client::split_transaction_template] splits it into as many transactions as
+    /// needed to consume all of them.
+    ConsumeAllNotes(AccountId),
     /// Mint fungible assets using a faucet account
     MintFungibleAsset {
         asset: FungibleAsset,
@@ -52,6 +176,46 @@ pub enum TransactionTemplate {
     /// Creates a pay-to-id note directed to a specific account, specifying a block height after
     /// which the note can be recalled
     PayToIdWithRecall(PaymentTransactionData, u32),
+    /// Creates a pay-to-id note directed to a specific account, which the account can only
+    /// consume once the chain is past `spendable_after_block`
+    PayToIdWithTimelock {
+        payment_data: PaymentTransactionData,
+        spendable_after_block: u32,
+    },
+    /// Creates a pay-to-id note transferring a specific non-fungible asset to an account
+    SendNonFungibleAsset {
+        asset: NonFungibleAsset,
+        sender_account_id: AccountId,
+        target_account_id: AccountId,
+    },
+    /// Consumes a P2IDR note back into the account that sent it, once the chain reached the note's
+    /// recall height
+    RecallNote {
+        sender_account_id: AccountId,
+        note_id: NoteId,
+    },
+    /// Creates arbitrary notes sent by an account, such as the ones assembled with a
+    /// [NoteBuilder](super::notes::NoteBuilder). Each note must carry a single asset, which is
+    /// moved out of the account's vault
+    CreateNotes(AccountId, Vec<Note>),
+    /// Creates notes of which only the recipient digest is known, the counterparty holding the
+    /// script, inputs and serial number behind it. One note is created for every asset, all of
+    /// them sharing the recipient and tag
+    PayToRecipient {
+        sender_account_id: AccountId,
+        recipient_digest: Digest,
+        assets: Vec<Asset>,
+        tag: u64,
+    },
+    /// Runs a custom transaction script against an account. The script's inputs are loaded into
+    /// its advice map along with the account's public key, so that the script can authenticate
+    /// the transaction with `call.auth_tx::auth_tx_rpo_falcon512`. Notes created by the script
+    /// are not known to the client
+    CustomScript {
+        account_id: AccountId,
+        script: ProgramAst,
+        script_inputs: TransactionScriptInputs,
+    },
 }
 
 impl TransactionTemplate {
@@ -59,16 +223,103 @@ impl TransactionTemplate {
     pub fn account_id(&self) -> AccountId {
         match self {
             TransactionTemplate::ConsumeNotes(account_id, _) => *account_id,
+            TransactionTemplate::ConsumeAllNotes(account_id) => *account_id,
             TransactionTemplate::MintFungibleAsset {
                 asset,
                 target_account_id: _target_account_id,
             } => asset.faucet_id(),
             TransactionTemplate::PayToId(p) => *p.account_id(),
             TransactionTemplate::PayToIdWithRecall(p, _) => *p.account_id(),
+            TransactionTemplate::PayToIdWithTimelock { payment_data, .. } => {
+                *payment_data.account_id()
+            }
+            TransactionTemplate::SendNonFungibleAsset {
+                sender_account_id, ..
+            } => *sender_account_id,
+            TransactionTemplate::RecallNote {
+                sender_account_id, ..
+            } => *sender_account_id,
+            TransactionTemplate::CreateNotes(account_id, _) => *account_id,
+            TransactionTemplate::PayToRecipient {
+                sender_account_id, ..
+            } => *sender_account_id,
+            TransactionTemplate::CustomScript { account_id, .. } => *account_id,
+        }
+    }
+
+    /// Splits the template into several templates that each respect `limits`, where doing so is
+    /// safe.
+    ///
+    /// Only templates that consume notes are split, by chunking the list of notes. The resulting
+    /// templates are meant to be executed and submitted one after the other, as each of them is
+    /// executed against the account state produced by the previous one.
+    pub fn split_into_batches(self, limits: ProtocolLimits) -> Vec<TransactionTemplate> {
+        let max_input_notes = limits.max_input_notes_per_transaction;
+        match self {
+            TransactionTemplate::ConsumeNotes(account_id, note_ids)
+                if note_ids.len() > max_input_notes =>
+            {
+                note_ids
+                    .chunks(max_input_notes)
+                    .map(|chunk| TransactionTemplate::ConsumeNotes(account_id, chunk.to_vec()))
+                    .collect()
+            }
+            template => vec![template],
         }
     }
 }
 
+// TRANSACTION FEES
+// --------------------------------------------------------------------------------------------
+
+/// Decides the fee charged for the transactions the client executes.
+///
+/// The network does not charge fees yet, so the client uses [NoFees] by default. Once it does, a
+/// policy following the network's fee schedule can be set with
+/// [ClientBuilder::fee_policy](super::builder::ClientBuilder::fee_policy) or
+/// [Client::set_fee_policy], and the fee it estimates is recorded along with every transaction.
+///
+/// The estimated fee is only recorded: it is not checked against the balance of the account, nor
+/// taken into account when picking the assets a template spends, so a policy that must not let
+/// the account run out of funds to pay the fee has to check its balance itself.
+pub trait FeePolicy: Send {
+    /// Returns the fee the transaction described by `template` is expected to be charged, or
+    /// `None` if it is charged no fee.
+    ///
+    /// # Errors
+    /// Returning an error, for instance because the fee would exceed what the user is willing to
+    /// pay, keeps the transaction from being executed.
+    fn estimate_fee(
+        &self,
+        template: &TransactionTemplate,
+    ) -> Result<Option<FungibleAsset>, ClientError>;
+}
+
+/// [FeePolicy] of networks that charge no fee, which all of them are until the protocol adds fees
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoFees;
+
+impl FeePolicy for NoFees {
+    fn estimate_fee(
+        &self,
+        _template: &TransactionTemplate,
+    ) -> Result<Option<FungibleAsset>, ClientError> {
+        Ok(None)
+    }
+}
+
+impl<F> FeePolicy for F
+where
+    F: Fn(&TransactionTemplate) -> Result<Option<FungibleAsset>, ClientError> + Send,
+{
+    fn estimate_fee(
+        &self,
+        template: &TransactionTemplate,
+    ) -> Result<Option<FungibleAsset>, ClientError> {
+        self(template)
+    }
+}
+
 // PAYMENT TRANSACTION DATA
 // --------------------------------------------------------------------------------------------
 
@@ -77,6 +328,8 @@ pub struct PaymentTransactionData {
     asset: Asset,
     sender_account_id: AccountId,
     target_account_id: AccountId,
+    memo: Option<String>,
+    change_note: bool,
 }
 
 impl PaymentTransactionData {
@@ -89,9 +342,27 @@ impl PaymentTransactionData {
             asset,
             sender_account_id,
             target_account_id,
+            memo: None,
+            change_note: false,
         }
     }
 
+    /// Attaches a memo to the created note, such as an invoice reference, which is encoded in
+    /// the note's inputs with [encode_memo](super::notes::encode_memo)
+    pub fn with_memo(mut self, memo: impl Into<String>) -> Self {
+        self.memo = Some(memo.into());
+        self
+    }
+
+    /// Sends the change back to the sender in a note of its own when notes are consumed to cover
+    /// a fungible payment. The change is the amount the consumed notes carry beyond what the
+    /// payment needed, which would otherwise be left in the sender's vault. Like every note
+    /// created by the client, the change note is tracked once the transaction is submitted
+    pub fn with_change_note(mut self) -> Self {
+        self.change_note = true;
+        self
+    }
+
     /// Returns the executor [AccountId]
     pub fn account_id(&self) -> &AccountId {
         &self.sender_account_id
@@ -108,6 +379,7 @@ impl PaymentTransactionData {
 pub struct TransactionResult {
     executed_transaction: ExecutedTransaction,
     created_notes: Vec<Note>,
+    fee: Option<FungibleAsset>,
 }
 
 impl TransactionResult {
@@ -115,9 +387,16 @@ impl TransactionResult {
         Self {
             executed_transaction,
             created_notes,
+            fee: None,
         }
     }
 
+    /// Sets the fee the transaction is charged, as estimated by the client's [FeePolicy]
+    pub fn with_fee(mut self, fee: Option<FungibleAsset>) -> Self {
+        self.fee = fee;
+        self
+    }
+
     pub fn executed_transaction(&self) -> &ExecutedTransaction {
         &self.executed_transaction
     }
@@ -126,6 +405,11 @@ impl TransactionResult {
         &self.created_notes
     }
 
+    /// Returns the fee the transaction is charged, if any
+    pub fn fee(&self) -> Option<FungibleAsset> {
+        self.fee
+    }
+
     pub fn block_num(&self) -> u32 {
         self.executed_transaction.block_header().block_num()
     }
@@ -137,6 +421,117 @@ impl TransactionResult {
     pub fn account_delta(&self) -> &AccountDelta {
         self.executed_transaction.account_delta()
     }
+
+    /// Returns a [TransactionReceipt] summarizing the transaction
+    pub fn receipt(&self) -> TransactionReceipt {
+        let account_delta = self.account_delta();
+        let storage_delta = account_delta.storage();
+        let mut updated_storage_slots: Vec<u8> = storage_delta
+            .cleared_items
+            .iter()
+            .copied()
+            .chain(storage_delta.updated_items.iter().map(|(slot, _)| *slot))
+            .collect();
+        updated_storage_slots.sort_unstable();
+
+        TransactionReceipt {
+            transaction_id: self.executed_transaction.id().inner().to_hex(),
+            account_id: self.executed_transaction.account_id().to_hex(),
+            block_num: self.block_num(),
+            output_note_ids: self
+                .created_notes
+                .iter()
+                .map(|note| note.id().inner().to_hex())
+                .collect(),
+            account_delta: AccountDeltaSummary {
+                nonce: account_delta.nonce().map(|nonce| nonce.as_int()),
+                added_assets: account_delta
+                    .vault()
+                    .added_assets
+                    .iter()
+                    .map(AssetSummary::from)
+                    .collect(),
+                removed_assets: account_delta
+                    .vault()
+                    .removed_assets
+                    .iter()
+                    .map(AssetSummary::from)
+                    .collect(),
+                updated_storage_slots,
+            },
+            fee: self.fee.map(|fee| AssetSummary::from(&Asset::from(fee))),
+        }
+    }
+}
+
+impl Serializable for TransactionResult {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.executed_transaction.write_into(target);
+        self.created_notes.write_into(target);
+    }
+}
+
+impl Deserializable for TransactionResult {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let executed_transaction = ExecutedTransaction::read_from(source)?;
+        let created_notes = Vec::<Note>::read_from(source)?;
+
+        Ok(Self::new(executed_transaction, created_notes))
+    }
+}
+
+// TRANSACTION RECEIPT
+// --------------------------------------------------------------------------------------------
+
+/// Machine-readable summary of a transaction executed by the client, meant for external systems
+/// tracking payments. Identifiers are serialized as hex strings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TransactionReceipt {
+    pub transaction_id: String,
+    /// Account the transaction was executed against
+    pub account_id: String,
+    /// Number of the block the transaction was executed at
+    pub block_num: u32,
+    /// IDs of the notes created by the transaction
+    pub output_note_ids: Vec<String>,
+    pub account_delta: AccountDeltaSummary,
+    /// Fee the transaction is charged, if any
+    pub fee: Option<AssetSummary>,
+}
+
+/// Changes made by a transaction to the account it was executed against
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AccountDeltaSummary {
+    /// New nonce of the account, if the transaction changed it
+    pub nonce: Option<u64>,
+    pub added_assets: Vec<AssetSummary>,
+    pub removed_assets: Vec<AssetSummary>,
+    /// Indices of the storage slots updated or cleared by the transaction
+    pub updated_storage_slots: Vec<u8>,
+}
+
+/// Asset added to or removed from an account's vault. Fungible assets carry the amount that was
+/// added or removed, and non-fungible assets are identified by their digest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AssetSummary {
+    Fungible { faucet_id: String, amount: u64 },
+    NonFungible { faucet_id: String, digest: String },
+}
+
+impl From<&Asset> for AssetSummary {
+    fn from(asset: &Asset) -> Self {
+        match asset {
+            Asset::Fungible(asset) => AssetSummary::Fungible {
+                faucet_id: asset.faucet_id().to_hex(),
+                amount: asset.amount(),
+            },
+            Asset::NonFungible(asset) => AssetSummary::NonFungible {
+                faucet_id: asset.faucet_id().to_hex(),
+                digest: Digest::from(Word::from(*asset)).to_hex(),
+            },
+        }
+    }
 }
 
 // TRANSACTION RECORD
@@ -151,11 +546,16 @@ pub struct TransactionRecord {
     pub account_id: AccountId,
     pub init_account_state: Digest,
     pub final_account_state: Digest,
+    /// IDs of the notes consumed by the transaction. Despite its name, the field does not hold
+    /// the nullifiers of the notes
     pub input_note_nullifiers: Vec<Digest>,
     pub output_notes: OutputNotes<OutputNote>,
     pub transaction_script: Option<TransactionScript>,
     pub block_num: u32,
     pub transaction_status: TransactionStatus,
+    /// Fee the transaction is charged, as estimated by the client's [FeePolicy] when it was
+    /// executed
+    pub fee: Option<FungibleAsset>,
 }
 
 impl TransactionRecord {
@@ -181,14 +581,27 @@ impl TransactionRecord {
             transaction_script,
             block_num,
             transaction_status,
+            fee: None,
         }
     }
+
+    /// Sets the fee the transaction is charged
+    pub fn with_fee(mut self, fee: Option<FungibleAsset>) -> Self {
+        self.fee = fee;
+        self
+    }
 }
 
-/// Represents the status of a transaction
+/// Represents the status of a transaction, following its lifecycle:
+/// `Executed -> Proven -> Submitted -> Committed`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TransactionStatus {
+    /// Transaction has been executed locally but not yet proven
+    Executed,
+    /// Transaction has been proven but not yet submitted to the node
+    Proven,
     /// Transaction has been submitted but not yet committed
-    Pending,
+    Submitted,
     /// Transaction has been committed and included at the specified block number
     Committed(u32),
 }
@@ -196,7 +609,9 @@ pub enum TransactionStatus {
 impl std::fmt::Display for TransactionStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            TransactionStatus::Pending => write!(f, "Pending"),
+            TransactionStatus::Executed => write!(f, "Executed"),
+            TransactionStatus::Proven => write!(f, "Proven"),
+            TransactionStatus::Submitted => write!(f, "Submitted"),
             TransactionStatus::Committed(block_number) => {
                 write!(f, "Committed (Block: {})", block_number)
             }
@@ -204,6 +619,40 @@ impl std::fmt::Display for TransactionStatus {
     }
 }
 
+// ACCOUNT HISTORY ENTRY
+// --------------------------------------------------------------------------------------------
+
+/// Describes a transaction executed against an account, along with the notes it consumed and
+/// created and the resulting changes in the account's assets
+pub struct AccountHistoryEntry {
+    pub transaction_id: Digest,
+    pub block_num: u32,
+    pub transaction_status: TransactionStatus,
+    pub consumed_notes: Vec<NoteId>,
+    pub created_notes: Vec<NoteId>,
+    /// Net change in the amount held of each faucet's asset: assets received through consumed
+    /// notes minus assets sent through created notes. Non-fungible assets count as one unit.
+    pub asset_changes: Vec<(AccountId, i128)>,
+    /// Memos of the consumed and created notes that carry one
+    pub memos: Vec<(NoteId, String)>,
+}
+
+// OUTBOX FLUSH
+// --------------------------------------------------------------------------------------------
+
+/// Outcome of submitting the transactions queued in offline mode with [Client::flush_outbox]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct OutboxFlush {
+    /// Transactions submitted to the node, in the order they were queued
+    pub submitted: Vec<Digest>,
+    /// Transactions executed against a block too far behind the sync height to be submitted,
+    /// left in the outbox
+    pub stale: Vec<Digest>,
+    /// Transactions left in the outbox because they were queued after a stale transaction of the
+    /// same account, whose changes they build on
+    pub blocked: Vec<Digest>,
+}
+
 impl Client {
     // TRANSACTION DATA RETRIEVAL
     // --------------------------------------------------------------------------------------------
@@ -218,38 +667,459 @@ impl Client {
             .map_err(|err| err.into())
     }
 
+    /// Retrieves the specified page of the tracked transactions, filtered by [TransactionFilter].
+    pub fn get_transactions_page(
+        &self,
+        transaction_filter: TransactionFilter,
+        page: Page,
+    ) -> Result<Vec<TransactionRecord>, ClientError> {
+        self.store
+            .get_transactions_page(transaction_filter, page)
+            .map_err(|err| err.into())
+    }
+
+    /// Returns the block each committed transaction was committed in, with the block's timestamp
+    /// when its header is stored, keyed by transaction ID
+    pub fn get_transaction_commit_times(&self) -> Result<BTreeMap<Digest, BlockTime>, ClientError> {
+        self.store
+            .get_transaction_commit_times()
+            .map_err(|err| err.into())
+    }
+
+    /// Returns the [ProvenTransaction] of a transaction proven by this client, which carries the
+    /// STARK proof of its execution along with the account delta and notes it commits to.
+    ///
+    /// Its serialized form, as returned by [Serializable::to_bytes], is what gets submitted to the
+    /// node, so it can be verified independently of the client.
+    ///
+    /// # Errors
+    /// Returns [StoreError::TransactionProofNotFound] if the transaction was not proven yet, or
+    /// was submitted by a version of the client that didn't keep proofs after submission.
+    pub fn get_proven_transaction(
+        &self,
+        transaction_id: Digest,
+    ) -> Result<ProvenTransaction, ClientError> {
+        self.store
+            .get_proven_transaction(transaction_id)
+            .map_err(|err| err.into())
+    }
+
+    /// Returns the chronological history of the transactions executed against the specified
+    /// account.
+    ///
+    /// Asset changes are derived from the notes tracked by the store, so notes that are unknown
+    /// to the client don't contribute to them.
+    pub fn get_account_history(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<AccountHistoryEntry>, ClientError> {
+        self.store
+            .get_transactions(TransactionFilter::Account(account_id))?
+            .into_iter()
+            .map(|transaction| {
+                let consumed_notes: Vec<NoteId> = transaction
+                    .input_note_nullifiers
+                    .iter()
+                    .map(|note_id| NoteId::from(*note_id))
+                    .collect();
+                let created_notes: Vec<NoteId> = transaction
+                    .output_notes
+                    .iter()
+                    .map(|note| note.id())
+                    .collect();
+
+                let mut asset_changes = BTreeMap::<AccountId, i128>::new();
+                let mut memos = Vec::new();
+                for (note_id, sign) in consumed_notes
+                    .iter()
+                    .map(|note_id| (note_id, 1))
+                    .chain(created_notes.iter().map(|note_id| (note_id, -1)))
+                {
+                    let note = match self.store.get_input_note_by_id(*note_id) {
+                        Ok(note) => note,
+                        Err(StoreError::InputNoteNotFound(_)) => continue,
+                        Err(err) => return Err(err.into()),
+                    };
+                    if let Some(memo) = decode_memo(note.note().inputs().inputs()) {
+                        memos.push((*note_id, memo));
+                    }
+                    for asset in note.note().assets().iter() {
+                        let (faucet_id, amount) = match asset {
+                            Asset::Fungible(asset) => (asset.faucet_id(), asset.amount()),
+                            Asset::NonFungible(asset) => (asset.faucet_id(), 1),
+                        };
+                        *asset_changes.entry(faucet_id).or_default() += sign * amount as i128;
+                    }
+                }
+
+                Ok(AccountHistoryEntry {
+                    transaction_id: transaction.id,
+                    block_num: transaction.block_num,
+                    transaction_status: transaction.transaction_status,
+                    consumed_notes,
+                    created_notes,
+                    asset_changes: asset_changes
+                        .into_iter()
+                        .filter(|(_, change)| *change != 0)
+                        .collect(),
+                    memos,
+                })
+            })
+            .collect()
+    }
+
     // TRANSACTION
     // --------------------------------------------------------------------------------------------
 
-    /// Creates and executes a transaction specified by the template, but does not change the
-    /// local database.
+    /// Creates and executes a transaction specified by the template. The fee estimated by the
+    /// client's [FeePolicy] is attached to the result.
+    ///
+    /// The local database only changes to lock the notes the transaction consumes, from the moment
+    /// they are selected, so that other transactions cannot consume them. They stay locked while
+    /// the transaction is in progress, and are released if it fails or with
+    /// [Client::release_input_notes] if it is not sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns [ClientError::AccountStateDesynchronized] if a sync found the on-chain state of the
+    /// account to differ from the local one, as executing against a stale state would only fail
+    /// once the transaction is proven or submitted. Errors of the fee policy are returned as is.
+    ///
+    /// Returns [ClientError::UnauthenticatedNote] if one of the notes to consume has no inclusion
+    /// proof yet. Consuming notes before they are committed is not supported, as the transaction
+    /// kernel authenticates every input note against the block it was committed in.
+    #[instrument(skip_all, fields(account_id = %transaction_template.account_id()))]
     pub fn new_transaction(
         &mut self,
         transaction_template: TransactionTemplate,
     ) -> Result<TransactionResult, ClientError> {
-        match transaction_template {
+        let account_id = transaction_template.account_id();
+        if self.store.get_desynchronized_account(account_id)?.is_some() {
+            return Err(ClientError::AccountStateDesynchronized(account_id));
+        }
+        let fee = self.fee_policy.estimate_fee(&transaction_template)?;
+
+        // input notes are locked as soon as they are selected, on behalf of a reservation handed
+        // over to the transaction once it is executed, so that clients sharing the store cannot
+        // select the same notes in the meantime
+        let reservation = self.new_note_reservation();
+        let transaction_result = match transaction_template {
             TransactionTemplate::PayToId(PaymentTransactionData {
-                asset: fungible_asset,
+                asset,
+                sender_account_id,
+                target_account_id,
+                memo,
+                change_note,
+            }) => self.new_p2id_transaction(
+                asset,
+                sender_account_id,
+                target_account_id,
+                memo.as_deref(),
+                change_note,
+                PaymentNoteKind::P2ID,
+                reservation,
+            ),
+            TransactionTemplate::PayToIdWithRecall(
+                PaymentTransactionData {
+                    asset,
+                    sender_account_id,
+                    target_account_id,
+                    memo,
+                    change_note,
+                },
+                recall_height,
+            ) => self.new_p2id_transaction(
+                asset,
+                sender_account_id,
+                target_account_id,
+                memo.as_deref(),
+                change_note,
+                PaymentNoteKind::P2IDR { recall_height },
+                reservation,
+            ),
+            TransactionTemplate::PayToIdWithTimelock {
+                payment_data:
+                    PaymentTransactionData {
+                        asset,
+                        sender_account_id,
+                        target_account_id,
+                        memo,
+                        change_note,
+                    },
+                spendable_after_block,
+            } => self.new_p2id_transaction(
+                asset,
                 sender_account_id,
                 target_account_id,
-            }) => self.new_p2id_transaction(fungible_asset, sender_account_id, target_account_id),
-            TransactionTemplate::PayToIdWithRecall(_payment_data, _recall_height) => todo!(),
+                memo.as_deref(),
+                change_note,
+                PaymentNoteKind::P2IDTimelock {
+                    spendable_after_block,
+                },
+                reservation,
+            ),
             TransactionTemplate::ConsumeNotes(account_id, list_of_notes) => {
-                self.new_consume_notes_transaction(account_id, &list_of_notes)
+                self.new_consume_notes_transaction(account_id, &list_of_notes, reservation)
+            }
+            TransactionTemplate::ConsumeAllNotes(account_id) => {
+                let note_ids = self.get_consumable_note_ids(account_id)?;
+                let note_count = note_ids
+                    .len()
+                    .min(self.protocol_limits.max_input_notes_per_transaction);
+                self.new_consume_notes_transaction(account_id, &note_ids[..note_count], reservation)
             }
             TransactionTemplate::MintFungibleAsset {
                 asset,
                 target_account_id,
             } => self.new_mint_fungible_asset_transaction(asset, target_account_id),
+            TransactionTemplate::SendNonFungibleAsset {
+                asset,
+                sender_account_id,
+                target_account_id,
+            } => self.new_p2id_transaction(
+                asset.into(),
+                sender_account_id,
+                target_account_id,
+                None,
+                false,
+                PaymentNoteKind::P2ID,
+                reservation,
+            ),
+            TransactionTemplate::RecallNote {
+                sender_account_id,
+                note_id,
+            } => self.new_recall_transaction(sender_account_id, note_id, reservation),
+            TransactionTemplate::CreateNotes(account_id, notes) => {
+                self.new_create_notes_transaction(account_id, notes)
+            }
+            TransactionTemplate::PayToRecipient {
+                sender_account_id,
+                recipient_digest,
+                assets,
+                tag,
+            } => self.new_pay_to_recipient_transaction(
+                sender_account_id,
+                recipient_digest,
+                &assets,
+                tag,
+            ),
+            TransactionTemplate::CustomScript {
+                account_id,
+                script,
+                script_inputs,
+            } => self.new_custom_script_transaction(account_id, script, &script_inputs),
+        };
+
+        match transaction_result {
+            Ok(transaction_result) => {
+                let transaction_id = transaction_result.executed_transaction().id().inner();
+                self.store
+                    .transfer_note_locks(reservation, transaction_id)?;
+                Ok(transaction_result.with_fee(fee))
+            }
+            Err(err) => {
+                self.store.unlock_notes(reservation)?;
+                Err(err)
+            }
+        }
+    }
+
+    /// Releases the input notes of a transaction that will not be sent, which stay locked
+    /// otherwise until [NOTE_LOCK_DURATION](crate::store::notes::NOTE_LOCK_DURATION) elapses
+    pub fn release_input_notes(
+        &mut self,
+        transaction_result: &TransactionResult,
+    ) -> Result<(), ClientError> {
+        let transaction_id = transaction_result.executed_transaction().id().inner();
+        self.store
+            .unlock_notes(transaction_id)
+            .map_err(|err| err.into())
+    }
+
+    /// Returns the fee the client's [FeePolicy] estimates for the transaction described by the
+    /// template, or `None` if it would be charged no fee
+    pub fn estimate_fee(
+        &self,
+        transaction_template: &TransactionTemplate,
+    ) -> Result<Option<FungibleAsset>, ClientError> {
+        self.fee_policy.estimate_fee(transaction_template)
+    }
+
+    /// Replaces the [FeePolicy] the fees of the transactions executed by the client are estimated
+    /// with
+    pub fn set_fee_policy(&mut self, fee_policy: impl FeePolicy + 'static) {
+        self.fee_policy = Box::new(fee_policy);
+    }
+
+    /// Retrieves the inclusion proofs of the notes that were not committed as of the last sync,
+    /// such as the notes created by a transaction submitted right before, so that a transaction
+    /// can consume them.
+    ///
+    /// The transaction kernel authenticates every input note against the block it was committed
+    /// in, so notes cannot be consumed before the node commits them. If any of the notes lacks an
+    /// inclusion proof, the client is synced to retrieve the proofs of the notes committed since
+    /// the last sync. Notes that already have one are left as they are.
+    ///
+    /// # Errors
+    ///
+    /// Returns [ClientError::UnauthenticatedNote] if a note is still not committed once the client
+    /// is synced.
+    pub async fn authenticate_input_notes(
+        &mut self,
+        note_ids: &[NoteId],
+    ) -> Result<(), ClientError> {
+        let mut unauthenticated_note_ids = Vec::new();
+        for note_id in note_ids {
+            if self
+                .store
+                .get_input_note(*note_id)?
+                .inclusion_proof()
+                .is_none()
+            {
+                unauthenticated_note_ids.push(*note_id);
+            }
+        }
+        if unauthenticated_note_ids.is_empty() {
+            return Ok(());
+        }
+
+        info!(
+            "Syncing to retrieve the inclusion proofs of {} notes",
+            unauthenticated_note_ids.len()
+        );
+        self.sync_state().await?;
+        for note_id in unauthenticated_note_ids {
+            if self
+                .store
+                .get_input_note(note_id)?
+                .inclusion_proof()
+                .is_none()
+            {
+                return Err(ClientError::UnauthenticatedNote(note_id));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Splits the template into templates that each respect the limits of the client's
+    /// configuration, as [TransactionTemplate::split_into_batches] does. A [TransactionTemplate::ConsumeAllNotes]
+    /// template is first resolved into the notes the account can currently consume, so that the
+    /// resulting transactions consume all of them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a [TransactionTemplate::ConsumeAllNotes] template is provided and there
+    /// is no note the account can consume.
+    pub fn split_transaction_template(
+        &self,
+        transaction_template: TransactionTemplate,
+    ) -> Result<Vec<TransactionTemplate>, ClientError> {
+        let transaction_template = match transaction_template {
+            TransactionTemplate::ConsumeAllNotes(account_id) => TransactionTemplate::ConsumeNotes(
+                account_id,
+                self.get_consumable_note_ids(account_id)?,
+            ),
+            transaction_template => transaction_template,
+        };
+
+        Ok(transaction_template.split_into_batches(self.protocol_limits))
+    }
+
+    /// Returns the IDs of the committed notes the account can consume
+    fn get_consumable_note_ids(&self, account_id: AccountId) -> Result<Vec<NoteId>, ClientError> {
+        let note_ids: Vec<NoteId> = self
+            .get_input_notes(NoteFilter::new().consumable_by(account_id))?
+            .iter()
+            .map(|note| note.note_id())
+            .collect();
+
+        if note_ids.is_empty() {
+            return Err(ClientError::NoConsumableNoteForAccount(account_id));
+        }
+        Ok(note_ids)
+    }
+
+    /// Creates and executes a transaction that consumes a P2IDR note back into the account that
+    /// sent it. The client must be synced up to the note's recall height, as the transaction is
+    /// executed against the block it is synced to.
+    ///
+    /// The note's order is marked as recalled by the sync that finds the note consumed.
+    fn new_recall_transaction(
+        &mut self,
+        sender_account_id: AccountId,
+        note_id: NoteId,
+        reservation: Digest,
+    ) -> Result<TransactionResult, ClientError> {
+        let note = self.store.get_input_note(note_id)?;
+        let recall_height = match self.note_interpreter.decode(note.note()) {
+            Some(DecodedNote::P2IDR { recall_height, .. }) => recall_height,
+            _ => {
+                return Err(ClientError::NoteNotRecallable(
+                    note_id,
+                    "it is not a P2IDR note".to_string(),
+                ))
+            }
+        };
+
+        let sender = note.note().metadata().sender();
+        if sender != sender_account_id {
+            return Err(ClientError::NoteNotRecallable(
+                note_id,
+                format!("it was sent by account {sender}"),
+            ));
+        }
+
+        if let Some(order) = self
+            .store
+            .get_orders()?
+            .into_iter()
+            .find(|order| order.note_id == note_id && order.status.is_closed())
+        {
+            return Err(ClientError::NoteNotRecallable(
+                note_id,
+                format!("it was already {}", order.status),
+            ));
+        }
+
+        let sync_height = self.store.get_sync_height()?;
+        if sync_height < recall_height {
+            return Err(ClientError::NoteNotRecallable(
+                note_id,
+                format!(
+                    "its recall height is {recall_height}, but the client is only synced up to block {sync_height}"
+                ),
+            ));
         }
+
+        self.new_consume_notes_transaction(sender_account_id, &[note_id], reservation)
     }
 
-    /// Creates and executes a transaction that consumes a number of notes
+    /// Creates and executes a transaction that consumes a number of notes, which are locked on
+    /// behalf of `reservation` until the transaction is executed
     fn new_consume_notes_transaction(
         &mut self,
         account_id: AccountId,
         note_ids: &[NoteId],
+        reservation: Digest,
     ) -> Result<TransactionResult, ClientError> {
+        // fail before spending time on a transaction that would conflict with one in progress
+        self.store.lock_notes(note_ids, reservation)?;
+
+        // the transaction kernel authenticates every input note against the block it was
+        // committed in, so notes without an inclusion proof cannot be consumed until they are
+        // retrieved with `authenticate_input_notes`
+        for note_id in note_ids {
+            if self
+                .store
+                .get_input_note(*note_id)?
+                .inclusion_proof()
+                .is_none()
+            {
+                return Err(ClientError::UnauthenticatedNote(*note_id));
+            }
+        }
+
         self.tx_executor
             .load_account(account_id)
             .map_err(ClientError::TransactionExecutionError)?;
@@ -307,50 +1177,412 @@ impl Client {
         )
     }
 
+    /// Creates and executes a transaction sending `asset` in a note of the specified kind. If the
+    /// sender does not hold enough of the asset, notes it can consume are picked to make up for
+    /// the difference and consumed by the same transaction. The memo, if any, is appended to the
+    /// note's inputs. With `change_note`, what the consumed notes carry beyond the payment is sent
+    /// back to the sender in a second P2ID note.
+    #[allow(clippy::too_many_arguments)]
     fn new_p2id_transaction(
         &mut self,
-        fungible_asset: Asset,
+        asset: Asset,
         sender_account_id: AccountId,
         target_account_id: AccountId,
+        memo: Option<&str>,
+        change_note: bool,
+        note_kind: PaymentNoteKind,
+        reservation: Digest,
     ) -> Result<TransactionResult, ClientError> {
+        let memo_inputs = memo.map(encode_memo).transpose()?;
+        let (input_note_ids, surplus) =
+            self.select_notes_for_payment(sender_account_id, asset, reservation)?;
+
         let random_coin = self.get_random_coin();
+        let created_note = match note_kind {
+            PaymentNoteKind::P2IDTimelock {
+                spendable_after_block,
+            } => create_p2id_timelock_note(
+                sender_account_id,
+                target_account_id,
+                &[asset],
+                spendable_after_block,
+                memo,
+                random_coin,
+            )?,
+            PaymentNoteKind::P2ID | PaymentNoteKind::P2IDR { .. } => {
+                let (created_note, mut inputs) = match note_kind {
+                    PaymentNoteKind::P2IDR { recall_height } => (
+                        create_p2idr_note(
+                            sender_account_id,
+                            target_account_id,
+                            vec![asset],
+                            recall_height,
+                            random_coin,
+                        )?,
+                        vec![target_account_id.into(), Felt::from(recall_height)],
+                    ),
+                    _ => (
+                        create_p2id_note(
+                            sender_account_id,
+                            target_account_id,
+                            vec![asset],
+                            random_coin,
+                        )?,
+                        vec![target_account_id.into()],
+                    ),
+                };
 
-        let created_note = create_p2id_note(
-            sender_account_id,
-            target_account_id,
-            vec![fungible_asset],
-            random_coin,
-        )?;
+                // the scripts only read the inputs the memo follows, and the note is tagged for
+                // its target as the transaction script creates it
+                inputs.extend(memo_inputs.unwrap_or_default());
+                Note::new(
+                    created_note.script().clone(),
+                    &inputs,
+                    &[asset],
+                    created_note.serial_num(),
+                    sender_account_id,
+                    Felt::new(account_note_tag(target_account_id)),
+                )?
+            }
+        };
+
+        let mut created_notes = vec![(created_note, target_account_id)];
+        if change_note {
+            if let Some(change_note) = self.new_change_note(asset, sender_account_id, surplus)? {
+                created_notes.push((change_note, sender_account_id));
+            }
+        }
 
         self.tx_executor.load_account(sender_account_id)?;
 
         let block_ref = self.get_sync_height()?;
+        let tx_script_code = payment_notes_script(&created_notes);
 
-        let recipient = created_note
-            .recipient()
-            .iter()
-            .map(|x| x.as_int().to_string())
-            .collect::<Vec<_>>()
-            .join(".");
+        self.compile_and_execute_tx(
+            sender_account_id,
+            &input_note_ids,
+            created_notes.into_iter().map(|(note, _)| note).collect(),
+            tx_script_code,
+            block_ref,
+        )
+    }
+
+    /// Returns the P2ID note sending `surplus` of the paid `asset` back to the sender of a payment,
+    /// or `None` if there is nothing to send back. Only fungible payments can leave a surplus.
+    fn new_change_note(
+        &mut self,
+        asset: Asset,
+        sender_account_id: AccountId,
+        surplus: u64,
+    ) -> Result<Option<Note>, ClientError> {
+        let faucet_id = match asset {
+            Asset::Fungible(asset) if surplus > 0 => asset.faucet_id(),
+            _ => return Ok(None),
+        };
+
+        let change = FungibleAsset::new(faucet_id, surplus)?;
+        let random_coin = self.get_random_coin();
+        let change_note = create_p2id_note(
+            sender_account_id,
+            sender_account_id,
+            vec![change.into()],
+            random_coin,
+        )?;
+        Ok(Some(change_note))
+    }
+
+    /// Returns the notes the account needs to consume for its vault to hold `asset`, picked among
+    /// the notes it can consume according to the client's
+    /// [NoteSelectionStrategy](super::note_selection::NoteSelectionStrategy), along with the
+    /// amount of a fungible asset they carry beyond what is missing from the vault. The selected
+    /// notes are locked on behalf of `reservation`, so that no other transaction selects them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the account cannot get hold of the asset, even by consuming every note
+    /// it can consume, or if another transaction locked one of the selected notes first.
+    fn select_notes_for_payment(
+        &mut self,
+        account_id: AccountId,
+        asset: Asset,
+        reservation: Digest,
+    ) -> Result<(Vec<NoteId>, u64), ClientError> {
+        // the payment's transaction script consumes notes with the account's `receive_asset`, which
+        // only P2ID notes addressed to the account allow for: SWAP notes expect a payback, and the
+        // sender of a P2IDR note may recall it before the payment is committed
+        let consumable_notes: Vec<InputNoteRecord> = self
+            .get_input_notes(NoteFilter::new().consumable_by(account_id))?
+            .into_iter()
+            .filter(|note| {
+                matches!(
+                    self.note_interpreter.decode(note.note()),
+                    Some(DecodedNote::P2ID { target } | DecodedNote::P2IDTimelock { target, .. })
+                        if target == account_id
+                )
+            })
+            .collect();
+
+        let (selected_note_ids, surplus) = match asset {
+            Asset::Fungible(asset) => {
+                let faucet_id = asset.faucet_id();
+                let balance = self.get_account_balance(account_id, faucet_id)?;
+                let missing_amount = asset.amount().saturating_sub(balance);
+
+                // notes whose amounts cannot be added up are not valid and left out
+                let candidates: Vec<(NoteId, u64)> = consumable_notes
+                    .iter()
+                    .filter_map(|note| {
+                        let amount = checked_sum(note.note().assets().iter().filter_map(
+                            |asset| match asset {
+                                Asset::Fungible(asset) if asset.faucet_id() == faucet_id => {
+                                    Some(asset.amount())
+                                }
+                                _ => None,
+                            },
+                        ))?;
+                        Some((note.note_id(), amount))
+                    })
+                    .collect();
+
+                let selected_note_ids = self
+                    .note_selection
+                    .select(
+                        &candidates,
+                        missing_amount,
+                        self.protocol_limits.max_input_notes_per_transaction,
+                    )
+                    .ok_or_else(|| {
+                        // the selection only fails if the notes carry less than the missing amount
+                        let available = checked_sum(candidates.iter().map(|(_, amount)| *amount))
+                            .unwrap_or(u64::MAX);
+                        ClientError::InsufficientBalance(
+                            account_id,
+                            format!(
+                                "{} of the asset issued by {faucet_id} are needed, the account \
+                                holds {balance} and its consumable notes carry {available}",
+                                asset.amount()
+                            ),
+                        )
+                    })?;
+                let selected_amount = checked_sum(
+                    candidates
+                        .iter()
+                        .filter(|(note_id, _)| selected_note_ids.contains(note_id))
+                        .map(|(_, amount)| *amount),
+                )
+                .ok_or_else(|| {
+                    ClientError::InvalidNote(format!(
+                        "the notes selected for the payment carry more of the asset issued by \
+                        {faucet_id} than an amount can hold"
+                    ))
+                })?;
+
+                (
+                    selected_note_ids,
+                    selected_amount.saturating_sub(missing_amount),
+                )
+            }
+            Asset::NonFungible(_) => {
+                let (account, _) = self.get_account_stub_by_id(account_id)?;
+                if self
+                    .get_vault_assets(account.vault_root())?
+                    .contains(&asset)
+                {
+                    return Ok((Vec::new(), 0));
+                }
+
+                consumable_notes
+                    .iter()
+                    .find(|note| note.note().assets().iter().any(|held| *held == asset))
+                    .map(|note| (vec![note.note_id()], 0))
+                    .ok_or_else(|| {
+                        ClientError::InsufficientBalance(
+                            account_id,
+                            "the non-fungible asset is neither in the account's vault nor in a \
+                            note it can consume"
+                                .to_string(),
+                        )
+                    })?
+            }
+        };
+
+        self.store.lock_notes(&selected_note_ids, reservation)?;
+
+        Ok((selected_note_ids, surplus))
+    }
+
+    /// Returns a random holder for the locks of the notes selected for a transaction, until the
+    /// transaction is executed and its ID is known
+    fn new_note_reservation(&mut self) -> Digest {
+        let reservation: [u64; 4] = self.rng.gen();
+        Digest::new(reservation.map(Felt::new))
+    }
+
+    /// Creates and executes a transaction that creates the provided notes, moving their assets
+    /// out of the sender account
+    fn new_create_notes_transaction(
+        &mut self,
+        account_id: AccountId,
+        notes: Vec<Note>,
+    ) -> Result<TransactionResult, ClientError> {
+        // checked before generating the script, which grows with the number of notes
+        self.protocol_limits
+            .check(ProtocolLimit::OutputNotesPerTransaction, notes.len())?;
+
+        let mut create_notes = String::new();
+        for note in notes.iter() {
+            if note.metadata().sender() != account_id {
+                return Err(ClientError::InvalidNote(format!(
+                    "note {} is sent by account {} instead of {}",
+                    note.id().inner(),
+                    note.metadata().sender(),
+                    account_id
+                )));
+            }
+            // The basic wallet creates notes carrying exactly one asset
+            let asset = match note.assets().iter().collect::<Vec<_>>()[..] {
+                [asset] => *asset,
+                _ => {
+                    return Err(ClientError::InvalidNote(format!(
+                        "note {} must carry exactly one asset to be created by a wallet",
+                        note.id().inner()
+                    )))
+                }
+            };
+
+            let recipient = note
+                .recipient()
+                .iter()
+                .map(|x| x.as_int().to_string())
+                .collect::<Vec<_>>()
+                .join(".");
+            create_notes.push_str(&send_asset_masm(&recipient, note.metadata().tag(), asset));
+        }
+
+        self.tx_executor.load_account(account_id)?;
+
+        let block_ref = self.get_sync_height()?;
+
+        let tx_script_code =
+            ProgramAst::parse(&CREATE_NOTES_SCRIPT.replace("{create_notes}", &create_notes))
+                .expect("generated MASM is well-formed");
+
+        self.compile_and_execute_tx(account_id, &[], notes, tx_script_code, block_ref)
+    }
+
+    /// Creates and executes a transaction that sends every asset in a note to the provided
+    /// recipient. The notes' details are not known to the client, so they are not returned as
+    /// created notes
+    fn new_pay_to_recipient_transaction(
+        &mut self,
+        sender_account_id: AccountId,
+        recipient_digest: Digest,
+        assets: &[Asset],
+        tag: u64,
+    ) -> Result<TransactionResult, ClientError> {
+        let tx_script_code = pay_to_recipient_script(recipient_digest, assets, tag)?;
+
+        self.tx_executor.load_account(sender_account_id)?;
+
+        let block_ref = self.get_sync_height()?;
+
+        self.compile_and_execute_tx(sender_account_id, &[], vec![], tx_script_code, block_ref)
+    }
+
+    /// Creates and executes a transaction running a custom script against the account, after
+    /// validating the script's inputs
+    fn new_custom_script_transaction(
+        &mut self,
+        account_id: AccountId,
+        script: ProgramAst,
+        script_inputs: &TransactionScriptInputs,
+    ) -> Result<TransactionResult, ClientError> {
+        let script_inputs = script_inputs.to_advice_map()?;
+
+        self.tx_executor.load_account(account_id)?;
+
+        let block_ref = self.get_sync_height()?;
+
+        self.compile_and_execute_tx_with_inputs(
+            account_id,
+            &[],
+            vec![],
+            script,
+            script_inputs,
+            block_ref,
+        )
+    }
+
+    // ACCOUNT CODE UPGRADE
+    // --------------------------------------------------------------------------------------------
+
+    /// Replaces the code of an account with updatable code by `new_code`, the source of a MASM
+    /// module, then proves and submits the transaction making the change. Returns the ID of the
+    /// transaction.
+    ///
+    /// The change is made through the [SET_CODE_PROCEDURE] exported by the account's current
+    /// code. The previous code is kept in the store along with a record of the upgrade, so the
+    /// account can be moved back to it with another upgrade.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the account's code cannot be changed, either because of the account's
+    /// type or because its code does not export [SET_CODE_PROCEDURE], or if `new_code` is not a
+    /// valid module.
+    #[instrument(skip_all, fields(%account_id))]
+    pub async fn upgrade_account_code(
+        &mut self,
+        account_id: AccountId,
+        new_code: &str,
+    ) -> Result<Digest, ClientError> {
+        if account_id.account_type() != AccountType::RegularAccountUpdatableCode {
+            return Err(ClientError::InvalidAccountCodeUpgrade(format!(
+                "account {account_id} does not have updatable code"
+            )));
+        }
+
+        // the procedure roots of the code follow the order its procedures are exported in
+        let (procedures, module) = self.store.get_account_code_by_account_id(account_id)?;
+        let set_code_root = module
+            .procs()
+            .iter()
+            .filter(|procedure| procedure.is_export)
+            .position(|procedure| procedure.name.as_ref() == SET_CODE_PROCEDURE)
+            .and_then(|index| procedures.get(index))
+            .ok_or_else(|| {
+                ClientError::InvalidAccountCodeUpgrade(format!(
+                    "the code of account {account_id} does not export `{SET_CODE_PROCEDURE}`"
+                ))
+            })?;
+
+        let code_ast = ModuleAst::parse(new_code).map_err(|err| {
+            ClientError::InvalidAccountCodeUpgrade(format!("failed to parse the new code: {err}"))
+        })?;
+        let account_code = AccountCode::new(code_ast, &TransactionKernel::assembler())?;
+
+        self.tx_executor.load_account(account_id)?;
+
+        let block_ref = self.get_sync_height()?;
 
         let tx_script_code = ProgramAst::parse(
-            &AUTH_SEND_ASSET_SCRIPT
-                .replace("{recipient}", &recipient)
+            &UPGRADE_ACCOUNT_CODE_SCRIPT
                 .replace(
-                    "{tag}",
-                    &Felt::new(Into::<u64>::into(target_account_id)).to_string(),
+                    "{code_root}",
+                    &prepare_word(&account_code.root().into()).to_string(),
                 )
-                .replace("{asset}", &prepare_word(&fungible_asset.into()).to_string()),
+                .replace("{set_code}", &set_code_root.to_string()),
         )
         .expect("shipped MASM is well-formed");
 
-        self.compile_and_execute_tx(
-            sender_account_id,
-            &[],
-            vec![created_note],
-            tx_script_code,
-            block_ref,
-        )
+        let tx_result =
+            self.compile_and_execute_tx(account_id, &[], vec![], tx_script_code, block_ref)?;
+        let transaction_id = tx_result.executed_transaction().id().inner();
+
+        self.store
+            .insert_executed_code_upgrade(&tx_result, &account_code)?;
+        self.advance_transaction(transaction_id).await?;
+
+        Ok(transaction_id)
     }
 
     fn compile_and_execute_tx(
@@ -361,6 +1593,35 @@ impl Client {
         tx_script: ProgramAst,
         block_num: u32,
     ) -> Result<TransactionResult, ClientError> {
+        self.compile_and_execute_tx_with_inputs(
+            account_id,
+            input_notes,
+            output_notes,
+            tx_script,
+            vec![],
+            block_num,
+        )
+    }
+
+    /// Compiles and executes the transaction script with `script_inputs` added to its advice map,
+    /// next to the public key authenticating the transaction
+    fn compile_and_execute_tx_with_inputs(
+        &mut self,
+        account_id: AccountId,
+        input_notes: &[NoteId],
+        output_notes: Vec<Note>,
+        tx_script: ProgramAst,
+        mut script_inputs: Vec<(Word, Vec<Felt>)>,
+        block_num: u32,
+    ) -> Result<TransactionResult, ClientError> {
+        // Validate the transaction against protocol limits before spending time executing it
+        let limits = self.protocol_limits;
+        limits.check(ProtocolLimit::InputNotesPerTransaction, input_notes.len())?;
+        limits.check(ProtocolLimit::OutputNotesPerTransaction, output_notes.len())?;
+        for note in output_notes.iter() {
+            limits.check(ProtocolLimit::AssetsPerNote, note.assets().num_assets())?;
+        }
+
         let account_auth = self.get_account_auth(account_id)?;
         let (pubkey_input, advice_map): (Word, Vec<Felt>) = match account_auth {
             AuthInfo::RpoFalcon512(key) => (
@@ -371,7 +1632,7 @@ impl Client {
                     .collect::<Vec<Felt>>(),
             ),
         };
-        let script_inputs = vec![(pubkey_input, advice_map)];
+        script_inputs.push((pubkey_input, advice_map));
 
         let tx_script = self
             .tx_executor
@@ -388,23 +1649,362 @@ impl Client {
         Ok(TransactionResult::new(executed_transaction, output_notes))
     }
 
-    /// Proves the specified transaction witness, submits it to the node, and stores the transaction in
-    /// the local database for tracking.
+    /// Records the executed transaction in the local database for tracking, then proves it and
+    /// submits it to the node.
+    ///
+    /// Each stage is persisted as soon as it completes, so if this is interrupted the transaction
+    /// can be resumed with [Client::advance_transaction].
+    ///
+    /// In offline mode the transaction is queued with [Client::queue_transaction] instead of being
+    /// submitted.
     pub async fn send_transaction(
         &mut self,
         tx_result: TransactionResult,
     ) -> Result<(), ClientError> {
-        let transaction_prover = TransactionProver::new(ProvingOptions::default());
-        let proven_transaction =
-            transaction_prover.prove_transaction(tx_result.executed_transaction().clone())?;
+        if self.offline_mode {
+            return self.queue_transaction(tx_result);
+        }
+
+        let transaction_id = tx_result.executed_transaction().id().inner();
+        self.store.insert_executed_transaction(&tx_result)?;
+
+        self.advance_transaction(transaction_id).await?;
+
+        Ok(())
+    }
+
+    /// Records the executed transaction and proves it against the state the client last synced,
+    /// then queues it in the outbox until [Client::flush_outbox] submits it, which doesn't require
+    /// reaching the node.
+    ///
+    /// The account changes of the transaction are applied to the local state right away, so that
+    /// further transactions of the account can be executed and queued after it. If proving fails,
+    /// the transaction is discarded and the changes are reverted.
+    pub fn queue_transaction(&mut self, tx_result: TransactionResult) -> Result<(), ClientError> {
+        let transaction_id = tx_result.executed_transaction().id().inner();
+        self.store.insert_executed_transaction(&tx_result)?;
+
+        self.queue_stored_transaction(transaction_id)
+    }
+
+    /// Applies the account changes of a recorded transaction, then proves it and queues it in the
+    /// outbox, see [Client::queue_transaction]
+    fn queue_stored_transaction(&mut self, transaction_id: Digest) -> Result<(), ClientError> {
+        let watched_accounts = self.get_watched_accounts()?;
+        self.store
+            .apply_executed_transaction_delta(transaction_id)?;
+        self.notify_account_watchers(watched_accounts)?;
+
+        if let Err(err) = self.prove_stored_transaction(transaction_id) {
+            self.discard_transactions(&[transaction_id])?;
+            return Err(err);
+        }
+        self.store.queue_transaction(transaction_id)?;
+        info!("Queued transaction {transaction_id} until it can be submitted");
+
+        Ok(())
+    }
+
+    /// Returns the IDs of the transactions queued in offline mode that were not submitted yet, in
+    /// the order they were queued
+    pub fn get_queued_transactions(&self) -> Result<Vec<Digest>, ClientError> {
+        Ok(self.store.get_queued_transactions()?)
+    }
+
+    /// Submits the transactions queued in offline mode, in the order they were queued, each of
+    /// them being advanced like with [Client::advance_transaction].
+    ///
+    /// Transactions that became stale while queued are left in the outbox, along with the
+    /// transactions of the same accounts queued after them, and are reported in the returned
+    /// [OutboxFlush]. They can be re-executed with [Client::reexecute_transaction] once the
+    /// client is synced.
+    ///
+    /// # Errors
+    /// Returns an error if a transaction could not be submitted, in which case it and the
+    /// transactions queued after it are left in the outbox.
+    pub async fn flush_outbox(&mut self) -> Result<OutboxFlush, ClientError> {
+        let mut flush = OutboxFlush::default();
+        let mut blocked_accounts: Vec<AccountId> = Vec::new();
+
+        for transaction_id in self.store.get_queued_transactions()? {
+            let account_id = self.store.get_transaction_account_id(transaction_id)?;
+            if blocked_accounts.contains(&account_id) {
+                flush.blocked.push(transaction_id);
+                continue;
+            }
+
+            match self.advance_transaction(transaction_id).await {
+                Ok(_) => flush.submitted.push(transaction_id),
+                Err(ClientError::StaleTransaction(..)) => {
+                    flush.stale.push(transaction_id);
+                    blocked_accounts.push(account_id);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(flush)
+    }
+
+    /// Advances a tracked transaction through its lifecycle, resuming from the last stage that was
+    /// persisted:
+    ///
+    /// - An executed transaction is proven.
+    /// - A proven transaction is submitted to the node, after which the account changes and
+    ///   created notes are applied to the local state.
+    ///
+    /// Returns the status reached, which is [TransactionStatus::Submitted] unless the transaction
+    /// had already been committed. Committing is detected while syncing.
+    ///
+    /// A stage failing with a retryable error is retried according to the client's
+    /// [RetryPolicy](crate::config::RetryPolicy).
+    ///
+    /// An unsubmitted transaction executed against a block further behind the sync height than
+    /// the configured `max_staleness` is not advanced, and [ClientError::StaleTransaction] is
+    /// returned instead. It can then be re-executed with [Client::reexecute_transaction].
+    #[instrument(skip_all, fields(%transaction_id))]
+    pub async fn advance_transaction(
+        &mut self,
+        transaction_id: Digest,
+    ) -> Result<TransactionStatus, ClientError> {
+        if matches!(
+            self.store.get_transaction_status(transaction_id)?,
+            TransactionStatus::Executed | TransactionStatus::Proven
+        ) {
+            self.check_transaction_staleness(transaction_id)?;
+        }
+
+        let mut attempt = 1;
+        loop {
+            let result = match self.store.get_transaction_status(transaction_id)? {
+                TransactionStatus::Executed => self.prove_stored_transaction(transaction_id),
+                TransactionStatus::Proven => self.submit_stored_transaction(transaction_id).await,
+                status => return Ok(status),
+            };
+
+            match result {
+                Ok(()) => attempt = 1,
+                Err(err) => match self.retry_policy.retry_delay(&err, attempt) {
+                    Some(delay) => {
+                        warn!("Failed to advance transaction ({err}), retrying in {delay:?}");
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                    }
+                    None => return Err(err),
+                },
+            }
+        }
+    }
+
+    /// Stops tracking a transaction that will never be committed, for instance because one of the
+    /// notes it consumes was consumed by another transaction. The account changes applied for it
+    /// are reverted using the stored account deltas, the notes it consumes are released and the
+    /// notes it creates stop being tracked.
+    ///
+    /// # Errors
+    /// Returns an error if the transaction was committed, or if its account was changed after it,
+    /// in which case the later transactions of the account have to be discarded first.
+    pub fn discard_transaction(&mut self, transaction_id: Digest) -> Result<(), ClientError> {
+        let watched_accounts = self.get_watched_accounts()?;
+        self.store.discard_transaction(transaction_id)?;
+        info!("Discarded transaction {transaction_id}");
+        self.notify_account_watchers(watched_accounts)
+    }
+
+    /// Executes an unsubmitted transaction again against the block the client is synced to,
+    /// consuming the same notes with the same transaction script, then proves and submits it.
+    ///
+    /// The new transaction is executed against the account state the original one was executed
+    /// against, and replaces it only once executed, so the original transaction is kept if the
+    /// execution fails. Replacing it releases the notes it consumes and reverts the account
+    /// changes applied for it. Transactions upgrading the code of an account cannot be
+    /// re-executed, as the new code is not kept in the transaction script.
+    ///
+    /// Returns the ID of the new transaction.
+    pub async fn reexecute_transaction(
+        &mut self,
+        transaction_id: Digest,
+    ) -> Result<Digest, ClientError> {
+        if self.store.get_upgraded_code_root(transaction_id)?.is_some() {
+            return Err(ClientError::InvalidAccountCodeUpgrade(format!(
+                "transaction {transaction_id} upgrades the code of its account and cannot be re-executed"
+            )));
+        }
+
+        let tx_result = self.store.get_executed_transaction(transaction_id)?;
+        let executed_transaction = tx_result.executed_transaction();
+        let account_id = executed_transaction.account_id();
+        let input_notes: Vec<NoteId> = executed_transaction
+            .input_notes()
+            .iter()
+            .map(|input_note| input_note.note().id())
+            .collect();
+        let tx_script = tx_result.transaction_script().cloned();
+        let output_notes = tx_result.created_notes().clone();
+
+        let account_state = self
+            .store
+            .get_account_before_transaction(account_id, transaction_id)?;
+        self.account_state_overrides
+            .lock()
+            .expect("account states lock is not poisoned")
+            .insert(account_id, account_state);
+        let block_num = self.get_sync_height()?;
+        let executed_transaction = self.tx_executor.load_account(account_id).and_then(|_| {
+            self.tx_executor
+                .execute_transaction(account_id, block_num, &input_notes, tx_script)
+        });
+        self.account_state_overrides
+            .lock()
+            .expect("account states lock is not poisoned")
+            .remove(&account_id);
+
+        let tx_result = TransactionResult::new(executed_transaction?, output_notes);
+        let new_transaction_id = tx_result.executed_transaction().id().inner();
+        let watched_accounts = self.get_watched_accounts()?;
+        self.store.replace_transaction(transaction_id, &tx_result)?;
+        self.notify_account_watchers(watched_accounts)?;
+        info!("Re-executed stale transaction {transaction_id} as {new_transaction_id}");
+
+        if self.offline_mode {
+            self.queue_stored_transaction(new_transaction_id)?;
+        } else {
+            self.advance_transaction(new_transaction_id).await?;
+        }
+
+        Ok(new_transaction_id)
+    }
+
+    /// Executes a sequence of dependent transactions, such as consuming a note into an account
+    /// and then paying out of it, and submits them in order.
+    ///
+    /// Each transaction is executed against the state left by the previous ones, as the account
+    /// changes of every transaction are applied locally right after it is executed. All of them
+    /// are executed and proven before the first one is submitted, so if any of these steps fails
+    /// every transaction is discarded and the local state is left as it was. If submitting one of
+    /// them fails, it is discarded along with the ones following it.
+    ///
+    /// Returns the IDs of the submitted transactions.
+    pub async fn execute_program(
+        &mut self,
+        transaction_templates: Vec<TransactionTemplate>,
+    ) -> Result<Vec<Digest>, ClientError> {
+        let mut transaction_ids = Vec::with_capacity(transaction_templates.len());
+
+        for transaction_template in transaction_templates {
+            if let Err(err) = self.execute_program_step(transaction_template, &mut transaction_ids)
+            {
+                self.discard_transactions(&transaction_ids)?;
+                return Err(err);
+            }
+        }
+
+        for transaction_id in transaction_ids.iter() {
+            if let Err(err) = self.prove_stored_transaction(*transaction_id) {
+                self.discard_transactions(&transaction_ids)?;
+                return Err(err);
+            }
+        }
+
+        for (index, transaction_id) in transaction_ids.iter().enumerate() {
+            if let Err(err) = self.advance_transaction(*transaction_id).await {
+                self.discard_transactions(&transaction_ids[index..])?;
+                return Err(err);
+            }
+        }
+
+        Ok(transaction_ids)
+    }
+
+    /// Executes a transaction of a program, records it in the store and applies its account
+    /// changes locally. The ID of the transaction is added to `transaction_ids` as soon as it is
+    /// recorded, so that it can be discarded if a later step fails.
+    fn execute_program_step(
+        &mut self,
+        transaction_template: TransactionTemplate,
+        transaction_ids: &mut Vec<Digest>,
+    ) -> Result<(), ClientError> {
+        let tx_result = self.new_transaction(transaction_template)?;
+        let transaction_id = tx_result.executed_transaction().id().inner();
+
+        self.store.insert_executed_transaction(&tx_result)?;
+        transaction_ids.push(transaction_id);
+
+        let watched_accounts = self.get_watched_accounts()?;
+        self.store
+            .apply_executed_transaction_delta(transaction_id)?;
+        self.notify_account_watchers(watched_accounts)
+    }
+
+    /// Discards transactions of a program that were not submitted, most recent first so that the
+    /// account changes applied for them are reverted in order
+    fn discard_transactions(&mut self, transaction_ids: &[Digest]) -> Result<(), ClientError> {
+        let watched_accounts = self.get_watched_accounts()?;
+        for transaction_id in transaction_ids.iter().rev() {
+            self.store.discard_transaction(*transaction_id)?;
+        }
+        self.notify_account_watchers(watched_accounts)
+    }
 
+    /// Returns an error if an unsubmitted transaction was executed against a block too far behind
+    /// the sync height to be submitted
+    fn check_transaction_staleness(&self, transaction_id: Digest) -> Result<(), ClientError> {
+        let block_num = self.store.get_transaction_block_num(transaction_id)?;
+        let sync_height = self.get_sync_height()?;
+
+        if sync_height.saturating_sub(block_num) > self.max_transaction_staleness {
+            return Err(ClientError::StaleTransaction(
+                transaction_id,
+                block_num,
+                sync_height,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Proves an executed transaction and marks it as proven in the store
+    fn prove_stored_transaction(&mut self, transaction_id: Digest) -> Result<(), ClientError> {
+        let tx_result = self.store.get_executed_transaction(transaction_id)?;
+
+        let proving_started_at = Instant::now();
+        let watchdog = self.slow_proving_warning.map(spawn_slow_proving_watchdog);
+        let proven_transaction = info_span!("prove_transaction").in_scope(|| {
+            self.prover_mode.prove(
+                &self.proving_options,
+                tx_result.executed_transaction().clone(),
+            )
+        });
+        // dropping the sender wakes the watchdog up before its timeout if proving finished early
+        drop(watchdog);
+        let proven_transaction = proven_transaction?;
+        metrics::record_proving(proving_started_at.elapsed());
+
+        self.store
+            .mark_transaction_proven(transaction_id, &proven_transaction)?;
+        self.emit_events([ClientEvent::TransactionProven { transaction_id }]);
         info!("Proved transaction, submitting to the node...");
 
-        self.submit_proven_transaction_request(proven_transaction.clone())
+        Ok(())
+    }
+
+    /// Submits a proven transaction to the node and applies its effects to the local state
+    async fn submit_stored_transaction(
+        &mut self,
+        transaction_id: Digest,
+    ) -> Result<(), ClientError> {
+        let proven_transaction = self.store.get_proven_transaction(transaction_id)?;
+        self.submit_proven_transaction_request(proven_transaction)
             .await?;
 
-        // Transaction was proven and submitted to the node correctly, persist note details and update account
-        self.store.insert_transaction_data(tx_result)?;
+        // Transaction was submitted to the node correctly, persist note details and update
+        // account
+        let watched_accounts = self.get_watched_accounts()?;
+        let orders = self.get_created_orders(transaction_id)?;
+        self.store
+            .mark_transaction_submitted(transaction_id, &orders)?;
+        self.notify_account_watchers(watched_accounts)?;
+        self.emit_events([ClientEvent::TransactionSubmitted { transaction_id }]);
 
         Ok(())
     }
@@ -428,11 +2028,586 @@ impl Client {
     // --------------------------------------------------------------------------------------------
 
     /// Gets [RpoRandomCoin] from the client
-    fn get_random_coin(&self) -> RpoRandomCoin {
+    pub(super) fn get_random_coin(&mut self) -> RpoRandomCoin {
         // TODO: Initialize coin status once along with the client and persist status for retrieval
-        let mut rng = rand::thread_rng();
-        let coin_seed: [u64; 4] = rng.gen();
+        let coin_seed: [u64; 4] = self.rng.gen();
 
         RpoRandomCoin::new(coin_seed.map(|x| x.into()))
     }
 }
+
+// HELPERS
+// ================================================================================================
+
+/// Kind of the note created by a payment, see [Client::new_p2id_transaction]
+#[derive(Clone, Copy)]
+enum PaymentNoteKind {
+    P2ID,
+    P2IDR { recall_height: u32 },
+    P2IDTimelock { spendable_after_block: u32 },
+}
+
+/// Adds up asset amounts, returning `None` instead of wrapping around if the total does not fit
+/// in a `u64`
+fn checked_sum(amounts: impl IntoIterator<Item = u64>) -> Option<u64> {
+    amounts
+        .into_iter()
+        .try_fold(0u64, |total, amount| total.checked_add(amount))
+}
+
+/// Returns the MASM creating a note with the provided recipient and tag out of `asset`, to be
+/// inserted in a transaction script executed against a basic wallet
+fn send_asset_masm(recipient: &str, tag: Felt, asset: Asset) -> String {
+    format!(
+        "    push.{recipient}\n    push.{tag}\n    push.{}\n    call.wallet::send_asset drop\n    dropw dropw\n",
+        prepare_word(&asset.into())
+    )
+}
+
+/// Returns the script of a payment transaction creating each of `created_notes`, which carry a
+/// single asset, tagged for the account it is paired with
+fn payment_notes_script(created_notes: &[(Note, AccountId)]) -> ProgramAst {
+    let mut create_notes = String::new();
+    for (note, target_account_id) in created_notes.iter() {
+        let recipient = note
+            .recipient()
+            .iter()
+            .map(|x| x.as_int().to_string())
+            .collect::<Vec<_>>()
+            .join(".");
+        let tag = Felt::new(account_note_tag(*target_account_id));
+        let note_asset = *note
+            .assets()
+            .iter()
+            .next()
+            .expect("P2ID notes carry an asset");
+        create_notes.push_str(&send_asset_masm(&recipient, tag, note_asset));
+    }
+
+    ProgramAst::parse(&CREATE_NOTES_SCRIPT.replace("{create_notes}", &create_notes))
+        .expect("generated MASM is well-formed")
+}
+
+/// Returns the script of a transaction creating, for each of `assets`, a note carrying the asset
+/// to the recipient whose digest is `recipient_digest`, retrievable by `tag`
+fn pay_to_recipient_script(
+    recipient_digest: Digest,
+    assets: &[Asset],
+    tag: u64,
+) -> Result<ProgramAst, ClientError> {
+    if tag >= Felt::MODULUS {
+        return Err(ClientError::InvalidNote(format!(
+            "tag {tag} is not a valid field element"
+        )));
+    }
+    let recipient = Word::from(recipient_digest)
+        .iter()
+        .map(|x| x.as_int().to_string())
+        .collect::<Vec<_>>()
+        .join(".");
+    let create_notes = assets
+        .iter()
+        .map(|asset| send_asset_masm(&recipient, Felt::new(tag), *asset))
+        .collect::<String>();
+
+    Ok(
+        ProgramAst::parse(&CREATE_NOTES_SCRIPT.replace("{create_notes}", &create_notes))
+            .expect("generated MASM is well-formed"),
+    )
+}
+
+/// Spawns a thread logging a warning if the returned sender is not dropped within `threshold`,
+/// which is how long proving a transaction is expected to take at most
+fn spawn_slow_proving_watchdog(threshold: Duration) -> mpsc::Sender<()> {
+    let (sender, receiver) = mpsc::channel::<()>();
+    thread::spawn(move || {
+        if let Err(mpsc::RecvTimeoutError::Timeout) = receiver.recv_timeout(threshold) {
+            warn!(
+                "Proving the transaction has been running for more than {}s. Proving is much \
+                faster in release builds and with the `concurrent` feature enabled; on networks \
+                that do not verify proofs, `transactions.prover = \"trusted\"` skips it altogether",
+                threshold.as_secs()
+            );
+        }
+    });
+    sender
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crypto::{
+        dsa::rpo_falcon512::KeyPair, rand::RpoRandomCoin, Felt, FieldElement, StarkField,
+    };
+    use miden_lib::{
+        notes::{create_p2id_note, create_p2idr_note},
+        transaction::TransactionKernel,
+    };
+    use mock::{
+        constants::{ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN, ACCOUNT_ID_SENDER},
+        mock::{
+            account::mock_account, notes::AssetPreservationStatus, transaction::mock_executed_tx,
+        },
+    };
+    use objects::{
+        accounts::{Account, AccountId},
+        assembly::ProgramAst,
+        assets::{Asset, FungibleAsset},
+        notes::NoteId,
+        Digest,
+    };
+
+    use super::{
+        checked_sum, pay_to_recipient_script, payment_notes_script, ProtocolLimit, ProtocolLimits,
+        TransactionResult, TransactionTemplate, AUTH_CONSUME_NOTES_SCRIPT,
+        MAX_INPUT_NOTES_PER_TRANSACTION,
+    };
+    use crate::{
+        client::{
+            accounts::{AccountStorageMode, AccountTemplate},
+            note_interpreter::DecodedNote,
+            notes::create_p2id_timelock_note,
+            Client,
+        },
+        errors::{ClientError, StoreError},
+        store::{
+            accounts::AuthInfo, mock_executor_data_store::MockDataStore, tests::create_test_client,
+        },
+    };
+
+    /// Inserts a basic wallet holding the assets of the mock account vault, among which 100 units
+    /// of the asset of [ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN]
+    fn insert_mock_wallet(client: &mut Client) -> Account {
+        let (wallet, _) = client
+            .new_account(AccountTemplate::BasicWallet {
+                mutable_code: false,
+                storage_mode: AccountStorageMode::Local,
+            })
+            .unwrap();
+        let account = mock_account(
+            Some(ACCOUNT_ID_SENDER),
+            Felt::ONE,
+            Some(wallet.code().clone()),
+            &TransactionKernel::assembler(),
+        );
+        client
+            .store
+            .insert_account(
+                &account,
+                [Felt::ZERO; 4],
+                &AuthInfo::RpoFalcon512(KeyPair::new().unwrap()),
+            )
+            .unwrap();
+        account
+    }
+
+    #[tokio::test]
+    async fn stale_transactions_are_detected() {
+        let mut client = create_test_client();
+        let account_id = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+
+        // the transaction was executed against the genesis block and never submitted
+        let transaction_id = Digest::new([Felt::ONE; 4]);
+        client
+            .store
+            .db
+            .execute(
+                "INSERT INTO transactions (id, account_id, init_account_state, final_account_state, \
+                output_notes, block_num, stage) VALUES (?, ?, '', '', x'', 0, 'executed')",
+                rusqlite::params![String::from(transaction_id), u64::from(account_id) as i64],
+            )
+            .unwrap();
+        client.max_transaction_staleness = 2;
+
+        let set_sync_height = |client: &mut crate::client::Client, block_num: u32| {
+            client
+                .store
+                .db
+                .execute("UPDATE state_sync SET block_num = ?", [block_num])
+                .unwrap();
+        };
+
+        set_sync_height(&mut client, 2);
+        client.check_transaction_staleness(transaction_id).unwrap();
+
+        // once the client synced past the threshold, the transaction is detected as stale and
+        // not advanced any further
+        set_sync_height(&mut client, 3);
+        assert!(matches!(
+            client.check_transaction_staleness(transaction_id),
+            Err(ClientError::StaleTransaction(id, 0, 3)) if id == transaction_id
+        ));
+        assert!(matches!(
+            client.advance_transaction(transaction_id).await,
+            Err(ClientError::StaleTransaction(..))
+        ));
+    }
+
+    #[test]
+    fn transaction_receipts_are_serialized_as_json() {
+        let executed_transaction = mock_executed_tx(AssetPreservationStatus::Preserved);
+        let transaction_id = executed_transaction.id().inner();
+        let account_id = executed_transaction.account_id();
+        let block_num = executed_transaction.block_header().block_num();
+        let nonce = executed_transaction.account_delta().nonce();
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let fee = FungibleAsset::new(faucet_id, 7).unwrap();
+        let transaction =
+            TransactionResult::new(executed_transaction, Vec::new()).with_fee(Some(fee));
+
+        let receipt = serde_json::to_value(transaction.receipt()).unwrap();
+        assert_eq!(receipt["transaction_id"], transaction_id.to_hex());
+        assert_eq!(receipt["account_id"], account_id.to_hex());
+        assert_eq!(receipt["block_num"], block_num);
+        assert_eq!(receipt["output_note_ids"], serde_json::json!([]));
+        assert_eq!(
+            receipt["account_delta"]["nonce"],
+            serde_json::json!(nonce.map(|nonce| nonce.as_int()))
+        );
+        assert_eq!(
+            receipt["fee"],
+            serde_json::json!({
+                "type": "fungible",
+                "faucet_id": faucet_id.to_hex(),
+                "amount": 7,
+            })
+        );
+    }
+
+    #[test]
+    fn fees_are_estimated_by_the_fee_policy() {
+        let mut client = create_test_client();
+        let account_id = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let template = TransactionTemplate::ConsumeNotes(account_id, Vec::new());
+
+        // the network charges no fee by default
+        assert_eq!(client.estimate_fee(&template).unwrap(), None);
+
+        let fee = FungibleAsset::new(faucet_id, 3).unwrap();
+        client.set_fee_policy(move |template: &TransactionTemplate| match template {
+            TransactionTemplate::ConsumeNotes(..) => Ok(Some(fee)),
+            _ => Err(ClientError::InvalidNote("no fee schedule".to_string())),
+        });
+        assert_eq!(client.estimate_fee(&template).unwrap(), Some(fee));
+        assert!(client
+            .estimate_fee(&TransactionTemplate::ConsumeAllNotes(account_id))
+            .is_err());
+    }
+
+    #[test]
+    fn consume_notes_template_is_split_by_input_note_limit() {
+        let account_id = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+        let note_ids: Vec<NoteId> = (0..MAX_INPUT_NOTES_PER_TRANSACTION + 1)
+            .map(|_| NoteId::from(Digest::default()))
+            .collect();
+
+        let batches = TransactionTemplate::ConsumeNotes(account_id, note_ids.clone())
+            .split_into_batches(ProtocolLimits::default());
+        assert_eq!(batches.len(), 2);
+
+        match (&batches[0], &batches[1]) {
+            (
+                TransactionTemplate::ConsumeNotes(_, first_batch),
+                TransactionTemplate::ConsumeNotes(_, second_batch),
+            ) => {
+                assert_eq!(first_batch.len(), MAX_INPUT_NOTES_PER_TRANSACTION);
+                assert_eq!(second_batch.len(), 1);
+            }
+            _ => panic!("splitting a template should preserve its type"),
+        }
+
+        // lower limits split the notes into more batches
+        let limits = ProtocolLimits {
+            max_input_notes_per_transaction: 100,
+            ..ProtocolLimits::default()
+        };
+        let batches =
+            TransactionTemplate::ConsumeNotes(account_id, note_ids).split_into_batches(limits);
+        assert_eq!(batches.len(), 11);
+    }
+
+    #[test]
+    fn pay_to_recipient_script_compiles() {
+        let client = create_test_client();
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let assets: Vec<Asset> = [100, 30]
+            .into_iter()
+            .map(|amount| FungibleAsset::new(faucet_id, amount).unwrap().into())
+            .collect();
+        let recipient_digest = Digest::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::ONE]);
+
+        // the script creates a note for each asset with the wallet's procedures, then
+        // authenticates the transaction
+        let script = pay_to_recipient_script(recipient_digest, &assets, 42).unwrap();
+        client
+            .tx_executor
+            .compile_tx_script(script, vec![], vec![])
+            .unwrap();
+
+        assert!(matches!(
+            pay_to_recipient_script(recipient_digest, &assets, u64::MAX),
+            Err(ClientError::InvalidNote(_))
+        ));
+    }
+
+    #[test]
+    fn protocol_limit_check() {
+        let limit = ProtocolLimit::AssetsPerNote;
+        let limits = ProtocolLimits::default();
+        assert!(limits.check(limit, limit.max()).is_ok());
+        assert!(limits.check(limit, limit.max() + 1).is_err());
+
+        let limits = ProtocolLimits {
+            max_assets_per_note: 10,
+            ..ProtocolLimits::default()
+        };
+        assert!(limits.check(limit, 10).is_ok());
+        assert!(matches!(
+            limits.check(limit, 11),
+            Err(ClientError::ProtocolLimitExceeded(
+                ProtocolLimit::AssetsPerNote,
+                10,
+                11
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn payments_report_the_surplus_of_the_consumed_notes() {
+        let mut client = create_test_client();
+        let (account, _) = client
+            .new_account(AccountTemplate::BasicWallet {
+                mutable_code: false,
+                storage_mode: AccountStorageMode::Local,
+            })
+            .unwrap();
+
+        // the account holds nothing but a committed note carrying 100 units
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let note = create_p2id_note(
+            faucet_id,
+            account.id(),
+            vec![FungibleAsset::new(faucet_id, 100).unwrap().into()],
+            RpoRandomCoin::new([Felt::ZERO; 4]),
+        )
+        .unwrap();
+        client.import_input_note(note.clone().into()).unwrap();
+        client
+            .store
+            .db
+            .execute("UPDATE input_notes SET status = 'committed'", [])
+            .unwrap();
+
+        // paying 30 consumes the note and leaves 70 as change
+        let payment = FungibleAsset::new(faucet_id, 30).unwrap().into();
+        let reservation = client.new_note_reservation();
+        let (selected_note_ids, surplus) = client
+            .select_notes_for_payment(account.id(), payment, reservation)
+            .unwrap();
+        assert_eq!((selected_note_ids, surplus), (vec![note.id()], 70));
+
+        // the change is sent back to the account in a P2ID note of its own
+        let change_note = client
+            .new_change_note(payment, account.id(), surplus)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            change_note.assets().iter().collect::<Vec<_>>(),
+            vec![&Asset::from(FungibleAsset::new(faucet_id, 70).unwrap())]
+        );
+        assert_eq!(change_note.metadata().sender(), account.id());
+        assert_eq!(
+            client.note_interpreter.decode(&change_note),
+            Some(DecodedNote::P2ID {
+                target: account.id()
+            })
+        );
+
+        // a payment covered by the account's vault leaves no change
+        assert!(client
+            .new_change_note(payment, account.id(), 0)
+            .unwrap()
+            .is_none());
+
+        // the note is locked as soon as it is selected, so another payment cannot select it
+        assert!(matches!(
+            client.store.check_notes_unlocked(&[note.id()]),
+            Err(StoreError::NoteLocked(note_id, holder))
+                if note_id == note.id() && holder == reservation
+        ));
+        let other_reservation = client.new_note_reservation();
+        assert!(matches!(
+            client.select_notes_for_payment(account.id(), payment, other_reservation),
+            Err(ClientError::InsufficientBalance(..))
+        ));
+
+        // the lock is handed over to the executed transaction
+        let transaction_id = Digest::new([Felt::ONE; 4]);
+        client
+            .store
+            .transfer_note_locks(reservation, transaction_id)
+            .unwrap();
+        assert!(matches!(
+            client.store.check_notes_unlocked(&[note.id()]),
+            Err(StoreError::NoteLocked(_, holder)) if holder == transaction_id
+        ));
+    }
+
+    #[tokio::test]
+    async fn payments_create_their_change_note() {
+        let mut client = create_test_client();
+        let account = insert_mock_wallet(&mut client);
+        client.set_data_store(MockDataStore::with_existing(account.clone(), None, None));
+        client.tx_executor.load_account(account.id()).unwrap();
+
+        // paying 30 out of the 100 units, the 70 left are sent back in a change note
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let target_account_id = AccountId::from_hex("0x168187d729b31a84").unwrap();
+        let payment = FungibleAsset::new(faucet_id, 30).unwrap().into();
+        let payment_note = create_p2id_note(
+            account.id(),
+            target_account_id,
+            vec![payment],
+            RpoRandomCoin::new([Felt::ZERO; 4]),
+        )
+        .unwrap();
+        let change_note = client
+            .new_change_note(payment, account.id(), 70)
+            .unwrap()
+            .unwrap();
+        let expected_note_ids = vec![payment_note.id(), change_note.id()];
+
+        let created_notes = vec![
+            (payment_note, target_account_id),
+            (change_note, account.id()),
+        ];
+        let tx_script = payment_notes_script(&created_notes);
+        let transaction = client
+            .compile_and_execute_tx(
+                account.id(),
+                &[],
+                created_notes.into_iter().map(|(note, _)| note).collect(),
+                tx_script,
+                0,
+            )
+            .unwrap();
+
+        let output_note_ids = transaction
+            .executed_transaction()
+            .output_notes()
+            .iter()
+            .map(|note| note.id())
+            .collect::<Vec<_>>();
+        assert_eq!(output_note_ids, expected_note_ids);
+        assert!(transaction
+            .executed_transaction()
+            .account_delta()
+            .nonce()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn timelocked_notes_are_consumed_once_unlocked() {
+        let mut client = create_test_client();
+        let account = insert_mock_wallet(&mut client);
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let asset: Asset = FungibleAsset::new(faucet_id, 10).unwrap().into();
+
+        // the mock chain has the same reference block whichever notes it holds
+        let block_num = MockDataStore::with_existing(account.clone(), None, None)
+            .block_header
+            .block_num();
+
+        let mut consume = |spendable_after_block: u32| {
+            let note = create_p2id_timelock_note(
+                faucet_id,
+                account.id(),
+                &[asset],
+                spendable_after_block,
+                None,
+                RpoRandomCoin::new([Felt::new(spendable_after_block as u64); 4]),
+            )
+            .unwrap();
+            client.set_data_store(MockDataStore::with_existing(
+                account.clone(),
+                None,
+                Some(vec![note.clone()]),
+            ));
+            client.tx_executor.load_account(account.id()).unwrap();
+            let tx_script = ProgramAst::parse(AUTH_CONSUME_NOTES_SCRIPT).unwrap();
+            client
+                .compile_and_execute_tx(account.id(), &[note.id()], vec![], tx_script, block_num)
+                .map(|transaction| (note.id(), transaction))
+        };
+
+        // the note is locked up to the reference block, included
+        assert!(matches!(
+            consume(block_num),
+            Err(ClientError::TransactionExecutionError(_))
+        ));
+
+        // past its unlock height, the target account receives the note's asset
+        let (note_id, transaction) = consume(block_num - 1).unwrap();
+        let executed_transaction = transaction.executed_transaction();
+        assert_eq!(
+            executed_transaction
+                .input_notes()
+                .iter()
+                .map(|note| note.id())
+                .collect::<Vec<_>>(),
+            vec![note_id]
+        );
+        assert_eq!(
+            executed_transaction
+                .account_delta()
+                .vault()
+                .added_assets
+                .clone(),
+            vec![asset]
+        );
+    }
+
+    #[test]
+    fn payments_only_consume_p2id_notes() {
+        let mut client = create_test_client();
+        let (account, _) = client
+            .new_account(AccountTemplate::BasicWallet {
+                mutable_code: false,
+                storage_mode: AccountStorageMode::Local,
+            })
+            .unwrap();
+
+        // the only committed note addressed to the account is a P2IDR note its sender may recall
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let note = create_p2idr_note(
+            faucet_id,
+            account.id(),
+            vec![FungibleAsset::new(faucet_id, 100).unwrap().into()],
+            5,
+            RpoRandomCoin::new([Felt::ZERO; 4]),
+        )
+        .unwrap();
+        client.import_input_note(note.into()).unwrap();
+        client
+            .store
+            .db
+            .execute("UPDATE input_notes SET status = 'committed'", [])
+            .unwrap();
+
+        let payment = FungibleAsset::new(faucet_id, 30).unwrap().into();
+        let reservation = client.new_note_reservation();
+        assert!(matches!(
+            client.select_notes_for_payment(account.id(), payment, reservation),
+            Err(ClientError::InsufficientBalance(..))
+        ));
+    }
+
+    #[test]
+    fn amounts_are_summed_without_overflowing() {
+        assert_eq!(checked_sum([1, 2, 3]), Some(6));
+        assert_eq!(checked_sum([u64::MAX, 1]), None);
+    }
+}
@@ -1,31 +1,260 @@
-use core::fmt;
-use std::path::PathBuf;
+use core::{fmt, time::Duration};
+use std::{collections::BTreeMap, env, path::PathBuf};
 
-use serde::Deserialize;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    client::{
+        note_selection::NoteSelectionStrategy,
+        prover::TransactionProverMode,
+        proxy::{Proxy, DIRECT_CONNECTION},
+        transactions::{ProtocolLimit, ProtocolLimits},
+    },
+    errors::{ClientError, ConfigError},
+};
 
 // CLIENT CONFIG
 // ================================================================================================
 
 /// Configuration options of Miden client.
-#[derive(Debug, Default, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct ClientConfig {
+    /// Directory the client keeps its files in, which relative store paths are relative to.
+    /// Defaults to the current directory, while the CLI defaults to the platform's data
+    /// directory, see [default_data_directory]
+    #[serde(default)]
+    pub data_directory: PathBuf,
     /// Describes settings related to the store.
     pub store: StoreConfig,
     /// Describes settings related to the RPC endpoint
     pub rpc: RpcConfig,
+    /// Describes where sync events are reported when running in watch mode
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    /// Describes how operations failing with a retryable error are retried
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    /// Describes the named wallets kept alongside the default store, and which one is in use
+    #[serde(default)]
+    pub wallets: WalletsConfig,
+    /// How the notes consumed to top up an account's vault are picked when a payment needs more
+    /// than the account holds
+    #[serde(default)]
+    pub note_selection: NoteSelectionStrategy,
+    /// Describes how long executed transactions can wait before being proven and submitted
+    #[serde(default)]
+    pub transactions: TransactionsConfig,
+    /// Describes the faucet services test tokens are requested from
+    #[serde(default)]
+    pub faucet: FaucetConfig,
 }
 
 impl ClientConfig {
     /// Returns a new instance of [ClientConfig] with the specified store path and node endpoint.
-    pub const fn new(store: StoreConfig, rpc: RpcConfig) -> Self {
-        Self { store, rpc }
+    /// Notifications are disabled.
+    pub fn new(store: StoreConfig, rpc: RpcConfig) -> Self {
+        Self {
+            data_directory: PathBuf::new(),
+            store,
+            rpc,
+            notifications: NotificationsConfig::default(),
+            retry: RetryPolicy::default(),
+            wallets: WalletsConfig::default(),
+            note_selection: NoteSelectionStrategy::default(),
+            transactions: TransactionsConfig::default(),
+            faucet: FaucetConfig::default(),
+        }
+    }
+
+    /// Returns the URL of the faucet service tokens are requested from by default, which is the
+    /// faucet of the active wallet if one is set for it and the one under `faucet.url` otherwise
+    pub fn faucet_url(&self) -> Option<&str> {
+        self.wallets
+            .active
+            .as_ref()
+            .and_then(|name| self.faucet.wallets.get(name))
+            .or(self.faucet.url.as_ref())
+            .map(String::as_str)
+    }
+
+    /// Returns the settings of the store the client works with, which is the store of the active
+    /// wallet if one is set and the one in the `store` section otherwise
+    pub fn active_store(&self) -> &StoreConfig {
+        self.wallets
+            .active
+            .as_ref()
+            .and_then(|name| self.wallets.stores.get(name))
+            .unwrap_or(&self.store)
+    }
+
+    /// Returns the path of the database file of `store`, relative paths being resolved against
+    /// the [data directory](ClientConfig::data_directory)
+    pub fn database_path(&self, store: &StoreConfig) -> PathBuf {
+        self.data_directory.join(&store.database_filepath)
+    }
+
+    /// Mutable version of [ClientConfig::active_store]
+    pub fn active_store_mut(&mut self) -> &mut StoreConfig {
+        match self
+            .wallets
+            .active
+            .as_ref()
+            .and_then(|name| self.wallets.stores.get_mut(name))
+        {
+            Some(store) => store,
+            None => &mut self.store,
+        }
     }
+
+    /// Checks that the values of the options are within their valid ranges, which
+    /// deserialization alone does not ensure.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first invalid option found, along with the expected format of its value.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.store.database_filepath.is_empty() {
+            return Err(ConfigError::invalid(
+                "store.database_filepath",
+                &self.store.database_filepath,
+                "a path to the store file",
+            ));
+        }
+
+        self.rpc.endpoint.validate()?;
+        if self.rpc.nullifier_prefix_bits > 16 {
+            return Err(ConfigError::invalid(
+                "rpc.nullifier_prefix_bits",
+                self.rpc.nullifier_prefix_bits,
+                "a number of bits between 0 and 16",
+            ));
+        }
+        if self.rpc.nullifier_chunk_size == 0 {
+            return Err(ConfigError::invalid(
+                "rpc.nullifier_chunk_size",
+                self.rpc.nullifier_chunk_size,
+                "at least 1 nullifier prefix",
+            ));
+        }
+        if let Some(proxy) = &self.rpc.proxy {
+            if proxy != DIRECT_CONNECTION && Proxy::try_from(proxy.as_str()).is_err() {
+                return Err(ConfigError::invalid(
+                    "rpc.proxy",
+                    proxy,
+                    "an http://, socks5:// or socks5h:// URL, or `none`",
+                ));
+            }
+        }
+
+        if let Some(webhook_url) = &self.notifications.webhook_url {
+            let is_http_url = matches!(
+                reqwest::Url::parse(webhook_url),
+                Ok(url) if url.scheme() == "http" || url.scheme() == "https"
+            );
+            if !is_http_url {
+                return Err(ConfigError::invalid(
+                    "notifications.webhook_url",
+                    webhook_url,
+                    "an http:// or https:// URL",
+                ));
+            }
+        }
+
+        if self.retry.max_attempts == 0 {
+            return Err(ConfigError::invalid(
+                "retry.max_attempts",
+                self.retry.max_attempts,
+                "at least 1 attempt",
+            ));
+        }
+        if self.retry.initial_backoff_ms > self.retry.max_backoff_ms {
+            return Err(ConfigError::invalid(
+                "retry.initial_backoff_ms",
+                self.retry.initial_backoff_ms,
+                "a delay no longer than `retry.max_backoff_ms`",
+            ));
+        }
+
+        let limits = self.transactions.limits;
+        for (key, limit, expected) in [
+            (
+                "transactions.limits.max_input_notes_per_transaction",
+                ProtocolLimit::InputNotesPerTransaction,
+                "a number of input notes between 1 and 1023",
+            ),
+            (
+                "transactions.limits.max_output_notes_per_transaction",
+                ProtocolLimit::OutputNotesPerTransaction,
+                "a number of output notes between 1 and 4096",
+            ),
+            (
+                "transactions.limits.max_assets_per_note",
+                ProtocolLimit::AssetsPerNote,
+                "a number of assets between 1 and 255",
+            ),
+        ] {
+            // the limits can only be lowered, the node rejects anything past the protocol's
+            if limits.max(limit) == 0 || limits.max(limit) > limit.max() {
+                return Err(ConfigError::invalid(key, limits.max(limit), expected));
+            }
+        }
+
+        self.faucet.validate(&self.wallets)?;
+        self.wallets.validate()
+    }
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self::new(StoreConfig::default(), RpcConfig::default())
+    }
+}
+
+// DATA DIRECTORY
+// ================================================================================================
+
+/// Name of the directory the client keeps its files in, within the platform's data directory
+const DATA_DIRECTORY_NAME: &str = "miden-client";
+
+/// Returns the directory the CLI keeps its files in unless the config file sets one: `miden-client`
+/// within `$XDG_DATA_HOME` (or `~/.local/share`) on Linux, `~/Library/Application Support` on
+/// macOS and `%APPDATA%` on Windows. Falls back to the current directory when the platform's data
+/// directory cannot be determined.
+///
+/// Library users keep their stores relative to the current directory unless they set
+/// [ClientConfig::data_directory] to this directory themselves.
+pub fn default_data_directory() -> PathBuf {
+    platform_data_directory()
+        .map(|data_directory| data_directory.join(DATA_DIRECTORY_NAME))
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "windows")]
+fn platform_data_directory() -> Option<PathBuf> {
+    env::var_os("APPDATA").map(PathBuf::from)
+}
+
+#[cfg(target_os = "macos")]
+fn platform_data_directory() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn platform_data_directory() -> Option<PathBuf> {
+    // the XDG specification asks for relative paths to be ignored
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|data_home| data_home.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
 }
 
 // ENDPOINT
 // ================================================================================================
 
-#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct Endpoint {
     protocol: String,
     host: String,
@@ -41,6 +270,39 @@ impl Endpoint {
             port,
         }
     }
+
+    /// Checks that the endpoint is an `http` or `https` URL the client can connect to
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.protocol != "http" && self.protocol != "https" {
+            return Err(ConfigError::invalid(
+                "rpc.endpoint.protocol",
+                &self.protocol,
+                "`http` or `https`",
+            ));
+        }
+        if self.port == 0 {
+            return Err(ConfigError::invalid(
+                "rpc.endpoint.port",
+                self.port,
+                "a port between 1 and 65535",
+            ));
+        }
+
+        let is_host = !self.host.is_empty()
+            && matches!(
+                self.to_string().parse::<tonic::transport::Uri>(),
+                Ok(uri) if uri.host() == Some(self.host.as_str())
+            );
+        if !is_host {
+            return Err(ConfigError::invalid(
+                "rpc.endpoint.host",
+                &self.host,
+                "a host name or IP address, without protocol or port",
+            ));
+        }
+
+        Ok(())
+    }
 }
 
 impl fmt::Display for Endpoint {
@@ -49,6 +311,25 @@ impl fmt::Display for Endpoint {
     }
 }
 
+impl TryFrom<&str> for Endpoint {
+    type Error = String;
+
+    /// Parses an endpoint from a URL such as `http://localhost:57291`. The port defaults to the
+    /// protocol's well-known port when left out.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let url =
+            reqwest::Url::parse(value).map_err(|err| format!("invalid URL `{value}`: {err}"))?;
+        let host = url
+            .host_str()
+            .ok_or_else(|| format!("URL `{value}` has no host"))?;
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| format!("URL `{value}` has no port"))?;
+
+        Ok(Self::new(url.scheme().to_string(), host.to_string(), port))
+    }
+}
+
 impl Default for Endpoint {
     fn default() -> Self {
         const MIDEN_NODE_PORT: u16 = 57291;
@@ -64,15 +345,24 @@ impl Default for Endpoint {
 // STORE CONFIG
 // ================================================================================================
 
-#[derive(Debug, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct StoreConfig {
     pub database_filepath: String,
+    /// Passphrase the database is encrypted with. Only supported when the client is built with
+    /// the `sqlcipher` feature
+    #[serde(default)]
+    pub passphrase: Option<String>,
 }
 
 impl From<&ClientConfig> for StoreConfig {
+    /// Returns the settings of the active store, see [ClientConfig::active_store], with its path
+    /// resolved against the data directory, see [ClientConfig::database_path]
     fn from(config: &ClientConfig) -> Self {
+        let store = config.active_store();
         Self {
-            database_filepath: config.store.database_filepath.clone(),
+            database_filepath: config.database_path(store).to_string_lossy().into_owned(),
+            passphrase: store.passphrase.clone(),
         }
     }
 }
@@ -90,39 +380,560 @@ impl TryFrom<String> for StoreConfig {
     fn try_from(value: String) -> Result<Self, Self::Error> {
         Ok(Self {
             database_filepath: value,
+            passphrase: None,
         })
     }
 }
 
 impl Default for StoreConfig {
+    /// Returns the settings of a store kept in `store.sqlite3`, within the data directory
     fn default() -> Self {
         const STORE_FILENAME: &str = "store.sqlite3";
 
-        // Get current directory
-        let exec_dir = PathBuf::new();
-
-        // Append filepath
-        let database_filepath = exec_dir
-            .join(STORE_FILENAME)
-            .into_os_string()
-            .into_string()
-            .expect("Creating the hardcoded store path should not panic");
-
-        Self { database_filepath }
+        Self {
+            database_filepath: STORE_FILENAME.to_string(),
+            passphrase: None,
+        }
     }
 }
 
 // RPC CONFIG
 // ================================================================================================
 
-#[derive(Debug, Default, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct RpcConfig {
     /// Address of the Miden node to connect to.
     pub endpoint: Endpoint,
+    /// Number of most significant bits of the nullifiers of the client's notes revealed to the
+    /// node when syncing, up to 16. Fewer bits make the requests match more nullifiers, which
+    /// hides which notes the client owns at the cost of downloading and discarding more of them.
+    #[serde(default = "default_nullifier_prefix_bits")]
+    pub nullifier_prefix_bits: u8,
+    /// Maximum number of nullifier prefixes sent in a single sync request. Larger sets of
+    /// prefixes, as requested when few nullifier bits are revealed or many notes are unspent, are
+    /// checked with several requests so that they stay within the node's message size limits.
+    #[serde(default = "default_nullifier_chunk_size")]
+    pub nullifier_chunk_size: usize,
+    /// Whether the note paths received when syncing or importing notes start at the note ID
+    /// rather than at the note's authentication hash, as is the case with nodes that build their
+    /// note trees from note IDs. The first node of such paths, which are one node longer than the
+    /// note tree is deep, is dropped before verifying or storing them.
+    #[serde(default = "default_trim_note_paths")]
+    pub trim_note_paths: bool,
+    /// URL of the proxy the node is reached through, such as `http://proxy.example.com:3128` for
+    /// an HTTP proxy or `socks5h://localhost:1080` for a SOCKS5 proxy. When not set, the proxy is
+    /// read from the standard environment variables, see
+    /// [resolve_proxy](crate::client::proxy::resolve_proxy). Set it to `none` to connect directly
+    /// regardless of them.
+    #[serde(default)]
+    pub proxy: Option<String>,
+}
+
+impl Default for RpcConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: Endpoint::default(),
+            nullifier_prefix_bits: default_nullifier_prefix_bits(),
+            nullifier_chunk_size: default_nullifier_chunk_size(),
+            trim_note_paths: default_trim_note_paths(),
+            proxy: None,
+        }
+    }
 }
 
 impl From<Endpoint> for RpcConfig {
     fn from(value: Endpoint) -> Self {
-        Self { endpoint: value }
+        Self {
+            endpoint: value,
+            ..Default::default()
+        }
+    }
+}
+
+const fn default_nullifier_prefix_bits() -> u8 {
+    16
+}
+
+const fn default_nullifier_chunk_size() -> usize {
+    1024
+}
+
+const fn default_trim_note_paths() -> bool {
+    true
+}
+
+// NOTIFICATIONS CONFIG
+// ================================================================================================
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotificationsConfig {
+    /// URL the sync events are POSTed to as JSON. Notifications are disabled when not set
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Number of times a failed notification is retried before it is dropped
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Delay before the first retry of a failed notification, doubled after every retry
+    #[serde(default = "default_retry_delay_ms")]
+    pub retry_delay_ms: u64,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            max_retries: default_max_retries(),
+            retry_delay_ms: default_retry_delay_ms(),
+        }
+    }
+}
+
+const fn default_max_retries() -> u32 {
+    5
+}
+
+const fn default_retry_delay_ms() -> u64 {
+    500
+}
+
+// WALLETS CONFIG
+// ================================================================================================
+
+/// Named wallets, each with a store of its own, so that separate sets of accounts and notes (e.g.
+/// personal and testing ones) can be used with the same configuration. The node and the other
+/// settings are shared by every wallet.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct WalletsConfig {
+    /// Name of the wallet in use. The store in the `store` section is used when not set
+    #[serde(default)]
+    pub active: Option<String>,
+    /// Store of each wallet, by wallet name
+    #[serde(default)]
+    pub stores: BTreeMap<String, StoreConfig>,
+}
+
+impl WalletsConfig {
+    /// Name referring to the store in the `store` section, which cannot be given to a wallet
+    pub const DEFAULT_WALLET: &'static str = "default";
+
+    /// Returns whether `name` can be given to a wallet. Names are made of ASCII letters, digits,
+    /// `-` and `_`, so that they can be used as keys of the config file without quoting.
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty()
+            && name != Self::DEFAULT_WALLET
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        for (name, store) in self.stores.iter() {
+            if !Self::is_valid_name(name) {
+                return Err(ConfigError::invalid(
+                    "wallets.stores",
+                    name,
+                    "wallet names made of letters, digits, `-` and `_`, other than `default`",
+                ));
+            }
+            if store.database_filepath.is_empty() {
+                return Err(ConfigError::invalid(
+                    "wallets.stores",
+                    name,
+                    "a path to the store file of every wallet",
+                ));
+            }
+        }
+
+        match &self.active {
+            Some(active) if !self.stores.contains_key(active) => Err(ConfigError::invalid(
+                "wallets.active",
+                active,
+                "the name of a wallet in `wallets.stores`",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+// FAUCET CONFIG
+// ================================================================================================
+
+/// Faucet services, such as the ones of public testnets, that mint test tokens to accounts on
+/// request. Wallets used on different networks can each have a faucet of their own.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct FaucetConfig {
+    /// URL of the faucet service tokens are requested from when the active wallet has none
+    #[serde(default)]
+    pub url: Option<String>,
+    /// URL of the faucet service of each wallet, by wallet name
+    #[serde(default)]
+    pub wallets: BTreeMap<String, String>,
+}
+
+impl FaucetConfig {
+    fn validate(&self, wallets: &WalletsConfig) -> Result<(), ConfigError> {
+        let is_http_url = |url: &str| {
+            matches!(
+                reqwest::Url::parse(url),
+                Ok(url) if url.scheme() == "http" || url.scheme() == "https"
+            )
+        };
+
+        if let Some(url) = &self.url {
+            if !is_http_url(url) {
+                return Err(ConfigError::invalid(
+                    "faucet.url",
+                    url,
+                    "an http:// or https:// URL",
+                ));
+            }
+        }
+        for (name, url) in self.wallets.iter() {
+            if !wallets.stores.contains_key(name) {
+                return Err(ConfigError::invalid(
+                    "faucet.wallets",
+                    name,
+                    "the name of a wallet in `wallets.stores`",
+                ));
+            }
+            if !is_http_url(url) {
+                return Err(ConfigError::invalid(
+                    "faucet.wallets",
+                    url,
+                    "an http:// or https:// URL for every wallet",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// RETRY POLICY
+// ================================================================================================
+
+/// Policy for retrying operations that fail with a retryable error (see
+/// [ClientError::is_retryable]), such as syncing while the node is unreachable, submitting a
+/// transaction or writing to a store locked by another process.
+///
+/// The delay before each retry grows exponentially from `initial_backoff_ms` up to
+/// `max_backoff_ms`, plus a random jitter of up to `jitter_ms` so that several clients do not
+/// retry in lockstep.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryPolicy {
+    /// Number of times an operation is attempted, including the first attempt
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    pub jitter_ms: u64,
+}
+
+impl RetryPolicy {
+    /// A policy under which operations are attempted once, for callers that retry on their own
+    pub const NO_RETRIES: Self = Self {
+        max_attempts: 1,
+        initial_backoff_ms: 0,
+        max_backoff_ms: 0,
+        jitter_ms: 0,
+    };
+
+    /// Returns the delay to wait before retrying an operation that failed with `err` after
+    /// `attempt` attempts, or `None` if the error is not retryable or no attempts are left.
+    pub fn retry_delay(&self, err: &ClientError, attempt: u32) -> Option<Duration> {
+        if !err.is_retryable() || attempt >= self.max_attempts {
+            return None;
+        }
+
+        let backoff = self
+            .initial_backoff_ms
+            .saturating_mul(1 << attempt.saturating_sub(1).min(32))
+            .min(self.max_backoff_ms);
+        let jitter = rand::thread_rng().gen_range(0..=self.jitter_ms);
+
+        Some(Duration::from_millis(backoff + jitter))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 10_000,
+            jitter_ms: 250,
+        }
+    }
+}
+
+// TRANSACTIONS CONFIG
+// ================================================================================================
+
+/// Settings of the transactions executed by the client.
+///
+/// A transaction is executed against the block the client is synced to. The longer it waits
+/// before being submitted, the more likely it is to be rejected by the node, for instance because
+/// the notes it consumes were consumed in the meantime.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransactionsConfig {
+    /// Number of blocks the client's sync height can move past the block a transaction was
+    /// executed against before the transaction is considered stale and has to be re-executed
+    pub max_staleness: u32,
+    /// How transactions are proven before being submitted. Only networks that do not verify
+    /// transaction proofs accept transactions proven in `trusted` mode
+    pub prover: TransactionProverMode,
+    /// Number of seconds after which a warning is logged if proving a transaction has not
+    /// finished yet. Zero disables the warning
+    pub slow_proving_warning_secs: u64,
+    /// Whether transactions are proven and queued instead of being submitted, for when the node
+    /// cannot be reached. Queued transactions are submitted by the next sync outside of offline
+    /// mode
+    pub offline: bool,
+    /// Limits transactions are validated against before being executed, and split by when they
+    /// consume more notes than allowed
+    pub limits: ProtocolLimits,
+}
+
+impl Default for TransactionsConfig {
+    fn default() -> Self {
+        Self {
+            max_staleness: 256,
+            prover: TransactionProverMode::default(),
+            slow_proving_warning_secs: 120,
+            offline: false,
+            limits: ProtocolLimits::default(),
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use figment::{
+        providers::{Format, Toml},
+        Figment,
+    };
+
+    use super::{ClientConfig, Endpoint, RetryPolicy, StoreConfig};
+    use crate::{client::prover::TransactionProverMode, errors::ConfigError};
+
+    const CONFIG: &str = r#"
+        [rpc]
+        endpoint = { protocol = "http", host = "localhost", port = 57291 }
+
+        [store]
+        database_filepath = "store.sqlite3"
+    "#;
+
+    fn invalid_key(config: &ClientConfig) -> Option<&'static str> {
+        match config.validate() {
+            Ok(()) => None,
+            Err(ConfigError::InvalidValue { key, .. }) => Some(key),
+        }
+    }
+
+    #[test]
+    fn unknown_options_are_rejected() {
+        let config: ClientConfig = Figment::from(Toml::string(CONFIG)).extract().unwrap();
+        assert_eq!(invalid_key(&config), None);
+
+        let err = Figment::from(Toml::string(&format!("{CONFIG}\n[retry]\nmax_attempt = 2")))
+            .extract::<ClientConfig>()
+            .unwrap_err();
+        assert!(err.to_string().contains("max_attempt"));
+    }
+
+    #[test]
+    fn out_of_range_values_are_rejected() {
+        let endpoint = |protocol: &str, host: &str, port| {
+            ClientConfig::new(
+                Default::default(),
+                Endpoint::new(protocol.to_string(), host.to_string(), port).into(),
+            )
+        };
+        assert_eq!(invalid_key(&endpoint("https", "10.0.0.1", 443)), None);
+        assert_eq!(
+            invalid_key(&endpoint("grpc", "localhost", 443)),
+            Some("rpc.endpoint.protocol")
+        );
+        assert_eq!(
+            invalid_key(&endpoint("http", "localhost", 0)),
+            Some("rpc.endpoint.port")
+        );
+        assert_eq!(
+            invalid_key(&endpoint("http", "http://localhost", 443)),
+            Some("rpc.endpoint.host")
+        );
+        assert_eq!(
+            invalid_key(&endpoint("http", "", 443)),
+            Some("rpc.endpoint.host")
+        );
+
+        let mut config = ClientConfig::default();
+        config.rpc.nullifier_prefix_bits = 17;
+        assert_eq!(invalid_key(&config), Some("rpc.nullifier_prefix_bits"));
+
+        let mut config = ClientConfig::default();
+        config.rpc.nullifier_chunk_size = 0;
+        assert_eq!(invalid_key(&config), Some("rpc.nullifier_chunk_size"));
+
+        let mut config = ClientConfig::default();
+        config.notifications.webhook_url = Some("localhost:8080/events".to_string());
+        assert_eq!(invalid_key(&config), Some("notifications.webhook_url"));
+        config.notifications.webhook_url = Some("https://example.com/events".to_string());
+        assert_eq!(invalid_key(&config), None);
+
+        let mut config = ClientConfig::default();
+        config.retry = RetryPolicy {
+            max_attempts: 0,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(invalid_key(&config), Some("retry.max_attempts"));
+
+        let mut config = ClientConfig::default();
+        config.transactions.limits.max_input_notes_per_transaction = 2000;
+        assert_eq!(
+            invalid_key(&config),
+            Some("transactions.limits.max_input_notes_per_transaction")
+        );
+        config.transactions.limits.max_input_notes_per_transaction = 100;
+        assert_eq!(invalid_key(&config), None);
+        config.transactions.limits.max_assets_per_note = 0;
+        assert_eq!(
+            invalid_key(&config),
+            Some("transactions.limits.max_assets_per_note")
+        );
+    }
+
+    #[test]
+    fn prover_mode_is_read_from_the_transactions_section() {
+        let config: ClientConfig = Figment::from(Toml::string(CONFIG)).extract().unwrap();
+        assert_eq!(config.transactions.prover, TransactionProverMode::Local);
+
+        let config: ClientConfig = Figment::from(Toml::string(&format!(
+            "{CONFIG}\n[transactions]\nprover = \"trusted\""
+        )))
+        .extract()
+        .unwrap();
+        assert_eq!(config.transactions.prover, TransactionProverMode::Trusted);
+        assert_eq!(config.transactions.max_staleness, 256);
+        assert_eq!(config.transactions.slow_proving_warning_secs, 120);
+
+        let config: ClientConfig = Figment::from(Toml::string(&format!(
+            "{CONFIG}\n[transactions]\nslow_proving_warning_secs = 0"
+        )))
+        .extract()
+        .unwrap();
+        assert_eq!(config.transactions.slow_proving_warning_secs, 0);
+
+        let config: ClientConfig = Figment::from(Toml::string(&format!(
+            "{CONFIG}\n[transactions.limits]\nmax_input_notes_per_transaction = 100"
+        )))
+        .extract()
+        .unwrap();
+        assert_eq!(
+            config.transactions.limits.max_input_notes_per_transaction,
+            100
+        );
+        assert_eq!(config.transactions.limits.max_assets_per_note, 255);
+    }
+
+    #[test]
+    fn active_wallet_selects_the_store() {
+        let config: ClientConfig = Figment::from(Toml::string(&format!(
+            "{CONFIG}\n[wallets]\nactive = \"testing\"\n\
+            [wallets.stores.testing]\ndatabase_filepath = \"testing.sqlite3\""
+        )))
+        .extract()
+        .unwrap();
+        assert_eq!(invalid_key(&config), None);
+        assert_eq!(
+            PathBuf::from(StoreConfig::from(&config).database_filepath),
+            config.data_directory.join("testing.sqlite3")
+        );
+
+        let mut config = config;
+        config.wallets.active = None;
+        assert_eq!(
+            PathBuf::from(StoreConfig::from(&config).database_filepath),
+            config.data_directory.join("store.sqlite3")
+        );
+
+        config.wallets.active = Some("personal".to_string());
+        assert_eq!(invalid_key(&config), Some("wallets.active"));
+
+        config.wallets.active = None;
+        config
+            .wallets
+            .stores
+            .insert("default".to_string(), "other.sqlite3".try_into().unwrap());
+        assert_eq!(invalid_key(&config), Some("wallets.stores"));
+    }
+
+    #[test]
+    fn active_wallet_selects_the_faucet() {
+        let config: ClientConfig = Figment::from(Toml::string(&format!(
+            "{CONFIG}\n[wallets]\nactive = \"testing\"\n\
+            [wallets.stores.testing]\ndatabase_filepath = \"testing.sqlite3\"\n\
+            [faucet]\nurl = \"https://faucet.example.com\"\n\
+            [faucet.wallets]\ntesting = \"http://localhost:8080\""
+        )))
+        .extract()
+        .unwrap();
+        assert_eq!(invalid_key(&config), None);
+        assert_eq!(config.faucet_url(), Some("http://localhost:8080"));
+
+        let mut config = config;
+        config.wallets.active = None;
+        assert_eq!(config.faucet_url(), Some("https://faucet.example.com"));
+
+        config.faucet.wallets.insert(
+            "personal".to_string(),
+            "https://faucet.example.com".to_string(),
+        );
+        assert_eq!(invalid_key(&config), Some("faucet.wallets"));
+
+        let mut config = ClientConfig::default();
+        assert_eq!(config.faucet_url(), None);
+        config.faucet.url = Some("faucet.example.com".to_string());
+        assert_eq!(invalid_key(&config), Some("faucet.url"));
+    }
+
+    #[test]
+    fn store_paths_are_relative_to_the_data_directory() {
+        // library users keep their stores relative to the current directory by default
+        let config: ClientConfig = Figment::from(Toml::string(CONFIG)).extract().unwrap();
+        assert_eq!(config.data_directory, PathBuf::new());
+        assert_eq!(
+            ClientConfig::default().database_path(&config.store),
+            PathBuf::from(&config.store.database_filepath)
+        );
+
+        let config: ClientConfig = Figment::from(Toml::string(&format!(
+            "data_directory = \"/var/lib/miden\"\n{CONFIG}"
+        )))
+        .extract()
+        .unwrap();
+        assert_eq!(
+            PathBuf::from(StoreConfig::from(&config).database_filepath),
+            PathBuf::from("/var/lib/miden/store.sqlite3")
+        );
+
+        // absolute store paths are kept as is
+        let mut config = config;
+        config.store = "/tmp/store.sqlite3".try_into().unwrap();
+        assert_eq!(
+            StoreConfig::from(&config).database_filepath,
+            "/tmp/store.sqlite3"
+        );
     }
 }
@@ -1,4 +1,3 @@
-use core::fmt;
 use crypto::{
     dsa::rpo_falcon512::FalconError,
     merkle::MmrError,
@@ -7,149 +6,453 @@ use crypto::{
 use miden_node_proto::errors::ParseError;
 use miden_tx::{DataStoreError, TransactionExecutorError, TransactionProverError};
 use objects::{
-    accounts::AccountId, notes::NoteId, AccountError, AssetVaultError, Digest, NoteError,
-    TransactionScriptError,
+    accounts::AccountId, notes::NoteId, AccountError, AssetError, AssetVaultError, Digest,
+    NoteError, TransactionScriptError,
 };
-use tonic::{transport::Error as TransportError, Status as TonicStatus};
+use thiserror::Error;
+use tonic::{transport::Error as TransportError, Code as TonicCode, Status as TonicStatus};
+
+use crate::client::{transactions::ProtocolLimit, RpcApiEndpoint};
+
+// ERROR CATEGORY
+// ================================================================================================
+
+/// Broad classification of an error, telling callers how to react to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCategory {
+    /// The operation failed because of a transient condition, such as the node being unreachable
+    /// or the store being locked by another process, and may succeed if retried.
+    Retryable,
+    /// The operation was given input that is invalid for the client's current state, such as an
+    /// unknown account or a transaction over the protocol limits. Retrying will fail again.
+    InvalidInput,
+    /// The client ran into an error it cannot recover from, such as corrupted store data or a
+    /// node on a different chain.
+    Fatal,
+}
 
 // CLIENT ERROR
 // ================================================================================================
 
-#[derive(Debug)]
+#[derive(Debug, Error)]
 pub enum ClientError {
-    AccountError(AccountError),
-    AuthError(FalconError),
+    #[error("account error: {0}")]
+    AccountError(#[from] AccountError),
+    #[error("account {0} is not a fungible faucet")]
+    AccountIsNotFaucet(AccountId),
+    #[error("the on-chain state of account {0} differs from the local one, the account was probably updated from another store and has to be imported again")]
+    AccountStateDesynchronized(AccountId),
+    #[error("address error: {0}")]
+    AddressError(#[from] AddressError),
+    #[error("asset error: {0}")]
+    AssetError(#[from] AssetError),
+    #[error("account {0} does not hold {1}")]
+    AssetNotInVault(AccountId, String),
+    #[error("account auth error: {0}")]
+    AuthError(#[from] FalconError),
+    #[error("faucet request failed: {0}")]
+    FaucetRequestFailed(String),
+    #[error("faucet request rejected: {0}")]
+    FaucetRequestRejected(String),
+    #[error("import account error: can't import a new account without its initial seed")]
     ImportNewAccountWithoutSeed,
-    NoteError(NoteError),
+    #[error("account {0} cannot cover the payment: {1}")]
+    InsufficientBalance(AccountId, String),
+    #[error("invalid account code upgrade: {0}")]
+    InvalidAccountCodeUpgrade(String),
+    #[error("invalid proof of the state of account {0}: {1}")]
+    InvalidAccountProof(AccountId, String),
+    #[error("invalid proof of an asset of account {0}: {1}")]
+    InvalidAssetProof(AccountId, String),
+    #[error("invalid account template: {0}")]
+    InvalidAccountTemplate(String),
+    #[error("invalid header of block {0} received from the node: {1}")]
+    InvalidBlockHeaderReceived(u32, String),
+    #[error("invalid note: {0}")]
+    InvalidNote(String),
+    #[error("the inclusion proof of note {0} does not match the note root of block {1}")]
+    InvalidNoteInclusionProof(NoteId, u32),
+    #[error("the store belongs to the network with genesis block {0}, but the node's genesis block is {1}; the node was reset or belongs to another network")]
+    NetworkMismatch(Digest, Digest),
+    #[error("note error: {0}")]
+    NoteError(#[from] NoteError),
+    #[error("note {} cannot be recalled: {1}", .0.inner())]
+    NoteNotRecallable(NoteId, String),
+    #[error("No consumable note for account ID {0}")]
     NoConsumableNoteForAccount(AccountId),
-    RpcApiError(RpcApiError),
-    StoreError(StoreError),
-    TransactionExecutionError(TransactionExecutorError),
-    TransactionProvingError(TransactionProverError),
+    #[error("transaction exceeds the limit of {1} {0}: got {2}")]
+    ProtocolLimitExceeded(ProtocolLimit, usize, usize),
+    #[error("rpc api error: {0}")]
+    RpcApiError(#[from] RpcApiError),
+    #[error("invalid transaction script inputs: {0}")]
+    ScriptInputsError(#[from] ScriptInputsError),
+    #[error(
+        "transaction {0} was executed against block {1}, too far behind the sync height {2} to be submitted, it has to be re-executed"
+    )]
+    StaleTransaction(Digest, u32, u32),
+    #[error("store error: {0}")]
+    StoreError(#[from] StoreError),
+    #[error("transaction executor error: {0}")]
+    TransactionExecutionError(#[from] TransactionExecutorError),
+    #[error("transaction prover error: {0}")]
+    TransactionProvingError(#[from] TransactionProverError),
+    #[error(
+        "note {0} is not committed yet, it can be consumed once a sync provides its inclusion proof"
+    )]
+    UnauthenticatedNote(NoteId),
+    #[error("not supported yet: {0}")]
+    UnsupportedFeature(String),
+    #[error("webhook notification error: {0}")]
+    WebhookError(String),
 }
 
-impl fmt::Display for ClientError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl ClientError {
+    /// Returns a stable identifier of the error, suitable for matching on in scripts and logs.
+    /// Errors coming from the store or the node keep the code of the underlying error.
+    pub fn code(&self) -> &'static str {
         match self {
-            ClientError::AccountError(err) => write!(f, "account error: {err}"),
-            ClientError::AuthError(err) => write!(f, "account auth error: {err}"),
-            ClientError::ImportNewAccountWithoutSeed => write!(
-                f,
-                "import account error: can't import a new account without its initial seed"
-            ),
-            ClientError::NoConsumableNoteForAccount(account_id) => {
-                write!(f, "No consumable note for account ID {}", account_id)
-            }
-            ClientError::NoteError(err) => write!(f, "note error: {err}"),
-            ClientError::RpcApiError(err) => write!(f, "rpc api error: {err}"),
-            ClientError::StoreError(err) => write!(f, "store error: {err}"),
-            ClientError::TransactionExecutionError(err) => {
-                write!(f, "transaction executor error: {err}")
-            }
-            ClientError::TransactionProvingError(err) => {
-                write!(f, "transaction prover error: {err}")
+            ClientError::AccountError(_) => "client.account_error",
+            ClientError::AccountIsNotFaucet(_) => "client.account_is_not_faucet",
+            ClientError::AccountStateDesynchronized(_) => "client.account_state_desynchronized",
+            ClientError::AddressError(_) => "client.address_error",
+            ClientError::AssetError(_) => "client.asset_error",
+            ClientError::AssetNotInVault(..) => "client.asset_not_in_vault",
+            ClientError::AuthError(_) => "client.auth_error",
+            ClientError::FaucetRequestFailed(_) => "client.faucet_request_failed",
+            ClientError::FaucetRequestRejected(_) => "client.faucet_request_rejected",
+            ClientError::ImportNewAccountWithoutSeed => "client.import_new_account_without_seed",
+            ClientError::InsufficientBalance(..) => "client.insufficient_balance",
+            ClientError::InvalidAccountCodeUpgrade(_) => "client.invalid_account_code_upgrade",
+            ClientError::InvalidAccountProof(..) => "client.invalid_account_proof",
+            ClientError::InvalidAssetProof(..) => "client.invalid_asset_proof",
+            ClientError::InvalidAccountTemplate(_) => "client.invalid_account_template",
+            ClientError::InvalidBlockHeaderReceived(..) => "client.invalid_block_header_received",
+            ClientError::InvalidNote(_) => "client.invalid_note",
+            ClientError::InvalidNoteInclusionProof(..) => "client.invalid_note_inclusion_proof",
+            ClientError::NetworkMismatch(..) => "client.network_mismatch",
+            ClientError::NoteError(_) => "client.note_error",
+            ClientError::NoteNotRecallable(..) => "client.note_not_recallable",
+            ClientError::NoConsumableNoteForAccount(_) => "client.no_consumable_note_for_account",
+            ClientError::ProtocolLimitExceeded(..) => "client.protocol_limit_exceeded",
+            ClientError::RpcApiError(err) => err.code(),
+            ClientError::ScriptInputsError(_) => "client.script_inputs_error",
+            ClientError::StaleTransaction(..) => "client.stale_transaction",
+            ClientError::StoreError(err) => err.code(),
+            ClientError::TransactionExecutionError(_) => "client.transaction_execution_error",
+            ClientError::TransactionProvingError(_) => "client.transaction_proving_error",
+            ClientError::UnauthenticatedNote(_) => "client.unauthenticated_note",
+            ClientError::UnsupportedFeature(_) => "client.unsupported_feature",
+            ClientError::WebhookError(_) => "client.webhook_error",
+        }
+    }
+
+    /// Returns the [ErrorCategory] of the error
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            ClientError::AccountError(_)
+            | ClientError::AccountIsNotFaucet(_)
+            | ClientError::AccountStateDesynchronized(_)
+            | ClientError::AddressError(_)
+            | ClientError::AssetError(_)
+            | ClientError::AssetNotInVault(..)
+            | ClientError::FaucetRequestRejected(_)
+            | ClientError::ImportNewAccountWithoutSeed
+            | ClientError::InsufficientBalance(..)
+            | ClientError::InvalidAccountCodeUpgrade(_)
+            | ClientError::InvalidAccountTemplate(_)
+            | ClientError::InvalidNote(_)
+            | ClientError::NoteError(_)
+            | ClientError::NoteNotRecallable(..)
+            | ClientError::NoConsumableNoteForAccount(_)
+            | ClientError::ProtocolLimitExceeded(..)
+            | ClientError::ScriptInputsError(_)
+            | ClientError::StaleTransaction(..)
+            | ClientError::UnauthenticatedNote(_)
+            | ClientError::UnsupportedFeature(_) => ErrorCategory::InvalidInput,
+            ClientError::RpcApiError(err) => err.category(),
+            ClientError::StoreError(err) => err.category(),
+            ClientError::FaucetRequestFailed(_) | ClientError::WebhookError(_) => {
+                ErrorCategory::Retryable
             }
+            ClientError::AuthError(_)
+            | ClientError::NetworkMismatch(..)
+            | ClientError::InvalidAccountProof(..)
+            | ClientError::InvalidAssetProof(..)
+            | ClientError::InvalidBlockHeaderReceived(..)
+            | ClientError::InvalidNoteInclusionProof(..)
+            | ClientError::TransactionExecutionError(_)
+            | ClientError::TransactionProvingError(_) => ErrorCategory::Fatal,
         }
     }
+
+    /// Returns true if retrying the failed operation may succeed
+    pub fn is_retryable(&self) -> bool {
+        self.category() == ErrorCategory::Retryable
+    }
 }
 
 // CONVERSIONS
 // ================================================================================================
 
-impl From<AccountError> for ClientError {
-    fn from(err: AccountError) -> Self {
-        Self::AccountError(err)
+impl From<rusqlite::Error> for ClientError {
+    fn from(err: rusqlite::Error) -> Self {
+        Self::StoreError(StoreError::from(err))
     }
 }
 
-impl From<FalconError> for ClientError {
-    fn from(err: FalconError) -> Self {
-        Self::AuthError(err)
+impl From<ClientError> for String {
+    fn from(err: ClientError) -> String {
+        err.to_string()
     }
 }
 
-impl From<NoteError> for ClientError {
-    fn from(err: NoteError) -> Self {
-        Self::NoteError(err)
-    }
-}
+// ASSET AMOUNT ERROR
+// ================================================================================================
 
-impl From<RpcApiError> for ClientError {
-    fn from(err: RpcApiError) -> Self {
-        Self::RpcApiError(err)
-    }
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AssetAmountError {
+    #[error("amount `{0}` is not a valid decimal number")]
+    InvalidFormat(String),
+    #[error("amount `{0}` is too large")]
+    Overflow(String),
+    #[error("amount `{0}` has more than the {1} decimals supported by the faucet")]
+    TooManyDecimals(String, u8),
 }
 
-impl From<StoreError> for ClientError {
-    fn from(err: StoreError) -> Self {
-        Self::StoreError(err)
+impl From<AssetAmountError> for String {
+    fn from(err: AssetAmountError) -> String {
+        err.to_string()
     }
 }
 
-impl From<TransactionExecutorError> for ClientError {
-    fn from(err: TransactionExecutorError) -> Self {
-        Self::TransactionExecutionError(err)
-    }
+// ADDRESS ERROR
+// ================================================================================================
+
+/// Error returned when parsing an [Address](crate::client::address::Address)
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AddressError {
+    #[error("`{0}` is not a valid address: {1}")]
+    InvalidEncoding(String, String),
+    #[error("address does not hold a valid account ID: {0}")]
+    InvalidAccountId(String),
+    #[error("address holds {0} bytes of data, expected an account ID and an optional note tag")]
+    InvalidLength(usize),
+    #[error("address has prefix `{0}`, expected `mdn`")]
+    UnknownPrefix(String),
 }
 
-impl From<TransactionProverError> for ClientError {
-    fn from(err: TransactionProverError) -> Self {
-        Self::TransactionProvingError(err)
-    }
+// SCRIPT INPUTS ERROR
+// ================================================================================================
+
+/// Error returned when validating [TransactionScriptInputs](crate::client::script_inputs::TransactionScriptInputs)
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ScriptInputsError {
+    #[error("input {0} is declared more than once")]
+    DuplicateKey(String),
+    #[error("input {0} holds {1}, which is not a valid field element")]
+    InvalidFieldElement(String, u64),
+    #[error("`{0}` is not a valid input key, expected a hex-encoded word: {1}")]
+    InvalidKey(String, String),
+    #[error("input {0} holds `{1}`, which is not a valid hex-encoded word: {2}")]
+    InvalidWord(String, String, String),
 }
 
-impl From<rusqlite::Error> for ClientError {
-    fn from(err: rusqlite::Error) -> Self {
-        Self::StoreError(StoreError::from(err))
-    }
+// CONFIG ERROR
+// ================================================================================================
+
+/// Error returned when a [ClientConfig](crate::config::ClientConfig) option has a value outside
+/// of its valid range
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ConfigError {
+    #[error("invalid value `{value}` for `{key}`, expected {expected}")]
+    InvalidValue {
+        key: &'static str,
+        value: String,
+        expected: &'static str,
+    },
 }
 
-impl From<ClientError> for String {
-    fn from(err: ClientError) -> String {
-        err.to_string()
+impl ConfigError {
+    pub(crate) fn invalid(key: &'static str, value: impl ToString, expected: &'static str) -> Self {
+        Self::InvalidValue {
+            key,
+            value: value.to_string(),
+            expected,
+        }
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for ClientError {}
-
 // STORE ERROR
 // ================================================================================================
 
-#[derive(Debug)]
+#[derive(Debug, Error)]
 pub enum StoreError {
-    AssetVaultError(AssetVaultError),
+    #[error("asset vault with root {0} not found")]
+    AssetVaultError(#[from] AssetVaultError),
+    #[error("account code data with root {0} not found")]
     AccountCodeDataNotFound(Digest),
+    #[error("Account data was not found for Account Id {0}")]
     AccountDataNotFound(AccountId),
-    AccountError(AccountError),
+    #[error("error instantiating Account: {0}")]
+    AccountError(#[from] AccountError),
+    #[error("note ID prefix {0} is ambiguous, it matches notes {}", .1.join(", "))]
+    AmbiguousNoteIdPrefix(String, Vec<String>),
+    #[error("account hash mismatch for account {0}")]
     AccountHashMismatch(AccountId),
+    #[error("no verified proof of the state of account {0} is stored")]
+    AccountProofNotFound(AccountId),
+    #[error("no tracked or named account ID starts with {0}")]
+    AccountIdPrefixNotFound(String),
+    #[error("address book entry `{0}` not found")]
+    AddressBookEntryNotFound(String),
+    #[error("account ID prefix {0} is ambiguous, it matches accounts {}", .1.join(", "))]
+    AmbiguousAccountIdPrefix(String, Vec<String>),
+    #[error("account storage data with root {0} not found")]
     AccountStorageNotFound(Digest),
+    #[error("block header for block {0} not found")]
     BlockHeaderNotFound(u32),
+    #[error("chain mmr node at index {0} not found")]
     ChainMmrNodeNotFound(u64),
+    #[error("the database is locked by another connection: {0}")]
+    DatabaseBusy(String),
+    #[error("database-related non-query error: {0}")]
     DatabaseError(String),
-    DataDeserializationError(DeserializationError),
-    HexParseError(HexParseError),
+    #[error("error deserializing data from the store: {0}")]
+    DataDeserializationError(#[from] DeserializationError),
+    #[error("store encryption requires the client to be built with the `sqlcipher` feature")]
+    EncryptionNotSupported,
+    #[error("error parsing hex: {0}")]
+    HexParseError(#[from] HexParseError),
+    #[error("invalid account label `{0}`, labels must contain a non-whitespace character")]
+    InvalidAccountLabel(String),
+    #[error("invalid address book name `{0}`, names must be non-empty and cannot start with `0x`")]
+    InvalidAddressBookName(String),
+    #[error("input note with note id {} not found", .0.inner())]
     InputNoteNotFound(NoteId),
+    #[error("error trying to serialize inputs for the store: {0}")]
     InputSerializationError(serde_json::Error),
+    #[error("invalid schema version {0}, the latest one is {1}")]
+    InvalidSchemaVersion(usize, usize),
+    #[error("error deserializing data from JSON from the store: {0}")]
     JsonDataDeserializationError(serde_json::Error),
-    MmrError(MmrError),
+    #[error("error constructing mmr: {0}")]
+    MmrError(#[from] MmrError),
+    #[error("error rebuilding note inclusion proof: {0}")]
+    NoteInclusionProofError(NoteError),
+    #[error("note script {0} is already registered as `{1}`")]
+    NoteScriptAlreadyRegistered(Digest, String),
+    #[error("a different note script is already registered as `{0}`")]
+    NoteScriptNameTaken(String),
+    #[error("no input note ID starts with {0}")]
+    NoteIdPrefixNotFound(String),
+    #[error("note ID prefix {0} is too short, it must have at least {1} hex digits")]
+    NoteIdPrefixTooShort(String, usize),
+    #[error("note {} is locked by transaction {1}, which is still in progress", .0.inner())]
+    NoteLocked(NoteId, Digest),
+    #[error("note script `{0}` not found")]
+    NoteScriptNotFound(String),
+    #[error("note tag {0} is already being tracked")]
     NoteTagAlreadyTracked(u64),
+    #[error("failed to parse data retrieved from the database: {0}")]
     ParsingError(String),
+    #[error("failed to retrieve data from the database: {0}")]
     QueryError(String),
-    RpcTypeConversionFailure(ParseError),
-    TransactionScriptError(TransactionScriptError),
+    #[error("failed to convert data: {0}")]
+    RpcTypeConversionFailure(#[from] ParseError),
+    #[error("the delta of account {0} sets its nonce to {1}, but the stored nonce is already {2}")]
+    StaleAccountDelta(AccountId, u64, u64),
+    #[error("transaction {0} cannot be discarded: {1}")]
+    TransactionNotDiscardable(Digest, String),
+    #[error("transaction {0} not found")]
+    TransactionNotFound(Digest),
+    #[error("no proof is stored for transaction {0}, which was either not proven yet or submitted before proofs were kept")]
+    TransactionProofNotFound(Digest),
+    #[error("error instantiating transaction script: {0}")]
+    TransactionScriptError(#[from] TransactionScriptError),
+    #[error("account vault data for root {0} not found")]
     VaultDataNotFound(Digest),
 }
 
-impl From<AssetVaultError> for StoreError {
-    fn from(value: AssetVaultError) -> Self {
-        StoreError::AssetVaultError(value)
+impl StoreError {
+    /// Returns a stable identifier of the error, suitable for matching on in scripts and logs
+    pub fn code(&self) -> &'static str {
+        use StoreError::*;
+        match self {
+            AssetVaultError(_) => "store.asset_vault_error",
+            AccountCodeDataNotFound(_) => "store.account_code_data_not_found",
+            AccountDataNotFound(_) => "store.account_data_not_found",
+            AccountError(_) => "store.account_error",
+            AmbiguousNoteIdPrefix(..) => "store.ambiguous_note_id_prefix",
+            AccountHashMismatch(_) => "store.account_hash_mismatch",
+            AccountProofNotFound(_) => "store.account_proof_not_found",
+            AccountIdPrefixNotFound(_) => "store.account_id_prefix_not_found",
+            AddressBookEntryNotFound(_) => "store.address_book_entry_not_found",
+            AmbiguousAccountIdPrefix(..) => "store.ambiguous_account_id_prefix",
+            AccountStorageNotFound(_) => "store.account_storage_not_found",
+            BlockHeaderNotFound(_) => "store.block_header_not_found",
+            ChainMmrNodeNotFound(_) => "store.chain_mmr_node_not_found",
+            DatabaseBusy(_) => "store.database_busy",
+            DatabaseError(_) => "store.database_error",
+            DataDeserializationError(_) => "store.data_deserialization_error",
+            EncryptionNotSupported => "store.encryption_not_supported",
+            HexParseError(_) => "store.hex_parse_error",
+            InvalidAccountLabel(_) => "store.invalid_account_label",
+            InvalidAddressBookName(_) => "store.invalid_address_book_name",
+            InputNoteNotFound(_) => "store.input_note_not_found",
+            InputSerializationError(_) => "store.input_serialization_error",
+            InvalidSchemaVersion(..) => "store.invalid_schema_version",
+            JsonDataDeserializationError(_) => "store.json_data_deserialization_error",
+            MmrError(_) => "store.mmr_error",
+            NoteInclusionProofError(_) => "store.note_inclusion_proof_error",
+            NoteIdPrefixNotFound(_) => "store.note_id_prefix_not_found",
+            NoteIdPrefixTooShort(..) => "store.note_id_prefix_too_short",
+            NoteLocked(..) => "store.note_locked",
+            NoteScriptAlreadyRegistered(..) => "store.note_script_already_registered",
+            NoteScriptNameTaken(_) => "store.note_script_name_taken",
+            NoteScriptNotFound(_) => "store.note_script_not_found",
+            NoteTagAlreadyTracked(_) => "store.note_tag_already_tracked",
+            ParsingError(_) => "store.parsing_error",
+            QueryError(_) => "store.query_error",
+            RpcTypeConversionFailure(_) => "store.rpc_type_conversion_failure",
+            StaleAccountDelta(..) => "store.stale_account_delta",
+            TransactionNotDiscardable(..) => "store.transaction_not_discardable",
+            TransactionNotFound(_) => "store.transaction_not_found",
+            TransactionProofNotFound(_) => "store.transaction_proof_not_found",
+            TransactionScriptError(_) => "store.transaction_script_error",
+            VaultDataNotFound(_) => "store.vault_data_not_found",
+        }
     }
-}
 
-impl From<AccountError> for StoreError {
-    fn from(value: AccountError) -> Self {
-        StoreError::AccountError(value)
+    /// Returns the [ErrorCategory] of the error. Records the user can ask for by identifier are
+    /// invalid input when missing, while other missing records point at an inconsistent store.
+    pub fn category(&self) -> ErrorCategory {
+        use StoreError::*;
+        match self {
+            DatabaseBusy(_) => ErrorCategory::Retryable,
+            AccountDataNotFound(_)
+            | AccountIdPrefixNotFound(_)
+            | AccountProofNotFound(_)
+            | AddressBookEntryNotFound(_)
+            | AmbiguousAccountIdPrefix(..)
+            | AmbiguousNoteIdPrefix(..)
+            | EncryptionNotSupported
+            | InputNoteNotFound(_)
+            | InvalidAccountLabel(_)
+            | InvalidAddressBookName(_)
+            | InvalidSchemaVersion(..)
+            | NoteIdPrefixNotFound(_)
+            | NoteIdPrefixTooShort(..)
+            | NoteLocked(..)
+            | NoteScriptAlreadyRegistered(..)
+            | NoteScriptNameTaken(_)
+            | NoteScriptNotFound(_)
+            | NoteTagAlreadyTracked(_)
+            | StaleAccountDelta(..)
+            | TransactionNotDiscardable(..)
+            | TransactionNotFound(_)
+            | TransactionProofNotFound(_) => ErrorCategory::InvalidInput,
+            _ => ErrorCategory::Fatal,
+        }
     }
 }
 
@@ -158,9 +461,17 @@ impl From<rusqlite_migration::Error> for StoreError {
         StoreError::DatabaseError(value.to_string())
     }
 }
+
 impl From<rusqlite::Error> for StoreError {
     fn from(value: rusqlite::Error) -> Self {
         match value {
+            rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error {
+                    code: rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked,
+                    ..
+                },
+                _,
+            ) => StoreError::DatabaseBusy(value.to_string()),
             rusqlite::Error::FromSqlConversionFailure(_, _, _)
             | rusqlite::Error::IntegralValueOutOfRange(_, _)
             | rusqlite::Error::InvalidColumnIndex(_)
@@ -180,96 +491,6 @@ impl From<rusqlite::Error> for StoreError {
     }
 }
 
-impl From<DeserializationError> for StoreError {
-    fn from(value: DeserializationError) -> Self {
-        StoreError::DataDeserializationError(value)
-    }
-}
-
-impl From<ParseError> for StoreError {
-    fn from(value: ParseError) -> Self {
-        StoreError::RpcTypeConversionFailure(value)
-    }
-}
-
-impl From<HexParseError> for StoreError {
-    fn from(value: HexParseError) -> Self {
-        StoreError::HexParseError(value)
-    }
-}
-
-impl From<MmrError> for StoreError {
-    fn from(value: MmrError) -> Self {
-        StoreError::MmrError(value)
-    }
-}
-
-impl From<TransactionScriptError> for StoreError {
-    fn from(value: TransactionScriptError) -> Self {
-        StoreError::TransactionScriptError(value)
-    }
-}
-
-impl fmt::Display for StoreError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        use StoreError::*;
-        match self {
-            AssetVaultError(err) => {
-                write!(f, "asset vault with root {} not found", err)
-            }
-            AccountCodeDataNotFound(root) => {
-                write!(f, "account code data with root {} not found", root)
-            }
-            AccountDataNotFound(account_id) => {
-                write!(f, "Account data was not found for Account Id {account_id}")
-            }
-            AccountError(err) => write!(f, "error instantiating Account: {err}"),
-            AccountHashMismatch(account_id) => {
-                write!(f, "account hash mismatch for account {account_id}")
-            }
-            AccountStorageNotFound(root) => {
-                write!(f, "account storage data with root {} not found", root)
-            }
-            BlockHeaderNotFound(block_number) => {
-                write!(f, "block header for block {} not found", block_number)
-            }
-            ChainMmrNodeNotFound(node_index) => {
-                write!(f, "chain mmr node at index {} not found", node_index)
-            }
-            DatabaseError(err) => write!(f, "database-related non-query error: {err}"),
-            DataDeserializationError(err) => {
-                write!(f, "error deserializing data from the store: {err}")
-            }
-            HexParseError(err) => {
-                write!(f, "error parsing hex: {err}")
-            }
-            InputNoteNotFound(note_id) => {
-                write!(f, "input note with note id {} not found", note_id.inner())
-            }
-            InputSerializationError(err) => {
-                write!(f, "error trying to serialize inputs for the store: {err}")
-            }
-            JsonDataDeserializationError(err) => {
-                write!(
-                    f,
-                    "error deserializing data from JSON from the store: {err}"
-                )
-            }
-            MmrError(err) => write!(f, "error constructing mmr: {err}"),
-            NoteTagAlreadyTracked(tag) => write!(f, "note tag {} is already being tracked", tag),
-            ParsingError(err) => {
-                write!(f, "failed to parse data retrieved from the database: {err}")
-            }
-            QueryError(err) => write!(f, "failed to retrieve data from the database: {err}"),
-            TransactionScriptError(err) => {
-                write!(f, "error instantiating transaction script: {err}")
-            }
-            VaultDataNotFound(root) => write!(f, "account vault data for root {} not found", root),
-            RpcTypeConversionFailure(err) => write!(f, "failed to convert data: {err}"),
-        }
-    }
-}
-
 impl From<StoreError> for DataStoreError {
     fn from(value: StoreError) -> Self {
         match value {
@@ -283,56 +504,60 @@ impl From<StoreError> for DataStoreError {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for StoreError {}
-
 // API CLIENT ERROR
 // ================================================================================================
 
-use crate::client::RpcApiEndpoint;
-
-#[derive(Debug)]
+#[derive(Debug, Error)]
 pub enum RpcApiError {
-    ConnectionError(TransportError),
-    ConversionFailure(ParseError),
+    #[error("failed to connect to the API server: {0}")]
+    ConnectionError(#[source] TransportError),
+    #[error("failed to convert RPC data: {0}")]
+    ConversionFailure(#[from] ParseError),
+    #[error("rpc API reponse missing an expected field: {0}")]
     ExpectedFieldMissing(String),
-    InvalidAccountReceived(AccountError),
-    RequestError(RpcApiEndpoint, TonicStatus),
+    #[error(
+        "node version {node} is not compatible with client version {client}, upgrade the client or connect it to a node running a compatible version"
+    )]
+    IncompatibleNodeVersion { node: String, client: String },
+    #[error("rpc API reponse contained an invalid account: {0}")]
+    InvalidAccountReceived(#[from] AccountError),
+    #[error("invalid proxy settings: {0}")]
+    InvalidProxy(String),
+    #[error("rpc request failed for {0}: {1}")]
+    RequestError(RpcApiEndpoint, #[source] TonicStatus),
 }
 
-impl fmt::Display for RpcApiError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl RpcApiError {
+    /// Returns a stable identifier of the error, suitable for matching on in scripts and logs
+    pub fn code(&self) -> &'static str {
         match self {
-            RpcApiError::ConnectionError(err) => {
-                write!(f, "failed to connect to the API server: {err}")
-            }
-            RpcApiError::ConversionFailure(err) => {
-                write!(f, "failed to convert RPC data: {err}")
-            }
-            RpcApiError::ExpectedFieldMissing(err) => {
-                write!(f, "rpc API reponse missing an expected field: {err}")
-            }
-            RpcApiError::InvalidAccountReceived(account_error) => {
-                write!(
-                    f,
-                    "rpc API reponse contained an invalid account: {account_error}"
-                )
-            }
-            RpcApiError::RequestError(endpoint, err) => {
-                write!(f, "rpc request failed for {endpoint}: {err}")
-            }
+            RpcApiError::ConnectionError(_) => "rpc.connection_error",
+            RpcApiError::ConversionFailure(_) => "rpc.conversion_failure",
+            RpcApiError::ExpectedFieldMissing(_) => "rpc.expected_field_missing",
+            RpcApiError::IncompatibleNodeVersion { .. } => "rpc.incompatible_node_version",
+            RpcApiError::InvalidAccountReceived(_) => "rpc.invalid_account_received",
+            RpcApiError::InvalidProxy(_) => "rpc.invalid_proxy",
+            RpcApiError::RequestError(..) => "rpc.request_error",
         }
     }
-}
-
-impl From<ParseError> for RpcApiError {
-    fn from(err: ParseError) -> Self {
-        Self::ConversionFailure(err)
-    }
-}
 
-impl From<AccountError> for RpcApiError {
-    fn from(err: AccountError) -> Self {
-        Self::InvalidAccountReceived(err)
+    /// Returns the [ErrorCategory] of the error. Failing to reach the node and requests the node
+    /// could not serve at the time are retryable, malformed responses are not.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            RpcApiError::ConnectionError(_) => ErrorCategory::Retryable,
+            RpcApiError::InvalidProxy(_) => ErrorCategory::InvalidInput,
+            RpcApiError::RequestError(_, status) => match status.code() {
+                TonicCode::Unavailable
+                | TonicCode::DeadlineExceeded
+                | TonicCode::ResourceExhausted
+                | TonicCode::Aborted => ErrorCategory::Retryable,
+                _ => ErrorCategory::Fatal,
+            },
+            RpcApiError::ConversionFailure(_)
+            | RpcApiError::ExpectedFieldMissing(_)
+            | RpcApiError::IncompatibleNodeVersion { .. }
+            | RpcApiError::InvalidAccountReceived(_) => ErrorCategory::Fatal,
+        }
     }
 }
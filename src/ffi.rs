@@ -0,0 +1,465 @@
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    panic::{self, AssertUnwindSafe},
+    ptr,
+};
+
+use objects::{
+    accounts::AccountId,
+    assets::{Asset, FungibleAsset},
+    notes::NoteId,
+    Digest,
+};
+use serde::Serialize;
+use tokio::runtime::Runtime;
+
+use crate::{
+    client::{
+        accounts::{AccountStorageMode, AccountTemplate},
+        notes::NoteSummary,
+        transactions::{PaymentTransactionData, TransactionTemplate},
+        Client,
+    },
+    config::ClientConfig,
+    errors::{ClientError, ErrorCategory},
+    store::notes::InputNoteFilter,
+};
+
+/// Returned by binding functions that succeed
+pub const MIDEN_OK: i32 = 0;
+/// Returned by binding functions failing with an error the client cannot recover from
+pub const MIDEN_ERROR_FATAL: i32 = 1;
+/// Returned by binding functions given invalid arguments or input invalid for the client's state
+pub const MIDEN_ERROR_INVALID_INPUT: i32 = 2;
+/// Returned by binding functions failing with an error that may go away if the call is retried
+pub const MIDEN_ERROR_RETRYABLE: i32 = 75;
+
+thread_local! {
+    /// Error code and message of the last binding call that failed on this thread
+    static LAST_ERROR: RefCell<Option<(CString, CString)>> = RefCell::new(None);
+}
+
+// CLIENT HANDLE
+// ================================================================================================
+
+/// A [Client] owned by the caller of the bindings, along with the runtime its asynchronous
+/// operations are driven by. Created by [miden_client_new] and released by [miden_client_free].
+pub struct MidenClient {
+    client: Client,
+    runtime: Runtime,
+}
+
+/// Creates a client from its configuration in JSON format, which has the same options as the
+/// client's config file, and writes its handle to `out_client`.
+///
+/// # Safety
+/// `config_json` must be a valid NUL-terminated string and `out_client` a valid pointer. The
+/// handle must be released with [miden_client_free].
+#[no_mangle]
+pub unsafe extern "C" fn miden_client_new(
+    config_json: *const c_char,
+    out_client: *mut *mut MidenClient,
+) -> i32 {
+    run(|| {
+        let config: ClientConfig = serde_json::from_str(read_str(config_json, "config_json")?)
+            .map_err(|err| FfiError::InvalidArgument(format!("invalid configuration: {err}")))?;
+        config
+            .validate()
+            .map_err(|err| FfiError::InvalidArgument(format!("invalid configuration: {err}")))?;
+
+        let runtime = Runtime::new().map_err(|err| FfiError::Runtime(err.to_string()))?;
+        let client = Client::new(config)?;
+        write_out(
+            out_client,
+            Box::into_raw(Box::new(MidenClient { client, runtime })),
+        )
+    })
+}
+
+/// Releases a client created by [miden_client_new]. Does nothing if `client` is null.
+///
+/// # Safety
+/// `client` must be null or a handle returned by [miden_client_new] that was not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn miden_client_free(client: *mut MidenClient) {
+    if !client.is_null() {
+        drop(Box::from_raw(client));
+    }
+}
+
+// OPERATIONS
+// ================================================================================================
+
+/// Syncs the client with the node and writes the block number it is synced to to
+/// `out_block_num`.
+///
+/// # Safety
+/// `client` must be a handle returned by [miden_client_new] and `out_block_num` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn miden_client_sync(
+    client: *mut MidenClient,
+    out_block_num: *mut u32,
+) -> i32 {
+    run(|| {
+        let MidenClient { client, runtime } = client_mut(client)?;
+        let block_num = runtime.block_on(client.sync_state())?;
+        write_out(out_block_num, block_num)
+    })
+}
+
+/// Creates a basic wallet account and writes its ID to `out_account_id`. On-chain wallets are
+/// not supported yet, so setting `on_chain` fails with [MIDEN_ERROR_INVALID_INPUT].
+///
+/// # Safety
+/// `client` must be a handle returned by [miden_client_new] and `out_account_id` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn miden_client_new_wallet(
+    client: *mut MidenClient,
+    on_chain: bool,
+    out_account_id: *mut u64,
+) -> i32 {
+    run(|| {
+        if on_chain {
+            return Err(FfiError::InvalidArgument(
+                "on-chain wallets are not supported yet".to_string(),
+            ));
+        }
+        let (account, _seed) =
+            client_mut(client)?
+                .client
+                .new_account(AccountTemplate::BasicWallet {
+                    mutable_code: false,
+                    storage_mode: AccountStorageMode::Local,
+                })?;
+        write_out(out_account_id, u64::from(account.id()))
+    })
+}
+
+/// Writes the client's input notes to `out_notes_json` as a JSON array. `status` selects the
+/// notes listed: `0` for all of them, `1` for committed, `2` for consumed, `3` for pending and
+/// `4` for expected ones.
+///
+/// # Safety
+/// `client` must be a handle returned by [miden_client_new] and `out_notes_json` a valid
+/// pointer. The string written to it must be released with [miden_string_free].
+#[no_mangle]
+pub unsafe extern "C" fn miden_client_list_notes(
+    client: *mut MidenClient,
+    status: u8,
+    out_notes_json: *mut *mut c_char,
+) -> i32 {
+    run(|| {
+        let filter = match status {
+            0 => InputNoteFilter::All,
+            1 => InputNoteFilter::Committed,
+            2 => InputNoteFilter::Consumed,
+            3 => InputNoteFilter::Pending,
+            4 => InputNoteFilter::Expected,
+            _ => {
+                return Err(FfiError::InvalidArgument(format!(
+                    "invalid note status {status}"
+                )))
+            }
+        };
+
+        let notes: Vec<NoteSummary> = client_mut(client)?
+            .client
+            .get_input_notes(filter)?
+            .iter()
+            .map(NoteSummary::from)
+            .collect();
+        write_out(out_notes_json, to_json(&notes)?)
+    })
+}
+
+/// Sends `amount` base units of the fungible asset issued by `faucet_id` from `sender_id` to
+/// `target_id` in a pay-to-id note, and writes the receipt of the submitted transaction to
+/// `out_receipt_json` in JSON format.
+///
+/// # Safety
+/// `client` must be a handle returned by [miden_client_new] and `out_receipt_json` a valid
+/// pointer. The string written to it must be released with [miden_string_free].
+#[no_mangle]
+pub unsafe extern "C" fn miden_client_send_payment(
+    client: *mut MidenClient,
+    sender_id: u64,
+    target_id: u64,
+    faucet_id: u64,
+    amount: u64,
+    out_receipt_json: *mut *mut c_char,
+) -> i32 {
+    run(|| {
+        let asset = FungibleAsset::new(parse_account_id(faucet_id)?, amount)
+            .map_err(|err| FfiError::InvalidArgument(err.to_string()))?;
+        let payment = PaymentTransactionData::new(
+            Asset::Fungible(asset),
+            parse_account_id(sender_id)?,
+            parse_account_id(target_id)?,
+        );
+
+        let receipt =
+            submit_transaction(client_mut(client)?, TransactionTemplate::PayToId(payment))?;
+        write_out(out_receipt_json, receipt)
+    })
+}
+
+/// Consumes the input notes with the IDs listed in `note_ids_json`, a JSON array of hex strings,
+/// into `account_id`, and writes the receipt of the submitted transaction to `out_receipt_json`
+/// in JSON format.
+///
+/// # Safety
+/// `client` must be a handle returned by [miden_client_new], `note_ids_json` a valid
+/// NUL-terminated string and `out_receipt_json` a valid pointer. The string written to it must
+/// be released with [miden_string_free].
+#[no_mangle]
+pub unsafe extern "C" fn miden_client_consume_notes(
+    client: *mut MidenClient,
+    account_id: u64,
+    note_ids_json: *const c_char,
+    out_receipt_json: *mut *mut c_char,
+) -> i32 {
+    run(|| {
+        let note_ids: Vec<String> = serde_json::from_str(read_str(note_ids_json, "note_ids_json")?)
+            .map_err(|err| FfiError::InvalidArgument(format!("invalid note IDs: {err}")))?;
+        let note_ids = note_ids
+            .iter()
+            .map(|note_id| Digest::try_from(note_id.as_str()).map(NoteId::from))
+            .collect::<Result<Vec<NoteId>, _>>()
+            .map_err(|err| FfiError::InvalidArgument(format!("invalid note ID: {err}")))?;
+
+        let template = TransactionTemplate::ConsumeNotes(parse_account_id(account_id)?, note_ids);
+        let receipt = submit_transaction(client_mut(client)?, template)?;
+        write_out(out_receipt_json, receipt)
+    })
+}
+
+// ERRORS AND STRINGS
+// ================================================================================================
+
+/// Returns the message of the error of the last binding call that failed on the calling thread,
+/// or null if the last call succeeded. The string is owned by the bindings and stays valid until
+/// the next binding call on the same thread.
+#[no_mangle]
+pub extern "C" fn miden_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last_error| match &*last_error.borrow() {
+        Some((_, message)) => message.as_ptr(),
+        None => ptr::null(),
+    })
+}
+
+/// Returns the stable code of the error of the last binding call that failed on the calling
+/// thread, such as `store.transaction_not_found`, or null if the last call succeeded. The string
+/// is owned by the bindings and stays valid until the next binding call on the same thread.
+#[no_mangle]
+pub extern "C" fn miden_last_error_code() -> *const c_char {
+    LAST_ERROR.with(|last_error| match &*last_error.borrow() {
+        Some((code, _)) => code.as_ptr(),
+        None => ptr::null(),
+    })
+}
+
+/// Releases a string returned by the bindings. Does nothing if `string` is null.
+///
+/// # Safety
+/// `string` must be null or a string written by a binding function that was not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn miden_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Error of a binding call, reported through [miden_last_error_message] and
+/// [miden_last_error_code]
+enum FfiError {
+    Client(ClientError),
+    InvalidArgument(String),
+    Runtime(String),
+    Panic,
+}
+
+impl FfiError {
+    fn status(&self) -> i32 {
+        let category = match self {
+            FfiError::Client(err) => err.category(),
+            FfiError::InvalidArgument(_) => ErrorCategory::InvalidInput,
+            FfiError::Runtime(_) | FfiError::Panic => ErrorCategory::Fatal,
+        };
+
+        match category {
+            ErrorCategory::Fatal => MIDEN_ERROR_FATAL,
+            ErrorCategory::InvalidInput => MIDEN_ERROR_INVALID_INPUT,
+            ErrorCategory::Retryable => MIDEN_ERROR_RETRYABLE,
+        }
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            FfiError::Client(err) => err.code(),
+            FfiError::InvalidArgument(_) => "ffi.invalid_argument",
+            FfiError::Runtime(_) => "ffi.runtime_error",
+            FfiError::Panic => "ffi.panic",
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            FfiError::Client(err) => err.to_string(),
+            FfiError::InvalidArgument(message) => message.clone(),
+            FfiError::Runtime(message) => format!("failed to start the async runtime: {message}"),
+            FfiError::Panic => "the client panicked".to_string(),
+        }
+    }
+}
+
+impl From<ClientError> for FfiError {
+    fn from(err: ClientError) -> Self {
+        FfiError::Client(err)
+    }
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Runs the body of a binding call, recording its error if it fails, and returns its status.
+/// Panics are caught so that they do not unwind into the caller.
+fn run(body: impl FnOnce() -> Result<(), FfiError>) -> i32 {
+    let result = panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or(Err(FfiError::Panic));
+
+    LAST_ERROR.with(|last_error| match result {
+        Ok(()) => {
+            *last_error.borrow_mut() = None;
+            MIDEN_OK
+        }
+        Err(err) => {
+            let code = CString::new(err.code()).expect("error codes contain no NUL bytes");
+            let message = CString::new(err.message().replace('\0', ""))
+                .expect("NUL bytes were removed from the message");
+            *last_error.borrow_mut() = Some((code, message));
+            err.status()
+        }
+    })
+}
+
+/// Executes the transaction described by `template`, proves and submits it, and returns its
+/// receipt in JSON format
+fn submit_transaction(
+    handle: &mut MidenClient,
+    template: TransactionTemplate,
+) -> Result<*mut c_char, FfiError> {
+    let MidenClient { client, runtime } = handle;
+
+    let tx_result = client.new_transaction(template)?;
+    let receipt = tx_result.receipt();
+    runtime.block_on(client.send_transaction(tx_result))?;
+
+    to_json(&receipt)
+}
+
+unsafe fn client_mut<'a>(client: *mut MidenClient) -> Result<&'a mut MidenClient, FfiError> {
+    client
+        .as_mut()
+        .ok_or_else(|| FfiError::InvalidArgument("client handle is null".to_string()))
+}
+
+unsafe fn read_str<'a>(string: *const c_char, name: &str) -> Result<&'a str, FfiError> {
+    if string.is_null() {
+        return Err(FfiError::InvalidArgument(format!("{name} is null")));
+    }
+
+    CStr::from_ptr(string)
+        .to_str()
+        .map_err(|err| FfiError::InvalidArgument(format!("{name} is not valid UTF-8: {err}")))
+}
+
+unsafe fn write_out<T>(out: *mut T, value: T) -> Result<(), FfiError> {
+    if out.is_null() {
+        return Err(FfiError::InvalidArgument(
+            "output pointer is null".to_string(),
+        ));
+    }
+
+    out.write(value);
+    Ok(())
+}
+
+fn parse_account_id(account_id: u64) -> Result<AccountId, FfiError> {
+    AccountId::try_from(account_id)
+        .map_err(|err| FfiError::InvalidArgument(format!("invalid account ID: {err}")))
+}
+
+/// Serializes `value` to a JSON string owned by the caller
+fn to_json(value: &impl Serialize) -> Result<*mut c_char, FfiError> {
+    let json = serde_json::to_string(value).map_err(|err| FfiError::Runtime(err.to_string()))?;
+    Ok(CString::new(json)
+        .expect("JSON strings contain no NUL bytes")
+        .into_raw())
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        ffi::{CStr, CString},
+        ptr,
+    };
+
+    use super::{
+        miden_client_free, miden_client_list_notes, miden_client_new, miden_client_new_wallet,
+        miden_last_error_code, miden_string_free, MidenClient, MIDEN_ERROR_INVALID_INPUT, MIDEN_OK,
+    };
+    use crate::store::tests::create_test_store_path;
+
+    #[test]
+    fn client_handles_report_errors() {
+        let config = CString::new(format!(
+            r#"{{"store": {{"database_filepath": "{}"}}, "rpc": {{"endpoint": {{"protocol": "http", "host": "localhost", "port": 57291}}}}}}"#,
+            create_test_store_path().display()
+        ))
+        .unwrap();
+
+        unsafe {
+            let mut client: *mut MidenClient = ptr::null_mut();
+            assert_eq!(miden_client_new(config.as_ptr(), &mut client), MIDEN_OK);
+            assert!(miden_last_error_code().is_null());
+
+            let mut account_id = 0u64;
+            assert_eq!(
+                miden_client_new_wallet(client, false, &mut account_id),
+                MIDEN_OK
+            );
+            assert_ne!(account_id, 0);
+            assert_eq!(
+                miden_client_new_wallet(client, true, &mut account_id),
+                MIDEN_ERROR_INVALID_INPUT
+            );
+            assert_eq!(
+                CStr::from_ptr(miden_last_error_code()).to_str().unwrap(),
+                "ffi.invalid_argument"
+            );
+
+            let mut notes = ptr::null_mut();
+            assert_eq!(miden_client_list_notes(client, 0, &mut notes), MIDEN_OK);
+            assert_eq!(CStr::from_ptr(notes).to_str().unwrap(), "[]");
+            miden_string_free(notes);
+
+            assert_eq!(
+                miden_client_list_notes(client, 5, &mut notes),
+                MIDEN_ERROR_INVALID_INPUT
+            );
+            assert_eq!(
+                CStr::from_ptr(miden_last_error_code()).to_str().unwrap(),
+                "ffi.invalid_argument"
+            );
+
+            assert_eq!(
+                miden_client_list_notes(ptr::null_mut(), 0, &mut notes),
+                MIDEN_ERROR_INVALID_INPUT
+            );
+            miden_client_free(client);
+        }
+    }
+}
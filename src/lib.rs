@@ -1,6 +1,10 @@
 pub mod client;
 pub mod config;
 pub mod errors;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod metrics;
+pub mod prelude;
 pub mod store;
 
 #[cfg(any(test, feature = "mock"))]
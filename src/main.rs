@@ -5,12 +5,15 @@ use cli::Cli;
 
 #[tokio::main]
 async fn main() {
-    tracing_subscriber::fmt::init();
     // read command-line args
     let cli = Cli::parse();
 
     // execute cli action
     if let Err(error) = cli.execute().await {
-        println!("{}", error);
+        eprintln!("{}", error);
+        if let Some(hint) = error.hint() {
+            eprintln!("{hint}");
+        }
+        std::process::exit(error.exit_code());
     }
 }
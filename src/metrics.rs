@@ -0,0 +1,105 @@
+use core::time::Duration;
+
+#[cfg(feature = "metrics")]
+use std::net::SocketAddr;
+
+use crate::client::RpcApiEndpoint;
+
+// METRIC NAMES
+// ================================================================================================
+
+/// Histogram of the time taken by a full sync, in seconds
+pub const SYNC_DURATION_SECONDS: &str = "miden_client_sync_duration_seconds";
+/// Counter of the blocks the client's state was advanced by while syncing
+pub const BLOCKS_APPLIED: &str = "miden_client_blocks_applied_total";
+/// Counter of the tracked notes found to be committed while syncing
+pub const NOTES_DISCOVERED: &str = "miden_client_notes_discovered_total";
+/// Histogram of the time taken to prove a transaction, in seconds
+pub const PROVING_DURATION_SECONDS: &str = "miden_client_proving_duration_seconds";
+/// Counter of failed requests to the node, labeled by `endpoint`
+pub const RPC_ERRORS: &str = "miden_client_rpc_errors_total";
+
+// RECORDING
+// ================================================================================================
+//
+// Without the `metrics` feature these are no-ops. With it, values are reported through the
+// `metrics` facade to whichever recorder the application installed.
+
+#[cfg(feature = "metrics")]
+pub(crate) fn record_sync(duration: Duration, blocks_applied: u32) {
+    ::metrics::histogram!(SYNC_DURATION_SECONDS, duration.as_secs_f64());
+    ::metrics::counter!(BLOCKS_APPLIED, blocks_applied as u64);
+}
+
+#[cfg(not(feature = "metrics"))]
+pub(crate) fn record_sync(_duration: Duration, _blocks_applied: u32) {}
+
+#[cfg(feature = "metrics")]
+pub(crate) fn record_notes_discovered(count: usize) {
+    ::metrics::counter!(NOTES_DISCOVERED, count as u64);
+}
+
+#[cfg(not(feature = "metrics"))]
+pub(crate) fn record_notes_discovered(_count: usize) {}
+
+#[cfg(feature = "metrics")]
+pub(crate) fn record_proving(duration: Duration) {
+    ::metrics::histogram!(PROVING_DURATION_SECONDS, duration.as_secs_f64());
+}
+
+#[cfg(not(feature = "metrics"))]
+pub(crate) fn record_proving(_duration: Duration) {}
+
+// only called by the node RPC client, which is replaced by a mock in tests
+#[cfg_attr(any(test, feature = "mock"), allow(dead_code))]
+#[cfg(feature = "metrics")]
+pub(crate) fn record_rpc_error(endpoint: &RpcApiEndpoint) {
+    ::metrics::increment_counter!(RPC_ERRORS, "endpoint" => endpoint.to_string());
+}
+
+#[cfg_attr(any(test, feature = "mock"), allow(dead_code))]
+#[cfg(not(feature = "metrics"))]
+pub(crate) fn record_rpc_error(_endpoint: &RpcApiEndpoint) {}
+
+// PROMETHEUS EXPORTER
+// ================================================================================================
+
+/// Installs a global recorder serving the client's metrics at `http://<address>/metrics` in the
+/// Prometheus text format. Must be called from within a Tokio runtime, which the HTTP listener is
+/// spawned on.
+///
+/// # Errors
+///
+/// Returns an error if a recorder is already installed or the listener cannot be bound.
+#[cfg(feature = "metrics")]
+pub fn install_prometheus_exporter(
+    address: SocketAddr,
+) -> Result<(), metrics_exporter_prometheus::BuildError> {
+    metrics_exporter_prometheus::PrometheusBuilder::new()
+        .with_http_listener(address)
+        .install()?;
+
+    // descriptions are recorded by the installed recorder, so they are only kept once it is set
+    describe_metrics();
+    Ok(())
+}
+
+#[cfg(feature = "metrics")]
+fn describe_metrics() {
+    ::metrics::describe_histogram!(
+        SYNC_DURATION_SECONDS,
+        ::metrics::Unit::Seconds,
+        "Time taken by a full sync"
+    );
+    ::metrics::describe_counter!(BLOCKS_APPLIED, "Blocks the client's state was advanced by");
+    ::metrics::describe_counter!(
+        NOTES_DISCOVERED,
+        "Tracked notes found committed while syncing"
+    );
+    ::metrics::describe_histogram!(
+        PROVING_DURATION_SECONDS,
+        ::metrics::Unit::Seconds,
+        "Time taken to prove a transaction"
+    );
+    ::metrics::describe_counter!(RPC_ERRORS, "Failed requests to the node");
+}
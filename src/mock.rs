@@ -1,13 +1,17 @@
 use crate::{
     client::{
         rpc_client::StateSyncInfo,
-        sync::FILTER_ID_SHIFT,
+        sync::{ACCOUNT_TREE_DEPTH, FILTER_ID_SHIFT},
         transactions::{PaymentTransactionData, TransactionTemplate},
         Client, RpcApiEndpoint,
     },
     errors::RpcApiError,
 };
-use crypto::{dsa::rpo_falcon512::KeyPair, Felt, FieldElement, StarkField, Word};
+use crypto::{
+    dsa::rpo_falcon512::KeyPair,
+    merkle::{EmptySubtreeRoots, MmrPeaks, MmrProof, PartialMmr},
+    Felt, FieldElement, StarkField, Word,
+};
 use miden_lib::transaction::TransactionKernel;
 use miden_node_proto::{
     account::AccountId as ProtoAccountId,
@@ -41,14 +45,19 @@ use objects::{
 ///
 /// This struct implements the RPC API used by the client to communicate with the node. It is
 /// intended to be used for testing purposes only.
+#[derive(Clone)]
 pub struct MockRpcApi {
     pub state_sync_requests: BTreeMap<SyncStateRequest, SyncStateResponse>,
+    /// Genesis block of the mocked chain, which the chain roots of the other blocks commit to
+    pub genesis_block: BlockHeader,
 }
 
 impl Default for MockRpcApi {
     fn default() -> Self {
+        let genesis_block = block::mock_block_header(0, None, None, &[]);
         Self {
-            state_sync_requests: generate_state_sync_mock_requests(),
+            state_sync_requests: generate_state_sync_mock_requests(&genesis_block),
+            genesis_block,
         }
     }
 }
@@ -82,7 +91,8 @@ impl MockRpcApi {
     }
 
     /// Creates and executes a [GetBlockHeaderByNumberRequest].
-    /// Only used for retrieving genesis block right now so that's the only case we need to cover.
+    /// Blocks other than the genesis block are looked up among the block headers returned by the
+    /// mocked sync state responses.
     pub async fn get_block_header_by_number(
         &mut self,
         request: impl IntoRequest<GetBlockHeaderByNumberRequest>,
@@ -90,10 +100,74 @@ impl MockRpcApi {
         let request: GetBlockHeaderByNumberRequest = request.into_request().into_inner();
 
         if request.block_num == Some(0) {
-            let block_header: objects::BlockHeader = block::mock_block_header(0, None, None, &[]);
-            return Ok(block_header);
+            return Ok(self.genesis_block);
         }
-        panic!("get_block_header_by_number is supposed to be only used for genesis block")
+
+        self.state_sync_requests
+            .values()
+            .filter_map(|response| response.block_header.clone())
+            .find(|block_header| Some(block_header.block_num) == request.block_num)
+            .ok_or(RpcApiError::RequestError(
+                RpcApiEndpoint::GetBlockHeaderByNumber,
+                Status::not_found("no block header for the requested block number"),
+            ))?
+            .try_into()
+            .map_err(RpcApiError::ConversionFailure)
+    }
+
+    /// Looks up the requested block header like [MockRpcApi::get_block_header_by_number]. The
+    /// returned proof has an empty path in an MMR whose forest is the highest mocked block number,
+    /// so it only authenticates blocks that are single-leaf peaks.
+    pub async fn get_block_header_with_proof(
+        &mut self,
+        block_num: u32,
+    ) -> Result<(BlockHeader, MmrProof), RpcApiError> {
+        let block_header = self
+            .get_block_header_by_number(GetBlockHeaderByNumberRequest {
+                block_num: Some(block_num),
+                include_mmr_proof: Some(true),
+            })
+            .await?;
+        let forest = self
+            .state_sync_requests
+            .values()
+            .map(|response| response.chain_tip)
+            .max()
+            .unwrap_or_default();
+
+        Ok((
+            block_header,
+            MmrProof {
+                forest: forest as usize,
+                position: block_num as usize,
+                merkle_path: crypto::merkle::MerklePath::new(Vec::new()),
+            },
+        ))
+    }
+
+    /// Looks up the header of the requested block like [MockRpcApi::get_block_header_by_number].
+    /// Account states are not mocked, so the returned proof is that of an empty leaf in the empty
+    /// account tree the mocked block headers commit to.
+    pub async fn get_account_proof(
+        &mut self,
+        _account_id: AccountId,
+        block_num: u32,
+    ) -> Result<(BlockHeader, Digest, crypto::merkle::MerklePath), RpcApiError> {
+        let block_header = self
+            .get_block_header_by_number(GetBlockHeaderByNumberRequest {
+                block_num: Some(block_num),
+                include_mmr_proof: None,
+            })
+            .await?;
+
+        let empty_hashes = EmptySubtreeRoots::empty_hashes(ACCOUNT_TREE_DEPTH as u8);
+        let merkle_path = empty_hashes[1..].iter().rev().copied().collect();
+
+        Ok((
+            block_header,
+            Digest::default(),
+            crypto::merkle::MerklePath::new(merkle_path),
+        ))
     }
 
     pub async fn submit_proven_transaction(
@@ -109,11 +183,13 @@ impl MockRpcApi {
     }
 }
 
-/// Generates mock sync state requests and responses
+/// Generates mock sync state requests and responses. The chain roots of the returned block headers
+/// commit to the chain MMR built from `genesis_block` and the returned MMR deltas.
 fn create_mock_state_sync_request_for_account_and_notes(
     requests: &mut BTreeMap<SyncStateRequest, SyncStateResponse>,
     account_id: AccountId,
     recorded_notes: &InputNotes,
+    genesis_block: &BlockHeader,
 ) {
     let accounts = vec![ProtoAccountId {
         id: u64::from(account_id),
@@ -139,17 +215,21 @@ fn create_mock_state_sync_request_for_account_and_notes(
     let chain_tip = 10;
 
     // create a block header for the response
-    let block_header: objects::BlockHeader = block::mock_block_header(8, None, None, &[]);
+    let mut chain_mmr = PartialMmr::from_peaks(MmrPeaks::new(0, vec![]).unwrap());
+    let mmr_delta = MmrDelta {
+        forest: 8,
+        data: (0..3)
+            .map(|_| Digest::new(Word::default()).into())
+            .collect(),
+    };
+    let chain_root = extend_chain_mmr(&mut chain_mmr, genesis_block, &mmr_delta);
+    let block_header: objects::BlockHeader =
+        block::mock_block_header(8, Some(chain_root), None, &[]);
 
     // create a state sync response
     let response = SyncStateResponse {
         chain_tip,
-        mmr_delta: Some(MmrDelta {
-            forest: 8,
-            data: (0..3)
-                .map(|_| Digest::new(Word::default()).into())
-                .collect(),
-        }),
+        mmr_delta: Some(mmr_delta),
         block_header: Some(NodeBlockHeader::from(block_header)),
         accounts: vec![],
         notes: vec![NoteSyncRecord {
@@ -178,15 +258,18 @@ fn create_mock_state_sync_request_for_account_and_notes(
     };
 
     // create a block header for the response
-    let block_header: objects::BlockHeader = block::mock_block_header(10, None, None, &[]);
+    let mmr_delta = MmrDelta {
+        forest: 10,
+        data: vec![Digest::new(Word::default()).into()],
+    };
+    let chain_root = extend_chain_mmr(&mut chain_mmr, &block_header, &mmr_delta);
+    let block_header: objects::BlockHeader =
+        block::mock_block_header(10, Some(chain_root), None, &[]);
 
     // create a state sync response
     let response = SyncStateResponse {
         chain_tip,
-        mmr_delta: Some(MmrDelta {
-            forest: 10,
-            data: vec![Digest::new(Word::default()).into()],
-        }),
+        mmr_delta: Some(mmr_delta),
         block_header: Some(NodeBlockHeader::from(block_header)),
         accounts: vec![],
         notes: vec![NoteSyncRecord {
@@ -205,8 +288,24 @@ fn create_mock_state_sync_request_for_account_and_notes(
     requests.insert(request, response);
 }
 
+/// Adds the block the client syncs from to the chain MMR and applies the delta received along
+/// with the next block, the way the client does, returning the root the next block commits to
+fn extend_chain_mmr(
+    chain_mmr: &mut PartialMmr,
+    block_header: &BlockHeader,
+    mmr_delta: &MmrDelta,
+) -> Digest {
+    chain_mmr.add(block_header.hash(), false);
+    chain_mmr
+        .apply(mmr_delta.clone().try_into().unwrap())
+        .unwrap();
+    chain_mmr.peaks().hash_peaks()
+}
+
 /// Generates mock sync state requests and responses
-fn generate_state_sync_mock_requests() -> BTreeMap<SyncStateRequest, SyncStateResponse> {
+fn generate_state_sync_mock_requests(
+    genesis_block: &BlockHeader,
+) -> BTreeMap<SyncStateRequest, SyncStateResponse> {
     use mock::mock::{account::MockAccountType, transaction::mock_inputs};
 
     // generate test data
@@ -222,6 +321,7 @@ fn generate_state_sync_mock_requests() -> BTreeMap<SyncStateRequest, SyncStateRe
         &mut requests,
         transaction_inputs.account().id(),
         transaction_inputs.input_notes(),
+        genesis_block,
     );
 
     requests
@@ -262,14 +362,28 @@ pub async fn insert_mock_data(client: &mut Client) {
         .unwrap();
 
     // insert some sync request
+    let genesis_block = client.rpc_api.genesis_block;
     create_mock_state_sync_request_for_account_and_notes(
         &mut client.rpc_api.state_sync_requests,
         account_id,
         transaction_inputs.input_notes(),
+        &genesis_block,
     );
 }
 
 pub async fn create_mock_transaction(client: &mut Client) {
+    let transaction_template = create_mock_payment_template(client);
+    let transaction_execution_result = client.new_transaction(transaction_template).unwrap();
+
+    client
+        .send_transaction(transaction_execution_result)
+        .await
+        .unwrap();
+}
+
+/// Inserts a sender wallet, a target wallet and a fungible faucet into the client and returns the
+/// template of a payment of 5 units of the faucet's asset between the two wallets
+pub fn create_mock_payment_template(client: &mut Client) -> TransactionTemplate {
     let key_pair: KeyPair = KeyPair::new()
         .map_err(|err| format!("Error generating KeyPair: {}", err))
         .unwrap();
@@ -342,20 +456,11 @@ pub async fn create_mock_transaction(client: &mut Client) {
 
     let asset: objects::assets::Asset = FungibleAsset::new(faucet.id(), 5u64).unwrap().into();
 
-    // Insert a P2ID transaction object
-
-    let transaction_template = TransactionTemplate::PayToId(PaymentTransactionData::new(
+    TransactionTemplate::PayToId(PaymentTransactionData::new(
         asset,
         sender_account.id(),
         target_account.id(),
-    ));
-
-    let transaction_execution_result = client.new_transaction(transaction_template).unwrap();
-
-    client
-        .send_transaction(transaction_execution_result)
-        .await
-        .unwrap();
+    ))
 }
 
 #[cfg(test)]
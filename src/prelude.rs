@@ -0,0 +1,82 @@
+//! Types commonly needed to use the client, gathered in one module so that applications can name
+//! them without depending on the Miden crates the client is built on:
+//!
+//! ```ignore
+//! use miden_client::prelude::*;
+//!
+//! let mut client = Client::new(ClientConfig::default())?;
+//! let account_id = AccountId::from_hex("0x9a3f...")?;
+//! let asset = FungibleAsset::new(faucet_id, 100)?;
+//! ```
+//!
+//! Every type is re-exported under the name it has in its own crate or module, so the prelude can
+//! be mixed with explicit imports of the same types.
+
+// PROTOCOL TYPES
+// ================================================================================================
+
+pub use crypto::dsa::rpo_falcon512::KeyPair;
+pub use miden_tx::ProvingOptions;
+pub use objects::{
+    accounts::{Account, AccountData, AccountId, AccountStub, AccountType, AuthData},
+    assets::{Asset, FungibleAsset, NonFungibleAsset, NonFungibleAssetDetails, TokenSymbol},
+    notes::{Note, NoteId, NoteInclusionProof, NoteMetadata},
+    BlockHeader, Digest, Felt, StarkField, Word,
+};
+
+// CLIENT TYPES
+// ================================================================================================
+
+pub use crate::{
+    client::{
+        accounts::{AccountStorageMode, AccountTemplate},
+        address::Address,
+        assets::AssetAmount,
+        builder::ClientBuilder,
+        events::ClientEvent,
+        note_tags::{account_note_tag, custom_note_tag, swap_note_tag, NoteExecutionHint},
+        notes::{ExpectedNote, NoteBuilder},
+        prover::TransactionProverMode,
+        read_only::ReadOnlyClient,
+        transactions::{
+            FeePolicy, NoFees, PaymentTransactionData, TransactionRecord, TransactionResult,
+            TransactionStatus, TransactionTemplate,
+        },
+        Client, SyncProgress,
+    },
+    config::{ClientConfig, RpcConfig, StoreConfig},
+    errors::{ClientError, StoreError},
+    store::{
+        notes::{InputNoteFilter, InputNoteRecord, NoteFilter},
+        orders::{OrderKind, OrderRecord, OrderStatus},
+        transactions::TransactionFilter,
+        Page,
+    },
+};
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use mock::constants::{ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN, ACCOUNT_ID_SENDER};
+
+    use super::*;
+
+    #[test]
+    fn prelude_names_the_types_of_the_client_api() {
+        let account_id = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let asset: Asset = FungibleAsset::new(faucet_id, 100).unwrap().into();
+
+        let template = TransactionTemplate::MintFungibleAsset {
+            asset: FungibleAsset::new(faucet_id, 100).unwrap(),
+            target_account_id: account_id,
+        };
+        assert!(matches!(
+            template,
+            TransactionTemplate::MintFungibleAsset { asset: minted, .. } if Asset::from(minted) == asset
+        ));
+        assert_eq!(AssetAmount::new(1500, 3).to_string(), "1.5");
+    }
+}
@@ -1,22 +1,28 @@
-use super::Store;
+use std::ops::RangeBounds;
+
+use super::{notes::unix_timestamp, Store};
 
 use crate::errors::StoreError;
 
-use clap::error::Result;
 use crypto::{
     dsa::rpo_falcon512::KeyPair,
     hash::rpo::RpoDigest,
+    merkle::MerklePath,
     utils::{Deserializable, Serializable},
     StarkField, Word,
 };
 use miden_lib::transaction::TransactionKernel;
 use objects::{
-    accounts::{Account, AccountCode, AccountDelta, AccountId, AccountStorage, AccountStub},
+    accounts::{
+        Account, AccountCode, AccountDelta, AccountId, AccountStorage, AccountStub, AccountType,
+    },
     assembly::{AstSerdeOptions, ModuleAst},
-    assets::{Asset, AssetVault},
-    Digest,
+    assets::{Asset, AssetVault, TokenSymbol},
+    utils::collections::{BTreeMap, BTreeSet},
+    AssetError, Digest, Felt,
 };
-use rusqlite::{params, Transaction};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use tracing::warn;
 
 // TYPES
 // ================================================================================================
@@ -35,6 +41,68 @@ type SerializedAccountCodeParts = (String, String, Vec<u8>);
 type SerializedAccountStorageData = (String, Vec<u8>);
 type SerializedAccountStorageParts = (String, Vec<u8>);
 
+type SerializedAccountDeltaData = (i64, Option<String>, i64, Vec<u8>);
+type SerializedAccountDeltaParts = (i64, Option<String>, i64, Vec<u8>);
+
+type SerializedAccountCodeUpgradeData = (i64, String, String, String);
+type SerializedAccountCodeUpgradeParts = (i64, String, String, String);
+
+type SerializedAccountProofData = (i64, u32, String, String);
+type SerializedAccountProofParts = (i64, u32, String, String);
+
+type SerializedFaucetDetailsData = (i64, String, u8);
+type SerializedFaucetDetailsParts = (String, u8);
+
+// FAUCET DETAILS
+// ================================================================================================
+
+/// Storage slot in which basic fungible faucets keep their metadata, laid out as
+/// `[max_supply, decimals, token_symbol, 0]`.
+pub(crate) const FAUCET_METADATA_SLOT: u8 = 1;
+
+/// Token symbol and decimals of a fungible faucet, used to present amounts of its assets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FaucetDetails {
+    pub token_symbol: TokenSymbol,
+    pub decimals: u8,
+}
+
+impl TryFrom<&AccountStorage> for FaucetDetails {
+    type Error = AssetError;
+
+    /// Reads the details from the storage of a basic fungible faucet
+    fn try_from(storage: &AccountStorage) -> Result<Self, Self::Error> {
+        let [_max_supply, decimals, token_symbol, _] = *storage.get_item(FAUCET_METADATA_SLOT);
+
+        Ok(Self {
+            token_symbol: TokenSymbol::try_from(token_symbol)?,
+            decimals: decimals.as_int() as u8,
+        })
+    }
+}
+
+impl Serializable for FaucetDetails {
+    fn write_into<W: crypto::utils::ByteWriter>(&self, target: &mut W) {
+        target.write_u64(Felt::from(self.token_symbol).as_int());
+        target.write_u8(self.decimals);
+    }
+}
+
+impl Deserializable for FaucetDetails {
+    fn read_from<R: crypto::utils::ByteReader>(
+        source: &mut R,
+    ) -> Result<Self, crypto::utils::DeserializationError> {
+        let token_symbol = TokenSymbol::try_from(Felt::new(source.read_u64()?))
+            .map_err(|err| crypto::utils::DeserializationError::InvalidValue(err.to_string()))?;
+        let decimals = source.read_u8()?;
+
+        Ok(Self {
+            token_symbol,
+            decimals,
+        })
+    }
+}
+
 // DATABASE AUTH INFO
 // ================================================================================================
 
@@ -86,6 +154,89 @@ impl Deserializable for AuthInfo {
     }
 }
 
+// ACCOUNT STATE UPDATE
+// ================================================================================================
+
+/// The state of an account after applying an [AccountDelta] to it. Only the parts of the account
+/// a delta can change are held, while the code is referenced by its root.
+pub(super) struct AccountStateUpdate {
+    account_id: AccountId,
+    nonce: Felt,
+    code_root: Digest,
+    storage: AccountStorage,
+    vault: AssetVault,
+    account_seed: Word,
+    delta: AccountDelta,
+}
+
+// ACCOUNT DELTA RECORD
+// ================================================================================================
+
+/// An [AccountDelta] applied to one of the accounts tracked by the client
+#[derive(Debug, Clone)]
+pub struct AccountDeltaRecord {
+    pub account_id: AccountId,
+    /// The transaction the delta results from, or `None` if the delta was applied directly
+    /// through [Client::update_account](crate::client::Client::update_account)
+    pub transaction_id: Option<Digest>,
+    /// The nonce of the account after applying the delta
+    pub nonce: Felt,
+    pub delta: AccountDelta,
+}
+
+// ACCOUNT CODE UPGRADE
+// ================================================================================================
+
+/// A change of the code of an account made by a transaction. The code the account had before is
+/// kept in the store, so it can still be retrieved by its root after the upgrade.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountCodeUpgrade {
+    pub account_id: AccountId,
+    pub transaction_id: Digest,
+    pub previous_code_root: Digest,
+    pub code_root: Digest,
+}
+
+// ACCOUNT PROOF
+// ================================================================================================
+
+/// The proof of the state of an on-chain account at a block: the path from the account's leaf,
+/// whose value is the account hash, to the account root of the block header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountProof {
+    pub account_id: AccountId,
+    pub block_num: u32,
+    pub account_hash: Digest,
+    pub merkle_path: MerklePath,
+}
+
+// DESYNCHRONIZED ACCOUNT
+// ================================================================================================
+
+/// An account whose on-chain state, as reported by the node while syncing, differs from the
+/// local one. This happens when the account was updated from another store, in which case the
+/// local state can no longer be used to execute transactions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesynchronizedAccount {
+    pub account_id: AccountId,
+    /// Hash of the local state of the account when the divergence was found
+    pub local_hash: Digest,
+    /// Hash of the on-chain state of the account reported by the node
+    pub remote_hash: Digest,
+    /// Block at which the divergence was found
+    pub block_num: u32,
+}
+
+// ADDRESS BOOK
+// ================================================================================================
+
+/// A name given to an account so it can be referred to by name instead of by ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressBookEntry {
+    pub name: String,
+    pub account_id: AccountId,
+}
+
 impl Store {
     // ACCOUNTS
     // --------------------------------------------------------------------------------------------
@@ -106,14 +257,15 @@ impl Store {
     }
 
     /// Returns a list of [AccountStub] of all accounts stored in the database along with the seeds
-    /// used to create them.
+    /// used to create them. Hidden accounts are left out, see [Store::hide_account].
     ///
     /// Said accounts' state is the state at the last sync made.
     pub fn get_accounts(&self) -> Result<Vec<(AccountStub, Word)>, StoreError> {
         const QUERY: &str =
             "SELECT a.id, a.nonce, a.vault_root, a.storage_root, a.code_root, a.account_seed \
             FROM accounts a \
-            WHERE a.nonce = (SELECT MAX(b.nonce) FROM accounts b WHERE b.id = a.id)";
+            WHERE a.nonce = (SELECT MAX(b.nonce) FROM accounts b WHERE b.id = a.id) \
+            AND a.id NOT IN (SELECT account_id FROM hidden_accounts)";
 
         self.db
             .prepare(QUERY)?
@@ -133,6 +285,14 @@ impl Store {
     pub fn get_account_stub_by_id(
         &self,
         account_id: AccountId,
+    ) -> Result<(AccountStub, Word), StoreError> {
+        Self::get_account_stub_by_id_tx(&self.db, account_id)
+    }
+
+    /// Version of [Store::get_account_stub_by_id] reading from a database transaction
+    pub(super) fn get_account_stub_by_id_tx(
+        conn: &Connection,
+        account_id: AccountId,
     ) -> Result<(AccountStub, Word), StoreError> {
         let account_id_int: u64 = account_id.into();
         const QUERY: &str = "SELECT id, nonce, vault_root, storage_root, code_root, account_seed \
@@ -140,8 +300,7 @@ impl Store {
             ORDER BY nonce DESC \
             LIMIT 1";
 
-        self.db
-            .prepare(QUERY)?
+        conn.prepare(QUERY)?
             .query_map(params![account_id_int as i64], parse_accounts_columns)?
             .map(|result| Ok(result?).and_then(parse_accounts))
             .next()
@@ -159,10 +318,87 @@ impl Store {
         self.get_account_code(account.code_root())
     }
 
+    /// Retrieves an [AccountStub] object for the state the specified account had at block
+    /// `block_num`, along with the seed used to create it. This is the latest state stored while
+    /// the client was synced up to that block.
+    ///
+    /// The block of a state is the sync height when it was stored, not the block it was committed
+    /// in: the state resulting from a transaction is stored when the transaction is executed, so
+    /// it is returned for the blocks between that sync height and the transaction's commit block,
+    /// even if the transaction ends up never being committed.
+    ///
+    /// # Errors
+    /// Returns an [Err] if the account was not tracked by the client at that block
+    pub fn get_account_stub_at(
+        &self,
+        account_id: AccountId,
+        block_num: u32,
+    ) -> Result<(AccountStub, Word), StoreError> {
+        let account_id_int: u64 = account_id.into();
+        const QUERY: &str = "SELECT id, nonce, vault_root, storage_root, code_root, account_seed \
+            FROM accounts WHERE id = ? AND block_num <= ? \
+            ORDER BY nonce DESC \
+            LIMIT 1";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map(
+                params![account_id_int as i64, block_num],
+                parse_accounts_columns,
+            )?
+            .map(|result| Ok(result?).and_then(parse_accounts))
+            .next()
+            .ok_or(StoreError::AccountDataNotFound(account_id))?
+    }
+
+    /// Retrieves a full [Account] object for the state the specified account had at block
+    /// `block_num`. See [Store::get_account_stub_at].
+    pub fn get_account_state_at(
+        &self,
+        account_id: AccountId,
+        block_num: u32,
+    ) -> Result<(Account, Word), StoreError> {
+        let (account_stub, seed) = self.get_account_stub_at(account_id, block_num)?;
+        Ok((self.get_account_from_stub(&account_stub)?, seed))
+    }
+
     // TODO: Get all parts from a single query
     /// Retrieves a full [Account] object
     pub fn get_account_by_id(&self, account_id: AccountId) -> Result<(Account, Word), StoreError> {
         let (account_stub, seed) = self.get_account_stub_by_id(account_id)?;
+        Ok((self.get_account_from_stub(&account_stub)?, seed))
+    }
+
+    /// Retrieves the state of the account `account_id` the transaction `transaction_id` was
+    /// executed against, along with the account seed. This is the latest state of the account
+    /// unless the changes of the transaction were already applied to it.
+    pub fn get_account_before_transaction(
+        &self,
+        account_id: AccountId,
+        transaction_id: Digest,
+    ) -> Result<(Account, Word), StoreError> {
+        let account_id_int: u64 = account_id.into();
+        const QUERY: &str = "SELECT id, nonce, vault_root, storage_root, code_root, account_seed \
+            FROM accounts WHERE id = ?1 AND nonce < \
+            COALESCE((SELECT nonce FROM account_deltas WHERE transaction_id = ?2), nonce + 1) \
+            ORDER BY nonce DESC \
+            LIMIT 1";
+
+        let (account_stub, seed) = self
+            .db
+            .prepare(QUERY)?
+            .query_map(
+                params![account_id_int as i64, String::from(transaction_id)],
+                parse_accounts_columns,
+            )?
+            .map(|result| Ok(result?).and_then(parse_accounts))
+            .next()
+            .ok_or(StoreError::AccountDataNotFound(account_id))??;
+        Ok((self.get_account_from_stub(&account_stub)?, seed))
+    }
+
+    /// Builds the full [Account] whose state is described by `account_stub`
+    fn get_account_from_stub(&self, account_stub: &AccountStub) -> Result<Account, StoreError> {
         let (_procedures, module_ast) = self.get_account_code(account_stub.code_root())?;
 
         //let account_code = AccountCode::from_parts(module_ast, procedures);
@@ -181,7 +417,7 @@ impl Store {
             account_stub.nonce(),
         );
 
-        Ok((account, seed))
+        Ok(account)
     }
 
     /// Retrieve account keys data by Account Id
@@ -196,25 +432,115 @@ impl Store {
             .ok_or(StoreError::AccountDataNotFound(account_id))?
     }
 
+    /// Returns the hash of the latest state of the account, which is computed from the roots
+    /// stored along with the account without loading its code, storage or vault.
+    ///
+    /// # Errors
+    /// Returns an [Err] if the account was not found
+    pub fn get_account_hash(&self, account_id: AccountId) -> Result<Digest, StoreError> {
+        Ok(self.get_account_stub_by_id(account_id)?.0.hash())
+    }
+
     /// Update account after a transaction execution
     pub fn update_account(
         &mut self,
         account_id: AccountId,
         account_delta: &AccountDelta,
     ) -> Result<(), StoreError> {
-        let (mut account, seed) = self.get_account_by_id(account_id)?;
+        let tx = self.write_transaction()?;
 
-        account
-            .apply_delta(account_delta)
-            .map_err(StoreError::AccountError)?;
+        let account_update = Self::get_account_state_update(&tx, account_id, account_delta, None)?;
+        Self::insert_account_state_update(&tx, &account_update, None)?;
 
-        let tx = self.db.transaction()?;
+        Ok(tx.commit()?)
+    }
 
-        Self::insert_account_storage(&tx, account.storage())?;
-        Self::insert_account_asset_vault(&tx, account.vault())?;
-        Self::insert_account_record(&tx, &account, seed)?;
+    /// Retrieves the deltas applied to the account, in the order they were applied, whose
+    /// resulting account nonce is within `nonces`
+    pub fn get_account_deltas(
+        &self,
+        account_id: AccountId,
+        nonces: impl RangeBounds<u64>,
+    ) -> Result<Vec<AccountDeltaRecord>, StoreError> {
+        const QUERY: &str = "SELECT account_id, transaction_id, nonce, delta FROM account_deltas \
+            WHERE account_id = ? ORDER BY id";
 
-        Ok(tx.commit()?)
+        let deltas = self
+            .db
+            .prepare(QUERY)?
+            .query_map(
+                params![u64::from(account_id) as i64],
+                parse_account_delta_columns,
+            )?
+            .map(|result| Ok(result?).and_then(parse_account_delta))
+            .collect::<Result<Vec<AccountDeltaRecord>, StoreError>>()?;
+
+        Ok(deltas
+            .into_iter()
+            .filter(|record| nonces.contains(&record.nonce.as_int()))
+            .collect())
+    }
+
+    /// Applies `account_delta` to the latest stored state of the account. Only the storage and
+    /// vault of the account are loaded, as its code cannot be changed by a delta. If the delta
+    /// results from a transaction upgrading the account's code, the update refers to the new code.
+    ///
+    /// The state is read through `conn`, the database transaction the update is then written in,
+    /// so that no other connection can change it in between.
+    ///
+    /// # Errors
+    /// Returns [StoreError::StaleAccountDelta] if the delta sets a nonce that is not higher than
+    /// the stored one, as it was either already applied or computed against an older state.
+    pub(super) fn get_account_state_update(
+        conn: &Connection,
+        account_id: AccountId,
+        account_delta: &AccountDelta,
+        transaction_id: Option<Digest>,
+    ) -> Result<AccountStateUpdate, StoreError> {
+        let (account_stub, account_seed) = Self::get_account_stub_by_id_tx(conn, account_id)?;
+        if let Some(nonce) = account_delta.nonce() {
+            if nonce.as_int() <= account_stub.nonce().as_int() {
+                return Err(StoreError::StaleAccountDelta(
+                    account_id,
+                    nonce.as_int(),
+                    account_stub.nonce().as_int(),
+                ));
+            }
+        }
+
+        let code_root = match transaction_id {
+            Some(transaction_id) => Self::get_upgraded_code_root_tx(conn, transaction_id)?,
+            None => None,
+        };
+
+        let mut storage = Self::get_account_storage_tx(conn, account_stub.storage_root())?;
+        let storage_delta = account_delta.storage();
+        for slot in storage_delta.cleared_items.iter() {
+            storage.set_item(*slot, Word::default())?;
+        }
+        for (slot, value) in storage_delta.updated_items.iter() {
+            storage.set_item(*slot, *value)?;
+        }
+
+        let mut vault =
+            AssetVault::new(&Self::get_vault_assets_tx(conn, account_stub.vault_root())?)?;
+        let vault_delta = account_delta.vault();
+        for asset in vault_delta.added_assets.iter() {
+            vault.add_asset(*asset)?;
+        }
+        for asset in vault_delta.removed_assets.iter() {
+            vault.remove_asset(*asset)?;
+        }
+
+        Ok(AccountStateUpdate {
+            account_id,
+            nonce: account_delta.nonce().unwrap_or(account_stub.nonce()),
+            code_root: code_root.unwrap_or(account_stub.code_root()),
+            storage,
+            vault,
+            account_seed,
+            delta: account_delta.clone(),
+        })
     }
 
     /// Retrieve account code-related data by code root
@@ -235,31 +561,147 @@ impl Store {
 
     /// Retrieve account storage data by vault root
     pub fn get_account_storage(&self, root: RpoDigest) -> Result<AccountStorage, StoreError> {
+        Self::get_account_storage_tx(&self.db, root)
+    }
+
+    fn get_account_storage_tx(
+        conn: &Connection,
+        root: RpoDigest,
+    ) -> Result<AccountStorage, StoreError> {
         let root_serialized = &root.to_string();
 
         const QUERY: &str = "SELECT root, slots FROM account_storage WHERE root = ?";
-        self.db
-            .prepare(QUERY)?
+        conn.prepare(QUERY)?
             .query_map(params![root_serialized], parse_account_storage_columns)?
             .map(|result| Ok(result?).and_then(parse_account_storage))
             .next()
             .ok_or(StoreError::AccountStorageNotFound(root))?
     }
 
+    /// Retrieves the latest storage of the account with the specified [AccountId], resolving the
+    /// storage root and its slots in a single query.
+    ///
+    /// # Errors
+    /// Returns an [Err] if the account was not found
+    pub fn get_account_storage_by_account_id(
+        &self,
+        account_id: AccountId,
+    ) -> Result<AccountStorage, StoreError> {
+        let account_id_int: u64 = account_id.into();
+        const QUERY: &str = "SELECT s.root, s.slots \
+            FROM accounts a JOIN account_storage s ON a.storage_root = s.root \
+            WHERE a.id = ? \
+            ORDER BY a.nonce DESC \
+            LIMIT 1";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map(
+                params![account_id_int as i64],
+                parse_account_storage_columns,
+            )?
+            .map(|result| Ok(result?).and_then(parse_account_storage))
+            .next()
+            .ok_or(StoreError::AccountDataNotFound(account_id))?
+    }
+
     /// Retrieve assets by vault root
     pub fn get_vault_assets(&self, root: RpoDigest) -> Result<Vec<Asset>, StoreError> {
+        Self::get_vault_assets_tx(&self.db, root)
+    }
+
+    fn get_vault_assets_tx(conn: &Connection, root: RpoDigest) -> Result<Vec<Asset>, StoreError> {
         let vault_root =
             serde_json::to_string(&root).map_err(StoreError::InputSerializationError)?;
 
         const QUERY: &str = "SELECT root, assets FROM account_vaults WHERE root = ?";
-        self.db
-            .prepare(QUERY)?
+        conn.prepare(QUERY)?
             .query_map(params![vault_root], parse_account_asset_vault_columns)?
             .map(|result| Ok(result?).and_then(parse_account_asset_vault))
             .next()
             .ok_or(StoreError::VaultDataNotFound(root))?
     }
 
+    /// Retrieves the assets held in the latest vault of the account with the specified
+    /// [AccountId], resolving the vault root and its assets in a single query.
+    ///
+    /// # Errors
+    /// Returns an [Err] if the account was not found
+    pub fn get_account_vault_assets(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<Asset>, StoreError> {
+        let account_id_int: u64 = account_id.into();
+        const QUERY: &str = "SELECT v.root, v.assets \
+            FROM accounts a JOIN account_vaults v ON a.vault_root = v.root \
+            WHERE a.id = ? \
+            ORDER BY a.nonce DESC \
+            LIMIT 1";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map(
+                params![account_id_int as i64],
+                parse_account_asset_vault_columns,
+            )?
+            .map(|result| Ok(result?).and_then(parse_account_asset_vault))
+            .next()
+            .ok_or(StoreError::AccountDataNotFound(account_id))?
+    }
+
+    /// Returns the balances of the account with the specified [AccountId], aggregated per faucet.
+    ///
+    /// Fungible assets contribute their amount, while each non-fungible asset counts as a single
+    /// unit for its issuing faucet.
+    pub fn get_account_balances(
+        &self,
+        account_id: AccountId,
+    ) -> Result<BTreeMap<AccountId, u64>, StoreError> {
+        let mut balances = BTreeMap::new();
+        for asset in self.get_account_vault_assets(account_id)? {
+            let (faucet_id, amount) = match asset {
+                Asset::Fungible(fungible_asset) => {
+                    (fungible_asset.faucet_id(), fungible_asset.amount())
+                }
+                Asset::NonFungible(non_fungible_asset) => (non_fungible_asset.faucet_id(), 1),
+            };
+            *balances.entry(faucet_id).or_insert(0) += amount;
+        }
+
+        Ok(balances)
+    }
+
+    /// Retrieves the [FaucetDetails] of the faucet with the specified [AccountId], if known
+    pub fn get_faucet_details(
+        &self,
+        faucet_id: AccountId,
+    ) -> Result<Option<FaucetDetails>, StoreError> {
+        let faucet_id_int: u64 = faucet_id.into();
+        const QUERY: &str = "SELECT token_symbol, decimals FROM faucet_details WHERE faucet_id = ?";
+
+        self.db
+            .query_row(
+                QUERY,
+                params![faucet_id_int as i64],
+                parse_faucet_details_columns,
+            )
+            .optional()?
+            .map(parse_faucet_details)
+            .transpose()
+    }
+
+    /// Inserts or replaces the [FaucetDetails] of the faucet with the specified [AccountId]
+    pub fn insert_faucet_details(
+        &mut self,
+        faucet_id: AccountId,
+        faucet_details: &FaucetDetails,
+    ) -> Result<(), StoreError> {
+        let tx = self.write_transaction()?;
+        Self::insert_faucet_details_tx(&tx, faucet_id, faucet_details)?;
+
+        Ok(tx.commit()?)
+    }
+
     /// Inserts an [Account] along with the seed used to create it and its [AuthInfo]
     pub fn insert_account(
         &mut self,
@@ -267,28 +709,123 @@ impl Store {
         account_seed: Word,
         auth_info: &AuthInfo,
     ) -> Result<(), StoreError> {
-        let tx = self.db.transaction()?;
+        let tx = self.write_transaction()?;
 
         Self::insert_account_code(&tx, account.code())?;
         Self::insert_account_storage(&tx, account.storage())?;
         Self::insert_account_asset_vault(&tx, account.vault())?;
-        Self::insert_account_record(&tx, account, account_seed)?;
+        Self::insert_account_record(&tx, &account_stub(account), account_seed)?;
         Self::insert_account_auth(&tx, account.id(), auth_info)?;
+        Self::insert_faucet_details_from_storage(&tx, account.id(), account.storage())?;
 
         Ok(tx.commit()?)
     }
 
+    /// Returns the ID of the single account whose hex ID starts with `prefix`, which may omit the
+    /// `0x`. Both the accounts tracked by the client and the accounts in the address book are
+    /// considered.
+    ///
+    /// # Errors
+    /// Returns an [Err] if no account or more than one account matches the prefix
+    pub fn get_account_id_by_prefix(&self, prefix: &str) -> Result<AccountId, StoreError> {
+        /// Number of matching account IDs listed when the prefix is ambiguous
+        const MAX_LISTED_MATCHES: usize = 5;
+
+        let digits = prefix.strip_prefix("0x").unwrap_or(prefix).to_lowercase();
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(StoreError::AccountIdPrefixNotFound(prefix.to_string()));
+        }
+        let prefix_with_0x = format!("0x{digits}");
+
+        let account_ids: Vec<AccountId> = self
+            .get_account_ids()?
+            .into_iter()
+            .chain(
+                self.get_address_book()?
+                    .into_iter()
+                    .map(|entry| entry.account_id),
+            )
+            .collect::<BTreeSet<AccountId>>()
+            .into_iter()
+            .filter(|account_id| account_id.to_string().starts_with(&prefix_with_0x))
+            .collect();
+
+        match account_ids.as_slice() {
+            [] => Err(StoreError::AccountIdPrefixNotFound(prefix.to_string())),
+            [account_id] => Ok(*account_id),
+            _ => Err(StoreError::AmbiguousAccountIdPrefix(
+                prefix.to_string(),
+                account_ids
+                    .iter()
+                    .take(MAX_LISTED_MATCHES)
+                    .map(ToString::to_string)
+                    .collect(),
+            )),
+        }
+    }
+
+    /// Stores the state of an account resulting from applying a delta to it, along with the
+    /// storage and vault it refers to and the delta itself. `transaction_id` identifies the
+    /// transaction the delta results from, if any.
+    pub(super) fn insert_account_state_update(
+        tx: &Transaction<'_>,
+        account_update: &AccountStateUpdate,
+        transaction_id: Option<Digest>,
+    ) -> Result<(), StoreError> {
+        let AccountStateUpdate {
+            account_id,
+            nonce,
+            code_root,
+            storage,
+            vault,
+            account_seed,
+            delta,
+        } = account_update;
+
+        Self::insert_account_storage(tx, storage)?;
+        Self::insert_account_asset_vault(tx, vault)?;
+
+        let account_stub = AccountStub::new(
+            *account_id,
+            *nonce,
+            vault.commitment(),
+            storage.root(),
+            *code_root,
+        );
+        Self::insert_account_record(tx, &account_stub, *account_seed)?;
+        Self::insert_faucet_details_from_storage(tx, *account_id, storage)?;
+
+        let delta_record = AccountDeltaRecord {
+            account_id: *account_id,
+            transaction_id,
+            nonce: *nonce,
+            delta: delta.clone(),
+        };
+        Self::insert_account_delta(tx, &delta_record)
+    }
+
+    /// Inserts an [AccountDeltaRecord]
+    fn insert_account_delta(
+        tx: &Transaction<'_>,
+        delta_record: &AccountDeltaRecord,
+    ) -> Result<(), StoreError> {
+        let (account_id, transaction_id, nonce, delta) = serialize_account_delta(delta_record);
+        const QUERY: &str = "INSERT INTO account_deltas (account_id, transaction_id, nonce, delta) VALUES (?, ?, ?, ?)";
+        tx.execute(QUERY, params![account_id, transaction_id, nonce, delta])?;
+        Ok(())
+    }
+
     pub(super) fn insert_account_record(
         tx: &Transaction<'_>,
-        account: &Account,
+        account_stub: &AccountStub,
         account_seed: Word,
     ) -> Result<(), StoreError> {
         let (id, code_root, storage_root, vault_root, nonce, committed) =
-            serialize_account(account)?;
+            serialize_account(account_stub)?;
 
         let account_seed = account_seed.to_bytes();
 
-        const QUERY: &str =  "INSERT INTO accounts (id, code_root, storage_root, vault_root, nonce, committed, account_seed) VALUES (?, ?, ?, ?, ?, ?, ?)";
+        const QUERY: &str =  "INSERT INTO accounts (id, code_root, storage_root, vault_root, nonce, committed, account_seed, block_num) VALUES (?, ?, ?, ?, ?, ?, ?, (SELECT block_num FROM state_sync))";
         tx.execute(
             QUERY,
             params![
@@ -338,6 +875,39 @@ impl Store {
         Ok(())
     }
 
+    pub(super) fn insert_faucet_details_tx(
+        tx: &Transaction<'_>,
+        faucet_id: AccountId,
+        faucet_details: &FaucetDetails,
+    ) -> Result<(), StoreError> {
+        let (faucet_id, token_symbol, decimals) =
+            serialize_faucet_details(faucet_id, faucet_details);
+        const QUERY: &str = "INSERT OR REPLACE INTO faucet_details (faucet_id, token_symbol, decimals) VALUES (?, ?, ?)";
+        tx.execute(QUERY, params![faucet_id, token_symbol, decimals])?;
+        Ok(())
+    }
+
+    /// Records the [FaucetDetails] of the account if it is a fungible faucet, reading them from
+    /// its `storage`. Faucets whose storage does not follow the basic fungible faucet layout are
+    /// skipped.
+    pub(super) fn insert_faucet_details_from_storage(
+        tx: &Transaction<'_>,
+        account_id: AccountId,
+        storage: &AccountStorage,
+    ) -> Result<(), StoreError> {
+        if account_id.account_type() != AccountType::FungibleFaucet {
+            return Ok(());
+        }
+
+        match FaucetDetails::try_from(storage) {
+            Ok(faucet_details) => Self::insert_faucet_details_tx(tx, account_id, &faucet_details),
+            Err(err) => {
+                warn!("Could not read the details of faucet {}: {err}", account_id);
+                Ok(())
+            }
+        }
+    }
+
     /// Inserts an [AuthInfo] for the account with id `account_id`
     pub(super) fn insert_account_auth(
         tx: &Transaction<'_>,
@@ -349,11 +919,461 @@ impl Store {
         tx.execute(QUERY, params![account_id, auth_info])?;
         Ok(())
     }
+
+    // ACCOUNT CODE UPGRADES
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the code upgrades of the specified account, in the order they were made
+    pub fn get_account_code_upgrades(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<AccountCodeUpgrade>, StoreError> {
+        let account_id_int: u64 = account_id.into();
+        const QUERY: &str = "SELECT account_id, transaction_id, previous_code_root, code_root \
+            FROM account_code_upgrades WHERE account_id = ? ORDER BY rowid";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map(
+                params![account_id_int as i64],
+                parse_account_code_upgrade_columns,
+            )?
+            .map(|result| Ok(result?).and_then(parse_account_code_upgrade))
+            .collect()
+    }
+
+    /// Returns the root of the code an account has after the specified transaction, if the
+    /// transaction upgrades its code
+    pub fn get_upgraded_code_root(
+        &self,
+        transaction_id: Digest,
+    ) -> Result<Option<Digest>, StoreError> {
+        Self::get_upgraded_code_root_tx(&self.db, transaction_id)
+    }
+
+    fn get_upgraded_code_root_tx(
+        conn: &Connection,
+        transaction_id: Digest,
+    ) -> Result<Option<Digest>, StoreError> {
+        const QUERY: &str = "SELECT code_root FROM account_code_upgrades WHERE transaction_id = ?";
+
+        conn.query_row(QUERY, params![String::from(transaction_id)], |row| {
+            row.get::<_, String>(0)
+        })
+        .optional()?
+        .map(|code_root| Ok(Digest::try_from(&code_root)?))
+        .transpose()
+    }
+
+    /// Records a transaction upgrading the code of an account, along with the new code. The
+    /// upgrade takes effect once the account changes of the transaction are applied.
+    pub(super) fn insert_account_code_upgrade(
+        tx: &Transaction<'_>,
+        upgrade: &AccountCodeUpgrade,
+        account_code: &AccountCode,
+    ) -> Result<(), StoreError> {
+        Self::insert_account_code(tx, account_code)?;
+
+        let (account_id, transaction_id, previous_code_root, code_root) =
+            serialize_account_code_upgrade(upgrade);
+        const QUERY: &str = "INSERT INTO account_code_upgrades \
+            (account_id, transaction_id, previous_code_root, code_root) VALUES (?, ?, ?, ?) \
+            ON CONFLICT (transaction_id) DO NOTHING";
+        tx.execute(
+            QUERY,
+            params![account_id, transaction_id, previous_code_root, code_root],
+        )?;
+        Ok(())
+    }
+
+    // ACCOUNT PROOFS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the latest verified proof of the state of the specified on-chain account
+    pub fn get_account_proof(&self, account_id: AccountId) -> Result<AccountProof, StoreError> {
+        let account_id_int: u64 = account_id.into();
+        const QUERY: &str = "SELECT account_id, block_num, account_hash, merkle_path \
+            FROM account_proofs WHERE account_id = ?";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map(params![account_id_int as i64], parse_account_proof_columns)?
+            .map(|result| Ok(result?).and_then(parse_account_proof))
+            .next()
+            .ok_or(StoreError::AccountProofNotFound(account_id))?
+    }
+
+    /// Stores a verified proof of the state of an on-chain account, replacing its previous one
+    pub fn insert_account_proof(&mut self, account_proof: &AccountProof) -> Result<(), StoreError> {
+        let tx = self.write_transaction()?;
+        Self::insert_account_proof_tx(&tx, account_proof)?;
+        Ok(tx.commit()?)
+    }
+
+    pub(super) fn insert_account_proof_tx(
+        tx: &Transaction<'_>,
+        account_proof: &AccountProof,
+    ) -> Result<(), StoreError> {
+        let (account_id, block_num, account_hash, merkle_path) =
+            serialize_account_proof(account_proof)?;
+        const QUERY: &str = "INSERT OR REPLACE INTO account_proofs \
+            (account_id, block_num, account_hash, merkle_path) VALUES (?, ?, ?, ?)";
+        tx.execute(
+            QUERY,
+            params![account_id, block_num, account_hash, merkle_path],
+        )?;
+        Ok(())
+    }
+
+    // DESYNCHRONIZED ACCOUNTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the accounts whose on-chain state was found to differ from the local one
+    pub fn get_desynchronized_accounts(&self) -> Result<Vec<DesynchronizedAccount>, StoreError> {
+        const QUERY: &str = "SELECT account_id, local_hash, remote_hash, block_num \
+            FROM desynchronized_accounts";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map([], parse_desynchronized_account_columns)
+            .expect("no binding parameters used in query")
+            .map(|result| Ok(result?).and_then(parse_desynchronized_account))
+            .collect()
+    }
+
+    /// Returns the divergence found between the local and on-chain states of the account
+    /// `account_id`, if any
+    pub fn get_desynchronized_account(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<DesynchronizedAccount>, StoreError> {
+        const QUERY: &str = "SELECT account_id, local_hash, remote_hash, block_num \
+            FROM desynchronized_accounts WHERE account_id = ?";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map(
+                params![u64::from(account_id) as i64],
+                parse_desynchronized_account_columns,
+            )?
+            .map(|result| Ok(result?).and_then(parse_desynchronized_account))
+            .next()
+            .transpose()
+    }
+
+    /// Replaces the local state of the desynchronized account with `account`, keeping its seed
+    /// and auth info, and clears the divergence
+    ///
+    /// The local states of the account at or above the nonce of `account` are discarded, as the
+    /// local state may have diverged at the same nonce or moved past the on-chain one.
+    pub fn replace_desynchronized_account(&mut self, account: &Account) -> Result<(), StoreError> {
+        let (_, account_seed) = self.get_account_stub_by_id(account.id())?;
+
+        let tx = self.write_transaction()?;
+
+        const DISCARD_QUERY: &str = "DELETE FROM accounts WHERE id = ? AND nonce >= ?";
+        tx.execute(
+            DISCARD_QUERY,
+            params![
+                u64::from(account.id()) as i64,
+                account.nonce().as_int() as i64
+            ],
+        )?;
+        Self::insert_account_code(&tx, account.code())?;
+        Self::insert_account_storage(&tx, account.storage())?;
+        Self::insert_account_asset_vault(&tx, account.vault())?;
+        Self::insert_account_record(&tx, &account_stub(account), account_seed)?;
+        Self::remove_desynchronized_account_tx(&tx, account.id())?;
+
+        Ok(tx.commit()?)
+    }
+
+    pub(super) fn insert_desynchronized_account_tx(
+        tx: &Transaction<'_>,
+        account: &DesynchronizedAccount,
+    ) -> Result<(), StoreError> {
+        const QUERY: &str = "INSERT OR REPLACE INTO desynchronized_accounts \
+            (account_id, local_hash, remote_hash, block_num) VALUES (?, ?, ?, ?)";
+        tx.execute(
+            QUERY,
+            params![
+                u64::from(account.account_id) as i64,
+                account.local_hash.to_hex(),
+                account.remote_hash.to_hex(),
+                account.block_num
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub(super) fn remove_desynchronized_account_tx(
+        tx: &Transaction<'_>,
+        account_id: AccountId,
+    ) -> Result<(), StoreError> {
+        const QUERY: &str = "DELETE FROM desynchronized_accounts WHERE account_id = ?";
+        tx.execute(QUERY, params![u64::from(account_id) as i64])?;
+        Ok(())
+    }
+
+    // ADDRESS BOOK
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns all address book entries, sorted by name
+    pub fn get_address_book(&self) -> Result<Vec<AddressBookEntry>, StoreError> {
+        const QUERY: &str = "SELECT name, account_id FROM address_book ORDER BY name";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .expect("no binding parameters used in query")
+            .map(|result| Ok(result?).map(parse_address_book_entry))
+            .collect()
+    }
+
+    /// Returns the ID of the account named `name` in the address book, if any
+    pub fn get_address_book_account_id(&self, name: &str) -> Result<Option<AccountId>, StoreError> {
+        const QUERY: &str = "SELECT name, account_id FROM address_book WHERE name = ?";
+
+        Ok(self
+            .db
+            .query_row(QUERY, params![name], |row| Ok((row.get(0)?, row.get(1)?)))
+            .optional()?
+            .map(|parts| parse_address_book_entry(parts).account_id))
+    }
+
+    /// Names the account `account_id` in the address book, replacing the account previously
+    /// given the same name, if any
+    ///
+    /// # Errors
+    /// Returns an [Err] if the name is empty or starts with `0x`, which would make it
+    /// indistinguishable from an account ID
+    pub fn insert_address_book_entry(
+        &mut self,
+        name: &str,
+        account_id: AccountId,
+    ) -> Result<(), StoreError> {
+        const QUERY: &str = "INSERT OR REPLACE INTO address_book (name, account_id) VALUES (?, ?)";
+
+        if name.is_empty() || name.starts_with("0x") {
+            return Err(StoreError::InvalidAddressBookName(name.to_string()));
+        }
+
+        self.db
+            .execute(QUERY, params![name, u64::from(account_id) as i64])?;
+        Ok(())
+    }
+
+    /// Removes the entry named `name` from the address book
+    ///
+    /// # Errors
+    /// Returns an [Err] if there is no entry with that name
+    pub fn remove_address_book_entry(&mut self, name: &str) -> Result<(), StoreError> {
+        const QUERY: &str = "DELETE FROM address_book WHERE name = ?";
+
+        if self.db.execute(QUERY, params![name])? == 0 {
+            return Err(StoreError::AddressBookEntryNotFound(name.to_string()));
+        }
+        Ok(())
+    }
+
+    // ACCOUNT LABELS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the labels of the labelled accounts
+    pub fn get_account_labels(&self) -> Result<BTreeMap<AccountId, String>, StoreError> {
+        const QUERY: &str = "SELECT account_id, label FROM account_labels";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .expect("no binding parameters used in query")
+            .map(|result| {
+                let (account_id, label): (i64, String) = result?;
+                Ok((AccountId::try_from(account_id as u64)?, label))
+            })
+            .collect()
+    }
+
+    /// Returns the label of the account `account_id`, if it has one
+    pub fn get_account_label(&self, account_id: AccountId) -> Result<Option<String>, StoreError> {
+        const QUERY: &str = "SELECT label FROM account_labels WHERE account_id = ?";
+
+        Ok(self
+            .db
+            .query_row(QUERY, params![u64::from(account_id) as i64], |row| {
+                row.get(0)
+            })
+            .optional()?)
+    }
+
+    /// Sets the label of the account `account_id`, replacing its previous label, or removes it if
+    /// `label` is `None`
+    ///
+    /// # Errors
+    /// Returns an [Err] if the label is blank
+    pub fn set_account_label(
+        &mut self,
+        account_id: AccountId,
+        label: Option<&str>,
+    ) -> Result<(), StoreError> {
+        const QUERY: &str =
+            "INSERT OR REPLACE INTO account_labels (account_id, label) VALUES (?, ?)";
+        const REMOVE_QUERY: &str = "DELETE FROM account_labels WHERE account_id = ?";
+
+        let account_id = u64::from(account_id) as i64;
+        match label {
+            Some(label) if label.trim().is_empty() => {
+                return Err(StoreError::InvalidAccountLabel(label.to_string()))
+            }
+            Some(label) => self.db.execute(QUERY, params![account_id, label.trim()])?,
+            None => self.db.execute(REMOVE_QUERY, params![account_id])?,
+        };
+        Ok(())
+    }
+
+    // HIDDEN ACCOUNTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the hidden accounts along with the seeds used to create them
+    pub fn get_hidden_accounts(&self) -> Result<Vec<(AccountStub, Word)>, StoreError> {
+        const QUERY: &str =
+            "SELECT a.id, a.nonce, a.vault_root, a.storage_root, a.code_root, a.account_seed \
+            FROM accounts a \
+            WHERE a.nonce = (SELECT MAX(b.nonce) FROM accounts b WHERE b.id = a.id) \
+            AND a.id IN (SELECT account_id FROM hidden_accounts)";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map([], parse_accounts_columns)
+            .expect("no binding parameters used in query")
+            .map(|result| Ok(result?).and_then(parse_accounts))
+            .collect()
+    }
+
+    /// Hides the account `account_id`, leaving it out of [Store::get_accounts] and of the note
+    /// tags registered for it, while keeping all its data. Returns false if the account was
+    /// already hidden.
+    ///
+    /// The sync height of the account stays at the block the client was synced to when it was
+    /// hidden, as the account is left out of the syncs from then on.
+    pub fn hide_account(&mut self, account_id: AccountId) -> Result<bool, StoreError> {
+        const QUERY: &str =
+            "INSERT OR IGNORE INTO hidden_accounts (account_id, hidden_at) VALUES (?, ?)";
+        const SYNC_HEIGHT_QUERY: &str = "INSERT OR IGNORE INTO account_sync_heights \
+            (account_id, block_num) VALUES (?, (SELECT block_num FROM state_sync))";
+
+        let account_id = u64::from(account_id) as i64;
+        let tx = self.write_transaction()?;
+        let hidden = tx.execute(QUERY, params![account_id, unix_timestamp()])?;
+        tx.execute(SYNC_HEIGHT_QUERY, params![account_id])?;
+        tx.commit()?;
+
+        Ok(hidden > 0)
+    }
+
+    /// Stops hiding the account `account_id`. Returns false if the account was not hidden.
+    ///
+    /// The sync height of the account is left where it was when it was hidden, so that the next
+    /// sync of every account retrieves the notes committed for it in the meantime.
+    pub fn restore_account(&mut self, account_id: AccountId) -> Result<bool, StoreError> {
+        const QUERY: &str = "DELETE FROM hidden_accounts WHERE account_id = ?";
+
+        let restored = self
+            .db
+            .execute(QUERY, params![u64::from(account_id) as i64])?;
+        Ok(restored > 0)
+    }
+
+    /// Deletes the account `account_id` along with its keys, its transactions and the notes they
+    /// created, the notes targeting it and everything else recorded about it, in a single
+    /// transaction. The account's code, storage and vault are kept, as other accounts may share
+    /// them, and so are the address book entries naming it.
+    pub fn purge_account(&mut self, account_id: AccountId) -> Result<(), StoreError> {
+        const NOTES_QUERY: &str = "SELECT note_id FROM input_notes \
+            WHERE (origin = 'transaction' \
+                AND origin_detail IN (SELECT id FROM transactions WHERE account_id = ?1)) \
+            OR note_id IN (SELECT note_id FROM input_note_targets WHERE account_id = ?1)";
+        const NOTE_QUERIES: [&str; 7] = [
+            "DELETE FROM watched_nullifiers \
+                WHERE nullifier IN (SELECT nullifier FROM input_notes WHERE note_id = ?)",
+            "DELETE FROM sync_quarantine WHERE note_id = ?",
+            "DELETE FROM note_inclusion_paths WHERE note_id = ?",
+            "DELETE FROM input_note_assets WHERE note_id = ?",
+            "DELETE FROM input_note_targets WHERE note_id = ?",
+            "DELETE FROM note_tags WHERE source = 'note' AND source_note_id = ?",
+            "DELETE FROM input_notes WHERE note_id = ?",
+        ];
+        const ACCOUNT_QUERIES: [&str; 15] = [
+            "DELETE FROM watched_nullifiers \
+                WHERE nullifier IN (SELECT nullifier FROM orders WHERE account_id = ?1)",
+            "DELETE FROM orders WHERE account_id = ?1",
+            "DELETE FROM partial_output_notes \
+                WHERE transaction_id IN (SELECT id FROM transactions WHERE account_id = ?1)",
+            "DELETE FROM transaction_outbox \
+                WHERE transaction_id IN (SELECT id FROM transactions WHERE account_id = ?1)",
+            "DELETE FROM account_code_upgrades WHERE account_id = ?1",
+            "DELETE FROM account_deltas WHERE account_id = ?1",
+            "DELETE FROM transactions WHERE account_id = ?1",
+            "DELETE FROM note_tags WHERE source = 'account' AND source_account_id = ?1",
+            "DELETE FROM account_sync_heights WHERE account_id = ?1",
+            "DELETE FROM account_proofs WHERE account_id = ?1",
+            "DELETE FROM desynchronized_accounts WHERE account_id = ?1",
+            "DELETE FROM account_labels WHERE account_id = ?1",
+            "DELETE FROM hidden_accounts WHERE account_id = ?1",
+            "DELETE FROM account_auth WHERE account_id = ?1",
+            "DELETE FROM accounts WHERE id = ?1",
+        ];
+
+        let account_id = u64::from(account_id) as i64;
+        let tx = self.write_transaction()?;
+
+        let note_ids = tx
+            .prepare(NOTES_QUERY)?
+            .query_map(params![account_id], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        for note_id in note_ids {
+            for query in NOTE_QUERIES {
+                tx.prepare_cached(query)?.execute(params![note_id])?;
+            }
+        }
+        for query in ACCOUNT_QUERIES {
+            tx.execute(query, params![account_id])?;
+        }
+
+        Ok(tx.commit()?)
+    }
 }
 
 // HELPERS
 // ================================================================================================
 
+/// Parse the columns of a desynchronized account from the provided row
+fn parse_desynchronized_account_columns(
+    row: &rusqlite::Row<'_>,
+) -> Result<(i64, String, String, u32), rusqlite::Error> {
+    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+}
+
+/// Parse a desynchronized account from the provided columns
+fn parse_desynchronized_account(
+    (account_id, local_hash, remote_hash, block_num): (i64, String, String, u32),
+) -> Result<DesynchronizedAccount, StoreError> {
+    Ok(DesynchronizedAccount {
+        account_id: AccountId::try_from(account_id as u64)?,
+        local_hash: Digest::try_from(local_hash.as_str())?,
+        remote_hash: Digest::try_from(remote_hash.as_str())?,
+        block_num,
+    })
+}
+
+/// Parse an address book entry from the provided name and account ID columns
+fn parse_address_book_entry((name, account_id): (String, i64)) -> AddressBookEntry {
+    AddressBookEntry {
+        name,
+        account_id: AccountId::try_from(account_id as u64).expect("account id is valid"),
+    }
+}
+
 /// Parse accounts colums from the provided row into native types
 pub(crate) fn parse_accounts_columns(
     row: &rusqlite::Row<'_>,
@@ -388,15 +1408,26 @@ pub(crate) fn parse_accounts(
     ))
 }
 
+/// Returns the [AccountStub] of `account`, which holds everything stored in its account record
+fn account_stub(account: &Account) -> AccountStub {
+    AccountStub::new(
+        account.id(),
+        account.nonce(),
+        account.vault().commitment(),
+        account.storage().root(),
+        account.code().root(),
+    )
+}
+
 /// Serialized the provided account into database compatible types.
-fn serialize_account(account: &Account) -> Result<SerializedAccountData, StoreError> {
-    let id: u64 = account.id().into();
-    let code_root = account.code().root().to_string();
-    let storage_root = account.storage().root().to_string();
-    let vault_root = serde_json::to_string(&account.vault().commitment())
+fn serialize_account(account_stub: &AccountStub) -> Result<SerializedAccountData, StoreError> {
+    let id: u64 = account_stub.id().into();
+    let code_root = account_stub.code_root().to_string();
+    let storage_root = account_stub.storage_root().to_string();
+    let vault_root = serde_json::to_string(&account_stub.vault_root())
         .map_err(StoreError::InputSerializationError)?;
-    let committed = account.is_on_chain();
-    let nonce = account.nonce().as_int() as i64;
+    let committed = account_stub.id().is_on_chain();
+    let nonce = account_stub.nonce().as_int() as i64;
 
     Ok((
         id as i64,
@@ -531,6 +1562,153 @@ fn serialize_account_asset_vault(
     Ok((root, assets))
 }
 
+fn parse_account_delta_columns(
+    row: &rusqlite::Row<'_>,
+) -> Result<SerializedAccountDeltaParts, rusqlite::Error> {
+    let account_id: i64 = row.get(0)?;
+    let transaction_id: Option<String> = row.get(1)?;
+    let nonce: i64 = row.get(2)?;
+    let delta: Vec<u8> = row.get(3)?;
+    Ok((account_id, transaction_id, nonce, delta))
+}
+
+fn parse_account_delta(
+    serialized_account_delta_parts: SerializedAccountDeltaParts,
+) -> Result<AccountDeltaRecord, StoreError> {
+    let (account_id, transaction_id, nonce, delta) = serialized_account_delta_parts;
+
+    Ok(AccountDeltaRecord {
+        account_id: (account_id as u64)
+            .try_into()
+            .expect("Conversion from stored AccountID should not panic"),
+        transaction_id: transaction_id.map(Digest::try_from).transpose()?,
+        nonce: Felt::new(nonce as u64),
+        delta: AccountDelta::read_from_bytes(&delta)?,
+    })
+}
+
+fn serialize_account_delta(delta_record: &AccountDeltaRecord) -> SerializedAccountDeltaData {
+    let account_id: u64 = delta_record.account_id.into();
+    (
+        account_id as i64,
+        delta_record.transaction_id.map(String::from),
+        delta_record.nonce.as_int() as i64,
+        delta_record.delta.to_bytes(),
+    )
+}
+
+fn parse_account_code_upgrade_columns(
+    row: &rusqlite::Row<'_>,
+) -> Result<SerializedAccountCodeUpgradeParts, rusqlite::Error> {
+    let account_id: i64 = row.get(0)?;
+    let transaction_id: String = row.get(1)?;
+    let previous_code_root: String = row.get(2)?;
+    let code_root: String = row.get(3)?;
+    Ok((account_id, transaction_id, previous_code_root, code_root))
+}
+
+fn parse_account_code_upgrade(
+    serialized_upgrade_parts: SerializedAccountCodeUpgradeParts,
+) -> Result<AccountCodeUpgrade, StoreError> {
+    let (account_id, transaction_id, previous_code_root, code_root) = serialized_upgrade_parts;
+
+    Ok(AccountCodeUpgrade {
+        account_id: (account_id as u64)
+            .try_into()
+            .expect("Conversion from stored AccountID should not panic"),
+        transaction_id: Digest::try_from(&transaction_id)?,
+        previous_code_root: Digest::try_from(&previous_code_root)?,
+        code_root: Digest::try_from(&code_root)?,
+    })
+}
+
+fn serialize_account_code_upgrade(
+    upgrade: &AccountCodeUpgrade,
+) -> SerializedAccountCodeUpgradeData {
+    let account_id: u64 = upgrade.account_id.into();
+    (
+        account_id as i64,
+        String::from(upgrade.transaction_id),
+        upgrade.previous_code_root.to_string(),
+        upgrade.code_root.to_string(),
+    )
+}
+
+fn parse_account_proof_columns(
+    row: &rusqlite::Row<'_>,
+) -> Result<SerializedAccountProofParts, rusqlite::Error> {
+    let account_id: i64 = row.get(0)?;
+    let block_num: u32 = row.get(1)?;
+    let account_hash: String = row.get(2)?;
+    let merkle_path: String = row.get(3)?;
+    Ok((account_id, block_num, account_hash, merkle_path))
+}
+
+fn parse_account_proof(
+    serialized_proof_parts: SerializedAccountProofParts,
+) -> Result<AccountProof, StoreError> {
+    let (account_id, block_num, account_hash, merkle_path) = serialized_proof_parts;
+    let merkle_path: Vec<Digest> =
+        serde_json::from_str(&merkle_path).map_err(StoreError::JsonDataDeserializationError)?;
+
+    Ok(AccountProof {
+        account_id: (account_id as u64)
+            .try_into()
+            .expect("Conversion from stored AccountID should not panic"),
+        block_num,
+        account_hash: Digest::try_from(&account_hash)?,
+        merkle_path: MerklePath::new(merkle_path),
+    })
+}
+
+fn serialize_account_proof(
+    account_proof: &AccountProof,
+) -> Result<SerializedAccountProofData, StoreError> {
+    let account_id: u64 = account_proof.account_id.into();
+    let merkle_path = serde_json::to_string(&account_proof.merkle_path.to_vec())
+        .map_err(StoreError::InputSerializationError)?;
+
+    Ok((
+        account_id as i64,
+        account_proof.block_num,
+        account_proof.account_hash.to_string(),
+        merkle_path,
+    ))
+}
+
+fn parse_faucet_details_columns(
+    row: &rusqlite::Row<'_>,
+) -> Result<SerializedFaucetDetailsParts, rusqlite::Error> {
+    let token_symbol: String = row.get(0)?;
+    let decimals: u8 = row.get(1)?;
+    Ok((token_symbol, decimals))
+}
+
+fn parse_faucet_details(
+    serialized_faucet_details_parts: SerializedFaucetDetailsParts,
+) -> Result<FaucetDetails, StoreError> {
+    let (token_symbol, decimals) = serialized_faucet_details_parts;
+    let token_symbol =
+        TokenSymbol::new(&token_symbol).map_err(|err| StoreError::ParsingError(err.to_string()))?;
+
+    Ok(FaucetDetails {
+        token_symbol,
+        decimals,
+    })
+}
+
+fn serialize_faucet_details(
+    faucet_id: AccountId,
+    faucet_details: &FaucetDetails,
+) -> SerializedFaucetDetailsData {
+    let faucet_id: u64 = faucet_id.into();
+    (
+        faucet_id as i64,
+        faucet_details.token_symbol.to_str(),
+        faucet_details.decimals,
+    )
+}
+
 #[cfg(test)]
 pub mod tests {
     use crypto::{
@@ -1,20 +1,31 @@
 use std::num::NonZeroUsize;
 
-use super::Store;
+use super::{data_store::mmr_merkle_path_len, rows_per_insert, values_clause, Store};
 use crate::errors::StoreError;
-use clap::error::Result;
 
-use crypto::merkle::{InOrderIndex, MmrPeaks};
+use crypto::{
+    merkle::{InOrderIndex, MmrPeaks},
+    StarkField,
+};
 
-use objects::utils::collections::BTreeMap;
+use objects::utils::collections::{BTreeMap, BTreeSet};
 use objects::{BlockHeader, Digest};
-use rusqlite::{params, OptionalExtension, Transaction};
+use rusqlite::{params, params_from_iter, OptionalExtension, ToSql, Transaction};
 type SerializedBlockHeaderData = (i64, String, String, String, String, bool);
-type SerializedBlockHeaderParts = (u64, String, String, String, String, bool);
+type SerializedBlockHeaderParts = (u64, String, String, String, Option<String>, bool);
 
 type SerializedChainMmrNodeData = (i64, String);
 type SerializedChainMmrNodeParts = (u64, String);
 
+/// Block something happened in, such as a note being committed, along with the block's timestamp
+/// if the client tracks the block's header
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockTime {
+    pub block_num: u32,
+    /// UNIX timestamp, in seconds, of the block
+    pub timestamp: Option<u64>,
+}
+
 pub enum ChainMmrNodeFilter<'a> {
     All,
     List(&'a [InOrderIndex]),
@@ -51,29 +62,64 @@ impl Store {
         chain_mmr_peaks: MmrPeaks,
         has_client_notes: bool,
     ) -> Result<(), StoreError> {
-        let chain_mmr_peaks = chain_mmr_peaks.peaks().to_vec();
-        let (block_num, header, notes_root, sub_hash, chain_mmr, has_client_notes) =
-            serialize_block_header(block_header, chain_mmr_peaks, has_client_notes)?;
+        Self::insert_block_headers(tx, &[(block_header, chain_mmr_peaks, has_client_notes)])
+    }
 
+    /// Inserts a list of block headers into the store, alongside peaks information at each
+    /// block's height and whether the block has notes relevant to the client. The headers are
+    /// written with as few statements as possible.
+    ///
+    /// Headers that are already stored get the provided peaks, and keep being flagged as having
+    /// notes relevant to the client if they were, so that a sync interrupted midway can be applied
+    /// again.
+    pub fn insert_block_headers(
+        tx: &Transaction<'_>,
+        block_headers: &[(BlockHeader, MmrPeaks, bool)],
+    ) -> Result<(), StoreError> {
         const QUERY: &str = "\
         INSERT INTO block_headers
             (block_num, header, notes_root, sub_hash, chain_mmr_peaks, has_client_notes)
-         VALUES (?, ?, ?, ?, ?, ?)";
-
-        tx.execute(
-            QUERY,
-            params![
-                block_num,
-                header,
-                notes_root,
-                sub_hash,
-                chain_mmr,
-                has_client_notes
-            ],
-        )?;
+         VALUES";
+        const ON_CONFLICT: &str = "\
+        ON CONFLICT (block_num) DO UPDATE SET
+            chain_mmr_peaks = excluded.chain_mmr_peaks,
+            has_client_notes = block_headers.has_client_notes OR excluded.has_client_notes";
+        const COLUMNS: usize = 6;
+
+        let block_headers = block_headers
+            .iter()
+            .map(|(block_header, chain_mmr_peaks, has_client_notes)| {
+                serialize_block_header(
+                    *block_header,
+                    chain_mmr_peaks.peaks().to_vec(),
+                    *has_client_notes,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for chunk in block_headers.chunks(rows_per_insert(COLUMNS)) {
+            let mut params: Vec<&dyn ToSql> = Vec::with_capacity(chunk.len() * COLUMNS);
+            for (block_num, header, notes_root, sub_hash, chain_mmr, has_client_notes) in chunk {
+                params.extend([
+                    block_num as &dyn ToSql,
+                    header,
+                    notes_root,
+                    sub_hash,
+                    chain_mmr,
+                    has_client_notes,
+                ]);
+            }
+
+            tx.prepare_cached(&format!(
+                "{QUERY} {} {ON_CONFLICT}",
+                values_clause(COLUMNS, chunk.len())
+            ))?
+            .execute(params_from_iter(params))?;
+        }
 
         Ok(())
     }
+
     /// Retrieves a list of [BlockHeader] by number and a boolean value that represents whether the
     /// block contains notes relevant to the client. It's up to the callee to check that all
     /// requested block headers were found
@@ -127,30 +173,111 @@ impl Store {
             .collect()
     }
 
-    /// Inserts a node represented by its in-order index and the node value.
-    fn insert_chain_mmr_node(
+    /// Inserts a list of MMR authentication nodes to the Chain MMR nodes table. The nodes are
+    /// written with as few statements as possible, and nodes that are already stored are left
+    /// untouched, as a node of the chain MMR never changes once it exists.
+    pub(super) fn insert_chain_mmr_nodes(
         tx: &Transaction<'_>,
-        id: InOrderIndex,
-        node: Digest,
+        nodes: &[(InOrderIndex, Digest)],
     ) -> Result<(), StoreError> {
-        let (id, node) = serialize_chain_mmr_node(id, node)?;
+        const QUERY: &str = "INSERT OR IGNORE INTO chain_mmr_nodes (id, node) VALUES";
+        const COLUMNS: usize = 2;
+
+        let nodes = nodes
+            .iter()
+            .map(|(index, node)| serialize_chain_mmr_node(*index, *node))
+            .collect::<Result<Vec<_>, _>>()?;
 
-        const QUERY: &str = "INSERT INTO chain_mmr_nodes (id, node) VALUES (?, ?)";
+        for chunk in nodes.chunks(rows_per_insert(COLUMNS)) {
+            let params = chunk.iter().flat_map(|(id, node)| [id as &dyn ToSql, node]);
+
+            tx.prepare_cached(&format!("{QUERY} {}", values_clause(COLUMNS, chunk.len())))?
+                .execute(params_from_iter(params))?;
+        }
 
-        tx.execute(QUERY, params![id, node])?;
         Ok(())
     }
 
-    /// Inserts a list of MMR authentication nodes to the Chain MMR nodes table.
-    pub(super) fn insert_chain_mmr_nodes(
-        tx: &Transaction<'_>,
+    /// Stores the header of a block committed before the client's sync height, along with the
+    /// MMR nodes authenticating it in the chain MMR at the sync height, and marks the block as
+    /// having notes relevant to the client. Nodes that are already stored are left untouched.
+    ///
+    /// The peaks of the chain MMR at the block are not known, so they are stored as NULL. The
+    /// flag is set on the already stored header of the block, if any.
+    pub fn insert_authenticated_block_header(
+        &mut self,
+        block_header: BlockHeader,
         nodes: &[(InOrderIndex, Digest)],
     ) -> Result<(), StoreError> {
-        for (index, node) in nodes {
-            Self::insert_chain_mmr_node(tx, *index, *node)?;
+        const QUERY: &str = "\
+        INSERT INTO block_headers
+            (block_num, header, notes_root, sub_hash, chain_mmr_peaks, has_client_notes)
+        VALUES (?, ?, ?, ?, NULL, true)
+        ON CONFLICT (block_num) DO UPDATE SET has_client_notes = true";
+
+        let (block_num, header, notes_root, sub_hash, _, _) =
+            serialize_block_header(block_header, vec![], true)?;
+
+        let tx = self.write_transaction()?;
+        tx.execute(QUERY, params![block_num, header, notes_root, sub_hash])?;
+        Self::insert_chain_mmr_nodes(&tx, nodes)?;
+
+        Ok(tx.commit()?)
+    }
+
+    /// Removes the chain MMR nodes that are not needed to authenticate the blocks of unconsumed
+    /// input notes in the chain MMR at the sync height, and unflags the blocks that no longer have
+    /// notes relevant to the client. The peaks stored along with block headers are kept, so the
+    /// chain MMR can still be extended while syncing.
+    ///
+    /// Returns the number of removed nodes.
+    pub fn prune_irrelevant_mmr_nodes(&mut self) -> Result<usize, StoreError> {
+        const RELEVANT_BLOCKS_QUERY: &str = "\
+        SELECT DISTINCT commit_height FROM input_notes
+        WHERE status = 'committed' AND commit_height < (SELECT block_num FROM state_sync)";
+        const UNFLAG_QUERY: &str = "\
+        UPDATE block_headers SET has_client_notes = false
+        WHERE has_client_notes AND block_num < (SELECT block_num FROM state_sync)
+            AND block_num NOT IN
+                (SELECT commit_height FROM input_notes WHERE status = 'committed')";
+
+        let tx = self.write_transaction()?;
+
+        let forest: u32 = tx.query_row("SELECT block_num FROM state_sync", [], |row| row.get(0))?;
+        let relevant_blocks: Vec<u32> = tx
+            .prepare(RELEVANT_BLOCKS_QUERY)?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+
+        // the leaf of each relevant block and the siblings along its path up to its peak
+        let mut relevant_nodes = BTreeSet::new();
+        for block_num in relevant_blocks {
+            let mut index = InOrderIndex::from_leaf_pos(block_num as usize);
+            relevant_nodes.insert(u64::from(index));
+            for _ in 0..mmr_merkle_path_len(block_num as usize, forest as usize) {
+                relevant_nodes.insert(u64::from(index.sibling()));
+                index = index.parent();
+            }
         }
 
-        Ok(())
+        let node_ids: Vec<i64> = tx
+            .prepare("SELECT id FROM chain_mmr_nodes")?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        let mut removed_nodes = 0;
+        {
+            let mut statement = tx.prepare("DELETE FROM chain_mmr_nodes WHERE id = ?")?;
+            for node_id in node_ids {
+                if !relevant_nodes.contains(&(node_id as u64)) {
+                    removed_nodes += statement.execute(params![node_id])?;
+                }
+            }
+        }
+
+        tx.execute(UNFLAG_QUERY, [])?;
+        tx.commit()?;
+
+        Ok(removed_nodes)
     }
 
     /// Retrieves all MMR authentication nodes based on [ChainMmrNodeFilter].
@@ -165,7 +292,9 @@ impl Store {
             .collect()
     }
 
-    /// Returns peaks information from the blockchain by a specific block number.
+    /// Returns peaks information from the blockchain by a specific block number. Blocks whose
+    /// peaks are not known, like those stored through
+    /// [Store::insert_authenticated_block_header], have empty peaks.
     pub fn get_chain_mmr_peaks_by_block_num(&self, block_num: u32) -> Result<MmrPeaks, StoreError> {
         const QUERY: &str = "SELECT chain_mmr_peaks FROM block_headers WHERE block_num = ?";
 
@@ -173,10 +302,11 @@ impl Store {
             .db
             .prepare(QUERY)?
             .query_row(params![block_num], |row| {
-                let peaks: String = row.get(0)?;
+                let peaks: Option<String> = row.get(0)?;
                 Ok(peaks)
             })
-            .optional()?;
+            .optional()?
+            .flatten();
 
         if let Some(mmr_peaks) = mmr_peaks {
             return parse_mmr_peaks(block_num, mmr_peaks);
@@ -221,6 +351,27 @@ fn serialize_block_header(
     ))
 }
 
+/// Parses the number and the serialized header, if tracked, of a block joined to another table
+pub(super) fn parse_block_time(
+    block_num: Option<u32>,
+    header: Option<String>,
+) -> Result<Option<BlockTime>, StoreError> {
+    let block_num = match block_num {
+        Some(block_num) => block_num,
+        None => return Ok(None),
+    };
+    let timestamp = header
+        .map(|header| serde_json::from_str::<BlockHeader>(&header))
+        .transpose()
+        .map_err(StoreError::JsonDataDeserializationError)?
+        .map(|header| header.timestamp().as_int());
+
+    Ok(Some(BlockTime {
+        block_num,
+        timestamp,
+    }))
+}
+
 fn parse_block_headers_columns(
     row: &rusqlite::Row<'_>,
 ) -> Result<SerializedBlockHeaderParts, rusqlite::Error> {
@@ -228,7 +379,7 @@ fn parse_block_headers_columns(
     let header: String = row.get(1)?;
     let notes_root: String = row.get(2)?;
     let sub_hash: String = row.get(3)?;
-    let chain_mmr: String = row.get(4)?;
+    let chain_mmr: Option<String> = row.get(4)?;
     let has_client_notes: bool = row.get(5)?;
 
     Ok((
@@ -282,10 +433,14 @@ fn parse_chain_mmr_nodes(
 
 #[cfg(test)]
 mod test {
+    use std::num::NonZeroUsize;
+
     use crate::store::{tests::create_test_store, Store};
-    use crypto::merkle::MmrPeaks;
+    use crypto::merkle::{InOrderIndex, MmrPeaks};
     use mock::mock::block::mock_block_header;
-    use objects::BlockHeader;
+    use objects::{BlockHeader, Digest, Felt};
+
+    use super::ChainMmrNodeFilter;
 
     fn insert_dummy_block_headers(store: &mut Store) -> Vec<BlockHeader> {
         let block_headers: Vec<BlockHeader> = (0..5)
@@ -327,4 +482,141 @@ mod test {
             &block_headers[..]
         );
     }
+
+    #[test]
+    fn insert_block_headers_over_multiple_statements() {
+        let mut store = create_test_store();
+        let dummy_peaks = MmrPeaks::new(0, Vec::new()).unwrap();
+        let block_headers: Vec<(BlockHeader, MmrPeaks, bool)> = (0..400)
+            .map(|block_num| {
+                let block_header = mock_block_header(block_num, None, None, &[]);
+                (block_header, dummy_peaks.clone(), block_num % 2 == 0)
+            })
+            .collect();
+
+        let tx = store.db.transaction().unwrap();
+        Store::insert_block_headers(&tx, &block_headers).unwrap();
+        tx.commit().unwrap();
+
+        let stored = store.get_block_headers(&[0, 1, 399]).unwrap();
+        assert_eq!(stored.len(), 3);
+        for (block_header, has_client_notes) in stored {
+            let block_num = block_header.block_num() as usize;
+            assert_eq!(block_header, block_headers[block_num].0);
+            assert_eq!(has_client_notes, block_headers[block_num].2);
+        }
+    }
+
+    #[test]
+    fn block_headers_and_nodes_can_be_inserted_again() {
+        let mut store = create_test_store();
+        let block_headers = insert_dummy_block_headers(&mut store);
+        let nodes = vec![
+            (InOrderIndex::from_leaf_pos(0), block_headers[0].hash()),
+            (InOrderIndex::from_leaf_pos(1), block_headers[1].hash()),
+        ];
+        let peaks = MmrPeaks::new(1, vec![block_headers[0].hash()]).unwrap();
+
+        // applying the same sync twice, as after a crash, keeps the relevant blocks flagged
+        let tx = store.db.transaction().unwrap();
+        Store::insert_block_header(&tx, block_headers[3], peaks.clone(), true).unwrap();
+        Store::insert_chain_mmr_nodes(&tx, &nodes).unwrap();
+        tx.commit().unwrap();
+        let tx = store.db.transaction().unwrap();
+        Store::insert_block_header(&tx, block_headers[3], peaks.clone(), false).unwrap();
+        Store::insert_chain_mmr_nodes(&tx, &nodes[1..]).unwrap();
+        tx.commit().unwrap();
+
+        assert_eq!(
+            store.get_block_header_by_num(3).unwrap(),
+            (block_headers[3], true)
+        );
+        assert_eq!(
+            store.get_chain_mmr_peaks_by_block_num(3).unwrap().peaks(),
+            peaks.peaks()
+        );
+        assert_eq!(
+            store
+                .get_chain_mmr_nodes(ChainMmrNodeFilter::All)
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            nodes
+        );
+    }
+
+    #[test]
+    fn insert_authenticated_block_headers() {
+        let mut store = create_test_store();
+        let block_headers = insert_dummy_block_headers(&mut store);
+
+        let leaf_index = InOrderIndex::from_leaf_pos(2);
+        let nodes = vec![
+            (leaf_index, block_headers[2].hash()),
+            (leaf_index.sibling(), Digest::default()),
+        ];
+
+        // already stored headers are flagged and only the missing nodes are inserted
+        store
+            .insert_authenticated_block_header(block_headers[2], &nodes[..1])
+            .unwrap();
+        store
+            .insert_authenticated_block_header(block_headers[2], &nodes)
+            .unwrap();
+        assert_eq!(
+            store.get_block_header_by_num(2).unwrap(),
+            (block_headers[2], true)
+        );
+        assert_eq!(
+            store
+                .get_chain_mmr_nodes(ChainMmrNodeFilter::All)
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            nodes
+        );
+
+        // new headers are stored without peaks, so they are not candidates to roll back to
+        let old_block_header = mock_block_header(7, None, None, &[]);
+        store
+            .insert_authenticated_block_header(old_block_header, &[])
+            .unwrap();
+        assert_eq!(
+            store.get_block_header_by_num(7).unwrap(),
+            (old_block_header, true)
+        );
+        assert!(store
+            .get_chain_mmr_peaks_by_block_num(7)
+            .unwrap()
+            .peaks()
+            .is_empty());
+        assert_eq!(
+            store.get_block_numbers_below(8).unwrap(),
+            vec![4, 3, 2, 1, 0]
+        );
+    }
+
+    #[test]
+    fn insert_chain_mmr_nodes_over_multiple_statements() {
+        let mut store = create_test_store();
+        let nodes: Vec<(InOrderIndex, Digest)> = (1..=1200u64)
+            .map(|index| {
+                let node = Digest::from([Felt::new(index); 4]);
+                (
+                    InOrderIndex::new(NonZeroUsize::new(index as usize).unwrap()),
+                    node,
+                )
+            })
+            .collect();
+
+        let tx = store.db.transaction().unwrap();
+        Store::insert_chain_mmr_nodes(&tx, &nodes).unwrap();
+        tx.commit().unwrap();
+
+        let stored = store.get_chain_mmr_nodes(ChainMmrNodeFilter::All).unwrap();
+        assert_eq!(stored.len(), nodes.len());
+        for (index, node) in nodes {
+            assert_eq!(stored.get(&index), Some(&node));
+        }
+    }
 }
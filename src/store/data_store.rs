@@ -1,12 +1,15 @@
+use std::sync::{Arc, Mutex};
+
 use crate::errors::{ClientError, StoreError};
-use objects::utils::collections::BTreeSet;
+use objects::utils::collections::{BTreeMap, BTreeSet};
 
 use super::{chain_data::ChainMmrNodeFilter, Store};
 use crypto::merkle::{InOrderIndex, MerklePath, PartialMmr};
 use miden_tx::{DataStore, DataStoreError, TransactionInputs};
+use objects::Word;
 
 use objects::{
-    accounts::AccountId,
+    accounts::{Account, AccountId},
     assembly::ModuleAst,
     transaction::{ChainMmr, InputNote, InputNotes},
     BlockHeader,
@@ -15,14 +18,27 @@ use objects::{
 // DATA STORE
 // ================================================================================================
 
+/// States of accounts, along with their seeds, that transactions are executed against instead
+/// of the latest ones in the store. They are shared between a client and its [SqliteDataStore].
+pub(crate) type AccountStateOverrides = Arc<Mutex<BTreeMap<AccountId, (Account, Word)>>>;
+
 pub struct SqliteDataStore {
     /// Local database containing information about the accounts managed by this client.
     pub(crate) store: Store,
+    /// States of the accounts being re-executed against an earlier state
+    account_states: AccountStateOverrides,
 }
 
 impl SqliteDataStore {
     pub fn new(store: Store) -> Self {
-        Self { store }
+        Self::with_account_states(store, Default::default())
+    }
+
+    pub(crate) fn with_account_states(store: Store, account_states: AccountStateOverrides) -> Self {
+        Self {
+            store,
+            account_states,
+        }
     }
 }
 
@@ -34,7 +50,16 @@ impl DataStore for SqliteDataStore {
         notes: &[objects::notes::NoteId],
     ) -> Result<TransactionInputs, DataStoreError> {
         // Construct Account
-        let (account, seed) = self.store.get_account_by_id(account_id)?;
+        let account_state = self
+            .account_states
+            .lock()
+            .expect("account states lock is not poisoned")
+            .get(&account_id)
+            .cloned();
+        let (account, seed) = match account_state {
+            Some(account_state) => account_state,
+            None => self.store.get_account_by_id(account_id)?,
+        };
 
         // Get header data
         let (block_header, _had_notes) = self.store.get_block_header_by_num(block_num)?;
@@ -164,7 +189,7 @@ pub fn get_authentication_path_for_blocks(
 /// Calculates the merkle path length for an MMR of a specific forest and a leaf index
 /// `leaf_index` is a 0-indexed leaf number and `forest` is the total amount of leaves
 /// in the MMR at this point.
-fn mmr_merkle_path_len(leaf_index: usize, forest: usize) -> usize {
+pub(crate) fn mmr_merkle_path_len(leaf_index: usize, forest: usize) -> usize {
     let before = forest & leaf_index;
     let after = forest ^ before;
 
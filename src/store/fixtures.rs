@@ -0,0 +1,500 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crypto::{dsa::rpo_falcon512::KeyPair, Felt, FieldElement};
+use miden_lib::transaction::TransactionKernel;
+use mock::{
+    constants::{generate_account_seed, AccountSeedType},
+    mock::{
+        account::{mock_account, MockAccountType},
+        notes::{mock_notes, AssetPreservationStatus},
+        transaction::mock_inputs,
+    },
+};
+use objects::{
+    transaction::{OutputNote, OutputNotes},
+    Digest,
+};
+use rusqlite::{params_from_iter, types::Value};
+use serde::{Deserialize, Serialize};
+
+use super::{accounts::AuthInfo, notes::InputNoteRecord, notes::NoteOrigin, Store};
+use crate::{
+    client::transactions::{TransactionRecord, TransactionStatus},
+    errors::StoreError,
+};
+
+/// Command writing the golden files from the current snapshots, which is never run by a plain
+/// `cargo test`
+const UPDATE_GOLDEN_FILES_COMMAND: &str = "cargo test write_golden_files -- --ignored";
+
+/// Tables holding the serialized accounts, notes and transactions, in an order they can be loaded
+/// in without breaking their foreign keys
+pub const SERIALIZED_DATA_TABLES: [&str; 11] = [
+    "account_code",
+    "account_storage",
+    "account_vaults",
+    "accounts",
+    "note_scripts",
+    "input_notes",
+    "input_note_assets",
+    "input_note_targets",
+    "note_inclusion_paths",
+    "transaction_scripts",
+    "transactions",
+];
+
+/// Columns holding the time rows were written at, which differ between runs
+const TIMESTAMP_COLUMNS: [&str; 2] = ["created_at", "committed_at"];
+
+// STORE FIXTURE
+// ================================================================================================
+
+/// A value of a column of a [StoreFixture], with blobs hex-encoded so that fixtures can be kept
+/// as readable JSON files
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixtureValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(String),
+}
+
+impl From<Value> for FixtureValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => FixtureValue::Null,
+            Value::Integer(value) => FixtureValue::Integer(value),
+            Value::Real(value) => FixtureValue::Real(value),
+            Value::Text(value) => FixtureValue::Text(value),
+            Value::Blob(value) => {
+                FixtureValue::Blob(value.iter().map(|b| format!("{b:02x}")).collect())
+            }
+        }
+    }
+}
+
+impl TryFrom<&FixtureValue> for Value {
+    type Error = StoreError;
+
+    fn try_from(value: &FixtureValue) -> Result<Self, Self::Error> {
+        Ok(match value {
+            FixtureValue::Null => Value::Null,
+            FixtureValue::Integer(value) => Value::Integer(*value),
+            FixtureValue::Real(value) => Value::Real(*value),
+            FixtureValue::Text(value) => Value::Text(value.clone()),
+            FixtureValue::Blob(value) => Value::Blob(parse_hex(value)?),
+        })
+    }
+}
+
+fn parse_hex(value: &str) -> Result<Vec<u8>, StoreError> {
+    if value.len() % 2 != 0 {
+        return Err(StoreError::ParsingError(format!(
+            "hex-encoded blob `{value}` has an odd length"
+        )));
+    }
+
+    (0..value.len())
+        .step_by(2)
+        .map(|i| {
+            value
+                .get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| {
+                    StoreError::ParsingError(format!("`{value}` is not a hex-encoded blob"))
+                })
+        })
+        .collect()
+}
+
+/// The rows of a table, in the order they were inserted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableSnapshot {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<FixtureValue>>,
+}
+
+/// Canonical snapshot of some of the tables of a store, used as golden data for tests of the
+/// store's serialization formats and to fill stores with realistic data, for instance to test
+/// migrations.
+///
+/// Snapshots are taken with [Store::dump_fixture] and loaded with [Store::load_fixture].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StoreFixture {
+    pub tables: BTreeMap<String, TableSnapshot>,
+}
+
+impl StoreFixture {
+    /// Replaces the values of the specified column with nulls in every table, for columns such
+    /// as timestamps that are not expected to match between runs
+    pub fn redact(&mut self, column: &str) {
+        for table in self.tables.values_mut() {
+            if let Some(index) = table.columns.iter().position(|name| name == column) {
+                for row in table.rows.iter_mut() {
+                    row[index] = FixtureValue::Null;
+                }
+            }
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, StoreError> {
+        serde_json::to_string_pretty(self).map_err(StoreError::InputSerializationError)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, StoreError> {
+        serde_json::from_str(json).map_err(StoreError::JsonDataDeserializationError)
+    }
+}
+
+// FIXTURE STORE METHODS
+// ================================================================================================
+
+impl Store {
+    /// Takes a snapshot of the specified tables, with their rows in insertion order
+    ///
+    /// # Errors
+    /// Returns an error if one of the tables does not exist.
+    pub fn dump_fixture(&self, tables: &[&str]) -> Result<StoreFixture, StoreError> {
+        let mut fixture = StoreFixture::default();
+        for &table in tables {
+            self.check_table_exists(table)?;
+
+            let mut statement = self
+                .db
+                .prepare(&format!("SELECT * FROM {table} ORDER BY rowid"))?;
+            let columns: Vec<String> = statement
+                .column_names()
+                .into_iter()
+                .map(String::from)
+                .collect();
+            let rows = statement
+                .query_map([], |row| {
+                    (0..columns.len())
+                        .map(|index| row.get::<_, Value>(index).map(FixtureValue::from))
+                        .collect::<Result<Vec<_>, _>>()
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            fixture
+                .tables
+                .insert(table.to_string(), TableSnapshot { columns, rows });
+        }
+
+        Ok(fixture)
+    }
+
+    /// Inserts the rows of the fixture into the store, in a single transaction. Tables are
+    /// loaded in the order of [SERIALIZED_DATA_TABLES], followed by any other table in
+    /// alphabetical order.
+    ///
+    /// # Errors
+    /// Returns an error if the fixture does not match the schema of the store, or if one of its
+    /// rows is already stored.
+    pub fn load_fixture(&mut self, fixture: &StoreFixture) -> Result<(), StoreError> {
+        let mut tables: Vec<&String> = SERIALIZED_DATA_TABLES
+            .iter()
+            .filter_map(|table| fixture.tables.get_key_value(*table).map(|(name, _)| name))
+            .collect();
+        tables.extend(
+            fixture
+                .tables
+                .keys()
+                .filter(|table| !SERIALIZED_DATA_TABLES.contains(&table.as_str())),
+        );
+
+        for table in tables.iter() {
+            self.check_table_exists(table)?;
+        }
+
+        let tx = self.write_transaction()?;
+        for table in tables {
+            let snapshot = &fixture.tables[table];
+            let query = format!(
+                "INSERT INTO {table} ({}) VALUES ({})",
+                snapshot.columns.join(", "),
+                vec!["?"; snapshot.columns.len()].join(", ")
+            );
+            let mut statement = tx.prepare(&query)?;
+            for row in snapshot.rows.iter() {
+                let values = row
+                    .iter()
+                    .map(Value::try_from)
+                    .collect::<Result<Vec<_>, _>>()?;
+                statement.execute(params_from_iter(values))?;
+            }
+        }
+
+        Ok(tx.commit()?)
+    }
+
+    /// Ensures the table exists, as table names cannot be bound as query parameters
+    fn check_table_exists(&self, table: &str) -> Result<(), StoreError> {
+        const QUERY: &str = "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?";
+
+        let count: usize = self.db.query_row(QUERY, [table], |row| row.get(0))?;
+        if count == 0 {
+            return Err(StoreError::QueryError(format!(
+                "table `{table}` does not exist"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+// CANONICAL DATA
+// ================================================================================================
+
+/// Input notes built from the notes of the mock crate, which do not change between runs: the
+/// committed notes of a mock transaction, along with the notes it creates, which are not committed
+pub fn canonical_notes() -> Vec<InputNoteRecord> {
+    let transaction_inputs = mock_inputs(
+        MockAccountType::StandardExisting,
+        AssetPreservationStatus::Preserved,
+    );
+    let (_, created_notes) = mock_notes(
+        &TransactionKernel::assembler(),
+        &AssetPreservationStatus::Preserved,
+    );
+
+    transaction_inputs
+        .input_notes()
+        .clone()
+        .into_iter()
+        .map(InputNoteRecord::from)
+        .chain(created_notes.into_iter().map(InputNoteRecord::from))
+        .collect()
+}
+
+/// Transactions of a mock account creating the notes of [canonical_notes] that are not committed,
+/// one of them committed and the other one only submitted
+pub fn canonical_transactions() -> Vec<TransactionRecord> {
+    let (account_id, _) =
+        generate_account_seed(AccountSeedType::RegularAccountUpdatableCodeOnChain);
+    let (consumed_notes, created_notes) = mock_notes(
+        &TransactionKernel::assembler(),
+        &AssetPreservationStatus::Preserved,
+    );
+    let output_notes = OutputNotes::new(created_notes.into_iter().map(OutputNote::from).collect())
+        .expect("mock notes are valid output notes");
+    let state = |value: u64| Digest::new([Felt::new(value); 4]);
+
+    vec![
+        TransactionRecord::new(
+            state(1),
+            account_id,
+            state(10),
+            state(11),
+            consumed_notes
+                .iter()
+                .map(|note| note.nullifier().inner())
+                .collect(),
+            output_notes,
+            None,
+            3,
+            TransactionStatus::Committed(5),
+        ),
+        TransactionRecord::new(
+            state(2),
+            account_id,
+            state(11),
+            state(12),
+            Vec::new(),
+            OutputNotes::new(Vec::new()).expect("empty output notes are valid"),
+            None,
+            6,
+            TransactionStatus::Submitted,
+        ),
+    ]
+}
+
+/// Fills the store with [canonical_notes], [canonical_transactions] and a mock account. The key
+/// pair of the account is generated anew, so the `account_auth` table differs between runs.
+pub fn insert_canonical_data(store: &mut Store) -> Result<(), StoreError> {
+    let (account_id, account_seed) =
+        generate_account_seed(AccountSeedType::RegularAccountUpdatableCodeOnChain);
+    let account = mock_account(
+        Some(account_id.into()),
+        Felt::ONE,
+        None,
+        &TransactionKernel::assembler(),
+    );
+    let key_pair = KeyPair::new().map_err(|err| StoreError::ParsingError(err.to_string()))?;
+    store.insert_account(&account, account_seed, &AuthInfo::RpoFalcon512(key_pair))?;
+
+    for note in canonical_notes() {
+        store.insert_input_note(&note, &NoteOrigin::Imported)?;
+    }
+    for transaction in canonical_transactions() {
+        store.insert_transaction_record(&transaction)?;
+    }
+
+    Ok(())
+}
+
+// GOLDEN FILES
+// ================================================================================================
+
+/// Returns the path of the golden file with the specified name
+pub fn golden_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/store/fixtures")
+        .join(format!("{name}.json"))
+}
+
+/// Dumps the serialized data tables of the store, without the columns that differ between runs
+pub fn dump_serialized_data(store: &Store) -> Result<StoreFixture, StoreError> {
+    let mut fixture = store.dump_fixture(&SERIALIZED_DATA_TABLES)?;
+    for column in TIMESTAMP_COLUMNS {
+        fixture.redact(column);
+    }
+
+    Ok(fixture)
+}
+
+/// Writes the fixture to the golden file with the specified name, which should only be done to
+/// add a golden file, or along with a migration when a serialization format is changed on purpose.
+///
+/// # Panics
+/// Panics if the golden file cannot be written.
+pub fn write_golden(name: &str, fixture: &StoreFixture) {
+    let path = golden_path(name);
+    let json = fixture.to_json().expect("fixtures can be serialized");
+
+    fs::create_dir_all(path.parent().expect("golden files are in a directory"))
+        .expect("golden file directory can be created");
+    // the file is moved in place once written, so that tests reading it never see it partially
+    // written
+    let temp_path = path.with_extension(format!("json.{}", std::process::id()));
+    fs::write(&temp_path, json + "\n").expect("golden file can be written");
+    fs::rename(&temp_path, &path).expect("golden file can be written");
+}
+
+/// Compares the fixture against the golden file with the specified name.
+///
+/// # Panics
+/// Panics if the fixture differs from the golden file, if the golden file is missing, or if it
+/// cannot be read.
+pub fn assert_golden(name: &str, fixture: &StoreFixture) {
+    let path = golden_path(name);
+    let golden = read_golden(name);
+    for (table, snapshot) in fixture.tables.iter() {
+        assert_eq!(
+            golden.tables.get(table),
+            Some(snapshot),
+            "serialized `{table}` rows differ from {}; if the format was changed on purpose, add a \
+            migration and update the golden files with `{UPDATE_GOLDEN_FILES_COMMAND}`",
+            path.display()
+        );
+    }
+    assert_eq!(
+        golden.tables.len(),
+        fixture.tables.len(),
+        "{} holds other tables than the fixture",
+        path.display()
+    );
+}
+
+/// Reads the golden file with the specified name.
+///
+/// # Panics
+/// Panics if the golden file is missing or is not a valid fixture.
+pub fn read_golden(name: &str) -> StoreFixture {
+    let path = golden_path(name);
+    let golden = fs::read_to_string(&path).unwrap_or_else(|err| {
+        panic!(
+            "golden file {} cannot be read ({err}); write it with `{UPDATE_GOLDEN_FILES_COMMAND}`",
+            path.display()
+        )
+    });
+    StoreFixture::from_json(&golden).expect("golden file is a valid fixture")
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        assert_golden, canonical_notes, canonical_transactions, dump_serialized_data,
+        insert_canonical_data, read_golden, write_golden, FixtureValue, StoreFixture,
+    };
+    use crate::store::{
+        notes::NoteFilter, tests::create_test_store, transactions::TransactionFilter,
+    };
+
+    /// Updates the golden files, see [write_golden]
+    #[test]
+    #[ignore = "writes the golden files instead of checking them"]
+    fn write_golden_files() {
+        let mut store = create_test_store();
+        insert_canonical_data(&mut store).unwrap();
+
+        write_golden("serialized_data", &dump_serialized_data(&store).unwrap());
+    }
+
+    #[test]
+    fn serialized_data_matches_the_golden_file() {
+        let mut store = create_test_store();
+        insert_canonical_data(&mut store).unwrap();
+
+        assert_golden("serialized_data", &dump_serialized_data(&store).unwrap());
+    }
+
+    #[test]
+    fn golden_data_is_parsed_back() {
+        let fixture = read_golden("serialized_data");
+        let mut store = create_test_store();
+        store.load_fixture(&fixture).unwrap();
+
+        let mut notes = store.get_input_notes(NoteFilter::new()).unwrap();
+        let mut expected_notes = canonical_notes();
+        notes.sort_by_key(|note| note.note_id().inner().to_string());
+        expected_notes.sort_by_key(|note| note.note_id().inner().to_string());
+        assert_eq!(notes, expected_notes);
+
+        let transactions = store.get_transactions(TransactionFilter::All).unwrap();
+        let expected_transactions = canonical_transactions();
+        assert_eq!(transactions.len(), expected_transactions.len());
+        for (transaction, expected) in transactions.iter().zip(expected_transactions.iter()) {
+            assert_eq!(transaction.id, expected.id);
+            assert_eq!(transaction.account_id, expected.account_id);
+            assert_eq!(
+                transaction.final_account_state,
+                expected.final_account_state
+            );
+            assert_eq!(
+                transaction.input_note_nullifiers,
+                expected.input_note_nullifiers
+            );
+            assert_eq!(transaction.output_notes, expected.output_notes);
+            assert_eq!(transaction.transaction_status, expected.transaction_status);
+        }
+    }
+
+    #[test]
+    fn fixtures_round_trip_through_json() {
+        let mut store = create_test_store();
+        insert_canonical_data(&mut store).unwrap();
+        let fixture = dump_serialized_data(&store).unwrap();
+
+        let json = fixture.to_json().unwrap();
+        assert_eq!(StoreFixture::from_json(&json).unwrap(), fixture);
+
+        let notes = &fixture.tables["input_notes"];
+        let created_at = notes
+            .columns
+            .iter()
+            .position(|column| column == "created_at");
+        assert!(notes
+            .rows
+            .iter()
+            .all(|row| row[created_at.unwrap()] == FixtureValue::Null));
+    }
+}
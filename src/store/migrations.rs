@@ -6,14 +6,85 @@ use rusqlite_migration::{Migrations, M};
 // MIGRATIONS
 // ================================================================================================
 
+/// Builds the migration named `$name` from `migrations/$name.sql`, reverted by
+/// `migrations/$name.down.sql`.
+macro_rules! migration {
+    ($name:literal) => {
+        M::up(include_str!(concat!("migrations/", $name, ".sql"))).down(include_str!(concat!(
+            "migrations/",
+            $name,
+            ".down.sql"
+        )))
+    };
+}
+
 lazy_static! {
-    static ref MIGRATIONS: Migrations<'static> =
-        Migrations::new(vec![M::up(include_str!("store.sql")),]);
+    static ref MIGRATIONS: Migrations<'static> = Migrations::new(vec![
+        migration!("0001_initial_schema"),
+        migration!("0002_note_inclusion_paths"),
+        migration!("0003_faucet_details"),
+        migration!("0004_sync_quarantine"),
+        migration!("0005_transaction_lifecycle"),
+        migration!("0006_note_filter_indices"),
+        migration!("0007_note_consumed_height"),
+        migration!("0008_account_sync_heights"),
+        migration!("0009_note_tag_sources"),
+        migration!("0010_account_note_tags"),
+        migration!("0011_partial_output_notes"),
+        migration!("0012_note_scripts"),
+        migration!("0013_transaction_script_references"),
+        migration!("0014_account_deltas"),
+        migration!("0015_account_state_blocks"),
+        migration!("0016_address_book"),
+        migration!("0017_block_header_unknown_peaks"),
+        migration!("0018_account_code_upgrades"),
+        migration!("0019_note_locks"),
+        migration!("0020_account_proofs"),
+        migration!("0021_nullifier_check_heights"),
+        migration!("0022_expected_notes"),
+        migration!("0023_expected_note_ids"),
+        migration!("0024_note_memos"),
+        migration!("0025_note_provenance"),
+        migration!("0026_account_labels"),
+        migration!("0027_desynchronized_accounts"),
+        migration!("0028_note_unlock_heights"),
+        migration!("0029_transaction_outbox"),
+        migration!("0030_sync_target"),
+        migration!("0031_watched_nullifiers"),
+        migration!("0032_hidden_accounts"),
+        migration!("0033_orders"),
+        migration!("0034_transaction_fees"),
+    ]);
 }
 
+/// Schema version of a store with every migration applied.
+pub(crate) const LATEST_VERSION: usize = 34;
+
+/// Schema version introducing the `note_inclusion_paths` table, which the inclusion proofs stored
+/// in full by earlier versions are moved to when migrating.
+pub(crate) const NOTE_INCLUSION_PATHS_VERSION: usize = 2;
+
+/// Schema version introducing the tables notes are filtered with, which the notes stored by
+/// earlier versions are indexed in when migrating.
+pub(crate) const NOTE_FILTER_INDICES_VERSION: usize = 6;
+
+/// Schema version introducing the `note_scripts` table, which the scripts of the notes stored by
+/// earlier versions are moved to when migrating.
+pub(crate) const NOTE_SCRIPTS_VERSION: usize = 12;
+
 // PUBLIC FUNCTIONS
 // ================================================================================================
 
 pub(crate) fn update_to_latest(conn: &mut Connection) -> Result<(), StoreError> {
     Ok(MIGRATIONS.to_latest(conn)?)
 }
+
+/// Applies or reverts migrations until the database is at schema `version`.
+pub(crate) fn update_to_version(conn: &mut Connection, version: usize) -> Result<(), StoreError> {
+    Ok(MIGRATIONS.to_version(conn, version)?)
+}
+
+/// Returns the schema version of the database, which is the number of migrations applied to it.
+pub(crate) fn current_version(conn: &Connection) -> Result<usize, StoreError> {
+    Ok((&MIGRATIONS.current_version(conn)?).into())
+}
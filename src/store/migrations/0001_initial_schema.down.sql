@@ -0,0 +1,12 @@
+-- Drop every table of the initial schema
+DROP TABLE chain_mmr_nodes;
+DROP TABLE block_headers;
+DROP TABLE state_sync;
+DROP TABLE input_notes;
+DROP TABLE transactions;
+DROP TABLE transaction_scripts;
+DROP TABLE accounts;
+DROP TABLE account_auth;
+DROP TABLE account_vaults;
+DROP TABLE account_storage;
+DROP TABLE account_code;
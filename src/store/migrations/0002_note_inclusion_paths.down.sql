@@ -0,0 +1,5 @@
+-- Drop note inclusion paths table
+--
+-- Inclusion proofs normalized into this table cannot be rebuilt in SQL, so they are dropped along
+-- with it and the affected notes have to be imported again to be consumed.
+DROP TABLE note_inclusion_paths;
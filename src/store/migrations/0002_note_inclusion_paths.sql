@@ -0,0 +1,15 @@
+-- Create note inclusion paths table
+--
+-- Inclusion proofs are normalized against the `block_headers` table: only the note's position and
+-- its path in the block's note tree are stored here, while the block's sub hash and note root are
+-- read from the corresponding block header. The block's authentication path in the chain MMR is
+-- built on demand from `chain_mmr_nodes`, which sync updates incrementally as the MMR grows.
+CREATE TABLE note_inclusion_paths (
+    note_id BLOB NOT NULL,                  -- the note id
+    block_num UNSIGNED BIG INT NOT NULL,    -- the block number at which the note was included into the chain
+    note_index UNSIGNED BIG INT NOT NULL,   -- the index of the note in the block's note tree
+    note_path BLOB NOT NULL,                -- the serialized merkle path of the note in the block's note tree
+    PRIMARY KEY (note_id),
+    FOREIGN KEY (note_id) REFERENCES input_notes(note_id),
+    FOREIGN KEY (block_num) REFERENCES block_headers(block_num)
+);
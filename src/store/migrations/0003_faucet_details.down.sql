@@ -0,0 +1,2 @@
+-- Drop faucet details table
+DROP TABLE faucet_details;
@@ -0,0 +1,10 @@
+-- Create faucet details table
+--
+-- Stores the token symbol and decimals of fungible faucets so that amounts of their assets can be
+-- shown and entered in whole tokens instead of base units.
+CREATE TABLE faucet_details (
+    faucet_id UNSIGNED BIG INT NOT NULL,    -- the faucet's account id
+    token_symbol TEXT NOT NULL,             -- the symbol of the tokens issued by the faucet
+    decimals UNSIGNED INT NOT NULL,         -- the number of decimals of the tokens issued by the faucet
+    PRIMARY KEY (faucet_id)
+);
@@ -0,0 +1,2 @@
+-- Drop sync quarantine table
+DROP TABLE sync_quarantine;
@@ -0,0 +1,14 @@
+-- Create sync quarantine table
+--
+-- Notes whose inclusion proof could not be built or verified while syncing are kept here instead
+-- of failing the whole sync, so that they can be inspected and retried later on.
+CREATE TABLE sync_quarantine (
+    note_id BLOB NOT NULL,                  -- the note id
+    block_num UNSIGNED BIG INT NOT NULL,    -- the block number at which the note was reported as included
+    note_index UNSIGNED BIG INT NOT NULL,   -- the index of the note in the block's note tree
+    note_path BLOB NOT NULL,                -- the serialized merkle path of the note in the block's note tree
+    reason TEXT NOT NULL,                   -- the error that caused the note to be quarantined
+    PRIMARY KEY (note_id),
+    FOREIGN KEY (note_id) REFERENCES input_notes(note_id),
+    FOREIGN KEY (block_num) REFERENCES block_headers(block_num)
+);
@@ -0,0 +1,9 @@
+-- Stop tracking the lifecycle of transactions
+--
+-- Earlier versions only record submitted transactions, so the ones that were not submitted yet are
+-- dropped.
+DELETE FROM transactions WHERE stage != 'submitted';
+
+ALTER TABLE transactions DROP COLUMN proven_transaction;
+ALTER TABLE transactions DROP COLUMN transaction_result;
+ALTER TABLE transactions DROP COLUMN stage;
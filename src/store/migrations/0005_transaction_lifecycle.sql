@@ -0,0 +1,9 @@
+-- Track the lifecycle of transactions
+--
+-- Transactions are now recorded as soon as they are executed and move through the `executed`,
+-- `proven` and `submitted` stages, with the artifacts needed to resume the next stage stored
+-- alongside them until the transaction is submitted. A transaction is committed once its
+-- `commit_height` is set. Transactions recorded before this migration were already submitted.
+ALTER TABLE transactions ADD COLUMN stage TEXT CHECK (stage IN ('executed', 'proven', 'submitted')) NOT NULL DEFAULT 'submitted';
+ALTER TABLE transactions ADD COLUMN transaction_result BLOB NULL;      -- serialized executed transaction and created notes, cleared once submitted
+ALTER TABLE transactions ADD COLUMN proven_transaction BLOB NULL;      -- serialized proven transaction, cleared once submitted
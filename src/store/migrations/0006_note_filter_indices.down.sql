@@ -0,0 +1,8 @@
+-- Drop the input note filter indices and tables
+DROP TABLE input_note_targets;
+DROP TABLE input_note_assets;
+
+DROP INDEX idx_input_notes_commit_height;
+DROP INDEX idx_input_notes_tag;
+DROP INDEX idx_input_notes_sender_id;
+DROP INDEX idx_input_notes_status;
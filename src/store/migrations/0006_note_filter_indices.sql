@@ -0,0 +1,29 @@
+-- Index input notes for filtered queries
+--
+-- Notes can be filtered by their metadata and by the assets they carry. Assets and note inputs are
+-- stored serialized, so the data needed to filter on them is extracted into the tables below when
+-- a note is inserted.
+CREATE INDEX idx_input_notes_status ON input_notes(status);
+CREATE INDEX idx_input_notes_sender_id ON input_notes(sender_id);
+CREATE INDEX idx_input_notes_tag ON input_notes(tag);
+CREATE INDEX idx_input_notes_commit_height ON input_notes(commit_height);
+
+-- Create input note assets table
+CREATE TABLE input_note_assets (
+    note_id BLOB NOT NULL,                  -- the note id
+    faucet_id UNSIGNED BIG INT NOT NULL,    -- the id of the faucet that issued one of the note's assets
+    PRIMARY KEY (note_id, faucet_id),
+    FOREIGN KEY (note_id) REFERENCES input_notes(note_id)
+);
+
+CREATE INDEX idx_input_note_assets_faucet_id ON input_note_assets(faucet_id);
+
+-- Create input note targets table
+CREATE TABLE input_note_targets (
+    note_id BLOB NOT NULL,                  -- the note id
+    account_id UNSIGNED BIG INT NULL,       -- the account the note's inputs are addressed to, if any
+    PRIMARY KEY (note_id),
+    FOREIGN KEY (note_id) REFERENCES input_notes(note_id)
+);
+
+CREATE INDEX idx_input_note_targets_account_id ON input_note_targets(account_id);
@@ -0,0 +1,2 @@
+-- Stop tracking the block at which input notes were seen consumed
+ALTER TABLE input_notes DROP COLUMN consumed_height;
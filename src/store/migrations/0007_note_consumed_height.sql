@@ -0,0 +1,5 @@
+-- Track the block at which input notes were seen consumed
+--
+-- Consumed notes are reverted to committed when the chain is reorganized past the block at which
+-- they were marked as consumed.
+ALTER TABLE input_notes ADD COLUMN consumed_height UNSIGNED BIG INT NULL;
@@ -0,0 +1,2 @@
+-- Drop account sync heights table
+DROP TABLE account_sync_heights;
@@ -0,0 +1,9 @@
+-- Create account sync heights table
+--
+-- Accounts can be synced selectively, so each of them tracks the block it was last synced to in
+-- addition to the client-wide sync height.
+CREATE TABLE account_sync_heights (
+    account_id UNSIGNED BIG INT NOT NULL,   -- the account id
+    block_num UNSIGNED BIG INT NOT NULL,    -- the block number the account was last synced to
+    PRIMARY KEY (account_id)
+);
@@ -0,0 +1,9 @@
+-- Move the tags registered by the user back to the list serialized in the state_sync table. Tags
+-- registered for notes are dropped, as earlier versions do not track them
+ALTER TABLE state_sync ADD COLUMN tags BLOB NOT NULL DEFAULT '[]';
+
+UPDATE state_sync SET tags = (
+    SELECT json_group_array(tag) FROM (SELECT tag FROM note_tags WHERE source = 'user' ORDER BY rowid)
+);
+
+DROP TABLE note_tags;
@@ -0,0 +1,22 @@
+-- Create note_tags table, replacing the list of tags serialized in the state_sync table
+CREATE TABLE note_tags (
+    tag UNSIGNED BIG INT NOT NULL,  -- the tracked note tag
+    source TEXT NOT NULL,           -- what registered the tag, either 'user' or 'note'
+    source_note_id TEXT NULL,       -- for tags registered for a note, the ID of the note. The tag is dropped once the note is consumed
+    label TEXT NULL,                -- optional description of the tag
+
+    CONSTRAINT check_valid_source CHECK (
+        (source = 'user' AND source_note_id IS NULL) OR
+        (source = 'note' AND source_note_id IS NOT NULL)
+    )
+);
+
+CREATE UNIQUE INDEX idx_note_tags_user ON note_tags(tag) WHERE source = 'user';
+CREATE UNIQUE INDEX idx_note_tags_note ON note_tags(tag, source_note_id) WHERE source = 'note';
+CREATE INDEX idx_note_tags_source_note_id ON note_tags(source_note_id);
+
+-- Tags tracked so far were all added by the user
+INSERT INTO note_tags (tag, source)
+SELECT value, 'user' FROM json_each((SELECT tags FROM state_sync));
+
+ALTER TABLE state_sync DROP COLUMN tags;
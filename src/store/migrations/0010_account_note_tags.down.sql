@@ -0,0 +1,23 @@
+-- Drop the note tags registered for accounts. SQLite cannot alter check constraints, so the
+-- note_tags table is recreated
+CREATE TABLE note_tags_old (
+    tag UNSIGNED BIG INT NOT NULL,  -- the tracked note tag
+    source TEXT NOT NULL,           -- what registered the tag, either 'user' or 'note'
+    source_note_id TEXT NULL,       -- for tags registered for a note, the ID of the note. The tag is dropped once the note is consumed
+    label TEXT NULL,                -- optional description of the tag
+
+    CONSTRAINT check_valid_source CHECK (
+        (source = 'user' AND source_note_id IS NULL) OR
+        (source = 'note' AND source_note_id IS NOT NULL)
+    )
+);
+
+INSERT INTO note_tags_old (tag, source, source_note_id, label)
+SELECT tag, source, source_note_id, label FROM note_tags WHERE source != 'account' ORDER BY rowid;
+
+DROP TABLE note_tags;
+ALTER TABLE note_tags_old RENAME TO note_tags;
+
+CREATE UNIQUE INDEX idx_note_tags_user ON note_tags(tag) WHERE source = 'user';
+CREATE UNIQUE INDEX idx_note_tags_note ON note_tags(tag, source_note_id) WHERE source = 'note';
+CREATE INDEX idx_note_tags_source_note_id ON note_tags(source_note_id);
@@ -0,0 +1,30 @@
+-- Allow note tags to be registered for accounts. SQLite cannot alter check constraints, so the
+-- note_tags table is recreated
+CREATE TABLE note_tags_new (
+    tag UNSIGNED BIG INT NOT NULL,  -- the tracked note tag
+    source TEXT NOT NULL,           -- what registered the tag, one of 'user', 'note' or 'account'
+    source_note_id TEXT NULL,       -- for tags registered for a note, the ID of the note. The tag is dropped once the note is consumed
+    source_account_id UNSIGNED BIG INT NULL, -- for tags registered for an account, the ID of the account the tagged notes target
+    label TEXT NULL,                -- optional description of the tag
+
+    CONSTRAINT check_valid_source CHECK (
+        (source = 'user' AND source_note_id IS NULL AND source_account_id IS NULL) OR
+        (source = 'note' AND source_note_id IS NOT NULL AND source_account_id IS NULL) OR
+        (source = 'account' AND source_note_id IS NULL AND source_account_id IS NOT NULL)
+    )
+);
+
+INSERT INTO note_tags_new (tag, source, source_note_id, label)
+SELECT tag, source, source_note_id, label FROM note_tags ORDER BY rowid;
+
+DROP TABLE note_tags;
+ALTER TABLE note_tags_new RENAME TO note_tags;
+
+CREATE UNIQUE INDEX idx_note_tags_user ON note_tags(tag) WHERE source = 'user';
+CREATE UNIQUE INDEX idx_note_tags_note ON note_tags(tag, source_note_id) WHERE source = 'note';
+CREATE UNIQUE INDEX idx_note_tags_account ON note_tags(tag, source_account_id) WHERE source = 'account';
+CREATE INDEX idx_note_tags_source_note_id ON note_tags(source_note_id);
+
+-- Register the tags of the accounts tracked so far. Notes targeting an account are tagged with its ID
+INSERT INTO note_tags (tag, source, source_account_id)
+SELECT DISTINCT id, 'account', id FROM accounts;
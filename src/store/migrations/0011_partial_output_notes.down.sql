@@ -0,0 +1,2 @@
+-- Drop partial_output_notes table
+DROP TABLE partial_output_notes;
@@ -0,0 +1,11 @@
+-- Create partial_output_notes table, holding the notes created by the client of which only the
+-- recipient digest is known
+CREATE TABLE partial_output_notes (
+    note_id TEXT NOT NULL,                  -- the note id
+    recipient TEXT NOT NULL,                -- the digest of the note's recipient, i.e. of its script, inputs and serial number
+    assets BLOB NOT NULL,                   -- the serialized NoteAssets, including vault hash and list of assets
+    sender_id UNSIGNED BIG INT NOT NULL,    -- the account ID of the sender
+    tag UNSIGNED BIG INT NOT NULL,          -- the note tag
+    transaction_id TEXT NOT NULL,           -- the ID of the transaction that created the note
+    PRIMARY KEY (note_id)
+);
@@ -0,0 +1,37 @@
+-- Store the script of each input note along with it again. SQLite cannot add a NOT NULL
+-- constraint to the script column, so the table is recreated. Scripts registered by name without
+-- a note using them are dropped
+CREATE TABLE input_notes_old (
+    note_id BLOB NOT NULL,                                  -- the note id
+    nullifier BLOB NOT NULL,                                -- the nullifier of the note
+    script BLOB NOT NULL,                                   -- the serialized NoteScript, including script hash and ProgramAst
+    vault BLOB NOT NULL,                                    -- the serialized NoteVault, including vault hash and list of assets
+    inputs BLOB NOT NULL,                                   -- the serialized NoteInputs, including inputs hash and list of inputs
+    serial_num BLOB NOT NULL,                               -- the note serial number
+    sender_id UNSIGNED BIG INT NOT NULL,                    -- the account ID of the sender
+    tag UNSIGNED BIG INT NOT NULL,                          -- the note tag
+    inclusion_proof BLOB NULL,                              -- the inclusion proof of the note against a block number
+    recipients BLOB NOT NULL,                               -- a list of account IDs of accounts which can consume this note
+    status TEXT CHECK( status IN (                          -- the status of the note - either pending, committed or consumed
+        'pending', 'committed', 'consumed'
+        )),
+    commit_height UNSIGNED BIG INT NOT NULL,                -- the block number at which the note was included into the chain
+    consumed_height UNSIGNED BIG INT NULL,                  -- the block number at which the note was seen consumed
+    PRIMARY KEY (note_id)
+);
+
+INSERT INTO input_notes_old
+    (note_id, nullifier, script, vault, inputs, serial_num, sender_id, tag, inclusion_proof, recipients, status, commit_height, consumed_height)
+SELECT note.note_id, note.nullifier, COALESCE(note.script, script.script), note.vault, note.inputs, note.serial_num,
+    note.sender_id, note.tag, note.inclusion_proof, note.recipients, note.status, note.commit_height, note.consumed_height
+FROM input_notes AS note
+LEFT JOIN note_scripts AS script ON note.script_hash = script.script_hash;
+
+DROP TABLE input_notes;
+DROP TABLE note_scripts;
+ALTER TABLE input_notes_old RENAME TO input_notes;
+
+CREATE INDEX idx_input_notes_status ON input_notes(status);
+CREATE INDEX idx_input_notes_sender_id ON input_notes(sender_id);
+CREATE INDEX idx_input_notes_tag ON input_notes(tag);
+CREATE INDEX idx_input_notes_commit_height ON input_notes(commit_height);
@@ -0,0 +1,48 @@
+-- Create note_scripts table, storing every note script once
+CREATE TABLE note_scripts (
+    script_hash TEXT NOT NULL,      -- the hash of the note script
+    name TEXT NULL,                 -- name the script was registered with, if any
+    script BLOB NOT NULL,           -- the serialized NoteScript, including script hash and ProgramAst
+    PRIMARY KEY (script_hash)
+);
+
+CREATE UNIQUE INDEX idx_note_scripts_name ON note_scripts(name) WHERE name IS NOT NULL;
+
+-- Input notes reference their script by hash. SQLite cannot relax the NOT NULL constraint of the
+-- script column, so the table is recreated. The scripts of the notes stored so far are moved to
+-- note_scripts when the store is opened, as their hash cannot be computed here
+CREATE TABLE input_notes_new (
+    note_id BLOB NOT NULL,                                  -- the note id
+    nullifier BLOB NOT NULL,                                -- the nullifier of the note
+    script_hash TEXT NULL,                                  -- the hash of the note script, stored in note_scripts
+    script BLOB NULL,                                       -- the serialized NoteScript of notes stored before note_scripts existed, until it is moved there
+    vault BLOB NOT NULL,                                    -- the serialized NoteVault, including vault hash and list of assets
+    inputs BLOB NOT NULL,                                   -- the serialized NoteInputs, including inputs hash and list of inputs
+    serial_num BLOB NOT NULL,                               -- the note serial number
+    sender_id UNSIGNED BIG INT NOT NULL,                    -- the account ID of the sender
+    tag UNSIGNED BIG INT NOT NULL,                          -- the note tag
+    inclusion_proof BLOB NULL,                              -- the inclusion proof of the note against a block number
+    recipients BLOB NOT NULL,                               -- a list of account IDs of accounts which can consume this note
+    status TEXT CHECK( status IN (                          -- the status of the note - either pending, committed or consumed
+        'pending', 'committed', 'consumed'
+        )),
+    commit_height UNSIGNED BIG INT NOT NULL,                -- the block number at which the note was included into the chain
+    consumed_height UNSIGNED BIG INT NULL,                  -- the block number at which the note was seen consumed
+    PRIMARY KEY (note_id),
+    FOREIGN KEY (script_hash) REFERENCES note_scripts(script_hash),
+    CONSTRAINT check_script CHECK (script_hash IS NOT NULL OR script IS NOT NULL)
+);
+
+INSERT INTO input_notes_new
+    (note_id, nullifier, script, vault, inputs, serial_num, sender_id, tag, inclusion_proof, recipients, status, commit_height, consumed_height)
+SELECT note_id, nullifier, script, vault, inputs, serial_num, sender_id, tag, inclusion_proof, recipients, status, commit_height, consumed_height
+FROM input_notes;
+
+DROP TABLE input_notes;
+ALTER TABLE input_notes_new RENAME TO input_notes;
+
+CREATE INDEX idx_input_notes_status ON input_notes(status);
+CREATE INDEX idx_input_notes_sender_id ON input_notes(sender_id);
+CREATE INDEX idx_input_notes_tag ON input_notes(tag);
+CREATE INDEX idx_input_notes_commit_height ON input_notes(commit_height);
+CREATE INDEX idx_input_notes_script_hash ON input_notes(script_hash);
@@ -0,0 +1,44 @@
+-- Store transaction scripts by hash only, with their inputs kept in the transactions executing them
+CREATE TABLE transaction_scripts_old (
+    script_hash BLOB NOT NULL,                       -- Transaction script Hash
+    program BLOB,                                    -- Transaction script program, serialized
+
+    PRIMARY KEY (script_hash)
+);
+
+INSERT OR IGNORE INTO transaction_scripts_old (script_hash, program)
+SELECT script_hash, program FROM transaction_scripts ORDER BY id;
+
+CREATE TABLE transactions_old (
+    id BLOB NOT NULL,                                -- Transaction ID (hash of various components)
+    account_id UNSIGNED BIG INT NOT NULL,            -- ID of the account against which the transaction was executed.
+    init_account_state BLOB NOT NULL,                -- Hash of the account state before the transaction was executed.
+    final_account_state BLOB NOT NULL,               -- Hash of the account state after the transaction was executed.
+    input_notes BLOB,                                -- Serialized list of input note hashes
+    output_notes BLOB,                               -- Serialized list of output note hashes
+    script_hash BLOB,                                -- Transaction script hash
+    script_inputs BLOB,                              -- Transaction script inputs
+    block_num UNSIGNED BIG INT,                      -- Block number for the block against which the transaction was executed.
+    commit_height UNSIGNED BIG INT NULL,             -- Block number of the block at which the transaction was included in the chain.
+    stage TEXT CHECK (stage IN ('executed', 'proven', 'submitted')) NOT NULL DEFAULT 'submitted',
+    transaction_result BLOB NULL,                    -- serialized executed transaction and created notes, cleared once submitted
+    proven_transaction BLOB NULL,                    -- serialized proven transaction, cleared once submitted
+
+    FOREIGN KEY (script_hash) REFERENCES transaction_scripts(script_hash),
+    PRIMARY KEY (id)
+);
+
+INSERT INTO transactions_old
+    (id, account_id, init_account_state, final_account_state, input_notes, output_notes, script_hash,
+    script_inputs, block_num, commit_height, stage, transaction_result, proven_transaction)
+SELECT tx.id, tx.account_id, tx.init_account_state, tx.final_account_state, tx.input_notes, tx.output_notes,
+    script.script_hash, script.inputs, tx.block_num, tx.commit_height, tx.stage, tx.transaction_result,
+    tx.proven_transaction
+FROM transactions AS tx
+LEFT JOIN transaction_scripts AS script ON tx.script_id = script.id
+ORDER BY tx.rowid;
+
+DROP TABLE transactions;
+DROP TABLE transaction_scripts;
+ALTER TABLE transactions_old RENAME TO transactions;
+ALTER TABLE transaction_scripts_old RENAME TO transaction_scripts;
@@ -0,0 +1,55 @@
+-- Store transaction scripts along with their inputs, and only reference them from transactions
+--
+-- The inputs of a script depend on the account executing it, so scripts are identified by their
+-- hash together with their inputs. Transactions executing the same script for the same account
+-- share a single row.
+CREATE TABLE transaction_scripts_new (
+    id INTEGER NOT NULL,            -- identifier of the script referenced by transactions
+    script_hash BLOB NOT NULL,      -- Transaction script Hash
+    program BLOB NOT NULL,          -- Transaction script program, serialized
+    inputs TEXT NOT NULL,           -- Transaction script inputs, serialized
+
+    PRIMARY KEY (id),
+    CONSTRAINT unique_script UNIQUE (script_hash, inputs)
+);
+
+INSERT INTO transaction_scripts_new (script_hash, program, inputs)
+SELECT DISTINCT tx.script_hash, script.program, tx.script_inputs
+FROM transactions AS tx
+JOIN transaction_scripts AS script ON tx.script_hash = script.script_hash
+WHERE tx.script_inputs IS NOT NULL;
+
+CREATE TABLE transactions_new (
+    id BLOB NOT NULL,                                -- Transaction ID (hash of various components)
+    account_id UNSIGNED BIG INT NOT NULL,            -- ID of the account against which the transaction was executed.
+    init_account_state BLOB NOT NULL,                -- Hash of the account state before the transaction was executed.
+    final_account_state BLOB NOT NULL,               -- Hash of the account state after the transaction was executed.
+    input_notes BLOB,                                -- Serialized list of input note hashes
+    output_notes BLOB,                               -- Serialized list of output note hashes
+    script_id INTEGER NULL,                          -- Transaction script, if the transaction was executed with one
+    block_num UNSIGNED BIG INT,                      -- Block number for the block against which the transaction was executed.
+    commit_height UNSIGNED BIG INT NULL,             -- Block number of the block at which the transaction was included in the chain.
+    stage TEXT CHECK (stage IN ('executed', 'proven', 'submitted')) NOT NULL DEFAULT 'submitted',
+    transaction_result BLOB NULL,                    -- serialized executed transaction and created notes, cleared once submitted
+    proven_transaction BLOB NULL,                    -- serialized proven transaction, cleared once submitted
+
+    FOREIGN KEY (script_id) REFERENCES transaction_scripts(id),
+    PRIMARY KEY (id)
+);
+
+INSERT INTO transactions_new
+    (id, account_id, init_account_state, final_account_state, input_notes, output_notes, script_id,
+    block_num, commit_height, stage, transaction_result, proven_transaction)
+SELECT tx.id, tx.account_id, tx.init_account_state, tx.final_account_state, tx.input_notes, tx.output_notes,
+    (SELECT script.id FROM transaction_scripts_new AS script
+        WHERE script.script_hash = tx.script_hash AND script.inputs = tx.script_inputs),
+    tx.block_num, tx.commit_height, tx.stage, tx.transaction_result, tx.proven_transaction
+FROM transactions AS tx
+ORDER BY tx.rowid;
+
+DROP TABLE transactions;
+DROP TABLE transaction_scripts;
+ALTER TABLE transactions_new RENAME TO transactions;
+ALTER TABLE transaction_scripts_new RENAME TO transaction_scripts;
+
+CREATE INDEX idx_transactions_script_id ON transactions(script_id);
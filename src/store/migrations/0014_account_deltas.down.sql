@@ -0,0 +1,2 @@
+-- Drop account_deltas table
+DROP TABLE account_deltas;
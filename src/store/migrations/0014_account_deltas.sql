@@ -0,0 +1,13 @@
+-- Create account_deltas table, holding every account delta applied to the accounts tracked by the
+-- client in the order they were applied
+CREATE TABLE account_deltas (
+    id INTEGER NOT NULL,                    -- order in which the delta was applied
+    account_id UNSIGNED BIG INT NOT NULL,   -- ID of the account the delta was applied to
+    transaction_id TEXT NULL,               -- ID of the transaction the delta results from, NULL if it was applied directly
+    nonce UNSIGNED BIG INT NOT NULL,        -- account nonce after applying the delta
+    delta BLOB NOT NULL,                    -- the serialized AccountDelta
+    PRIMARY KEY (id),
+    CONSTRAINT unique_transaction_id UNIQUE (transaction_id)
+);
+
+CREATE INDEX idx_account_deltas_account_id ON account_deltas(account_id);
@@ -0,0 +1,4 @@
+-- Stop recording the sync height at which each account state was stored
+DROP INDEX idx_accounts_block_num;
+
+ALTER TABLE accounts DROP COLUMN block_num;
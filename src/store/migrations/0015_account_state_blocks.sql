@@ -0,0 +1,10 @@
+-- Record the sync height at which each account state was stored, so that the state an account
+-- had at an earlier block can be retrieved
+ALTER TABLE accounts ADD COLUMN block_num UNSIGNED BIG INT NOT NULL DEFAULT 0;
+
+-- The height at which existing states were stored is unknown. The first state of each account is
+-- taken to date back to the genesis block, and later ones to the current sync height.
+UPDATE accounts SET block_num = (SELECT block_num FROM state_sync)
+WHERE nonce > (SELECT MIN(b.nonce) FROM accounts AS b WHERE b.id = accounts.id);
+
+CREATE INDEX idx_accounts_block_num ON accounts(id, block_num);
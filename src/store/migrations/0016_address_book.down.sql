@@ -0,0 +1,2 @@
+-- Drop address_book table
+DROP TABLE address_book;
@@ -0,0 +1,7 @@
+-- Create address_book table, holding the names given to accounts so they can be referred to by
+-- name instead of by ID
+CREATE TABLE address_book (
+    name TEXT NOT NULL,                     -- name given to the account
+    account_id UNSIGNED BIG INT NOT NULL,   -- ID of the named account, which need not be tracked by the client
+    PRIMARY KEY (name)
+);
@@ -0,0 +1,22 @@
+-- Require the chain MMR peaks of every stored block header. Headers whose peaks are unknown are
+-- dropped, along with the inclusion paths of the notes authenticated against them. SQLite cannot
+-- alter column constraints, so the block_headers table is recreated
+DELETE FROM note_inclusion_paths
+WHERE block_num IN (SELECT block_num FROM block_headers WHERE chain_mmr_peaks IS NULL);
+
+CREATE TABLE block_headers_old (
+    block_num UNSIGNED BIG INT NOT NULL,  -- block number
+    header BLOB NOT NULL,                 -- serialized block header
+    notes_root BLOB NOT NULL,             -- root of the notes Merkle tree in this block
+    sub_hash BLOB NOT NULL,               -- hash of all other header fields in the block
+    chain_mmr_peaks BLOB NOT NULL,        -- serialized peaks of the chain MMR at this block
+    has_client_notes BOOL NOT NULL,       -- whether the block has notes relevant to the client
+    PRIMARY KEY (block_num)
+);
+
+INSERT INTO block_headers_old
+SELECT block_num, header, notes_root, sub_hash, chain_mmr_peaks, has_client_notes FROM block_headers
+WHERE chain_mmr_peaks IS NOT NULL;
+
+DROP TABLE block_headers;
+ALTER TABLE block_headers_old RENAME TO block_headers;
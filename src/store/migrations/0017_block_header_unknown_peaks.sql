@@ -0,0 +1,19 @@
+-- Allow storing block headers whose chain MMR peaks are unknown. Headers of blocks committed before
+-- the client's sync height are fetched along with their MMR authentication path to authenticate
+-- notes imported after the fact, but the peaks of the chain MMR at those blocks are not. SQLite
+-- cannot alter column constraints, so the block_headers table is recreated
+CREATE TABLE block_headers_new (
+    block_num UNSIGNED BIG INT NOT NULL,  -- block number
+    header BLOB NOT NULL,                 -- serialized block header
+    notes_root BLOB NOT NULL,             -- root of the notes Merkle tree in this block
+    sub_hash BLOB NOT NULL,               -- hash of all other header fields in the block
+    chain_mmr_peaks BLOB NULL,            -- serialized peaks of the chain MMR at this block, NULL if unknown
+    has_client_notes BOOL NOT NULL,       -- whether the block has notes relevant to the client
+    PRIMARY KEY (block_num)
+);
+
+INSERT INTO block_headers_new
+SELECT block_num, header, notes_root, sub_hash, chain_mmr_peaks, has_client_notes FROM block_headers;
+
+DROP TABLE block_headers;
+ALTER TABLE block_headers_new RENAME TO block_headers;
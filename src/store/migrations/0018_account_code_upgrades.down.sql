@@ -0,0 +1,2 @@
+-- Drop account_code_upgrades table
+DROP TABLE account_code_upgrades;
@@ -0,0 +1,13 @@
+-- Create account_code_upgrades table, holding the transactions that changed the code of an account.
+-- The code the account had before is kept in account_code so that the upgrade can be rolled back
+CREATE TABLE account_code_upgrades (
+    transaction_id TEXT NOT NULL,           -- ID of the transaction changing the code
+    account_id UNSIGNED BIG INT NOT NULL,   -- ID of the upgraded account
+    previous_code_root BLOB NOT NULL,       -- root of the code the account had before the transaction
+    code_root BLOB NOT NULL,                -- root of the code the account has after the transaction
+    PRIMARY KEY (transaction_id),
+    FOREIGN KEY (previous_code_root) REFERENCES account_code(root),
+    FOREIGN KEY (code_root) REFERENCES account_code(root)
+);
+
+CREATE INDEX idx_account_code_upgrades_account_id ON account_code_upgrades(account_id);
@@ -0,0 +1,5 @@
+-- Stop reserving input notes for the transaction consuming them
+DROP INDEX idx_input_notes_locked_by_tx;
+
+ALTER TABLE input_notes DROP COLUMN locked_until;
+ALTER TABLE input_notes DROP COLUMN locked_by_tx;
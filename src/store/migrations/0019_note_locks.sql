@@ -0,0 +1,6 @@
+-- Add columns reserving input notes for the transaction consuming them, so that other
+-- transactions do not pick them while it is being proven and submitted
+ALTER TABLE input_notes ADD COLUMN locked_by_tx TEXT NULL;                 -- ID of the transaction consuming the note
+ALTER TABLE input_notes ADD COLUMN locked_until UNSIGNED BIG INT NULL;     -- UNIX timestamp, in seconds, at which the lock expires
+
+CREATE INDEX idx_input_notes_locked_by_tx ON input_notes(locked_by_tx);
@@ -0,0 +1,2 @@
+-- Drop account proofs table
+DROP TABLE account_proofs;
@@ -0,0 +1,11 @@
+-- Create account proofs table
+--
+-- The state of on-chain accounts is proven against the account root of a block header when
+-- syncing. The latest verified proof of each account is kept.
+CREATE TABLE account_proofs (
+    account_id UNSIGNED BIG INT NOT NULL,   -- the account id
+    block_num UNSIGNED BIG INT NOT NULL,    -- the block whose account root the proof was verified against
+    account_hash TEXT NOT NULL,             -- hash of the account's state at that block
+    merkle_path TEXT NOT NULL,              -- JSON list of the nodes of the path from the account's leaf to the account root
+    PRIMARY KEY (account_id)
+);
@@ -0,0 +1,2 @@
+-- Stop tracking the block up to which the nullifier of each input note has been checked
+ALTER TABLE input_notes DROP COLUMN nullifier_checked_height;
@@ -0,0 +1,10 @@
+-- Track the block up to which the nullifier of each input note has been checked against the
+-- node, so that nullifiers left behind by syncs limited to some accounts are caught up from
+-- where they stopped rather than from the client's sync height
+--
+-- NULL means the nullifier is checked along with the client's sync height.
+ALTER TABLE input_notes ADD COLUMN nullifier_checked_height UNSIGNED BIG INT NULL;
+
+-- The nullifiers of existing notes were checked along with the client's sync height
+UPDATE input_notes SET nullifier_checked_height = (SELECT block_num FROM state_sync)
+WHERE status = 'committed';
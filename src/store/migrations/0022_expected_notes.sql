@@ -0,0 +1,42 @@
+-- Add the `expected` status of input notes, for the notes created by a transaction the client
+-- submitted that are not committed yet. SQLite cannot alter the CHECK constraint of the status
+-- column, so the table is recreated
+CREATE TABLE input_notes_new (
+    note_id BLOB NOT NULL,                                  -- the note id
+    nullifier BLOB NOT NULL,                                -- the nullifier of the note
+    script_hash TEXT NULL,                                  -- the hash of the note script, stored in note_scripts
+    script BLOB NULL,                                       -- the serialized NoteScript of notes stored before note_scripts existed, until it is moved there
+    vault BLOB NOT NULL,                                    -- the serialized NoteVault, including vault hash and list of assets
+    inputs BLOB NOT NULL,                                   -- the serialized NoteInputs, including inputs hash and list of inputs
+    serial_num BLOB NOT NULL,                               -- the note serial number
+    sender_id UNSIGNED BIG INT NOT NULL,                    -- the account ID of the sender
+    tag UNSIGNED BIG INT NOT NULL,                          -- the note tag
+    inclusion_proof BLOB NULL,                              -- the inclusion proof of the note against a block number
+    recipients BLOB NOT NULL,                               -- a list of account IDs of accounts which can consume this note
+    status TEXT CHECK( status IN (                          -- the status of the note - either pending, expected, committed or consumed
+        'pending', 'expected', 'committed', 'consumed'
+        )),
+    commit_height UNSIGNED BIG INT NOT NULL,                -- the block number at which the note was included into the chain
+    consumed_height UNSIGNED BIG INT NULL,                  -- the block number at which the note was seen consumed
+    locked_by_tx TEXT NULL,                                 -- ID of the transaction consuming the note
+    locked_until UNSIGNED BIG INT NULL,                     -- UNIX timestamp, in seconds, at which the lock expires
+    nullifier_checked_height UNSIGNED BIG INT NULL,         -- the block up to which the nullifier was checked against the node
+    PRIMARY KEY (note_id),
+    FOREIGN KEY (script_hash) REFERENCES note_scripts(script_hash),
+    CONSTRAINT check_script CHECK (script_hash IS NOT NULL OR script IS NOT NULL)
+);
+
+INSERT INTO input_notes_new
+    (note_id, nullifier, script_hash, script, vault, inputs, serial_num, sender_id, tag, inclusion_proof, recipients, status, commit_height, consumed_height, locked_by_tx, locked_until, nullifier_checked_height)
+SELECT note_id, nullifier, script_hash, script, vault, inputs, serial_num, sender_id, tag, inclusion_proof, recipients, status, commit_height, consumed_height, locked_by_tx, locked_until, nullifier_checked_height
+FROM input_notes;
+
+DROP TABLE input_notes;
+ALTER TABLE input_notes_new RENAME TO input_notes;
+
+CREATE INDEX idx_input_notes_status ON input_notes(status);
+CREATE INDEX idx_input_notes_sender_id ON input_notes(sender_id);
+CREATE INDEX idx_input_notes_tag ON input_notes(tag);
+CREATE INDEX idx_input_notes_commit_height ON input_notes(commit_height);
+CREATE INDEX idx_input_notes_script_hash ON input_notes(script_hash);
+CREATE INDEX idx_input_notes_locked_by_tx ON input_notes(locked_by_tx);
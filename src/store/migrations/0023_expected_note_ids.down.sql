@@ -0,0 +1,3 @@
+-- Drop expected_note_ids table, along with the note tags registered for the notes it holds
+DELETE FROM note_tags WHERE source = 'note' AND source_note_id IN (SELECT note_id FROM expected_note_ids);
+DROP TABLE expected_note_ids;
@@ -0,0 +1,9 @@
+-- Create expected_note_ids table, holding the notes the client waits for of which only the ID
+-- is known, e.g. payments announced by the payer before submitting them
+CREATE TABLE expected_note_ids (
+    note_id TEXT NOT NULL,                      -- ID of the expected note
+    tag UNSIGNED BIG INT NOT NULL,              -- tag the note is expected with, tracked until the note is committed
+    label TEXT NULL,                            -- optional description of the note
+    commit_height UNSIGNED BIG INT NULL,        -- block the note was committed in, NULL while it is still expected
+    PRIMARY KEY (note_id)
+);
@@ -0,0 +1,2 @@
+-- Stop storing the memos of input notes
+ALTER TABLE input_notes DROP COLUMN memo;
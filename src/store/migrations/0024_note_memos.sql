@@ -0,0 +1,2 @@
+-- Store the memo encoded in the inputs of input notes, so that notes can be looked up by memo
+ALTER TABLE input_notes ADD COLUMN memo TEXT NULL;
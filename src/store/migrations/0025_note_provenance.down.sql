@@ -0,0 +1,5 @@
+-- Stop recording the provenance of input notes
+ALTER TABLE input_notes DROP COLUMN committed_at;
+ALTER TABLE input_notes DROP COLUMN created_at;
+ALTER TABLE input_notes DROP COLUMN origin_detail;
+ALTER TABLE input_notes DROP COLUMN origin;
@@ -0,0 +1,6 @@
+-- Record where each input note came from, when it was stored, and when syncing found it committed.
+-- Notes stored before this migration have no recorded origin.
+ALTER TABLE input_notes ADD COLUMN origin TEXT NULL CHECK (origin IN ('imported', 'file', 'transaction', 'expected'));
+ALTER TABLE input_notes ADD COLUMN origin_detail TEXT NULL;
+ALTER TABLE input_notes ADD COLUMN created_at UNSIGNED BIG INT NULL;
+ALTER TABLE input_notes ADD COLUMN committed_at UNSIGNED BIG INT NULL;
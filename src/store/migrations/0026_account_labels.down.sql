@@ -0,0 +1,3 @@
+-- Drop account_labels table
+DROP INDEX idx_account_labels_label;
+DROP TABLE account_labels;
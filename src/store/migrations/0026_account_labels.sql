@@ -0,0 +1,9 @@
+-- Create account_labels table, holding the labels users give to the accounts tracked by the client
+-- to organize their wallet
+CREATE TABLE account_labels (
+    account_id UNSIGNED BIG INT NOT NULL,   -- ID of the labelled account
+    label TEXT NOT NULL,                    -- label given to the account, which several accounts can share
+    PRIMARY KEY (account_id)
+);
+
+CREATE INDEX idx_account_labels_label ON account_labels(label);
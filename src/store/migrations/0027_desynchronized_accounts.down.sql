@@ -0,0 +1,2 @@
+-- Drop desynchronized_accounts table
+DROP TABLE desynchronized_accounts;
@@ -0,0 +1,9 @@
+-- Create desynchronized_accounts table, holding the accounts whose on-chain state was found by a
+-- sync to differ from the local one, for instance because they were updated from another store
+CREATE TABLE desynchronized_accounts (
+    account_id UNSIGNED BIG INT NOT NULL,   -- ID of the desynchronized account
+    local_hash TEXT NOT NULL,               -- hash of the account's local state when the divergence was found
+    remote_hash TEXT NOT NULL,              -- hash of the account's on-chain state reported by the node
+    block_num UNSIGNED BIG INT NOT NULL,    -- block at which the divergence was found
+    PRIMARY KEY (account_id)
+);
@@ -0,0 +1,2 @@
+-- Stop storing the unlock heights of input notes
+ALTER TABLE input_notes DROP COLUMN unlock_height;
@@ -0,0 +1,3 @@
+-- Store the block after which timelocked input notes can be consumed, so that notes that are still
+-- locked are not picked to be consumed
+ALTER TABLE input_notes ADD COLUMN unlock_height UNSIGNED BIG INT NULL;
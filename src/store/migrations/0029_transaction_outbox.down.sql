@@ -0,0 +1,2 @@
+-- Drop transaction_outbox table
+DROP TABLE transaction_outbox;
@@ -0,0 +1,8 @@
+-- Create transaction_outbox table, holding the proven transactions queued in offline mode until
+-- they are submitted, in the order they were queued
+CREATE TABLE transaction_outbox (
+    transaction_id BLOB NOT NULL,           -- ID of the queued transaction
+    queued_at UNSIGNED BIG INT NOT NULL,    -- UNIX timestamp, in seconds, at which the transaction was queued
+    PRIMARY KEY (transaction_id),
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id)
+);
@@ -0,0 +1,2 @@
+-- Stop tracking the chain tip syncs are heading to
+ALTER TABLE state_sync DROP COLUMN target_block_num;
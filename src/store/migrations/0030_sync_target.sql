@@ -0,0 +1,5 @@
+-- Track the chain tip a sync is heading to, so that an interrupted sync can report its progress
+-- when it is resumed from the last block it applied
+--
+-- NULL means the client was synced to the chain tip it last knew of.
+ALTER TABLE state_sync ADD COLUMN target_block_num UNSIGNED BIG INT NULL;
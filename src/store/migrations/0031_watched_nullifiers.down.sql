@@ -0,0 +1,2 @@
+-- Stop watching nullifiers of notes the client does not hold
+DROP TABLE watched_nullifiers;
@@ -0,0 +1,9 @@
+-- Create watched nullifiers table, holding the nullifiers of notes the client does not hold but
+-- wants to know the consumption of, such as notes it created for other accounts
+CREATE TABLE watched_nullifiers (
+    nullifier TEXT NOT NULL,                    -- nullifier of the watched note
+    label TEXT NULL,                            -- description of the note, shown when listing
+    checked_height UNSIGNED BIG INT NOT NULL,   -- block up to which the nullifier was checked against the node
+    consumed_height UNSIGNED BIG INT NULL,      -- block the note was consumed in, NULL if it was not found consumed
+    PRIMARY KEY (nullifier)
+);
@@ -0,0 +1,2 @@
+-- Stop hiding accounts, which are listed and synced again
+DROP TABLE hidden_accounts;
@@ -0,0 +1,7 @@
+-- Create hidden_accounts table, holding the accounts removed from the client without deleting
+-- their data. They are left out of account lists and syncs until they are restored
+CREATE TABLE hidden_accounts (
+    account_id UNSIGNED BIG INT NOT NULL,   -- ID of the hidden account
+    hidden_at UNSIGNED BIG INT NOT NULL,    -- UNIX timestamp, in seconds, at which the account was hidden
+    PRIMARY KEY (account_id)
+);
@@ -0,0 +1,2 @@
+-- Stop tracking the lifecycle of the notes created as orders
+DROP TABLE orders;
@@ -0,0 +1,13 @@
+-- Create orders table, holding the SWAP and P2IDR notes created by the client's accounts, which
+-- stay open until a counterparty fills them or their sender recalls them
+CREATE TABLE orders (
+    note_id TEXT NOT NULL,                      -- ID of the created note
+    nullifier TEXT NOT NULL,                    -- nullifier of the note, spent when it is filled or recalled
+    kind TEXT CHECK(kind IN ('swap', 'p2idr')), -- script of the note
+    account_id UNSIGNED BIG INT NOT NULL,       -- ID of the account that created the note
+    transaction_id TEXT NOT NULL,               -- ID of the transaction that created the note
+    recall_height UNSIGNED BIG INT NULL,        -- block from which the sender can recall the note, NULL if it cannot
+    status TEXT CHECK(status IN ('open', 'expired', 'filled', 'recalled')) NOT NULL DEFAULT 'open',
+    closed_height UNSIGNED BIG INT NULL,        -- block the note was consumed in, NULL while it is not
+    PRIMARY KEY (note_id)
+);
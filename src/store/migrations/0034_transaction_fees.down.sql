@@ -0,0 +1,3 @@
+-- Stop storing the fees of transactions
+ALTER TABLE transactions DROP COLUMN fee_amount;
+ALTER TABLE transactions DROP COLUMN fee_faucet_id;
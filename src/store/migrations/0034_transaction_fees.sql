@@ -0,0 +1,7 @@
+-- Store the fee transactions are charged, as estimated by the fee policy of the client when they
+-- were executed
+--
+-- Both columns are NULL for transactions charged no fee, which are all of them until the network
+-- enables fees.
+ALTER TABLE transactions ADD COLUMN fee_faucet_id UNSIGNED BIG INT NULL;
+ALTER TABLE transactions ADD COLUMN fee_amount UNSIGNED BIG INT NULL;
@@ -1,20 +1,44 @@
 use crate::{config::StoreConfig, errors::StoreError};
 
-use clap::error::Result;
-use rusqlite::Connection;
+use objects::accounts::AccountId;
+
+use rusqlite::{Connection, OpenFlags, Transaction, TransactionBehavior};
+use std::{
+    env::temp_dir,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tracing::info;
 
 pub mod accounts;
 pub mod chain_data;
 mod migrations;
 pub mod notes;
+pub mod orders;
+pub mod pool;
 pub mod sync;
 pub mod transactions;
 
+#[cfg(any(test, feature = "mock"))]
+pub mod fixtures;
 #[cfg(any(test, feature = "mock"))]
 pub mod mock_executor_data_store;
 
 pub mod data_store;
 
+/// How long a connection waits for another one to release the database lock before giving up
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Number of prepared statements kept by a connection for reuse, enough to hold every query run
+/// while applying a sync update
+const STATEMENT_CACHE_CAPACITY: usize = 64;
+
+/// Maximum number of parameters bound to a single statement. This is the default limit of SQLite
+/// versions before 3.32, which is the lowest the client may run against.
+const MAX_STATEMENT_PARAMETERS: usize = 999;
+
 // CLIENT STORE
 // ================================================================================================
 
@@ -27,12 +51,577 @@ impl Store {
     // --------------------------------------------------------------------------------------------
 
     /// Returns a new instance of [Store] instantiated with the specified configuration options.
+    ///
+    /// If the store has pending migrations, a copy of it is saved next to it before they are
+    /// applied, see [Store::backup_path].
     pub fn new(config: StoreConfig) -> Result<Self, StoreError> {
-        let mut db = Connection::open(config.database_filepath)?;
-        migrations::update_to_latest(&mut db)?;
+        // stores are kept in the data directory by default, which may not exist yet
+        if let Some(parent) = Path::new(&config.database_filepath).parent() {
+            fs::create_dir_all(parent).map_err(|err| StoreError::DatabaseError(err.to_string()))?;
+        }
+        let mut db = Self::open(&config.database_filepath, config.passphrase.as_deref())?;
+
+        // Write-ahead logging lets readers proceed while another connection, possibly from a
+        // different process, is writing to the database
+        db.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+
+        let current_version = migrations::current_version(&db)?;
+        if current_version > 0 && current_version < migrations::LATEST_VERSION {
+            Self::backup(&db, &config.database_filepath, current_version)?;
+        }
+        Self::migrate(&mut db)?;
+
+        Ok(Self { db })
+    }
+
+    /// Opens the database at `path`, unlocking it with `passphrase` if one is provided.
+    ///
+    /// # Errors
+    /// Returns an error if a passphrase is provided and the client was built without the
+    /// `sqlcipher` feature, or if the database cannot be decrypted with it.
+    fn open(path: impl AsRef<Path>, passphrase: Option<&str>) -> Result<Connection, StoreError> {
+        let db = Connection::open(path)?;
+        db.busy_timeout(BUSY_TIMEOUT)?;
+        db.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+
+        match passphrase {
+            #[cfg(feature = "sqlcipher")]
+            Some(passphrase) => {
+                db.pragma_update(None, "key", passphrase)?;
+
+                // SQLCipher only checks the key once the database is read
+                db.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+                    .map_err(|err| {
+                        StoreError::DatabaseError(format!(
+                            "could not decrypt the store, either the passphrase is wrong or the store is not encrypted: {err}"
+                        ))
+                    })?;
+            }
+            #[cfg(not(feature = "sqlcipher"))]
+            Some(_) => return Err(StoreError::EncryptionNotSupported),
+            None => {}
+        }
+
+        Ok(db)
+    }
+
+    /// Opens the existing store at `path` without write access, for tools that report on a
+    /// store another client keeps using, such as dashboards. The database is neither created nor
+    /// migrated, and any statement that would modify it fails. Since the store runs in
+    /// write-ahead logging mode, the reads do not block the client writing to it.
+    ///
+    /// Only plaintext stores can be opened this way.
+    ///
+    /// # Errors
+    /// Returns an error if there is no database at `path`, or if its schema is not at the latest
+    /// version, in which case the client writing to it has to migrate it first.
+    pub fn open_read_only(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let db = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        db.busy_timeout(BUSY_TIMEOUT)?;
+        db.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+
+        let current_version = migrations::current_version(&db)?;
+        if current_version != migrations::LATEST_VERSION {
+            return Err(StoreError::InvalidSchemaVersion(
+                current_version,
+                migrations::LATEST_VERSION,
+            ));
+        }
 
         Ok(Self { db })
     }
+
+    /// Returns whether the database at `path` is encrypted. Plaintext databases start with the
+    /// SQLite header, while the contents of encrypted ones are indistinguishable from random
+    /// data. Missing and empty files are not considered encrypted.
+    pub fn is_encrypted(path: impl AsRef<Path>) -> Result<bool, StoreError> {
+        const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+        let mut file = match fs::File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(StoreError::DatabaseError(err.to_string())),
+        };
+
+        let mut header = [0u8; 16];
+        match file.read_exact(&mut header) {
+            Ok(()) => Ok(&header != SQLITE_HEADER),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+            Err(err) => Err(StoreError::DatabaseError(err.to_string())),
+        }
+    }
+
+    // ENCRYPTION
+    // --------------------------------------------------------------------------------------------
+
+    /// Encrypts the plaintext store specified by `config` with `passphrase`, replacing the
+    /// original database file.
+    ///
+    /// The encrypted database is written next to the original one and only moved into place once
+    /// it is complete, so the store is left untouched if encryption fails.
+    ///
+    /// # Errors
+    /// Returns an error if the store is already encrypted or could not be exported.
+    #[cfg(feature = "sqlcipher")]
+    pub fn encrypt(config: &StoreConfig, passphrase: &str) -> Result<(), StoreError> {
+        let path = Path::new(&config.database_filepath);
+        if Self::is_encrypted(path)? {
+            return Err(StoreError::DatabaseError(
+                "the store is already encrypted".to_string(),
+            ));
+        }
+
+        let encrypted_path = path.with_extension("encrypted");
+        let result = Self::export_encrypted(path, &encrypted_path, passphrase).and_then(|_| {
+            fs::rename(&encrypted_path, path)
+                .map_err(|err| StoreError::DatabaseError(err.to_string()))
+        });
+        if result.is_err() {
+            let _ = fs::remove_file(&encrypted_path);
+        }
+
+        result
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    fn export_encrypted(
+        path: &Path,
+        encrypted_path: &Path,
+        passphrase: &str,
+    ) -> Result<(), StoreError> {
+        let db = Connection::open(path)?;
+
+        db.execute(
+            "ATTACH DATABASE ? AS encrypted KEY ?",
+            rusqlite::params![encrypted_path.to_string_lossy(), passphrase],
+        )?;
+        db.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))?;
+
+        // The schema version migrations rely on is not exported along with the data
+        let version: i64 = db.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        db.pragma_update(
+            Some(rusqlite::DatabaseName::Attached("encrypted")),
+            "user_version",
+            version,
+        )?;
+
+        db.execute("DETACH DATABASE encrypted", [])?;
+        Ok(())
+    }
+
+    // RESET
+    // --------------------------------------------------------------------------------------------
+
+    /// Moves the store specified by `config` to its archive path and creates an empty store in
+    /// its place, for when the network the store was synced against no longer exists, such as
+    /// after the node was reset. Returns the archive path, or `None` if there was no store yet.
+    pub fn reset(config: StoreConfig) -> Result<Option<PathBuf>, StoreError> {
+        let database_filepath = Path::new(&config.database_filepath);
+        let archive_path = if database_filepath.exists() {
+            let db = Self::open(database_filepath, config.passphrase.as_deref())?;
+            // Fold the write-ahead log back into the database file, so that the archive is complete
+            db.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+            drop(db);
+
+            let archive_path = Self::archive_path(&config.database_filepath);
+            fs::rename(database_filepath, &archive_path)
+                .map_err(|err| StoreError::DatabaseError(err.to_string()))?;
+            for suffix in ["-wal", "-shm"] {
+                let _ = fs::remove_file(format!("{}{suffix}", config.database_filepath));
+            }
+            info!("Archived the store to {}", archive_path.display());
+            Some(archive_path)
+        } else {
+            None
+        };
+
+        Self::new(config)?;
+        Ok(archive_path)
+    }
+
+    /// Moves the database at `from` to `to`, along with its write-ahead log, creating the
+    /// directory it is moved to if needed. The database must not be open.
+    ///
+    /// # Errors
+    /// Returns an error if a database already exists at `to` or if the files could not be moved.
+    pub fn relocate(from: &Path, to: &Path) -> Result<(), StoreError> {
+        if to.exists() {
+            return Err(StoreError::DatabaseError(format!(
+                "a store already exists at {}",
+                to.display()
+            )));
+        }
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent).map_err(|err| StoreError::DatabaseError(err.to_string()))?;
+        }
+
+        for suffix in ["", "-wal", "-shm"] {
+            let from = PathBuf::from(format!("{}{suffix}", from.display()));
+            if !from.exists() {
+                continue;
+            }
+            let to = PathBuf::from(format!("{}{suffix}", to.display()));
+            // renaming fails across file systems, in which case the file is copied instead
+            if fs::rename(&from, &to).is_err() {
+                fs::copy(&from, &to)
+                    .and_then(|_| fs::remove_file(&from))
+                    .map_err(|err| StoreError::DatabaseError(err.to_string()))?;
+            }
+        }
+
+        info!(
+            "Moved the store from {} to {}",
+            from.display(),
+            to.display()
+        );
+        Ok(())
+    }
+
+    /// Returns the path the database at `database_filepath` is archived to when it is reset,
+    /// which is unique to the time of the reset
+    fn archive_path(database_filepath: &str) -> PathBuf {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("the system clock is set after the UNIX epoch")
+            .as_secs();
+        PathBuf::from(format!("{database_filepath}.{timestamp}.archive"))
+    }
+
+    // TRANSACTIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Starts a database transaction for a multi-step update.
+    ///
+    /// The transaction takes the database write lock right away rather than on its first write,
+    /// so that an update cannot fail halfway through because another connection started writing
+    /// after it was read from.
+    pub(crate) fn write_transaction(&mut self) -> Result<Transaction<'_>, StoreError> {
+        Ok(self
+            .db
+            .transaction_with_behavior(TransactionBehavior::Immediate)?)
+    }
+
+    // MIGRATIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Runs the pending migrations against a temporary copy of the store specified by `config`
+    /// and reports the outcome. The original database is left untouched.
+    ///
+    /// # Errors
+    /// Returns an error if the store could not be copied or if any of the migrations failed.
+    pub fn check_migrations(config: StoreConfig) -> Result<MigrationCheck, StoreError> {
+        let copy_path = temp_dir().join(format!(
+            "miden-store-migration-check-{}.sqlite3",
+            rand::random::<u64>()
+        ));
+        // Fold the write-ahead log back into the database file, so that the copy is complete
+        if Path::new(&format!("{}-wal", config.database_filepath)).exists() {
+            let db = Self::open(&config.database_filepath, config.passphrase.as_deref())?;
+            db.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        }
+        fs::copy(&config.database_filepath, &copy_path)
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))?;
+
+        let check = Self::run_migrations_check(&copy_path, config.passphrase.as_deref());
+        let _ = fs::remove_file(&copy_path);
+
+        check
+    }
+
+    fn run_migrations_check(
+        path: &Path,
+        passphrase: Option<&str>,
+    ) -> Result<MigrationCheck, StoreError> {
+        let mut db = Self::open(path, passphrase)?;
+        let current_version = migrations::current_version(&db)?;
+
+        let start = Instant::now();
+        Self::migrate(&mut db)?;
+        let duration = start.elapsed();
+
+        Ok(MigrationCheck {
+            current_version,
+            latest_version: migrations::current_version(&db)?,
+            duration,
+        })
+    }
+
+    /// Returns the schema version of the store specified by `config` and the latest version,
+    /// without applying any migration.
+    pub fn migration_status(config: StoreConfig) -> Result<MigrationStatus, StoreError> {
+        let db = Self::open(&config.database_filepath, config.passphrase.as_deref())?;
+
+        Ok(MigrationStatus {
+            current_version: migrations::current_version(&db)?,
+            latest_version: migrations::LATEST_VERSION,
+        })
+    }
+
+    /// Applies or reverts migrations until the store specified by `config` is at schema
+    /// `version`, after saving a copy of it next to it, see [Store::backup_path].
+    ///
+    /// Reverting migrations drops the data earlier versions of the client cannot represent, such
+    /// as transactions that were not submitted yet.
+    ///
+    /// # Errors
+    /// Returns an error if `version` is greater than the latest schema version, or if any of the
+    /// migrations failed.
+    pub fn migrate_to(config: StoreConfig, version: usize) -> Result<MigrationStatus, StoreError> {
+        if version > migrations::LATEST_VERSION {
+            return Err(StoreError::InvalidSchemaVersion(
+                version,
+                migrations::LATEST_VERSION,
+            ));
+        }
+
+        let mut db = Self::open(&config.database_filepath, config.passphrase.as_deref())?;
+        let current_version = migrations::current_version(&db)?;
+        if current_version > 0 && current_version != version {
+            Self::backup(&db, &config.database_filepath, current_version)?;
+        }
+
+        if version == migrations::LATEST_VERSION {
+            Self::migrate(&mut db)?;
+        } else {
+            migrations::update_to_version(&mut db, version)?;
+        }
+
+        Ok(MigrationStatus {
+            current_version: version,
+            latest_version: migrations::LATEST_VERSION,
+        })
+    }
+
+    /// Returns the path of the copy of the database at `database_filepath` saved before migrating
+    /// it from schema `version`.
+    pub fn backup_path(database_filepath: &str, version: usize) -> PathBuf {
+        PathBuf::from(format!("{database_filepath}.v{version}.bak"))
+    }
+
+    /// Copies the database at `database_filepath`, currently at schema `version`, to its backup
+    /// path. An earlier backup of the same version is overwritten.
+    fn backup(db: &Connection, database_filepath: &str, version: usize) -> Result<(), StoreError> {
+        // Fold the write-ahead log back into the database file, so that the copy is complete
+        db.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        let backup_path = Self::backup_path(database_filepath, version);
+        fs::copy(database_filepath, &backup_path)
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))?;
+        info!(
+            "Saved a copy of the store at schema version {version} to {}",
+            backup_path.display()
+        );
+
+        Ok(())
+    }
+
+    /// Brings the database schema up to date and normalizes data left behind by older versions.
+    /// Each normalization scans every note, so it only runs when migrating from a version older
+    /// than the one introducing the table it fills.
+    fn migrate(db: &mut Connection) -> Result<(), StoreError> {
+        let previous_version = migrations::current_version(db)?;
+        migrations::update_to_latest(db)?;
+
+        // Normalize inclusion proofs that were stored in full before the note inclusion paths
+        // table existed, move the scripts of notes to the note scripts table, and index notes
+        // stored before they could be filtered by assets and inputs
+        let tx = db.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        if previous_version < migrations::NOTE_INCLUSION_PATHS_VERSION {
+            Self::normalize_inclusion_proofs(&tx)?;
+        }
+        if previous_version < migrations::NOTE_SCRIPTS_VERSION {
+            Self::move_note_scripts(&tx)?;
+        }
+        if previous_version < migrations::NOTE_FILTER_INDICES_VERSION {
+            Self::index_input_notes(&tx)?;
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    // STATS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the size of the store, the number of rows of each of its tables, the range of
+    /// stored block headers and the number of notes targeting each account, to see what makes
+    /// the store grow.
+    pub fn get_stats(&self) -> Result<StoreStats, StoreError> {
+        let page_size: u64 = self
+            .db
+            .query_row("PRAGMA page_size", [], |row| row.get(0))?;
+        let page_count: u64 = self
+            .db
+            .query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let free_pages: u64 = self
+            .db
+            .query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+
+        const TABLES_QUERY: &str = "SELECT name FROM sqlite_master \
+            WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name";
+        let tables = self
+            .db
+            .prepare(TABLES_QUERY)?
+            .query_map([], |row| row.get(0))
+            .expect("no binding parameters used in query")
+            .collect::<Result<Vec<String>, _>>()?;
+        let mut table_rows = Vec::with_capacity(tables.len());
+        for table in tables {
+            // table names come from the schema, not from user input
+            let query = format!("SELECT COUNT(*) FROM \"{}\"", table.replace('"', "\"\""));
+            let rows: u64 = self.db.query_row(&query, [], |row| row.get(0))?;
+            table_rows.push((table, rows));
+        }
+
+        const BLOCK_HEADERS_QUERY: &str =
+            "SELECT MIN(block_num), MAX(block_num) FROM block_headers";
+        let block_header_range = match self.db.query_row(BLOCK_HEADERS_QUERY, [], |row| {
+            Ok((row.get::<_, Option<u32>>(0)?, row.get::<_, Option<u32>>(1)?))
+        })? {
+            (Some(oldest), Some(newest)) => Some((oldest, newest)),
+            _ => None,
+        };
+
+        const ACCOUNT_NOTES_QUERY: &str = "SELECT target.account_id, COUNT(*), \
+            COUNT(CASE WHEN note.status != 'consumed' THEN 1 END) \
+            FROM input_note_targets AS target \
+            JOIN input_notes AS note ON note.note_id = target.note_id \
+            WHERE target.account_id IS NOT NULL \
+            GROUP BY target.account_id ORDER BY target.account_id";
+        let account_notes = self
+            .db
+            .prepare(ACCOUNT_NOTES_QUERY)?
+            .query_map([], |row| {
+                let account_id: i64 = row.get(0)?;
+                Ok((account_id, row.get(1)?, row.get(2)?))
+            })
+            .expect("no binding parameters used in query")
+            .map(|result| {
+                let (account_id, notes, unconsumed_notes) = result?;
+                Ok(AccountNoteCounts {
+                    account_id: AccountId::try_from(account_id as u64)?,
+                    notes,
+                    unconsumed_notes,
+                })
+            })
+            .collect::<Result<Vec<_>, StoreError>>()?;
+
+        Ok(StoreStats {
+            schema_version: migrations::current_version(&self.db)?,
+            database_size: page_size * page_count,
+            free_size: page_size * free_pages,
+            table_rows,
+            block_header_range,
+            account_notes,
+        })
+    }
+}
+
+/// Outcome of a migration dry run performed by [Store::check_migrations].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationCheck {
+    /// Schema version of the store before migrating
+    pub current_version: usize,
+    /// Schema version the store is at after applying all pending migrations
+    pub latest_version: usize,
+    /// Time it took to apply the pending migrations to the copy of the store
+    pub duration: Duration,
+}
+
+impl MigrationCheck {
+    /// Returns the number of migrations that would be applied to the store
+    pub fn pending_migrations(&self) -> usize {
+        self.latest_version - self.current_version
+    }
+}
+
+/// Schema version of a store, as reported by [Store::migration_status].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationStatus {
+    /// Schema version of the store
+    pub current_version: usize,
+    /// Schema version the store is brought to when it is opened
+    pub latest_version: usize,
+}
+
+impl MigrationStatus {
+    /// Returns the number of migrations that are applied to the store when it is opened
+    pub fn pending_migrations(&self) -> usize {
+        self.latest_version.saturating_sub(self.current_version)
+    }
+}
+
+/// Size and contents of a store, as reported by [Store::get_stats].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreStats {
+    /// Schema version of the store
+    pub schema_version: usize,
+    /// Size of the database file in bytes, not counting its write-ahead log
+    pub database_size: u64,
+    /// Bytes of the database file taken by free pages, which `VACUUM` gives back
+    pub free_size: u64,
+    /// Number of rows of each table, sorted by table name
+    pub table_rows: Vec<(String, u64)>,
+    /// Numbers of the oldest and newest stored block headers, if any
+    pub block_header_range: Option<(u32, u32)>,
+    /// Number of input notes targeting each account, whether the client tracks it or not
+    pub account_notes: Vec<AccountNoteCounts>,
+}
+
+/// Number of input notes targeting an account, see [StoreStats]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountNoteCounts {
+    pub account_id: AccountId,
+    pub notes: u64,
+    /// Notes not consumed yet, whatever their status otherwise
+    pub unconsumed_notes: u64,
+}
+
+// PAGINATION
+// ================================================================================================
+
+/// A page of the results of a store query, used to avoid loading every record at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    /// Maximum number of records in the page
+    pub limit: u32,
+    /// Number of records skipped before the page starts
+    pub offset: u64,
+}
+
+impl Page {
+    /// Returns the page starting after the first `offset` records and containing at most `limit`
+    /// records.
+    pub const fn new(limit: u32, offset: u64) -> Self {
+        Self { limit, offset }
+    }
+
+    /// Returns the `number`th page of `limit` records, where the first page is page 1.
+    pub const fn nth(limit: u32, number: u64) -> Self {
+        Self::new(limit, limit as u64 * number.saturating_sub(1))
+    }
+
+    /// Appends the `LIMIT` and `OFFSET` clauses for this page to the provided query
+    pub(crate) fn apply(&self, query: &str) -> String {
+        format!("{query} LIMIT {} OFFSET {}", self.limit, self.offset)
+    }
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Returns the number of rows of `columns` values each that can be inserted with one statement
+pub(crate) const fn rows_per_insert(columns: usize) -> usize {
+    MAX_STATEMENT_PARAMETERS / columns
+}
+
+/// Returns the placeholders of a multi-row `VALUES` clause for `rows` rows of `columns` values
+/// each, such as `(?, ?), (?, ?)`
+pub(crate) fn values_clause(columns: usize, rows: usize) -> String {
+    let row = format!("({})", vec!["?"; columns].join(", "));
+    vec![row; rows].join(", ")
 }
 
 // TESTS
@@ -50,10 +639,11 @@ pub mod tests {
         config::{ClientConfig, RpcConfig},
     };
 
-    use super::{migrations, Store};
+    use super::{migrations, Store, StoreConfig, StoreError};
 
     pub fn create_test_client() -> Client {
         let client_config = ClientConfig {
+            data_directory: temp_dir(),
             store: create_test_store_path()
                 .into_os_string()
                 .into_string()
@@ -61,6 +651,12 @@ pub mod tests {
                 .try_into()
                 .unwrap(),
             rpc: RpcConfig::default(),
+            notifications: Default::default(),
+            retry: Default::default(),
+            wallets: Default::default(),
+            note_selection: Default::default(),
+            transactions: Default::default(),
+            faucet: Default::default(),
         };
 
         Client::new(client_config).unwrap()
@@ -79,4 +675,1429 @@ pub mod tests {
 
         Store { db }
     }
+
+    /// Records a submitted transaction of `account_id` that consumed the specified notes and
+    /// created the specified orders. The mock executor can neither mint assets nor consume notes
+    /// (see `test_mint_transaction`), so client tests record such transactions without executing
+    /// them.
+    pub(crate) fn insert_submitted_transaction(
+        store: &mut Store,
+        transaction_id: objects::Digest,
+        account_id: objects::accounts::AccountId,
+        consumed_note_ids: &[objects::notes::NoteId],
+        orders: &[super::orders::OrderRecord],
+    ) {
+        use objects::transaction::OutputNotes;
+
+        use crate::client::transactions::{TransactionRecord, TransactionStatus};
+
+        let transaction = TransactionRecord::new(
+            transaction_id,
+            account_id,
+            Default::default(),
+            Default::default(),
+            consumed_note_ids
+                .iter()
+                .map(|note_id| note_id.inner())
+                .collect(),
+            OutputNotes::new(Vec::new()).unwrap(),
+            None,
+            0,
+            TransactionStatus::Submitted,
+        );
+        store.insert_transaction_record(&transaction).unwrap();
+
+        let tx = store.write_transaction().unwrap();
+        Store::insert_orders_tx(&tx, orders).unwrap();
+        tx.commit().unwrap();
+    }
+
+    #[test]
+    fn check_migrations_leaves_store_untouched() {
+        let store_path = create_test_store_path();
+        let db = Connection::open(&store_path).unwrap();
+        db.execute_batch("CREATE TABLE placeholder (id INTEGER)")
+            .unwrap();
+
+        let check =
+            Store::check_migrations(store_path.to_str().unwrap().try_into().unwrap()).unwrap();
+        assert_eq!(check.current_version, 0);
+        assert!(check.pending_migrations() > 0);
+
+        // the original database was not migrated
+        assert_eq!(migrations::current_version(&db).unwrap(), 0);
+    }
+
+    /// Data written by the first version of the client, covering the tables later migrations
+    /// move or reshape
+    const INITIAL_SCHEMA_DATA: &str = "
+        INSERT INTO account_code VALUES (x'01', x'00', x'00');
+        INSERT INTO account_storage VALUES (x'02', x'00');
+        INSERT INTO account_vaults VALUES (x'03', x'00');
+        INSERT INTO accounts VALUES (42, x'01', x'02', x'03', 0, true, x'00');
+        UPDATE state_sync SET block_num = 7, tags = '[5, 9]';
+        INSERT INTO transaction_scripts VALUES ('0xabc', x'00');
+        INSERT INTO transactions
+            (id, account_id, init_account_state, final_account_state, script_hash, script_inputs, block_num)
+            VALUES ('0x01', 42, x'00', x'00', '0xabc', '[]', 3);
+    ";
+
+    #[test]
+    fn latest_version_matches_migrations() {
+        let store = create_test_store();
+        assert_eq!(
+            migrations::current_version(&store.db).unwrap(),
+            migrations::LATEST_VERSION
+        );
+    }
+
+    #[test]
+    fn old_stores_are_migrated_forward() {
+        let store_path = create_test_store_path();
+        let mut db = Connection::open(&store_path).unwrap();
+        migrations::update_to_version(&mut db, 1).unwrap();
+        db.execute_batch(INITIAL_SCHEMA_DATA).unwrap();
+        drop(db);
+
+        let config: StoreConfig = store_path.to_str().unwrap().try_into().unwrap();
+        assert_eq!(
+            Store::migration_status(config.clone())
+                .unwrap()
+                .pending_migrations(),
+            migrations::LATEST_VERSION - 1
+        );
+        let store = Store::new(config.clone()).unwrap();
+        assert_eq!(
+            Store::migration_status(config)
+                .unwrap()
+                .pending_migrations(),
+            0
+        );
+
+        // tags tracked by the user are kept, and the account's tag is registered for it
+        assert_eq!(store.get_note_tags().unwrap(), vec![5, 9, 42]);
+        assert_eq!(store.get_sync_height().unwrap(), 7);
+        let (stage, has_script): (String, bool) = store
+            .db
+            .query_row(
+                "SELECT stage, script_id IS NOT NULL FROM transactions WHERE id = '0x01'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(stage, "submitted");
+        assert!(has_script);
+
+        // a copy of the store was saved before migrating it
+        let backup_path = Store::backup_path(store_path.to_str().unwrap(), 1);
+        let backup = Connection::open(backup_path).unwrap();
+        assert_eq!(migrations::current_version(&backup).unwrap(), 1);
+    }
+
+    #[test]
+    fn transaction_scripts_are_shared_by_transactions_once_migrated() {
+        let mut db = Connection::open(create_test_store_path()).unwrap();
+        migrations::update_to_version(&mut db, 12).unwrap();
+        db.execute_batch(
+            "
+            INSERT INTO transaction_scripts VALUES ('0xabc', x'aa'), ('0xdef', x'bb');
+            INSERT INTO transactions
+                (id, account_id, init_account_state, final_account_state, script_hash, script_inputs, block_num)
+                VALUES ('0x01', 42, x'00', x'00', '0xabc', '[1]', 3),
+                ('0x02', 42, x'00', x'00', '0xabc', '[1]', 4),
+                ('0x03', 43, x'00', x'00', '0xabc', '[2]', 4),
+                ('0x04', 42, x'00', x'00', '0xdef', '[1]', 5),
+                ('0x05', 42, x'00', x'00', NULL, NULL, 6);
+            ",
+        )
+        .unwrap();
+
+        migrations::update_to_version(&mut db, 13).unwrap();
+
+        // transactions executing the same script with the same inputs reference the same row, and
+        // the transactions keep their order
+        let transactions: Vec<(String, Option<i64>, Option<String>, Option<String>)> = db
+            .prepare(
+                "SELECT tx.id, tx.script_id, script.script_hash, script.inputs FROM transactions AS tx \
+                LEFT JOIN transaction_scripts AS script ON tx.script_id = script.id ORDER BY tx.rowid",
+            )
+            .unwrap()
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            transactions
+                .iter()
+                .map(|(id, _, script_hash, inputs)| {
+                    (id.as_str(), script_hash.as_deref(), inputs.as_deref())
+                })
+                .collect::<Vec<_>>(),
+            vec![
+                ("0x01", Some("0xabc"), Some("[1]")),
+                ("0x02", Some("0xabc"), Some("[1]")),
+                ("0x03", Some("0xabc"), Some("[2]")),
+                ("0x04", Some("0xdef"), Some("[1]")),
+                ("0x05", None, None),
+            ]
+        );
+        assert_eq!(transactions[0].1, transactions[1].1);
+        assert_ne!(transactions[0].1, transactions[2].1);
+
+        let programs: Vec<Vec<u8>> = db
+            .prepare("SELECT program FROM transaction_scripts ORDER BY program")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(programs, vec![vec![0xaa], vec![0xaa], vec![0xbb]]);
+    }
+
+    #[test]
+    fn migrations_can_be_reverted() {
+        let store_path = create_test_store_path();
+        let mut db = Connection::open(&store_path).unwrap();
+        migrations::update_to_version(&mut db, 1).unwrap();
+        db.execute_batch(INITIAL_SCHEMA_DATA).unwrap();
+
+        for version in (0..migrations::LATEST_VERSION).rev() {
+            migrations::update_to_latest(&mut db).unwrap();
+            migrations::update_to_version(&mut db, version).unwrap();
+            assert_eq!(migrations::current_version(&db).unwrap(), version);
+
+            if version == 1 {
+                let tags: String = db
+                    .query_row("SELECT tags FROM state_sync", [], |row| row.get(0))
+                    .unwrap();
+                assert_eq!(tags, "[5,9]");
+            }
+        }
+        drop(db);
+
+        let config: StoreConfig = store_path.to_str().unwrap().try_into().unwrap();
+        Store::migrate_to(config.clone(), 4).unwrap();
+        assert_eq!(
+            Store::migration_status(config.clone())
+                .unwrap()
+                .current_version,
+            4
+        );
+        assert!(matches!(
+            Store::migrate_to(config, migrations::LATEST_VERSION + 1),
+            Err(StoreError::InvalidSchemaVersion(..))
+        ));
+    }
+
+    #[test]
+    fn read_only_stores_reject_writes() {
+        use super::sync::NoteTagSource;
+
+        let store_path = create_test_store_path();
+        assert!(Store::open_read_only(&store_path).is_err());
+
+        let config: StoreConfig = store_path.to_str().unwrap().try_into().unwrap();
+        let mut store = Store::new(config).unwrap();
+        store.add_note_tag(42, NoteTagSource::User, None).unwrap();
+
+        // the writing connection stays open, as a running client's would
+        let mut read_only_store = Store::open_read_only(&store_path).unwrap();
+        assert_eq!(read_only_store.get_note_tags().unwrap(), vec![42]);
+        assert!(read_only_store
+            .add_note_tag(43, NoteTagSource::User, None)
+            .is_err());
+
+        store.add_note_tag(43, NoteTagSource::User, None).unwrap();
+        assert_eq!(read_only_store.get_note_tags().unwrap(), vec![42, 43]);
+    }
+
+    #[test]
+    fn reset_archives_the_store() {
+        use super::sync::NoteTagSource;
+
+        let config: StoreConfig = create_test_store_path()
+            .to_str()
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let mut store = Store::new(config.clone()).unwrap();
+        store.add_note_tag(42, NoteTagSource::User, None).unwrap();
+        drop(store);
+
+        let archive_path = Store::reset(config.clone()).unwrap().unwrap();
+        let store = Store::new(config).unwrap();
+        assert!(store.get_note_tags().unwrap().is_empty());
+
+        let archive = Connection::open(archive_path).unwrap();
+        let tags: usize = archive
+            .query_row("SELECT COUNT(*) FROM note_tags", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(tags, 1);
+    }
+
+    #[test]
+    fn relocated_stores_keep_their_data() {
+        use super::sync::NoteTagSource;
+
+        let from = create_test_store_path();
+        let to = temp_dir()
+            .join(Uuid::new_v4().to_string())
+            .join("store.sqlite3");
+        let mut store = Store::new(from.to_str().unwrap().try_into().unwrap()).unwrap();
+        store.add_note_tag(42, NoteTagSource::User, None).unwrap();
+        drop(store);
+
+        Store::relocate(&from, &to).unwrap();
+        assert!(!from.exists());
+        let store = Store::new(to.to_str().unwrap().try_into().unwrap()).unwrap();
+        assert_eq!(store.get_note_tags().unwrap().len(), 1);
+
+        // stores are never overwritten
+        assert!(Store::relocate(&create_test_store_path(), &to).is_err());
+    }
+
+    #[test]
+    fn is_encrypted_detects_plaintext_stores() {
+        let store_path = create_test_store_path();
+        assert!(!Store::is_encrypted(&store_path).unwrap());
+
+        Store::new(store_path.to_str().unwrap().try_into().unwrap()).unwrap();
+        assert!(!Store::is_encrypted(&store_path).unwrap());
+
+        std::fs::write(&store_path, rand::random::<[u8; 32]>()).unwrap();
+        assert!(Store::is_encrypted(&store_path).unwrap());
+    }
+
+    #[test]
+    fn only_new_stores_create_their_directory() {
+        let directory = temp_dir().join(Uuid::new_v4().to_string());
+        let store_path = directory.join("store.sqlite3");
+
+        // reading the state of a store that does not exist leaves the file system alone
+        assert!(!Store::is_encrypted(&store_path).unwrap());
+        assert!(Store::migration_status(store_path.to_str().unwrap().try_into().unwrap()).is_err());
+        assert!(!directory.exists());
+
+        Store::new(store_path.to_str().unwrap().try_into().unwrap()).unwrap();
+        assert!(store_path.exists());
+    }
+
+    #[test]
+    fn busy_store_errors_are_retryable() {
+        use crate::errors::{ErrorCategory, StoreError};
+
+        let store_path = create_test_store_path();
+        let store = Store::new(store_path.to_str().unwrap().try_into().unwrap()).unwrap();
+        store.db.execute_batch("BEGIN IMMEDIATE").unwrap();
+
+        // a second writer gives up right away while the first one holds the lock
+        let db = Connection::open(&store_path).unwrap();
+        let err = db
+            .execute_batch("BEGIN IMMEDIATE")
+            .map_err(StoreError::from)
+            .unwrap_err();
+        assert!(matches!(err, StoreError::DatabaseBusy(_)));
+        assert_eq!(err.category(), ErrorCategory::Retryable);
+    }
+
+    #[test]
+    fn importing_a_note_completes_its_partial_record() {
+        use mock::mock::{
+            account::MockAccountType, notes::AssetPreservationStatus, transaction::mock_inputs,
+        };
+        use objects::Digest;
+
+        use super::notes::{NoteOrigin, PartialNoteRecord};
+
+        let mut store = create_test_store();
+        let transaction_inputs = mock_inputs(
+            MockAccountType::StandardExisting,
+            AssetPreservationStatus::Preserved,
+        );
+        let note = transaction_inputs.input_notes().get_note(0).note().clone();
+
+        let partial_note = PartialNoteRecord {
+            note_id: note.id(),
+            recipient: Digest::default(),
+            assets: note.assets().clone(),
+            metadata: *note.metadata(),
+            transaction_id: Digest::default(),
+        };
+        let tx = store.write_transaction().unwrap();
+        Store::insert_partial_output_note_tx(&tx, &partial_note).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(
+            store.get_partial_output_notes().unwrap(),
+            vec![partial_note]
+        );
+
+        store
+            .insert_input_note(&note.into(), &NoteOrigin::Imported)
+            .unwrap();
+        assert!(store.get_partial_output_notes().unwrap().is_empty());
+    }
+
+    #[test]
+    fn locked_notes_cannot_be_locked_by_other_transactions() {
+        use crypto::Felt;
+        use mock::mock::{
+            account::MockAccountType, notes::AssetPreservationStatus, transaction::mock_inputs,
+        };
+        use objects::Digest;
+
+        use super::notes::NoteOrigin;
+        use crate::errors::StoreError;
+
+        let mut store = create_test_store();
+        let transaction_inputs = mock_inputs(
+            MockAccountType::StandardExisting,
+            AssetPreservationStatus::Preserved,
+        );
+        let note = transaction_inputs.input_notes().get_note(0).note().clone();
+        let note_ids = [note.id()];
+        store
+            .insert_input_note(&note.into(), &NoteOrigin::Imported)
+            .unwrap();
+
+        let first_transaction = Digest::new([Felt::new(1); 4]);
+        let second_transaction = Digest::new([Felt::new(2); 4]);
+
+        let tx = store.write_transaction().unwrap();
+        Store::lock_notes_tx(&tx, &note_ids, first_transaction).unwrap();
+        tx.commit().unwrap();
+        assert!(matches!(
+            store.check_notes_unlocked(&note_ids),
+            Err(StoreError::NoteLocked(_, holder)) if holder == first_transaction
+        ));
+
+        let tx = store.write_transaction().unwrap();
+        assert!(matches!(
+            Store::lock_notes_tx(&tx, &note_ids, second_transaction),
+            Err(StoreError::NoteLocked(..))
+        ));
+        drop(tx);
+
+        // releasing the lock lets another transaction take it
+        let tx = store.write_transaction().unwrap();
+        Store::unlock_notes_tx(&tx, first_transaction).unwrap();
+        Store::lock_notes_tx(&tx, &note_ids, second_transaction).unwrap();
+        tx.commit().unwrap();
+        assert!(matches!(
+            store.check_notes_unlocked(&note_ids),
+            Err(StoreError::NoteLocked(_, holder)) if holder == second_transaction
+        ));
+    }
+
+    #[test]
+    fn stats_count_rows_and_notes_per_account() {
+        use crypto::{rand::RpoRandomCoin, Felt, FieldElement};
+        use mock::constants::{ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN, ACCOUNT_ID_SENDER};
+        use objects::{accounts::AccountId, assets::FungibleAsset};
+
+        use super::{notes::NoteOrigin, AccountNoteCounts};
+        use crate::client::notes::create_p2id_timelock_note;
+
+        let mut store = create_test_store();
+        let stats = store.get_stats().unwrap();
+        assert_eq!(stats.schema_version, migrations::LATEST_VERSION);
+        assert!(stats.database_size > 0);
+        assert!(stats.table_rows.contains(&("input_notes".to_string(), 0)));
+        assert_eq!(stats.block_header_range, None);
+        assert!(stats.account_notes.is_empty());
+
+        let account_id = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let asset = FungibleAsset::new(faucet_id, 100).unwrap().into();
+        let note = create_p2id_timelock_note(
+            account_id,
+            account_id,
+            &[asset],
+            10,
+            None,
+            RpoRandomCoin::new([Felt::ZERO; 4]),
+        )
+        .unwrap();
+        store
+            .insert_input_note(&note.into(), &NoteOrigin::Imported)
+            .unwrap();
+
+        let stats = store.get_stats().unwrap();
+        assert!(stats.table_rows.contains(&("input_notes".to_string(), 1)));
+        assert_eq!(
+            stats.account_notes,
+            [AccountNoteCounts {
+                account_id,
+                notes: 1,
+                unconsumed_notes: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn timelocked_notes_are_consumable_past_their_unlock_height() {
+        use crypto::{rand::RpoRandomCoin, Felt, FieldElement};
+        use mock::constants::{ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN, ACCOUNT_ID_SENDER};
+        use objects::{accounts::AccountId, assets::FungibleAsset};
+
+        use super::notes::{NoteFilter, NoteOrigin};
+        use crate::client::notes::{create_p2id_timelock_note, decode_unlock_height};
+
+        let mut store = create_test_store();
+        let account_id = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let asset = FungibleAsset::new(faucet_id, 100).unwrap().into();
+        let note = create_p2id_timelock_note(
+            account_id,
+            account_id,
+            &[asset],
+            10,
+            Some("vesting"),
+            RpoRandomCoin::new([Felt::ZERO; 4]),
+        )
+        .unwrap();
+        assert_eq!(decode_unlock_height(&note), Some(10));
+        store
+            .insert_input_note(&note.into(), &NoteOrigin::Imported)
+            .unwrap();
+        store
+            .db
+            .execute("UPDATE input_notes SET status = 'committed'", [])
+            .unwrap();
+
+        let consumable_notes = |store: &Store| {
+            store
+                .get_input_notes(NoteFilter::new().consumable_by(account_id))
+                .unwrap()
+                .len()
+        };
+        store
+            .db
+            .execute("UPDATE state_sync SET block_num = 10", [])
+            .unwrap();
+        assert_eq!(consumable_notes(&store), 0);
+
+        store
+            .db
+            .execute("UPDATE state_sync SET block_num = 11", [])
+            .unwrap();
+        assert_eq!(consumable_notes(&store), 1);
+    }
+
+    #[test]
+    fn note_provenance_records_the_origin_and_commit() {
+        use crypto::Felt;
+        use mock::mock::{
+            account::MockAccountType, notes::AssetPreservationStatus, transaction::mock_inputs,
+        };
+        use objects::Digest;
+
+        use super::notes::{InputNoteRecord, NoteOrigin};
+        use crate::errors::StoreError;
+
+        let mut store = create_test_store();
+        let transaction_inputs = mock_inputs(
+            MockAccountType::StandardExisting,
+            AssetPreservationStatus::Preserved,
+        );
+        let committed_note = transaction_inputs.input_notes().get_note(0).clone();
+        let pending_note = transaction_inputs.input_notes().get_note(1).note().clone();
+        let transaction_id = Digest::new([Felt::new(3); 4]);
+
+        assert!(matches!(
+            store.get_note_provenance(pending_note.id()),
+            Err(StoreError::InputNoteNotFound(_))
+        ));
+
+        store
+            .insert_input_note(
+                &InputNoteRecord::from(committed_note.clone()),
+                &NoteOrigin::File("note.mno".to_string()),
+            )
+            .unwrap();
+        store
+            .insert_input_note(
+                &pending_note.clone().into(),
+                &NoteOrigin::Transaction(transaction_id),
+            )
+            .unwrap();
+
+        let provenance = store.get_note_provenance(committed_note.id()).unwrap();
+        assert_eq!(
+            provenance.origin,
+            Some(NoteOrigin::File("note.mno".to_string()))
+        );
+        assert!(provenance.created_at.is_some());
+        assert_eq!(
+            provenance.commit_height,
+            Some(committed_note.proof().origin().block_num)
+        );
+        // the note was imported committed, syncing never found it
+        assert_eq!(provenance.committed_at, None);
+
+        let provenance = store.get_note_provenance(pending_note.id()).unwrap();
+        assert_eq!(
+            provenance.origin,
+            Some(NoteOrigin::Transaction(transaction_id))
+        );
+        assert_eq!(provenance.commit_height, None);
+    }
+
+    #[test]
+    fn note_timelines_read_the_timestamps_of_tracked_blocks() {
+        use crypto::{merkle::MmrPeaks, StarkField};
+        use mock::mock::{
+            account::MockAccountType, block::mock_block_header, notes::AssetPreservationStatus,
+            transaction::mock_inputs,
+        };
+
+        use super::{
+            chain_data::BlockTime,
+            notes::{InputNoteRecord, NoteOrigin},
+        };
+
+        let mut store = create_test_store();
+        let transaction_inputs = mock_inputs(
+            MockAccountType::StandardExisting,
+            AssetPreservationStatus::Preserved,
+        );
+        let committed_note = transaction_inputs.input_notes().get_note(0).clone();
+        let pending_note = transaction_inputs.input_notes().get_note(1).note().clone();
+        let commit_height = committed_note.proof().origin().block_num;
+        for note in [
+            InputNoteRecord::from(committed_note.clone()),
+            pending_note.clone().into(),
+        ] {
+            store
+                .insert_input_note(&note, &NoteOrigin::Imported)
+                .unwrap();
+        }
+
+        // the header of the block the note was committed in is not tracked yet
+        let timelines = store.get_note_timelines().unwrap();
+        assert_eq!(timelines.len(), 2);
+        assert_eq!(timelines[0].note_id, committed_note.id());
+        assert_eq!(
+            timelines[0].committed,
+            Some(BlockTime {
+                block_num: commit_height,
+                timestamp: None
+            })
+        );
+        assert_eq!(timelines[1].note_id, pending_note.id());
+        assert_eq!(timelines[1].committed, None);
+        assert_eq!(timelines[1].consumed, None);
+
+        let block_header = mock_block_header(commit_height, None, None, &[]);
+        let tx = store.db.transaction().unwrap();
+        Store::insert_block_header(
+            &tx,
+            block_header,
+            MmrPeaks::new(0, Vec::new()).unwrap(),
+            true,
+        )
+        .unwrap();
+        tx.commit().unwrap();
+
+        let timelines = store.get_note_timelines().unwrap();
+        assert_eq!(
+            timelines[0].committed,
+            Some(BlockTime {
+                block_num: commit_height,
+                timestamp: Some(block_header.timestamp().as_int())
+            })
+        );
+    }
+
+    #[test]
+    fn transaction_commit_times_read_the_timestamps_of_tracked_blocks() {
+        use crypto::{merkle::MmrPeaks, Felt, FieldElement, StarkField};
+        use mock::{constants::ACCOUNT_ID_SENDER, mock::block::mock_block_header};
+        use objects::{accounts::AccountId, Digest};
+
+        use super::chain_data::BlockTime;
+
+        let mut store = create_test_store();
+        let account_id = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+        let committed_id = Digest::new([Felt::ONE; 4]);
+        let pending_id = Digest::new([Felt::new(2); 4]);
+        insert_submitted_transaction(&mut store, committed_id, account_id, &[], &[]);
+        insert_submitted_transaction(&mut store, pending_id, account_id, &[], &[]);
+        store
+            .db
+            .execute(
+                "UPDATE transactions SET commit_height = 5 WHERE id = ?",
+                rusqlite::params![String::from(committed_id)],
+            )
+            .unwrap();
+
+        // only committed transactions have a commit time, without a timestamp until the header
+        // of their block is tracked
+        let commit_times = store.get_transaction_commit_times().unwrap();
+        assert_eq!(commit_times.len(), 1);
+        assert_eq!(
+            commit_times.get(&committed_id),
+            Some(&BlockTime {
+                block_num: 5,
+                timestamp: None
+            })
+        );
+
+        let block_header = mock_block_header(5, None, None, &[]);
+        let tx = store.db.transaction().unwrap();
+        Store::insert_block_header(
+            &tx,
+            block_header,
+            MmrPeaks::new(0, Vec::new()).unwrap(),
+            true,
+        )
+        .unwrap();
+        tx.commit().unwrap();
+
+        let commit_times = store.get_transaction_commit_times().unwrap();
+        assert_eq!(
+            commit_times.get(&committed_id),
+            Some(&BlockTime {
+                block_num: 5,
+                timestamp: Some(block_header.timestamp().as_int())
+            })
+        );
+        assert_eq!(commit_times.get(&pending_id), None);
+    }
+
+    #[test]
+    fn inserting_a_stored_note_again_merges_its_inclusion_proof() {
+        use crypto::Felt;
+        use mock::mock::{
+            account::MockAccountType, notes::AssetPreservationStatus, transaction::mock_inputs,
+        };
+        use objects::Digest;
+
+        use super::notes::{InputNoteFilter, InputNoteRecord, NoteOrigin};
+
+        let mut store = create_test_store();
+        let transaction_inputs = mock_inputs(
+            MockAccountType::StandardExisting,
+            AssetPreservationStatus::Preserved,
+        );
+        let committed_note = transaction_inputs.input_notes().get_note(0).clone();
+        let pending_note = InputNoteRecord::from(committed_note.note().clone());
+        let transaction_id = Digest::new([Felt::new(5); 4]);
+        let committed_notes = |store: &Store| {
+            store
+                .get_input_notes(InputNoteFilter::Committed)
+                .unwrap()
+                .len()
+        };
+
+        // inserting the same pending note twice, as after a crash, leaves a single record
+        for _ in 0..2 {
+            store
+                .insert_input_note(&pending_note, &NoteOrigin::Transaction(transaction_id))
+                .unwrap();
+        }
+        assert_eq!(
+            store.get_input_notes(InputNoteFilter::All).unwrap().len(),
+            1
+        );
+        assert_eq!(committed_notes(&store), 0);
+
+        // the pending note gets the inclusion proof of the committed one, and keeps its origin
+        store
+            .insert_input_note(&committed_note.clone().into(), &NoteOrigin::Imported)
+            .unwrap();
+        assert_eq!(committed_notes(&store), 1);
+        let stored_note = store.get_input_note_by_id(committed_note.id()).unwrap();
+        assert_eq!(
+            stored_note
+                .inclusion_proof()
+                .map(|proof| proof.origin().block_num),
+            Some(committed_note.proof().origin().block_num)
+        );
+        let provenance = store.get_note_provenance(committed_note.id()).unwrap();
+        assert_eq!(
+            provenance.origin,
+            Some(NoteOrigin::Transaction(transaction_id))
+        );
+        assert_eq!(
+            provenance.commit_height,
+            Some(committed_note.proof().origin().block_num)
+        );
+        assert!(provenance.committed_at.is_some());
+
+        // older data does not overwrite the committed note
+        store
+            .insert_input_note(&pending_note, &NoteOrigin::Imported)
+            .unwrap();
+        assert_eq!(committed_notes(&store), 1);
+        assert!(store
+            .get_input_note_by_id(committed_note.id())
+            .unwrap()
+            .inclusion_proof()
+            .is_some());
+    }
+
+    #[test]
+    fn inserted_notes_only_normalize_their_own_inclusion_proof() {
+        use crypto::merkle::MmrPeaks;
+        use mock::mock::{
+            account::MockAccountType, block::mock_block_header, notes::AssetPreservationStatus,
+            transaction::mock_inputs,
+        };
+        use objects::utils::collections::BTreeSet;
+
+        use super::notes::NoteOrigin;
+
+        let mut store = create_test_store();
+        let transaction_inputs = mock_inputs(
+            MockAccountType::StandardExisting,
+            AssetPreservationStatus::Preserved,
+        );
+        let first_note = transaction_inputs.input_notes().get_note(0).clone();
+        let second_note = transaction_inputs.input_notes().get_note(1).clone();
+        let stored_in_full = |store: &Store, note_id: objects::notes::NoteId| -> bool {
+            store
+                .db
+                .query_row(
+                    "SELECT inclusion_proof IS NOT NULL FROM input_notes WHERE note_id = ?",
+                    [note_id.inner().to_string()],
+                    |row| row.get(0),
+                )
+                .unwrap()
+        };
+
+        // the proof of a note whose block header is not tracked is stored in full
+        store
+            .insert_input_note(&first_note.clone().into(), &NoteOrigin::Imported)
+            .unwrap();
+        assert!(stored_in_full(&store, first_note.id()));
+
+        let block_nums: BTreeSet<u32> = [&first_note, &second_note]
+            .iter()
+            .map(|note| note.proof().origin().block_num)
+            .collect();
+        let tx = store.write_transaction().unwrap();
+        for block_num in block_nums {
+            let block_header = mock_block_header(block_num, None, None, &[]);
+            Store::insert_block_header(&tx, block_header, MmrPeaks::new(0, vec![]).unwrap(), true)
+                .unwrap();
+        }
+        tx.commit().unwrap();
+
+        // inserting another note only normalizes the proof of that note
+        store
+            .insert_input_note(&second_note.clone().into(), &NoteOrigin::Imported)
+            .unwrap();
+        assert!(!stored_in_full(&store, second_note.id()));
+        assert!(stored_in_full(&store, first_note.id()));
+
+        // the proofs of the notes inserted before their block header are normalized once the
+        // block is synced
+        let tx = store.write_transaction().unwrap();
+        let block_num = first_note.proof().origin().block_num;
+        assert_eq!(
+            Store::normalize_block_inclusion_proofs(&tx, block_num).unwrap(),
+            1
+        );
+        tx.commit().unwrap();
+        assert!(!stored_in_full(&store, first_note.id()));
+    }
+
+    #[test]
+    fn queued_transactions_are_kept_in_order_until_discarded() {
+        use crypto::Felt;
+        use objects::Digest;
+
+        use crate::errors::StoreError;
+
+        let mut store = create_test_store();
+        let transaction_ids: Vec<Digest> =
+            (1..=3).map(|id| Digest::new([Felt::new(id); 4])).collect();
+        for (transaction_id, stage) in
+            transaction_ids
+                .iter()
+                .zip(["proven", "executed", "submitted"])
+        {
+            store
+                .db
+                .execute(
+                    "INSERT INTO transactions (id, account_id, init_account_state, final_account_state, \
+                    output_notes, block_num, stage) VALUES (?, 1, '', '', x'', 0, ?)",
+                    rusqlite::params![String::from(*transaction_id), stage],
+                )
+                .unwrap();
+        }
+
+        store.queue_transaction(transaction_ids[1]).unwrap();
+        store.queue_transaction(transaction_ids[0]).unwrap();
+        store.queue_transaction(transaction_ids[1]).unwrap();
+        assert_eq!(
+            store.get_queued_transactions().unwrap(),
+            vec![transaction_ids[1], transaction_ids[0]]
+        );
+
+        // submitted transactions cannot be queued
+        assert!(matches!(
+            store.queue_transaction(transaction_ids[2]),
+            Err(StoreError::TransactionNotFound(_))
+        ));
+
+        store.discard_transaction(transaction_ids[1]).unwrap();
+        assert_eq!(
+            store.get_queued_transactions().unwrap(),
+            vec![transaction_ids[0]]
+        );
+    }
+
+    #[test]
+    fn discarded_transactions_stop_tracking_their_notes() {
+        use crypto::Felt;
+        use mock::mock::{
+            account::MockAccountType, notes::AssetPreservationStatus, transaction::mock_inputs,
+        };
+        use objects::Digest;
+
+        use super::notes::NoteOrigin;
+        use crate::errors::StoreError;
+
+        let mut store = create_test_store();
+        let transaction_ids: Vec<Digest> =
+            (1..=3).map(|id| Digest::new([Felt::new(id); 4])).collect();
+        for (index, transaction_id) in transaction_ids.iter().enumerate() {
+            let commit_height = (index == 2).then_some(5);
+            store
+                .db
+                .execute(
+                    "INSERT INTO transactions (id, account_id, init_account_state, final_account_state, \
+                    output_notes, block_num, commit_height, stage) VALUES (?, 1, '', '', x'', 0, ?, 'submitted')",
+                    rusqlite::params![String::from(*transaction_id), commit_height],
+                )
+                .unwrap();
+        }
+        // the first two transactions changed the same account, one after the other
+        for (nonce, transaction_id) in transaction_ids[..2].iter().enumerate() {
+            store
+                .db
+                .execute(
+                    "INSERT INTO account_deltas (account_id, transaction_id, nonce, delta) VALUES (1, ?, ?, x'')",
+                    rusqlite::params![String::from(*transaction_id), nonce + 1],
+                )
+                .unwrap();
+        }
+        let transaction_inputs = mock_inputs(
+            MockAccountType::StandardExisting,
+            AssetPreservationStatus::Preserved,
+        );
+        let created_note = transaction_inputs.input_notes().get_note(0).note().clone();
+        store
+            .insert_input_note(
+                &created_note.clone().into(),
+                &NoteOrigin::Transaction(transaction_ids[0]),
+            )
+            .unwrap();
+
+        assert!(matches!(
+            store.discard_transaction(transaction_ids[2]),
+            Err(StoreError::TransactionNotDiscardable(..))
+        ));
+        assert!(matches!(
+            store.discard_transaction(transaction_ids[0]),
+            Err(StoreError::TransactionNotDiscardable(..))
+        ));
+
+        store.discard_transaction(transaction_ids[1]).unwrap();
+        store.discard_transaction(transaction_ids[0]).unwrap();
+        assert!(matches!(
+            store.get_transaction_status(transaction_ids[0]),
+            Err(StoreError::TransactionNotFound(_))
+        ));
+        assert!(matches!(
+            store.get_input_note_by_id(created_note.id()),
+            Err(StoreError::InputNoteNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn account_proofs_are_replaced() {
+        use crypto::{merkle::MerklePath, Felt};
+        use objects::{accounts::AccountId, Digest};
+
+        use super::accounts::AccountProof;
+        use crate::errors::StoreError;
+
+        let mut store = create_test_store();
+        let account_id = AccountId::from_hex("0x168187d729b31a84").unwrap();
+        assert!(matches!(
+            store.get_account_proof(account_id),
+            Err(StoreError::AccountProofNotFound(_))
+        ));
+
+        for block_num in [4, 7] {
+            let account_proof = AccountProof {
+                account_id,
+                block_num,
+                account_hash: Digest::new([Felt::new(block_num as u64); 4]),
+                merkle_path: MerklePath::new(vec![Digest::default(); 3]),
+            };
+            store.insert_account_proof(&account_proof).unwrap();
+            assert_eq!(store.get_account_proof(account_id).unwrap(), account_proof);
+        }
+    }
+
+    #[test]
+    fn desynchronized_accounts_are_cleared_by_replacing_their_state() {
+        use crypto::{dsa::rpo_falcon512::KeyPair, Felt, FieldElement};
+        use miden_lib::transaction::TransactionKernel;
+        use mock::{
+            constants::{generate_account_seed, AccountSeedType},
+            mock::account::mock_account,
+        };
+
+        use super::accounts::{AuthInfo, DesynchronizedAccount};
+
+        let mut store = create_test_store();
+        let assembler = TransactionKernel::assembler();
+        let (account_id, account_seed) =
+            generate_account_seed(AccountSeedType::RegularAccountUpdatableCodeOnChain);
+        let account = mock_account(Some(account_id.into()), Felt::ONE, None, &assembler);
+        let key_pair = KeyPair::new().unwrap();
+        store
+            .insert_account(&account, account_seed, &AuthInfo::RpoFalcon512(key_pair))
+            .unwrap();
+
+        // the account was updated from another store
+        let remote_account = mock_account(Some(account_id.into()), Felt::new(2), None, &assembler);
+        let desynchronized = DesynchronizedAccount {
+            account_id,
+            local_hash: account.hash(),
+            remote_hash: remote_account.hash(),
+            block_num: 8,
+        };
+        let tx = store.write_transaction().unwrap();
+        Store::insert_desynchronized_account_tx(&tx, &desynchronized).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(
+            store.get_desynchronized_accounts().unwrap(),
+            vec![desynchronized.clone()]
+        );
+        assert_eq!(
+            store.get_desynchronized_account(account_id).unwrap(),
+            Some(desynchronized)
+        );
+
+        store
+            .replace_desynchronized_account(&remote_account)
+            .unwrap();
+        assert_eq!(store.get_desynchronized_account(account_id).unwrap(), None);
+        let (account_stub, seed) = store.get_account_stub_by_id(account_id).unwrap();
+        assert_eq!(account_stub.hash(), remote_account.hash());
+        assert_eq!(seed, account_seed);
+    }
+
+    #[test]
+    fn desynchronized_accounts_are_replaced_at_the_same_or_a_lower_nonce() {
+        use crypto::{dsa::rpo_falcon512::KeyPair, Felt};
+        use miden_lib::transaction::TransactionKernel;
+        use mock::{
+            constants::{
+                generate_account_seed, AccountSeedType, ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN,
+            },
+            mock::account::mock_account,
+        };
+        use objects::{
+            accounts::{Account, AccountId},
+            assets::{AssetVault, FungibleAsset},
+        };
+
+        use super::accounts::AuthInfo;
+
+        let mut store = create_test_store();
+        let assembler = TransactionKernel::assembler();
+        let (account_id, account_seed) =
+            generate_account_seed(AccountSeedType::RegularAccountUpdatableCodeOnChain);
+        let local_account = mock_account(Some(account_id.into()), Felt::new(3), None, &assembler);
+        store
+            .insert_account(
+                &local_account,
+                account_seed,
+                &AuthInfo::RpoFalcon512(KeyPair::new().unwrap()),
+            )
+            .unwrap();
+
+        // the on-chain state diverged at the same nonce
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let remote_account = Account::new(
+            account_id,
+            AssetVault::new(&[FungibleAsset::new(faucet_id, 100).unwrap().into()]).unwrap(),
+            local_account.storage().clone(),
+            local_account.code().clone(),
+            Felt::new(3),
+        );
+        assert_ne!(remote_account.hash(), local_account.hash());
+        store
+            .replace_desynchronized_account(&remote_account)
+            .unwrap();
+        let (account_stub, seed) = store.get_account_stub_by_id(account_id).unwrap();
+        assert_eq!(account_stub.hash(), remote_account.hash());
+        assert_eq!(seed, account_seed);
+
+        // the local state moved past the on-chain one
+        let remote_account = mock_account(Some(account_id.into()), Felt::new(2), None, &assembler);
+        store
+            .replace_desynchronized_account(&remote_account)
+            .unwrap();
+        let (account_stub, seed) = store.get_account_stub_by_id(account_id).unwrap();
+        assert_eq!(account_stub.hash(), remote_account.hash());
+        assert_eq!(account_stub.nonce(), Felt::new(2));
+        assert_eq!(seed, account_seed);
+    }
+
+    #[test]
+    fn sync_target_is_kept_until_the_sync_data_is_reset() {
+        let mut store = create_test_store();
+        assert_eq!(store.get_sync_target().unwrap(), None);
+
+        // a sync was interrupted at block 7 while heading to block 12
+        store
+            .db
+            .execute(
+                "UPDATE state_sync SET block_num = 7, target_block_num = 12",
+                [],
+            )
+            .unwrap();
+        assert_eq!(store.get_sync_height().unwrap(), 7);
+        assert_eq!(store.get_sync_target().unwrap(), Some(12));
+
+        // an account delta was applied while syncing
+        store
+            .db
+            .execute(
+                "INSERT INTO account_deltas (account_id, transaction_id, nonce, delta) VALUES (1, NULL, 1, x'')",
+                [],
+            )
+            .unwrap();
+
+        store.reset_sync_data().unwrap();
+        assert_eq!(store.get_sync_height().unwrap(), 0);
+        assert_eq!(store.get_sync_target().unwrap(), None);
+        let deltas: i64 = store
+            .db
+            .query_row("SELECT COUNT(*) FROM account_deltas", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(deltas, 0);
+    }
+
+    #[test]
+    fn watched_nullifiers_are_reverted_by_rollbacks() {
+        use crypto::Felt;
+        use objects::Digest;
+
+        let mut store = create_test_store();
+        let nullifier = Digest::new([Felt::new(5); 4]);
+
+        assert!(store
+            .watch_nullifier(nullifier, Some("payment"), 3)
+            .unwrap());
+        assert!(!store.watch_nullifier(nullifier, None, 0).unwrap());
+        let watched = store.get_watched_nullifiers().unwrap();
+        assert_eq!(watched.len(), 1);
+        assert_eq!(watched[0].label.as_deref(), Some("payment"));
+        assert_eq!(watched[0].checked_height, 3);
+        assert_eq!(watched[0].consumed_height, None);
+
+        // syncing found the note consumed in block 9 while checking up to block 10
+        store
+            .db
+            .execute(
+                "UPDATE watched_nullifiers SET checked_height = 10, consumed_height = 9",
+                [],
+            )
+            .unwrap();
+        store.rollback_to_block(8).unwrap();
+        let watched = store.get_watched_nullifiers().unwrap();
+        assert_eq!(watched[0].checked_height, 8);
+        assert_eq!(watched[0].consumed_height, None);
+
+        assert!(store.unwatch_nullifier(nullifier).unwrap());
+        assert!(!store.unwatch_nullifier(nullifier).unwrap());
+        assert!(store.get_watched_nullifiers().unwrap().is_empty());
+    }
+
+    #[test]
+    fn orders_are_closed_expired_and_reverted_by_rollbacks() {
+        use crypto::Felt;
+        use mock::constants::ACCOUNT_ID_SENDER;
+        use objects::{accounts::AccountId, notes::NoteId, Digest};
+
+        use super::orders::{OrderKind, OrderRecord, OrderStatus};
+
+        let mut store = create_test_store();
+        let order = |seed: u64, kind: OrderKind, recall_height: Option<u32>| OrderRecord {
+            note_id: NoteId::from(Digest::new([Felt::new(seed); 4])),
+            nullifier: Digest::new([Felt::new(seed + 100); 4]),
+            kind,
+            account_id: AccountId::try_from(ACCOUNT_ID_SENDER).unwrap(),
+            transaction_id: Digest::new([Felt::new(7); 4]),
+            recall_height,
+            status: OrderStatus::Open,
+            closed_height: None,
+        };
+        let swap = order(1, OrderKind::Swap, None);
+        let payment = order(2, OrderKind::P2idr, Some(8));
+        let recalled_payment = order(3, OrderKind::P2idr, Some(4));
+
+        let tx = store.write_transaction().unwrap();
+        Store::insert_orders_tx(
+            &tx,
+            &[swap.clone(), payment.clone(), recalled_payment.clone()],
+        )
+        .unwrap();
+        tx.commit().unwrap();
+        assert_eq!(
+            store.get_orders().unwrap(),
+            vec![swap.clone(), payment.clone(), recalled_payment.clone()]
+        );
+
+        // the SWAP note is filled in block 5, the last payment recalled in block 9, by which the
+        // recall height of the other payment is reached
+        let tx = store.write_transaction().unwrap();
+        Store::update_orders_tx(&tx, &[(swap.note_id, OrderStatus::Filled)], 5).unwrap();
+        Store::update_orders_tx(&tx, &[(recalled_payment.note_id, OrderStatus::Recalled)], 9)
+            .unwrap();
+        tx.commit().unwrap();
+        let statuses = |store: &Store| -> Vec<(OrderStatus, Option<u32>)> {
+            store
+                .get_orders()
+                .unwrap()
+                .into_iter()
+                .map(|order| (order.status, order.closed_height))
+                .collect()
+        };
+        assert_eq!(
+            statuses(&store),
+            vec![
+                (OrderStatus::Filled, Some(5)),
+                (OrderStatus::Expired, None),
+                (OrderStatus::Recalled, Some(9)),
+            ]
+        );
+
+        store.rollback_to_block(6).unwrap();
+        assert_eq!(
+            statuses(&store),
+            vec![
+                (OrderStatus::Filled, Some(5)),
+                (OrderStatus::Open, None),
+                (OrderStatus::Expired, None),
+            ]
+        );
+
+        store.reset_sync_data().unwrap();
+        assert!(store.get_orders().unwrap().is_empty());
+    }
+
+    #[test]
+    fn hidden_accounts_can_be_restored_or_purged() {
+        use crypto::{dsa::rpo_falcon512::KeyPair, Felt, FieldElement};
+        use miden_lib::transaction::TransactionKernel;
+        use mock::{
+            constants::{generate_account_seed, AccountSeedType},
+            mock::account::mock_account,
+        };
+
+        use objects::{notes::NoteId, Digest};
+
+        use super::{
+            accounts::AuthInfo,
+            orders::{OrderKind, OrderRecord, OrderStatus},
+            sync::NoteTagSource,
+        };
+        use crate::{client::sync::account_note_tag, errors::StoreError};
+
+        let mut store = create_test_store();
+        let (account_id, account_seed) =
+            generate_account_seed(AccountSeedType::RegularAccountUpdatableCodeOnChain);
+        let account = mock_account(
+            Some(account_id.into()),
+            Felt::ONE,
+            None,
+            &TransactionKernel::assembler(),
+        );
+        store
+            .insert_account(
+                &account,
+                account_seed,
+                &AuthInfo::RpoFalcon512(KeyPair::new().unwrap()),
+            )
+            .unwrap();
+        let tag = account_note_tag(account_id);
+        store
+            .add_note_tag(tag, NoteTagSource::Account(account_id), None)
+            .unwrap();
+
+        assert!(store.hide_account(account_id).unwrap());
+        assert!(!store.hide_account(account_id).unwrap());
+        assert_eq!(store.get_account_sync_height(account_id).unwrap(), Some(0));
+        assert!(store.get_accounts().unwrap().is_empty());
+        assert_eq!(store.get_hidden_accounts().unwrap().len(), 1);
+        assert!(store.get_note_tags().unwrap().is_empty());
+
+        assert!(store.restore_account(account_id).unwrap());
+        assert_eq!(store.get_accounts().unwrap().len(), 1);
+        assert_eq!(store.get_note_tags().unwrap(), vec![tag]);
+
+        let order = OrderRecord {
+            note_id: NoteId::from(Digest::new([Felt::new(1); 4])),
+            nullifier: Digest::new([Felt::new(2); 4]),
+            kind: OrderKind::P2idr,
+            account_id,
+            transaction_id: Digest::new([Felt::new(3); 4]),
+            recall_height: Some(8),
+            status: OrderStatus::Open,
+            closed_height: None,
+        };
+        let tx = store.write_transaction().unwrap();
+        Store::insert_orders_tx(&tx, &[order.clone()]).unwrap();
+        tx.commit().unwrap();
+        store
+            .watch_nullifier(order.nullifier, Some("order"), 0)
+            .unwrap();
+        let other_nullifier = Digest::new([Felt::new(4); 4]);
+        store.watch_nullifier(other_nullifier, None, 0).unwrap();
+
+        store.hide_account(account_id).unwrap();
+        store.purge_account(account_id).unwrap();
+        assert!(store.get_hidden_accounts().unwrap().is_empty());
+        assert!(store.get_note_tag_records().unwrap().is_empty());
+        assert!(store.get_orders().unwrap().is_empty());
+        let watched_nullifiers: Vec<Digest> = store
+            .get_watched_nullifiers()
+            .unwrap()
+            .into_iter()
+            .map(|watched| watched.nullifier)
+            .collect();
+        assert_eq!(watched_nullifiers, vec![other_nullifier]);
+        assert_eq!(store.get_account_sync_height(account_id).unwrap(), None);
+        assert!(matches!(
+            store.get_account_stub_by_id(account_id),
+            Err(StoreError::AccountDataNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn expected_note_ids_track_their_tag_until_committed() {
+        use crypto::Felt;
+        use objects::{notes::NoteId, Digest};
+
+        use super::sync::NoteTagSource;
+
+        let mut store = create_test_store();
+        let note_id = NoteId::from(Digest::new([Felt::new(3); 4]));
+        assert!(store
+            .insert_expected_note_id(note_id, 42, Some("order"))
+            .unwrap());
+        assert!(!store.insert_expected_note_id(note_id, 42, None).unwrap());
+        let tag_sources = |store: &Store| -> Vec<NoteTagSource> {
+            store
+                .get_note_tag_records()
+                .unwrap()
+                .into_iter()
+                .map(|record| record.source)
+                .collect()
+        };
+        assert_eq!(tag_sources(&store), vec![NoteTagSource::Note(note_id)]);
+
+        let tx = store.write_transaction().unwrap();
+        Store::commit_expected_note_ids_tx(&tx, &[note_id], 5).unwrap();
+        tx.commit().unwrap();
+        let records = store.get_expected_note_ids().unwrap();
+        assert_eq!(records[0].commit_height, Some(5));
+        assert_eq!(records[0].label.as_deref(), Some("order"));
+        assert!(tag_sources(&store).is_empty());
+
+        // a reorganization past the note's block makes it expected again
+        let tx = store.write_transaction().unwrap();
+        Store::uncommit_expected_note_ids_tx(&tx, 4).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(
+            store.get_expected_note_ids().unwrap()[0].commit_height,
+            None
+        );
+        assert_eq!(tag_sources(&store), vec![NoteTagSource::Note(note_id)]);
+    }
+
+    #[test]
+    fn proven_transactions_are_stored_and_exported() {
+        use crypto::utils::{Deserializable, Serializable};
+        use miden_tx::ProvingOptions;
+        use mock::mock::{notes::AssetPreservationStatus, transaction::mock_executed_tx};
+        use objects::transaction::ProvenTransaction;
+
+        use crate::client::{
+            prover::TransactionProverMode,
+            transactions::{TransactionResult, TransactionStatus},
+        };
+
+        let mut store = create_test_store();
+        let executed_transaction = mock_executed_tx(AssetPreservationStatus::Preserved);
+        let transaction_id = executed_transaction.id().inner();
+        store
+            .insert_executed_transaction(&TransactionResult::new(
+                executed_transaction.clone(),
+                Vec::new(),
+            ))
+            .unwrap();
+        assert!(matches!(
+            store.get_proven_transaction(transaction_id),
+            Err(StoreError::TransactionProofNotFound(_))
+        ));
+
+        let proven_transaction = TransactionProverMode::Trusted
+            .prove(&ProvingOptions::default(), executed_transaction)
+            .unwrap();
+        store
+            .mark_transaction_proven(transaction_id, &proven_transaction)
+            .unwrap();
+        assert_eq!(
+            store.get_transaction_status(transaction_id).unwrap(),
+            TransactionStatus::Proven
+        );
+
+        // `tx export-proof` writes the serialized proven transaction, which reads back as the
+        // transaction that was proven
+        let exported = store
+            .get_proven_transaction(transaction_id)
+            .unwrap()
+            .to_bytes();
+        assert_eq!(exported, proven_transaction.to_bytes());
+        let imported = ProvenTransaction::read_from_bytes(&exported).unwrap();
+        assert_eq!(imported.id().inner(), transaction_id);
+        assert_eq!(imported.account_id(), proven_transaction.account_id());
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    #[test]
+    fn encrypt_store() {
+        let store_path = create_test_store_path();
+        let config: StoreConfig = store_path.to_str().unwrap().try_into().unwrap();
+        let version = migrations::current_version(&Store::new(config).unwrap().db).unwrap();
+
+        let config: StoreConfig = store_path.to_str().unwrap().try_into().unwrap();
+        Store::encrypt(&config, "passphrase").unwrap();
+        assert!(Store::is_encrypted(&store_path).unwrap());
+
+        let config = StoreConfig {
+            passphrase: Some("passphrase".to_string()),
+            ..config
+        };
+        let store = Store::new(config).unwrap();
+        assert_eq!(migrations::current_version(&store.db).unwrap(), version);
+
+        let config = StoreConfig {
+            database_filepath: store_path.to_str().unwrap().to_string(),
+            passphrase: Some("wrong passphrase".to_string()),
+        };
+        assert!(Store::new(config).is_err());
+    }
 }
@@ -1,30 +1,76 @@
-use crate::errors::{ClientError, StoreError};
+use crate::{
+    client::notes::{decode_memo, decode_unlock_height},
+    errors::{ClientError, StoreError},
+};
 
-use super::Store;
+use super::{
+    chain_data::{parse_block_time, BlockTime},
+    Page, Store,
+};
 
-use clap::error::Result;
+use std::{
+    collections::BTreeMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use crypto::utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 
+use crypto::merkle::MerklePath;
 use objects::notes::{Note, NoteAssets, NoteId, NoteInclusionProof, NoteInputs, NoteScript};
 
 use objects::{accounts::AccountId, notes::NoteMetadata, transaction::InputNote, Digest, Felt};
-use rusqlite::{params, Transaction};
+use rusqlite::{params, params_from_iter, types::Value, OptionalExtension, Transaction};
 
+/// Inserts an input note. A note that is already stored is only updated when it was not
+/// committed yet and is now inserted as committed, in which case it takes the inclusion proof and
+/// commit height of the new record. Its provenance is kept.
 pub(crate) const INSERT_NOTE_QUERY: &str = "\
 INSERT INTO input_notes
-    (note_id, nullifier, script, vault, inputs, serial_num, sender_id, tag, inclusion_proof, recipients, status, commit_height)
- VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
+    (note_id, nullifier, script_hash, vault, inputs, serial_num, sender_id, tag, inclusion_proof, recipients, status, commit_height, origin, origin_detail, created_at)
+ VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+ ON CONFLICT (note_id) DO UPDATE SET
+    inclusion_proof = excluded.inclusion_proof, status = excluded.status,
+    commit_height = excluded.commit_height, committed_at = excluded.created_at
+ WHERE input_notes.status IN ('pending', 'expected') AND excluded.status = 'committed'";
+
+pub(crate) const INSERT_NOTE_INCLUSION_PATH_QUERY: &str = "\
+INSERT OR REPLACE INTO note_inclusion_paths
+    (note_id, block_num, note_index, note_path)
+ VALUES (?, ?, ?, ?)";
+
+/// Base query for retrieving input notes. Inclusion proofs are rebuilt from the normalized note
+/// inclusion path and the header of the block the note was committed in, falling back to the
+/// `inclusion_proof` column for notes whose block header is not tracked yet.
+const INPUT_NOTE_QUERY: &str = "\
+SELECT COALESCE(script.script, note.script), note.inputs, note.vault, note.serial_num, note.sender_id, note.tag, note.inclusion_proof,
+    path.block_num, path.note_index, path.note_path, header.sub_hash, header.notes_root
+ FROM input_notes AS note
+ LEFT JOIN note_scripts AS script ON note.script_hash = script.script_hash
+ LEFT JOIN note_inclusion_paths AS path ON note.note_id = path.note_id
+ LEFT JOIN block_headers AS header ON path.block_num = header.block_num";
+
+const NOTE_SCRIPT_QUERY: &str = "SELECT script_hash, name, script FROM note_scripts";
+
+/// Time after which the lock a transaction holds on the notes it consumes expires, releasing
+/// notes whose transaction was abandoned without being discarded. It leaves ample time to prove
+/// and submit the transaction.
+pub const NOTE_LOCK_DURATION: Duration = Duration::from_secs(60 * 60);
+
+/// Minimum number of hex digits of the note ID prefixes resolved by
+/// [Store::get_input_note_id_by_prefix], so that a short prefix does not silently pick a note
+pub const MIN_NOTE_ID_PREFIX_LEN: usize = 4;
 
 // TYPES
 // ================================================================================================
 
 type SerializedInputNoteData = (
+    String,
     String,
     String,
     Vec<u8>,
     Vec<u8>,
     Vec<u8>,
+    Vec<u8>,
     String,
     i64,
     i64,
@@ -34,7 +80,32 @@ type SerializedInputNoteData = (
     i64,
 );
 
-type SerializedInputNoteParts = (Vec<u8>, Vec<u8>, Vec<u8>, String, u64, u64, Option<Vec<u8>>);
+type SerializedInputNoteParts = (
+    Vec<u8>,
+    Vec<u8>,
+    Vec<u8>,
+    String,
+    u64,
+    u64,
+    Option<Vec<u8>>,
+    Option<SerializedNoteInclusionPathParts>,
+);
+
+type SerializedNoteInclusionPathData = (String, u32, i64, String);
+
+type SerializedNoteInclusionPathParts = (u32, u64, String, String, String);
+
+type SerializedPartialNoteData = (String, String, Vec<u8>, i64, i64, String);
+
+type SerializedNoteScriptData = (String, Option<String>, Vec<u8>);
+
+type SerializedNoteProvenanceParts = (
+    Option<String>,
+    Option<String>,
+    Option<i64>,
+    Option<u32>,
+    Option<i64>,
+);
 
 // NOTE FILTER
 // ================================================================================================
@@ -44,19 +115,196 @@ pub enum InputNoteFilter {
     All,
     Consumed,
     Committed,
+    /// Notes imported without an inclusion proof, waiting to be seen committed
     Pending,
+    /// Notes created by transactions submitted by the client, waiting to be seen committed. As
+    /// the transaction kernel authenticates every input note against a block, they can only be
+    /// consumed once a sync provides their inclusion proof
+    Expected,
 }
 
 impl InputNoteFilter {
     /// Returns a [String] containing the query for this Filter
     pub fn to_query(&self) -> String {
-        let base = String::from("SELECT script, inputs, vault, serial_num, sender_id, tag, inclusion_proof FROM input_notes");
+        match self.condition() {
+            Some(condition) => format!("{INPUT_NOTE_QUERY} WHERE {condition}"),
+            None => String::from(INPUT_NOTE_QUERY),
+        }
+    }
+
+    /// Returns the condition on the note status for this filter, if any
+    fn condition(&self) -> Option<&'static str> {
         match self {
-            InputNoteFilter::All => base,
-            InputNoteFilter::Committed => format!("{base} WHERE status = 'committed'"),
-            InputNoteFilter::Consumed => format!("{base} WHERE status = 'consumed'"),
-            InputNoteFilter::Pending => format!("{base} WHERE status = 'pending'"),
+            InputNoteFilter::All => None,
+            InputNoteFilter::Committed => Some("note.status = 'committed'"),
+            InputNoteFilter::Consumed => Some("note.status = 'consumed'"),
+            InputNoteFilter::Pending => Some("note.status = 'pending'"),
+            InputNoteFilter::Expected => Some("note.status = 'expected'"),
+        }
+    }
+}
+
+/// A composable filter for input notes.
+///
+/// Each criterion that is set narrows down the set of returned notes, so a note is returned only if
+/// it satisfies all of them. For instance, the following filter matches the committed notes sent
+/// by `sender` that carry assets issued by `faucet_id`:
+///
+/// ```ignore
+/// let filter = NoteFilter::new()
+///     .status(InputNoteFilter::Committed)
+///     .sender(sender)
+///     .faucet(faucet_id);
+/// ```
+#[derive(Clone, Debug)]
+pub struct NoteFilter {
+    status: InputNoteFilter,
+    sender: Option<AccountId>,
+    tag: Option<u64>,
+    faucet_id: Option<AccountId>,
+    from_block: Option<u32>,
+    to_block: Option<u32>,
+    consumable_by: Option<AccountId>,
+    memo: Option<String>,
+}
+
+impl NoteFilter {
+    /// Returns a new filter that matches all input notes
+    pub fn new() -> Self {
+        Self {
+            status: InputNoteFilter::All,
+            sender: None,
+            tag: None,
+            faucet_id: None,
+            from_block: None,
+            to_block: None,
+            consumable_by: None,
+            memo: None,
+        }
+    }
+
+    /// Only matches notes with the specified status
+    pub fn status(mut self, status: InputNoteFilter) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Only matches notes sent by the specified account
+    pub fn sender(mut self, sender: AccountId) -> Self {
+        self.sender = Some(sender);
+        self
+    }
+
+    /// Only matches notes with the specified tag
+    pub fn tag(mut self, tag: u64) -> Self {
+        self.tag = Some(tag);
+        self
+    }
+
+    /// Only matches notes that carry at least one asset issued by the specified faucet
+    pub fn faucet(mut self, faucet_id: AccountId) -> Self {
+        self.faucet_id = Some(faucet_id);
+        self
+    }
+
+    /// Only matches notes that were included in a block between `from_block` and `to_block`,
+    /// both inclusive. Pending and expected notes are never matched, as they have not been included
+    /// in a block yet.
+    pub fn block_range(mut self, from_block: u32, to_block: u32) -> Self {
+        self.from_block = Some(from_block);
+        self.to_block = Some(to_block);
+        self
+    }
+
+    /// Only matches committed notes that can be consumed by the specified account.
+    ///
+    /// Notes whose first input is an account ID, such as P2ID notes, are only matched for that
+    /// account. Notes whose inputs are not addressed to an account are matched for any account.
+    /// Notes locked by a transaction in progress are not matched, nor are timelocked notes until
+    /// the client is synced past their unlock height.
+    pub fn consumable_by(mut self, account_id: AccountId) -> Self {
+        self.consumable_by = Some(account_id);
+        self
+    }
+
+    /// Only matches notes whose memo contains `text`, see
+    /// [encode_memo](crate::client::notes::encode_memo)
+    pub fn memo(mut self, text: &str) -> Self {
+        self.memo = Some(text.to_string());
+        self
+    }
+
+    /// Returns the query for this filter along with the values to bind to its parameters
+    fn to_query(&self) -> (String, Vec<Value>) {
+        let mut conditions = Vec::new();
+        let mut params = Vec::new();
+
+        if let Some(condition) = self.status.condition() {
+            conditions.push(condition);
+        }
+        if let Some(sender) = self.sender {
+            conditions.push("note.sender_id = ?");
+            params.push(Value::Integer(u64::from(sender) as i64));
+        }
+        if let Some(tag) = self.tag {
+            conditions.push("note.tag = ?");
+            params.push(Value::Integer(tag as i64));
+        }
+        if let Some(faucet_id) = self.faucet_id {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM input_note_assets AS asset \
+                    WHERE asset.note_id = note.note_id AND asset.faucet_id = ?)",
+            );
+            params.push(Value::Integer(u64::from(faucet_id) as i64));
+        }
+        if self.from_block.is_some() || self.to_block.is_some() {
+            conditions.push("note.status NOT IN ('pending', 'expected')");
         }
+        if let Some(from_block) = self.from_block {
+            conditions.push("note.commit_height >= ?");
+            params.push(Value::Integer(from_block as i64));
+        }
+        if let Some(to_block) = self.to_block {
+            conditions.push("note.commit_height <= ?");
+            params.push(Value::Integer(to_block as i64));
+        }
+        if let Some(account_id) = self.consumable_by {
+            conditions.push(
+                "note.status = 'committed' AND NOT EXISTS (SELECT 1 FROM input_note_targets AS target \
+                    WHERE target.note_id = note.note_id AND target.account_id != ?) \
+                    AND (note.locked_until IS NULL OR note.locked_until <= ?) \
+                    AND (note.unlock_height IS NULL OR note.unlock_height < (SELECT block_num FROM state_sync))",
+            );
+            params.push(Value::Integer(u64::from(account_id) as i64));
+            params.push(Value::Integer(unix_timestamp() as i64));
+        }
+        if let Some(memo) = &self.memo {
+            conditions.push("instr(note.memo, ?) > 0");
+            params.push(Value::Text(memo.clone()));
+        }
+
+        let query = if conditions.is_empty() {
+            format!("{INPUT_NOTE_QUERY} ORDER BY note.rowid")
+        } else {
+            format!(
+                "{INPUT_NOTE_QUERY} WHERE {} ORDER BY note.rowid",
+                conditions.join(" AND ")
+            )
+        };
+
+        (query, params)
+    }
+}
+
+impl Default for NoteFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<InputNoteFilter> for NoteFilter {
+    fn from(status: InputNoteFilter) -> Self {
+        NoteFilter::new().status(status)
     }
 }
 
@@ -136,19 +384,128 @@ impl TryInto<InputNote> for InputNoteRecord {
     }
 }
 
+// PARTIAL NOTE RECORD
+// ================================================================================================
+
+/// A note created by the client of which only the recipient digest is known, such as the notes
+/// created with [TransactionTemplate::PayToRecipient](crate::client::transactions::TransactionTemplate::PayToRecipient).
+///
+/// The script, inputs and serial number behind the recipient are shared off-band by the
+/// counterparty. Importing the full note as an input note completes the record.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartialNoteRecord {
+    pub note_id: NoteId,
+    pub recipient: Digest,
+    pub assets: NoteAssets,
+    pub metadata: NoteMetadata,
+    /// ID of the transaction that created the note
+    pub transaction_id: Digest,
+}
+
+// NOTE SCRIPT RECORD
+// ================================================================================================
+
+/// A note script stored by the client. Scripts are stored once and referenced by hash from the
+/// notes using them, and can be registered under a name to be reused when building notes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoteScriptRecord {
+    pub script_hash: Digest,
+    pub name: Option<String>,
+    pub script: NoteScript,
+}
+
+// EXPECTED NOTE ID RECORD
+// ================================================================================================
+
+/// A note the client waits for of which only the ID is known. The note's tag is tracked until the
+/// note is committed, at which point `commit_height` is set.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExpectedNoteIdRecord {
+    pub note_id: NoteId,
+    pub tag: u64,
+    pub label: Option<String>,
+    pub commit_height: Option<u32>,
+}
+
+// NOTE PROVENANCE
+// ================================================================================================
+
+/// How an input note came to be stored by the client
+#[derive(Clone, Debug, PartialEq)]
+pub enum NoteOrigin {
+    /// Imported through [Client::import_input_note](crate::client::Client::import_input_note), for
+    /// example from a wallet archive
+    Imported,
+    /// Imported from the note file at the given path
+    File(String),
+    /// Created by the transaction with the given ID, submitted by the client
+    Transaction(Digest),
+    /// Added with [Client::add_expected_note](crate::client::Client::add_expected_note) before it
+    /// was committed
+    Expected,
+}
+
+/// Where an input note came from and when the client learnt about it, to help answer where an
+/// unexpected note came from
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoteProvenance {
+    /// How the note was stored, or `None` for notes stored before provenance was recorded
+    pub origin: Option<NoteOrigin>,
+    /// UNIX timestamp, in seconds, at which the note was stored
+    pub created_at: Option<u64>,
+    /// Block the note was committed in, if it is known to be committed
+    pub commit_height: Option<u32>,
+    /// UNIX timestamp, in seconds, at which syncing found the note committed. Notes imported with
+    /// an inclusion proof are never found by syncing and have none.
+    pub committed_at: Option<u64>,
+}
+
+// NOTE TIMELINE
+// ================================================================================================
+
+/// The blocks an input note was committed and consumed in, with their timestamps, as reported in
+/// exports of the client's notes
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoteTimeline {
+    pub note_id: NoteId,
+    /// Block the note was committed in, if it is known to be committed
+    pub committed: Option<BlockTime>,
+    /// Block the note was consumed in, if syncing found it consumed
+    pub consumed: Option<BlockTime>,
+}
+
 // NOTES STORE METHODS
 // --------------------------------------------------------------------------------------------
 
 impl Store {
-    /// Retrieves the input notes from the database
+    /// Retrieves the input notes that match the provided filter from the database
     pub fn get_input_notes(
         &self,
-        note_filter: InputNoteFilter,
+        note_filter: impl Into<NoteFilter>,
+    ) -> Result<Vec<InputNoteRecord>, StoreError> {
+        let (query, params) = note_filter.into().to_query();
+        self.query_input_notes(&query, params)
+    }
+
+    /// Retrieves the specified page of the input notes that match the provided filter from the
+    /// database
+    pub fn get_input_notes_page(
+        &self,
+        note_filter: impl Into<NoteFilter>,
+        page: Page,
+    ) -> Result<Vec<InputNoteRecord>, StoreError> {
+        let (query, params) = note_filter.into().to_query();
+        self.query_input_notes(&page.apply(&query), params)
+    }
+
+    fn query_input_notes(
+        &self,
+        query: &str,
+        params: Vec<Value>,
     ) -> Result<Vec<InputNoteRecord>, StoreError> {
         self.db
-            .prepare(&note_filter.to_query())?
-            .query_map([], parse_input_note_columns)
-            .expect("no binding parameters used in query")
+            .prepare(query)?
+            .query_map(params_from_iter(params), parse_input_note_columns)?
             .map(|result| Ok(result?).and_then(parse_input_note))
             .collect::<Result<Vec<InputNoteRecord>, _>>()
     }
@@ -156,21 +513,136 @@ impl Store {
     /// Retrieves the input note with the specified id from the database
     pub fn get_input_note_by_id(&self, note_id: NoteId) -> Result<InputNoteRecord, StoreError> {
         let query_id = &note_id.inner().to_string();
-        const QUERY: &str = "SELECT script, inputs, vault, serial_num, sender_id, tag, inclusion_proof FROM input_notes WHERE note_id = ?";
+        let query = format!("{INPUT_NOTE_QUERY} WHERE note.note_id = ?");
 
         self.db
-            .prepare(QUERY)?
+            .prepare(&query)?
             .query_map(params![query_id.to_string()], parse_input_note_columns)?
             .map(|result| Ok(result?).and_then(parse_input_note))
             .next()
             .ok_or(StoreError::InputNoteNotFound(note_id))?
     }
 
-    /// Inserts the provided input note into the database
-    pub fn insert_input_note(&mut self, note: &InputNoteRecord) -> Result<(), StoreError> {
-        let tx = self.db.transaction()?;
+    /// Returns the ID of the input note whose ID starts with `prefix`, given in hex with or
+    /// without the `0x` prefix. A full note ID resolves to itself.
+    ///
+    /// # Errors
+    /// Returns an error if `prefix` has fewer than [MIN_NOTE_ID_PREFIX_LEN] hex digits, if no
+    /// input note ID starts with it, or if several of them do.
+    pub fn get_input_note_id_by_prefix(&self, prefix: &str) -> Result<NoteId, StoreError> {
+        const QUERY: &str = "SELECT note_id FROM input_notes WHERE note_id LIKE ? ORDER BY note_id";
+        /// Number of matching note IDs listed when the prefix is ambiguous
+        const MAX_LISTED_MATCHES: usize = 5;
 
-        Self::insert_input_note_tx(&tx, note)?;
+        let digits = prefix.strip_prefix("0x").unwrap_or(prefix).to_lowercase();
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(StoreError::NoteIdPrefixNotFound(prefix.to_string()));
+        }
+        if digits.len() < MIN_NOTE_ID_PREFIX_LEN {
+            return Err(StoreError::NoteIdPrefixTooShort(
+                prefix.to_string(),
+                MIN_NOTE_ID_PREFIX_LEN,
+            ));
+        }
+
+        let mut note_ids = self
+            .db
+            .prepare(QUERY)?
+            .query_map(params![format!("0x{digits}%")], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+
+        match note_ids.len() {
+            0 => Err(StoreError::NoteIdPrefixNotFound(prefix.to_string())),
+            1 => Ok(Digest::try_from(note_ids.remove(0))?.into()),
+            _ => {
+                note_ids.truncate(MAX_LISTED_MATCHES);
+                Err(StoreError::AmbiguousNoteIdPrefix(
+                    prefix.to_string(),
+                    note_ids,
+                ))
+            }
+        }
+    }
+
+    /// Returns where the input note with the specified ID came from
+    pub fn get_note_provenance(&self, note_id: NoteId) -> Result<NoteProvenance, StoreError> {
+        const QUERY: &str = "SELECT origin, origin_detail, created_at, \
+            CASE WHEN status IN ('committed', 'consumed') THEN commit_height END, committed_at \
+            FROM input_notes WHERE note_id = ?";
+
+        self.db
+            .query_row(
+                QUERY,
+                params![note_id.inner().to_string()],
+                parse_note_provenance_columns,
+            )
+            .optional()?
+            .ok_or(StoreError::InputNoteNotFound(note_id))
+            .and_then(parse_note_provenance)
+    }
+
+    /// Returns when every input note was committed and consumed, in the order the notes were
+    /// stored. Block timestamps are read from the headers of the blocks, which the client only
+    /// tracks for some blocks.
+    pub fn get_note_timelines(&self) -> Result<Vec<NoteTimeline>, StoreError> {
+        const QUERY: &str = "SELECT note.note_id, \
+            CASE WHEN note.status IN ('committed', 'consumed') THEN note.commit_height END, \
+            commit_header.header, note.consumed_height, consumed_header.header \
+            FROM input_notes AS note \
+            LEFT JOIN block_headers AS commit_header \
+                ON note.status IN ('committed', 'consumed') \
+                AND commit_header.block_num = note.commit_height \
+            LEFT JOIN block_headers AS consumed_header \
+                ON consumed_header.block_num = note.consumed_height \
+            ORDER BY note.rowid";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map([], |row| {
+                let note_id: String = row.get(0)?;
+                let commit_height: Option<u32> = row.get(1)?;
+                let commit_header: Option<String> = row.get(2)?;
+                let consumed_height: Option<u32> = row.get(3)?;
+                let consumed_header: Option<String> = row.get(4)?;
+                Ok((
+                    note_id,
+                    commit_height,
+                    commit_header,
+                    consumed_height,
+                    consumed_header,
+                ))
+            })?
+            .map(|result| {
+                let (note_id, commit_height, commit_header, consumed_height, consumed_header) =
+                    result?;
+                Ok(NoteTimeline {
+                    note_id: Digest::try_from(note_id)?.into(),
+                    committed: parse_block_time(commit_height, commit_header)?,
+                    consumed: parse_block_time(consumed_height, consumed_header)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Inserts the provided input note into the database, recording where it came from
+    pub fn insert_input_note(
+        &mut self,
+        note: &InputNoteRecord,
+        origin: &NoteOrigin,
+    ) -> Result<(), StoreError> {
+        let tx = self.write_transaction()?;
+
+        Self::insert_input_note_tx(&tx, note, origin)?;
+
+        Ok(tx.commit()?)
+    }
+
+    /// Inserts the provided input note, which is not committed yet, with the `expected` status
+    pub fn insert_expected_input_note(&mut self, note: &InputNoteRecord) -> Result<(), StoreError> {
+        let tx = self.write_transaction()?;
+
+        Self::insert_input_note_tx(&tx, note, &NoteOrigin::Expected)?;
+        Self::mark_notes_expected_tx(&tx, &[note.note_id()])?;
 
         Ok(tx.commit()?)
     }
@@ -191,14 +663,52 @@ impl Store {
             .collect::<Result<Vec<Digest>, _>>()
     }
 
-    /// Inserts the provided input note into the database
+    /// Returns the nullifiers of the unspent input notes along with the block up to which each of
+    /// them has been checked against the node. Nullifiers that were never checked are reported as
+    /// checked up to the block their note was committed in.
+    pub fn get_nullifier_check_heights(&self) -> Result<BTreeMap<Digest, u32>, StoreError> {
+        const QUERY: &str = "SELECT nullifier, COALESCE(nullifier_checked_height, commit_height) \
+            FROM input_notes WHERE status = 'committed'";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .expect("no binding parameters used in query")
+            .map(|result| {
+                result
+                    .map_err(|err| StoreError::ParsingError(err.to_string()))
+                    .and_then(|(nullifier, block_num): (String, u32)| {
+                        Ok((Digest::try_from(nullifier)?, block_num))
+                    })
+            })
+            .collect()
+    }
+
+    /// Inserts the provided input notes, which share the same origin, as part of the transaction
+    /// `tx`, reusing the prepared statements across notes
+    pub(super) fn insert_input_notes_tx(
+        tx: &Transaction<'_>,
+        notes: &[InputNoteRecord],
+        origin: &NoteOrigin,
+    ) -> Result<(), StoreError> {
+        for note in notes {
+            Self::insert_input_note_tx(tx, note, origin)?;
+        }
+
+        Ok(())
+    }
+
+    /// Inserts the provided input note into the database. Inserting a note that is already stored
+    /// does not fail, see [INSERT_NOTE_QUERY] for how the stored record is updated.
     pub(super) fn insert_input_note_tx(
         tx: &Transaction<'_>,
         note: &InputNoteRecord,
+        origin: &NoteOrigin,
     ) -> Result<(), StoreError> {
         let (
             note_id,
             nullifier,
+            script_hash,
             script,
             vault,
             inputs,
@@ -210,13 +720,14 @@ impl Store {
             status,
             commit_height,
         ) = serialize_input_note(note)?;
+        let (origin, origin_detail) = serialize_note_origin(origin);
 
-        tx.execute(
-            INSERT_NOTE_QUERY,
-            params![
+        Self::insert_note_script_tx(tx, &script_hash, &script)?;
+        tx.prepare_cached(INSERT_NOTE_QUERY)?
+            .execute(params![
                 note_id,
                 nullifier,
-                script,
+                script_hash,
                 vault,
                 inputs,
                 serial_num,
@@ -225,17 +736,592 @@ impl Store {
                 inclusion_proof,
                 recipients,
                 status,
-                commit_height
-            ],
-        )
-        .map_err(|err| StoreError::QueryError(err.to_string()))
-        .map(|_| ())
+                commit_height,
+                origin,
+                origin_detail,
+                unix_timestamp() as i64
+            ])
+            .map_err(|err| StoreError::QueryError(err.to_string()))?;
+
+        Self::insert_note_filter_data(tx, note.note())?;
+        Self::normalize_inclusion_proof(tx, &note_id)?;
+
+        // The full note completes the partial record created along with it, if any
+        const COMPLETE_PARTIAL_NOTE_QUERY: &str =
+            "DELETE FROM partial_output_notes WHERE note_id = ?";
+        tx.prepare_cached(COMPLETE_PARTIAL_NOTE_QUERY)?
+            .execute(params![note_id])?;
+
+        Ok(())
+    }
+
+    // NOTE LOCKS
+    // --------------------------------------------------------------------------------------------
+
+    /// Checks that none of the specified notes is locked by a transaction in progress, so that a
+    /// transaction consuming them can be executed without risking a conflict.
+    ///
+    /// # Errors
+    /// Returns [StoreError::NoteLocked] for the first locked note found.
+    pub fn check_notes_unlocked(&self, note_ids: &[NoteId]) -> Result<(), StoreError> {
+        const QUERY: &str =
+            "SELECT locked_by_tx FROM input_notes WHERE note_id = ? AND locked_until > ?";
+        let now = unix_timestamp() as i64;
+
+        let mut statement = self.db.prepare_cached(QUERY)?;
+        for note_id in note_ids {
+            let locked_by_tx: Option<String> = statement
+                .query_row(params![note_id.inner().to_string(), now], |row| row.get(0))
+                .optional()?;
+            if let Some(locked_by_tx) = locked_by_tx {
+                return Err(StoreError::NoteLocked(
+                    *note_id,
+                    Digest::try_from(locked_by_tx)?,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Locks the specified notes on behalf of the transaction consuming them for
+    /// [NOTE_LOCK_DURATION]. Notes the client does not track are skipped.
+    ///
+    /// # Errors
+    /// Returns [StoreError::NoteLocked] if one of the notes is locked by another transaction.
+    pub(super) fn lock_notes_tx(
+        tx: &Transaction<'_>,
+        note_ids: &[NoteId],
+        transaction_id: Digest,
+    ) -> Result<(), StoreError> {
+        const LOCK_QUERY: &str =
+            "UPDATE input_notes SET locked_by_tx = ?, locked_until = ? WHERE note_id = ? \
+            AND (locked_by_tx IS NULL OR locked_by_tx = ? OR locked_until <= ?)";
+        const HOLDER_QUERY: &str = "SELECT locked_by_tx FROM input_notes WHERE note_id = ?";
+        let transaction_id_str = String::from(transaction_id);
+        let now = unix_timestamp();
+        let locked_until = (now + NOTE_LOCK_DURATION.as_secs()) as i64;
+
+        for note_id in note_ids {
+            let note_id_str = note_id.inner().to_string();
+            let locked = tx.execute(
+                LOCK_QUERY,
+                params![
+                    transaction_id_str,
+                    locked_until,
+                    note_id_str,
+                    transaction_id_str,
+                    now as i64
+                ],
+            )?;
+            if locked > 0 {
+                continue;
+            }
+
+            let holder: Option<Option<String>> = tx
+                .query_row(HOLDER_QUERY, params![note_id_str], |row| row.get(0))
+                .optional()?;
+            if let Some(Some(holder)) = holder {
+                return Err(StoreError::NoteLocked(*note_id, Digest::try_from(holder)?));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Locks the specified notes on behalf of `holder`, such as a transaction about to be
+    /// executed whose ID is not known yet, see [Store::transfer_note_locks].
+    ///
+    /// # Errors
+    /// Returns [StoreError::NoteLocked] if one of the notes is locked by another holder, in which
+    /// case none of the notes is locked.
+    pub fn lock_notes(&mut self, note_ids: &[NoteId], holder: Digest) -> Result<(), StoreError> {
+        let tx = self.write_transaction()?;
+
+        Self::lock_notes_tx(&tx, note_ids, holder)?;
+
+        Ok(tx.commit()?)
+    }
+
+    /// Hands the notes locked by `holder` over to the transaction with the specified ID, which
+    /// keeps them locked until the lock of `holder` would have expired
+    pub fn transfer_note_locks(
+        &mut self,
+        holder: Digest,
+        transaction_id: Digest,
+    ) -> Result<(), StoreError> {
+        const QUERY: &str = "UPDATE input_notes SET locked_by_tx = ? WHERE locked_by_tx = ?";
+        self.db.execute(
+            QUERY,
+            params![String::from(transaction_id), String::from(holder)],
+        )?;
+        Ok(())
+    }
+
+    /// Releases the notes locked by `holder`
+    pub fn unlock_notes(&mut self, holder: Digest) -> Result<(), StoreError> {
+        let tx = self.write_transaction()?;
+
+        Self::unlock_notes_tx(&tx, holder)?;
+
+        Ok(tx.commit()?)
+    }
+
+    /// Releases the notes locked by the specified transaction
+    pub(super) fn unlock_notes_tx(
+        tx: &Transaction<'_>,
+        transaction_id: Digest,
+    ) -> Result<(), StoreError> {
+        const QUERY: &str = "UPDATE input_notes SET locked_by_tx = NULL, locked_until = NULL \
+            WHERE locked_by_tx = ?";
+        tx.execute(QUERY, params![String::from(transaction_id)])?;
+        Ok(())
+    }
+
+    /// Stops tracking the notes created by the specified transaction, along with the data derived
+    /// from them, unless they were seen committed
+    pub(super) fn remove_transaction_notes_tx(
+        tx: &Transaction<'_>,
+        transaction_id: Digest,
+    ) -> Result<(), StoreError> {
+        const NOTES_QUERY: &str = "SELECT note_id FROM input_notes \
+            WHERE origin = ? AND origin_detail = ? AND status IN ('pending', 'expected')";
+        const QUERIES: [&str; 4] = [
+            "DELETE FROM input_note_assets WHERE note_id = ?",
+            "DELETE FROM input_note_targets WHERE note_id = ?",
+            "DELETE FROM note_tags WHERE source = 'note' AND source_note_id = ?",
+            "DELETE FROM input_notes WHERE note_id = ?",
+        ];
+        const PARTIAL_NOTES_QUERY: &str =
+            "DELETE FROM partial_output_notes WHERE transaction_id = ?";
+
+        let (origin, origin_detail) =
+            serialize_note_origin(&NoteOrigin::Transaction(transaction_id));
+        let note_ids = tx
+            .prepare(NOTES_QUERY)?
+            .query_map(params![origin, origin_detail], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+
+        for note_id in note_ids {
+            for query in QUERIES {
+                tx.prepare_cached(query)?.execute(params![note_id])?;
+            }
+        }
+        tx.execute(PARTIAL_NOTES_QUERY, params![transaction_id.to_string()])?;
+
+        Ok(())
+    }
+
+    /// Marks the specified pending notes as expected, as they are created by a transaction the
+    /// client submitted
+    pub(super) fn mark_notes_expected_tx(
+        tx: &Transaction<'_>,
+        note_ids: &[NoteId],
+    ) -> Result<(), StoreError> {
+        const QUERY: &str =
+            "UPDATE input_notes SET status = 'expected' WHERE note_id = ? AND status = 'pending'";
+        let mut statement = tx.prepare_cached(QUERY)?;
+        for note_id in note_ids {
+            statement.execute(params![note_id.inner().to_string()])?;
+        }
+        Ok(())
+    }
+
+    // EXPECTED NOTE IDS
+    // --------------------------------------------------------------------------------------------
+
+    /// Retrieves the notes expected by ID, in the order they were added
+    pub fn get_expected_note_ids(&self) -> Result<Vec<ExpectedNoteIdRecord>, StoreError> {
+        const QUERY: &str =
+            "SELECT note_id, tag, label, commit_height FROM expected_note_ids ORDER BY rowid";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map([], |row| {
+                let note_id: String = row.get(0)?;
+                let tag: i64 = row.get(1)?;
+                let label: Option<String> = row.get(2)?;
+                let commit_height: Option<u32> = row.get(3)?;
+                Ok((note_id, tag, label, commit_height))
+            })?
+            .map(|result| {
+                let (note_id, tag, label, commit_height) = result?;
+                Ok(ExpectedNoteIdRecord {
+                    note_id: Digest::try_from(note_id)?.into(),
+                    tag: tag as u64,
+                    label,
+                    commit_height,
+                })
+            })
+            .collect()
+    }
+
+    /// Starts waiting for the note with the specified ID, tracking `tag` until it is committed.
+    /// Returns false if the note was already expected, in which case the store is left unchanged.
+    pub fn insert_expected_note_id(
+        &mut self,
+        note_id: NoteId,
+        tag: u64,
+        label: Option<&str>,
+    ) -> Result<bool, StoreError> {
+        const QUERY: &str =
+            "INSERT OR IGNORE INTO expected_note_ids (note_id, tag, label) VALUES (?, ?, ?)";
+        const TAG_QUERY: &str =
+            "INSERT OR IGNORE INTO note_tags (tag, source, source_note_id) VALUES (?, 'note', ?)";
+
+        let note_id = note_id.inner().to_string();
+        let tx = self.write_transaction()?;
+        let inserted_rows = tx.execute(QUERY, params![note_id, tag as i64, label])?;
+        if inserted_rows > 0 {
+            tx.execute(TAG_QUERY, params![tag as i64, note_id])?;
+        }
+        tx.commit()?;
+
+        Ok(inserted_rows > 0)
+    }
+
+    /// Records that the specified expected notes were committed in `block_num` and stops tracking
+    /// their tags
+    pub(super) fn commit_expected_note_ids_tx(
+        tx: &Transaction<'_>,
+        note_ids: &[NoteId],
+        block_num: u32,
+    ) -> Result<(), StoreError> {
+        const QUERY: &str = "UPDATE expected_note_ids SET commit_height = ? WHERE note_id = ?";
+        const TAG_QUERY: &str =
+            "DELETE FROM note_tags WHERE source = 'note' AND source_note_id = ?";
+
+        for note_id in note_ids {
+            let note_id = note_id.inner().to_string();
+            tx.prepare_cached(QUERY)?
+                .execute(params![block_num, note_id])?;
+            tx.prepare_cached(TAG_QUERY)?.execute(params![note_id])?;
+        }
+        Ok(())
+    }
+
+    /// Expects again the notes committed after `block_num`, tracking their tags again
+    pub(super) fn uncommit_expected_note_ids_tx(
+        tx: &Transaction<'_>,
+        block_num: u32,
+    ) -> Result<(), StoreError> {
+        const TAG_QUERY: &str = "INSERT OR IGNORE INTO note_tags (tag, source, source_note_id) \
+            SELECT tag, 'note', note_id FROM expected_note_ids WHERE commit_height > ?";
+        const QUERY: &str =
+            "UPDATE expected_note_ids SET commit_height = NULL WHERE commit_height > ?";
+
+        tx.execute(TAG_QUERY, params![block_num])?;
+        tx.execute(QUERY, params![block_num])?;
+        Ok(())
+    }
+
+    // NOTE SCRIPTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Retrieves every note script stored by the client, registered ones first
+    pub fn get_note_scripts(&self) -> Result<Vec<NoteScriptRecord>, StoreError> {
+        let query = format!("{NOTE_SCRIPT_QUERY} ORDER BY name IS NULL, name");
+
+        self.db
+            .prepare(&query)?
+            .query_map([], parse_note_script_columns)?
+            .map(|result| Ok(result?).and_then(parse_note_script))
+            .collect()
+    }
+
+    /// Retrieves the note script registered under the specified name
+    pub fn get_note_script_by_name(&self, name: &str) -> Result<NoteScriptRecord, StoreError> {
+        let query = format!("{NOTE_SCRIPT_QUERY} WHERE name = ?");
+
+        self.db
+            .prepare(&query)?
+            .query_map(params![name], parse_note_script_columns)?
+            .map(|result| Ok(result?).and_then(parse_note_script))
+            .next()
+            .ok_or_else(|| StoreError::NoteScriptNotFound(name.to_string()))?
+    }
+
+    /// Stores the provided note script under `name`. Registering a script that is already stored,
+    /// for instance because a note using it was imported, only names it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a different script is already registered under `name`, or if the script
+    /// is already registered under another name.
+    pub fn register_note_script(
+        &mut self,
+        name: &str,
+        script: &NoteScript,
+    ) -> Result<(), StoreError> {
+        const NAME_QUERY: &str = "SELECT script_hash FROM note_scripts WHERE name = ?";
+        const SCRIPT_NAME_QUERY: &str = "SELECT name FROM note_scripts WHERE script_hash = ?";
+        const UPDATE_NAME_QUERY: &str = "UPDATE note_scripts SET name = ? WHERE script_hash = ?";
+
+        let script_hash = script.hash().to_string();
+        let tx = self.write_transaction()?;
+
+        let registered_hash: Option<String> = tx
+            .query_row(NAME_QUERY, params![name], |row| row.get(0))
+            .optional()?;
+        if matches!(registered_hash, Some(registered_hash) if registered_hash != script_hash) {
+            return Err(StoreError::NoteScriptNameTaken(name.to_string()));
+        }
+
+        let registered_name: Option<String> = tx
+            .query_row(SCRIPT_NAME_QUERY, params![script_hash], |row| row.get(0))
+            .optional()?
+            .flatten();
+        if let Some(registered_name) =
+            registered_name.filter(|registered_name| registered_name != name)
+        {
+            return Err(StoreError::NoteScriptAlreadyRegistered(
+                script.hash(),
+                registered_name,
+            ));
+        }
+
+        Self::insert_note_script_tx(&tx, &script_hash, &script.to_bytes())?;
+        tx.execute(UPDATE_NAME_QUERY, params![name, script_hash])?;
+
+        Ok(tx.commit()?)
+    }
+
+    /// Stores a serialized note script unless a script with the same hash is already stored
+    fn insert_note_script_tx(
+        tx: &Transaction<'_>,
+        script_hash: &str,
+        script: &[u8],
+    ) -> Result<(), StoreError> {
+        const QUERY: &str =
+            "INSERT OR IGNORE INTO note_scripts (script_hash, script) VALUES (?, ?)";
+
+        tx.prepare_cached(QUERY)?
+            .execute(params![script_hash, script])?;
+        Ok(())
+    }
+
+    /// Moves the scripts of the notes stored before note scripts were deduplicated to the
+    /// note_scripts table.
+    ///
+    /// Returns the number of notes updated.
+    pub(super) fn move_note_scripts(tx: &Transaction<'_>) -> Result<usize, StoreError> {
+        const QUERY: &str = "SELECT note_id, script FROM input_notes WHERE script IS NOT NULL";
+        const UPDATE_QUERY: &str =
+            "UPDATE input_notes SET script_hash = ?, script = NULL WHERE note_id = ?";
+
+        let scripts = tx
+            .prepare(QUERY)?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<(String, Vec<u8>)>, _>>()?;
+
+        for (note_id, script) in scripts.iter() {
+            let script_hash = NoteScript::read_from_bytes(script)?.hash().to_string();
+            Self::insert_note_script_tx(tx, &script_hash, script)?;
+            tx.execute(UPDATE_QUERY, params![script_hash, note_id])?;
+        }
+
+        Ok(scripts.len())
+    }
+
+    // PARTIAL NOTES
+    // --------------------------------------------------------------------------------------------
+
+    /// Retrieves the notes created by the client of which only the recipient is known
+    pub fn get_partial_output_notes(&self) -> Result<Vec<PartialNoteRecord>, StoreError> {
+        const QUERY: &str = "SELECT note_id, recipient, assets, sender_id, tag, transaction_id \
+            FROM partial_output_notes";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map([], parse_partial_note_columns)?
+            .map(|result| Ok(result?).and_then(parse_partial_note))
+            .collect()
+    }
+
+    /// Inserts a note of which only the recipient is known
+    pub(super) fn insert_partial_output_note_tx(
+        tx: &Transaction<'_>,
+        note: &PartialNoteRecord,
+    ) -> Result<(), StoreError> {
+        const QUERY: &str = "INSERT OR IGNORE INTO partial_output_notes \
+            (note_id, recipient, assets, sender_id, tag, transaction_id) VALUES (?, ?, ?, ?, ?, ?)";
+
+        let (note_id, recipient, assets, sender_id, tag, transaction_id) =
+            serialize_partial_note(note);
+        tx.execute(
+            QUERY,
+            params![note_id, recipient, assets, sender_id, tag, transaction_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Stores the note's path in the note tree of the block it was committed in. The remaining
+    /// parts of the [NoteInclusionProof] are read from the block header when the note is
+    /// retrieved, so the header for the proof's block is expected to be tracked.
+    pub(super) fn insert_note_inclusion_path(
+        tx: &Transaction<'_>,
+        note_id: NoteId,
+        inclusion_proof: &NoteInclusionProof,
+    ) -> Result<(), StoreError> {
+        let (note_id, block_num, note_index, note_path) =
+            serialize_note_inclusion_path(note_id, inclusion_proof)?;
+
+        tx.prepare_cached(INSERT_NOTE_INCLUSION_PATH_QUERY)?
+            .execute(params![note_id, block_num, note_index, note_path])?;
+
+        Ok(())
+    }
+
+    /// Stores the data extracted from the note's assets and inputs that [NoteFilter] queries rely
+    /// on, including its memo and unlock height, as it cannot be read from the serialized note in
+    /// SQL.
+    fn insert_note_filter_data(tx: &Transaction<'_>, note: &Note) -> Result<(), StoreError> {
+        const ASSET_QUERY: &str =
+            "INSERT OR IGNORE INTO input_note_assets (note_id, faucet_id) VALUES (?, ?)";
+        const TARGET_QUERY: &str =
+            "INSERT OR REPLACE INTO input_note_targets (note_id, account_id) VALUES (?, ?)";
+        const MEMO_QUERY: &str =
+            "UPDATE input_notes SET memo = ?, unlock_height = ? WHERE note_id = ?";
+
+        let note_id = note.id().inner().to_string();
+        let mut asset_statement = tx.prepare_cached(ASSET_QUERY)?;
+        for asset in note.assets().iter() {
+            asset_statement.execute(params![note_id, u64::from(asset.faucet_id()) as i64])?;
+        }
+
+        let target_account_id = note
+            .inputs()
+            .inputs()
+            .first()
+            .and_then(|input| AccountId::try_from(*input).ok())
+            .map(|account_id| u64::from(account_id) as i64);
+        tx.prepare_cached(TARGET_QUERY)?
+            .execute(params![note_id, target_account_id])?;
+
+        let memo = decode_memo(note.inputs().inputs());
+        let unlock_height = decode_unlock_height(note);
+        tx.prepare_cached(MEMO_QUERY)?
+            .execute(params![memo, unlock_height, note_id])?;
+
+        Ok(())
+    }
+
+    /// Extracts the [NoteFilter] data of the input notes stored before it was being tracked.
+    ///
+    /// Returns the number of indexed notes.
+    pub(super) fn index_input_notes(tx: &Transaction<'_>) -> Result<usize, StoreError> {
+        const QUERY: &str = "SELECT COALESCE(script.script, note.script), note.inputs, note.vault, note.serial_num, note.sender_id, note.tag \
+            FROM input_notes AS note \
+            LEFT JOIN note_scripts AS script ON note.script_hash = script.script_hash \
+            WHERE NOT EXISTS (SELECT 1 FROM input_note_targets AS target WHERE target.note_id = note.note_id)";
+
+        let notes = tx
+            .prepare(QUERY)?
+            .query_map([], |row| {
+                let script: Vec<u8> = row.get(0)?;
+                let inputs: Vec<u8> = row.get(1)?;
+                let vault: Vec<u8> = row.get(2)?;
+                let serial_num: String = row.get(3)?;
+                let sender_id = row.get::<usize, i64>(4)? as u64;
+                let tag = row.get::<usize, i64>(5)? as u64;
+                Ok((
+                    script, inputs, vault, serial_num, sender_id, tag, None, None,
+                ))
+            })
+            .expect("no binding parameters used in query")
+            .map(|result| Ok(result?).and_then(parse_input_note))
+            .collect::<Result<Vec<InputNoteRecord>, StoreError>>()?;
+
+        for note in notes.iter() {
+            Self::insert_note_filter_data(tx, note.note())?;
+        }
+
+        Ok(notes.len())
+    }
+
+    /// Moves inclusion proofs stored in full in the `input_notes` table into the normalized
+    /// `note_inclusion_paths` table, for every note whose block header is now being tracked. This
+    /// scans every note, so it is only done when migrating stores written before the table
+    /// existed; inserted notes are normalized one at a time with
+    /// [Store::normalize_inclusion_proof], and the notes committed in a synced block with
+    /// [Store::normalize_block_inclusion_proofs].
+    ///
+    /// Returns the number of normalized proofs.
+    pub(super) fn normalize_inclusion_proofs(tx: &Transaction<'_>) -> Result<usize, StoreError> {
+        Self::normalize_inclusion_proofs_of(tx, None, None)
+    }
+
+    /// Normalizes the inclusion proof of the specified note like
+    /// [Store::normalize_inclusion_proofs], if it is stored in full and its block header is being
+    /// tracked
+    fn normalize_inclusion_proof(tx: &Transaction<'_>, note_id: &str) -> Result<(), StoreError> {
+        Self::normalize_inclusion_proofs_of(tx, Some(note_id), None)?;
+        Ok(())
+    }
+
+    /// Normalizes the inclusion proofs of the notes committed in block `block_num` like
+    /// [Store::normalize_inclusion_proofs], if its header is being tracked. Notes imported before
+    /// the client tracked the header of their block are normalized this way once it is synced.
+    ///
+    /// Returns the number of normalized proofs.
+    pub(super) fn normalize_block_inclusion_proofs(
+        tx: &Transaction<'_>,
+        block_num: u32,
+    ) -> Result<usize, StoreError> {
+        Self::normalize_inclusion_proofs_of(tx, None, Some(block_num))
+    }
+
+    /// Normalizes the inclusion proofs of every note, or only of the note with ID `note_id`, or
+    /// of the notes committed in block `block_num`
+    fn normalize_inclusion_proofs_of(
+        tx: &Transaction<'_>,
+        note_id: Option<&str>,
+        block_num: Option<u32>,
+    ) -> Result<usize, StoreError> {
+        const QUERY: &str = "SELECT note.note_id, note.inclusion_proof FROM input_notes AS note \
+            WHERE note.inclusion_proof IS NOT NULL AND (?1 IS NULL OR note.note_id = ?1) \
+            AND (?2 IS NULL OR note.commit_height = ?2) \
+            AND EXISTS (SELECT 1 FROM block_headers AS header WHERE header.block_num = note.commit_height)";
+
+        let proofs = tx
+            .prepare_cached(QUERY)?
+            .query_map(params![note_id, block_num], |row| {
+                let note_id: String = row.get(0)?;
+                let inclusion_proof: Vec<u8> = row.get(1)?;
+                Ok((note_id, inclusion_proof))
+            })?
+            .map(|result| {
+                let (note_id, inclusion_proof) = result?;
+                let note_id: NoteId = Digest::try_from(note_id)?.into();
+                let inclusion_proof = NoteInclusionProof::read_from_bytes(&inclusion_proof)?;
+                Ok((note_id, inclusion_proof))
+            })
+            .collect::<Result<Vec<(NoteId, NoteInclusionProof)>, StoreError>>()?;
+
+        for (note_id, inclusion_proof) in proofs.iter() {
+            Self::insert_note_inclusion_path(tx, *note_id, inclusion_proof)?;
+
+            const CLEAR_QUERY: &str =
+                "UPDATE input_notes SET inclusion_proof = NULL WHERE note_id = ?";
+            tx.execute(CLEAR_QUERY, params![note_id.inner().to_string()])?;
+        }
+
+        Ok(proofs.len())
     }
 }
 
 // HELPERS
 // ================================================================================================
 
+/// Returns the current time as a UNIX timestamp, in seconds
+pub(super) fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("the system clock is set after the UNIX epoch")
+        .as_secs()
+}
+
 /// Parse input note columns from the provided row into native types.
 fn parse_input_note_columns(
     row: &rusqlite::Row<'_>,
@@ -247,6 +1333,19 @@ fn parse_input_note_columns(
     let sender_id = row.get::<usize, i64>(4)? as u64;
     let tag = row.get::<usize, i64>(5)? as u64;
     let inclusion_proof: Option<Vec<u8>> = row.get(6)?;
+    let block_num: Option<u32> = row.get(7)?;
+    let note_index: Option<i64> = row.get(8)?;
+    let note_path: Option<String> = row.get(9)?;
+    let sub_hash: Option<String> = row.get(10)?;
+    let note_root: Option<String> = row.get(11)?;
+
+    let inclusion_path = match (block_num, note_index, note_path, sub_hash, note_root) {
+        (Some(block_num), Some(note_index), Some(note_path), Some(sub_hash), Some(note_root)) => {
+            Some((block_num, note_index as u64, note_path, sub_hash, note_root))
+        }
+        _ => None,
+    };
+
     Ok((
         script,
         inputs,
@@ -255,6 +1354,7 @@ fn parse_input_note_columns(
         sender_id,
         tag,
         inclusion_proof,
+        inclusion_path,
     ))
 }
 
@@ -262,7 +1362,7 @@ fn parse_input_note_columns(
 fn parse_input_note(
     serialized_input_note_parts: SerializedInputNoteParts,
 ) -> Result<InputNoteRecord, StoreError> {
-    let (script, inputs, note_assets, serial_num, sender_id, tag, inclusion_proof) =
+    let (script, inputs, note_assets, serial_num, sender_id, tag, inclusion_proof, inclusion_path) =
         serialized_input_note_parts;
     let script = NoteScript::read_from_bytes(&script)?;
     let inputs = NoteInputs::read_from_bytes(&inputs)?;
@@ -275,19 +1375,61 @@ fn parse_input_note(
     );
     let note = Note::from_parts(script, inputs, vault, serial_num, note_metadata);
 
-    let inclusion_proof = inclusion_proof
-        .map(|proof| NoteInclusionProof::read_from_bytes(&proof))
-        .transpose()?;
+    let inclusion_proof = match inclusion_path {
+        Some(inclusion_path) => Some(parse_note_inclusion_path(inclusion_path)?),
+        None => inclusion_proof
+            .map(|proof| NoteInclusionProof::read_from_bytes(&proof))
+            .transpose()?,
+    };
 
     Ok(InputNoteRecord::new(note, inclusion_proof))
 }
 
+/// Rebuilds a [NoteInclusionProof] from the normalized note inclusion path and the header data of
+/// the block the note was committed in.
+fn parse_note_inclusion_path(
+    serialized_inclusion_path_parts: SerializedNoteInclusionPathParts,
+) -> Result<NoteInclusionProof, StoreError> {
+    let (block_num, note_index, note_path, sub_hash, note_root) = serialized_inclusion_path_parts;
+
+    let note_path: Vec<Digest> =
+        serde_json::from_str(&note_path).map_err(StoreError::JsonDataDeserializationError)?;
+    let sub_hash: Digest =
+        serde_json::from_str(&sub_hash).map_err(StoreError::JsonDataDeserializationError)?;
+    let note_root: Digest =
+        serde_json::from_str(&note_root).map_err(StoreError::JsonDataDeserializationError)?;
+
+    NoteInclusionProof::new(
+        block_num,
+        sub_hash,
+        note_root,
+        note_index,
+        MerklePath::new(note_path),
+    )
+    .map_err(StoreError::NoteInclusionProofError)
+}
+
+/// Serialize the path of a note in its block's note tree into database compatible types.
+fn serialize_note_inclusion_path(
+    note_id: NoteId,
+    inclusion_proof: &NoteInclusionProof,
+) -> Result<SerializedNoteInclusionPathData, StoreError> {
+    let note_id = note_id.inner().to_string();
+    let block_num = inclusion_proof.origin().block_num;
+    let note_index = inclusion_proof.origin().node_index.value() as i64;
+    let note_path = serde_json::to_string(&inclusion_proof.note_path().to_vec())
+        .map_err(StoreError::InputSerializationError)?;
+
+    Ok((note_id, block_num, note_index, note_path))
+}
+
 /// Serialize the provided input note into database compatible types.
 pub(crate) fn serialize_input_note(
     note: &InputNoteRecord,
 ) -> Result<SerializedInputNoteData, StoreError> {
     let note_id = note.note_id().inner().to_string();
     let nullifier = note.note().nullifier().inner().to_string();
+    let script_hash = note.note().script().hash().to_string();
     let script = note.note().script().to_bytes();
     let note_assets = note.note().assets().to_bytes();
     let inputs = note.note().inputs().to_bytes();
@@ -296,33 +1438,11 @@ pub(crate) fn serialize_input_note(
     let sender_id = u64::from(note.note().metadata().sender()) as i64;
     let tag = u64::from(note.note().metadata().tag()) as i64;
     let (inclusion_proof, status, commit_height) = match note.inclusion_proof() {
-        Some(proof) => {
-            // FIXME: This removal is to accomodate a problem with how the node constructs paths where
-            // they are constructed using note ID instead of authentication hash, so for now we remove the first
-            // node here.
-            //
-            // See: https://github.com/0xPolygonMiden/miden-node/blob/main/store/src/state.rs#L274
-            let mut path = proof.note_path().clone();
-            if path.len() > 0 {
-                let _removed = path.remove(0);
-            }
-
-            (
-                Some(
-                    NoteInclusionProof::new(
-                        proof.origin().block_num,
-                        proof.sub_hash(),
-                        proof.note_root(),
-                        proof.origin().node_index.value(),
-                        path,
-                    )
-                    .unwrap()
-                    .to_bytes(),
-                ),
-                String::from("committed"),
-                proof.origin().block_num,
-            )
-        }
+        Some(proof) => (
+            Some(proof.to_bytes()),
+            String::from("committed"),
+            proof.origin().block_num,
+        ),
         None => (None, String::from("pending"), 0u32),
     };
     let recipients = note.note().recipient().to_string();
@@ -330,6 +1450,7 @@ pub(crate) fn serialize_input_note(
     Ok((
         note_id,
         nullifier,
+        script_hash,
         script,
         note_assets,
         inputs,
@@ -342,3 +1463,120 @@ pub(crate) fn serialize_input_note(
         commit_height as i64,
     ))
 }
+
+fn serialize_note_origin(origin: &NoteOrigin) -> (&'static str, Option<String>) {
+    match origin {
+        NoteOrigin::Imported => ("imported", None),
+        NoteOrigin::File(path) => ("file", Some(path.clone())),
+        NoteOrigin::Transaction(transaction_id) => {
+            ("transaction", Some(transaction_id.to_string()))
+        }
+        NoteOrigin::Expected => ("expected", None),
+    }
+}
+
+fn parse_note_provenance_columns(
+    row: &rusqlite::Row<'_>,
+) -> Result<SerializedNoteProvenanceParts, rusqlite::Error> {
+    let origin: Option<String> = row.get(0)?;
+    let origin_detail: Option<String> = row.get(1)?;
+    let created_at: Option<i64> = row.get(2)?;
+    let commit_height: Option<u32> = row.get(3)?;
+    let committed_at: Option<i64> = row.get(4)?;
+    Ok((
+        origin,
+        origin_detail,
+        created_at,
+        commit_height,
+        committed_at,
+    ))
+}
+
+fn parse_note_provenance(
+    serialized_provenance: SerializedNoteProvenanceParts,
+) -> Result<NoteProvenance, StoreError> {
+    let (origin, origin_detail, created_at, commit_height, committed_at) = serialized_provenance;
+
+    let origin = match (origin.as_deref(), origin_detail) {
+        (None, _) => None,
+        (Some("imported"), None) => Some(NoteOrigin::Imported),
+        (Some("file"), Some(path)) => Some(NoteOrigin::File(path)),
+        (Some("transaction"), Some(transaction_id)) => {
+            Some(NoteOrigin::Transaction(Digest::try_from(transaction_id)?))
+        }
+        (Some("expected"), None) => Some(NoteOrigin::Expected),
+        (Some(origin), _) => {
+            return Err(StoreError::DataDeserializationError(
+                DeserializationError::InvalidValue(format!("Invalid note origin: {origin}")),
+            ))
+        }
+    };
+
+    Ok(NoteProvenance {
+        origin,
+        created_at: created_at.map(|timestamp| timestamp as u64),
+        commit_height,
+        committed_at: committed_at.map(|timestamp| timestamp as u64),
+    })
+}
+
+fn parse_partial_note_columns(
+    row: &rusqlite::Row<'_>,
+) -> Result<SerializedPartialNoteData, rusqlite::Error> {
+    let note_id: String = row.get(0)?;
+    let recipient: String = row.get(1)?;
+    let assets: Vec<u8> = row.get(2)?;
+    let sender_id: i64 = row.get(3)?;
+    let tag: i64 = row.get(4)?;
+    let transaction_id: String = row.get(5)?;
+    Ok((note_id, recipient, assets, sender_id, tag, transaction_id))
+}
+
+fn parse_partial_note(
+    serialized_partial_note: SerializedPartialNoteData,
+) -> Result<PartialNoteRecord, StoreError> {
+    let (note_id, recipient, assets, sender_id, tag, transaction_id) = serialized_partial_note;
+
+    Ok(PartialNoteRecord {
+        note_id: Digest::try_from(note_id)?.into(),
+        recipient: Digest::try_from(recipient)?,
+        assets: NoteAssets::read_from_bytes(&assets)?,
+        metadata: NoteMetadata::new(
+            AccountId::new_unchecked(Felt::new(sender_id as u64)),
+            Felt::new(tag as u64),
+        ),
+        transaction_id: Digest::try_from(transaction_id)?,
+    })
+}
+
+fn serialize_partial_note(note: &PartialNoteRecord) -> SerializedPartialNoteData {
+    (
+        note.note_id.inner().to_string(),
+        note.recipient.to_string(),
+        note.assets.to_bytes(),
+        u64::from(note.metadata.sender()) as i64,
+        u64::from(note.metadata.tag()) as i64,
+        note.transaction_id.to_string(),
+    )
+}
+
+fn parse_note_script_columns(
+    row: &rusqlite::Row<'_>,
+) -> Result<SerializedNoteScriptData, rusqlite::Error> {
+    let script_hash: String = row.get(0)?;
+    let name: Option<String> = row.get(1)?;
+    let script: Vec<u8> = row.get(2)?;
+    Ok((script_hash, name, script))
+}
+
+fn parse_note_script(
+    serialized_note_script: SerializedNoteScriptData,
+) -> Result<NoteScriptRecord, StoreError> {
+    let (script_hash, name, script) = serialized_note_script;
+
+    Ok(NoteScriptRecord {
+        script_hash: Digest::try_from(script_hash)?,
+        name,
+        script: NoteScript::read_from_bytes(&script)?,
+    })
+}
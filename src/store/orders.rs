@@ -0,0 +1,246 @@
+use core::fmt;
+
+use objects::{accounts::AccountId, notes::NoteId, Digest};
+use rusqlite::{params, Transaction};
+
+use super::Store;
+use crate::errors::StoreError;
+
+// TYPES
+// ================================================================================================
+type SerializedOrderData = (
+    String,
+    String,
+    String,
+    i64,
+    String,
+    Option<u32>,
+    String,
+    Option<u32>,
+);
+
+// ORDERS
+// ================================================================================================
+
+/// Script of a note tracked as an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderKind {
+    /// SWAP note, filled by the account that sends the requested asset back to the sender
+    Swap,
+    /// P2IDR note, filled by its target, which the sender can recall from its recall height on
+    P2idr,
+}
+
+impl OrderKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            OrderKind::Swap => "swap",
+            OrderKind::P2idr => "p2idr",
+        }
+    }
+}
+
+impl fmt::Display for OrderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderKind::Swap => write!(f, "SWAP"),
+            OrderKind::P2idr => write!(f, "P2IDR"),
+        }
+    }
+}
+
+/// Stage of the lifecycle of an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderStatus {
+    /// The note awaits its counterparty
+    Open,
+    /// The chain reached the recall height of the note while it was open. Its target can still
+    /// consume it until the sender recalls it
+    Expired,
+    /// The note was consumed by an account other than its sender
+    Filled,
+    /// The note was consumed by its sender
+    Recalled,
+}
+
+impl OrderStatus {
+    /// Returns whether the note of the order was consumed
+    pub fn is_closed(&self) -> bool {
+        matches!(self, OrderStatus::Filled | OrderStatus::Recalled)
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            OrderStatus::Open => "open",
+            OrderStatus::Expired => "expired",
+            OrderStatus::Filled => "filled",
+            OrderStatus::Recalled => "recalled",
+        }
+    }
+}
+
+impl fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A note created by one of the client's accounts that awaits a counterparty, along with the
+/// stage of its lifecycle, which syncing updates as the chain progresses and the note's nullifier
+/// is spent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderRecord {
+    pub note_id: NoteId,
+    pub nullifier: Digest,
+    pub kind: OrderKind,
+    /// Account that created the note
+    pub account_id: AccountId,
+    pub transaction_id: Digest,
+    /// Block from which the sender can recall the note, if it can
+    pub recall_height: Option<u32>,
+    pub status: OrderStatus,
+    /// Block the note was consumed in, if it was filled or recalled
+    pub closed_height: Option<u32>,
+}
+
+impl Store {
+    // ORDERS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the orders of the client's accounts, in the order they were created
+    pub fn get_orders(&self) -> Result<Vec<OrderRecord>, StoreError> {
+        const QUERY: &str = "SELECT note_id, nullifier, kind, account_id, transaction_id, \
+            recall_height, status, closed_height FROM orders ORDER BY rowid";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map([], parse_order_columns)
+            .expect("no binding parameters used in query")
+            .map(|result| Ok(result?).and_then(parse_order))
+            .collect()
+    }
+
+    /// Starts tracking the specified orders, which are expected to be open
+    pub(super) fn insert_orders_tx(
+        tx: &Transaction<'_>,
+        orders: &[OrderRecord],
+    ) -> Result<(), StoreError> {
+        const QUERY: &str = "INSERT OR IGNORE INTO orders \
+            (note_id, nullifier, kind, account_id, transaction_id, recall_height, status, closed_height) \
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)";
+
+        let mut statement = tx.prepare_cached(QUERY)?;
+        for order in orders {
+            statement.execute(params![
+                order.note_id.inner().to_string(),
+                order.nullifier.to_string(),
+                order.kind.as_str(),
+                u64::from(order.account_id) as i64,
+                String::from(order.transaction_id),
+                order.recall_height,
+                order.status.as_str(),
+                order.closed_height,
+            ])?;
+        }
+        Ok(())
+    }
+
+    /// Moves the orders whose note was consumed in block `block_num` to the specified status,
+    /// then marks the open orders whose recall height is reached by the block as expired
+    pub(super) fn update_orders_tx(
+        tx: &Transaction<'_>,
+        closed_orders: &[(NoteId, OrderStatus)],
+        block_num: u32,
+    ) -> Result<(), StoreError> {
+        const CLOSED_QUERY: &str = "UPDATE orders SET status = ?, closed_height = ? \
+            WHERE note_id = ? AND status IN ('open', 'expired')";
+        let mut closed_statement = tx.prepare_cached(CLOSED_QUERY)?;
+        for (note_id, status) in closed_orders {
+            closed_statement.execute(params![
+                status.as_str(),
+                block_num,
+                note_id.inner().to_string()
+            ])?;
+        }
+        drop(closed_statement);
+
+        const EXPIRED_QUERY: &str =
+            "UPDATE orders SET status = 'expired' WHERE status = 'open' AND recall_height <= ?";
+        tx.execute(EXPIRED_QUERY, params![block_num])?;
+        Ok(())
+    }
+
+    /// Reopens the orders closed after `fork_block` and sets back the expiration of the orders
+    /// whose recall height is past it
+    pub(super) fn rollback_orders_tx(
+        tx: &Transaction<'_>,
+        fork_block: u32,
+    ) -> Result<(), StoreError> {
+        const QUERIES: [&str; 2] = [
+            "UPDATE orders SET status = 'open', closed_height = NULL WHERE closed_height > ?1",
+            "UPDATE orders SET status = CASE WHEN recall_height <= ?1 THEN 'expired' ELSE 'open' END \
+            WHERE status IN ('open', 'expired')",
+        ];
+        for query in QUERIES {
+            tx.execute(query, params![fork_block])?;
+        }
+        Ok(())
+    }
+}
+
+// HELPERS
+// ================================================================================================
+
+fn parse_order_columns(row: &rusqlite::Row<'_>) -> Result<SerializedOrderData, rusqlite::Error> {
+    Ok((
+        row.get(0)?,
+        row.get(1)?,
+        row.get(2)?,
+        row.get(3)?,
+        row.get(4)?,
+        row.get(5)?,
+        row.get(6)?,
+        row.get(7)?,
+    ))
+}
+
+fn parse_order(serialized_order: SerializedOrderData) -> Result<OrderRecord, StoreError> {
+    let (
+        note_id,
+        nullifier,
+        kind,
+        account_id,
+        transaction_id,
+        recall_height,
+        status,
+        closed_height,
+    ) = serialized_order;
+
+    let kind = match kind.as_str() {
+        "swap" => OrderKind::Swap,
+        "p2idr" => OrderKind::P2idr,
+        kind => return Err(invalid_value(format!("Invalid order kind: {kind}"))),
+    };
+    let status = match status.as_str() {
+        "open" => OrderStatus::Open,
+        "expired" => OrderStatus::Expired,
+        "filled" => OrderStatus::Filled,
+        "recalled" => OrderStatus::Recalled,
+        status => return Err(invalid_value(format!("Invalid order status: {status}"))),
+    };
+
+    Ok(OrderRecord {
+        note_id: Digest::try_from(note_id)?.into(),
+        nullifier: Digest::try_from(nullifier)?,
+        kind,
+        account_id: AccountId::try_from(account_id as u64)?,
+        transaction_id: Digest::try_from(transaction_id)?,
+        recall_height,
+        status,
+        closed_height,
+    })
+}
+
+fn invalid_value(message: String) -> StoreError {
+    StoreError::DataDeserializationError(crypto::utils::DeserializationError::InvalidValue(message))
+}
@@ -0,0 +1,142 @@
+use std::{
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex},
+};
+
+use rusqlite::Connection;
+
+use super::Store;
+use crate::{config::StoreConfig, errors::StoreError};
+
+// STORE POOL
+// ================================================================================================
+
+/// A pool of connections to a single database, handing out [Store] instances that can be used
+/// from different threads at the same time.
+///
+/// Connections are opened on demand and returned to the pool when the [PooledStore] using them
+/// is dropped, so that they can be reused instead of reopening the database for every operation.
+pub struct StorePool {
+    config: StoreConfig,
+    idle_connections: Mutex<Vec<Connection>>,
+    max_idle_connections: usize,
+}
+
+impl StorePool {
+    /// Returns a new pool for the store specified by `config`, keeping at most
+    /// `max_idle_connections` connections open while they are not in use.
+    ///
+    /// The database is migrated to the latest schema version when the pool is created.
+    pub fn new(config: StoreConfig, max_idle_connections: usize) -> Result<Arc<Self>, StoreError> {
+        let store = Store::new(config.clone())?;
+
+        Ok(Arc::new(Self {
+            config,
+            idle_connections: Mutex::new(vec![store.db]),
+            max_idle_connections,
+        }))
+    }
+
+    /// Returns a store backed by an idle connection of the pool, or by a new connection if all of
+    /// them are in use.
+    pub fn get(self: &Arc<Self>) -> Result<PooledStore, StoreError> {
+        let idle_connection = self
+            .idle_connections
+            .lock()
+            .expect("store pool lock is not poisoned")
+            .pop();
+
+        let db = match idle_connection {
+            Some(db) => db,
+            None => Store::open(
+                &self.config.database_filepath,
+                self.config.passphrase.as_deref(),
+            )?,
+        };
+
+        Ok(PooledStore {
+            store: Some(Store { db }),
+            pool: self.clone(),
+        })
+    }
+
+    fn release(&self, db: Connection) {
+        let mut idle_connections = self
+            .idle_connections
+            .lock()
+            .expect("store pool lock is not poisoned");
+
+        if idle_connections.len() < self.max_idle_connections {
+            idle_connections.push(db);
+        }
+    }
+}
+
+// POOLED STORE
+// ================================================================================================
+
+/// A [Store] borrowed from a [StorePool], whose connection goes back to the pool when dropped.
+pub struct PooledStore {
+    store: Option<Store>,
+    pool: Arc<StorePool>,
+}
+
+impl Deref for PooledStore {
+    type Target = Store;
+
+    fn deref(&self) -> &Self::Target {
+        self.store.as_ref().expect("store is only taken on drop")
+    }
+}
+
+impl DerefMut for PooledStore {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.store.as_mut().expect("store is only taken on drop")
+    }
+}
+
+impl Drop for PooledStore {
+    fn drop(&mut self) {
+        if let Some(store) = self.store.take() {
+            self.pool.release(store.db);
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::StorePool;
+    use crate::store::{sync::NoteTagSource, tests::create_test_store_path};
+
+    #[test]
+    fn pooled_stores_write_concurrently() {
+        let store_path = create_test_store_path();
+        let pool = StorePool::new(store_path.to_str().unwrap().try_into().unwrap(), 2).unwrap();
+
+        let handles = (0..4u64)
+            .map(|thread_idx| {
+                let pool = pool.clone();
+                thread::spawn(move || {
+                    for tag in 0..10 {
+                        let mut store = pool.get().unwrap();
+                        store
+                            .add_note_tag(thread_idx * 10 + tag, NoteTagSource::User, None)
+                            .unwrap();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let mut tags = pool.get().unwrap().get_note_tags().unwrap();
+        tags.sort();
+        assert_eq!(tags, (0..40).collect::<Vec<_>>());
+    }
+}
@@ -1,54 +1,167 @@
-use crypto::{
-    merkle::{InOrderIndex, MmrPeaks},
-    utils::Serializable,
-};
+use crypto::merkle::{InOrderIndex, MerklePath, MmrPeaks};
 
 use objects::{
+    accounts::AccountId,
     notes::{NoteId, NoteInclusionProof},
     BlockHeader, Digest,
 };
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension, Transaction};
 
-use crate::{errors::StoreError, store::transactions::TransactionFilter};
+use crate::{
+    errors::StoreError,
+    store::{
+        accounts::{AccountProof, DesynchronizedAccount},
+        notes::{unix_timestamp, InputNoteRecord, NoteOrigin},
+        orders::OrderStatus,
+        transactions::TransactionFilter,
+    },
+};
 
 use super::Store;
 
+// TYPES
+// ================================================================================================
+type SerializedNoteTagData = (i64, String, Option<String>, Option<i64>, Option<String>);
+type SerializedQuarantinedNoteData = (String, u32, i64, String, String);
+type SerializedQuarantinedNoteParts = (String, u32, u64, String, String);
+
+// QUARANTINED NOTE
+// ================================================================================================
+
+/// A note reported as committed by the node whose inclusion proof could not be built while
+/// syncing. It is set aside so that the rest of the sync can be applied, and can be retried later.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuarantinedNote {
+    pub note_id: NoteId,
+    pub block_num: u32,
+    pub note_index: u64,
+    pub note_path: MerklePath,
+    /// Description of the error that caused the note to be quarantined
+    pub reason: String,
+}
+
+// NOTE TAGS
+// ================================================================================================
+
+/// What caused a note tag to be tracked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteTagSource {
+    /// Added by the user, tracked until removed
+    User,
+    /// Added to retrieve the specified note, such as an imported note that is not committed yet.
+    /// Tracked until the note is consumed
+    Note(NoteId),
+    /// Added to retrieve the notes targeting the specified account
+    Account(AccountId),
+}
+
+/// A registration of a note tag in the store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteTagRecord {
+    pub tag: u64,
+    pub source: NoteTagSource,
+    pub label: Option<String>,
+}
+
+// WATCHED NULLIFIER
+// ================================================================================================
+
+/// The nullifier of a note the client does not hold, such as a note it created for another
+/// account, watched so that syncing reports when the note is consumed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchedNullifier {
+    pub nullifier: Digest,
+    pub label: Option<String>,
+    /// Block up to which the nullifier was checked against the node
+    pub checked_height: u32,
+    /// Block the note was consumed in, if syncing found it consumed
+    pub consumed_height: Option<u32>,
+}
+
 impl Store {
     // STATE SYNC
     // --------------------------------------------------------------------------------------------
 
-    /// Returns the note tags that the client is interested in.
+    /// Returns the note tags that the client is interested in, in the order they were added. The
+    /// tags registered for hidden accounts are left out.
     pub fn get_note_tags(&self) -> Result<Vec<u64>, StoreError> {
-        const QUERY: &str = "SELECT tags FROM state_sync";
+        const QUERY: &str = "SELECT tag FROM note_tags \
+            WHERE source_account_id IS NULL \
+            OR source_account_id NOT IN (SELECT account_id FROM hidden_accounts) \
+            GROUP BY tag ORDER BY MIN(rowid)";
 
         self.db
             .prepare(QUERY)?
-            .query_map([], |row| row.get(0))
-            .expect("no binding parameters used in query")
-            .map(|result| {
-                result
-                    .map_err(|err| StoreError::ParsingError(err.to_string()))
-                    .and_then(|v: String| {
-                        serde_json::from_str(&v).map_err(StoreError::JsonDataDeserializationError)
-                    })
-            })
-            .next()
-            .expect("state sync tags exist")
+            .query_map([], |row| row.get(0))?
+            .map(|result| Ok(result?).map(|tag: i64| tag as u64))
+            .collect()
     }
 
-    /// Adds a note tag to the list of tags that the client is interested in.
-    pub fn add_note_tag(&mut self, tag: u64) -> Result<bool, StoreError> {
-        let mut tags = self.get_note_tags()?;
-        if tags.contains(&tag) {
-            return Ok(false);
-        }
-        tags.push(tag);
-        let tags = serde_json::to_string(&tags).map_err(StoreError::InputSerializationError)?;
+    /// Returns every registration of the tracked note tags, along with what added them. A tag can
+    /// be registered several times, e.g. by the user and for an imported note.
+    pub fn get_note_tag_records(&self) -> Result<Vec<NoteTagRecord>, StoreError> {
+        const QUERY: &str =
+            "SELECT tag, source, source_note_id, source_account_id, label FROM note_tags ORDER BY rowid";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map([], parse_note_tag_columns)?
+            .map(|result| Ok(result?).and_then(parse_note_tag))
+            .collect()
+    }
+
+    /// Registers a note tag the client is interested in. Returns false if the tag was already
+    /// registered by the same source, in which case the store is left unchanged.
+    pub fn add_note_tag(
+        &mut self,
+        tag: u64,
+        source: NoteTagSource,
+        label: Option<&str>,
+    ) -> Result<bool, StoreError> {
+        const QUERY: &str = "INSERT OR IGNORE INTO note_tags \
+            (tag, source, source_note_id, source_account_id, label) VALUES (?, ?, ?, ?, ?)";
 
-        const QUERY: &str = "UPDATE state_sync SET tags = ?";
-        self.db.execute(QUERY, params![tags])?;
+        let (source, source_note_id, source_account_id) = serialize_note_tag_source(source);
+        let inserted_rows = self.db.execute(
+            QUERY,
+            params![tag as i64, source, source_note_id, source_account_id, label],
+        )?;
+
+        Ok(inserted_rows > 0)
+    }
+
+    /// Stops tracking a note tag, whatever source registered it. Returns false if the tag was not
+    /// being tracked.
+    pub fn remove_note_tag(&mut self, tag: u64) -> Result<bool, StoreError> {
+        const QUERY: &str = "DELETE FROM note_tags WHERE tag = ?";
 
-        Ok(true)
+        let removed_rows = self.db.execute(QUERY, params![tag as i64])?;
+        Ok(removed_rows > 0)
+    }
+
+    /// Drops the tags registered for notes that have since been consumed
+    fn remove_consumed_note_tags(tx: &Transaction<'_>) -> Result<(), StoreError> {
+        const QUERY: &str = "DELETE FROM note_tags WHERE source = 'note' AND source_note_id IN \
+            (SELECT note_id FROM input_notes WHERE status = 'consumed')";
+
+        tx.execute(QUERY, [])?;
+        Ok(())
+    }
+
+    /// Returns the block number the specified account was last synced to, or `None` if it was
+    /// never synced.
+    pub fn get_account_sync_height(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<u32>, StoreError> {
+        const QUERY: &str = "SELECT block_num FROM account_sync_heights WHERE account_id = ?";
+
+        Ok(self
+            .db
+            .query_row(QUERY, params![u64::from(account_id) as i64], |row| {
+                row.get(0)
+            })
+            .optional()?)
     }
 
     /// Returns the block number of the last state sync block.
@@ -64,39 +177,111 @@ impl Store {
             .expect("state sync block number exists")
     }
 
+    /// Returns the chain tip the last sync was heading to if it was interrupted before reaching
+    /// it, or `None` if the client was synced to the chain tip it last knew of.
+    pub fn get_sync_target(&self) -> Result<Option<u32>, StoreError> {
+        const QUERY: &str = "SELECT target_block_num FROM state_sync";
+
+        let target_block_num: Option<i64> = self.db.query_row(QUERY, [], |row| row.get(0))?;
+        Ok(target_block_num.map(|block_num| block_num as u32))
+    }
+
     /// Applies the state sync update to the store. An update involves:
     ///
     /// - Inserting the new block header to the store alongside new MMR peaks information
     /// - Updating the notes, marking them as `committed` or `consumed` based on incoming
     ///   inclusion proofs and nullifiers
+    /// - Quarantining the notes whose inclusion proofs could not be built
+    /// - Recording the arrival of the notes expected by ID
     /// - Storing new MMR authentication nodes
+    /// - Flagging the accounts whose on-chain state differs from the local one, and clearing the
+    ///   flag of the ones whose on-chain state matches a local one again
+    /// - Updating the sync height of the synced accounts, and the height up to which the checked
+    ///   nullifiers have been checked
+    /// - Recording the block each of the consumed watched nullifiers was found in
+    /// - Recording `chain_tip` as the target of the sync if the update does not reach it, so that
+    ///   an interrupted sync resumes from this update knowing how far it has to go
+    #[allow(clippy::too_many_arguments)]
     pub fn apply_state_sync(
         &mut self,
+        synced_accounts: &[AccountId],
         block_header: BlockHeader,
+        chain_tip: u32,
         nullifiers: Vec<Digest>,
+        checked_nullifiers: &[Digest],
+        consumed_watched_nullifiers: &[(Digest, u32)],
+        closed_orders: &[(NoteId, OrderStatus)],
         committed_notes: Vec<(NoteId, NoteInclusionProof)>,
+        committed_expected_note_ids: &[NoteId],
+        quarantined_notes: &[QuarantinedNote],
+        account_proofs: &[AccountProof],
+        desynchronized_accounts: &[DesynchronizedAccount],
+        resynchronized_accounts: &[AccountId],
         new_mmr_peaks: MmrPeaks,
         new_authentication_nodes: &[(InOrderIndex, Digest)],
     ) -> Result<(), StoreError> {
-        let uncommitted_transactions = self.get_transactions(TransactionFilter::Uncomitted)?;
+        let tx = self.write_transaction()?;
+        let uncommitted_transactions =
+            Store::get_transactions_tx(&tx, TransactionFilter::Uncomitted)?;
 
-        let tx = self.db.transaction()?;
+        // Accounts left out of the sync keep the height they were synced to, from which their
+        // notes are caught up later
+        const LAGGING_ACCOUNTS_QUERY: &str = "INSERT OR IGNORE INTO account_sync_heights \
+            (account_id, block_num) SELECT DISTINCT id, (SELECT block_num FROM state_sync) FROM accounts";
+        tx.execute(LAGGING_ACCOUNTS_QUERY, [])?;
 
-        // Update state sync block number
-        const BLOCK_NUMBER_QUERY: &str = "UPDATE state_sync SET block_num = ?";
-        tx.execute(BLOCK_NUMBER_QUERY, params![block_header.block_num()])?;
+        // Update state sync block number, checkpointing the sync until the chain tip is reached
+        const BLOCK_NUMBER_QUERY: &str =
+            "UPDATE state_sync SET block_num = ?, target_block_num = ?";
+        let target_block_num = (block_header.block_num() < chain_tip).then_some(chain_tip);
+        tx.execute(
+            BLOCK_NUMBER_QUERY,
+            params![block_header.block_num(), target_block_num],
+        )?;
 
-        // Update spent notes
-        for nullifier in nullifiers {
-            const SPENT_QUERY: &str =
-                "UPDATE input_notes SET status = 'consumed' WHERE nullifier = ?";
-            let nullifier = nullifier.to_string();
-            tx.execute(SPENT_QUERY, params![nullifier])?;
+        const ACCOUNT_BLOCK_NUMBER_QUERY: &str =
+            "INSERT OR REPLACE INTO account_sync_heights (account_id, block_num) VALUES (?, ?)";
+        let mut account_block_number_statement = tx.prepare_cached(ACCOUNT_BLOCK_NUMBER_QUERY)?;
+        for account_id in synced_accounts {
+            account_block_number_statement.execute(params![
+                u64::from(*account_id) as i64,
+                block_header.block_num()
+            ])?;
+        }
+        drop(account_block_number_statement);
+
+        const CHECKED_NULLIFIER_QUERY: &str =
+            "UPDATE input_notes SET nullifier_checked_height = ? WHERE nullifier = ?";
+        let mut checked_nullifier_statement = tx.prepare_cached(CHECKED_NULLIFIER_QUERY)?;
+        for nullifier in checked_nullifiers {
+            checked_nullifier_statement
+                .execute(params![block_header.block_num(), nullifier.to_string()])?;
         }
+        drop(checked_nullifier_statement);
+
+        const CHECKED_WATCHED_NULLIFIER_QUERY: &str =
+            "UPDATE watched_nullifiers SET checked_height = ? WHERE nullifier = ?";
+        let mut checked_watched_statement = tx.prepare_cached(CHECKED_WATCHED_NULLIFIER_QUERY)?;
+        for nullifier in checked_nullifiers {
+            checked_watched_statement
+                .execute(params![block_header.block_num(), nullifier.to_string()])?;
+        }
+        drop(checked_watched_statement);
+
+        const CONSUMED_WATCHED_NULLIFIER_QUERY: &str = "UPDATE watched_nullifiers \
+            SET consumed_height = ? WHERE nullifier = ? AND consumed_height IS NULL";
+        let mut consumed_watched_statement = tx.prepare_cached(CONSUMED_WATCHED_NULLIFIER_QUERY)?;
+        for (nullifier, block_num) in consumed_watched_nullifiers {
+            consumed_watched_statement.execute(params![block_num, nullifier.to_string()])?;
+        }
+        drop(consumed_watched_statement);
+
+        Store::update_orders_tx(&tx, closed_orders, block_header.block_num())?;
 
         // TODO: Due to the fact that notes are returned based on fuzzy matching of tags,
         // this process of marking if the header has notes needs to be revisited
-        let block_has_relevant_notes = !committed_notes.is_empty();
+        // Quarantined notes count as relevant so that their block stays authenticated for retries
+        let block_has_relevant_notes = !committed_notes.is_empty() || !quarantined_notes.is_empty();
         Store::insert_block_header(&tx, block_header, new_mmr_peaks, block_has_relevant_notes)?;
 
         // Insert new authentication nodes (inner nodes of the PartialMmr)
@@ -104,16 +289,45 @@ impl Store {
 
         // Update tracked notes
         for (note_id, inclusion_proof) in committed_notes.iter() {
-            const SPENT_QUERY: &str =
-                "UPDATE input_notes SET status = 'committed', inclusion_proof = ? WHERE note_id = ?";
+            Store::commit_note(&tx, *note_id, inclusion_proof)?;
+        }
 
-            let inclusion_proof = Some(inclusion_proof.to_bytes());
-            tx.execute(
-                SPENT_QUERY,
-                params![inclusion_proof, note_id.inner().to_string()],
-            )?;
+        // Update spent notes, once the notes consumed in the blocks that committed them are
+        // committed
+        const SPENT_QUERY: &str =
+            "UPDATE input_notes SET status = 'consumed', consumed_height = ? WHERE nullifier = ?";
+        let mut spent_statement = tx.prepare_cached(SPENT_QUERY)?;
+        for nullifier in nullifiers {
+            let nullifier = nullifier.to_string();
+            spent_statement.execute(params![block_header.block_num(), nullifier])?;
+        }
+        drop(spent_statement);
+        Store::remove_consumed_note_tags(&tx)?;
+
+        Store::commit_expected_note_ids_tx(
+            &tx,
+            committed_expected_note_ids,
+            block_header.block_num(),
+        )?;
+
+        for quarantined_note in quarantined_notes {
+            Store::insert_quarantined_note(&tx, quarantined_note)?;
+        }
+
+        for account_proof in account_proofs {
+            Store::insert_account_proof_tx(&tx, account_proof)?;
+        }
+
+        for desynchronized_account in desynchronized_accounts {
+            Store::insert_desynchronized_account_tx(&tx, desynchronized_account)?;
+        }
+        for account_id in resynchronized_accounts {
+            Store::remove_desynchronized_account_tx(&tx, *account_id)?;
         }
 
+        // Normalize proofs of previously imported notes whose block header is now tracked
+        Store::normalize_block_inclusion_proofs(&tx, block_header.block_num())?;
+
         let note_ids: Vec<NoteId> = committed_notes.iter().map(|(id, _)| (*id)).collect();
 
         Store::mark_transactions_as_committed_by_note_id(
@@ -128,4 +342,471 @@ impl Store {
 
         Ok(())
     }
+
+    /// Applies a step of the catch-up of accounts whose sync height is behind the client's,
+    /// which retrieved the notes of the accounts committed up to block `block_num`:
+    ///
+    /// - Committing the retrieved notes along with their full inclusion proof, as their block
+    ///   headers may not be tracked
+    /// - Recording the arrival of the notes expected by ID and quarantining the notes whose proof
+    ///   could not be built
+    /// - Updating the sync height of the accounts to `block_num`
+    pub fn apply_account_catch_up(
+        &mut self,
+        account_ids: &[AccountId],
+        block_num: u32,
+        committed_notes: &[InputNoteRecord],
+        committed_expected_note_ids: &[NoteId],
+        quarantined_notes: &[QuarantinedNote],
+    ) -> Result<(), StoreError> {
+        let tx = self.write_transaction()?;
+        let uncommitted_transactions =
+            Store::get_transactions_tx(&tx, TransactionFilter::Uncomitted)?;
+
+        for note in committed_notes {
+            Store::insert_input_note_tx(&tx, note, &NoteOrigin::Imported)?;
+        }
+        Store::commit_expected_note_ids_tx(&tx, committed_expected_note_ids, block_num)?;
+        for quarantined_note in quarantined_notes {
+            Store::insert_quarantined_note(&tx, quarantined_note)?;
+        }
+
+        let note_ids: Vec<NoteId> = committed_notes.iter().map(|note| note.note_id()).collect();
+        Store::mark_transactions_as_committed_by_note_id(
+            &uncommitted_transactions,
+            &note_ids,
+            block_num,
+            &tx,
+        )?;
+
+        const ACCOUNT_BLOCK_NUMBER_QUERY: &str =
+            "INSERT OR REPLACE INTO account_sync_heights (account_id, block_num) VALUES (?, ?)";
+        let mut account_block_number_statement = tx.prepare_cached(ACCOUNT_BLOCK_NUMBER_QUERY)?;
+        for account_id in account_ids {
+            account_block_number_statement
+                .execute(params![u64::from(*account_id) as i64, block_num])?;
+        }
+        drop(account_block_number_statement);
+
+        Ok(tx.commit()?)
+    }
+
+    /// Marks the note as committed and stores its inclusion path
+    fn commit_note(
+        tx: &Transaction<'_>,
+        note_id: NoteId,
+        inclusion_proof: &NoteInclusionProof,
+    ) -> Result<(), StoreError> {
+        const COMMITTED_QUERY: &str =
+            "UPDATE input_notes SET status = 'committed', inclusion_proof = NULL, commit_height = ?, committed_at = ? WHERE note_id = ?";
+
+        tx.prepare_cached(COMMITTED_QUERY)?.execute(params![
+            inclusion_proof.origin().block_num,
+            unix_timestamp() as i64,
+            note_id.inner().to_string()
+        ])?;
+
+        Store::insert_note_inclusion_path(tx, note_id, inclusion_proof)
+    }
+
+    // REORG ROLLBACK
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the numbers of the stored block headers below `block_num`, from highest to lowest
+    pub fn get_block_numbers_below(&self, block_num: u32) -> Result<Vec<u32>, StoreError> {
+        const QUERY: &str =
+            "SELECT block_num FROM block_headers WHERE block_num < ? AND chain_mmr_peaks IS NOT NULL \
+            ORDER BY block_num DESC";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map(params![block_num], |row| row.get(0))?
+            .map(|result| Ok(result?))
+            .collect()
+    }
+
+    /// Reverts the sync state to `fork_block` after the chain was reorganized past it.
+    ///
+    /// This discards the block headers and MMR authentication nodes of later blocks, reverts notes
+    /// committed or consumed after the fork, and the orders closed after it, to their previous
+    /// status and clears the commit height of transactions committed after it, so that all of them
+    /// are picked up again by the next sync.
+    ///
+    /// Account states recorded after the fork that do not result from a delta applied by the
+    /// client, such as the ones taken from the node to replace desynchronized accounts, are
+    /// discarded as well, so that the accounts are back to their state at the fork. The states
+    /// resulting from the client's transactions are kept, as the transactions are still tracked.
+    ///
+    /// The header of `fork_block` is expected to be stored.
+    pub fn rollback_to_block(&mut self, fork_block: u32) -> Result<(), StoreError> {
+        let tx = self.write_transaction()?;
+
+        const SYNC_QUERY: &str = "UPDATE state_sync SET block_num = ?";
+        tx.execute(SYNC_QUERY, params![fork_block])?;
+
+        const ACCOUNT_SYNC_QUERY: &str =
+            "UPDATE account_sync_heights SET block_num = ? WHERE block_num > ?";
+        tx.execute(ACCOUNT_SYNC_QUERY, params![fork_block, fork_block])?;
+
+        const NULLIFIER_CHECK_QUERY: &str = "UPDATE input_notes SET nullifier_checked_height = ? \
+            WHERE nullifier_checked_height > ?";
+        tx.execute(NULLIFIER_CHECK_QUERY, params![fork_block, fork_block])?;
+
+        const WATCHED_NULLIFIER_QUERIES: [&str; 2] = [
+            "UPDATE watched_nullifiers SET consumed_height = NULL WHERE consumed_height > ?1",
+            "UPDATE watched_nullifiers SET checked_height = ?1 WHERE checked_height > ?1",
+        ];
+        for query in WATCHED_NULLIFIER_QUERIES {
+            tx.execute(query, params![fork_block])?;
+        }
+        Store::rollback_orders_tx(&tx, fork_block)?;
+
+        const CONSUMED_QUERY: &str =
+            "UPDATE input_notes SET status = 'committed', consumed_height = NULL \
+            WHERE status = 'consumed' AND consumed_height > ?";
+        tx.execute(CONSUMED_QUERY, params![fork_block])?;
+
+        const COMMITTED_QUERY: &str =
+            "UPDATE input_notes SET status = 'pending', commit_height = 0, committed_at = NULL, \
+            consumed_height = NULL \
+            WHERE status NOT IN ('pending', 'expected') AND commit_height > ?";
+        tx.execute(COMMITTED_QUERY, params![fork_block])?;
+        Store::uncommit_expected_note_ids_tx(&tx, fork_block)?;
+
+        const PATHS_QUERY: &str = "DELETE FROM note_inclusion_paths WHERE block_num > ?";
+        tx.execute(PATHS_QUERY, params![fork_block])?;
+
+        const QUARANTINE_QUERY: &str = "DELETE FROM sync_quarantine WHERE block_num > ?";
+        tx.execute(QUARANTINE_QUERY, params![fork_block])?;
+
+        const ACCOUNT_PROOFS_QUERY: &str = "DELETE FROM account_proofs WHERE block_num > ?";
+        tx.execute(ACCOUNT_PROOFS_QUERY, params![fork_block])?;
+
+        const DESYNCHRONIZED_ACCOUNTS_QUERY: &str =
+            "DELETE FROM desynchronized_accounts WHERE block_num > ?";
+        tx.execute(DESYNCHRONIZED_ACCOUNTS_QUERY, params![fork_block])?;
+
+        // the first state of an account is the one it was created or imported with
+        const ACCOUNT_STATES_QUERY: &str = "DELETE FROM accounts WHERE block_num > ? \
+            AND nonce > (SELECT MIN(b.nonce) FROM accounts AS b WHERE b.id = accounts.id) \
+            AND NOT EXISTS (SELECT 1 FROM account_deltas AS d \
+                WHERE d.account_id = accounts.id AND d.nonce = accounts.nonce)";
+        tx.execute(ACCOUNT_STATES_QUERY, params![fork_block])?;
+
+        const TRANSACTIONS_QUERY: &str =
+            "UPDATE transactions SET commit_height = NULL WHERE commit_height > ?";
+        tx.execute(TRANSACTIONS_QUERY, params![fork_block])?;
+
+        const HEADERS_QUERY: &str = "DELETE FROM block_headers WHERE block_num > ?";
+        tx.execute(HEADERS_QUERY, params![fork_block])?;
+
+        // The chain MMR at the fork block has one leaf per block before it, so nodes covering any
+        // later block no longer belong to it
+        let node_ids: Vec<i64> = tx
+            .prepare("SELECT id FROM chain_mmr_nodes")?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        for node_id in node_ids {
+            if !is_in_chain_mmr(node_id as u64, fork_block) {
+                tx.execute("DELETE FROM chain_mmr_nodes WHERE id = ?", params![node_id])?;
+            }
+        }
+
+        Ok(tx.commit()?)
+    }
+
+    // SYNC DATA RESET
+    // --------------------------------------------------------------------------------------------
+
+    /// Clears everything the client learnt from the node, so that the next sync starts over from
+    /// the genesis block, which is fetched again. This is meant for devnets that were restarted,
+    /// where the stored chain data, notes and transactions no longer exist.
+    ///
+    /// Accounts, their keys and code, the address book, account labels, faucet details, named
+    /// note scripts and the note tags registered by the user or for accounts are preserved. The
+    /// states of accounts are kept as they are, no longer attached to any block, while the
+    /// history of the deltas that led to them is dropped.
+    pub fn reset_sync_data(&mut self) -> Result<(), StoreError> {
+        let tx = self.write_transaction()?;
+
+        Self::clear_notes_tx(&tx)?;
+        Self::clear_transactions_tx(&tx)?;
+        Self::clear_chain_data_tx(&tx)?;
+
+        const ACCOUNT_QUERY: &str = "\
+            DELETE FROM account_deltas;
+            UPDATE accounts SET block_num = 0;";
+        tx.execute_batch(ACCOUNT_QUERY)?;
+
+        Ok(tx.commit()?)
+    }
+
+    /// Removes every input note, partial output note and expected note ID, along with the data
+    /// derived from them, such as orders, and the note tags tracked for them
+    fn clear_notes_tx(tx: &Transaction<'_>) -> Result<(), StoreError> {
+        const QUERY: &str = "\
+            DELETE FROM sync_quarantine;
+            DELETE FROM note_inclusion_paths;
+            DELETE FROM input_note_assets;
+            DELETE FROM input_note_targets;
+            DELETE FROM input_notes;
+            DELETE FROM partial_output_notes;
+            DELETE FROM orders;
+            DELETE FROM expected_note_ids;
+            DELETE FROM note_tags WHERE source = 'note';
+            DELETE FROM note_scripts WHERE name IS NULL;";
+        tx.execute_batch(QUERY)?;
+        Ok(())
+    }
+
+    /// Removes every transaction along with the scripts they were executed with
+    fn clear_transactions_tx(tx: &Transaction<'_>) -> Result<(), StoreError> {
+        const QUERY: &str = "\
+            DELETE FROM transaction_outbox;
+            DELETE FROM transactions;
+            DELETE FROM transaction_scripts;";
+        tx.execute_batch(QUERY)?;
+        Ok(())
+    }
+
+    /// Removes the block headers, chain MMR nodes, account proofs and sync heights, and sets the
+    /// sync height back to the genesis block
+    fn clear_chain_data_tx(tx: &Transaction<'_>) -> Result<(), StoreError> {
+        const QUERY: &str = "\
+            DELETE FROM block_headers;
+            DELETE FROM chain_mmr_nodes;
+            DELETE FROM account_proofs;
+            DELETE FROM account_sync_heights;
+            DELETE FROM desynchronized_accounts;
+            UPDATE watched_nullifiers SET checked_height = 0, consumed_height = NULL;
+            UPDATE state_sync SET block_num = 0, target_block_num = NULL;";
+        tx.execute_batch(QUERY)?;
+        Ok(())
+    }
+
+    // SYNC QUARANTINE
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the notes that were quarantined while syncing
+    pub fn get_quarantined_notes(&self) -> Result<Vec<QuarantinedNote>, StoreError> {
+        const QUERY: &str =
+            "SELECT note_id, block_num, note_index, note_path, reason FROM sync_quarantine";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map([], parse_quarantined_note_columns)
+            .expect("no binding parameters used in query")
+            .map(|result| Ok(result?).and_then(parse_quarantined_note))
+            .collect()
+    }
+
+    /// Marks a quarantined note as committed with the provided inclusion proof and releases it
+    /// from the quarantine
+    pub fn release_quarantined_note(
+        &mut self,
+        note_id: NoteId,
+        inclusion_proof: &NoteInclusionProof,
+    ) -> Result<(), StoreError> {
+        let tx = self.write_transaction()?;
+        let uncommitted_transactions =
+            Store::get_transactions_tx(&tx, TransactionFilter::Uncomitted)?;
+
+        Store::commit_note(&tx, note_id, inclusion_proof)?;
+        Store::mark_transactions_as_committed_by_note_id(
+            &uncommitted_transactions,
+            &[note_id],
+            inclusion_proof.origin().block_num,
+            &tx,
+        )?;
+
+        const QUERY: &str = "DELETE FROM sync_quarantine WHERE note_id = ?";
+        tx.execute(QUERY, params![note_id.inner().to_string()])?;
+
+        Ok(tx.commit()?)
+    }
+
+    /// Inserts a note into the quarantine, replacing it if it was already quarantined
+    pub fn quarantine_note(
+        &mut self,
+        quarantined_note: &QuarantinedNote,
+    ) -> Result<(), StoreError> {
+        let tx = self.write_transaction()?;
+        Store::insert_quarantined_note(&tx, quarantined_note)?;
+
+        Ok(tx.commit()?)
+    }
+
+    fn insert_quarantined_note(
+        tx: &Transaction<'_>,
+        quarantined_note: &QuarantinedNote,
+    ) -> Result<(), StoreError> {
+        let (note_id, block_num, note_index, note_path, reason) =
+            serialize_quarantined_note(quarantined_note)?;
+
+        const QUERY: &str = "INSERT OR REPLACE INTO sync_quarantine (note_id, block_num, note_index, note_path, reason) VALUES (?, ?, ?, ?, ?)";
+        tx.execute(
+            QUERY,
+            params![note_id, block_num, note_index, note_path, reason],
+        )?;
+
+        Ok(())
+    }
+
+    // WATCHED NULLIFIERS
+    // --------------------------------------------------------------------------------------------
+
+    /// Starts watching a nullifier, which is checked against the node from block
+    /// `from_block_num` on. Returns false if the nullifier was already watched, in which case the
+    /// store is left unchanged.
+    pub fn watch_nullifier(
+        &mut self,
+        nullifier: Digest,
+        label: Option<&str>,
+        from_block_num: u32,
+    ) -> Result<bool, StoreError> {
+        const QUERY: &str = "INSERT OR IGNORE INTO watched_nullifiers (nullifier, label, checked_height) VALUES (?, ?, ?)";
+
+        let inserted = self
+            .db
+            .execute(QUERY, params![nullifier.to_string(), label, from_block_num])?;
+        Ok(inserted > 0)
+    }
+
+    /// Stops watching a nullifier. Returns false if the nullifier was not watched
+    pub fn unwatch_nullifier(&mut self, nullifier: Digest) -> Result<bool, StoreError> {
+        const QUERY: &str = "DELETE FROM watched_nullifiers WHERE nullifier = ?";
+
+        let removed = self.db.execute(QUERY, params![nullifier.to_string()])?;
+        Ok(removed > 0)
+    }
+
+    /// Returns the watched nullifiers, in the order they were added
+    pub fn get_watched_nullifiers(&self) -> Result<Vec<WatchedNullifier>, StoreError> {
+        const QUERY: &str = "SELECT nullifier, label, checked_height, consumed_height \
+            FROM watched_nullifiers ORDER BY rowid";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .expect("no binding parameters used in query")
+            .map(|result| {
+                let (nullifier, label, checked_height, consumed_height): (
+                    String,
+                    Option<String>,
+                    u32,
+                    Option<u32>,
+                ) = result?;
+                Ok(WatchedNullifier {
+                    nullifier: Digest::try_from(nullifier)?,
+                    label,
+                    checked_height,
+                    consumed_height,
+                })
+            })
+            .collect()
+    }
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Returns whether the MMR node with the specified in-order index belongs to an MMR with `forest`
+/// leaves, that is, whether every leaf below the node is one of the first `forest` leaves.
+fn is_in_chain_mmr(in_order_index: u64, forest: u32) -> bool {
+    // A node at height `h` has `h` trailing zeros in its in-order index and its rightmost leaf is
+    // `2^h - 1` positions to its right. Leaf `i` is at in-order index `2 * i + 1`.
+    let height = in_order_index.trailing_zeros();
+    let rightmost_leaf = (in_order_index + (1 << height) - 2) / 2;
+
+    rightmost_leaf < forest as u64
+}
+
+fn serialize_note_tag_source(source: NoteTagSource) -> (&'static str, Option<String>, Option<i64>) {
+    match source {
+        NoteTagSource::User => ("user", None, None),
+        NoteTagSource::Note(note_id) => ("note", Some(note_id.inner().to_string()), None),
+        NoteTagSource::Account(account_id) => ("account", None, Some(u64::from(account_id) as i64)),
+    }
+}
+
+fn parse_note_tag_columns(
+    row: &rusqlite::Row<'_>,
+) -> Result<SerializedNoteTagData, rusqlite::Error> {
+    let tag: i64 = row.get(0)?;
+    let source: String = row.get(1)?;
+    let source_note_id: Option<String> = row.get(2)?;
+    let source_account_id: Option<i64> = row.get(3)?;
+    let label: Option<String> = row.get(4)?;
+    Ok((tag, source, source_note_id, source_account_id, label))
+}
+
+fn parse_note_tag(serialized_note_tag: SerializedNoteTagData) -> Result<NoteTagRecord, StoreError> {
+    let (tag, source, source_note_id, source_account_id, label) = serialized_note_tag;
+
+    let source = match (source.as_str(), source_note_id, source_account_id) {
+        ("user", None, None) => NoteTagSource::User,
+        ("note", Some(note_id), None) => NoteTagSource::Note(Digest::try_from(note_id)?.into()),
+        ("account", None, Some(account_id)) => {
+            NoteTagSource::Account(AccountId::try_from(account_id as u64)?)
+        }
+        (source, ..) => {
+            return Err(StoreError::DataDeserializationError(
+                crypto::utils::DeserializationError::InvalidValue(format!(
+                    "Invalid note tag source: {source}"
+                )),
+            ))
+        }
+    };
+
+    Ok(NoteTagRecord {
+        tag: tag as u64,
+        source,
+        label,
+    })
+}
+
+fn parse_quarantined_note_columns(
+    row: &rusqlite::Row<'_>,
+) -> Result<SerializedQuarantinedNoteParts, rusqlite::Error> {
+    let note_id: String = row.get(0)?;
+    let block_num: u32 = row.get(1)?;
+    let note_index: u64 = row.get(2)?;
+    let note_path: String = row.get(3)?;
+    let reason: String = row.get(4)?;
+    Ok((note_id, block_num, note_index, note_path, reason))
+}
+
+fn parse_quarantined_note(
+    serialized_quarantined_note_parts: SerializedQuarantinedNoteParts,
+) -> Result<QuarantinedNote, StoreError> {
+    let (note_id, block_num, note_index, note_path, reason) = serialized_quarantined_note_parts;
+
+    let note_path: Vec<Digest> =
+        serde_json::from_str(&note_path).map_err(StoreError::JsonDataDeserializationError)?;
+
+    Ok(QuarantinedNote {
+        note_id: Digest::try_from(note_id)?.into(),
+        block_num,
+        note_index,
+        note_path: MerklePath::new(note_path),
+        reason,
+    })
+}
+
+fn serialize_quarantined_note(
+    quarantined_note: &QuarantinedNote,
+) -> Result<SerializedQuarantinedNoteData, StoreError> {
+    let note_id = quarantined_note.note_id.inner().to_string();
+    let note_path = serde_json::to_string(&quarantined_note.note_path.to_vec())
+        .map_err(StoreError::InputSerializationError)?;
+
+    Ok((
+        note_id,
+        quarantined_note.block_num,
+        quarantined_note.note_index as i64,
+        note_path,
+        quarantined_note.reason.clone(),
+    ))
 }
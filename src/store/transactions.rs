@@ -1,7 +1,11 @@
 use crate::{
     client::transactions::{TransactionRecord, TransactionResult, TransactionStatus},
     errors::StoreError,
-    store::notes::InputNoteRecord,
+    store::{
+        accounts::AccountCodeUpgrade,
+        notes::{unix_timestamp, InputNoteRecord, NoteOrigin, PartialNoteRecord},
+        orders::OrderRecord,
+    },
 };
 use crypto::{
     utils::{collections::BTreeMap, Deserializable, Serializable},
@@ -10,24 +14,34 @@ use crypto::{
 
 use tracing::info;
 
-use super::Store;
+use super::{
+    chain_data::{parse_block_time, BlockTime},
+    Page, Store,
+};
 use objects::{
-    accounts::AccountId,
+    accounts::{AccountCode, AccountId},
     assembly::{AstSerdeOptions, ProgramAst},
+    assets::FungibleAsset,
     notes::NoteId,
-    transaction::{OutputNote, OutputNotes, TransactionScript},
+    transaction::{OutputNote, OutputNotes, ProvenTransaction, TransactionScript},
     Digest,
 };
-use rusqlite::{params, Transaction};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
 
 pub(crate) const INSERT_TRANSACTION_QUERY: &str =
     "INSERT INTO transactions (id, account_id, init_account_state, final_account_state, \
-    input_notes, output_notes, script_hash, script_inputs, block_num, commit_height) \
-    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
+    input_notes, output_notes, script_id, block_num, commit_height, stage, transaction_result, \
+    fee_faucet_id, fee_amount) \
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+    ON CONFLICT (id) DO NOTHING";
+
+const EXECUTED_STAGE: &str = "executed";
+const PROVEN_STAGE: &str = "proven";
+const SUBMITTED_STAGE: &str = "submitted";
 
 pub(crate) const INSERT_TRANSACTION_SCRIPT_QUERY: &str =
-    "INSERT OR IGNORE INTO transaction_scripts (script_hash, program) \
-    VALUES (?, ?)";
+    "INSERT OR IGNORE INTO transaction_scripts (script_hash, program, inputs) \
+    VALUES (?, ?, ?)";
 
 // TRANSACTIONS FILTERS
 // ================================================================================================
@@ -35,17 +49,26 @@ pub(crate) const INSERT_TRANSACTION_SCRIPT_QUERY: &str =
 pub enum TransactionFilter {
     All,
     Uncomitted,
+    /// Transactions executed against the specified account, in chronological order
+    Account(AccountId),
 }
 
 impl TransactionFilter {
     /// Returns a [String] containing the query for this Filter
     pub fn to_query(&self) -> String {
         const QUERY: &str = "SELECT tx.id, tx.account_id, tx.init_account_state, tx.final_account_state, \
-            tx.input_notes, tx.output_notes, tx.script_hash, script.program, tx.script_inputs, tx.block_num, tx.commit_height \
-            FROM transactions AS tx LEFT JOIN transaction_scripts AS script ON tx.script_hash = script.script_hash";
+            tx.input_notes, tx.output_notes, script.script_hash, script.program, script.inputs, tx.block_num, tx.commit_height, tx.stage, \
+            tx.fee_faucet_id, tx.fee_amount \
+            FROM transactions AS tx LEFT JOIN transaction_scripts AS script ON tx.script_id = script.id";
         match self {
-            TransactionFilter::All => QUERY.to_string(),
-            TransactionFilter::Uncomitted => format!("{QUERY} WHERE tx.commit_height IS NULL"),
+            TransactionFilter::All => format!("{QUERY} ORDER BY tx.rowid"),
+            TransactionFilter::Uncomitted => {
+                format!("{QUERY} WHERE tx.commit_height IS NULL ORDER BY tx.rowid")
+            }
+            TransactionFilter::Account(account_id) => format!(
+                "{QUERY} WHERE tx.account_id = {} ORDER BY tx.block_num, tx.rowid",
+                u64::from(*account_id) as i64
+            ),
         }
     }
 }
@@ -65,6 +88,9 @@ type SerializedTransactionData = (
     Option<String>,
     u32,
     Option<u32>,
+    String,
+    Option<i64>,
+    Option<i64>,
 );
 
 impl Store {
@@ -73,27 +99,416 @@ impl Store {
         &self,
         transaction_filter: TransactionFilter,
     ) -> Result<Vec<TransactionRecord>, StoreError> {
-        self.db
-            .prepare(&transaction_filter.to_query())?
+        Self::get_transactions_tx(&self.db, transaction_filter)
+    }
+
+    /// Version of [Store::get_transactions] reading from a database transaction, for updates
+    /// that depend on the transactions read
+    pub(super) fn get_transactions_tx(
+        conn: &Connection,
+        transaction_filter: TransactionFilter,
+    ) -> Result<Vec<TransactionRecord>, StoreError> {
+        Self::query_transactions(conn, &transaction_filter.to_query())
+    }
+
+    /// Retrieves the specified page of the executed transactions from the database
+    pub fn get_transactions_page(
+        &self,
+        transaction_filter: TransactionFilter,
+        page: Page,
+    ) -> Result<Vec<TransactionRecord>, StoreError> {
+        Self::query_transactions(&self.db, &page.apply(&transaction_filter.to_query()))
+    }
+
+    fn query_transactions(
+        conn: &Connection,
+        query: &str,
+    ) -> Result<Vec<TransactionRecord>, StoreError> {
+        conn.prepare(query)?
             .query_map([], parse_transaction_columns)
             .expect("no binding parameters used in query")
             .map(|result| Ok(result?).and_then(parse_transaction))
             .collect::<Result<Vec<TransactionRecord>, _>>()
     }
 
-    /// Inserts a transaction and updates the current state based on the `tx_result` changes
-    pub fn insert_transaction_data(
+    /// Returns the [TransactionStatus] of the transaction with the specified ID
+    pub fn get_transaction_status(
+        &self,
+        transaction_id: Digest,
+    ) -> Result<TransactionStatus, StoreError> {
+        let transaction_id_str: String = transaction_id.into();
+        const QUERY: &str = "SELECT stage, commit_height FROM transactions WHERE id = ?";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map(params![transaction_id_str], |row| {
+                let stage: String = row.get(0)?;
+                let commit_height: Option<u32> = row.get(1)?;
+                Ok((stage, commit_height))
+            })?
+            .map(|result| {
+                let (stage, commit_height) = result?;
+                parse_transaction_status(&stage, commit_height)
+            })
+            .next()
+            .ok_or(StoreError::TransactionNotFound(transaction_id))?
+    }
+
+    /// Returns the blocks the committed transactions were committed in, along with their
+    /// timestamps when the client tracks their headers
+    pub fn get_transaction_commit_times(&self) -> Result<BTreeMap<Digest, BlockTime>, StoreError> {
+        const QUERY: &str = "SELECT tx.id, tx.commit_height, header.header \
+            FROM transactions AS tx \
+            LEFT JOIN block_headers AS header ON header.block_num = tx.commit_height \
+            WHERE tx.commit_height IS NOT NULL";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map([], |row| {
+                let transaction_id: String = row.get(0)?;
+                let commit_height: u32 = row.get(1)?;
+                let header: Option<String> = row.get(2)?;
+                Ok((transaction_id, commit_height, header))
+            })?
+            .map(|result| {
+                let (transaction_id, commit_height, header) = result?;
+                let commit_time = parse_block_time(Some(commit_height), header)?
+                    .expect("a block time is parsed for every block number");
+                Ok((Digest::try_from(transaction_id)?, commit_time))
+            })
+            .collect()
+    }
+
+    /// Returns the number of the block the specified transaction was executed against
+    pub fn get_transaction_block_num(&self, transaction_id: Digest) -> Result<u32, StoreError> {
+        let transaction_id_str: String = transaction_id.into();
+        const QUERY: &str = "SELECT block_num FROM transactions WHERE id = ?";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map(params![transaction_id_str], |row| row.get(0))?
+            .next()
+            .ok_or(StoreError::TransactionNotFound(transaction_id))?
+            .map_err(StoreError::from)
+    }
+
+    /// Returns the ID of the account that executed the specified transaction
+    pub fn get_transaction_account_id(
+        &self,
+        transaction_id: Digest,
+    ) -> Result<AccountId, StoreError> {
+        let transaction_id_str: String = transaction_id.into();
+        const QUERY: &str = "SELECT account_id FROM transactions WHERE id = ?";
+
+        let account_id: i64 = self
+            .db
+            .prepare(QUERY)?
+            .query_map(params![transaction_id_str], |row| row.get(0))?
+            .next()
+            .ok_or(StoreError::TransactionNotFound(transaction_id))??;
+
+        Ok(AccountId::try_from(account_id as u64)?)
+    }
+
+    /// Returns the [TransactionResult] of an executed transaction that has not been submitted yet
+    pub fn get_executed_transaction(
+        &self,
+        transaction_id: Digest,
+    ) -> Result<TransactionResult, StoreError> {
+        let transaction_id_str: String = transaction_id.into();
+        const QUERY: &str =
+            "SELECT transaction_result, fee_faucet_id, fee_amount FROM transactions \
+            WHERE id = ? AND transaction_result IS NOT NULL";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map(params![transaction_id_str], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .map(|result| {
+                let (transaction_result, fee_faucet_id, fee_amount): (
+                    Vec<u8>,
+                    Option<i64>,
+                    Option<i64>,
+                ) = result?;
+                let fee = parse_fee(fee_faucet_id, fee_amount)?;
+                Ok(TransactionResult::read_from_bytes(&transaction_result)?.with_fee(fee))
+            })
+            .next()
+            .ok_or(StoreError::TransactionNotFound(transaction_id))?
+    }
+
+    /// Returns the [ProvenTransaction] of a transaction proven by the client, which is kept after
+    /// the transaction is submitted
+    pub fn get_proven_transaction(
+        &self,
+        transaction_id: Digest,
+    ) -> Result<ProvenTransaction, StoreError> {
+        let transaction_id_str: String = transaction_id.into();
+        const QUERY: &str =
+            "SELECT proven_transaction FROM transactions WHERE id = ? AND proven_transaction IS NOT NULL";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map(params![transaction_id_str], |row| row.get(0))?
+            .map(|result| {
+                let proven_transaction: Vec<u8> = result?;
+                Ok(ProvenTransaction::read_from_bytes(&proven_transaction)?)
+            })
+            .next()
+            .ok_or(StoreError::TransactionProofNotFound(transaction_id))?
+    }
+
+    /// Records an executed transaction, which is the first stage of its lifecycle. The
+    /// [TransactionResult] is kept until the transaction is submitted so that it can be proven
+    /// later on.
+    ///
+    /// Recording a transaction that is already tracked does nothing.
+    pub fn insert_executed_transaction(
+        &mut self,
+        tx_result: &TransactionResult,
+    ) -> Result<(), StoreError> {
+        let tx = self.write_transaction()?;
+        Self::insert_executed_transaction_data(&tx, tx_result)?;
+
+        Ok(tx.commit()?)
+    }
+
+    /// Records a transaction that was already submitted, such as one restored from a wallet
+    /// archive, along with whether it was committed. The artifacts needed to prove and submit a
+    /// transaction are not part of a [TransactionRecord], so transactions in earlier stages of
+    /// their lifecycle cannot be recorded this way.
+    ///
+    /// Recording a transaction that is already tracked does nothing.
+    pub fn insert_transaction_record(
         &mut self,
-        tx_result: TransactionResult,
+        record: &TransactionRecord,
+    ) -> Result<(), StoreError> {
+        let commit_height = match record.transaction_status {
+            TransactionStatus::Submitted => None,
+            TransactionStatus::Committed(height) => Some(height),
+            TransactionStatus::Executed | TransactionStatus::Proven => {
+                return Err(StoreError::QueryError(format!(
+                    "transaction {} was not submitted and cannot be recorded without its artifacts",
+                    record.id
+                )))
+            }
+        };
+
+        let transaction_id: String = record.id.into();
+
+        let input_notes = serde_json::to_string(&record.input_note_nullifiers)
+            .map_err(StoreError::InputSerializationError)?;
+        let (fee_faucet_id, fee_amount) = serialize_fee(record.fee);
+
+        let tx = self.write_transaction()?;
+        let script_id = record
+            .transaction_script
+            .as_ref()
+            .map(|tx_script| {
+                let (program, hash, inputs) = serialize_transaction_script(tx_script)?;
+                Self::insert_transaction_script(&tx, hash, program, inputs)
+            })
+            .transpose()?;
+
+        tx.execute(
+            INSERT_TRANSACTION_QUERY,
+            params![
+                transaction_id,
+                u64::from(record.account_id) as i64,
+                record.init_account_state.to_string(),
+                record.final_account_state.to_string(),
+                input_notes,
+                record.output_notes.to_bytes(),
+                script_id,
+                record.block_num,
+                commit_height,
+                SUBMITTED_STAGE,
+                None::<Vec<u8>>,
+                fee_faucet_id,
+                fee_amount,
+            ],
+        )?;
+
+        Ok(tx.commit()?)
+    }
+
+    /// Starts tracking an executed transaction that upgrades the code of its account to
+    /// `account_code`. The code the account had before is kept, and the account starts referring
+    /// to the new code once the changes of the transaction are applied.
+    pub fn insert_executed_code_upgrade(
+        &mut self,
+        tx_result: &TransactionResult,
+        account_code: &AccountCode,
     ) -> Result<(), StoreError> {
         let account_id = tx_result.executed_transaction().account_id();
-        let account_delta = tx_result.account_delta();
+        let (account_stub, _) = self.get_account_stub_by_id(account_id)?;
+        let upgrade = AccountCodeUpgrade {
+            account_id,
+            transaction_id: tx_result.executed_transaction().id().inner(),
+            previous_code_root: account_stub.code_root(),
+            code_root: account_code.root(),
+        };
 
-        let (mut account, seed) = self.get_account_by_id(account_id)?;
+        let tx = self.write_transaction()?;
+        Self::insert_executed_transaction_data(&tx, tx_result)?;
+        Self::insert_account_code_upgrade(&tx, &upgrade, account_code)?;
+
+        Ok(tx.commit()?)
+    }
+
+    /// Moves an executed transaction to the proven stage, storing its [ProvenTransaction]
+    pub fn mark_transaction_proven(
+        &mut self,
+        transaction_id: Digest,
+        proven_transaction: &ProvenTransaction,
+    ) -> Result<(), StoreError> {
+        let transaction_id_str: String = transaction_id.into();
+        const QUERY: &str = "UPDATE transactions SET stage = 'proven', proven_transaction = ? WHERE id = ? AND stage = 'executed'";
+
+        let rows = self.db.execute(
+            QUERY,
+            params![proven_transaction.to_bytes(), transaction_id_str],
+        )?;
+        if rows == 0 {
+            return Err(StoreError::TransactionNotFound(transaction_id));
+        }
+
+        Ok(())
+    }
+
+    /// Applies the account changes of an executed transaction to the local state ahead of its
+    /// submission, so that transactions depending on them can be executed right away. The changes
+    /// are not applied again once the transaction is submitted.
+    pub fn apply_executed_transaction_delta(
+        &mut self,
+        transaction_id: Digest,
+    ) -> Result<(), StoreError> {
+        let tx_result = self.get_executed_transaction(transaction_id)?;
+
+        let account_id = tx_result.executed_transaction().account_id();
+        let tx = self.write_transaction()?;
+        let account_update = Self::get_account_state_update(
+            &tx,
+            account_id,
+            tx_result.account_delta(),
+            Some(transaction_id),
+        )?;
+        Self::insert_account_state_update(&tx, &account_update, Some(transaction_id))?;
+
+        Ok(tx.commit()?)
+    }
 
-        account
-            .apply_delta(account_delta)
-            .map_err(StoreError::AccountError)?;
+    /// Stops tracking a transaction that was not committed, reverting the account changes applied
+    /// for it and releasing the notes it consumes. The notes created by a submitted transaction
+    /// stop being tracked as well.
+    ///
+    /// # Errors
+    /// Returns [StoreError::TransactionNotDiscardable] if the transaction was committed, or if
+    /// the account was changed after the transaction, as the later changes build on its own.
+    pub fn discard_transaction(&mut self, transaction_id: Digest) -> Result<(), StoreError> {
+        self.check_transaction_discardable(transaction_id)?;
+
+        let tx = self.write_transaction()?;
+        Self::discard_transaction_tx(&tx, transaction_id)?;
+
+        Ok(tx.commit()?)
+    }
+
+    /// Replaces a transaction that was not committed with `tx_result`, the result of executing
+    /// it again. The original transaction is discarded as in [Store::discard_transaction] and the
+    /// new one recorded in the same database transaction, so that the original one is kept if
+    /// either step fails.
+    pub fn replace_transaction(
+        &mut self,
+        transaction_id: Digest,
+        tx_result: &TransactionResult,
+    ) -> Result<(), StoreError> {
+        self.check_transaction_discardable(transaction_id)?;
+
+        let tx = self.write_transaction()?;
+        Self::discard_transaction_tx(&tx, transaction_id)?;
+        Self::insert_executed_transaction_data(&tx, tx_result)?;
+
+        Ok(tx.commit()?)
+    }
+
+    /// Returns an error if the transaction was committed or if the account was changed after it
+    fn check_transaction_discardable(&self, transaction_id: Digest) -> Result<(), StoreError> {
+        let transaction_id_str: String = transaction_id.into();
+        const LATER_DELTA_QUERY: &str = "SELECT later.transaction_id FROM account_deltas AS delta \
+            JOIN account_deltas AS later ON later.account_id = delta.account_id AND later.id > delta.id \
+            WHERE delta.transaction_id = ? ORDER BY later.id LIMIT 1";
+
+        if let TransactionStatus::Committed(block_num) =
+            self.get_transaction_status(transaction_id)?
+        {
+            return Err(StoreError::TransactionNotDiscardable(
+                transaction_id,
+                format!("it was committed in block {block_num}"),
+            ));
+        }
+        let later_delta: Option<Option<String>> = self
+            .db
+            .query_row(LATER_DELTA_QUERY, params![transaction_id_str], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        if let Some(later_transaction_id) = later_delta {
+            let reason = match later_transaction_id {
+                Some(later_transaction_id) => format!(
+                    "transaction {later_transaction_id} changed the account after it and has to be discarded first"
+                ),
+                None => "the account state was updated after it".to_string(),
+            };
+            return Err(StoreError::TransactionNotDiscardable(
+                transaction_id,
+                reason,
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn discard_transaction_tx(
+        tx: &Transaction<'_>,
+        transaction_id: Digest,
+    ) -> Result<(), StoreError> {
+        let transaction_id_str: String = transaction_id.into();
+        const TRANSACTION_QUERY: &str =
+            "DELETE FROM transactions WHERE id = ? AND commit_height IS NULL";
+        const ACCOUNT_QUERY: &str = "DELETE FROM accounts WHERE (id, nonce) IN \
+            (SELECT account_id, nonce FROM account_deltas WHERE transaction_id = ?)";
+        const DELTA_QUERY: &str = "DELETE FROM account_deltas WHERE transaction_id = ?";
+        const CODE_UPGRADE_QUERY: &str =
+            "DELETE FROM account_code_upgrades WHERE transaction_id = ?";
+        const OUTBOX_QUERY: &str = "DELETE FROM transaction_outbox WHERE transaction_id = ?";
+
+        tx.execute(OUTBOX_QUERY, params![transaction_id_str])?;
+        if tx.execute(TRANSACTION_QUERY, params![transaction_id_str])? == 0 {
+            return Err(StoreError::TransactionNotFound(transaction_id));
+        }
+        tx.execute(ACCOUNT_QUERY, params![transaction_id_str])?;
+        tx.execute(DELTA_QUERY, params![transaction_id_str])?;
+        tx.execute(CODE_UPGRADE_QUERY, params![transaction_id_str])?;
+        Self::unlock_notes_tx(tx, transaction_id)?;
+        Self::remove_transaction_notes_tx(tx, transaction_id)?;
+
+        Ok(())
+    }
+
+    /// Moves a proven transaction to the submitted stage and updates the current state based on
+    /// its changes: the account delta is applied, unless it already was ahead of the submission,
+    /// and the created notes start being tracked, along with the `orders` among them
+    pub fn mark_transaction_submitted(
+        &mut self,
+        transaction_id: Digest,
+        orders: &[OrderRecord],
+    ) -> Result<(), StoreError> {
+        let tx_result = self.get_executed_transaction(transaction_id)?;
+
+        let account_id = tx_result.executed_transaction().account_id();
+        let account_delta = tx_result.account_delta();
 
         let created_notes = tx_result
             .created_notes()
@@ -101,29 +516,85 @@ impl Store {
             .map(|note| InputNoteRecord::from(note.clone()))
             .collect::<Vec<_>>();
 
-        let tx = self.db.transaction()?;
+        // Output notes whose details were not provided are only known by their recipient
+        let partial_notes = tx_result
+            .executed_transaction()
+            .output_notes()
+            .iter()
+            .filter(|note| {
+                !created_notes
+                    .iter()
+                    .any(|created| created.note_id() == note.id())
+            })
+            .map(|note| PartialNoteRecord {
+                note_id: note.id(),
+                recipient: *note.recipient(),
+                assets: note.assets().clone(),
+                metadata: *note.metadata(),
+                transaction_id,
+            })
+            .collect::<Vec<_>>();
+
+        let tx = self.write_transaction()?;
+
+        const APPLIED_QUERY: &str = "SELECT COUNT(*) FROM account_deltas WHERE transaction_id = ?";
+        let applied_deltas: usize = tx.query_row(
+            APPLIED_QUERY,
+            params![String::from(transaction_id)],
+            |row| row.get(0),
+        )?;
+        let account_update = if applied_deltas == 0 {
+            match Self::get_account_state_update(
+                &tx,
+                account_id,
+                account_delta,
+                Some(transaction_id),
+            ) {
+                Ok(account_update) => Some(account_update),
+                // the stored state already moved past the transaction, e.g. after a sync
+                Err(StoreError::StaleAccountDelta(..)) => None,
+                Err(err) => return Err(err),
+            }
+        } else {
+            None
+        };
 
         // Transaction Data
-        Self::insert_proven_transaction_data(&tx, tx_result)?;
+        let transaction_id_str: String = transaction_id.into();
+        const QUERY: &str = "UPDATE transactions SET stage = 'submitted', transaction_result = NULL WHERE id = ? AND stage = 'proven'";
+        if tx.execute(QUERY, params![transaction_id_str])? == 0 {
+            return Err(StoreError::TransactionNotFound(transaction_id));
+        }
+        const OUTBOX_QUERY: &str = "DELETE FROM transaction_outbox WHERE transaction_id = ?";
+        tx.execute(OUTBOX_QUERY, params![transaction_id_str])?;
 
         // Account Data
-        Self::insert_account_storage(&tx, account.storage())?;
-        Self::insert_account_asset_vault(&tx, account.vault())?;
-        Self::insert_account_record(&tx, &account, seed)?;
+        if let Some(account_update) = account_update {
+            Self::insert_account_state_update(&tx, &account_update, Some(transaction_id))?;
+        }
 
         // Updates for notes
-        for note in created_notes {
-            Self::insert_input_note_tx(&tx, &note)?;
+        Self::insert_input_notes_tx(
+            &tx,
+            &created_notes,
+            &NoteOrigin::Transaction(transaction_id),
+        )?;
+        let created_note_ids: Vec<NoteId> =
+            created_notes.iter().map(|note| note.note_id()).collect();
+        Self::mark_notes_expected_tx(&tx, &created_note_ids)?;
+        for note in partial_notes.iter() {
+            Self::insert_partial_output_note_tx(&tx, note)?;
         }
+        Self::insert_orders_tx(&tx, orders)?;
 
         tx.commit()?;
 
         Ok(())
     }
 
-    fn insert_proven_transaction_data(
+    fn insert_executed_transaction_data(
         tx: &Transaction<'_>,
-        transaction_result: TransactionResult,
+        transaction_result: &TransactionResult,
     ) -> Result<(), StoreError> {
         let (
             transaction_id,
@@ -137,16 +608,19 @@ impl Store {
             script_inputs,
             block_num,
             committed,
+            stage,
+            fee_faucet_id,
+            fee_amount,
         ) = serialize_transaction_data(transaction_result)?;
 
-        if let Some(hash) = script_hash.clone() {
-            tx.execute(
-                INSERT_TRANSACTION_SCRIPT_QUERY,
-                params![hash, script_program],
-            )?;
-        }
+        let script_id = match (script_hash, script_program, script_inputs) {
+            (Some(hash), Some(program), Some(inputs)) => {
+                Some(Self::insert_transaction_script(tx, hash, program, inputs)?)
+            }
+            _ => None,
+        };
 
-        tx.execute(
+        let inserted = tx.execute(
             INSERT_TRANSACTION_QUERY,
             params![
                 transaction_id,
@@ -155,16 +629,50 @@ impl Store {
                 final_account_state,
                 input_notes,
                 output_notes,
-                script_hash,
-                script_inputs,
+                script_id,
                 block_num,
                 committed,
+                stage,
+                transaction_result.to_bytes(),
+                fee_faucet_id,
+                fee_amount,
             ],
         )?;
+        // the transaction was already recorded, and may have moved on from the executed stage
+        if inserted == 0 {
+            return Ok(());
+        }
+
+        // keep other transactions from consuming the same notes while this one is in progress
+        let executed_transaction = transaction_result.executed_transaction();
+        let input_note_ids: Vec<NoteId> = executed_transaction
+            .input_notes()
+            .iter()
+            .map(|note| note.id())
+            .collect();
+        Self::lock_notes_tx(tx, &input_note_ids, executed_transaction.id().inner())?;
 
         Ok(())
     }
 
+    /// Stores a transaction script along with its inputs unless it is already stored, and
+    /// returns the identifier transactions reference it by
+    fn insert_transaction_script(
+        tx: &Transaction<'_>,
+        script_hash: Vec<u8>,
+        program: Vec<u8>,
+        inputs: String,
+    ) -> Result<i64, StoreError> {
+        const ID_QUERY: &str =
+            "SELECT id FROM transaction_scripts WHERE script_hash = ? AND inputs = ?";
+
+        tx.execute(
+            INSERT_TRANSACTION_SCRIPT_QUERY,
+            params![script_hash, program, inputs],
+        )?;
+        Ok(tx.query_row(ID_QUERY, params![script_hash, inputs], |row| row.get(0))?)
+    }
+
     /// Updates transactions as committed if the input `note_ids` belongs to one uncommitted transaction
     pub(crate) fn mark_transactions_as_committed_by_note_id(
         uncommitted_transactions: &[TransactionRecord],
@@ -179,17 +687,49 @@ impl Store {
 
         let mut rows = 0;
         for transaction in updated_transactions {
-            const QUERY: &str = "UPDATE transactions set commit_height=? where id=?";
+            const QUERY: &str =
+                "UPDATE transactions set commit_height=? where id=? AND stage='submitted'";
             rows += tx.execute(QUERY, params![Some(block_num), transaction.id.to_string()])?;
         }
         info!("Marked {} transactions as committed", rows);
 
         Ok(rows)
     }
+
+    /// Queues an unsubmitted transaction in the outbox, from which it is submitted once the node
+    /// can be reached again. Queuing a transaction that is already queued does nothing.
+    pub fn queue_transaction(&mut self, transaction_id: Digest) -> Result<(), StoreError> {
+        const QUERY: &str = "INSERT OR IGNORE INTO transaction_outbox (transaction_id, queued_at) \
+            SELECT id, ? FROM transactions WHERE id = ? AND stage IN ('executed', 'proven')";
+
+        let rows = self.db.execute(
+            QUERY,
+            params![unix_timestamp() as i64, String::from(transaction_id)],
+        )?;
+        if rows == 0 && !self.get_queued_transactions()?.contains(&transaction_id) {
+            return Err(StoreError::TransactionNotFound(transaction_id));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the IDs of the transactions in the outbox, in the order they were queued
+    pub fn get_queued_transactions(&self) -> Result<Vec<Digest>, StoreError> {
+        const QUERY: &str = "SELECT transaction_id FROM transaction_outbox ORDER BY rowid";
+
+        self.db
+            .prepare(QUERY)?
+            .query_map([], |row| row.get(0))?
+            .map(|result| {
+                let transaction_id: String = result?;
+                Ok(Digest::try_from(transaction_id)?)
+            })
+            .collect()
+    }
 }
 
 pub(super) fn serialize_transaction_data(
-    transaction_result: TransactionResult,
+    transaction_result: &TransactionResult,
 ) -> Result<SerializedTransactionData, StoreError> {
     let executed_transaction = transaction_result.executed_transaction();
     let transaction_id: String = executed_transaction.id().inner().into();
@@ -215,21 +755,15 @@ pub(super) fn serialize_transaction_data(
         executed_transaction.account_id()
     );
 
-    // TODO: Scripts should be in their own tables and only identifiers should be stored here
-    let mut script_program = None;
-    let mut script_hash = None;
-    let mut script_inputs = None;
-
-    if let Some(tx_script) = transaction_result.transaction_script() {
-        script_program = Some(tx_script.code().to_bytes(AstSerdeOptions {
-            serialize_imports: true,
-        }));
-        script_hash = Some(tx_script.hash().to_bytes());
-        script_inputs = Some(
-            serde_json::to_string(&tx_script.inputs())
-                .map_err(StoreError::InputSerializationError)?,
-        );
-    }
+    let (script_program, script_hash, script_inputs) = match transaction_result.transaction_script()
+    {
+        Some(tx_script) => {
+            let (program, hash, inputs) = serialize_transaction_script(tx_script)?;
+            (Some(program), Some(hash), Some(inputs))
+        }
+        None => (None, None, None),
+    };
+    let (fee_faucet_id, fee_amount) = serialize_fee(transaction_result.fee());
 
     Ok((
         transaction_id,
@@ -243,9 +777,52 @@ pub(super) fn serialize_transaction_data(
         script_inputs,
         transaction_result.block_num(),
         None,
+        EXECUTED_STAGE.to_string(),
+        fee_faucet_id,
+        fee_amount,
     ))
 }
 
+/// Serializes a fee as the ID of its faucet and its amount, both NULL if there is no fee
+fn serialize_fee(fee: Option<FungibleAsset>) -> (Option<i64>, Option<i64>) {
+    match fee {
+        Some(fee) => (
+            Some(u64::from(fee.faucet_id()) as i64),
+            Some(fee.amount() as i64),
+        ),
+        None => (None, None),
+    }
+}
+
+fn parse_fee(
+    fee_faucet_id: Option<i64>,
+    fee_amount: Option<i64>,
+) -> Result<Option<FungibleAsset>, StoreError> {
+    match (fee_faucet_id, fee_amount) {
+        (Some(faucet_id), Some(amount)) => {
+            let faucet_id = AccountId::try_from(faucet_id as u64)?;
+            FungibleAsset::new(faucet_id, amount as u64)
+                .map(Some)
+                .map_err(|err| StoreError::ParsingError(err.to_string()))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Serializes the program, hash and inputs of a transaction script, in this order
+fn serialize_transaction_script(
+    tx_script: &TransactionScript,
+) -> Result<(Vec<u8>, Vec<u8>, String), StoreError> {
+    let program = tx_script.code().to_bytes(AstSerdeOptions {
+        serialize_imports: true,
+    });
+    let hash = tx_script.hash().to_bytes();
+    let inputs =
+        serde_json::to_string(&tx_script.inputs()).map_err(StoreError::InputSerializationError)?;
+
+    Ok((program, hash, inputs))
+}
+
 fn parse_transaction_columns(
     row: &rusqlite::Row<'_>,
 ) -> Result<SerializedTransactionData, rusqlite::Error> {
@@ -260,6 +837,9 @@ fn parse_transaction_columns(
     let script_inputs: Option<String> = row.get(8)?;
     let block_num: u32 = row.get(9)?;
     let commit_height: Option<u32> = row.get(10)?;
+    let stage: String = row.get(11)?;
+    let fee_faucet_id: Option<i64> = row.get(12)?;
+    let fee_amount: Option<i64> = row.get(13)?;
 
     Ok((
         id,
@@ -273,6 +853,9 @@ fn parse_transaction_columns(
         script_inputs,
         block_num,
         commit_height,
+        stage,
+        fee_faucet_id,
+        fee_amount,
     ))
 }
 
@@ -292,6 +875,9 @@ fn parse_transaction(
         script_inputs,
         block_num,
         commit_height,
+        stage,
+        fee_faucet_id,
+        fee_amount,
     ) = serialized_transaction;
     let account_id = AccountId::try_from(account_id as u64)?;
     let id: Digest = id.try_into()?;
@@ -332,9 +918,7 @@ fn parse_transaction(
         None
     };
 
-    let transaction_status = commit_height.map_or(TransactionStatus::Pending, |height| {
-        TransactionStatus::Committed(height)
-    });
+    let transaction_status = parse_transaction_status(&stage, commit_height)?;
 
     Ok(TransactionRecord {
         id,
@@ -346,5 +930,22 @@ fn parse_transaction(
         transaction_script,
         block_num,
         transaction_status,
+        fee: parse_fee(fee_faucet_id, fee_amount)?,
     })
 }
+
+/// Parse the status of a transaction from its lifecycle stage and commit height.
+fn parse_transaction_status(
+    stage: &str,
+    commit_height: Option<u32>,
+) -> Result<TransactionStatus, StoreError> {
+    match (stage, commit_height) {
+        (_, Some(height)) => Ok(TransactionStatus::Committed(height)),
+        (EXECUTED_STAGE, None) => Ok(TransactionStatus::Executed),
+        (PROVEN_STAGE, None) => Ok(TransactionStatus::Proven),
+        (SUBMITTED_STAGE, None) => Ok(TransactionStatus::Submitted),
+        (stage, None) => Err(StoreError::ParsingError(format!(
+            "unknown transaction stage `{stage}`"
+        ))),
+    }
+}
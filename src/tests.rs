@@ -3,32 +3,64 @@
 use crate::{
     client::{
         accounts::{AccountStorageMode, AccountTemplate},
-        transactions::TransactionTemplate,
+        archive::{ArchiveImportSummary, WalletArchive},
+        builder::ClientBuilder,
+        events::ClientEvent,
+        notes::{decode_memo, encode_memo, NoteBuilder, MAX_MEMO_LEN},
+        prover::TransactionProverMode,
+        sync::account_note_tag,
+        transactions::{TransactionResult, TransactionStatus, TransactionTemplate},
+        Client,
     },
+    config::{ClientConfig, RetryPolicy, StoreConfig},
+    errors::{ClientError, StoreError},
     store::{
-        accounts::AuthInfo,
+        accounts::{AddressBookEntry, AuthInfo},
+        chain_data::ChainMmrNodeFilter,
+        data_store::mmr_merkle_path_len,
         mock_executor_data_store::MockDataStore,
-        notes::{InputNoteFilter, InputNoteRecord},
-        tests::create_test_client,
+        notes::{InputNoteFilter, InputNoteRecord, NoteFilter},
+        orders::{OrderKind, OrderRecord, OrderStatus},
+        sync::{NoteTagRecord, NoteTagSource, QuarantinedNote},
+        tests::{create_test_client, create_test_store_path, insert_submitted_transaction},
+        transactions::TransactionFilter,
+        Page, Store,
     },
 };
 
-use assembly::ast::{AstSerdeOptions, ModuleAst};
-use crypto::{dsa::rpo_falcon512::KeyPair, Felt, FieldElement, Word};
+use assembly::ast::{AstSerdeOptions, ModuleAst, ProgramAst};
+use crypto::{
+    dsa::rpo_falcon512::KeyPair,
+    merkle::{InOrderIndex, MerklePath},
+    utils::{Deserializable, Serializable},
+    Felt, FieldElement, Word,
+};
 use miden_lib::transaction::TransactionKernel;
+use miden_node_proto::requests::SyncStateRequest;
 use mock::{
-    constants::{generate_account_seed, AccountSeedType},
+    constants::{
+        generate_account_seed, AccountSeedType, ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN,
+        ACCOUNT_ID_SENDER,
+    },
     mock::{
         account::{self, mock_account, MockAccountType},
+        block::mock_block_header,
         notes::AssetPreservationStatus,
-        transaction::mock_inputs,
+        transaction::{mock_executed_tx, mock_inputs},
     },
 };
 use objects::{
-    accounts::{AccountId, AccountStub},
-    assets::{FungibleAsset, TokenSymbol},
+    accounts::{
+        AccountDelta, AccountId, AccountStorageDelta, AccountStub, AccountType, AccountVaultDelta,
+    },
+    assets::{Asset, FungibleAsset, TokenSymbol},
+    notes::NoteId,
     transaction::InputNotes,
+    utils::collections::BTreeSet,
+    Digest,
 };
+use rand::{rngs::StdRng, SeedableRng};
+use std::time::Duration;
 
 #[tokio::test]
 async fn test_input_notes_round_trip() {
@@ -61,6 +93,60 @@ async fn test_input_notes_round_trip() {
     }
 }
 
+#[tokio::test]
+async fn test_wallet_archive_round_trip() {
+    let mut client = create_test_client();
+    let (account, account_seed) = client
+        .new_account(AccountTemplate::BasicWallet {
+            mutable_code: false,
+            storage_mode: AccountStorageMode::Local,
+        })
+        .unwrap();
+    let transaction_inputs = mock_inputs(
+        MockAccountType::StandardExisting,
+        AssetPreservationStatus::Preserved,
+    );
+    for note in transaction_inputs.input_notes().iter().cloned() {
+        client.import_input_note(note.into()).unwrap();
+    }
+
+    let mut config = ClientConfig::default();
+    config.store.passphrase = Some("passphrase".to_string());
+    let archive = client.export_archive(true).unwrap().with_config(config);
+    let archive = WalletArchive::read_from_bytes(&archive.to_bytes()).unwrap();
+    assert_eq!(archive.config.unwrap().store.passphrase, None);
+
+    // the archive restores the accounts and notes into an empty store
+    let mut restored_client = create_test_client();
+    let archive = client.export_archive(true).unwrap();
+    let summary = restored_client.import_archive(archive).unwrap();
+    assert_eq!(summary.imported_accounts, 1);
+    assert_eq!(
+        summary.imported_notes,
+        transaction_inputs.input_notes().num_notes()
+    );
+    let (restored_account, restored_seed) =
+        restored_client.get_account_by_id(account.id()).unwrap();
+    assert_eq!(restored_account.hash(), account.hash());
+    assert_eq!(restored_seed, account_seed);
+    assert!(restored_client
+        .get_note_tags()
+        .unwrap()
+        .contains(&account_note_tag(account.id())));
+
+    // importing it again does not duplicate anything
+    let archive = client.export_archive(true).unwrap();
+    let summary = restored_client.import_archive(archive).unwrap();
+    assert_eq!(summary, ArchiveImportSummary::default());
+
+    // accounts cannot be restored from an archive without their keys
+    let archive = client.export_archive(false).unwrap();
+    let archive = WalletArchive::read_from_bytes(&archive.to_bytes()).unwrap();
+    let summary = create_test_client().import_archive(archive).unwrap();
+    assert_eq!(summary.imported_accounts, 0);
+    assert_eq!(summary.accounts_without_keys, vec![account.id()]);
+}
+
 #[tokio::test]
 async fn test_get_input_note() {
     // generate test client with a random store name
@@ -88,6 +174,213 @@ async fn test_get_input_note() {
     assert_eq!(recorded_note.note_id(), retrieved_note.note_id())
 }
 
+#[tokio::test]
+async fn test_filter_input_notes() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // generate test data
+    let transaction_inputs = mock_inputs(
+        MockAccountType::StandardExisting,
+        AssetPreservationStatus::Preserved,
+    );
+    let recorded_notes: Vec<InputNoteRecord> = transaction_inputs
+        .input_notes()
+        .iter()
+        .map(|n| n.clone().into())
+        .collect();
+
+    for note in recorded_notes.iter().cloned() {
+        client.import_input_note(note).unwrap();
+    }
+
+    let note = recorded_notes[0].note();
+    let sender = note.metadata().sender();
+    let faucet_id = note.assets().iter().next().unwrap().faucet_id();
+
+    let expected = recorded_notes
+        .iter()
+        .filter(|record| record.note().metadata().sender() == sender)
+        .filter(|record| {
+            record
+                .note()
+                .assets()
+                .iter()
+                .any(|asset| asset.faucet_id() == faucet_id)
+        })
+        .count();
+    let retrieved_notes = client
+        .get_input_notes(
+            NoteFilter::new()
+                .status(InputNoteFilter::Committed)
+                .sender(sender)
+                .faucet(faucet_id),
+        )
+        .unwrap();
+    assert_eq!(retrieved_notes.len(), expected);
+    assert!(retrieved_notes
+        .iter()
+        .any(|record| record.note_id() == note.id()));
+
+    // filters that match no note
+    let block_num = recorded_notes[0]
+        .inclusion_proof()
+        .unwrap()
+        .origin()
+        .block_num;
+    assert!(client
+        .get_input_notes(NoteFilter::new().block_range(block_num + 1, u32::MAX))
+        .unwrap()
+        .iter()
+        .all(|record| record.note_id() != note.id()));
+    assert!(client
+        .get_input_notes(NoteFilter::new().status(InputNoteFilter::Pending))
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_prune_irrelevant_mmr_nodes() {
+    let mut client = create_test_client();
+
+    let transaction_inputs = mock_inputs(
+        MockAccountType::StandardExisting,
+        AssetPreservationStatus::Preserved,
+    );
+    let note: InputNoteRecord = transaction_inputs.input_notes().get_note(0).clone().into();
+    let note_block = note.inclusion_proof().unwrap().origin().block_num;
+    client.import_input_note(note).unwrap();
+
+    // store every node of the chain MMR at a later sync height
+    let forest = note_block + 8;
+    client
+        .store
+        .db
+        .execute("UPDATE state_sync SET block_num = ?", [forest])
+        .unwrap();
+    let node = serde_json::to_string(&Digest::default()).unwrap();
+    for id in 1..2 * forest as i64 {
+        client
+            .store
+            .db
+            .execute(
+                "INSERT INTO chain_mmr_nodes (id, node) VALUES (?, ?)",
+                rusqlite::params![id, node],
+            )
+            .unwrap();
+    }
+
+    // only the note block's leaf and its authentication path are kept
+    let path_len = mmr_merkle_path_len(note_block as usize, forest as usize);
+    let removed = client.store.prune_irrelevant_mmr_nodes().unwrap();
+    assert_eq!(removed, 2 * forest as usize - 1 - (path_len + 1));
+
+    let remaining = client
+        .store
+        .get_chain_mmr_nodes(ChainMmrNodeFilter::All)
+        .unwrap();
+    let leaf_index = InOrderIndex::from_leaf_pos(note_block as usize);
+    assert!(remaining.contains_key(&leaf_index));
+    assert!(remaining.contains_key(&leaf_index.sibling()));
+    assert_eq!(remaining.len(), path_len + 1);
+
+    // pruning again removes nothing
+    assert_eq!(client.store.prune_irrelevant_mmr_nodes().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_input_note_id_prefix() {
+    let mut client = create_test_client();
+
+    let transaction_inputs = mock_inputs(
+        MockAccountType::StandardExisting,
+        AssetPreservationStatus::Preserved,
+    );
+    let recorded_notes: Vec<InputNoteRecord> = transaction_inputs
+        .input_notes()
+        .iter()
+        .map(|n| n.clone().into())
+        .collect();
+    assert!(recorded_notes.len() > 1);
+    for note in recorded_notes.iter().cloned() {
+        client.import_input_note(note).unwrap();
+    }
+
+    let note_id = recorded_notes[0].note_id();
+    let full_id = note_id.inner().to_string();
+
+    // full IDs and unique prefixes, with or without `0x`, resolve to the note
+    assert_eq!(
+        client.get_input_note_id_by_prefix(&full_id).unwrap(),
+        note_id
+    );
+    assert_eq!(
+        client.get_input_note_id_by_prefix(&full_id[..20]).unwrap(),
+        note_id
+    );
+    assert_eq!(
+        client
+            .get_input_note_id_by_prefix(&full_id[2..20].to_uppercase())
+            .unwrap(),
+        note_id
+    );
+
+    // prefixes too short to identify a note are rejected rather than matching every note
+    for prefix in ["", "0x", "0xabc", "abc"] {
+        assert!(matches!(
+            client.get_input_note_id_by_prefix(prefix),
+            Err(ClientError::StoreError(StoreError::NoteIdPrefixTooShort(
+                _,
+                4
+            )))
+        ));
+    }
+    assert!(matches!(
+        client.get_input_note_id_by_prefix("0xzz"),
+        Err(ClientError::StoreError(StoreError::NoteIdPrefixNotFound(_)))
+    ));
+}
+
+#[tokio::test]
+async fn test_input_notes_pagination() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // generate test data
+    let transaction_inputs = mock_inputs(
+        MockAccountType::StandardExisting,
+        AssetPreservationStatus::Preserved,
+    );
+    for note in transaction_inputs.input_notes().iter().cloned() {
+        client.import_input_note(note.into()).unwrap();
+    }
+
+    let all_notes = client.get_input_notes(InputNoteFilter::All).unwrap();
+    assert!(all_notes.len() > 1);
+
+    // walking through the pages yields every note once, in the same order
+    let mut paged_notes = Vec::new();
+    for number in 1.. {
+        let page = client
+            .get_input_notes_page(InputNoteFilter::All, Page::nth(1, number))
+            .unwrap();
+        if page.is_empty() {
+            break;
+        }
+        assert_eq!(page.len(), 1);
+        paged_notes.extend(page);
+    }
+    assert_eq!(paged_notes, all_notes);
+
+    let last_page = client
+        .get_input_notes_page(
+            InputNoteFilter::All,
+            Page::new(10, all_notes.len() as u64 - 1),
+        )
+        .unwrap();
+    assert_eq!(last_page, all_notes[all_notes.len() - 1..]);
+}
+
 #[tokio::test]
 async fn insert_basic_account() {
     // generate test client with a random store name
@@ -154,6 +447,236 @@ async fn insert_faucet_account() {
     assert_eq!(account_seed, fetched_account_seed);
 }
 
+#[tokio::test]
+async fn insert_custom_account() {
+    const WALLET_CODE: &str = "
+        use.miden::contracts::wallets::basic->basic_wallet
+        use.miden::contracts::auth::basic
+
+        export.basic_wallet::receive_asset
+        export.basic_wallet::send_asset
+        export.basic::auth_tx_rpo_falcon512
+    ";
+    let mut client = create_test_client();
+    let template = |storage_slots| AccountTemplate::Custom {
+        account_type: AccountType::RegularAccountImmutableCode,
+        code: WALLET_CODE.to_string(),
+        storage_slots,
+        storage_mode: AccountStorageMode::Local,
+    };
+
+    // on-chain accounts cannot be created yet
+    assert!(matches!(
+        client.new_account(AccountTemplate::Custom {
+            account_type: AccountType::RegularAccountImmutableCode,
+            code: WALLET_CODE.to_string(),
+            storage_slots: Vec::new(),
+            storage_mode: AccountStorageMode::OnChain,
+        }),
+        Err(ClientError::UnsupportedFeature(_))
+    ));
+
+    let value: Word = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+    let (account, _) = client.new_account(template(vec![(1, value)])).unwrap();
+
+    let (fetched_account, _) = client.get_account_by_id(account.id()).unwrap();
+    assert_eq!(
+        fetched_account.id().account_type(),
+        AccountType::RegularAccountImmutableCode
+    );
+    assert_eq!(fetched_account.code().root(), account.code().root());
+    assert_eq!(fetched_account.storage().get_item(1), value.into());
+
+    // slot 0 holds the account's public key
+    assert!(matches!(
+        client.new_account(template(vec![(0, value)])),
+        Err(ClientError::InvalidAccountTemplate(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_upgrade_account_code_requires_updatable_code() {
+    const NEW_CODE: &str = "
+        use.miden::contracts::wallets::basic->basic_wallet
+        use.miden::contracts::auth::basic
+
+        export.basic_wallet::receive_asset
+        export.basic::auth_tx_rpo_falcon512
+    ";
+    let mut client = create_test_client();
+
+    let (immutable_account, _) = client
+        .new_account(AccountTemplate::BasicWallet {
+            mutable_code: false,
+            storage_mode: AccountStorageMode::Local,
+        })
+        .unwrap();
+    assert!(matches!(
+        client
+            .upgrade_account_code(immutable_account.id(), NEW_CODE)
+            .await,
+        Err(ClientError::InvalidAccountCodeUpgrade(_))
+    ));
+
+    // the basic wallet does not export a procedure to change its code
+    let (mutable_account, _) = client
+        .new_account(AccountTemplate::BasicWallet {
+            mutable_code: true,
+            storage_mode: AccountStorageMode::Local,
+        })
+        .unwrap();
+    assert!(matches!(
+        client
+            .upgrade_account_code(mutable_account.id(), NEW_CODE)
+            .await,
+        Err(ClientError::InvalidAccountCodeUpgrade(_))
+    ));
+    assert!(client
+        .get_account_code_upgrades(mutable_account.id())
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_upgrade_account_code_moves_the_account_to_the_new_code() {
+    use objects::accounts::AccountCode;
+
+    const NEW_CODE: &str = "
+        use.miden::contracts::wallets::basic->basic_wallet
+        use.miden::contracts::auth::basic
+
+        export.basic_wallet::receive_asset
+        export.basic::auth_tx_rpo_falcon512
+    ";
+    let mut client = create_test_client();
+
+    // the mock executor cannot execute transactions against client accounts (see
+    // `test_mint_transaction`), so the upgrade is recorded with a mocked executed transaction
+    let executed_transaction = mock_executed_tx(AssetPreservationStatus::Preserved);
+    let initial_account = executed_transaction.initial_account().clone();
+    let account_id = initial_account.id();
+    client
+        .store
+        .insert_account(
+            &initial_account,
+            Word::default(),
+            &AuthInfo::RpoFalcon512(KeyPair::new().unwrap()),
+        )
+        .unwrap();
+
+    let new_code = AccountCode::new(
+        ModuleAst::parse(NEW_CODE).unwrap(),
+        &TransactionKernel::assembler(),
+    )
+    .unwrap();
+    let transaction_id = executed_transaction.id().inner();
+    let transaction = TransactionResult::new(executed_transaction, Vec::new());
+    client
+        .store
+        .insert_executed_code_upgrade(&transaction, &new_code)
+        .unwrap();
+
+    let upgrades = client.get_account_code_upgrades(account_id).unwrap();
+    assert_eq!(upgrades.len(), 1);
+    assert_eq!(upgrades[0].transaction_id, transaction_id);
+    assert_eq!(
+        upgrades[0].previous_code_root,
+        initial_account.code().root()
+    );
+    assert_eq!(upgrades[0].code_root, new_code.root());
+
+    // the account refers to the new code once the changes of the transaction are applied
+    client
+        .store
+        .apply_executed_transaction_delta(transaction_id)
+        .unwrap();
+    let (account, _) = client.get_account_by_id(account_id).unwrap();
+    assert_eq!(account.code().root(), new_code.root());
+}
+
+#[tokio::test]
+async fn test_client_builder_with_provided_stores() {
+    let store_config: StoreConfig = create_test_store_path()
+        .into_os_string()
+        .into_string()
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let open_store = || Store::new(store_config.clone()).unwrap();
+
+    let mut client = ClientBuilder::new()
+        .stores(open_store(), open_store())
+        .rng(StdRng::seed_from_u64(0))
+        .build()
+        .unwrap();
+    let (account, _) = client
+        .new_account(AccountTemplate::BasicWallet {
+            mutable_code: false,
+            storage_mode: AccountStorageMode::Local,
+        })
+        .unwrap();
+
+    // the account was saved in the provided store rather than in the default one
+    let (account_stub, _) = open_store().get_account_stub_by_id(account.id()).unwrap();
+    assert_eq!(account_stub.id(), account.id());
+    assert!(client.executor_store.is_some());
+}
+
+#[tokio::test]
+async fn test_client_builder_with_provided_rpc_client_and_keystore() {
+    use crate::mock::MockRpcApi;
+
+    let rpc_api = MockRpcApi::default();
+    let genesis_hash = rpc_api.genesis_block.hash();
+
+    let (account_id, _) =
+        generate_account_seed(AccountSeedType::RegularAccountUpdatableCodeOnChain);
+    let key_pair = KeyPair::new().unwrap();
+    let public_key = key_pair.public_key();
+
+    let client = ClientBuilder::new()
+        .config(ClientConfig {
+            store: create_test_store_path()
+                .to_str()
+                .unwrap()
+                .try_into()
+                .unwrap(),
+            ..Default::default()
+        })
+        .rpc_client(rpc_api)
+        .keystore(move |id: AccountId| {
+            Ok::<_, ClientError>((id == account_id).then(|| AuthInfo::RpoFalcon512(key_pair)))
+        })
+        .build()
+        .unwrap();
+
+    // the node's responses come from the provided RPC client
+    assert_eq!(client.rpc_api.genesis_block.hash(), genesis_hash);
+
+    // the keys of the account come from the keystore even though it is not in the store
+    let AuthInfo::RpoFalcon512(keys) = client.get_account_auth(account_id).unwrap();
+    assert_eq!(keys.public_key(), public_key);
+}
+
+#[tokio::test]
+async fn test_client_builder_prover_mode() {
+    let store_path = create_test_store_path();
+    let config = || ClientConfig {
+        store: store_path.to_str().unwrap().try_into().unwrap(),
+        ..Default::default()
+    };
+
+    // the configured mode is used unless another one is set
+    let client = ClientBuilder::new().config(config()).build().unwrap();
+    assert_eq!(client.prover_mode, TransactionProverMode::Local);
+    let client = ClientBuilder::new()
+        .config(config())
+        .prover_mode(TransactionProverMode::Trusted)
+        .build()
+        .unwrap();
+    assert_eq!(client.prover_mode, TransactionProverMode::Trusted);
+}
+
 #[tokio::test]
 async fn insert_same_account_twice_fails() {
     // generate test client with a random store name
@@ -219,52 +742,313 @@ async fn test_acc_code() {
 }
 
 #[tokio::test]
-async fn test_get_account_by_id() {
-    // generate test client with a random store name
+async fn test_update_account_applies_delta() {
     let mut client = create_test_client();
 
     let assembler = TransactionKernel::assembler();
-
     let (account_id, account_seed) =
         generate_account_seed(AccountSeedType::RegularAccountUpdatableCodeOnChain);
     let account = account::mock_account(Some(account_id.into()), Felt::ZERO, None, &assembler);
+    let key_pair: KeyPair = KeyPair::new().unwrap();
+    client
+        .insert_account(&account, account_seed, &AuthInfo::RpoFalcon512(key_pair))
+        .unwrap();
+    assert_eq!(client.get_account_hash(account_id).unwrap(), account.hash());
 
-    let key_pair: KeyPair = KeyPair::new()
-        .map_err(|err| format!("Error generating KeyPair: {}", err))
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let storage_delta = AccountStorageDelta {
+        cleared_items: vec![],
+        updated_items: vec![(5, [Felt::new(7); 4])],
+    };
+    let vault_delta = AccountVaultDelta {
+        added_assets: vec![FungibleAsset::new(faucet_id, 100).unwrap().into()],
+        removed_assets: vec![],
+    };
+    let delta = AccountDelta::new(storage_delta, vault_delta, Some(Felt::new(1))).unwrap();
+    client.update_account(account_id, &delta).unwrap();
+
+    let mut expected_account = account.clone();
+    expected_account.apply_delta(&delta).unwrap();
+
+    // The stored state matches the one of the account with the delta applied to it
+    assert_eq!(
+        client.get_account_hash(account_id).unwrap(),
+        expected_account.hash()
+    );
+    let (updated_account, _seed) = client.get_account_by_id(account_id).unwrap();
+    assert_eq!(updated_account.nonce(), expected_account.nonce());
+    assert_eq!(
+        updated_account.storage().root(),
+        expected_account.storage().root()
+    );
+    assert_eq!(
+        updated_account.vault().commitment(),
+        expected_account.vault().commitment()
+    );
+
+    // Deltas that do not move the nonce forward were already applied or are outdated
+    for nonce in [0, 1] {
+        let stale_delta = AccountDelta::new(
+            AccountStorageDelta::default(),
+            AccountVaultDelta::default(),
+            Some(Felt::new(nonce)),
+        )
         .unwrap();
+        assert!(matches!(
+            client.update_account(account_id, &stale_delta),
+            Err(ClientError::StoreError(StoreError::StaleAccountDelta(id, delta_nonce, 1)))
+                if id == account_id && delta_nonce == nonce
+        ));
+    }
+
+    // The applied delta is recorded along with the resulting nonce
+    let deltas = client.get_account_deltas(account_id, ..).unwrap();
+    assert_eq!(deltas.len(), 1);
+    assert_eq!(deltas[0].account_id, account_id);
+    assert_eq!(deltas[0].transaction_id, None);
+    assert_eq!(deltas[0].nonce, Felt::new(1));
+    assert_eq!(
+        deltas[0].delta.vault().added_assets,
+        delta.vault().added_assets
+    );
+    assert!(client
+        .get_account_deltas(account_id, 2..)
+        .unwrap()
+        .is_empty());
+}
 
+#[tokio::test]
+async fn test_account_state_at_block() {
+    let mut client = create_test_client();
+
+    let assembler = TransactionKernel::assembler();
+    let (account_id, account_seed) =
+        generate_account_seed(AccountSeedType::RegularAccountUpdatableCodeOnChain);
+    let account = account::mock_account(Some(account_id.into()), Felt::ZERO, None, &assembler);
+    let key_pair: KeyPair = KeyPair::new().unwrap();
     client
         .insert_account(&account, account_seed, &AuthInfo::RpoFalcon512(key_pair))
         .unwrap();
 
-    // Retrieving an existing account should succeed
-    let (acc_from_db, _account_seed) = match client.get_account_stub_by_id(account.id()) {
-        Ok(account) => account,
-        Err(err) => panic!("Error retrieving account: {}", err),
+    // The account is updated once the client is synced up to block 10
+    client
+        .store
+        .db
+        .execute("UPDATE state_sync SET block_num = 10", [])
+        .unwrap();
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let vault_delta = AccountVaultDelta {
+        added_assets: vec![FungibleAsset::new(faucet_id, 100).unwrap().into()],
+        removed_assets: vec![],
     };
-    assert_eq!(AccountStub::from(account), acc_from_db);
+    let storage_delta = AccountStorageDelta {
+        cleared_items: vec![],
+        updated_items: vec![],
+    };
+    let delta = AccountDelta::new(storage_delta, vault_delta, Some(Felt::new(1))).unwrap();
+    client.update_account(account_id, &delta).unwrap();
 
-    // Retrieving a non existing account should fail
-    let hex = format!("0x{}", "1".repeat(16));
-    let invalid_id = AccountId::from_hex(&hex).unwrap();
-    assert!(client.get_account_stub_by_id(invalid_id).is_err());
+    let (stub_before, _seed) = client.get_account_stub_at(account_id, 9).unwrap();
+    assert_eq!(stub_before, AccountStub::from(account));
+
+    let (stub_after, _seed) = client.get_account_stub_at(account_id, 10).unwrap();
+    assert_eq!(stub_after.nonce(), Felt::new(1));
+    assert_eq!(
+        stub_after,
+        client.get_account_stub_by_id(account_id).unwrap().0
+    );
 }
 
 #[tokio::test]
-async fn test_sync_state() {
+async fn test_account_labels() {
     // generate test client with a random store name
     let mut client = create_test_client();
 
-    // generate test data
-    crate::mock::insert_mock_data(&mut client).await;
+    let new_wallet = || AccountTemplate::BasicWallet {
+        mutable_code: false,
+        storage_mode: AccountStorageMode::Local,
+    };
+    let (savings, _) = client.new_account(new_wallet()).unwrap();
+    let (spending, _) = client.new_account(new_wallet()).unwrap();
 
-    // assert that we have no consumed nor pending notes prior to syncing state
+    client
+        .set_account_label(savings.id(), Some("savings"))
+        .unwrap();
+    client
+        .set_account_label(spending.id(), Some("daily"))
+        .unwrap();
+    client
+        .set_account_label(spending.id(), Some("savings"))
+        .unwrap();
+    assert_eq!(
+        client.get_account_label(savings.id()).unwrap().as_deref(),
+        Some("savings")
+    );
     assert_eq!(
         client
-            .get_input_notes(InputNoteFilter::Consumed)
+            .get_account_labels()
             .unwrap()
-            .len(),
-        0
+            .into_values()
+            .collect::<Vec<_>>(),
+        vec!["savings", "savings"]
+    );
+
+    client.set_account_label(spending.id(), None).unwrap();
+    assert_eq!(client.get_account_label(spending.id()).unwrap(), None);
+
+    // labels must not be blank, and only tracked accounts can be labelled
+    assert!(matches!(
+        client.set_account_label(savings.id(), Some("  ")),
+        Err(ClientError::StoreError(StoreError::InvalidAccountLabel(_)))
+    ));
+    let unknown_account_id = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+    assert!(matches!(
+        client.set_account_label(unknown_account_id, Some("savings")),
+        Err(ClientError::StoreError(StoreError::AccountDataNotFound(_)))
+    ));
+}
+
+#[tokio::test]
+async fn test_resolve_account_id() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    let template = || AccountTemplate::BasicWallet {
+        mutable_code: false,
+        storage_mode: AccountStorageMode::Local,
+    };
+    let (account_a, _) = client.new_account(template()).unwrap();
+    let (account_b, _) = client.new_account(template()).unwrap();
+    let (hex_a, hex_b) = (account_a.id().to_string(), account_b.id().to_string());
+
+    // full IDs and the shortest prefix telling the accounts apart resolve to the account
+    assert_eq!(client.resolve_account_id(&hex_a).unwrap(), account_a.id());
+    let prefix_len = hex_a
+        .chars()
+        .zip(hex_b.chars())
+        .position(|(a, b)| a != b)
+        .unwrap()
+        + 1;
+    assert_eq!(
+        client.resolve_account_id(&hex_a[..prefix_len]).unwrap(),
+        account_a.id()
+    );
+    assert_eq!(
+        client.resolve_account_id(&hex_b[2..prefix_len]).unwrap(),
+        account_b.id()
+    );
+    assert!(matches!(
+        client.resolve_account_id("0x"),
+        Err(ClientError::StoreError(StoreError::AmbiguousAccountIdPrefix(_, matches)))
+            if matches.len() == 2
+    ));
+
+    // address book names resolve to accounts the client does not track, whose IDs can then be
+    // shortened as well
+    let untracked_id = AccountId::from_hex(&format!("0x{}", "1".repeat(16))).unwrap();
+    client
+        .add_address_book_entry("alice", untracked_id)
+        .unwrap();
+    assert_eq!(client.resolve_account_id("alice").unwrap(), untracked_id);
+    assert_eq!(
+        client.resolve_account_id("0x1111111111").unwrap(),
+        untracked_id
+    );
+    assert_eq!(
+        client.get_address_book().unwrap(),
+        vec![AddressBookEntry {
+            name: "alice".to_string(),
+            account_id: untracked_id,
+        }]
+    );
+
+    assert!(matches!(
+        client.add_address_book_entry("0xbob", untracked_id),
+        Err(ClientError::StoreError(StoreError::InvalidAddressBookName(
+            _
+        )))
+    ));
+
+    // labels resolve to the single account holding them, after address book names
+    client
+        .set_account_label(account_a.id(), Some("savings"))
+        .unwrap();
+    assert_eq!(
+        client.resolve_account_id("savings").unwrap(),
+        account_a.id()
+    );
+    client
+        .set_account_label(account_b.id(), Some("alice"))
+        .unwrap();
+    assert_eq!(client.resolve_account_id("alice").unwrap(), untracked_id);
+    client
+        .set_account_label(account_b.id(), Some("savings"))
+        .unwrap();
+    assert!(client.resolve_account_id("savings").is_err());
+    client.set_account_label(account_b.id(), None).unwrap();
+
+    client.remove_address_book_entry("alice").unwrap();
+    assert!(matches!(
+        client.resolve_account_id("alice"),
+        Err(ClientError::StoreError(
+            StoreError::AccountIdPrefixNotFound(_)
+        ))
+    ));
+    assert!(matches!(
+        client.remove_address_book_entry("alice"),
+        Err(ClientError::StoreError(
+            StoreError::AddressBookEntryNotFound(_)
+        ))
+    ));
+}
+
+#[tokio::test]
+async fn test_get_account_by_id() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    let assembler = TransactionKernel::assembler();
+
+    let (account_id, account_seed) =
+        generate_account_seed(AccountSeedType::RegularAccountUpdatableCodeOnChain);
+    let account = account::mock_account(Some(account_id.into()), Felt::ZERO, None, &assembler);
+
+    let key_pair: KeyPair = KeyPair::new()
+        .map_err(|err| format!("Error generating KeyPair: {}", err))
+        .unwrap();
+
+    client
+        .insert_account(&account, account_seed, &AuthInfo::RpoFalcon512(key_pair))
+        .unwrap();
+
+    // Retrieving an existing account should succeed
+    let (acc_from_db, _account_seed) = match client.get_account_stub_by_id(account.id()) {
+        Ok(account) => account,
+        Err(err) => panic!("Error retrieving account: {}", err),
+    };
+    assert_eq!(AccountStub::from(account), acc_from_db);
+
+    // Retrieving a non existing account should fail
+    let hex = format!("0x{}", "1".repeat(16));
+    let invalid_id = AccountId::from_hex(&hex).unwrap();
+    assert!(client.get_account_stub_by_id(invalid_id).is_err());
+}
+
+#[tokio::test]
+async fn test_sync_state() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // generate test data
+    crate::mock::insert_mock_data(&mut client).await;
+
+    // assert that we have no consumed nor pending notes prior to syncing state
+    assert_eq!(
+        client
+            .get_input_notes(InputNoteFilter::Consumed)
+            .unwrap()
+            .len(),
+        0
     );
 
     let pending_notes = client.get_input_notes(InputNoteFilter::Pending).unwrap();
@@ -313,114 +1097,2193 @@ async fn test_sync_state() {
 }
 
 #[tokio::test]
-async fn test_add_tag() {
+async fn test_sync_state_events() {
     // generate test client with a random store name
     let mut client = create_test_client();
 
-    // assert that no tags are being tracked
-    assert_eq!(client.get_note_tags().unwrap().len(), 0);
+    // generate test data
+    crate::mock::insert_mock_data(&mut client).await;
+    let mut events = client.subscribe();
+
+    client.sync_state().await.unwrap();
+
+    let mut committed_notes = 0;
+    let mut consumed_notes = Vec::new();
+    let mut externally_consumed_notes = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        match event {
+            ClientEvent::NoteCommitted { .. } => committed_notes += 1,
+            ClientEvent::NoteConsumed {
+                note_id, by_client, ..
+            } => {
+                // the mock data doesn't include transactions consuming notes
+                assert!(!by_client);
+                consumed_notes.push(note_id);
+            }
+            ClientEvent::NoteConsumedExternally { note_id, .. } => {
+                externally_consumed_notes.push(note_id)
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(externally_consumed_notes, consumed_notes);
 
-    // add a tag
-    const TAG_VALUE_1: u64 = 1;
-    const TAG_VALUE_2: u64 = 2;
-    client.add_note_tag(TAG_VALUE_1).unwrap();
-    client.add_note_tag(TAG_VALUE_2).unwrap();
+    assert!(committed_notes > 0);
+    let consumed_note_ids: Vec<NoteId> = client
+        .get_input_notes(InputNoteFilter::Consumed)
+        .unwrap()
+        .iter()
+        .map(|note| note.note_id())
+        .collect();
+    assert_eq!(consumed_notes, consumed_note_ids);
+}
 
-    // verify that the tag is being tracked
-    assert_eq!(
-        client.get_note_tags().unwrap(),
-        vec![TAG_VALUE_1, TAG_VALUE_2]
+#[tokio::test]
+async fn test_sync_state_events_for_notes_consumed_by_the_client() {
+    let mut client = create_test_client();
+    crate::mock::insert_mock_data(&mut client).await;
+
+    // the mock node reports the first of the mock input notes as consumed
+    let consumed_note_id = mock_inputs(
+        MockAccountType::StandardExisting,
+        AssetPreservationStatus::Preserved,
+    )
+    .input_notes()
+    .get_note(0)
+    .note()
+    .id();
+    insert_submitted_transaction(
+        &mut client.store,
+        Digest::new([Felt::ONE; 4]),
+        AccountId::try_from(ACCOUNT_ID_SENDER).unwrap(),
+        &[consumed_note_id],
+        &[],
     );
 
-    // attempt to add the same tag again
-    client.add_note_tag(TAG_VALUE_1).unwrap();
+    let mut events = client.subscribe();
+    client.sync_state().await.unwrap();
+
+    let mut consumed_notes = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        match event {
+            ClientEvent::NoteConsumed {
+                note_id, by_client, ..
+            } => {
+                assert!(by_client);
+                consumed_notes.push(note_id);
+            }
+            ClientEvent::NoteConsumedExternally { note_id, .. } => {
+                panic!("note {} was consumed by the client", note_id.inner())
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(consumed_notes, vec![consumed_note_id]);
+}
 
-    // verify that the tag is still being tracked only once
-    assert_eq!(
-        client.get_note_tags().unwrap(),
-        vec![TAG_VALUE_1, TAG_VALUE_2]
+#[tokio::test]
+async fn test_sync_state_events_for_notes_committed_and_consumed_in_the_same_update() {
+    use miden_node_proto::responses::NullifierUpdate;
+    use mock::mock::notes::mock_notes;
+
+    let mut client = create_test_client();
+    crate::mock::insert_mock_data(&mut client).await;
+
+    // the first update commits the first of the mock created notes, which the mock node also
+    // reports as consumed in the same block
+    let (_consumed, created_notes) = mock_notes(
+        &TransactionKernel::assembler(),
+        &AssetPreservationStatus::Preserved,
     );
+    let note = created_notes.first().unwrap();
+    let (_, first_response) = client
+        .rpc_api
+        .state_sync_requests
+        .iter_mut()
+        .find(|(request, _)| request.block_num == 0)
+        .unwrap();
+    first_response.nullifiers.push(NullifierUpdate {
+        nullifier: Some(note.nullifier().inner().into()),
+        block_num: 8,
+    });
+
+    let mut events = client.subscribe();
+    client.sync_state().await.unwrap();
+
+    let mut committed = false;
+    let mut consumed = false;
+    let mut consumed_externally = false;
+    while let Ok(event) = events.try_recv() {
+        match event {
+            ClientEvent::NoteCommitted { note_id, .. } if note_id == note.id() => committed = true,
+            ClientEvent::NoteConsumed {
+                note_id,
+                block_num,
+                by_client,
+            } if note_id == note.id() => {
+                assert_eq!(block_num, 8);
+                assert!(!by_client);
+                consumed = true;
+            }
+            ClientEvent::NoteConsumedExternally { note_id, .. } if note_id == note.id() => {
+                consumed_externally = true
+            }
+            _ => {}
+        }
+    }
+    assert!(committed && consumed && consumed_externally);
+
+    // the note is consumed rather than left committed
+    assert!(client
+        .get_input_notes(InputNoteFilter::Consumed)
+        .unwrap()
+        .iter()
+        .any(|consumed_note| consumed_note.note_id() == note.id()));
 }
 
 #[tokio::test]
-#[ignore = "currently fails with PhantomCallsNotAllowed"]
-async fn test_mint_transaction() {
-    const FAUCET_ID: u64 = 10347894387879516201u64;
-    const FAUCET_SEED: Word = [Felt::ZERO, Felt::ZERO, Felt::ZERO, Felt::ZERO];
+async fn test_sync_state_commits_notes_expected_by_id() {
+    use crate::client::notes::ExpectedNote;
+    use miden_node_proto::{block_header::BlockHeader as NodeBlockHeader, note::NoteSyncRecord};
+    use objects::{
+        notes::{NoteMetadata, NOTE_TREE_DEPTH},
+        BlockHeader, Hasher,
+    };
+
+    let mut client = create_test_client();
+    crate::mock::insert_mock_data(&mut client).await;
+
+    // only the ID of the note is known, along with the tag it is created with
+    let note_id = NoteId::from(Digest::new([Felt::new(7); 4]));
+    let tag = 42;
+    assert!(client
+        .add_expected_note(ExpectedNote::Id { note_id, tag }, Some("payment"))
+        .unwrap());
+    assert!(!client
+        .add_expected_note(ExpectedNote::Id { note_id, tag }, None)
+        .unwrap());
+
+    // the note is the first leaf of the note tree of block 10, whose other nodes are all empty
+    let sender = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+    let metadata = NoteMetadata::new(sender, Felt::new(tag));
+    let note_path = MerklePath::new(vec![Digest::default(); NOTE_TREE_DEPTH as usize]);
+    let auth_hash = Hasher::merge(&[note_id.inner(), Word::from(metadata).into()]);
+    let note_root = note_path
+        .iter()
+        .fold(auth_hash, |node, sibling| Hasher::merge(&[node, *sibling]));
+
+    // the node sends the note in the last update, with the extra node trimmed from note paths
+    let (_, last_response) = client
+        .rpc_api
+        .state_sync_requests
+        .iter_mut()
+        .find(|(request, _)| request.block_num == 8)
+        .unwrap();
+    let tip_header = BlockHeader::try_from(last_response.block_header.clone().unwrap()).unwrap();
+    let tip_header = mock_block_header(10, Some(tip_header.chain_root()), Some(note_root), &[]);
+    last_response.block_header = Some(NodeBlockHeader::from(tip_header));
+    let mut sent_path = vec![Digest::default()];
+    sent_path.extend(note_path.iter().copied());
+    last_response.notes.push(NoteSyncRecord {
+        note_index: 0,
+        note_hash: Some(note_id.into()),
+        sender: sender.into(),
+        tag,
+        merkle_path: Some(MerklePath::new(sent_path).into()),
+    });
+
+    let mut events = client.subscribe();
+    assert_eq!(client.sync_state().await.unwrap(), 10);
+
+    let records = client.get_expected_note_ids().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].note_id, note_id);
+    assert_eq!(records[0].label.as_deref(), Some("payment"));
+    assert_eq!(records[0].commit_height, Some(10));
+
+    let mut committed = false;
+    while let Ok(event) = events.try_recv() {
+        if let ClientEvent::NoteCommitted {
+            note_id: committed_note_id,
+            block_num,
+        } = event
+        {
+            if committed_note_id == note_id {
+                assert_eq!(block_num, 10);
+                committed = true;
+            }
+        }
+    }
+    assert!(committed);
+
+    // the note is not reported again once committed
+    let mut events = client.subscribe();
+    client.sync_state().await.unwrap();
+    while let Ok(event) = events.try_recv() {
+        assert!(!matches!(
+            event,
+            ClientEvent::NoteCommitted { note_id: committed_note_id, .. } if committed_note_id == note_id
+        ));
+    }
+}
+
+#[tokio::test]
+async fn test_orders_are_filled_or_recalled_by_syncing() {
+    // the mock node reports the first of the mock input notes as consumed
+    let consumed_note = mock_inputs(
+        MockAccountType::StandardExisting,
+        AssetPreservationStatus::Preserved,
+    )
+    .input_notes()
+    .get_note(0)
+    .note()
+    .clone();
+
+    let sender = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+    for consumed_by_sender in [false, true] {
+        let mut client = create_test_client();
+        crate::mock::insert_mock_data(&mut client).await;
+
+        let consumed_note_ids = if consumed_by_sender {
+            vec![consumed_note.id()]
+        } else {
+            Vec::new()
+        };
+        let transaction_id = Digest::new([Felt::ONE; 4]);
+        let order = OrderRecord {
+            note_id: consumed_note.id(),
+            nullifier: consumed_note.nullifier().inner(),
+            kind: OrderKind::P2idr,
+            account_id: sender,
+            transaction_id,
+            recall_height: Some(20),
+            status: OrderStatus::Open,
+            closed_height: None,
+        };
+        insert_submitted_transaction(
+            &mut client.store,
+            transaction_id,
+            sender,
+            &consumed_note_ids,
+            &[order],
+        );
+
+        client.sync_state().await.unwrap();
+
+        let orders = client.get_orders().unwrap();
+        assert_eq!(orders.len(), 1);
+        let expected_status = if consumed_by_sender {
+            OrderStatus::Recalled
+        } else {
+            OrderStatus::Filled
+        };
+        assert_eq!(orders[0].status, expected_status);
+        assert!(orders[0].closed_height.is_some());
+    }
+}
 
+#[tokio::test]
+async fn test_sync_state_for_accounts() {
     // generate test client with a random store name
     let mut client = create_test_client();
 
-    let (faucet, _seed) = client
-        .new_account(AccountTemplate::FungibleFaucet {
-            token_symbol: TokenSymbol::new("TST").unwrap(),
-            decimals: 10u8,
-            max_supply: 1000u64,
+    // generate test data
+    crate::mock::insert_mock_data(&mut client).await;
+    let (other_account, _) = client
+        .new_account(AccountTemplate::BasicWallet {
+            mutable_code: false,
             storage_mode: AccountStorageMode::Local,
         })
         .unwrap();
 
-    let faucet = mock_account(
-        Some(FAUCET_ID),
-        Felt::new(10u64),
-        Some(faucet.code().clone()),
-        &TransactionKernel::assembler(),
+    let account_ids: Vec<AccountId> = client
+        .get_accounts()
+        .unwrap()
+        .into_iter()
+        .map(|(account_stub, _)| account_stub.id())
+        .filter(|account_id| *account_id != other_account.id())
+        .collect();
+
+    // sync every account but one, which is left at the height the client was synced to
+    let block_num = client.sync_state_for_accounts(&account_ids).await.unwrap();
+    assert_eq!(client.get_sync_height().unwrap(), block_num);
+    for account_id in account_ids.iter() {
+        assert_eq!(
+            client.get_account_sync_height(*account_id).unwrap(),
+            Some(block_num)
+        );
+    }
+    assert_eq!(
+        client.get_account_sync_height(other_account.id()).unwrap(),
+        Some(0)
     );
 
-    let key_pair: KeyPair = KeyPair::new()
-        .map_err(|err| format!("Error generating KeyPair: {}", err))
+    // the chain has not advanced since, so the next sync only catches the account up
+    let (last_request, last_response) = client
+        .rpc_api
+        .state_sync_requests
+        .iter()
+        .find(|(_, response)| response.block_header.as_ref().unwrap().block_num == block_num)
+        .map(|(request, response)| (request.clone(), response.clone()))
+        .unwrap();
+    client.rpc_api.state_sync_requests.insert(
+        SyncStateRequest {
+            block_num,
+            ..last_request
+        },
+        last_response,
+    );
+
+    assert_eq!(client.sync_state().await.unwrap(), block_num);
+    assert_eq!(
+        client.get_account_sync_height(other_account.id()).unwrap(),
+        Some(block_num)
+    );
+    for account_id in account_ids.iter() {
+        assert_eq!(
+            client.get_account_sync_height(*account_id).unwrap(),
+            Some(block_num)
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_restored_accounts_are_caught_up() {
+    let mut client = create_test_client();
+    crate::mock::insert_mock_data(&mut client).await;
+    let (account_stub, _) = client.get_accounts().unwrap().remove(0);
+    let account_id = account_stub.id();
+
+    // hidden accounts are left out of the syncs
+    client.hide_account(account_id).unwrap();
+    let block_num = client.sync_state().await.unwrap();
+    assert_eq!(client.get_account_sync_height(account_id).unwrap(), Some(0));
+
+    // the chain has not advanced since, so the next sync only catches the account up
+    let (last_request, last_response) = client
+        .rpc_api
+        .state_sync_requests
+        .iter()
+        .find(|(_, response)| response.block_header.as_ref().unwrap().block_num == block_num)
+        .map(|(request, response)| (request.clone(), response.clone()))
         .unwrap();
+    client.rpc_api.state_sync_requests.insert(
+        SyncStateRequest {
+            block_num,
+            ..last_request
+        },
+        last_response,
+    );
+
+    client.restore_account(account_id).unwrap();
+    assert_eq!(client.get_account_sync_height(account_id).unwrap(), Some(0));
+    assert_eq!(client.sync_state().await.unwrap(), block_num);
+    assert_eq!(
+        client.get_account_sync_height(account_id).unwrap(),
+        Some(block_num)
+    );
+}
+
+#[tokio::test]
+async fn test_consume_unauthenticated_note() {
+    use crypto::rand::RpoRandomCoin;
+    use miden_lib::notes::create_p2id_note;
+
+    let mut client = create_test_client();
+
+    // notes created by the client's own transactions are tracked as expected until committed
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let account_id = AccountId::from_hex("0x168187d729b31a84").unwrap();
+    let asset: Asset = FungibleAsset::new(faucet_id, 5).unwrap().into();
+    let note = create_p2id_note(
+        faucet_id,
+        account_id,
+        vec![asset],
+        RpoRandomCoin::new([Felt::ZERO; 4]),
+    )
+    .unwrap();
+    let note_id = note.id();
     client
         .store
-        .insert_account(&faucet, FAUCET_SEED, &AuthInfo::RpoFalcon512(key_pair))
+        .insert_expected_input_note(&note.into())
         .unwrap();
-    client.set_data_store(MockDataStore::with_existing(faucet.clone(), None, None));
+    let expected_notes = client.get_input_notes(InputNoteFilter::Expected).unwrap();
+    assert_eq!(expected_notes.len(), 1);
+    assert_eq!(expected_notes[0].note_id(), note_id);
 
-    // Test submitting a mint transaction
+    // they cannot be consumed before their inclusion proof is retrieved
+    assert!(matches!(
+        client.new_transaction(TransactionTemplate::ConsumeNotes(account_id, vec![note_id])),
+        Err(ClientError::UnauthenticatedNote(id)) if id == note_id
+    ));
 
-    let transaction_template = TransactionTemplate::MintFungibleAsset {
-        asset: FungibleAsset::new(faucet.id(), 5u64).unwrap(),
-        target_account_id: AccountId::from_hex("0x168187d729b31a84").unwrap(),
-    };
+    // retrieving it syncs the client, and fails as long as the node did not commit the note
+    crate::mock::insert_mock_data(&mut client).await;
+    assert!(matches!(
+        client.authenticate_input_notes(&[note_id]).await,
+        Err(ClientError::UnauthenticatedNote(id)) if id == note_id
+    ));
+    let sync_height = client.get_sync_height().unwrap();
+    assert!(sync_height > 0);
+
+    // notes that were already committed do not need a sync
+    let committed_note_ids: Vec<NoteId> = client
+        .get_input_notes(InputNoteFilter::Committed)
+        .unwrap()
+        .iter()
+        .map(|note| note.note_id())
+        .collect();
+    assert!(!committed_note_ids.is_empty());
+    client
+        .authenticate_input_notes(&committed_note_ids)
+        .await
+        .unwrap();
+    assert_eq!(client.get_sync_height().unwrap(), sync_height);
+}
 
-    let transaction = client.new_transaction(transaction_template).unwrap();
-    assert!(transaction
-        .executed_transaction()
-        .account_delta()
-        .nonce()
-        .is_some());
+#[tokio::test]
+async fn test_unauthenticated_note_is_authenticated_once_committed() {
+    use crypto::rand::RpoRandomCoin;
+    use miden_lib::notes::create_p2id_note;
+    use miden_node_proto::{block_header::BlockHeader as NodeBlockHeader, note::NoteSyncRecord};
+    use objects::{notes::NOTE_TREE_DEPTH, BlockHeader, Hasher};
+
+    let mut client = create_test_client();
+    crate::mock::insert_mock_data(&mut client).await;
+
+    // a note minted right before is expected until the node commits it
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let account_id = AccountId::from_hex("0x168187d729b31a84").unwrap();
+    let asset: Asset = FungibleAsset::new(faucet_id, 5).unwrap().into();
+    let note = create_p2id_note(
+        faucet_id,
+        account_id,
+        vec![asset],
+        RpoRandomCoin::new([Felt::ONE; 4]),
+    )
+    .unwrap();
+    let note_id = note.id();
+    client
+        .store
+        .insert_expected_input_note(&note.clone().into())
+        .unwrap();
+    assert!(matches!(
+        client.new_transaction(TransactionTemplate::ConsumeNotes(account_id, vec![note_id])),
+        Err(ClientError::UnauthenticatedNote(id)) if id == note_id
+    ));
+
+    // the node commits it as the first leaf of the note tree of block 10
+    let note_path = MerklePath::new(vec![Digest::default(); NOTE_TREE_DEPTH as usize]);
+    let auth_hash = Hasher::merge(&[note_id.inner(), Word::from(*note.metadata()).into()]);
+    let note_root = note_path
+        .iter()
+        .fold(auth_hash, |node, sibling| Hasher::merge(&[node, *sibling]));
+    let (_, last_response) = client
+        .rpc_api
+        .state_sync_requests
+        .iter_mut()
+        .find(|(request, _)| request.block_num == 8)
+        .unwrap();
+    let tip_header = BlockHeader::try_from(last_response.block_header.clone().unwrap()).unwrap();
+    let tip_header = mock_block_header(10, Some(tip_header.chain_root()), Some(note_root), &[]);
+    last_response.block_header = Some(NodeBlockHeader::from(tip_header));
+    let mut sent_path = vec![Digest::default()];
+    sent_path.extend(note_path.iter().copied());
+    last_response.notes.push(NoteSyncRecord {
+        note_index: 0,
+        note_hash: Some(note_id.into()),
+        sender: note.metadata().sender().into(),
+        tag: u64::from(note.metadata().tag()),
+        merkle_path: Some(MerklePath::new(sent_path).into()),
+    });
+
+    // retrieving its inclusion proof syncs the client, after which it is committed and no longer
+    // locked by the failed transaction
+    client.authenticate_input_notes(&[note_id]).await.unwrap();
+    assert_eq!(client.get_sync_height().unwrap(), 10);
+    let committed_note = client.get_input_note(note_id).unwrap();
+    let inclusion_proof = committed_note.inclusion_proof().unwrap();
+    assert_eq!(inclusion_proof.origin().block_num, 10);
+    assert_eq!(inclusion_proof.note_root(), note_root);
+    assert!(client
+        .get_input_notes(InputNoteFilter::Committed)
+        .unwrap()
+        .iter()
+        .any(|committed_note| committed_note.note_id() == note_id));
+    assert!(client
+        .get_input_notes(InputNoteFilter::Expected)
+        .unwrap()
+        .iter()
+        .all(|expected_note| expected_note.note_id() != note_id));
 }
 
 #[tokio::test]
-#[ignore = "currently fails because executor's DuplicateProcName error, see https://github.com/0xPolygonMiden/miden-base/issues/443"]
-async fn test_consume_all_transaction() {
+async fn test_sync_state_rollback() {
     // generate test client with a random store name
     let mut client = create_test_client();
 
     // generate test data
-    let transaction_inputs = mock_inputs(
-        MockAccountType::StandardExisting,
-        AssetPreservationStatus::Preserved,
-    );
+    crate::mock::insert_mock_data(&mut client).await;
+    let pending_notes = client.get_input_notes(InputNoteFilter::Pending).unwrap();
 
-    let recorded_notes: InputNotes = transaction_inputs.input_notes().clone();
-    let mut notes_for_data_store = vec![];
-    for note in recorded_notes.iter() {
-        notes_for_data_store.push(note.note().clone());
+    // sync state, then roll it back as if the chain had been reorganized past the genesis block
+    let block_num = client.sync_state().await.unwrap();
+    let committed_notes = client.get_input_notes(InputNoteFilter::Committed).unwrap();
+    let consumed_notes = client.get_input_notes(InputNoteFilter::Consumed).unwrap();
+    client.store.rollback_to_block(0).unwrap();
+
+    assert_eq!(client.get_sync_height().unwrap(), 0);
+    assert!(client.store.get_block_header_by_num(block_num).is_err());
+    assert!(client
+        .get_input_notes(InputNoteFilter::Consumed)
+        .unwrap()
+        .is_empty());
+    let rolled_back_notes = client.get_input_notes(InputNoteFilter::Pending).unwrap();
+    for note in pending_notes.iter() {
+        assert!(rolled_back_notes
+            .iter()
+            .any(|rolled_back_note| rolled_back_note.note_id() == note.note_id()));
     }
 
-    let (account, seed) = client
-        .new_account(AccountTemplate::BasicWallet {
-            mutable_code: false,
-            storage_mode: AccountStorageMode::Local,
-        })
-        .unwrap();
+    // syncing again restores the rolled back state
+    assert_eq!(client.sync_state().await.unwrap(), block_num);
+    assert_eq!(
+        client.get_input_notes(InputNoteFilter::Committed).unwrap(),
+        committed_notes
+    );
+    assert_eq!(
+        client.get_input_notes(InputNoteFilter::Consumed).unwrap(),
+        consumed_notes
+    );
+}
 
-    let data_store =
-        MockDataStore::with_existing(account.clone(), Some(seed), Some(notes_for_data_store));
-    client.set_data_store(data_store);
+#[tokio::test]
+async fn test_sync_state_rolls_back_reorganized_blocks() {
+    use miden_node_proto::block_header::BlockHeader as NodeBlockHeader;
+
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // generate test data
+    crate::mock::insert_mock_data(&mut client).await;
+    assert_eq!(client.sync_state().await.unwrap(), 10);
+    let (fork_header, _) = client.store.get_block_header_by_num(8).unwrap();
+    let (tip_header, _) = client.store.get_block_header_by_num(10).unwrap();
+    let committed_note_ids: Vec<NoteId> = client
+        .get_input_notes(InputNoteFilter::Committed)
+        .unwrap()
+        .iter()
+        .map(|note| note.note_id())
+        .collect();
+    assert!(!committed_note_ids.is_empty());
+
+    // the state of the account is replaced with the node's after the fork
+    let (account_stub, _) = client.get_accounts().unwrap().remove(0);
+    let assembler = TransactionKernel::assembler();
+    let remote_account = mock_account(
+        Some(u64::from(account_stub.id())),
+        Felt::new(2),
+        None,
+        &assembler,
+    );
+    client
+        .store
+        .replace_desynchronized_account(&remote_account)
+        .unwrap();
+
+    // the node's chain is reorganized past block 8, replacing block 10 with another one extending
+    // the same chain
+    let reorganized_header = mock_block_header(10, Some(tip_header.chain_root()), None, &[]);
+    assert_ne!(reorganized_header.hash(), tip_header.hash());
+    for response in client.rpc_api.state_sync_requests.values_mut() {
+        if response
+            .block_header
+            .as_ref()
+            .map(|header| header.block_num)
+            == Some(10)
+        {
+            response.block_header = Some(NodeBlockHeader::from(reorganized_header));
+        }
+    }
+
+    // the client rolls back to block 8, the last one still in the node's chain, then syncs the
+    // reorganized block
+    assert_eq!(client.sync_state().await.unwrap(), 10);
+    assert_eq!(
+        client.store.get_block_header_by_num(8).unwrap().0.hash(),
+        fork_header.hash()
+    );
+    assert_eq!(
+        client.store.get_block_header_by_num(10).unwrap().0.hash(),
+        reorganized_header.hash()
+    );
+    let recommitted_note_ids: Vec<NoteId> = client
+        .get_input_notes(InputNoteFilter::Committed)
+        .unwrap()
+        .iter()
+        .map(|note| note.note_id())
+        .collect();
+    assert_eq!(recommitted_note_ids, committed_note_ids);
+
+    // the account is back to its state at the fork
+    let (rolled_back_account, _) = client.get_account_stub_by_id(account_stub.id()).unwrap();
+    assert_eq!(rolled_back_account.hash(), account_stub.hash());
+}
+
+#[tokio::test]
+async fn test_sync_state_detects_network_mismatch() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // generate test data
+    crate::mock::insert_mock_data(&mut client).await;
+    let block_num = client.sync_state().await.unwrap();
+    let genesis_hash = client.store.get_block_header_by_num(0).unwrap().0.hash();
+
+    // the node is reset with a new genesis block
+    let node_genesis_block = mock_block_header(0, Some(Digest::new([Felt::new(7); 4])), None, &[]);
+    client.rpc_api.genesis_block = node_genesis_block;
+
+    assert!(matches!(
+        client.sync_state().await,
+        Err(ClientError::NetworkMismatch(store_genesis, node_genesis))
+            if store_genesis == genesis_hash && node_genesis == node_genesis_block.hash()
+    ));
+    assert_eq!(client.get_sync_height().unwrap(), block_num);
+}
+
+#[tokio::test]
+async fn test_reset_sync_data() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // generate test data
+    crate::mock::insert_mock_data(&mut client).await;
+    let block_num = client.sync_state().await.unwrap();
+    let accounts = client.get_accounts().unwrap();
+    assert!(!client
+        .get_input_notes(InputNoteFilter::All)
+        .unwrap()
+        .is_empty());
+
+    client.store.reset_sync_data().unwrap();
+
+    // accounts are kept while everything learnt from the node is discarded
+    assert_eq!(client.get_accounts().unwrap(), accounts);
+    assert_eq!(client.get_sync_height().unwrap(), 0);
+    assert!(client
+        .get_input_notes(InputNoteFilter::All)
+        .unwrap()
+        .is_empty());
+    assert!(client.store.get_block_header_by_num(0).is_err());
+    assert!(client
+        .get_transactions(TransactionFilter::All)
+        .unwrap()
+        .is_empty());
+
+    // the genesis block is fetched again by the next sync
+    assert_eq!(client.sync_state().await.unwrap(), block_num);
+    assert!(client.store.get_block_header_by_num(0).is_ok());
+}
+
+#[tokio::test]
+async fn test_add_tag() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // assert that no tags are being tracked
+    assert_eq!(client.get_note_tags().unwrap().len(), 0);
+
+    // add a tag
+    const TAG_VALUE_1: u64 = 1;
+    const TAG_VALUE_2: u64 = 2;
+    client.add_note_tag(TAG_VALUE_1).unwrap();
+    client.add_note_tag(TAG_VALUE_2).unwrap();
+
+    // verify that the tag is being tracked
+    assert_eq!(
+        client.get_note_tags().unwrap(),
+        vec![TAG_VALUE_1, TAG_VALUE_2]
+    );
+
+    // attempt to add the same tag again
+    client.add_note_tag(TAG_VALUE_1).unwrap();
+
+    // verify that the tag is still being tracked only once
+    assert_eq!(
+        client.get_note_tags().unwrap(),
+        vec![TAG_VALUE_1, TAG_VALUE_2]
+    );
+}
+
+#[tokio::test]
+async fn test_note_tag_sources() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    const USER_TAG: u64 = 1;
+    client.add_labeled_note_tag(USER_TAG, "faucet").unwrap();
+
+    // importing a note that is not committed yet tracks its tag on behalf of the note
+    let transaction_inputs = mock_inputs(
+        MockAccountType::StandardExisting,
+        AssetPreservationStatus::Preserved,
+    );
+    let note = transaction_inputs.input_notes().get_note(0).note().clone();
+    let note_tag = u64::from(note.metadata().tag());
+    client.import_input_note(note.clone().into()).unwrap();
+
+    assert_eq!(
+        client.get_note_tag_records().unwrap(),
+        vec![
+            NoteTagRecord {
+                tag: USER_TAG,
+                source: NoteTagSource::User,
+                label: Some("faucet".to_string()),
+            },
+            NoteTagRecord {
+                tag: note_tag,
+                source: NoteTagSource::Note(note.id()),
+                label: None,
+            },
+        ]
+    );
+
+    // removing a tag drops it whatever registered it
+    client.remove_note_tag(note_tag).unwrap();
+    client.remove_note_tag(note_tag).unwrap();
+    assert_eq!(client.get_note_tags().unwrap(), vec![USER_TAG]);
+}
+
+#[tokio::test]
+async fn test_account_note_tags() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // accounts created by the client have their tags registered
+    let (account, _) = client
+        .new_account(AccountTemplate::BasicWallet {
+            mutable_code: false,
+            storage_mode: AccountStorageMode::Local,
+        })
+        .unwrap();
+    assert_eq!(
+        client.get_note_tag_records().unwrap(),
+        vec![NoteTagRecord {
+            tag: account_note_tag(account.id()),
+            source: NoteTagSource::Account(account.id()),
+            label: None,
+        }]
+    );
+
+    // other accounts need to be registered explicitly
+    let assembler = TransactionKernel::assembler();
+    let (account_id, account_seed) =
+        generate_account_seed(AccountSeedType::RegularAccountUpdatableCodeOnChain);
+    let imported_account =
+        account::mock_account(Some(account_id.into()), Felt::ZERO, None, &assembler);
+    let key_pair: KeyPair = KeyPair::new().unwrap();
+    client
+        .insert_account(
+            &imported_account,
+            account_seed,
+            &AuthInfo::RpoFalcon512(key_pair),
+        )
+        .unwrap();
+    assert_eq!(client.get_note_tags().unwrap().len(), 1);
+
+    client.register_account_tags(imported_account.id()).unwrap();
+    client.register_account_tags(imported_account.id()).unwrap();
+    assert_eq!(
+        client.get_note_tags().unwrap(),
+        vec![
+            account_note_tag(account.id()),
+            account_note_tag(imported_account.id())
+        ]
+    );
+
+    // tags cannot be registered for unknown accounts
+    let unknown_account_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    assert!(client.register_account_tags(unknown_account_id).is_err());
+}
+
+#[tokio::test]
+async fn test_note_builder() {
+    use crate::client::transactions::{ProtocolLimit, ProtocolLimits};
+
+    let mut client = create_test_client();
+
+    let sender = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let asset: Asset = FungibleAsset::new(faucet_id, 100).unwrap().into();
+    let serial_num = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+    let builder = || {
+        NoteBuilder::new(sender)
+            .script(ProgramAst::parse("begin push.1 drop end").unwrap())
+            .add_asset(asset)
+    };
+
+    let note = client
+        .build_note(
+            builder()
+                .inputs(vec![Felt::new(42)])
+                .serial_num(serial_num)
+                .tag(7),
+        )
+        .unwrap();
+
+    assert_eq!(note.metadata().sender(), sender);
+    assert_eq!(note.metadata().tag(), Felt::new(7));
+    assert_eq!(note.serial_num(), serial_num);
+    assert_eq!(note.assets().iter().collect::<Vec<_>>(), vec![&asset]);
+
+    // serial numbers are drawn from the client's random generator when not set
+    let first_note = client.build_note(builder()).unwrap();
+    let second_note = client.build_note(builder()).unwrap();
+    assert_ne!(first_note.serial_num(), second_note.serial_num());
+
+    // a script is required
+    assert!(matches!(
+        client.build_note(NoteBuilder::new(sender).add_asset(asset)),
+        Err(ClientError::InvalidNote(_))
+    ));
+
+    // tags have to be field elements
+    assert!(matches!(
+        client.build_note(builder().tag(u64::MAX)),
+        Err(ClientError::InvalidNote(_))
+    ));
+
+    // notes are checked against the limits of the client's configuration
+    client.protocol_limits = ProtocolLimits {
+        max_assets_per_note: 1,
+        max_output_notes_per_transaction: 1,
+        ..ProtocolLimits::default()
+    };
+    let other_asset: Asset = FungibleAsset::new(faucet_id, 50).unwrap().into();
+    assert!(matches!(
+        client.build_note(builder().add_asset(other_asset)),
+        Err(ClientError::ProtocolLimitExceeded(
+            ProtocolLimit::AssetsPerNote,
+            1,
+            2
+        ))
+    ));
+    let result = client.new_transaction(TransactionTemplate::CreateNotes(
+        sender,
+        vec![first_note, second_note],
+    ));
+    assert!(matches!(
+        result,
+        Err(ClientError::ProtocolLimitExceeded(
+            ProtocolLimit::OutputNotesPerTransaction,
+            1,
+            2
+        ))
+    ));
+}
+
+#[test]
+fn test_note_builder_tags() {
+    use crate::client::note_tags::{account_note_tag, swap_note_tag};
+    use crypto::rand::RpoRandomCoin;
+
+    let sender = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let target_account_id = AccountId::from_hex("0x168187d729b31a84").unwrap();
+    let asset: Asset = FungibleAsset::new(faucet_id, 100).unwrap().into();
+    let builder = || {
+        NoteBuilder::new(sender)
+            .script(ProgramAst::parse("begin push.1 drop end").unwrap())
+            .add_asset(asset)
+    };
+
+    // notes meant for an account are retrieved by the account's tag
+    let note = builder()
+        .tag_for_account(target_account_id)
+        .build(RpoRandomCoin::new([Felt::ZERO; 4]))
+        .unwrap();
+    assert_eq!(
+        note.metadata().tag(),
+        Felt::new(account_note_tag(target_account_id))
+    );
+
+    // SWAP notes are retrieved by the tag of the traded pair
+    let note = builder()
+        .tag_for_swap(faucet_id, sender)
+        .build(RpoRandomCoin::new([Felt::ZERO; 4]))
+        .unwrap();
+    assert_eq!(
+        note.metadata().tag(),
+        Felt::new(swap_note_tag(faucet_id, sender))
+    );
+}
+
+#[tokio::test]
+async fn test_add_swap_note_tag() {
+    use crate::client::note_tags::swap_note_tag;
+
+    let mut client = create_test_client();
+
+    let offered_faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let requested_faucet_id = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+    let tag = client
+        .add_swap_note_tag(offered_faucet_id, requested_faucet_id)
+        .unwrap();
+    assert_eq!(tag, swap_note_tag(offered_faucet_id, requested_faucet_id));
+
+    // the tag is tracked as added by the user, labeled with the traded pair
+    let record = client
+        .get_note_tag_records()
+        .unwrap()
+        .into_iter()
+        .find(|record| record.tag == tag)
+        .unwrap();
+    assert_eq!(record.source, NoteTagSource::User);
+    assert_eq!(
+        record.label,
+        Some(format!(
+            "SWAP {offered_faucet_id} for {requested_faucet_id}"
+        ))
+    );
+    assert!(client.get_note_tags().unwrap().contains(&tag));
+}
+
+#[tokio::test]
+async fn test_note_memos() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    let memo = "rent for march, thanks!";
+    let encoded = encode_memo(memo).unwrap();
+    assert_eq!(decode_memo(&encoded).as_deref(), Some(memo));
+    assert!(encode_memo(&"a".repeat(MAX_MEMO_LEN + 1)).is_err());
+
+    // only the end of the inputs is looked at, so a leading input that happens to match the memo
+    // marker does not make up a memo
+    let mut leading_marker = encoded.clone();
+    leading_marker.push(Felt::new(42));
+    assert_eq!(decode_memo(&leading_marker), None);
+    assert_eq!(decode_memo(&encoded[1..]), None);
+
+    // memos follow the inputs the note script reads
+    let sender = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let mut inputs = vec![Felt::new(42)];
+    inputs.extend(encoded);
+    let note = client
+        .build_note(
+            NoteBuilder::new(sender)
+                .script(ProgramAst::parse("begin push.1 drop end").unwrap())
+                .inputs(inputs)
+                .add_asset(FungibleAsset::new(faucet_id, 100).unwrap().into()),
+        )
+        .unwrap();
+    assert_eq!(decode_memo(note.inputs().inputs()).as_deref(), Some(memo));
+    client.import_input_note(note.clone().into()).unwrap();
+
+    let retrieved_notes = client
+        .get_input_notes(NoteFilter::new().memo("march"))
+        .unwrap();
+    assert_eq!(retrieved_notes.len(), 1);
+    assert_eq!(retrieved_notes[0].note_id(), note.id());
+    assert!(client
+        .get_input_notes(NoteFilter::new().memo("april"))
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_account_history() {
+    let mut client = create_test_client();
+    let account_id = AccountId::from_hex("0x168187d729b31a84").unwrap();
+    let sender = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+
+    let memo = "invoice 12";
+    let notes: Vec<_> = [(100, encode_memo(memo).unwrap()), (30, Vec::new())]
+        .into_iter()
+        .map(|(amount, inputs)| {
+            client
+                .build_note(
+                    NoteBuilder::new(sender)
+                        .script(ProgramAst::parse("begin push.1 drop end").unwrap())
+                        .inputs(inputs)
+                        .add_asset(FungibleAsset::new(faucet_id, amount).unwrap().into()),
+                )
+                .unwrap()
+        })
+        .collect();
+    for note in notes.iter() {
+        client.import_input_note(note.clone().into()).unwrap();
+    }
+
+    // the account consumed both notes along with one the client does not know of, and another
+    // account consumed nothing
+    let unknown_note_id = NoteId::from(Digest::new([Felt::new(9); 4]));
+    let transaction_id = Digest::new([Felt::ONE; 4]);
+    insert_submitted_transaction(
+        &mut client.store,
+        transaction_id,
+        account_id,
+        &[notes[0].id(), notes[1].id(), unknown_note_id],
+        &[],
+    );
+    insert_submitted_transaction(
+        &mut client.store,
+        Digest::new([Felt::new(2); 4]),
+        sender,
+        &[],
+        &[],
+    );
+
+    let history = client.get_account_history(account_id).unwrap();
+    assert_eq!(history.len(), 1);
+    let entry = &history[0];
+    assert_eq!(entry.transaction_id, transaction_id);
+    assert_eq!(entry.transaction_status, TransactionStatus::Submitted);
+    assert_eq!(
+        entry.consumed_notes,
+        vec![notes[0].id(), notes[1].id(), unknown_note_id]
+    );
+    assert!(entry.created_notes.is_empty());
+    // unknown notes do not contribute to the asset changes
+    assert_eq!(entry.asset_changes, vec![(faucet_id, 130)]);
+    assert_eq!(entry.memos, vec![(notes[0].id(), memo.to_string())]);
+}
+
+#[tokio::test]
+async fn test_create_notes_template_checks_sender() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    let sender = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let note = client
+        .build_note(
+            NoteBuilder::new(sender)
+                .script(ProgramAst::parse("begin push.1 drop end").unwrap())
+                .add_asset(FungibleAsset::new(faucet_id, 100).unwrap().into()),
+        )
+        .unwrap();
+
+    // notes can only be created by their sender
+    let result = client.new_transaction(TransactionTemplate::CreateNotes(faucet_id, vec![note]));
+    assert!(matches!(result, Err(ClientError::InvalidNote(_))));
+}
+
+#[tokio::test]
+async fn test_recall_template_checks_note() {
+    use crypto::rand::RpoRandomCoin;
+    use miden_lib::notes::{create_p2id_note, create_p2idr_note};
+
+    let mut client = create_test_client();
+
+    let sender = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let asset: Asset = FungibleAsset::new(faucet_id, 100).unwrap().into();
+    let rng = || RpoRandomCoin::new([Felt::ZERO; 4]);
+    let p2idr_note = create_p2idr_note(sender, faucet_id, vec![asset], 5, rng()).unwrap();
+    let p2id_note = create_p2id_note(sender, faucet_id, vec![asset], rng()).unwrap();
+    client.import_input_note(p2idr_note.clone().into()).unwrap();
+    client.import_input_note(p2id_note.clone().into()).unwrap();
+
+    let recall = |client: &mut crate::client::Client, sender_account_id, note_id| {
+        client.new_transaction(TransactionTemplate::RecallNote {
+            sender_account_id,
+            note_id,
+        })
+    };
+
+    // only P2IDR notes can be recalled, by their sender
+    assert!(matches!(
+        recall(&mut client, sender, p2id_note.id()),
+        Err(ClientError::NoteNotRecallable(..))
+    ));
+    assert!(matches!(
+        recall(&mut client, faucet_id, p2idr_note.id()),
+        Err(ClientError::NoteNotRecallable(..))
+    ));
+
+    // the client is not synced up to the recall height yet
+    match recall(&mut client, sender, p2idr_note.id()) {
+        Err(ClientError::NoteNotRecallable(note_id, reason)) => {
+            assert_eq!(note_id, p2idr_note.id());
+            assert!(reason.contains("recall height is 5"));
+        }
+        _ => panic!("the note cannot be recalled before its recall height"),
+    }
+}
+
+#[tokio::test]
+async fn test_watched_nullifiers_are_found_consumed_by_syncing() {
+    use miden_node_proto::responses::NullifierUpdate;
+
+    let mut client = create_test_client();
+    crate::mock::insert_mock_data(&mut client).await;
+
+    // the nullifier of a note the client does not track, which the mock node reports as consumed
+    // in block 9, in the update from block 8
+    let nullifier = Digest::new([Felt::new(42); 4]);
+    assert!(client
+        .watch_nullifier(nullifier, Some("payment"), 0)
+        .unwrap());
+    let (_, second_response) = client
+        .rpc_api
+        .state_sync_requests
+        .iter_mut()
+        .find(|(request, _)| request.block_num == 8)
+        .unwrap();
+    second_response.nullifiers.push(NullifierUpdate {
+        nullifier: Some(nullifier.into()),
+        block_num: 9,
+    });
+
+    let mut events = client.subscribe();
+    assert_eq!(client.sync_state().await.unwrap(), 10);
+
+    let watched_nullifiers = client.get_watched_nullifiers().unwrap();
+    assert_eq!(watched_nullifiers.len(), 1);
+    assert_eq!(watched_nullifiers[0].nullifier, nullifier);
+    assert_eq!(watched_nullifiers[0].consumed_height, Some(9));
+
+    let mut consumed_events = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if let ClientEvent::WatchedNullifierConsumed {
+            nullifier,
+            block_num,
+        } = event
+        {
+            consumed_events.push((nullifier, block_num));
+        }
+    }
+    assert_eq!(consumed_events, vec![(nullifier, 9)]);
+
+    // consumed nullifiers are no longer checked, so syncing again reports nothing new
+    let mut events = client.subscribe();
+    client.sync_state().await.unwrap();
+    while let Ok(event) = events.try_recv() {
+        assert!(!matches!(
+            event,
+            ClientEvent::WatchedNullifierConsumed { .. }
+        ));
+    }
+}
+
+#[tokio::test]
+async fn test_recalled_notes_close_their_order() {
+    use crypto::rand::RpoRandomCoin;
+    use miden_lib::notes::create_p2idr_note;
+    use miden_node_proto::responses::NullifierUpdate;
+
+    let mut client = create_test_client();
+    crate::mock::insert_mock_data(&mut client).await;
+
+    let sender = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let target = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+    let asset: Asset = FungibleAsset::new(sender, 100).unwrap().into();
+    let note = create_p2idr_note(
+        sender,
+        target,
+        vec![asset],
+        5,
+        RpoRandomCoin::new([Felt::ZERO; 4]),
+    )
+    .unwrap();
+
+    // the note is committed along with the mock input notes, and the mock node reports it as
+    // consumed
+    let inclusion_proof = mock_inputs(
+        MockAccountType::StandardExisting,
+        AssetPreservationStatus::Preserved,
+    )
+    .input_notes()
+    .get_note(0)
+    .proof()
+    .clone();
+    client
+        .import_input_note(InputNoteRecord::new(note.clone(), Some(inclusion_proof)))
+        .unwrap();
+    for response in client.rpc_api.state_sync_requests.values_mut() {
+        response.nullifiers.push(NullifierUpdate {
+            nullifier: Some(note.nullifier().inner().into()),
+            block_num: 7,
+        });
+    }
+
+    let transaction_id = Digest::new([Felt::ONE; 4]);
+    let order = OrderRecord {
+        note_id: note.id(),
+        nullifier: note.nullifier().inner(),
+        kind: OrderKind::P2idr,
+        account_id: sender,
+        transaction_id,
+        recall_height: Some(5),
+        status: OrderStatus::Open,
+        closed_height: None,
+    };
+    insert_submitted_transaction(
+        &mut client.store,
+        transaction_id,
+        sender,
+        &[note.id()],
+        &[order],
+    );
+
+    client.sync_state().await.unwrap();
+
+    let orders = client.get_orders().unwrap();
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].status, OrderStatus::Recalled);
+
+    // the note cannot be recalled twice
+    match client.new_transaction(TransactionTemplate::RecallNote {
+        sender_account_id: sender,
+        note_id: note.id(),
+    }) {
+        Err(ClientError::NoteNotRecallable(note_id, reason)) => {
+            assert_eq!(note_id, note.id());
+            assert!(reason.contains("already recalled"));
+        }
+        _ => panic!("the note was already recalled"),
+    }
+}
+
+#[tokio::test]
+async fn test_note_scripts() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // the scripts of imported notes are stored once
+    let transaction_inputs = mock_inputs(
+        MockAccountType::StandardExisting,
+        AssetPreservationStatus::Preserved,
+    );
+    let notes: Vec<InputNoteRecord> = transaction_inputs
+        .input_notes()
+        .iter()
+        .map(|note| note.clone().into())
+        .collect();
+    for note in notes.iter().cloned() {
+        client.import_input_note(note).unwrap();
+    }
+    let script_hashes: BTreeSet<_> = notes
+        .iter()
+        .map(|note| note.note().script().hash())
+        .collect();
+    let scripts = client.get_note_scripts().unwrap();
+    assert_eq!(scripts.len(), script_hashes.len());
+    assert!(scripts.iter().all(|script| script.name.is_none()));
+    for note in notes.iter() {
+        let retrieved_note = client.get_input_note(note.note_id()).unwrap();
+        assert_eq!(retrieved_note.note().script(), note.note().script());
+    }
+
+    // registered scripts can be retrieved by name
+    let script_hash = client
+        .register_note_script("noop", "begin push.1 drop end")
+        .unwrap();
+    let script = client.get_note_script("noop").unwrap();
+    assert_eq!(script.script_hash, script_hash);
+    assert_eq!(script.name.as_deref(), Some("noop"));
+    assert_eq!(client.get_note_scripts().unwrap()[0], script);
+
+    // registering the same script again is a no-op, but neither the name nor the script can be
+    // registered again under another one
+    client
+        .register_note_script("noop", "begin push.1 drop end")
+        .unwrap();
+    assert!(matches!(
+        client.register_note_script("noop", "begin push.2 drop end"),
+        Err(ClientError::StoreError(StoreError::NoteScriptNameTaken(_)))
+    ));
+    assert!(matches!(
+        client.register_note_script("other", "begin push.1 drop end"),
+        Err(ClientError::StoreError(StoreError::NoteScriptAlreadyRegistered(hash, name)))
+            if hash == script_hash && name == "noop"
+    ));
+    assert_eq!(
+        client.get_note_script("noop").unwrap().script_hash,
+        script_hash
+    );
+    assert!(client.get_note_script("missing").is_err());
+}
+
+#[tokio::test]
+#[ignore = "currently fails with PhantomCallsNotAllowed"]
+async fn test_mint_transaction() {
+    const FAUCET_ID: u64 = 10347894387879516201u64;
+    const FAUCET_SEED: Word = [Felt::ZERO, Felt::ZERO, Felt::ZERO, Felt::ZERO];
+
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    let (faucet, _seed) = client
+        .new_account(AccountTemplate::FungibleFaucet {
+            token_symbol: TokenSymbol::new("TST").unwrap(),
+            decimals: 10u8,
+            max_supply: 1000u64,
+            storage_mode: AccountStorageMode::Local,
+        })
+        .unwrap();
+
+    let faucet = mock_account(
+        Some(FAUCET_ID),
+        Felt::new(10u64),
+        Some(faucet.code().clone()),
+        &TransactionKernel::assembler(),
+    );
+
+    let key_pair: KeyPair = KeyPair::new()
+        .map_err(|err| format!("Error generating KeyPair: {}", err))
+        .unwrap();
+    client
+        .store
+        .insert_account(&faucet, FAUCET_SEED, &AuthInfo::RpoFalcon512(key_pair))
+        .unwrap();
+    client.set_data_store(MockDataStore::with_existing(faucet.clone(), None, None));
+
+    // Test submitting a mint transaction
+
+    let transaction_template = TransactionTemplate::MintFungibleAsset {
+        asset: FungibleAsset::new(faucet.id(), 5u64).unwrap(),
+        target_account_id: AccountId::from_hex("0x168187d729b31a84").unwrap(),
+    };
+
+    // the fee estimated by the fee policy is recorded along with the transaction
+    let fee = FungibleAsset::new(faucet.id(), 1u64).unwrap();
+    client.set_fee_policy(
+        move |_: &TransactionTemplate| -> Result<Option<FungibleAsset>, ClientError> {
+            Ok(Some(fee))
+        },
+    );
+
+    let transaction = client.new_transaction(transaction_template).unwrap();
+    assert!(transaction
+        .executed_transaction()
+        .account_delta()
+        .nonce()
+        .is_some());
+    assert_eq!(transaction.fee(), Some(fee));
+
+    // Recording the executed transaction makes it resumable from the executed stage
+    let transaction_id = transaction.executed_transaction().id().inner();
+
+    let receipt = transaction.receipt();
+    assert_eq!(receipt.transaction_id, transaction_id.to_hex());
+    assert_eq!(receipt.account_id, faucet.id().to_hex());
+    assert_eq!(
+        receipt.output_note_ids,
+        vec![transaction.created_notes()[0].id().inner().to_hex()]
+    );
+    assert!(receipt.account_delta.nonce.is_some());
+    client
+        .store
+        .insert_executed_transaction(&transaction)
+        .unwrap();
+    client
+        .store
+        .insert_executed_transaction(&transaction)
+        .unwrap();
+    assert_eq!(
+        client.store.get_transaction_status(transaction_id).unwrap(),
+        TransactionStatus::Executed
+    );
+
+    let transactions = client.get_transactions(TransactionFilter::All).unwrap();
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(
+        transactions[0].transaction_status,
+        TransactionStatus::Executed
+    );
+    assert_eq!(transactions[0].fee, Some(fee));
+
+    let recorded_transaction = client
+        .store
+        .get_executed_transaction(transaction_id)
+        .unwrap();
+    assert_eq!(
+        recorded_transaction.executed_transaction().id(),
+        transaction.executed_transaction().id()
+    );
+    assert_eq!(recorded_transaction.fee(), Some(fee));
+    assert!(matches!(
+        client.get_proven_transaction(transaction_id),
+        Err(ClientError::StoreError(
+            StoreError::TransactionProofNotFound(_)
+        ))
+    ));
+
+    // Once the sync height moves too far past the block the transaction was executed against, it
+    // is not advanced anymore
+    let block_num = client
+        .store
+        .get_transaction_block_num(transaction_id)
+        .unwrap();
+    assert_eq!(block_num, transaction.block_num());
+    client.max_transaction_staleness = 2;
+    client
+        .store
+        .db
+        .execute("UPDATE state_sync SET block_num = ?", [block_num + 3])
+        .unwrap();
+    assert!(matches!(
+        client.advance_transaction(transaction_id).await,
+        Err(ClientError::StaleTransaction(id, _, sync_height))
+            if id == transaction_id && sync_height == block_num + 3
+    ));
+    client
+        .store
+        .db
+        .execute("UPDATE state_sync SET block_num = ?", [block_num])
+        .unwrap();
+
+    // The transaction shows up in the faucet's history, but the note it creates is not tracked
+    // until the transaction is submitted
+    let history = client.get_account_history(faucet.id()).unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].transaction_id, transaction_id);
+    assert_eq!(
+        history[0].created_notes.len(),
+        transaction.created_notes().len()
+    );
+    assert!(history[0].asset_changes.is_empty());
+
+    // Applying the account changes ahead of submission records them for the transaction, and
+    // discarding the transaction reverts them
+    client
+        .store
+        .apply_executed_transaction_delta(transaction_id)
+        .unwrap();
+    let (faucet_stub, _seed) = client.get_account_stub_by_id(faucet.id()).unwrap();
+    assert_eq!(
+        faucet_stub.hash(),
+        transaction.executed_transaction().final_account().hash()
+    );
+    let deltas = client.get_account_deltas(faucet.id(), ..).unwrap();
+    assert_eq!(deltas.len(), 1);
+    assert_eq!(deltas[0].transaction_id, Some(transaction_id));
+
+    // The transaction is re-executed against the state from before its changes, and replacing it
+    // reverts them until the new transaction is submitted
+    let (initial_state, _seed) = client
+        .store
+        .get_account_before_transaction(faucet.id(), transaction_id)
+        .unwrap();
+    assert_eq!(initial_state.hash(), faucet.hash());
+    client
+        .store
+        .replace_transaction(transaction_id, &transaction)
+        .unwrap();
+    assert_eq!(
+        client.get_account_stub_by_id(faucet.id()).unwrap().0,
+        AccountStub::from(faucet.clone())
+    );
+    let (initial_state, _seed) = client
+        .store
+        .get_account_before_transaction(faucet.id(), transaction_id)
+        .unwrap();
+    assert_eq!(initial_state.hash(), faucet.hash());
+    assert_eq!(
+        client.store.get_transaction_status(transaction_id).unwrap(),
+        TransactionStatus::Executed
+    );
+    client
+        .store
+        .apply_executed_transaction_delta(transaction_id)
+        .unwrap();
+
+    client.store.discard_transaction(transaction_id).unwrap();
+    assert_eq!(
+        client.get_account_stub_by_id(faucet.id()).unwrap().0,
+        AccountStub::from(faucet.clone())
+    );
+    assert!(client
+        .get_account_deltas(faucet.id(), ..)
+        .unwrap()
+        .is_empty());
+    assert!(client
+        .get_transactions(TransactionFilter::All)
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_recording_an_executed_transaction_again_leaves_it_unchanged() {
+    let mut client = create_test_client();
+
+    // mint transactions cannot be executed by the mock executor (see `test_mint_transaction`), so
+    // a mocked executed transaction is recorded
+    let executed_transaction = mock_executed_tx(AssetPreservationStatus::Preserved);
+    client
+        .store
+        .insert_account(
+            executed_transaction.initial_account(),
+            Word::default(),
+            &AuthInfo::RpoFalcon512(KeyPair::new().unwrap()),
+        )
+        .unwrap();
+    let transaction_id = executed_transaction.id().inner();
+    let transaction = TransactionResult::new(executed_transaction, Vec::new());
+    client
+        .store
+        .insert_executed_transaction(&transaction)
+        .unwrap();
+
+    // the transaction moved on before a crash kept its recording from being reported, and
+    // recording it again does not reset its stage
+    client
+        .store
+        .db
+        .execute(
+            "UPDATE transactions SET stage = 'submitted', transaction_result = NULL WHERE id = ?",
+            [String::from(transaction_id)],
+        )
+        .unwrap();
+    client
+        .store
+        .insert_executed_transaction(&transaction)
+        .unwrap();
+    let transactions = client.get_transactions(TransactionFilter::All).unwrap();
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(
+        transactions[0].transaction_status,
+        TransactionStatus::Submitted
+    );
+}
+
+#[tokio::test]
+async fn test_recording_a_transaction_record_again_leaves_it_unchanged() {
+    let mut client = create_test_client();
+    let transaction_id = Digest::new([Felt::ONE; 4]);
+    let account_id = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+    insert_submitted_transaction(&mut client.store, transaction_id, account_id, &[], &[]);
+
+    // transactions restored from their records are not recorded twice, nor moved back to an
+    // earlier stage
+    let transactions = client.get_transactions(TransactionFilter::All).unwrap();
+    client
+        .store
+        .insert_transaction_record(&transactions[0])
+        .unwrap();
+    let transactions = client.get_transactions(TransactionFilter::All).unwrap();
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].id, transaction_id);
+    assert_eq!(
+        transactions[0].transaction_status,
+        TransactionStatus::Submitted
+    );
+}
+
+#[tokio::test]
+async fn test_transaction_fees_are_recorded() {
+    use objects::transaction::OutputNotes;
+
+    use crate::client::transactions::TransactionRecord;
+
+    let mut client = create_test_client();
+    let account_id = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let fee = FungibleAsset::new(faucet_id, 3).unwrap();
+
+    for (transaction_id, fee) in [
+        (Digest::new([Felt::ONE; 4]), Some(fee)),
+        (Digest::default(), None),
+    ] {
+        let record = TransactionRecord::new(
+            transaction_id,
+            account_id,
+            Digest::default(),
+            Digest::default(),
+            Vec::new(),
+            OutputNotes::new(Vec::new()).unwrap(),
+            None,
+            0,
+            TransactionStatus::Committed(1),
+        )
+        .with_fee(fee);
+        client.store.insert_transaction_record(&record).unwrap();
+    }
+
+    // the fee is read back with the faucet it is paid in, and transactions charged no fee have none
+    let transactions = client.get_transactions(TransactionFilter::All).unwrap();
+    assert_eq!(transactions.len(), 2);
+    for transaction in transactions {
+        let expected_fee = (transaction.id == Digest::new([Felt::ONE; 4])).then_some(fee);
+        assert_eq!(transaction.fee, expected_fee);
+    }
+}
+
+#[tokio::test]
+async fn test_flushing_the_outbox_leaves_stale_transactions_queued() {
+    let mut client = create_test_client();
+    let sender_id = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+
+    // all transactions were executed against the genesis block, while offline
+    let transaction_ids: Vec<Digest> = (1..=3).map(|id| Digest::new([Felt::new(id); 4])).collect();
+    for (transaction_id, account_id) in transaction_ids
+        .iter()
+        .zip([sender_id, faucet_id, sender_id])
+    {
+        client
+            .store
+            .db
+            .execute(
+                "INSERT INTO transactions (id, account_id, init_account_state, final_account_state, \
+                output_notes, block_num, stage) VALUES (?, ?, '', '', x'', 0, 'executed')",
+                rusqlite::params![String::from(*transaction_id), u64::from(account_id) as i64],
+            )
+            .unwrap();
+        client.store.queue_transaction(*transaction_id).unwrap();
+    }
+
+    client.max_transaction_staleness = 2;
+    client
+        .store
+        .db
+        .execute("UPDATE state_sync SET block_num = ?", [3])
+        .unwrap();
+
+    // the third transaction builds on the stale first one, so it is not even checked
+    let flush = client.flush_outbox().await.unwrap();
+    assert!(flush.submitted.is_empty());
+    assert_eq!(flush.stale, vec![transaction_ids[0], transaction_ids[1]]);
+    assert_eq!(flush.blocked, vec![transaction_ids[2]]);
+    assert_eq!(client.get_queued_transactions().unwrap(), transaction_ids);
+}
+
+#[tokio::test]
+async fn test_execute_program_leaves_no_transaction_behind_when_a_step_fails() {
+    let mut client = create_test_client();
+
+    let sender = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let note = client
+        .build_note(
+            NoteBuilder::new(sender)
+                .script(ProgramAst::parse("begin push.1 drop end").unwrap())
+                .add_asset(FungibleAsset::new(faucet_id, 100).unwrap().into()),
+        )
+        .unwrap();
+
+    // the note is not created by its sender, so the program fails on its first step
+    let result = client
+        .execute_program(vec![TransactionTemplate::CreateNotes(
+            faucet_id,
+            vec![note],
+        )])
+        .await;
+    assert!(matches!(result, Err(ClientError::InvalidNote(_))));
+    assert!(client
+        .get_transactions(TransactionFilter::All)
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_discarding_a_transaction_reverts_its_account_changes() {
+    let mut client = create_test_client();
+
+    // the mock executor cannot execute transactions against client accounts (see
+    // `test_mint_transaction`), so a mocked executed transaction is recorded instead
+    let executed_transaction = mock_executed_tx(AssetPreservationStatus::Preserved);
+    let initial_account = executed_transaction.initial_account().clone();
+    let account_id = initial_account.id();
+    let key_pair = KeyPair::new().unwrap();
+    client
+        .store
+        .insert_account(
+            &initial_account,
+            Word::default(),
+            &AuthInfo::RpoFalcon512(key_pair),
+        )
+        .unwrap();
+
+    let delta_nonce = executed_transaction.account_delta().nonce().unwrap();
+    let transaction_id = executed_transaction.id().inner();
+    let transaction = TransactionResult::new(executed_transaction, Vec::new());
+    client
+        .store
+        .insert_executed_transaction(&transaction)
+        .unwrap();
+    client
+        .store
+        .apply_executed_transaction_delta(transaction_id)
+        .unwrap();
+    let (account_stub, _) = client.get_account_stub_by_id(account_id).unwrap();
+    assert_eq!(account_stub.nonce(), delta_nonce);
+
+    // discarding the transaction restores the account state it was executed against
+    client.discard_transaction(transaction_id).unwrap();
+    let (account_stub, _) = client.get_account_stub_by_id(account_id).unwrap();
+    assert_eq!(account_stub.hash(), initial_account.hash());
+    assert!(client
+        .get_transactions(TransactionFilter::All)
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_split_consume_all_template() {
+    let mut client = create_test_client();
+
+    let transaction_inputs = mock_inputs(
+        MockAccountType::StandardExisting,
+        AssetPreservationStatus::Preserved,
+    );
+    let account_id = transaction_inputs.account().id();
+
+    // Nothing to consume yet
+    assert!(matches!(
+        client.split_transaction_template(TransactionTemplate::ConsumeAllNotes(account_id)),
+        Err(ClientError::NoConsumableNoteForAccount(id)) if id == account_id
+    ));
+
+    for note in transaction_inputs.input_notes().iter().cloned() {
+        client.import_input_note(note.into()).unwrap();
+    }
+    let consumable_notes: Vec<NoteId> = client
+        .get_input_notes(NoteFilter::new().consumable_by(account_id))
+        .unwrap()
+        .iter()
+        .map(|note| note.note_id())
+        .collect();
+    assert!(!consumable_notes.is_empty());
+
+    let templates = client
+        .split_transaction_template(TransactionTemplate::ConsumeAllNotes(account_id))
+        .unwrap();
+    match templates.as_slice() {
+        [TransactionTemplate::ConsumeNotes(template_account_id, note_ids)] => {
+            assert_eq!(*template_account_id, account_id);
+            assert_eq!(*note_ids, consumable_notes);
+        }
+        _ => panic!("expected a single template consuming the notes"),
+    }
+}
+
+#[tokio::test]
+#[ignore = "currently fails because executor's DuplicateProcName error, see https://github.com/0xPolygonMiden/miden-base/issues/443"]
+async fn test_consume_all_transaction() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // generate test data
+    let transaction_inputs = mock_inputs(
+        MockAccountType::StandardExisting,
+        AssetPreservationStatus::Preserved,
+    );
+
+    let recorded_notes: InputNotes = transaction_inputs.input_notes().clone();
+    let mut notes_for_data_store = vec![];
+    for note in recorded_notes.iter() {
+        notes_for_data_store.push(note.note().clone());
+    }
+
+    let (account, seed) = client
+        .new_account(AccountTemplate::BasicWallet {
+            mutable_code: false,
+            storage_mode: AccountStorageMode::Local,
+        })
+        .unwrap();
+
+    let data_store =
+        MockDataStore::with_existing(account.clone(), Some(seed), Some(notes_for_data_store));
+    client.set_data_store(data_store);
 
     let note_list = recorded_notes.iter().map(|x| x.note().id()).collect();
     let transaction_template = TransactionTemplate::ConsumeNotes(account.id(), note_list);
     client.new_transaction(transaction_template).unwrap();
 }
+
+#[tokio::test]
+async fn test_get_account_balances() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    let assembler = TransactionKernel::assembler();
+
+    let (account_id, account_seed) =
+        generate_account_seed(AccountSeedType::RegularAccountUpdatableCodeOnChain);
+    let account = account::mock_account(Some(account_id.into()), Felt::ZERO, None, &assembler);
+
+    let key_pair: KeyPair = KeyPair::new()
+        .map_err(|err| format!("Error generating KeyPair: {}", err))
+        .unwrap();
+
+    client
+        .insert_account(&account, account_seed, &AuthInfo::RpoFalcon512(key_pair))
+        .unwrap();
+
+    // every fungible asset in the vault should be reported with its full amount
+    for asset in account.vault().assets() {
+        if let Asset::Fungible(fungible_asset) = asset {
+            assert_eq!(
+                client
+                    .get_account_balance(account_id, fungible_asset.faucet_id())
+                    .unwrap(),
+                fungible_asset.amount()
+            );
+        }
+    }
+
+    let balances = client.get_account_balances(account_id).unwrap();
+    let faucet_ids: BTreeSet<AccountId> = account
+        .vault()
+        .assets()
+        .map(|asset| asset.faucet_id())
+        .collect();
+    assert_eq!(balances.len(), faucet_ids.len());
+}
+
+#[tokio::test]
+async fn test_get_faucet_metadata() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    let (faucet, _seed) = client
+        .new_account(AccountTemplate::FungibleFaucet {
+            token_symbol: TokenSymbol::new("TEST").unwrap(),
+            decimals: 10,
+            max_supply: 9999999999,
+            storage_mode: AccountStorageMode::Local,
+        })
+        .unwrap();
+
+    assert_eq!(client.get_faucet_ids().unwrap(), vec![faucet.id()]);
+
+    let metadata = client.get_faucet_metadata(faucet.id()).unwrap();
+    assert_eq!(metadata.token_symbol, TokenSymbol::new("TEST").unwrap());
+    assert_eq!(metadata.decimals, 10);
+    assert_eq!(metadata.max_supply, 9999999999);
+    assert_eq!(metadata.total_issuance, 0);
+
+    // the faucet's details are recorded when it is created
+    let details = client.get_faucet_details(faucet.id()).unwrap().unwrap();
+    assert_eq!(details.token_symbol, metadata.token_symbol);
+    assert_eq!(details.decimals, metadata.decimals);
+
+    // regular accounts hold no faucet metadata
+    let (account, _seed) = client
+        .new_account(AccountTemplate::BasicWallet {
+            mutable_code: false,
+            storage_mode: AccountStorageMode::Local,
+        })
+        .unwrap();
+    assert!(client.get_faucet_metadata(account.id()).is_err());
+    assert!(client.get_faucet_details(account.id()).unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_watch_account() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    let assembler = TransactionKernel::assembler();
+
+    let (account_id, account_seed) =
+        generate_account_seed(AccountSeedType::RegularAccountUpdatableCodeOnChain);
+
+    // watching an account that is not tracked fails
+    assert!(client.watch_account(account_id).is_err());
+
+    let account = account::mock_account(Some(account_id.into()), Felt::ONE, None, &assembler);
+    let key_pair: KeyPair = KeyPair::new()
+        .map_err(|err| format!("Error generating KeyPair: {}", err))
+        .unwrap();
+    client
+        .insert_account(&account, account_seed, &AuthInfo::RpoFalcon512(key_pair))
+        .unwrap();
+
+    let mut receiver = client.watch_account(account_id).unwrap();
+
+    // an unchanged account produces no events
+    client
+        .notify_account_watchers(client.get_watched_accounts().unwrap())
+        .unwrap();
+    assert!(receiver.try_recv().is_err());
+
+    let previous_account =
+        account::mock_account(Some(account_id.into()), Felt::ZERO, None, &assembler);
+    client
+        .notify_account_watchers(vec![previous_account])
+        .unwrap();
+
+    let delta = receiver.try_recv().unwrap();
+    assert_eq!(delta.account_id, account_id);
+    assert_eq!(delta.previous_nonce, Felt::ZERO);
+    assert_eq!(delta.nonce, Felt::ONE);
+    assert!(delta.storage_slots.is_empty());
+    assert!(delta.added_assets.is_empty());
+    assert!(delta.removed_assets.is_empty());
+}
+
+#[tokio::test]
+async fn test_sync_quarantine_retry() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    let transaction_inputs = mock_inputs(
+        MockAccountType::StandardExisting,
+        AssetPreservationStatus::Preserved,
+    );
+    let note = transaction_inputs.input_notes().get_note(0).note().clone();
+
+    // the note's block is not tracked, so its inclusion proof can't be built yet
+    let quarantined_note = QuarantinedNote {
+        note_id: note.id(),
+        block_num: 100,
+        note_index: 0,
+        note_path: MerklePath::new(vec![]),
+        reason: "invalid proof".to_string(),
+    };
+    client.store.quarantine_note(&quarantined_note).unwrap();
+    assert_eq!(
+        client.get_quarantined_notes().unwrap(),
+        vec![quarantined_note.clone()]
+    );
+
+    // retrying keeps the note quarantined and records the new failure
+    assert!(client.retry_quarantined_notes().await.unwrap().is_empty());
+    let quarantined_notes = client.get_quarantined_notes().unwrap();
+    assert_eq!(quarantined_notes.len(), 1);
+    assert_eq!(quarantined_notes[0].note_id, quarantined_note.note_id);
+    assert_ne!(quarantined_notes[0].reason, quarantined_note.reason);
+}
+
+#[test]
+fn test_import_input_note_trims_node_note_paths() {
+    use objects::notes::{Note, NoteInclusionProof, NOTE_TREE_DEPTH};
+
+    let transaction_inputs = mock_inputs(
+        MockAccountType::StandardExisting,
+        AssetPreservationStatus::Preserved,
+    );
+    let notes: Vec<_> = transaction_inputs
+        .input_notes()
+        .iter()
+        .map(|input_note| input_note.note().clone())
+        .collect();
+    let note_with_path = |note: &Note, path_len: usize| {
+        let inclusion_proof = NoteInclusionProof::new(
+            4,
+            Digest::default(),
+            Digest::default(),
+            0,
+            MerklePath::new(vec![Digest::default(); path_len]),
+        )
+        .unwrap();
+        InputNoteRecord::new(note.clone(), Some(inclusion_proof))
+    };
+    let stored_path_len = |client: &Client, note: &Note| {
+        client
+            .get_input_note(note.id())
+            .unwrap()
+            .inclusion_proof()
+            .unwrap()
+            .note_path()
+            .len()
+    };
+    let depth = NOTE_TREE_DEPTH as usize;
+
+    // paths sent by nodes building their note trees from note IDs have an extra node, while
+    // paths that were already trimmed are left unchanged
+    let mut client = create_test_client();
+    assert!(client.trim_note_paths);
+    client
+        .import_input_note(note_with_path(&notes[0], depth + 1))
+        .unwrap();
+    client
+        .import_input_note(note_with_path(&notes[1], depth))
+        .unwrap();
+    assert_eq!(stored_path_len(&client, &notes[0]), depth);
+    assert_eq!(stored_path_len(&client, &notes[1]), depth);
+
+    // without the option, paths are stored as they are imported
+    let mut client = create_test_client();
+    client.trim_note_paths = false;
+    client
+        .import_input_note(note_with_path(&notes[0], depth + 1))
+        .unwrap();
+    assert_eq!(stored_path_len(&client, &notes[0]), depth + 1);
+}
+
+#[tokio::test]
+async fn test_authenticate_block() {
+    use crypto::merkle::MmrPeaks;
+    use miden_node_proto::{
+        block_header::BlockHeader as NodeBlockHeader, responses::SyncStateResponse,
+    };
+
+    // the node serves the header of block 2, which is a single-leaf peak of the chain MMR at the
+    // client's sync height of 3, so it is authenticated by the peak alone
+    let block_header = mock_block_header(2, None, None, &[]);
+    let client_synced_to_block_3 = |last_peak: Digest| {
+        let mut client = create_test_client();
+        client.rpc_api.state_sync_requests.insert(
+            SyncStateRequest {
+                block_num: 2,
+                account_ids: vec![],
+                note_tags: vec![],
+                nullifiers: vec![],
+            },
+            SyncStateResponse {
+                chain_tip: 3,
+                mmr_delta: None,
+                block_header: Some(NodeBlockHeader::from(block_header)),
+                accounts: vec![],
+                notes: vec![],
+                nullifiers: vec![],
+            },
+        );
+
+        let peaks = MmrPeaks::new(3, vec![Digest::new([Felt::new(5); 4]), last_peak]).unwrap();
+        let tx = client.store.write_transaction().unwrap();
+        Store::insert_block_header(&tx, mock_block_header(3, None, None, &[]), peaks, false)
+            .unwrap();
+        tx.commit().unwrap();
+        client
+            .store
+            .db
+            .execute("UPDATE state_sync SET block_num = 3", [])
+            .unwrap();
+        client
+    };
+
+    // a header matching the client's chain MMR is stored along with its node
+    let mut client = client_synced_to_block_3(block_header.hash());
+    client.authenticate_block(2).await.unwrap();
+    assert_eq!(
+        client.store.get_block_header_by_num(2).unwrap(),
+        (block_header, true)
+    );
+    assert!(client
+        .store
+        .get_chain_mmr_nodes(ChainMmrNodeFilter::All)
+        .unwrap()
+        .contains_key(&InOrderIndex::from_leaf_pos(2)));
+
+    // blocks the client tracks or has not synced past are not requested again
+    client.authenticate_block(2).await.unwrap();
+    client.authenticate_block(3).await.unwrap();
+
+    // a header the client's chain MMR does not commit to is rejected and not stored
+    let mut client = client_synced_to_block_3(Digest::new([Felt::new(6); 4]));
+    assert!(client.authenticate_block(2).await.is_err());
+    assert!(matches!(
+        client.store.get_block_header_by_num(2),
+        Err(StoreError::BlockHeaderNotFound(2))
+    ));
+}
+
+#[tokio::test]
+async fn test_sync_quarantine_retry_refetches_the_note() {
+    use crypto::merkle::MmrPeaks;
+    use miden_node_proto::{
+        block_header::BlockHeader as NodeBlockHeader, mmr::MmrDelta, note::NoteSyncRecord,
+        responses::SyncStateResponse,
+    };
+    use objects::{notes::NOTE_TREE_DEPTH, Hasher};
+
+    let mut client = create_test_client();
+
+    let transaction_inputs = mock_inputs(
+        MockAccountType::StandardExisting,
+        AssetPreservationStatus::Preserved,
+    );
+    let note = transaction_inputs.input_notes().get_note(0).note().clone();
+    client
+        .import_input_note(InputNoteRecord::new(note.clone(), None))
+        .unwrap();
+
+    // the note is the first leaf of the note tree of block 4, whose other nodes are all empty
+    let note_path = MerklePath::new(vec![Digest::default(); NOTE_TREE_DEPTH as usize]);
+    let auth_hash = Hasher::merge(&[note.id().inner(), Word::from(*note.metadata()).into()]);
+    let note_root = note_path
+        .iter()
+        .fold(auth_hash, |node, sibling| Hasher::merge(&[node, *sibling]));
+    let block_header = mock_block_header(4, None, Some(note_root), &[]);
+    let tx = client.store.write_transaction().unwrap();
+    Store::insert_block_header(&tx, block_header, MmrPeaks::new(0, vec![]).unwrap(), true).unwrap();
+    tx.commit().unwrap();
+
+    // the note was quarantined after the node sent it at the wrong index
+    let quarantined_note = QuarantinedNote {
+        note_id: note.id(),
+        block_num: 4,
+        note_index: 1,
+        note_path: note_path.clone(),
+        reason: "invalid proof".to_string(),
+    };
+    client.store.quarantine_note(&quarantined_note).unwrap();
+
+    // the node now sends the right index, along with the extra node trimmed from note paths
+    let mut sent_path = vec![Digest::default()];
+    sent_path.extend(note_path.iter().copied());
+    client.rpc_api.state_sync_requests.insert(
+        SyncStateRequest {
+            block_num: 3,
+            account_ids: vec![],
+            note_tags: vec![],
+            nullifiers: vec![],
+        },
+        SyncStateResponse {
+            chain_tip: 4,
+            mmr_delta: Some(MmrDelta {
+                forest: 4,
+                data: vec![],
+            }),
+            block_header: Some(NodeBlockHeader::from(block_header)),
+            accounts: vec![],
+            notes: vec![NoteSyncRecord {
+                note_index: 0,
+                note_hash: Some(note.id().into()),
+                sender: note.metadata().sender().into(),
+                tag: u64::from(note.metadata().tag()),
+                merkle_path: Some(MerklePath::new(sent_path).into()),
+            }],
+            nullifiers: vec![],
+        },
+    );
+
+    assert_eq!(
+        client.retry_quarantined_notes().await.unwrap(),
+        vec![note.id()]
+    );
+    assert!(client.get_quarantined_notes().unwrap().is_empty());
+    let committed_note = client.get_input_note(note.id()).unwrap();
+    assert_eq!(
+        committed_note.inclusion_proof().unwrap().note_root(),
+        note_root
+    );
+}
+
+#[test]
+fn test_retry_policy_delays() {
+    let policy = RetryPolicy {
+        max_attempts: 4,
+        initial_backoff_ms: 100,
+        max_backoff_ms: 300,
+        jitter_ms: 0,
+    };
+    let retryable = ClientError::WebhookError("connection refused".to_string());
+    let fatal = ClientError::NetworkMismatch(Digest::default(), Digest::default());
+
+    // the backoff doubles after every attempt until it reaches the maximum
+    let delays: Vec<_> = (1..=4)
+        .map(|attempt| policy.retry_delay(&retryable, attempt))
+        .collect();
+    assert_eq!(
+        delays,
+        vec![
+            Some(Duration::from_millis(100)),
+            Some(Duration::from_millis(200)),
+            Some(Duration::from_millis(300)),
+            None
+        ]
+    );
+
+    assert_eq!(policy.retry_delay(&fatal, 1), None);
+    assert_eq!(RetryPolicy::NO_RETRIES.retry_delay(&retryable, 1), None);
+}
@@ -0,0 +1,37 @@
+/*
+ * Smoke test of the C bindings, checking that the library built with the `ffi` feature links
+ * against include/miden_client.h and reports errors through its error functions.
+ *
+ * Built and run by CI after `cargo build --features ffi`:
+ *   cc tests/ffi/smoke_test.c -Iinclude -Ltarget/debug -lmiden_client -o target/ffi_smoke_test
+ *   LD_LIBRARY_PATH=target/debug target/ffi_smoke_test
+ */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "miden_client.h"
+
+int main(void) {
+    MidenClient *client = NULL;
+
+    /* an invalid configuration is rejected without creating a client */
+    int32_t status = miden_client_new("{ not json", &client);
+    if (status != MIDEN_ERROR_INVALID_INPUT || client != NULL) {
+        fprintf(stderr, "expected an invalid input error, got status %d\n", status);
+        return 1;
+    }
+
+    const char *code = miden_last_error_code();
+    const char *message = miden_last_error_message();
+    if (code == NULL || message == NULL || strlen(message) == 0) {
+        fprintf(stderr, "the error of the failed call is not reported\n");
+        return 1;
+    }
+
+    /* releasing a null handle does nothing */
+    miden_client_free(NULL);
+
+    printf("ffi smoke test passed (%s: %s)\n", code, message);
+    return 0;
+}